@@ -0,0 +1,212 @@
+//! Benchmarks for the record writing hot path.
+//!
+//! Besides throughput, this counts heap allocations with a counting global
+//! allocator to verify that steady-state record writing does not allocate.
+//!
+//! Run with: cargo bench --bench writer_benchmark
+
+use mdf4_rs::writer::{ChannelHandle, MdfWrite};
+use mdf4_rs::{DataType, DecodedValue, MdfWriter, Result};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Global allocator that counts allocation calls.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Writer backend that discards all data, so only the encoding path is measured.
+struct NullWriter {
+    position: u64,
+}
+
+impl MdfWrite for NullWriter {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn seek(&mut self, pos: u64) -> Result<u64> {
+        self.position = pos;
+        Ok(pos)
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+const CHANNELS: usize = 8;
+
+/// Records that fit into a single DT block (8 x 8 byte channels + time).
+/// DT block rollover allocates block bookkeeping, so the allocation check
+/// stays below the 4 MB block limit.
+const STEADY_STATE_RECORDS: usize = 50_000;
+
+/// Records written for the throughput measurement.
+const THROUGHPUT_RECORDS: usize = 2_000_000;
+
+/// Benchmark result for a single write path
+struct BenchResult {
+    name: &'static str,
+    steady_state_allocations: usize,
+    duration: Duration,
+    records: usize,
+}
+
+impl BenchResult {
+    fn records_per_sec(&self) -> f64 {
+        self.records as f64 / self.duration.as_secs_f64()
+    }
+}
+
+fn new_writer(data_type: DataType) -> Result<(MdfWriter<NullWriter>, String)> {
+    let mut writer = MdfWriter::from_writer(NullWriter { position: 0 });
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let mut prev: Option<String> = None;
+    for i in 0..=CHANNELS {
+        let id = writer.add_channel(&cg, prev.as_deref(), |ch| {
+            ch.data_type = data_type;
+            ch.bit_count = 64;
+            ch.name = Some(format!("Channel_{}", i));
+        })?;
+        if i == 0 {
+            writer.set_time_channel(&id)?;
+        }
+        prev = Some(id);
+    }
+    writer.start_data_block_for_cg(&cg, 0)?;
+    Ok((writer, cg))
+}
+
+/// Write `STEADY_STATE_RECORDS` records after a short warmup while counting
+/// allocations, then measure throughput over `THROUGHPUT_RECORDS` records.
+fn bench<F>(name: &'static str, mut write: F) -> Result<BenchResult>
+where
+    F: FnMut(usize) -> Result<()>,
+{
+    // Warmup: first records may initialise lazily allocated state
+    for i in 0..100 {
+        write(i)?;
+    }
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for i in 100..STEADY_STATE_RECORDS {
+        write(i)?;
+    }
+    let steady_state_allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    let start = Instant::now();
+    for i in 0..THROUGHPUT_RECORDS {
+        write(i)?;
+    }
+    let duration = start.elapsed();
+
+    Ok(BenchResult {
+        name,
+        steady_state_allocations,
+        duration,
+        records: THROUGHPUT_RECORDS,
+    })
+}
+
+fn main() -> Result<()> {
+    println!("=== MDF4-RS Writer Benchmark ===\n");
+    println!(
+        "{} channels + time, {} steady-state records, {} throughput records\n",
+        CHANNELS, STEADY_STATE_RECORDS, THROUGHPUT_RECORDS
+    );
+
+    let mut results = Vec::new();
+
+    let (mut writer, cg) = new_writer(DataType::FloatLE)?;
+    let mut record = vec![DecodedValue::Float(0.0); CHANNELS + 1];
+    results.push(bench("write_record (DecodedValue)", |i| {
+        record[0] = DecodedValue::Float(i as f64 * 0.001);
+        writer.write_record(&cg, &record)
+    })?);
+
+    let (mut writer, cg) = new_writer(DataType::UnsignedIntegerLE)?;
+    let mut record = [0u64; CHANNELS + 1];
+    results.push(bench("write_record_u64", |i| {
+        record[0] = i as u64;
+        writer.write_record_u64(&cg, &record)
+    })?);
+
+    let mut writer = MdfWriter::from_writer(NullWriter { position: 0 });
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time: ChannelHandle<f64> = writer.add_typed_channel(&cg, None, |_| {})?;
+    writer.set_time_channel(&time)?;
+    let rpm: ChannelHandle<u32> = writer.add_typed_channel(&cg, Some(&time), |_| {})?;
+    let temp: ChannelHandle<i16> = writer.add_typed_channel(&cg, Some(&rpm), |_| {})?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    results.push(bench("write_record_typed", |i| {
+        writer.write_record_typed(
+            &cg,
+            (
+                (&time, i as f64 * 0.001),
+                (&rpm, i as u32),
+                (&temp, (i % 100) as i16),
+            ),
+        )
+    })?);
+
+    let (mut writer, cg) = new_writer(DataType::FloatLE)?;
+    let batch: Vec<Vec<DecodedValue>> = (0..100)
+        .map(|i| vec![DecodedValue::Float(i as f64); CHANNELS + 1])
+        .collect();
+    results.push(bench("write_records (batches of 100)", |i| {
+        if i % 100 == 0 {
+            writer.write_records(&cg, batch.iter().map(|r| r.as_slice()))
+        } else {
+            Ok(())
+        }
+    })?);
+
+    println!(
+        "  {:40} {:>14} {:>16}",
+        "Write path", "records/s", "steady allocs"
+    );
+    let mut allocation_free = true;
+    for r in &results {
+        println!(
+            "  {:40} {:>14.0} {:>16}",
+            r.name,
+            r.records_per_sec(),
+            r.steady_state_allocations
+        );
+        allocation_free &= r.steady_state_allocations == 0;
+    }
+    println!();
+
+    assert!(allocation_free, "record writing allocated in steady state");
+    println!("All write paths are allocation-free in steady state.");
+
+    Ok(())
+}
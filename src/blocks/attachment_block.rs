@@ -213,6 +213,20 @@ impl AttachmentBlock<'_> {
         }
     }
 
+    /// Records the MD5 checksum of the embedded data and sets the
+    /// [`MD5_VALID`](AttachmentFlags::MD5_VALID) flag.
+    ///
+    /// The checksum covers the original data, so this does nothing for
+    /// compressed or external attachments.
+    pub fn with_md5(mut self) -> Self {
+        if self.flags.is_embedded() && !self.flags.is_compressed() {
+            self.md5_checksum = super::md5::md5(self.embedded_data);
+            self.flags =
+                AttachmentFlags::from_u16(self.flags.as_u16() | AttachmentFlags::MD5_VALID);
+        }
+        self
+    }
+
     /// Returns the embedded data if available.
     ///
     /// Returns `None` for compressed or external attachments.
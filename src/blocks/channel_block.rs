@@ -91,6 +91,17 @@ impl BlockParse<'_> for ChannelBlock {
 }
 
 impl ChannelBlock {
+    /// Channel flag: all values of the channel are invalid.
+    pub const FLAG_ALL_INVALID: u32 = 0x01;
+    /// Channel flag: `pos_invalidation_bit` is valid.
+    pub const FLAG_INVAL_BIT_VALID: u32 = 0x02;
+    /// Channel flag: `precision` is valid.
+    pub const FLAG_PRECISION_VALID: u32 = 0x04;
+    /// Channel flag: `min_raw_value` and `max_raw_value` are valid.
+    pub const FLAG_VALUE_RANGE_VALID: u32 = 0x08;
+    /// Channel flag: `lower_limit` and `upper_limit` are valid.
+    pub const FLAG_LIMIT_RANGE_VALID: u32 = 0x10;
+
     /// Serializes the ChannelBlock to bytes according to MDF 4.1 specification.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         validate_block_id(&self.header, "##CN")?;
@@ -32,6 +32,10 @@ pub struct ChannelGroupBlock {
     pub first_sample_reduction_addr: u64,
     /// Link to comment text/metadata block.
     pub comment_addr: u64,
+    /// Link to the channel group holding the master channel of this group
+    /// (MDF 4.2 column oriented storage); only stored with
+    /// [`FLAG_REMOTE_MASTER`](Self::FLAG_REMOTE_MASTER).
+    pub master_cg_addr: Option<u64>,
     /// Record ID for identifying records in unsorted data.
     pub record_id: u64,
     /// Number of cycles (records) in this channel group.
@@ -51,38 +55,63 @@ impl BlockParse<'_> for ChannelGroupBlock {
 
     fn from_bytes(bytes: &[u8]) -> Result<Self> {
         let header = Self::parse_header(bytes)?;
-        validate_buffer_size(bytes, CG_BLOCK_SIZE)?;
+        // MDF 4.2 adds a seventh link to the master channel group
+        let remote_master = header.link_count >= 7;
+        let data = if remote_master { 80 } else { 72 };
+        validate_buffer_size(bytes, data + 32)?;
 
         Ok(Self {
             header,
-            // Links section (6 x u64 = 48 bytes at offset 24)
+            // Links section (6 or 7 x u64 at offset 24)
             next_cg_addr: read_u64(bytes, 24),
             first_ch_addr: read_u64(bytes, 32),
             acq_name_addr: read_u64(bytes, 40),
             acq_source_addr: read_u64(bytes, 48),
             first_sample_reduction_addr: read_u64(bytes, 56),
             comment_addr: read_u64(bytes, 64),
-            // Data section at offset 72
-            record_id: read_u64(bytes, 72),
-            cycle_count: read_u64(bytes, 80),
-            flags: read_u16(bytes, 88),
-            path_separator: read_u16(bytes, 90),
-            // bytes 92-96: reserved (skipped)
-            record_size: read_u32(bytes, 96),
-            invalidation_size: read_u32(bytes, 100),
+            master_cg_addr: remote_master.then(|| read_u64(bytes, 72)),
+            // Data section after the links
+            record_id: read_u64(bytes, data),
+            cycle_count: read_u64(bytes, data + 8),
+            flags: read_u16(bytes, data + 16),
+            path_separator: read_u16(bytes, data + 18),
+            // 4 reserved bytes (skipped)
+            record_size: read_u32(bytes, data + 24),
+            invalidation_size: read_u32(bytes, data + 28),
         })
     }
 }
 impl ChannelGroupBlock {
-    /// Serializes the ChannelGroupBlock to bytes according to MDF 4.1 specification.
+    /// Remote master (MDF 4.2): the master channel of this group is in the
+    /// group linked by [`master_cg_addr`](Self::master_cg_addr).
+    pub const FLAG_REMOTE_MASTER: u16 = 0x08;
+
+    /// Offset of the data section, which follows the 6 links of MDF 4.1 or
+    /// the 7 links of a group with a remote master.
+    pub fn data_offset(&self) -> u64 {
+        if self.master_cg_addr.is_some() {
+            80
+        } else {
+            72
+        }
+    }
+
+    /// Serializes the ChannelGroupBlock to bytes according to MDF 4.1
+    /// specification, or MDF 4.2 if it links a remote master group.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         validate_block_id(&self.header, "##CG")?;
-        validate_block_length(&self.header, CG_BLOCK_SIZE as u64)?;
+        let mut header = self.header.clone();
+        if self.master_cg_addr.is_some() {
+            header.length = CG_BLOCK_SIZE as u64 + 8;
+            header.link_count = 7;
+        } else {
+            validate_block_length(&header, CG_BLOCK_SIZE as u64)?;
+        }
 
-        let mut buffer = Vec::with_capacity(CG_BLOCK_SIZE);
+        let mut buffer = Vec::with_capacity(header.length as usize);
 
         // Header (24 bytes)
-        buffer.extend_from_slice(&self.header.to_bytes()?);
+        buffer.extend_from_slice(&header.to_bytes()?);
 
         // Links (48 bytes)
         buffer.extend_from_slice(&self.next_cg_addr.to_le_bytes());
@@ -91,6 +120,9 @@ impl ChannelGroupBlock {
         buffer.extend_from_slice(&self.acq_source_addr.to_le_bytes());
         buffer.extend_from_slice(&self.first_sample_reduction_addr.to_le_bytes());
         buffer.extend_from_slice(&self.comment_addr.to_le_bytes());
+        if let Some(master) = self.master_cg_addr {
+            buffer.extend_from_slice(&master.to_le_bytes());
+        }
 
         // Data section (32 bytes)
         buffer.extend_from_slice(&self.record_id.to_le_bytes());
@@ -144,6 +176,7 @@ impl Default for ChannelGroupBlock {
             acq_source_addr: 0,
             first_sample_reduction_addr: 0,
             comment_addr: 0,
+            master_cg_addr: None,
             record_id: 0,
             cycle_count: 0,
             flags: 0,
@@ -536,6 +536,49 @@ impl ConversionBlock {
         }
     }
 
+    /// Creates a value to value table conversion without interpolation.
+    ///
+    /// Raw values between two keys map to the value of the nearest key; raw
+    /// values outside the table map to the first or last value.
+    ///
+    /// # Arguments
+    /// * `table` - `(raw, physical)` pairs sorted by raw value
+    ///
+    /// # Example
+    /// ```
+    /// use mdf4_rs::blocks::ConversionBlock;
+    ///
+    /// // Gear position: 0 -> 0, 1 -> 1, 2 (reverse) -> -1
+    /// let conv = ConversionBlock::value_to_value(&[(0.0, 0.0), (1.0, 1.0), (2.0, -1.0)]);
+    /// ```
+    pub fn value_to_value(table: &[(f64, f64)]) -> Self {
+        Self {
+            header: BlockHeader {
+                id: String::from("##CC"),
+                reserved: 0,
+                length: 0,
+                link_count: 4,
+            },
+            name_addr: None,
+            unit_addr: None,
+            comment_addr: None,
+            inverse_addr: None,
+            refs: Vec::new(),
+            conversion_type: ConversionType::TableLookupNoInterp,
+            precision: 0,
+            flags: 0,
+            ref_count: 0,
+            value_count: (table.len() * 2) as u16,
+            phys_range_min: None,
+            phys_range_max: None,
+            values: table.iter().flat_map(|&(raw, phys)| [raw, phys]).collect(),
+            formula: None,
+            resolved_texts: None,
+            resolved_conversions: None,
+            default_conversion: None,
+        }
+    }
+
     /// Check if this is a trivial identity conversion that can be omitted.
     ///
     /// Returns `true` if:
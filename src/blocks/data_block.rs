@@ -2,6 +2,7 @@ use crate::{
     Error, Result,
     blocks::common::{BlockHeader, BlockParse},
 };
+use alloc::string::ToString;
 
 #[derive(Debug, Clone)]
 pub struct DataBlock<'a> {
@@ -11,6 +12,20 @@ pub struct DataBlock<'a> {
 
 impl<'a> BlockParse<'a> for DataBlock<'a> {
     const ID: &'static str = "##DT";
+
+    /// Accept DT blocks and the DV blocks of column oriented storage, which
+    /// share their layout.
+    fn parse_header(bytes: &[u8]) -> Result<BlockHeader> {
+        let header = BlockHeader::from_bytes(&bytes[0..24])?;
+        if header.id != Self::ID && header.id != "##DV" {
+            return Err(Error::BlockIDError {
+                actual: header.id.clone(),
+                expected: Self::ID.to_string(),
+            });
+        }
+        Ok(header)
+    }
+
     /// Parse a DTBLOCK from the given byte slice.
     ///
     /// The slice must contain at least the number of bytes specified by the
@@ -107,6 +107,30 @@ impl DataListBlock {
         }
     }
 
+    /// Creates a new DataListBlock for data blocks of varying length.
+    ///
+    /// `block_offsets` holds the cumulative data offset of each referenced
+    /// block, i.e. the sum of the data section sizes of all previous blocks.
+    pub fn new_with_offsets(data_block_addrs: Vec<u64>, block_offsets: Vec<u64>) -> Self {
+        let link_count = data_block_addrs.len() as u64 + 1; // +1 for 'next'
+        let length = 24 + link_count * 8 + 8 + block_offsets.len() as u64 * 8;
+
+        Self {
+            header: BlockHeader {
+                id: "##DL".to_string(),
+                reserved: 0,
+                length,
+                link_count,
+            },
+            next_dl_addr: 0,
+            data_block_count: data_block_addrs.len() as u32,
+            data_block_addrs,
+            flags: 0,
+            equal_length: None,
+            block_offsets: Some(block_offsets),
+        }
+    }
+
     /// Serializes the DataListBlock to bytes according to MDF 4.1 specification.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         validate_block_id(&self.header, "##DL")?;
@@ -173,6 +173,68 @@ impl DzBlock<'_> {
 
         Ok(result)
     }
+
+    /// Compress the data section of a block into the bytes of a DZ block.
+    ///
+    /// With [`DzCompressionType::TranspositionDeflate`], `zip_parameter` is
+    /// the number of columns, usually the record size, and should divide the
+    /// data length; it is ignored for plain deflate.
+    ///
+    /// # Arguments
+    /// * `original_block_type` - Type of the compressed block, e.g. `*b"DT"`
+    /// * `data` - Data section of the block (without its header)
+    /// * `zip_type` - Compression algorithm
+    /// * `zip_parameter` - Column count for transposition
+    pub fn compress(
+        original_block_type: [u8; 2],
+        data: &[u8],
+        zip_type: DzCompressionType,
+        zip_parameter: u32,
+    ) -> Result<Vec<u8>> {
+        use miniz_oxide::deflate::compress_to_vec_zlib;
+
+        let (compressed, zip_parameter) = match zip_type {
+            DzCompressionType::Deflate => (compress_to_vec_zlib(data, 6), 0),
+            DzCompressionType::TranspositionDeflate => {
+                let columns = zip_parameter as usize;
+                if columns == 0 {
+                    return Err(Error::BlockSerializationError(
+                        "DZ transposition: zip_parameter (columns) cannot be 0".to_string(),
+                    ));
+                }
+                // Column by column, the inverse of inverse_transpose()
+                let rows = data.len().div_ceil(columns);
+                let mut transposed = vec![0u8; data.len()];
+                for col in 0..columns {
+                    for row in 0..rows {
+                        let src_idx = row * columns + col;
+                        let dst_idx = col * rows + row;
+                        if src_idx < data.len() && dst_idx < data.len() {
+                            transposed[dst_idx] = data[src_idx];
+                        }
+                    }
+                }
+                (compress_to_vec_zlib(&transposed, 6), zip_parameter)
+            }
+        };
+
+        let header = BlockHeader {
+            id: "##DZ".to_string(),
+            reserved: 0,
+            length: (DZ_HEADER_SIZE + compressed.len()) as u64,
+            link_count: 0,
+        };
+        let mut bytes = Vec::with_capacity(DZ_HEADER_SIZE + compressed.len());
+        bytes.extend_from_slice(&header.to_bytes()?);
+        bytes.extend_from_slice(&original_block_type);
+        bytes.push(zip_type as u8);
+        bytes.push(0);
+        bytes.extend_from_slice(&zip_parameter.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&compressed);
+        Ok(bytes)
+    }
 }
 
 #[cfg(test)]
@@ -308,6 +370,22 @@ mod tests {
             assert_eq!(decompressed, original_data);
         }
 
+        #[test]
+        fn compress_round_trip() {
+            let records: Vec<u8> = (0..64u8).collect();
+            for (zip_type, columns) in [
+                (DzCompressionType::Deflate, 0),
+                (DzCompressionType::TranspositionDeflate, 8),
+            ] {
+                let bytes = DzBlock::compress(*b"DT", &records, zip_type, columns).unwrap();
+                let dz = DzBlock::from_bytes(&bytes).unwrap();
+                assert_eq!(dz.header.length as usize, bytes.len());
+                assert_eq!(&dz.original_block_type, b"DT");
+                assert_eq!(dz.zip_type, zip_type);
+                assert_eq!(dz.decompress().unwrap(), records);
+            }
+        }
+
         #[test]
         fn decompress_size_mismatch() {
             let original_data = b"test";
@@ -16,6 +16,7 @@ use alloc::vec::Vec;
 
 /// Event type enumeration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EventType {
     /// Recording event (start/stop/pause/resume of recording).
@@ -40,6 +41,7 @@ impl EventType {
 
 /// Event synchronization type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EventSyncType {
     /// Time in seconds.
@@ -67,6 +69,7 @@ impl EventSyncType {
 
 /// Event range type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EventRangeType {
     /// Point event (single instant).
@@ -91,6 +94,7 @@ impl EventRangeType {
 
 /// Event cause enumeration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EventCause {
     /// Other/unknown cause.
@@ -37,9 +37,9 @@ pub struct HeaderBlock {
     pub dst_offset_min: i16,
     /// Time flags (bit 0: local time, bit 1: offsets valid).
     pub time_flags: u8,
-    /// Time quality class (0=unknown, 10=external sync, 16=local PC).
+    /// Time quality class (0=local PC, 10=external source, 16=external absolute sync).
     pub time_quality: u8,
-    /// Header flags.
+    /// Header flags (bit 0: start angle valid, bit 1: start distance valid).
     pub flags: u8,
     /// Start angle in radians (for angular synchronization).
     pub start_angle_rad: f64,
@@ -48,6 +48,23 @@ pub struct HeaderBlock {
 }
 
 impl HeaderBlock {
+    /// Time flag: `start_time_ns` is local time instead of UTC.
+    pub const TIME_FLAG_LOCAL_TIME: u8 = 0x01;
+    /// Time flag: `tz_offset_min` and `dst_offset_min` are valid.
+    pub const TIME_FLAG_OFFSETS_VALID: u8 = 0x02;
+
+    /// Time quality class: local PC reference time (default).
+    pub const TIME_QUALITY_LOCAL_PC: u8 = 0;
+    /// Time quality class: external time source.
+    pub const TIME_QUALITY_EXTERNAL: u8 = 10;
+    /// Time quality class: external absolute synchronized time.
+    pub const TIME_QUALITY_EXTERNAL_ABSOLUTE: u8 = 16;
+
+    /// Header flag: `start_angle_rad` is valid.
+    pub const FLAG_START_ANGLE_VALID: u8 = 0x01;
+    /// Header flag: `start_distance_m` is valid.
+    pub const FLAG_START_DISTANCE_VALID: u8 = 0x02;
+
     /// Serializes the HeaderBlock to bytes according to MDF 4.1 specification.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         validate_block_id(&self.header, "##HD")?;
@@ -114,3 +114,133 @@ impl Default for MetadataBlock {
         Self::new("")
     }
 }
+
+/// Name/value pairs for the `<common_properties>` element of an MD comment.
+///
+/// Properties are written in insertion order. Trees group related values,
+/// e.g. the entries of a value table.
+///
+/// # Example
+/// ```
+/// use mdf4_rs::blocks::CommonProperties;
+///
+/// let props = CommonProperties::new()
+///     .with_value("GenMsgCycleTime", "100")
+///     .with_tree("ValueTable", CommonProperties::new().with_value("0", "Off"));
+/// assert_eq!(
+///     props.to_xml(),
+///     "<common_properties><e name=\"GenMsgCycleTime\">100</e>\
+///      <tree name=\"ValueTable\"><e name=\"0\">Off</e></tree></common_properties>"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommonProperties {
+    entries: Vec<PropertyEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PropertyEntry {
+    Value(String, String),
+    Tree(String, CommonProperties),
+}
+
+impl CommonProperties {
+    /// Creates an empty property list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a value named `name`.
+    pub fn with_value(mut self, name: &str, value: &str) -> Self {
+        self.push_value(name, value);
+        self
+    }
+
+    /// Appends a tree named `name` holding `properties`.
+    pub fn with_tree(mut self, name: &str, properties: CommonProperties) -> Self {
+        self.push_tree(name, properties);
+        self
+    }
+
+    /// Appends a value named `name`.
+    pub fn push_value(&mut self, name: &str, value: &str) {
+        self.entries
+            .push(PropertyEntry::Value(name.to_string(), value.to_string()));
+    }
+
+    /// Appends a tree named `name` holding `properties`.
+    pub fn push_tree(&mut self, name: &str, properties: CommonProperties) {
+        self.entries
+            .push(PropertyEntry::Tree(name.to_string(), properties));
+    }
+
+    /// Returns `true` if no properties were added.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Renders the `<common_properties>` element.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<common_properties>");
+        self.write_entries(&mut xml);
+        xml.push_str("</common_properties>");
+        xml
+    }
+
+    fn write_entries(&self, xml: &mut String) {
+        for entry in &self.entries {
+            match entry {
+                PropertyEntry::Value(name, value) => xml.push_str(&format!(
+                    "<e name=\"{}\">{}</e>",
+                    xml_escape(name),
+                    xml_escape(value)
+                )),
+                PropertyEntry::Tree(name, properties) => {
+                    xml.push_str(&format!("<tree name=\"{}\">", xml_escape(name)));
+                    properties.write_entries(xml);
+                    xml.push_str("</tree>");
+                }
+            }
+        }
+    }
+}
+
+/// Escape the five XML special characters in `text`.
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverse of [`xml_escape`].
+#[cfg(feature = "std")]
+pub(crate) fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Return the unescaped text content of the first `<tag>...</tag>` element in `xml`.
+///
+/// This is a minimal extractor for the flat elements used in MDF comment
+/// blocks; it does not handle attributes on the element or nested elements
+/// with the same name.
+#[cfg(feature = "std")]
+pub(crate) fn xml_element_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    Some(xml_unescape(&xml[start..end]))
+}
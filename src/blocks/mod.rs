@@ -27,6 +27,9 @@ pub(crate) const SI_BLOCK_SIZE: usize = 56;
 /// File history block size (56 bytes) - records modification history.
 pub(crate) const FH_BLOCK_SIZE: usize = 56;
 
+/// Sample reduction block size (64 bytes) - describes min/mean/max overview data.
+pub(crate) const SR_BLOCK_SIZE: usize = 64;
+
 /// Event block minimum size (96 bytes) - timestamped markers.
 /// Actual size varies based on scope_count and attachment_count.
 /// Base: 24 (header) + 40 (5 fixed links) + 32 (data) = 96 bytes.
@@ -56,7 +59,9 @@ mod file_history_block;
 mod header_block;
 pub(crate) mod hl_block;
 mod identification_block;
+mod md5;
 mod metadata_block;
+mod sample_reduction_block;
 mod signal_data_block;
 mod source_block;
 mod text_block;
@@ -83,7 +88,11 @@ pub use file_history_block::FileHistoryBlock;
 pub use header_block::HeaderBlock;
 pub use hl_block::HlBlock;
 pub use identification_block::IdentificationBlock;
-pub use metadata_block::MetadataBlock;
+#[cfg(feature = "std")]
+pub(crate) use metadata_block::xml_element_text;
+pub(crate) use metadata_block::xml_escape;
+pub use metadata_block::{CommonProperties, MetadataBlock};
+pub use sample_reduction_block::SampleReductionBlock;
 pub use signal_data_block::SignalDataBlock;
 #[cfg(feature = "std")]
 pub(crate) use source_block::read_source_block;
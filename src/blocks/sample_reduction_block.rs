@@ -0,0 +1,157 @@
+//! Sample Reduction Block (##SR) - precomputed min/mean/max overview data.
+//!
+//! An SR block describes a reduced version of a channel group's records,
+//! which viewers use to plot long signals without reading every sample.
+
+use super::SR_BLOCK_SIZE;
+use crate::{
+    Result,
+    blocks::common::{
+        BlockHeader, BlockParse, debug_assert_aligned, read_f64, read_u8, read_u64,
+        validate_buffer_size,
+    },
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Sample Reduction Block (##SR) - describes one reduction of a channel group.
+///
+/// SR blocks form a linked list starting from the channel group's
+/// `first_sample_reduction_addr`. The referenced RD (or DZ/DL) block holds
+/// `cycle_count` reduction records. Each reduction record contains the
+/// channel group's record (without record ID) three times: first with the
+/// mean, then the minimum and finally the maximum values of the interval.
+///
+/// # MDF4 Specification
+///
+/// The SR block has:
+/// - 2 links: next SR block, reduction data block
+/// - Number of reduction records and the interval length
+/// - Sync type of the interval and flags
+#[derive(Debug, Clone)]
+pub struct SampleReductionBlock {
+    /// Standard block header.
+    pub header: BlockHeader,
+    /// Link to next sample reduction block (0 = end of list).
+    pub next_sr_addr: u64,
+    /// Link to the RD, DZ or DL block holding the reduction records.
+    pub data_addr: u64,
+    /// Number of reduction records.
+    pub cycle_count: u64,
+    /// Length of one interval, in the unit given by `sync_type`.
+    pub interval: f64,
+    /// Sync type of `interval` (see the `SYNC_TYPE_*` constants).
+    pub sync_type: u8,
+    /// Flags:
+    /// - Bit 0: Reduction records contain invalidation bytes
+    /// - Bit 1: Invalidation bits use dominant bit logic
+    pub flags: u8,
+}
+
+impl BlockParse<'_> for SampleReductionBlock {
+    const ID: &'static str = "##SR";
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let header = Self::parse_header(bytes)?;
+        validate_buffer_size(bytes, SR_BLOCK_SIZE)?;
+
+        Ok(Self {
+            header,
+            // Links section (2 x u64 = 16 bytes at offset 24)
+            next_sr_addr: read_u64(bytes, 24),
+            data_addr: read_u64(bytes, 32),
+            // Data section at offset 40
+            cycle_count: read_u64(bytes, 40),
+            interval: read_f64(bytes, 48),
+            sync_type: read_u8(bytes, 56),
+            flags: read_u8(bytes, 57),
+            // bytes 58-63: reserved
+        })
+    }
+}
+
+impl SampleReductionBlock {
+    /// Interval is a time in seconds.
+    pub const SYNC_TYPE_TIME: u8 = 1;
+    /// Interval is an angle in radians.
+    pub const SYNC_TYPE_ANGLE: u8 = 2;
+    /// Interval is a distance in meters.
+    pub const SYNC_TYPE_DISTANCE: u8 = 3;
+    /// Interval is a number of records.
+    pub const SYNC_TYPE_INDEX: u8 = 4;
+
+    /// Reduction records contain invalidation bytes.
+    pub const FLAG_INVALIDATION_BYTES: u8 = 0x01;
+    /// Invalidation bits use dominant bit logic.
+    pub const FLAG_DOMINANT_BIT: u8 = 0x02;
+
+    /// Creates a new SampleReductionBlock.
+    ///
+    /// # Arguments
+    /// * `data_addr` - Address of the block holding the reduction records
+    /// * `cycle_count` - Number of reduction records
+    /// * `interval` - Interval length in the unit given by `sync_type`
+    /// * `sync_type` - One of the `SYNC_TYPE_*` constants
+    pub fn new(data_addr: u64, cycle_count: u64, interval: f64, sync_type: u8) -> Self {
+        Self {
+            header: BlockHeader {
+                id: String::from("##SR"),
+                reserved: 0,
+                length: SR_BLOCK_SIZE as u64,
+                link_count: 2,
+            },
+            next_sr_addr: 0,
+            data_addr,
+            cycle_count,
+            interval,
+            sync_type,
+            flags: 0,
+        }
+    }
+
+    /// Serializes the SampleReductionBlock to bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::with_capacity(SR_BLOCK_SIZE);
+
+        // Header (24 bytes)
+        buffer.extend_from_slice(&self.header.to_bytes()?);
+
+        // Links (16 bytes)
+        buffer.extend_from_slice(&self.next_sr_addr.to_le_bytes());
+        buffer.extend_from_slice(&self.data_addr.to_le_bytes());
+
+        // Data section (24 bytes)
+        buffer.extend_from_slice(&self.cycle_count.to_le_bytes());
+        buffer.extend_from_slice(&self.interval.to_le_bytes());
+        buffer.push(self.sync_type);
+        buffer.push(self.flags);
+        buffer.extend_from_slice(&[0u8; 6]); // reserved
+
+        debug_assert_aligned(buffer.len());
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let mut original =
+            SampleReductionBlock::new(0x2000, 42, 100.0, SampleReductionBlock::SYNC_TYPE_INDEX);
+        original.next_sr_addr = 0x1000;
+
+        let bytes = original.to_bytes().unwrap();
+        assert_eq!(bytes.len(), SR_BLOCK_SIZE);
+
+        let parsed = SampleReductionBlock::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.header.id, "##SR");
+        assert_eq!(parsed.next_sr_addr, original.next_sr_addr);
+        assert_eq!(parsed.data_addr, original.data_addr);
+        assert_eq!(parsed.cycle_count, original.cycle_count);
+        assert_eq!(parsed.interval, original.interval);
+        assert_eq!(parsed.sync_type, original.sync_type);
+        assert_eq!(parsed.flags, 0);
+    }
+}
@@ -154,6 +154,26 @@ impl SourceBlock {
         Self::new(SourceType::Bus, BusType::FlexRay)
     }
 
+    /// Creates a new SourceBlock for a USB interface.
+    pub fn usb() -> Self {
+        Self::new(SourceType::Bus, BusType::USB)
+    }
+
+    /// Creates a new SourceBlock for an ECU on a LIN bus.
+    pub fn lin_ecu() -> Self {
+        Self::new(SourceType::ECU, BusType::LIN)
+    }
+
+    /// Creates a new SourceBlock for an ECU on a FlexRay bus.
+    pub fn flexray_ecu() -> Self {
+        Self::new(SourceType::ECU, BusType::FlexRay)
+    }
+
+    /// Creates a new SourceBlock for an ECU on an Ethernet network.
+    pub fn ethernet_ecu() -> Self {
+        Self::new(SourceType::ECU, BusType::Ethernet)
+    }
+
     /// Serializes the SourceBlock to bytes according to MDF 4.1 specification.
     pub fn to_bytes(&self) -> Result<alloc::vec::Vec<u8>> {
         use alloc::vec::Vec;
@@ -1,14 +1,77 @@
 //! Shared utilities for ASAM MDF4 Bus Logging.
 //!
 //! This module provides common traits and helper functions used by
-//! CAN, Ethernet, LIN, and FlexRay loggers.
+//! CAN, Ethernet, LIN, and FlexRay loggers:
+//!
+//! - [`BusFrame`]: record encoding and channel group naming of a frame type
+//! - [`BusChannel`]: buffered frames of one channel group, created on first
+//!   write
+//! - [`BusLoggerCore`]: the MDF writer, source name, buffer limit and
+//!   automatic flush state shared by all loggers
+//!
+//! The overlay readers of the buses share the merge of their channel groups
+//! by timestamp, which only needs a parser for the frame bytes of the bus.
+//!
+//! # Adding a Bus
+//!
+//! A logger for a new bus implements [`BusFrame`] for its frame type and
+//! combines a [`BusLoggerCore`] with one [`BusChannel`] per channel group:
+//!
+//! ```ignore
+//! use mdf4_rs::blocks::{BusType, SourceBlock, SourceType};
+//! use mdf4_rs::bus_logging::{BusChannel, BusFrame, BusLoggerCore};
+//! use mdf4_rs::writer::MdfWrite;
+//!
+//! #[derive(Clone)]
+//! struct MostFrame { /* ... */ }
+//!
+//! impl BusFrame for MostFrame {
+//!     const CHANNEL_NAME: &'static str = "MOST_MessageFrame";
+//!     const RECORD_SIZE: usize = 64;
+//!
+//!     fn source_block() -> SourceBlock {
+//!         SourceBlock::new(SourceType::Bus, BusType::MOST)
+//!     }
+//!
+//!     fn to_mdf_bytes(&self) -> Vec<u8> { /* ... */ }
+//! }
+//!
+//! struct RawMostLogger<W: MdfWrite> {
+//!     core: BusLoggerCore<W>,
+//!     frames: BusChannel<MostFrame>,
+//! }
+//!
+//! impl<W: MdfWrite> RawMostLogger<W> {
+//!     fn log(&mut self, timestamp_us: u64, frame: MostFrame) {
+//!         self.frames.push(timestamp_us, frame);
+//!         if self.core.is_full(self.frames.buffered()) {
+//!             let result = self.flush_buffers();
+//!             self.core.record_auto_flush(result);
+//!         }
+//!     }
+//!
+//!     fn flush(&mut self) -> mdf4_rs::Result<()> {
+//!         self.core.take_auto_flush_error()?;
+//!         self.flush_buffers()
+//!     }
+//!
+//!     fn flush_buffers(&mut self) -> mdf4_rs::Result<()> {
+//!         self.core.ensure_initialized()?;
+//!         self.frames.write(&mut self.core)
+//!     }
+//! }
+//! ```
 
+#[cfg(feature = "std")]
+use alloc::collections::VecDeque;
 use alloc::string::String;
 use alloc::vec::Vec;
 
 use crate::blocks::SourceBlock;
-use crate::writer::MdfWrite;
-use crate::{DataType, DecodedValue, MdfWriter, Result};
+#[cfg(feature = "std")]
+use crate::index::{ByteRangeReader, MdfIndex};
+use crate::writer::{FlushPolicy, MdfWrite};
+use crate::{DataType, DecodedValue, Error, MdfWriter, Result};
 
 /// Conversion factor from microseconds to seconds.
 pub const MICROS_TO_SECONDS: f64 = 1.0 / 1_000_000.0;
@@ -39,7 +102,29 @@ impl<F> TimestampedFrame<F> {
 }
 
 /// Trait for frame types that can be serialized to bytes.
+///
+/// Besides the record encoding, the trait describes the channel group the
+/// frames are logged to by a [`BusChannel`]: `{source_name}_{group_suffix}`
+/// with a Float64 `Timestamp` master channel and a fixed size ByteArray
+/// channel named [`CHANNEL_NAME`](Self::CHANNEL_NAME).
 pub trait BusFrame: Clone {
+    /// Name of the ByteArray channel holding the frames, e.g. `LIN_Frame`.
+    const CHANNEL_NAME: &'static str;
+
+    /// Size of the ByteArray channel in bytes. Shorter frames are
+    /// zero-padded.
+    const RECORD_SIZE: usize;
+
+    /// Source block of the bus, e.g. [`SourceBlock::lin_bus`].
+    fn source_block() -> SourceBlock;
+
+    /// Suffix of the channel group name `{source_name}_{suffix}`.
+    ///
+    /// Default: the channel name
+    fn group_suffix() -> &'static str {
+        Self::CHANNEL_NAME
+    }
+
     /// Serialize the frame to bytes for MDF storage.
     fn to_mdf_bytes(&self) -> Vec<u8>;
 
@@ -141,6 +226,240 @@ where
     Ok(())
 }
 
+/// Buffered frames of one bus logging channel group.
+///
+/// The channel group `{source_name}_{group_suffix}` is created by the first
+/// [`write`](Self::write) with buffered frames, so groups of frames that
+/// first appear after a flush are still created. The layout comes from the
+/// [`BusFrame`] implementation; [`with_names`](Self::with_names) logs a
+/// frame type to a differently named group, e.g. LIN frames with checksum
+/// errors.
+pub struct BusChannel<F> {
+    frames: Vec<TimestampedFrame<F>>,
+    /// Frames logged, including frames already written
+    count: usize,
+    /// Channel group name suffix
+    group_suffix: &'static str,
+    /// ByteArray channel name
+    channel_name: &'static str,
+    /// Channel group ID
+    channel_group: Option<String>,
+}
+
+impl<F: BusFrame> Default for BusChannel<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: BusFrame> BusChannel<F> {
+    /// Create a channel with the group and channel names of `F`.
+    pub fn new() -> Self {
+        Self::with_names(F::group_suffix(), F::CHANNEL_NAME)
+    }
+
+    /// Create a channel with a custom group name suffix and ByteArray
+    /// channel name.
+    pub fn with_names(group_suffix: &'static str, channel_name: &'static str) -> Self {
+        Self {
+            frames: Vec::new(),
+            count: 0,
+            group_suffix,
+            channel_name,
+            channel_group: None,
+        }
+    }
+
+    /// Reserve capacity for at least `additional` more buffered frames.
+    pub fn reserve(&mut self, additional: usize) {
+        self.frames.reserve(additional);
+    }
+
+    /// Buffer a frame.
+    pub fn push(&mut self, timestamp_us: u64, frame: F) {
+        self.frames.push(TimestampedFrame::new(timestamp_us, frame));
+        self.count += 1;
+    }
+
+    /// The buffered frames, not yet written.
+    pub fn frames(&self) -> &[TimestampedFrame<F>] {
+        &self.frames
+    }
+
+    /// Number of frames buffered.
+    pub fn buffered(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Number of frames logged, including frames already written.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Forget the channel group, so the next write creates it again, e.g.
+    /// after the logger continues in a new file.
+    pub fn reset_group(&mut self) {
+        self.channel_group = None;
+    }
+
+    /// Write and clear the buffered frames, creating the channel group on
+    /// first use.
+    pub fn write<W: MdfWrite>(&mut self, core: &mut BusLoggerCore<W>) -> Result<()> {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+        let writer = &mut core.writer;
+        let cg = match &self.channel_group {
+            Some(cg) => cg,
+            None => {
+                let config = BusLoggerConfig {
+                    source_name: core.source_name.clone(),
+                    group_name: alloc::format!("{}_{}", core.source_name, self.group_suffix),
+                    data_channel_name: String::from(self.channel_name),
+                    data_channel_bits: (F::RECORD_SIZE * 8) as u32,
+                    source_block: F::source_block(),
+                };
+                let (cg, _data_ch) = init_bus_channel_group(writer, &config)?;
+                self.channel_group.insert(cg)
+            }
+        };
+
+        writer.start_data_block_for_cg(cg, 0)?;
+        for entry in self.frames.drain(..) {
+            let mut bytes = entry.frame.to_mdf_bytes();
+            bytes.resize(F::RECORD_SIZE, 0);
+            let values = [
+                DecodedValue::Float(entry.timestamp_s),
+                DecodedValue::ByteArray(bytes),
+            ];
+            writer.write_record(cg, &values)?;
+        }
+        writer.finish_data_block(cg)
+    }
+}
+
+/// State shared by all bus loggers: the MDF writer, the source name, the
+/// buffer limit and the error of the last automatic flush.
+///
+/// Loggers keep their frames in [`BusChannel`]s or their own buffers and
+/// use the core to initialize the file once, flush when the buffer limit is
+/// reached and finalize the file.
+pub struct BusLoggerCore<W: MdfWrite> {
+    writer: MdfWriter<W>,
+    /// Source name for metadata and channel group names
+    source_name: String,
+    /// Write buffered frames once this many frames are buffered
+    max_buffered_frames: Option<usize>,
+    /// First error of an automatic flush, returned by the next `flush()`
+    auto_flush_error: Option<Error>,
+    initialized: bool,
+}
+
+impl<W: MdfWrite> BusLoggerCore<W> {
+    /// Create a core writing to `writer`.
+    pub fn new(writer: MdfWriter<W>, source_name: &str) -> Self {
+        Self {
+            writer,
+            source_name: String::from(source_name),
+            max_buffered_frames: None,
+            auto_flush_error: None,
+            initialized: false,
+        }
+    }
+
+    /// The source name.
+    pub fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    /// Set the source name. Channel groups created later use the new name.
+    pub fn set_source_name(&mut self, name: &str) {
+        self.source_name = String::from(name);
+    }
+
+    /// The MDF writer.
+    pub fn writer(&self) -> &MdfWriter<W> {
+        &self.writer
+    }
+
+    /// The MDF writer, for channel groups with a custom layout.
+    pub fn writer_mut(&mut self) -> &mut MdfWriter<W> {
+        &mut self.writer
+    }
+
+    /// The MDF writer and the source name, borrowed together.
+    pub fn writer_and_source_name(&mut self) -> (&mut MdfWriter<W>, &str) {
+        (&mut self.writer, &self.source_name)
+    }
+
+    /// Consume the core and return the MDF writer.
+    pub fn into_writer(self) -> MdfWriter<W> {
+        self.writer
+    }
+
+    /// Continue with another MDF writer and return the previous one.
+    ///
+    /// The new file is initialized by the next flush; the channels writing
+    /// to it must be reset with [`BusChannel::reset_group`].
+    pub fn replace_writer(&mut self, writer: MdfWriter<W>) -> MdfWriter<W> {
+        self.initialized = false;
+        core::mem::replace(&mut self.writer, writer)
+    }
+
+    /// The buffer limit set with
+    /// [`set_max_buffered_frames`](Self::set_max_buffered_frames).
+    pub fn max_buffered_frames(&self) -> Option<usize> {
+        self.max_buffered_frames
+    }
+
+    /// Flush once `frames` frames are buffered.
+    pub fn set_max_buffered_frames(&mut self, frames: usize) {
+        self.max_buffered_frames = Some(frames);
+    }
+
+    /// Set the flush policy of the MDF writer.
+    pub fn set_flush_policy(&mut self, policy: FlushPolicy) {
+        self.writer.set_flush_policy(policy);
+    }
+
+    /// Whether `buffered` frames reach the buffer limit.
+    pub fn is_full(&self, buffered: usize) -> bool {
+        self.max_buffered_frames.is_some_and(|max| buffered >= max)
+    }
+
+    /// Keep the error of an automatic flush for the next
+    /// [`take_auto_flush_error`](Self::take_auto_flush_error). Only the
+    /// first error is kept.
+    pub fn record_auto_flush(&mut self, result: Result<()>) {
+        match result {
+            Err(err) if self.auto_flush_error.is_none() => self.auto_flush_error = Some(err),
+            _ => {}
+        }
+    }
+
+    /// Return the error of an automatic flush since the last call, if any.
+    pub fn take_auto_flush_error(&mut self) -> Result<()> {
+        match self.auto_flush_error.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the file header on the first call.
+    pub fn ensure_initialized(&mut self) -> Result<()> {
+        if !self.initialized {
+            self.writer.init_mdf_file()?;
+            self.initialized = true;
+        }
+        Ok(())
+    }
+
+    /// Finalize the MDF file. Buffered frames must be written before.
+    pub fn finalize(&mut self) -> Result<()> {
+        self.writer.finalize()
+    }
+}
+
 /// Statistics for a bus logger.
 #[derive(Debug, Clone, Default)]
 pub struct BusLoggerStats {
@@ -154,6 +473,161 @@ pub struct BusLoggerStats {
     pub error_frames: usize,
 }
 
+/// Timestamp in microseconds of a loaded `Timestamp` value (seconds as
+/// f64, or integer microseconds).
+#[cfg(feature = "std")]
+pub(crate) fn capture_timestamp_us(value: &Option<DecodedValue>) -> Option<u64> {
+    match value {
+        Some(DecodedValue::Float(secs)) => Some((*secs * 1_000_000.0) as u64),
+        Some(DecodedValue::UnsignedInteger(us)) => Some(*us),
+        Some(DecodedValue::SignedInteger(us)) => Some(*us as u64),
+        _ => None,
+    }
+}
+
+/// A channel group of a bus capture with a timestamp channel and a
+/// ByteArray frame channel.
+#[cfg(feature = "std")]
+pub(crate) trait CaptureGroup {
+    /// Index in the `MdfIndex` channel groups
+    fn group_index(&self) -> usize;
+    /// Indices of the timestamp channel and of the frame channel
+    fn channels(&self) -> [usize; 2];
+}
+
+#[cfg(feature = "std")]
+impl<G: CaptureGroup> CaptureGroup for &G {
+    fn group_index(&self) -> usize {
+        (*self).group_index()
+    }
+
+    fn channels(&self) -> [usize; 2] {
+        (*self).channels()
+    }
+}
+
+/// Read position within one capture group.
+#[cfg(feature = "std")]
+struct GroupCursor<T> {
+    /// Next data block to read
+    next_block: usize,
+    /// Frames of the current data block not yet yielded
+    frames: VecDeque<(u64, T)>,
+}
+
+/// Streaming merge of the frames of several capture groups, in timestamp
+/// order.
+///
+/// The frames are read one data block per channel group at a time and
+/// merged across groups by timestamp, so memory use is bounded by the data
+/// block size rather than the capture size. Each channel group is expected
+/// to be in time order, as written by the loggers; the first group wins
+/// ties. Records without a timestamp, and frames `parse` rejects, are
+/// skipped. After a read error the error is yielded once and the iteration
+/// ends.
+#[cfg(feature = "std")]
+pub(crate) struct MergedFrames<'a, R, G, T> {
+    index: &'a MdfIndex,
+    reader: &'a mut R,
+    groups: Vec<(G, GroupCursor<T>)>,
+    /// Parser of the frame bytes of a record of a group
+    parse: fn(&G, &[u8]) -> Option<T>,
+    failed: bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a, R, G, T> MergedFrames<'a, R, G, T>
+where
+    R: ByteRangeReader<Error = Error>,
+    G: CaptureGroup,
+{
+    pub(crate) fn new(
+        index: &'a MdfIndex,
+        reader: &'a mut R,
+        groups: impl IntoIterator<Item = G>,
+        parse: fn(&G, &[u8]) -> Option<T>,
+    ) -> Self {
+        let groups = groups
+            .into_iter()
+            .map(|group| {
+                let cursor = GroupCursor {
+                    next_block: 0,
+                    frames: VecDeque::new(),
+                };
+                (group, cursor)
+            })
+            .collect();
+        Self {
+            index,
+            reader,
+            groups,
+            parse,
+            failed: false,
+        }
+    }
+
+    /// Read data blocks of a group until it has frames left or no more blocks.
+    fn fill(&mut self, group: usize) -> Result<()> {
+        let (group, cursor) = &mut self.groups[group];
+        let group_index = group.group_index();
+        let block_count = self.index.channel_groups[group_index].data_blocks.len();
+
+        while cursor.frames.is_empty() && cursor.next_block < block_count {
+            let columns = self.index.read_block_channels(
+                group_index,
+                cursor.next_block,
+                &group.channels(),
+                self.reader,
+            )?;
+            cursor.next_block += 1;
+            for (ts_val, frame_val) in columns[0].iter().zip(&columns[1]) {
+                let Some(timestamp_us) = capture_timestamp_us(ts_val) else {
+                    continue;
+                };
+                let Some(DecodedValue::ByteArray(bytes)) = frame_val else {
+                    continue;
+                };
+                if let Some(frame) = (self.parse)(group, bytes) {
+                    cursor.frames.push_back((timestamp_us, frame));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R, G, T> Iterator for MergedFrames<'_, R, G, T>
+where
+    R: ByteRangeReader<Error = Error>,
+    G: CaptureGroup,
+{
+    type Item = Result<(u64, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let mut earliest: Option<(usize, u64)> = None;
+        for group in 0..self.groups.len() {
+            if let Err(e) = self.fill(group) {
+                self.failed = true;
+                return Some(Err(e));
+            }
+            match self.groups[group].1.frames.front() {
+                Some(&(ts, _)) if earliest.is_none_or(|(_, min_ts)| ts < min_ts) => {
+                    earliest = Some((group, ts));
+                }
+                _ => {}
+            }
+        }
+
+        let (group, _) = earliest?;
+        self.groups[group].1.frames.pop_front().map(Ok)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +640,52 @@ mod tests {
         assert_eq!(timestamp_to_seconds(1_500_000), 1.5);
     }
 
+    #[derive(Clone)]
+    struct TestFrame(u8);
+
+    impl BusFrame for TestFrame {
+        const CHANNEL_NAME: &'static str = "TEST_Frame";
+        const RECORD_SIZE: usize = 4;
+
+        fn source_block() -> SourceBlock {
+            SourceBlock::can_bus()
+        }
+
+        fn to_mdf_bytes(&self) -> Vec<u8> {
+            alloc::vec![self.0]
+        }
+    }
+
+    #[test]
+    fn test_bus_channel_with_core() {
+        let writer = MdfWriter::in_memory();
+        let mut core = BusLoggerCore::new(writer, "Test");
+        core.set_max_buffered_frames(2);
+        let mut frames = BusChannel::<TestFrame>::new();
+        let mut events = BusChannel::<TestFrame>::with_names("Event", "TEST_Event");
+
+        for i in 0..5u8 {
+            frames.push(i as u64 * 1000, TestFrame(i));
+            if core.is_full(frames.buffered()) {
+                core.ensure_initialized().unwrap();
+                let result = frames.write(&mut core);
+                core.record_auto_flush(result);
+            }
+        }
+        assert_eq!(frames.buffered(), 1);
+        assert_eq!(frames.count(), 5);
+
+        // Created by a later write
+        events.push(6000, TestFrame(0xEE));
+        core.take_auto_flush_error().unwrap();
+        frames.write(&mut core).unwrap();
+        events.write(&mut core).unwrap();
+        core.finalize().unwrap();
+
+        let bytes = core.into_writer().into_inner().into_inner();
+        assert!(crate::writer::verify_mdf_bytes(&bytes).is_ok());
+    }
+
     #[test]
     fn test_timestamped_frame() {
         let frame = TimestampedFrame::new(2_500_000u64, vec![1u8, 2, 3]);
@@ -0,0 +1,267 @@
+//! Analysis of raw CAN captures.
+//!
+//! [`detect_gaps`] checks the periodic messages of a capture against their
+//! expected cycle times and reports frames that arrived late or are
+//! missing, a routine validation step for bus recordings.
+//!
+//! Messages are identified as in DBC files: the 29-bit CAN ID with bit 31
+//! set for extended IDs.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::can::analysis::{cycle_times_from_dbc, detect_gaps};
+//!
+//! let dbc_text = std::fs::read_to_string("vehicle.dbc")?;
+//! let cycle_times = cycle_times_from_dbc(&dbc_text);
+//!
+//! let frames = overlay.read_raw_frames(&mut reader)?;
+//! let gaps = detect_gaps(
+//!     frames.iter().map(|(ts, id, ext, _)| (*ts, if *ext { id | 0x8000_0000 } else { *id })),
+//!     &cycle_times,
+//! );
+//! for gap in gaps {
+//!     println!("{:#x}: {} frames missing at {} us", gap.message_id, gap.missing_frames, gap.start_us);
+//! }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use super::dbc_text::DbcText;
+
+/// Relative deviation from the cycle time accepted by [`detect_gaps`].
+pub const DEFAULT_CYCLE_TOLERANCE: f64 = 0.1;
+
+/// Kind of a timing violation found by [`detect_gaps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapKind {
+    /// The frame arrived later than the tolerance allows, but less than
+    /// one and a half cycles after the previous one
+    Late,
+    /// At least one frame is missing
+    Missing,
+}
+
+/// A late or missing periodic message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameGap {
+    /// Message ID, with bit 31 set for extended IDs
+    pub message_id: u32,
+    /// Timestamp of the last frame before the gap in microseconds, or the
+    /// start of the capture
+    pub start_us: u64,
+    /// Timestamp of the first frame after the gap in microseconds, or the
+    /// end of the capture
+    pub end_us: u64,
+    /// Expected cycle time in microseconds
+    pub cycle_time_us: u64,
+    /// Estimated number of missing frames; 0 for late frames
+    pub missing_frames: u64,
+    /// Whether the frame was late or frames are missing
+    pub kind: GapKind,
+}
+
+impl FrameGap {
+    /// Duration of the gap in microseconds.
+    pub fn duration_us(&self) -> u64 {
+        self.end_us - self.start_us
+    }
+}
+
+/// Find late and missing frames of periodic messages.
+///
+/// Uses a tolerance of [`DEFAULT_CYCLE_TOLERANCE`]; see
+/// [`detect_gaps_with_tolerance`].
+pub fn detect_gaps<I>(frames: I, expected_cycle_times: &BTreeMap<u32, u64>) -> Vec<FrameGap>
+where
+    I: IntoIterator<Item = (u64, u32)>,
+{
+    detect_gaps_with_tolerance(frames, expected_cycle_times, DEFAULT_CYCLE_TOLERANCE)
+}
+
+/// Find late and missing frames of periodic messages.
+///
+/// # Arguments
+/// * `frames` - (timestamp_us, message_id) of every frame of the capture,
+///   with bit 31 of the ID set for extended IDs
+/// * `expected_cycle_times` - Cycle time in microseconds by message ID,
+///   e.g. from [`cycle_times_from_dbc`]; other messages are ignored
+/// * `tolerance` - Accepted relative deviation, e.g. 0.1 for 10%
+///
+/// An interval between two frames longer than `cycle * (1 + tolerance)` is
+/// reported as [`GapKind::Late`], one longer than 1.5 cycles as
+/// [`GapKind::Missing`]. The start and end of the capture (its first and
+/// last frame of any message) also bound gaps, so messages that start late,
+/// stop early or never appear are reported as missing.
+///
+/// # Returns
+/// The gaps ordered by start time and message ID.
+pub fn detect_gaps_with_tolerance<I>(
+    frames: I,
+    expected_cycle_times: &BTreeMap<u32, u64>,
+    tolerance: f64,
+) -> Vec<FrameGap>
+where
+    I: IntoIterator<Item = (u64, u32)>,
+{
+    let mut timestamps: BTreeMap<u32, Vec<u64>> = BTreeMap::new();
+    let mut capture: Option<(u64, u64)> = None;
+    for (timestamp_us, message_id) in frames {
+        capture = Some(match capture {
+            Some((start, end)) => (start.min(timestamp_us), end.max(timestamp_us)),
+            None => (timestamp_us, timestamp_us),
+        });
+        if expected_cycle_times.contains_key(&message_id) {
+            timestamps.entry(message_id).or_default().push(timestamp_us);
+        }
+    }
+    let Some((capture_start, capture_end)) = capture else {
+        return Vec::new();
+    };
+
+    let mut gaps = Vec::new();
+    for (&message_id, &cycle_time_us) in expected_cycle_times {
+        if cycle_time_us == 0 {
+            continue;
+        }
+        let gap = |start_us: u64, end_us: u64, missing_frames: u64, kind: GapKind| FrameGap {
+            message_id,
+            start_us,
+            end_us,
+            cycle_time_us,
+            missing_frames,
+            kind,
+        };
+        // Capture boundaries are no frames, so only whole cycles count
+        let boundary_gap = |start_us: u64, end_us: u64| {
+            let missing = (end_us - start_us) / cycle_time_us;
+            (missing > 0).then(|| gap(start_us, end_us, missing, GapKind::Missing))
+        };
+
+        let Some(times) = timestamps.get_mut(&message_id) else {
+            gaps.extend(boundary_gap(capture_start, capture_end));
+            continue;
+        };
+        times.sort_unstable();
+
+        gaps.extend(boundary_gap(capture_start, times[0]));
+        let late_after = cycle_time_us as f64 * (1.0 + tolerance);
+        let missing_after = cycle_time_us as f64 * 1.5;
+        for pair in times.windows(2) {
+            let interval = (pair[1] - pair[0]) as f64;
+            if interval > missing_after {
+                let cycles = (interval / cycle_time_us as f64 + 0.5) as u64;
+                let missing = cycles.saturating_sub(1).max(1);
+                gaps.push(gap(pair[0], pair[1], missing, GapKind::Missing));
+            } else if interval > late_after {
+                gaps.push(gap(pair[0], pair[1], 0, GapKind::Late));
+            }
+        }
+        gaps.extend(boundary_gap(times[times.len() - 1], capture_end));
+    }
+
+    gaps.sort_by_key(|gap| (gap.start_us, gap.message_id));
+    gaps
+}
+
+/// Read the cycle times of the messages of a DBC file from its
+/// `GenMsgCycleTime` attributes.
+///
+/// A default set with `BA_DEF_DEF_` applies to every message without an
+/// own value. Messages with a cycle time of 0 (not periodic) are left out.
+///
+/// # Returns
+/// Cycle times in microseconds by message ID, with bit 31 set for extended
+/// IDs as in the DBC.
+pub fn cycle_times_from_dbc(dbc_text: &str) -> BTreeMap<u32, u64> {
+    let dbc = DbcText::parse(dbc_text);
+    dbc.message_ids()
+        .iter()
+        .filter_map(|&id| {
+            let ms: f64 = dbc.message_attribute(id, "GenMsgCycleTime")?.parse().ok()?;
+            (ms > 0.0).then_some((id, (ms * 1000.0 + 0.5) as u64))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_gaps() {
+        let mut frames = Vec::new();
+        // 0x100 every 10 ms with a late frame at 115 ms and 3 frames
+        // missing between 200 and 240 ms
+        for t in (0..=100)
+            .step_by(10)
+            .chain([115, 125])
+            .chain((135..=195).step_by(10))
+        {
+            frames.push((t * 1000, 0x100));
+        }
+        for t in (235..=495).step_by(10) {
+            frames.push((t * 1000, 0x100));
+        }
+        // Extended 0x18FEF100 every 100 ms, stopping after 200 ms
+        for t in [0, 100, 200] {
+            frames.push((t * 1000, 0x98FE_F100));
+        }
+        // Unchecked message marking the end of the capture
+        frames.push((500_000, 0x7FF));
+
+        let cycle_times = BTreeMap::from([
+            (0x100, 10_000),
+            (0x98FE_F100, 100_000),
+            (0x200, 50_000), // Never sent
+        ]);
+        let gaps = detect_gaps(frames, &cycle_times);
+
+        let summary: Vec<_> = gaps
+            .iter()
+            .map(|g| (g.message_id, g.start_us, g.missing_frames, g.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (0x200, 0, 10, GapKind::Missing),
+                (0x100, 100_000, 0, GapKind::Late),
+                (0x100, 195_000, 3, GapKind::Missing),
+                (0x98FE_F100, 200_000, 3, GapKind::Missing),
+            ]
+        );
+        assert_eq!(gaps[2].duration_us(), 40_000);
+        assert_eq!(gaps[3].end_us, 500_000);
+
+        assert!(detect_gaps(Vec::new(), &cycle_times).is_empty());
+    }
+
+    #[test]
+    fn test_cycle_times_from_dbc() {
+        let dbc = r#"VERSION ""
+
+BU_: ECM
+
+BO_ 256 Engine: 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" ECM
+
+BO_ 512 Gearbox: 8 ECM
+
+BO_ 2566844672 J1939: 8 ECM
+
+BO_ 768 Event: 8 ECM
+
+BA_DEF_ BO_ "GenMsgCycleTime" INT 0 65535;
+BA_DEF_DEF_ "GenMsgCycleTime" 100;
+BA_ "GenMsgCycleTime" BO_ 256 10;
+BA_ "GenMsgCycleTime" BO_ 2566844672 1000;
+BA_ "GenMsgCycleTime" BO_ 768 0;
+"#;
+        let cycle_times = cycle_times_from_dbc(dbc);
+        assert_eq!(
+            cycle_times,
+            BTreeMap::from([(256, 10_000), (512, 100_000), (2_566_844_672, 1_000_000)])
+        );
+    }
+}
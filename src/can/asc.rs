@@ -0,0 +1,422 @@
+//! Import of Vector ASCII (`.asc`) CAN logs.
+//!
+//! ASC files are the text export format of CANalyzer/CANoe and are written
+//! by many other tools. [`AscReader`] parses the classic CAN and CAN FD
+//! frame lines of a log; the frames can then be fed into a
+//! [`RawCanLogger`] or a `CanDbcLogger` to produce ASAM MDF bus logging
+//! files.
+//!
+//! ```text
+//! date Wed Jun 19 10:30:00.000 am 2024
+//! base hex  timestamps absolute
+//! Begin Triggerblock Wed Jun 19 10:30:00.000 am 2024
+//!    0.010000 1  123             Rx   d 8 01 02 03 04 05 06 07 08
+//!    0.020000 2  18FEF100x       Tx   d 3 AA BB CC
+//!    0.030000 CANFD   1 Rx        1A0  EngineData  1 0 d 12 01 02 ...
+//! End TriggerBlock
+//! ```
+//!
+//! Remote frames, error frames and events are skipped.
+
+use super::{FdFlags, RawCanLogger};
+use crate::{Error, Result, writer::MdfWrite};
+use std::io::{BufRead, BufReader};
+
+/// A CAN or CAN FD data frame read from an ASC log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AscFrame {
+    /// Time stamp in microseconds since the start of the measurement
+    pub timestamp_us: u64,
+    /// Bus channel (1 based)
+    pub channel: u8,
+    /// CAN identifier without the extended flag
+    pub can_id: u32,
+    /// Whether the identifier is a 29 bit extended one
+    pub extended: bool,
+    /// Whether the frame was transmitted (`Tx`) rather than received
+    pub tx: bool,
+    /// BRS/ESI flags of CAN FD frames; `None` for classic CAN frames
+    pub fd_flags: Option<FdFlags>,
+    /// Payload
+    pub data: Vec<u8>,
+}
+
+impl AscFrame {
+    /// Log this frame with a [`RawCanLogger`].
+    pub fn log_raw<W: MdfWrite>(&self, logger: &mut RawCanLogger<W>) -> bool {
+        match (self.fd_flags, self.extended) {
+            (None, false) => logger.log(self.can_id, self.timestamp_us, &self.data),
+            (None, true) => logger.log_extended(self.can_id, self.timestamp_us, &self.data),
+            (Some(flags), false) => {
+                logger.log_fd(self.can_id, self.timestamp_us, &self.data, flags)
+            }
+            (Some(flags), true) => {
+                logger.log_fd_extended(self.can_id, self.timestamp_us, &self.data, flags)
+            }
+        }
+    }
+
+    /// Log this frame with a `CanDbcLogger`; `false` if the DBC does not
+    /// define the message.
+    #[cfg(feature = "dbc")]
+    pub fn log_decoded<W: MdfWrite>(&self, logger: &mut super::CanDbcLogger<W>) -> bool {
+        match (self.fd_flags.is_some(), self.extended) {
+            (false, false) => logger.log(self.can_id, self.timestamp_us, &self.data),
+            (false, true) => logger.log_extended(self.can_id, self.timestamp_us, &self.data),
+            (true, false) => logger.log_fd(self.can_id, self.timestamp_us, &self.data),
+            (true, true) => logger.log_fd_extended(self.can_id, self.timestamp_us, &self.data),
+        }
+    }
+}
+
+/// Reader of the CAN frames of an ASC log.
+///
+/// Iterates over the data frames in the order of the file; every item is a
+/// frame or the error of a malformed frame line. The `base` and
+/// `timestamps` header lines are honored, so logs with decimal identifiers
+/// and relative time stamps are read correctly.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::can::{AscReader, RawCanLogger};
+///
+/// let mut logger = RawCanLogger::new_file("drive.mf4")?;
+/// for frame in AscReader::from_file("drive.asc")?.with_channel(1) {
+///     frame?.log_raw(&mut logger);
+/// }
+/// logger.finalize_file()?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+pub struct AscReader<R> {
+    lines: std::io::Lines<R>,
+    line_number: usize,
+    /// Radix of identifiers and data bytes
+    radix: u32,
+    /// Whether time stamps are relative to the previous line
+    relative: bool,
+    /// Time of the previous line in seconds
+    last_time: f64,
+    /// Only frames of this channel are returned
+    channel: Option<u8>,
+}
+
+impl AscReader<BufReader<std::fs::File>> {
+    /// Open an ASC file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(Error::IOError)?;
+        Ok(Self::new(BufReader::new(file)))
+    }
+}
+
+impl<R: BufRead> AscReader<R> {
+    /// Read an ASC log from a buffered reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_number: 0,
+            radix: 16,
+            relative: false,
+            last_time: 0.0,
+            channel: None,
+        }
+    }
+
+    /// Return only the frames of one bus channel (1 based).
+    pub fn with_channel(mut self, channel: u8) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::BlockSerializationError(format!("ASC line {}: {}", self.line_number, message))
+    }
+
+    /// Apply a `base ... timestamps ...` header line.
+    fn parse_header(&mut self, tokens: &[&str]) {
+        for pair in tokens.windows(2) {
+            match pair {
+                ["base", "hex"] => self.radix = 16,
+                ["base", "dec"] => self.radix = 10,
+                ["timestamps", "relative"] => self.relative = true,
+                ["timestamps", "absolute"] => self.relative = false,
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_number<T: TryFrom<u32>>(&self, token: &str) -> Result<T> {
+        u32::from_str_radix(token, self.radix)
+            .ok()
+            .and_then(|v| T::try_from(v).ok())
+            .ok_or_else(|| self.error(&format!("invalid number {:?}", token)))
+    }
+
+    /// Identifier token such as `123` or `18FEF100x`.
+    fn parse_id(&self, token: &str) -> Result<(u32, bool)> {
+        match token.strip_suffix(['x', 'X']) {
+            Some(id) => Ok((self.parse_number(id)?, true)),
+            None => Ok((self.parse_number(token)?, false)),
+        }
+    }
+
+    fn parse_data(&self, tokens: &[&str], len: usize) -> Result<Vec<u8>> {
+        let bytes = tokens
+            .get(..len)
+            .ok_or_else(|| self.error("missing data bytes"))?;
+        bytes.iter().map(|b| self.parse_number(b)).collect()
+    }
+
+    /// Frame of a classic CAN line after the time stamp:
+    /// `<channel> <id> <Rx|Tx> d <dlc> <bytes...>`.
+    fn parse_classic(&self, tokens: &[&str]) -> Result<Option<AscFrame>> {
+        let [channel, id, dir, kind, rest @ ..] = tokens else {
+            return Ok(None);
+        };
+        let (Ok(channel), true) = (channel.parse::<u8>(), matches!(*dir, "Rx" | "Tx")) else {
+            return Ok(None);
+        };
+        // Remote frames (`r`) carry no data
+        if *kind != "d" {
+            return Ok(None);
+        }
+        let (can_id, extended) = self.parse_id(id)?;
+        let dlc: u8 = match rest.first() {
+            Some(dlc) => self.parse_number(dlc)?,
+            None => return Err(self.error("missing DLC")),
+        };
+        let data = self.parse_data(&rest[1..], dlc.min(8) as usize)?;
+        Ok(Some(AscFrame {
+            timestamp_us: 0,
+            channel,
+            can_id,
+            extended,
+            tx: *dir == "Tx",
+            fd_flags: None,
+            data,
+        }))
+    }
+
+    /// Frame of a CAN FD line after `CANFD`:
+    /// `<channel> <Rx|Tx> <id> [<name>] <brs> <esi> <dlc> <length> <bytes...>`.
+    ///
+    /// `None` for error frames and other events logged as `CANFD` lines.
+    fn parse_fd(&self, tokens: &[&str]) -> Result<Option<AscFrame>> {
+        let [channel, dir, id, rest @ ..] = tokens else {
+            return Err(self.error("incomplete CAN FD frame"));
+        };
+        let channel = channel
+            .parse::<u8>()
+            .map_err(|_| self.error("invalid channel"))?;
+        let is_id = id
+            .trim_end_matches(['x', 'X'])
+            .chars()
+            .all(|c| c.is_ascii_hexdigit());
+        if !matches!(*dir, "Rx" | "Tx") || !is_id {
+            return Ok(None);
+        }
+        let (can_id, extended) = self.parse_id(id)?;
+        // The symbolic message name is optional
+        let rest = match rest.first() {
+            Some(&"0" | &"1") => rest,
+            Some(_) => &rest[1..],
+            None => rest,
+        };
+        let [brs, esi, _dlc, length, bytes @ ..] = rest else {
+            return Err(self.error("incomplete CAN FD frame"));
+        };
+        let length: usize = length
+            .parse()
+            .map_err(|_| self.error("invalid data length"))?;
+        let data = self.parse_data(bytes, length.min(super::MAX_FD_DATA_LEN))?;
+        Ok(Some(AscFrame {
+            timestamp_us: 0,
+            channel,
+            can_id,
+            extended,
+            tx: *dir == "Tx",
+            fd_flags: Some(FdFlags::new(*brs == "1", *esi == "1")),
+            data,
+        }))
+    }
+
+    /// Frame of a line; `None` for header, event and other lines.
+    fn parse_line(&mut self, line: &str) -> Result<Option<AscFrame>> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((first, rest)) = tokens.split_first() else {
+            return Ok(None);
+        };
+        let Ok(time) = first.parse::<f64>() else {
+            if *first == "base" {
+                self.parse_header(&tokens);
+            }
+            return Ok(None);
+        };
+        let time = if self.relative {
+            self.last_time + time
+        } else {
+            time
+        };
+        self.last_time = time;
+
+        let frame = match rest.first() {
+            Some(&"CANFD") => self.parse_fd(&rest[1..])?,
+            Some(_) => self.parse_classic(rest)?,
+            None => None,
+        };
+        Ok(frame.map(|frame| AscFrame {
+            timestamp_us: (time.max(0.0) * 1e6).round() as u64,
+            ..frame
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for AscReader<R> {
+    type Item = Result<AscFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(Error::IOError(e))),
+            };
+            self.line_number += 1;
+            match self.parse_line(&line) {
+                Ok(Some(frame)) if self.channel.is_none_or(|c| c == frame.channel) => {
+                    return Some(Ok(frame));
+                }
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Convert an ASC log into an MDF file of raw CAN frames.
+///
+/// All data frames of the log are written with a [`RawCanLogger`], on one
+/// bus regardless of their channel. Frames the logger rejects are not
+/// counted.
+///
+/// # Returns
+/// The number of logged frames, or an [`crate::Error`] if the log is
+/// malformed or writing fails.
+pub fn asc_to_mdf(asc_path: &str, mdf_path: &str) -> Result<usize> {
+    let mut logger = RawCanLogger::new_file(mdf_path)?;
+    let mut count = 0;
+    for frame in AscReader::from_file(asc_path)? {
+        if frame?.log_raw(&mut logger) {
+            count += 1;
+        }
+    }
+    logger.finalize_file()?;
+    Ok(count)
+}
+
+/// Convert an ASC log into an MDF file of signals decoded with a DBC.
+///
+/// Frames of messages the DBC does not define are dropped.
+///
+/// # Returns
+/// The number of decoded frames, or an [`crate::Error`] if the log is
+/// malformed or writing fails.
+#[cfg(feature = "dbc")]
+pub fn asc_to_mdf_with_dbc(asc_path: &str, mdf_path: &str, dbc: dbc_rs::Dbc) -> Result<usize> {
+    let mut logger = super::CanDbcLogger::new_file(dbc, mdf_path)?;
+    let mut count = 0;
+    for frame in AscReader::from_file(asc_path)? {
+        if frame?.log_decoded(&mut logger) {
+            count += 1;
+        }
+    }
+    logger.finalize_file()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "date Wed Jun 19 10:30:00.000 am 2024
+base hex  timestamps absolute
+internal events logged
+Begin Triggerblock Wed Jun 19 10:30:00.000 am 2024
+   0.000000 Start of measurement
+   0.010000 1  123             Rx   d 8 01 02 03 04 05 06 07 08  Length = 0 BitCount = 0
+   0.020000 2  18FEF100x       Tx   d 3 AA BB CC
+   0.025000 1  124             Rx   r
+   0.030000 1  ErrorFrame
+   0.040000 CANFD   1 Rx        1A0  EngineData  1 0 9 12 00 01 02 03 04 05 06 07 08 09 0A 0B   0 0 0 0
+   0.050000 CANFD   1 Rx        ErrorFrame                     1 0 9 12 00 00 00 00 00 00 00 00
+End TriggerBlock
+";
+
+    fn frames(log: &str) -> Vec<AscFrame> {
+        AscReader::new(log.as_bytes())
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_asc_frames() {
+        let frames = frames(LOG);
+        assert_eq!(frames.len(), 3);
+
+        assert_eq!(frames[0].timestamp_us, 10_000);
+        assert_eq!(frames[0].can_id, 0x123);
+        assert!(!frames[0].extended && !frames[0].tx);
+        assert_eq!(frames[0].data, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(frames[1].channel, 2);
+        assert_eq!(frames[1].can_id, 0x18FE_F100);
+        assert!(frames[1].extended && frames[1].tx);
+        assert_eq!(frames[1].data, [0xAA, 0xBB, 0xCC]);
+
+        assert_eq!(frames[2].timestamp_us, 40_000);
+        assert_eq!(frames[2].can_id, 0x1A0);
+        assert_eq!(frames[2].fd_flags, Some(FdFlags::new(true, false)));
+        assert_eq!(frames[2].data.len(), 12);
+
+        let channel_1 = AscReader::new(LOG.as_bytes()).with_channel(1).count();
+        assert_eq!(channel_1, 2);
+    }
+
+    #[test]
+    fn test_parse_asc_decimal_relative() {
+        let frames = frames(
+            "base dec  timestamps relative
+   0.5 1  291   Rx   d 2 1 255
+   0.25 1  291   Rx   d 1 16
+",
+        );
+        assert_eq!(frames[0].can_id, 0x123);
+        assert_eq!(frames[0].data, [1, 255]);
+        assert_eq!(frames[1].timestamp_us, 750_000);
+
+        let mut reader = AscReader::new("   0.1 1  123  Rx  d 8 01 02\n".as_bytes());
+        assert!(reader.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_asc_frames_to_raw_logger() {
+        let mut logger = RawCanLogger::new().unwrap();
+        for frame in frames(LOG) {
+            assert!(frame.log_raw(&mut logger));
+        }
+        assert_eq!(logger.standard_frame_count(), 2);
+        assert_eq!(logger.extended_frame_count(), 1);
+        assert!(logger.has_fd_frames());
+        assert!(!logger.finalize().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_asc_to_mdf_counts_logged_frames() {
+        let dir = std::env::temp_dir();
+        let asc = dir.join("mdf4_rs_asc_to_mdf.asc");
+        let mdf = dir.join("mdf4_rs_asc_to_mdf.mf4");
+        std::fs::write(&asc, LOG).unwrap();
+        let count = asc_to_mdf(asc.to_str().unwrap(), mdf.to_str().unwrap()).unwrap();
+        assert_eq!(count, 3);
+        std::fs::remove_file(asc).unwrap();
+        std::fs::remove_file(mdf).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+//! Bus statistics channel group for the CAN loggers.
+//!
+//! [`BusStatistics`] counts the frames passed to a logger in fixed windows
+//! of one second and writes one record per window to a
+//! `{bus_name}_BusStatistics` channel group:
+//! - `Timestamp`: start of the window in seconds (master channel)
+//! - `BusLoad`: share of the bit rate used by the frames, in percent
+//! - `FrameRate`: data and remote frames per second
+//! - `ErrorFrames`: number of error frames in the window
+//!
+//! The bus load is estimated from the nominal frame lengths without stuff
+//! bits, counting CAN FD frames at the nominal bit rate, so it is a lower
+//! bound for classic CAN and an upper bound for CAN FD with bit rate
+//! switching.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::writer::MdfWrite;
+use crate::{DataType, DecodedValue, MdfWriter, Result};
+
+/// Length of a statistics window in microseconds.
+const WINDOW_US: u64 = 1_000_000;
+
+/// Approximate length of an error frame on the bus: error flag, error
+/// delimiter and intermission.
+const ERROR_FRAME_BITS: u64 = 20;
+
+/// Nominal length in bits of a data or remote frame with `data_len` data
+/// bytes, without stuff bits.
+pub(super) fn frame_bits(is_extended: bool, is_fd: bool, data_len: usize) -> u64 {
+    let data_bits = data_len as u64 * 8;
+    // CRC delimiter, ACK slot and delimiter, end of frame and intermission
+    let trailer = 1 + 2 + 7 + 3;
+    if is_fd {
+        // SOF, ID, RRS, IDE, FDF, res, BRS, ESI and DLC; SRR and ID
+        // extension for extended IDs; stuff count and CRC
+        let header = if is_extended { 41 } else { 22 };
+        let crc = if data_len > 16 { 21 } else { 17 };
+        header + data_bits + 4 + crc + trailer
+    } else {
+        // SOF, ID, RTR, IDE, r0 and DLC; SRR, ID extension and r1 for
+        // extended IDs; CRC
+        let header = if is_extended { 39 } else { 19 };
+        header + data_bits + 15 + trailer
+    }
+}
+
+/// Nominal length in bits of a CAN XL frame with `data_len` data bytes,
+/// without stuff bits.
+pub(super) fn xl_frame_bits(data_len: usize) -> u64 {
+    // SOF, priority ID, RRS, IDE, FDF, XLF, resXL, ADH, DH1, DH2, DL1, SDT,
+    // SEC, DLC, SBC, preface CRC, VCID and AF
+    let header = 1 + 11 + 5 + 4 + 8 + 1 + 11 + 3 + 13 + 8 + 32;
+    // Frame CRC, FCP, DAS, ACK slot and delimiter, end of frame and
+    // intermission
+    let trailer = 32 + 4 + 3 + 2 + 7 + 3;
+    header + data_len as u64 * 8 + trailer
+}
+
+/// Counters of one statistics window.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start_us: u64,
+    bits: u64,
+    frames: u32,
+    errors: u32,
+}
+
+impl Window {
+    fn new(start_us: u64) -> Self {
+        Self {
+            start_us,
+            bits: 0,
+            frames: 0,
+            errors: 0,
+        }
+    }
+}
+
+/// Bus statistics accumulated per one-second window.
+///
+/// Windows start at the first recorded timestamp. Windows without traffic
+/// are not stored, except the one following a window with traffic, so
+/// plots drop to zero during bus silence.
+#[derive(Debug)]
+pub(super) struct BusStatistics {
+    /// Nominal bit rate in bits per second
+    bitrate: u32,
+    /// Window receiving frames
+    current: Option<Window>,
+    /// Completed windows not yet written
+    completed: Vec<Window>,
+    /// Channel group ID of the statistics
+    group: Option<String>,
+}
+
+impl BusStatistics {
+    pub(super) fn new(bitrate: u32) -> Self {
+        Self {
+            bitrate,
+            current: None,
+            completed: Vec::new(),
+            group: None,
+        }
+    }
+
+    /// Count a data or remote frame of `bits` bits.
+    pub(super) fn record_frame(&mut self, timestamp_us: u64, bits: u64) {
+        let window = self.window_at(timestamp_us);
+        window.frames += 1;
+        window.bits += bits;
+    }
+
+    /// Count an error frame.
+    pub(super) fn record_error(&mut self, timestamp_us: u64) {
+        let window = self.window_at(timestamp_us);
+        window.errors += 1;
+        window.bits += ERROR_FRAME_BITS;
+    }
+
+    /// The window containing `timestamp_us`, completing earlier windows.
+    /// Timestamps before the current window count towards it.
+    fn window_at(&mut self, timestamp_us: u64) -> &mut Window {
+        if let Some(window) = self.current {
+            let elapsed = timestamp_us.saturating_sub(window.start_us);
+            if elapsed >= WINDOW_US {
+                self.completed.push(window);
+                let windows = elapsed / WINDOW_US;
+                if windows > 1 {
+                    self.completed
+                        .push(Window::new(window.start_us + WINDOW_US));
+                }
+                self.current = Some(Window::new(window.start_us + windows * WINDOW_US));
+            }
+        }
+        self.current
+            .get_or_insert_with(|| Window::new(timestamp_us))
+    }
+
+    /// Complete the current window, e.g. before finalizing.
+    pub(super) fn close_window(&mut self) {
+        if let Some(window) = self.current.take() {
+            self.completed.push(window);
+        }
+    }
+
+    /// Create the `{bus_name}_BusStatistics` channel group, unless it
+    /// exists.
+    pub(super) fn init_group<W: MdfWrite>(
+        &mut self,
+        writer: &mut MdfWriter<W>,
+        bus_name: &str,
+    ) -> Result<()> {
+        if self.group.is_some() {
+            return Ok(());
+        }
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.set_channel_group_name(&cg, &alloc::format!("{}_BusStatistics", bus_name))?;
+        let source = crate::blocks::SourceBlock::can_bus();
+        writer.set_channel_group_source(&cg, &source, Some(bus_name))?;
+
+        let time_ch = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(String::from("Timestamp"));
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&time_ch)?;
+        writer.set_channel_unit(&time_ch, "s")?;
+
+        let load_ch = writer.add_channel(&cg, Some(&time_ch), |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(String::from("BusLoad"));
+            ch.bit_count = 64;
+        })?;
+        writer.set_channel_unit(&load_ch, "%")?;
+
+        let rate_ch = writer.add_channel(&cg, Some(&load_ch), |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(String::from("FrameRate"));
+            ch.bit_count = 64;
+        })?;
+        writer.set_channel_unit(&rate_ch, "1/s")?;
+
+        writer.add_channel(&cg, Some(&rate_ch), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from("ErrorFrames"));
+            ch.bit_count = 32;
+        })?;
+
+        self.group = Some(cg);
+        Ok(())
+    }
+
+    /// Write the completed windows as one data block. Does nothing before
+    /// [`init_group`](Self::init_group) or without completed windows.
+    pub(super) fn write<W: MdfWrite>(&mut self, writer: &mut MdfWriter<W>) -> Result<()> {
+        let Some(cg) = &self.group else {
+            return Ok(());
+        };
+        if self.completed.is_empty() {
+            return Ok(());
+        }
+
+        let window_s = WINDOW_US as f64 / 1_000_000.0;
+        let capacity = self.bitrate as f64 * window_s;
+        writer.start_data_block_for_cg(cg, 0)?;
+        for window in self.completed.drain(..) {
+            let load = if capacity > 0.0 {
+                window.bits as f64 / capacity * 100.0
+            } else {
+                0.0
+            };
+            let values = [
+                DecodedValue::Float(crate::bus_logging::timestamp_to_seconds(window.start_us)),
+                DecodedValue::Float(load),
+                DecodedValue::Float(window.frames as f64 / window_s),
+                DecodedValue::UnsignedInteger(window.errors as u64),
+            ];
+            writer.write_record(cg, &values)?;
+        }
+        writer.finish_data_block(cg)
+    }
+}
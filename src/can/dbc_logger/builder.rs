@@ -1,7 +1,21 @@
 //! Builder pattern for CanDbcLogger configuration.
 
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::can::e2e::E2eCheck;
 use crate::writer::FlushPolicy;
 
+/// Format of the time channel of the decoded channel groups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Unsigned 64-bit integer microseconds, unit "us"
+    #[default]
+    Microseconds,
+    /// 64-bit float seconds, unit "s", as used by ASAM bus logging
+    Seconds,
+}
+
 /// Configuration for CanDbcLogger.
 #[derive(Debug, Clone)]
 pub struct CanDbcLoggerConfig {
@@ -25,6 +39,83 @@ pub struct CanDbcLoggerConfig {
     /// When enabled, DBC VAL_ entries are converted to MDF4 ValueToText blocks.
     /// Default: true
     pub include_value_descriptions: bool,
+
+    /// Store DBC comments, attributes and value tables in the comment
+    /// blocks of the channel groups and channels. Comments and attributes
+    /// require [`dbc_source`](Self::dbc_source).
+    /// Default: true
+    pub include_dbc_metadata: bool,
+
+    /// Text of the DBC file the logger's DBC was parsed from.
+    /// Default: None
+    pub dbc_source: Option<String>,
+
+    /// Embed `dbc_source` in the MDF file as attachment.
+    /// Default: false
+    pub attach_dbc: bool,
+
+    /// Also store every frame in raw ASAM `CAN_DataFrame` channel groups.
+    /// Default: false
+    pub store_raw_frames: bool,
+
+    /// Names of the DBC messages to log; `None` logs all messages.
+    /// Default: None
+    pub include_messages: Option<Vec<String>>,
+
+    /// Names of the DBC signals not to log.
+    /// Default: empty
+    pub exclude_signals: Vec<String>,
+
+    /// Format of the time channels.
+    /// Default: TimestampFormat::Microseconds
+    pub timestamp_format: TimestampFormat,
+
+    /// Flush a buffer once it holds this many frames.
+    /// Default: None (no limit)
+    pub max_buffered_frames: Option<usize>,
+
+    /// Flush a buffer once its values take this many bytes.
+    /// Default: None (no limit)
+    pub max_buffered_bytes: Option<usize>,
+
+    /// Flush all buffers whenever the frame timestamps enter a new interval
+    /// of this many microseconds.
+    /// Default: None (no interval)
+    pub flush_interval_us: Option<u64>,
+
+    /// Nominal bit rate of the bus; when set, a `CAN_BusStatistics` channel
+    /// group with the bus load, frame rate and error frames of every second
+    /// is added.
+    /// Default: None (no statistics)
+    pub bus_statistics_bitrate: Option<u32>,
+
+    /// E2E checks of rolling counters and checksums, each adding a
+    /// `{Message}_E2E` channel group.
+    /// Default: empty
+    pub e2e_checks: Vec<E2eCheck>,
+}
+
+impl CanDbcLoggerConfig {
+    /// Whether a buffer with `frames` frames taking `bytes` bytes has
+    /// reached a buffer limit.
+    pub(super) fn buffer_full(&self, frames: usize, bytes: usize) -> bool {
+        self.max_buffered_frames.is_some_and(|max| frames >= max)
+            || self.max_buffered_bytes.is_some_and(|max| bytes >= max)
+    }
+
+    /// The DBC text to attach to the MDF file, if any.
+    ///
+    /// # Errors
+    /// Returns an error if `attach_dbc` is set without `dbc_source`.
+    pub(super) fn dbc_attachment(&self) -> crate::Result<Option<&str>> {
+        match (self.attach_dbc, &self.dbc_source) {
+            (false, _) => Ok(None),
+            (true, Some(source)) => Ok(Some(source)),
+            (true, None) => Err(crate::Error::BlockSerializationError(String::from(
+                "attach_dbc requires the DBC text, see dbc_source",
+            ))),
+        }
+    }
 }
 
 impl Default for CanDbcLoggerConfig {
@@ -35,6 +126,18 @@ impl Default for CanDbcLoggerConfig {
             include_limits: true,
             include_conversions: true,
             include_value_descriptions: true,
+            include_dbc_metadata: true,
+            dbc_source: None,
+            attach_dbc: false,
+            store_raw_frames: false,
+            include_messages: None,
+            exclude_signals: Vec::new(),
+            timestamp_format: TimestampFormat::Microseconds,
+            max_buffered_frames: None,
+            max_buffered_bytes: None,
+            flush_interval_us: None,
+            bus_statistics_bitrate: None,
+            e2e_checks: Vec::new(),
         }
     }
 }
@@ -108,6 +211,175 @@ impl CanDbcLoggerBuilder {
         self
     }
 
+    /// Set whether to store DBC metadata in the MDF comment blocks.
+    ///
+    /// Each message channel group gets the message comment as `CGcomment`
+    /// with the message attributes (e.g. `GenMsgCycleTime`) as common
+    /// properties; each signal channel gets the signal comment, attributes
+    /// and value table in its `CNcomment`. The file then documents its
+    /// signals without the original DBC.
+    ///
+    /// Comments and attributes are only available with
+    /// [`dbc_source`](Self::dbc_source); value tables come from the DBC.
+    ///
+    /// Default: true
+    pub fn include_dbc_metadata(mut self, enabled: bool) -> Self {
+        self.config.include_dbc_metadata = enabled;
+        self
+    }
+
+    /// Provide the text of the DBC file the logger's DBC was parsed from.
+    ///
+    /// The parsed DBC does not keep comments, attributes and extended
+    /// multiplexing, so they are read from this text: comments and
+    /// attributes for [`include_dbc_metadata`](Self::include_dbc_metadata),
+    /// and `SG_MUL_VAL_` value ranges to partition multiplexed messages into
+    /// channel groups.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let text = std::fs::read_to_string("vehicle.dbc")?;
+    /// let dbc = Dbc::parse(&text)?;
+    /// let mut logger = CanDbcLogger::builder(dbc)
+    ///     .dbc_source(&text)
+    ///     .build()?;
+    /// ```
+    pub fn dbc_source(mut self, text: &str) -> Self {
+        self.config.dbc_source = Some(String::from(text));
+        self
+    }
+
+    /// Set whether to embed the DBC text in the MDF file.
+    ///
+    /// The text given to [`dbc_source`](Self::dbc_source) is stored as an
+    /// embedded attachment named `database.dbc` with MIME type
+    /// `application/x-dbc` and its MD5 checksum, so the exact database used
+    /// for the capture travels with the data.
+    ///
+    /// Building the logger fails if no DBC text was provided.
+    ///
+    /// Default: false
+    pub fn attach_dbc(mut self, enabled: bool) -> Self {
+        self.config.attach_dbc = enabled;
+        self
+    }
+
+    /// Set whether to also store every frame in raw `CAN_DataFrame` channel
+    /// groups, alongside the decoded signal groups.
+    ///
+    /// The raw groups follow the [`RawCanLogger`](crate::can::RawCanLogger)
+    /// layout, so the recording can be decoded again with a newer DBC.
+    /// Frames unknown to the DBC are stored as well.
+    ///
+    /// Default: false
+    pub fn store_raw_frames(mut self, enabled: bool) -> Self {
+        self.config.store_raw_frames = enabled;
+        self
+    }
+
+    /// Log only the DBC messages with the given names.
+    ///
+    /// Other messages are neither buffered nor written, and `log()` returns
+    /// `false` for them. Calling this again adds to the list.
+    ///
+    /// Default: all messages
+    pub fn include_messages(mut self, names: &[&str]) -> Self {
+        self.config
+            .include_messages
+            .get_or_insert_with(Vec::new)
+            .extend(names.iter().map(|&name| String::from(name)));
+        self
+    }
+
+    /// Do not log the DBC signals with the given names, in any message.
+    ///
+    /// A multiplexor switch signal is still used to select the channel
+    /// group when excluded, but it is not written. Calling this again adds
+    /// to the list.
+    ///
+    /// Default: no signals excluded
+    pub fn exclude_signals(mut self, names: &[&str]) -> Self {
+        self.config
+            .exclude_signals
+            .extend(names.iter().map(|&name| String::from(name)));
+        self
+    }
+
+    /// Set the format of the time channels.
+    ///
+    /// [`TimestampFormat::Seconds`] stores f64 seconds with unit "s", which
+    /// some viewers expect for their time axis. Either way the time channel
+    /// is the master channel of its group.
+    ///
+    /// Default: [`TimestampFormat::Microseconds`]
+    pub fn timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.config.timestamp_format = format;
+        self
+    }
+
+    /// Flush a message buffer to the writer once it holds `frames` frames.
+    ///
+    /// Each message (or multiplexed group) is flushed on its own, so memory
+    /// stays bounded without calling `flush()`. The limit also applies to
+    /// each raw frame buffer (see [`store_raw_frames`](Self::store_raw_frames)).
+    ///
+    /// Default: no limit
+    pub fn max_buffered_frames(mut self, frames: usize) -> Self {
+        self.config.max_buffered_frames = Some(frames);
+        self
+    }
+
+    /// Flush a message buffer to the writer once its values take `bytes`
+    /// bytes of memory.
+    ///
+    /// Default: no limit
+    pub fn max_buffered_bytes(mut self, bytes: usize) -> Self {
+        self.config.max_buffered_bytes = Some(bytes);
+        self
+    }
+
+    /// Flush all buffers whenever the frame timestamps enter a new interval
+    /// of `interval_us` microseconds, so the file holds one data block per
+    /// message and interval.
+    ///
+    /// Intervals are counted from the first logged frame.
+    ///
+    /// Default: no interval
+    pub fn flush_interval_us(mut self, interval_us: u64) -> Self {
+        self.config.flush_interval_us = Some(interval_us);
+        self
+    }
+
+    /// Add a `CAN_BusStatistics` channel group with the bus load, frame rate
+    /// and error frame count of every one-second window.
+    ///
+    /// All logged frames are counted, including frames the DBC does not
+    /// define or the logger filters out. The bus load is estimated from the
+    /// nominal frame lengths at `bitrate` bits per second. Error frames are
+    /// counted with [`log_error_frame`](super::CanDbcLogger::log_error_frame).
+    ///
+    /// Default: no statistics
+    pub fn bus_statistics(mut self, bitrate: u32) -> Self {
+        self.config.bus_statistics_bitrate = Some(bitrate);
+        self
+    }
+
+    /// Check the rolling counter and checksum of a message, see
+    /// [`E2eCheck`].
+    ///
+    /// Every frame of the message is checked and the result is stored in a
+    /// `{Message}_E2E` channel group with the `Valid`, `Status` and
+    /// `ErrorCount` channels, so corrupted frames are flagged in the file.
+    /// Frames are decoded and logged whatever the result. `build()` fails
+    /// if the message or signals are not in the DBC.
+    ///
+    /// Default: no checks
+    pub fn e2e_check(mut self, check: E2eCheck) -> Self {
+        self.config.e2e_checks.push(check);
+        self
+    }
+
     /// Set the initial buffer capacity.
     pub fn with_capacity(mut self, capacity: usize) -> Self {
         self.capacity = Some(capacity);
@@ -137,6 +409,10 @@ impl CanDbcLoggerBuilder {
 
     /// Build the logger with in-memory output.
     pub fn build(self) -> crate::Result<super::CanDbcLogger<crate::writer::VecWriter>> {
+        self.config.dbc_attachment()?;
+        for check in &self.config.e2e_checks {
+            super::e2e_monitor(&self.dbc, check)?;
+        }
         let mut writer = match self.capacity {
             Some(cap) => {
                 crate::MdfWriter::from_writer(crate::writer::VecWriter::with_capacity(cap))
@@ -159,6 +435,10 @@ impl CanDbcLoggerBuilder {
         self,
         path: &str,
     ) -> crate::Result<super::CanDbcLogger<crate::writer::FileWriter>> {
+        self.config.dbc_attachment()?;
+        for check in &self.config.e2e_checks {
+            super::e2e_monitor(&self.dbc, check)?;
+        }
         let mut writer = match self.capacity {
             Some(cap) => crate::MdfWriter::new_with_capacity(path, cap)?,
             None => crate::MdfWriter::new(path)?,
@@ -3,7 +3,8 @@
 //! This module provides [`CanDbcLogger`], a high-performance logger that combines
 //! DBC signal definitions with MDF4 file writing. It supports:
 //!
-//! - Full metadata preservation (units, conversions, limits)
+//! - Full metadata preservation (units, conversions, limits, DBC comments,
+//!   attributes and value tables)
 //! - Raw value storage with conversion blocks for maximum precision
 //! - Physical value storage for compatibility
 //! - Multiplexed signal support with separate channel groups per mux value
@@ -22,6 +23,13 @@
 //! multiplexor switch signal itself. Non-multiplexed signals are included in
 //! all groups.
 //!
+//! Extended multiplexing (`SG_MUL_VAL_` with value ranges and nested
+//! multiplexors) is read from the DBC text given to
+//! [`CanDbcLoggerBuilder::dbc_source`]. The groups are then selected by the
+//! top-level multiplexor, with a group for every value of the ranges;
+//! signals of a nested multiplexor are included in the groups of that
+//! multiplexor.
+//!
 //! # Storage Modes
 //!
 //! The logger supports two storage modes:
@@ -32,17 +40,41 @@
 //! 2. **Raw Values**: Stores raw integer values with MDF4 conversion blocks.
 //!    This preserves full precision and allows MDF4 viewers to show both
 //!    raw and physical values.
+//!
+//! With [`CanDbcLoggerBuilder::store_raw_frames`], every frame is also stored
+//! in raw `CAN_DataFrame` channel groups, as written by
+//! [`RawCanLogger`](super::RawCanLogger). The file then stays decodable with
+//! future DBC versions. [`CanDbcLoggerBuilder::bus_statistics`] adds a
+//! channel group with the bus load of every second, and
+//! [`CanDbcLoggerBuilder::attach_dbc`] embeds the DBC file itself.
+//! [`CanDbcLoggerBuilder::e2e_check`] validates the rolling counter and
+//! checksum of a message and flags corrupted frames in a `{Message}_E2E`
+//! channel group, see [`e2e`](super::e2e).
+//!
+//! # Bounded Memory
+//!
+//! Frames are buffered until `flush()` or `finalize()`. For long captures,
+//! [`CanDbcLoggerBuilder::max_buffered_frames`],
+//! [`max_buffered_bytes`](CanDbcLoggerBuilder::max_buffered_bytes) and
+//! [`flush_interval_us`](CanDbcLoggerBuilder::flush_interval_us) flush
+//! buffers automatically while logging; [`CanDbcLogger::buffer_stats`]
+//! reports the buffer usage per message.
 
 mod builder;
 
-pub use builder::{CanDbcLoggerBuilder, CanDbcLoggerConfig};
+pub use builder::{CanDbcLoggerBuilder, CanDbcLoggerConfig, TimestampFormat};
 
 use alloc::collections::BTreeMap;
 use alloc::collections::BTreeSet;
 use alloc::string::String;
 use alloc::vec::Vec;
 
+use super::bus_statistics::{BusStatistics, frame_bits};
 use super::dbc_compat::SignalInfo;
+use super::dbc_text::{DbcText, SignalMux};
+use super::e2e::{E2eCheck, E2eMonitor, E2eStatus, signal_bytes};
+use super::fd::FdFlags;
+use super::raw_logger::{FrameType, RawFrame, init_dataframe_group, write_dataframes};
 
 /// Key for identifying a specific channel group buffer.
 /// For non-multiplexed messages: (can_id, None)
@@ -63,6 +95,8 @@ struct MessageBuffer {
     raw_values: Vec<Vec<i64>>,
     /// Physical values per signal (outer vec = signals, inner vec = samples)
     physical_values: Vec<Vec<f64>>,
+    /// Frames written by earlier flushes
+    flushed_frames: usize,
 }
 
 impl MessageBuffer {
@@ -74,6 +108,7 @@ impl MessageBuffer {
             timestamps: Vec::new(),
             raw_values: (0..num_signals).map(|_| Vec::new()).collect(),
             physical_values: (0..num_signals).map(|_| Vec::new()).collect(),
+            flushed_frames: 0,
         }
     }
 
@@ -108,6 +143,26 @@ impl MessageBuffer {
     fn frame_count(&self) -> usize {
         self.timestamps.len()
     }
+
+    /// Approximate memory of the buffered timestamps and values in bytes.
+    fn buffered_bytes(&self) -> usize {
+        self.timestamps.len() * (1 + self.signals.len()) * 8
+    }
+}
+
+/// Buffer usage of one decoded channel group of a [`CanDbcLogger`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferStats {
+    /// CAN ID of the message (bit 31 set for extended IDs)
+    pub can_id: u32,
+    /// Multiplexor value of the group, for multiplexed messages
+    pub mux_value: Option<u64>,
+    /// Frames buffered since the last flush
+    pub buffered_frames: usize,
+    /// Approximate memory of the buffered values in bytes
+    pub buffered_bytes: usize,
+    /// Frames written to the MDF writer by earlier flushes
+    pub flushed_frames: usize,
 }
 
 /// Channel IDs stored after MDF initialization.
@@ -179,6 +234,18 @@ pub struct CanDbcLogger<W: crate::writer::MdfWrite> {
     decode_buf: Vec<f64>,
     /// Pre-allocated decode buffer for raw values
     decode_raw_buf: Vec<i64>,
+    /// Raw frames by frame type, buffered if `store_raw_frames` is enabled
+    raw_frames: BTreeMap<FrameType, Vec<RawFrame>>,
+    /// Raw CAN_DataFrame channel groups by frame type
+    raw_groups: BTreeMap<FrameType, String>,
+    /// Start of the current flush interval in microseconds
+    interval_start_us: Option<u64>,
+    /// First error of an automatic flush, returned by the next `flush()`
+    auto_flush_error: Option<crate::Error>,
+    /// Bus statistics, if enabled
+    statistics: Option<BusStatistics>,
+    /// E2E checks keyed by can_id
+    e2e: BTreeMap<u32, E2eMonitor>,
     initialized: bool,
 }
 
@@ -371,78 +438,73 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
         let fast_dbc = dbc_rs::FastDbc::new(dbc);
         let mut buffers = BTreeMap::new();
         let mut mux_info = BTreeMap::new();
+        let is_excluded = |name: &str| config.exclude_signals.iter().any(|s| s == name);
+        // SG_MUL_VAL_ extended multiplexing is only in the DBC text
+        let dbc_text = config
+            .dbc_source
+            .as_deref()
+            .map(DbcText::parse)
+            .unwrap_or_default();
 
         for message in fast_dbc.dbc().messages().iter() {
-            let can_id = message.id();
-            let signals = message.signals();
-
-            // Build signal name to index map for this message
-            let mut signal_name_to_idx: BTreeMap<&str, usize> = BTreeMap::new();
-            for (idx, signal) in signals.iter().enumerate() {
-                signal_name_to_idx.insert(signal.name(), idx);
+            // Skip messages not selected by include_messages()
+            if config
+                .include_messages
+                .as_ref()
+                .is_some_and(|names| !names.iter().any(|n| n == message.name()))
+            {
+                continue;
             }
 
-            // Check if this message has multiplexed signals
-            let mut switch_name: Option<&str> = None;
-            let mut switch_index: Option<usize> = None;
-            let mut mux_values: BTreeSet<u64> = BTreeSet::new();
-
-            for (idx, signal) in signals.iter().enumerate() {
-                if signal.is_multiplexer_switch() {
-                    switch_name = Some(signal.name());
-                    switch_index = Some(idx);
-                }
-                if let Some(mux_val) = signal.multiplexer_switch_value() {
-                    mux_values.insert(mux_val);
-                }
-            }
+            let can_id = message.id();
+            let signals = message.signals();
 
-            if let (Some(_switch), Some(sw_idx)) = (switch_name, switch_index) {
-                if !mux_values.is_empty() {
-                    // This is a multiplexed message - create separate buffers per mux value
-                    mux_info.insert(
-                        can_id,
-                        MultiplexInfo {
-                            switch_index: sw_idx,
-                            mux_values: mux_values.clone(),
-                        },
-                    );
-
-                    for mux_val in &mux_values {
-                        // Collect signals for this mux value:
-                        // - The multiplexor switch signal
-                        // - Non-multiplexed signals
-                        // - Signals with this specific mux value
-                        let mut mux_signals: Vec<SignalInfo> = Vec::new();
-                        let mut signal_indices: Vec<usize> = Vec::new();
-
-                        for (idx, signal) in signals.iter().enumerate() {
-                            let include = signal.is_multiplexer_switch()
-                                || signal.multiplexer_switch_value() == Some(*mux_val)
-                                || (signal.multiplexer_switch_value().is_none()
-                                    && !signal.is_multiplexer_switch());
-
-                            if include {
-                                mux_signals.push(SignalInfo::from_signal(signal));
-                                signal_indices.push(idx);
-                            }
-                        }
+            // Multiplexed message - create separate buffers per mux value
+            let signal_list: Vec<&dbc_rs::Signal> = signals.iter().collect();
+            let signal_mux: Vec<SignalMux<'_>> = signal_list
+                .iter()
+                .map(|signal| SignalMux {
+                    name: signal.name(),
+                    length: signal.length(),
+                    is_switch: signal.is_multiplexer_switch(),
+                    switch_value: signal.multiplexer_switch_value(),
+                })
+                .collect();
+            if let Some(multiplexing) = dbc_text.multiplex_groups(can_id, &signal_mux) {
+                mux_info.insert(
+                    can_id,
+                    MultiplexInfo {
+                        switch_index: multiplexing.switch_index,
+                        mux_values: multiplexing.groups.keys().copied().collect(),
+                    },
+                );
 
-                        if !mux_signals.is_empty() {
-                            buffers.insert(
-                                (can_id, Some(*mux_val)),
-                                MessageBuffer::new(mux_signals, signal_indices),
-                            );
-                        }
+                // Signals of each mux value, unless excluded by
+                // exclude_signals()
+                for (mux_val, indices) in multiplexing.groups {
+                    let (mux_signals, signal_indices): (Vec<SignalInfo>, Vec<usize>) = indices
+                        .into_iter()
+                        .filter(|&idx| !is_excluded(signal_list[idx].name()))
+                        .map(|idx| (SignalInfo::from_signal(signal_list[idx]), idx))
+                        .unzip();
+
+                    if !mux_signals.is_empty() {
+                        buffers.insert(
+                            (can_id, Some(mux_val)),
+                            MessageBuffer::new(mux_signals, signal_indices),
+                        );
                     }
-                    continue;
                 }
+                continue;
             }
 
             // Non-multiplexed message - single buffer
-            let all_signals: Vec<SignalInfo> =
-                signals.iter().map(SignalInfo::from_signal).collect();
-            let signal_indices: Vec<usize> = (0..signals.len()).collect();
+            let (all_signals, signal_indices): (Vec<SignalInfo>, Vec<usize>) = signals
+                .iter()
+                .enumerate()
+                .filter(|(_, signal)| !is_excluded(signal.name()))
+                .map(|(idx, signal)| (SignalInfo::from_signal(signal), idx))
+                .unzip();
             if !all_signals.is_empty() {
                 buffers.insert(
                     (can_id, None),
@@ -456,6 +518,14 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
         let decode_buf = vec![0.0f64; max_signals];
         let decode_raw_buf = vec![0i64; max_signals];
 
+        let statistics = config.bus_statistics_bitrate.map(BusStatistics::new);
+        // Checks the DBC cannot resolve are rejected by the builder
+        let e2e = config
+            .e2e_checks
+            .iter()
+            .filter_map(|check| e2e_monitor(fast_dbc.dbc(), check).ok())
+            .collect();
+
         Self {
             fast_dbc,
             config,
@@ -466,6 +536,12 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
             mux_info,
             decode_buf,
             decode_raw_buf,
+            raw_frames: BTreeMap::new(),
+            raw_groups: BTreeMap::new(),
+            interval_start_us: None,
+            auto_flush_error: None,
+            statistics,
+            e2e,
             initialized: false,
         }
     }
@@ -486,9 +562,15 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
     /// Call `flush()` periodically or `finalize()` at the end to write data to MDF.
     ///
     /// Returns `true` if the message was recognized and logged, `false` otherwise.
+    /// With `store_raw_frames` enabled, unrecognized frames are still stored
+    /// in the raw channel groups.
+    ///
+    /// Buffers that reach a configured limit are flushed automatically; an
+    /// error of such a flush is returned by the next `flush()` or
+    /// `finalize()`.
     #[inline]
     pub fn log(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
-        self.log_internal(can_id, timestamp_us, data, false)
+        self.log_internal(can_id, timestamp_us, data, false, false)
     }
 
     /// Log a CAN frame with extended ID.
@@ -496,7 +578,7 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
     /// Use this for 29-bit extended CAN IDs.
     #[inline]
     pub fn log_extended(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
-        self.log_internal(can_id, timestamp_us, data, true)
+        self.log_internal(can_id, timestamp_us, data, true, false)
     }
 
     /// Log a CAN FD frame (up to 64 bytes).
@@ -511,13 +593,24 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
     /// * `data` - Raw frame data (up to 64 bytes for CAN FD)
     #[inline]
     pub fn log_fd(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
-        self.log_internal(can_id, timestamp_us, data, false)
+        self.log_internal(can_id, timestamp_us, data, false, true)
     }
 
     /// Log a CAN FD frame with extended ID.
     #[inline]
     pub fn log_fd_extended(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
-        self.log_internal(can_id, timestamp_us, data, true)
+        self.log_internal(can_id, timestamp_us, data, true, true)
+    }
+
+    /// Count a bus error frame in the bus statistics.
+    ///
+    /// Error frames are not stored otherwise; without
+    /// [`bus_statistics`](CanDbcLoggerBuilder::bus_statistics) this does
+    /// nothing.
+    pub fn log_error_frame(&mut self, timestamp_us: u64) {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_error(timestamp_us);
+        }
     }
 
     /// Internal logging implementation - zero allocation hot path.
@@ -528,7 +621,28 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
         timestamp_us: u64,
         data: &[u8],
         is_extended: bool,
+        is_fd: bool,
     ) -> bool {
+        if self.config.flush_interval_us.is_some() {
+            self.check_flush_interval(timestamp_us);
+        }
+
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_frame(timestamp_us, frame_bits(is_extended, is_fd, data.len()));
+        }
+
+        if self.config.store_raw_frames {
+            let frame_type = self.push_raw_frame(can_id, timestamp_us, data, is_extended, is_fd);
+            let frames = self.raw_frames.get(&frame_type).map_or(0, Vec::len);
+            if self
+                .config
+                .buffer_full(frames, frames * core::mem::size_of::<RawFrame>())
+            {
+                let result = self.flush_raw_frames(frame_type);
+                self.record_auto_flush(result);
+            }
+        }
+
         // O(1) message lookup via FastDbc
         let msg = if is_extended {
             self.fast_dbc.get_extended(can_id)
@@ -558,6 +672,27 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
             return false;
         }
 
+        if let Some(monitor) = self.e2e.get_mut(&dbc_id) {
+            // Counters and checksums are checked on the raw values
+            if !self.config.store_raw_values {
+                msg.decode_raw_into(data, &mut self.decode_raw_buf);
+            }
+            let counter = self.decode_raw_buf[monitor.counter_index] as u64;
+            let crc = monitor
+                .crc
+                .as_ref()
+                .map(|&(index, _, _)| self.decode_raw_buf[index] as u64);
+            monitor.check(timestamp_us, data, counter, crc);
+            let records = monitor.buffered();
+            if self.config.buffer_full(
+                records,
+                records * core::mem::size_of::<(u64, E2eStatus, u64)>(),
+            ) {
+                let result = self.flush_e2e(dbc_id);
+                self.record_auto_flush(result);
+            }
+        }
+
         // Determine the buffer key based on whether this is a multiplexed message
         let buffer_key = if let Some(mux) = self.mux_info.get(&dbc_id) {
             // Get the mux switch value from decoded values
@@ -594,12 +729,74 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
                     .collect();
                 buffer.push_physical(timestamp_us, &physical_values);
             }
+            if self
+                .config
+                .buffer_full(buffer.frame_count(), buffer.buffered_bytes())
+            {
+                let result = self.flush_message(buffer_key);
+                self.record_auto_flush(result);
+            }
             return true;
         }
 
         false
     }
 
+    /// Flush all buffers when `timestamp_us` enters a new flush interval.
+    fn check_flush_interval(&mut self, timestamp_us: u64) {
+        let interval = match self.config.flush_interval_us {
+            Some(interval) if interval > 0 => interval,
+            _ => return,
+        };
+        match self.interval_start_us {
+            Some(start) if timestamp_us >= start && timestamp_us - start < interval => {}
+            Some(start) if timestamp_us >= start => {
+                let result = self.flush_buffers();
+                self.record_auto_flush(result);
+                self.interval_start_us = Some(timestamp_us - (timestamp_us - start) % interval);
+            }
+            // First frame, or the timestamps went back: start over
+            _ => self.interval_start_us = Some(timestamp_us),
+        }
+    }
+
+    /// Keep the first error of an automatic flush for the next `flush()`.
+    fn record_auto_flush(&mut self, result: crate::Result<()>) {
+        if let Err(err) = result {
+            if self.auto_flush_error.is_none() {
+                self.auto_flush_error = Some(err);
+            }
+        }
+    }
+
+    /// Buffer a frame for the raw CAN_DataFrame channel groups.
+    fn push_raw_frame(
+        &mut self,
+        can_id: u32,
+        timestamp_us: u64,
+        data: &[u8],
+        is_extended: bool,
+        is_fd: bool,
+    ) -> FrameType {
+        let frame = if is_fd {
+            let dlc = super::fd::len_to_dlc(data.len());
+            RawFrame::new_fd(
+                timestamp_us,
+                can_id,
+                dlc,
+                data,
+                FdFlags::default(),
+                is_extended,
+            )
+        } else {
+            let dlc = data.len().min(8) as u8;
+            RawFrame::new_classic(timestamp_us, can_id, dlc, data, is_extended)
+        };
+        let frame_type = frame.frame_type();
+        self.raw_frames.entry(frame_type).or_default().push(frame);
+        frame_type
+    }
+
     /// Log an embedded-can frame with timestamp.
     #[cfg(feature = "can")]
     #[inline]
@@ -631,33 +828,129 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
         }
     }
 
+    /// Log a batch of embedded-can frames in timestamp order.
+    ///
+    /// The batch is sorted by timestamp first, then every frame is decoded
+    /// and buffered like [`log_frame`](Self::log_frame). Frames with equal
+    /// timestamps keep their order.
+    ///
+    /// # Returns
+    /// The number of frames recognized and logged
+    #[cfg(feature = "can")]
+    pub fn log_batch<F: embedded_can::Frame>(
+        &mut self,
+        frames: &[super::TimestampedFrame<F>],
+    ) -> usize {
+        super::timestamped_frame::in_time_order(frames)
+            .into_iter()
+            .filter(|entry| self.log_frame(entry.timestamp_us, &entry.frame))
+            .count()
+    }
+
+    /// Log a batch of CAN FD frames in timestamp order, like
+    /// [`log_batch`](Self::log_batch).
+    ///
+    /// # Returns
+    /// The number of frames recognized and logged
+    #[cfg(feature = "can")]
+    pub fn log_fd_batch<F: super::fd::FdFrame>(
+        &mut self,
+        frames: &[super::TimestampedFrame<F>],
+    ) -> usize {
+        super::timestamped_frame::in_time_order(frames)
+            .into_iter()
+            .filter(|entry| self.log_fd_frame(entry.timestamp_us, &entry.frame))
+            .count()
+    }
+
     /// Flush buffered data to the MDF writer.
     ///
     /// This writes all accumulated CAN data to the MDF file and clears the buffer.
+    /// If an automatic flush failed since the last call, its error is
+    /// returned instead.
     pub fn flush(&mut self) -> crate::Result<()> {
-        if !self.initialized {
-            self.initialize_mdf()?;
+        if let Some(err) = self.auto_flush_error.take() {
+            return Err(err);
         }
+        self.flush_buffers()
+    }
 
-        // Write data for each buffer key
+    /// Write and clear all message and raw frame buffers.
+    fn flush_buffers(&mut self) -> crate::Result<()> {
         for buffer_key in self.buffers.keys().copied().collect::<Vec<_>>() {
-            self.write_message_data(buffer_key)?;
+            self.flush_message(buffer_key)?;
+        }
+        for frame_type in FrameType::ALL {
+            self.flush_raw_frames(frame_type)?;
+        }
+        if let Some(statistics) = &mut self.statistics {
+            statistics.write(&mut self.writer)?;
         }
+        for monitor in self.e2e.values_mut() {
+            monitor.write(&mut self.writer)?;
+        }
+        Ok(())
+    }
 
-        // Clear all buffers
-        for buffer in self.buffers.values_mut() {
+    /// Write and clear the buffer of one message (or mux-specific group).
+    fn flush_message(&mut self, buffer_key: BufferKey) -> crate::Result<()> {
+        if !self.initialized {
+            self.initialize_mdf()?;
+        }
+        self.write_message_data(buffer_key)?;
+        if let Some(buffer) = self.buffers.get_mut(&buffer_key) {
+            buffer.flushed_frames += buffer.frame_count();
             buffer.clear();
         }
+        Ok(())
+    }
 
+    /// Write and clear the raw frames of one frame type.
+    fn flush_raw_frames(&mut self, frame_type: FrameType) -> crate::Result<()> {
+        if !self.initialized {
+            self.initialize_mdf()?;
+        }
+        if let (Some(cg), Some(frames)) = (
+            self.raw_groups.get(&frame_type),
+            self.raw_frames.get(&frame_type),
+        ) {
+            write_dataframes(&mut self.writer, cg, frames)?;
+        }
+        if let Some(frames) = self.raw_frames.get_mut(&frame_type) {
+            frames.clear();
+        }
         Ok(())
     }
 
+    /// Write the E2E check results of one message.
+    fn flush_e2e(&mut self, can_id: u32) -> crate::Result<()> {
+        if !self.initialized {
+            self.initialize_mdf()?;
+        }
+        match self.e2e.get_mut(&can_id) {
+            Some(monitor) => monitor.write(&mut self.writer),
+            None => Ok(()),
+        }
+    }
+
     /// Initialize the MDF file structure with full metadata.
     fn initialize_mdf(&mut self) -> crate::Result<()> {
         use crate::DataType;
+        use crate::blocks::CommonProperties;
 
         self.writer.init_mdf_file()?;
 
+        if let Some(source) = self.config.dbc_attachment()? {
+            self.writer
+                .add_attachment("database.dbc", "application/x-dbc", source.as_bytes())?;
+        }
+
+        // Comments and attributes are only in the DBC text
+        let dbc_text = match &self.config.dbc_source {
+            Some(source) if self.config.include_dbc_metadata => Some(DbcText::parse(source)),
+            _ => None,
+        };
+
         // Create a channel group for each buffer (message or mux-specific group)
         for (&buffer_key, buffer) in &self.buffers {
             let (can_id, mux_value) = buffer_key;
@@ -681,6 +974,19 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
                 if !sender.is_empty() && sender != "Vector__XXX" {
                     self.writer.set_channel_group_source_name(&cg, sender)?;
                 }
+
+                // Set channel group comment and properties from DBC message
+                // comment and attributes
+                if let Some(dbc_text) = &dbc_text {
+                    if let Some(comment) = dbc_text.message_comment(can_id) {
+                        self.writer.set_channel_group_comment(&cg, comment)?;
+                    }
+                    let mut properties = CommonProperties::new();
+                    for (name, value) in dbc_text.message_attributes(can_id) {
+                        properties.push_value(&name, &value);
+                    }
+                    self.writer.set_channel_group_properties(&cg, &properties)?;
+                }
             }
 
             // Add timestamp channel
@@ -688,13 +994,17 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
                 Some(val) => alloc::format!("Time_0x{:X}_Mux{}", can_id, val),
                 None => alloc::format!("Time_0x{:X}", can_id),
             };
+            let (time_type, time_unit) = match self.config.timestamp_format {
+                TimestampFormat::Microseconds => (DataType::UnsignedIntegerLE, "us"),
+                TimestampFormat::Seconds => (DataType::FloatLE, "s"),
+            };
             let time_ch = self.writer.add_channel(&cg, None, |ch| {
-                ch.data_type = DataType::UnsignedIntegerLE;
+                ch.data_type = time_type;
                 ch.name = Some(time_name.clone());
                 ch.bit_count = 64;
             })?;
             self.writer.set_time_channel(&time_ch)?;
-            self.writer.set_channel_unit(&time_ch, "us")?;
+            self.writer.set_channel_unit(&time_ch, time_unit)?;
 
             // Add signal channels with full metadata
             let mut prev_ch = time_ch.clone();
@@ -764,6 +1074,33 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
                     }
                 }
 
+                // Add DBC comment, attributes and value table if enabled
+                if self.config.include_dbc_metadata {
+                    let mut properties = CommonProperties::new();
+                    if let Some(dbc_text) = &dbc_text {
+                        if let Some(comment) = dbc_text.signal_comment(can_id, &info.name) {
+                            self.writer.set_channel_comment(&ch, comment)?;
+                        }
+                        for (name, value) in dbc_text.signal_attributes(can_id, &info.name) {
+                            properties.push_value(&name, &value);
+                        }
+                    }
+                    if let Some(vd) = self
+                        .fast_dbc
+                        .dbc()
+                        .value_descriptions_for_signal(can_id, &info.name)
+                    {
+                        let mut table = CommonProperties::new();
+                        for (value, desc) in vd.iter() {
+                            table.push_value(&alloc::format!("{}", value as i64), desc);
+                        }
+                        if !table.is_empty() {
+                            properties.push_tree("ValueTable", table);
+                        }
+                    }
+                    self.writer.set_channel_properties(&ch, &properties)?;
+                }
+
                 signal_channels.push(ch.clone());
                 prev_ch = ch;
             }
@@ -778,6 +1115,20 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
             );
         }
 
+        // Create a raw CAN_DataFrame channel group for each frame type that has data
+        for &frame_type in self.raw_frames.keys() {
+            let cg = init_dataframe_group(&mut self.writer, "CAN", frame_type)?;
+            self.raw_groups.insert(frame_type, cg);
+        }
+
+        if let Some(statistics) = &mut self.statistics {
+            statistics.init_group(&mut self.writer, "CAN")?;
+        }
+
+        for monitor in self.e2e.values_mut() {
+            monitor.init_group(&mut self.writer, "CAN")?;
+        }
+
         self.initialized = true;
         Ok(())
     }
@@ -796,12 +1147,20 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
             _ => return Ok(()),
         };
 
+        let timestamp_format = self.config.timestamp_format;
+        let time_value = |ts: u64| match timestamp_format {
+            TimestampFormat::Microseconds => DecodedValue::UnsignedInteger(ts),
+            TimestampFormat::Seconds => {
+                DecodedValue::Float(crate::bus_logging::timestamp_to_seconds(ts))
+            }
+        };
+
         self.writer.start_data_block_for_cg(&cg, 0)?;
 
         if self.config.store_raw_values {
             // Write raw values
             for (record_idx, &ts) in buffer.timestamps.iter().enumerate() {
-                let mut values = alloc::vec![DecodedValue::UnsignedInteger(ts)];
+                let mut values = alloc::vec![time_value(ts)];
 
                 for (sig_idx, info) in buffer.signals.iter().enumerate() {
                     if record_idx < buffer.raw_values[sig_idx].len() {
@@ -820,7 +1179,7 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
         } else {
             // Write physical values
             for (record_idx, &ts) in buffer.timestamps.iter().enumerate() {
-                let mut values = alloc::vec![DecodedValue::UnsignedInteger(ts)];
+                let mut values = alloc::vec![time_value(ts)];
 
                 for signal_values in &buffer.physical_values {
                     if record_idx < signal_values.len() {
@@ -838,11 +1197,46 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
 
     /// Flush and finalize the MDF file.
     fn flush_and_finalize(&mut self) -> crate::Result<()> {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.close_window();
+        }
         self.flush()?;
         self.writer.finalize()
     }
 
-    /// Get the number of frames logged for a specific CAN ID.
+    /// Finalize the MDF file and return the writer.
+    pub(crate) fn into_writer(mut self) -> crate::Result<crate::MdfWriter<W>> {
+        self.flush_and_finalize()?;
+        Ok(self.writer)
+    }
+
+    /// Get the buffer usage of each decoded channel group, ordered by CAN ID
+    /// and mux value.
+    pub fn buffer_stats(&self) -> Vec<BufferStats> {
+        self.buffers
+            .iter()
+            .map(|(&(can_id, mux_value), buffer)| BufferStats {
+                can_id,
+                mux_value,
+                buffered_frames: buffer.frame_count(),
+                buffered_bytes: buffer.buffered_bytes(),
+                flushed_frames: buffer.flushed_frames,
+            })
+            .collect()
+    }
+
+    /// Get the approximate memory of all buffered frames in bytes, including
+    /// raw frames.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffers
+            .values()
+            .map(MessageBuffer::buffered_bytes)
+            .sum::<usize>()
+            + self.raw_frame_count() * core::mem::size_of::<RawFrame>()
+    }
+
+    /// Get the number of frames buffered for a specific CAN ID since the
+    /// last flush.
     ///
     /// For multiplexed messages, this returns the sum of frames across all mux values.
     pub fn frame_count(&self, can_id: u32) -> usize {
@@ -853,6 +1247,21 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
             .sum()
     }
 
+    /// Get the number of frames of a message that failed its E2E check.
+    ///
+    /// Returns `None` if no [`e2e_check`](CanDbcLoggerBuilder::e2e_check)
+    /// is configured for the message.
+    pub fn e2e_error_count(&self, can_id: u32) -> Option<u64> {
+        self.e2e.get(&can_id).map(E2eMonitor::error_count)
+    }
+
+    /// Get the number of frames buffered for the raw channel groups.
+    ///
+    /// Always 0 unless `store_raw_frames` is enabled.
+    pub fn raw_frame_count(&self) -> usize {
+        self.raw_frames.values().map(|f| f.len()).sum()
+    }
+
     /// Get the number of frames logged for a specific CAN ID and mux value.
     ///
     /// For non-multiplexed messages, use `mux_value = None`.
@@ -899,6 +1308,51 @@ impl<W: crate::writer::MdfWrite> CanDbcLogger<W> {
     }
 }
 
+/// Resolve the message and signals of an E2E check in the DBC.
+///
+/// # Returns
+/// The CAN ID of the message and its monitor
+///
+/// # Errors
+/// Returns an error if the message or one of the signals is not in the DBC.
+fn e2e_monitor(dbc: &dbc_rs::Dbc, check: &E2eCheck) -> crate::Result<(u32, E2eMonitor)> {
+    let not_found = |kind: &str, name: &str| {
+        crate::Error::BlockSerializationError(alloc::format!(
+            "E2E check: {} '{}' not found in the DBC",
+            kind,
+            name
+        ))
+    };
+    let message = dbc
+        .messages()
+        .iter()
+        .find(|message| message.name() == check.message)
+        .ok_or_else(|| not_found("message", &check.message))?;
+    let signals: Vec<&dbc_rs::Signal> = message.signals().iter().collect();
+    let signal_index = |name: &str| {
+        signals
+            .iter()
+            .position(|signal| signal.name() == name)
+            .ok_or_else(|| not_found("signal", name))
+    };
+
+    let counter_index = signal_index(&check.counter_signal)?;
+    let crc = match &check.crc {
+        Some((name, _)) => {
+            let index = signal_index(name)?;
+            let signal = signals[index];
+            let little_endian = signal.byte_order() == dbc_rs::ByteOrder::LittleEndian;
+            Some((
+                index,
+                signal_bytes(signal.start_bit(), signal.length(), little_endian),
+            ))
+        }
+        None => None,
+    };
+    let monitor = E2eMonitor::new(check, counter_index, signals[counter_index].length(), crc);
+    Ok((message.id(), monitor))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1037,6 +1491,94 @@ BU_:
         assert!(logger.config().include_conversions);
     }
 
+    #[test]
+    fn test_message_and_signal_filtering() {
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM TCM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+ SG_ Temp : 16|8@1- (1,-40) [-40|215] "C" Vector__XXX
+
+ BO_ 512 Transmission : 8 TCM
+ SG_ Gear : 0|8@1+ (1,0) [0|5] "" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .include_messages(&["Engine"])
+            .exclude_signals(&["Temp"])
+            .build()
+            .unwrap();
+
+        assert_eq!(logger.channel_group_count(), 1);
+        assert_eq!(logger.total_signal_count(), 1);
+
+        assert!(logger.log(256, 1000, &[0x40, 0x1F, 0x5A, 0, 0, 0, 0, 0]));
+        assert!(!logger.log(512, 1000, &[3, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(logger.frame_count(256), 1);
+        assert_eq!(logger.frame_count(512), 0);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_filter_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups.len(), 1);
+        let names: Vec<String> = groups[0]
+            .channels()
+            .iter()
+            .map(|ch| ch.name().unwrap().unwrap_or_default())
+            .collect();
+        assert_eq!(names, ["Time_0x100", "RPM"]);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_timestamp_format_seconds() {
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .timestamp_format(TimestampFormat::Seconds)
+            .build()
+            .unwrap();
+        assert_eq!(logger.config().timestamp_format, TimestampFormat::Seconds);
+
+        assert!(logger.log(256, 1_500_000, &[0x40, 0x1F, 0, 0, 0, 0, 0, 0]));
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_seconds_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let channels = groups[0].channels();
+        let time = &channels[0];
+        assert_eq!(time.block().channel_type, 2);
+        assert_eq!(time.block().sync_type, 1);
+        assert_eq!(time.unit().unwrap().as_deref(), Some("s"));
+        assert_eq!(
+            time.values().unwrap(),
+            alloc::vec![Some(crate::DecodedValue::Float(1.5))]
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
     #[test]
     fn test_value_descriptions_to_text() {
         let dbc = dbc_rs::Dbc::parse(
@@ -1111,6 +1653,38 @@ BU_: ECM TCM
         // This is verified by the fact that it compiles and runs without errors
     }
 
+    #[test]
+    fn test_extended_multiplexing() {
+        let text = r#"VERSION "1.0"
+
+BU_: ECM
+
+BO_ 256 DiagResponse : 8 ECM
+ SG_ ServiceID M : 0|8@1+ (1,0) [0|255] "" Vector__XXX
+ SG_ SubFunction m1M : 8|8@1+ (1,0) [0|255] "" Vector__XXX
+ SG_ SessionType m2 : 16|8@1+ (1,0) [0|255] "" Vector__XXX
+ SG_ DataValue m3 : 16|16@1+ (1,0) [0|65535] "" Vector__XXX
+
+SG_MUL_VAL_ 256 SubFunction ServiceID 1-1;
+SG_MUL_VAL_ 256 SessionType SubFunction 2-2;
+SG_MUL_VAL_ 256 DataValue ServiceID 3-5;
+"#;
+        let dbc = dbc_rs::Dbc::parse(text).unwrap();
+        let mut logger = CanDbcLogger::builder(dbc).dbc_source(text).build().unwrap();
+
+        // ServiceID 1 (with the nested SubFunction multiplexor) and the
+        // DataValue range 3-5
+        let mux_vals: Vec<u64> = logger.mux_values(256).unwrap().collect();
+        assert_eq!(mux_vals, [1, 3, 4, 5]);
+        assert_eq!(logger.channel_group_count(), 4);
+
+        assert!(logger.log(256, 1000, &[1, 2, 7, 0, 0, 0, 0, 0]));
+        assert!(logger.log(256, 2000, &[4, 0, 0x34, 0x12, 0, 0, 0, 0]));
+        assert!(!logger.log(256, 3000, &[2, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(logger.frame_count_mux(256, Some(1)), 1);
+        assert_eq!(logger.frame_count_mux(256, Some(4)), 1);
+    }
+
     #[test]
     fn test_multiplexed_signals() {
         // DBC with multiplexed signals
@@ -1233,6 +1807,67 @@ BU_: ECM
         std::fs::remove_file(&temp_path).ok();
     }
 
+    #[test]
+    fn test_store_raw_frames() {
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .store_raw_frames(true)
+            .build()
+            .unwrap();
+        assert!(logger.config().store_raw_frames);
+
+        assert!(logger.log(256, 1000, &[0x40, 0x1F, 0, 0, 0, 0, 0, 0]));
+        // Unknown to the DBC, but kept in the raw groups
+        assert!(!logger.log(0x300, 2000, &[0x01, 0x02]));
+        assert!(!logger.log_fd_extended(0x18FF_0000, 3000, &[0xAA; 12]));
+
+        assert_eq!(logger.frame_count(256), 1);
+        assert_eq!(logger.raw_frame_count(), 3);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_raw_frames_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let names: Vec<String> = mdf
+            .channel_groups()
+            .iter()
+            .map(|g| g.name().unwrap().unwrap_or_default())
+            .collect();
+        assert!(names.contains(&String::from("Engine")));
+        assert!(names.contains(&String::from("CAN_DataFrame")));
+        assert!(names.contains(&String::from("CAN_DataFrame_FD_IDE_DLC_over_8")));
+
+        // The raw groups can be decoded again
+        let raw = CanDbcLogger::from_raw_mdf4(
+            temp_path.to_str().unwrap(),
+            dbc_rs::Dbc::parse(
+                r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(raw.frame_count(256), 1);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
     #[test]
     fn test_streaming_with_flush_policy() {
         use crate::FlushPolicy;
@@ -1270,6 +1905,334 @@ BU_: ECM
         assert_eq!(&mdf_bytes[0..3], b"MDF");
     }
 
+    #[test]
+    fn test_buffer_limits() {
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+
+ BO_ 512 Status : 8 ECM
+ SG_ Mode : 0|8@1+ (1,0) [0|255] "" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .max_buffered_frames(10)
+            .build()
+            .unwrap();
+
+        for i in 0..25u64 {
+            assert!(logger.log(256, i * 1000, &[i as u8, 0, 0, 0, 0, 0, 0, 0]));
+        }
+        assert!(logger.log(512, 30_000, &[1, 0, 0, 0, 0, 0, 0, 0]));
+
+        // Engine was flushed at 10 and 20 frames, Status not at all
+        let stats = logger.buffer_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].can_id, 256);
+        assert_eq!(stats[0].buffered_frames, 5);
+        assert_eq!(stats[0].flushed_frames, 20);
+        assert_eq!(stats[0].buffered_bytes, 5 * 2 * 8);
+        assert_eq!(stats[1].can_id, 512);
+        assert_eq!(stats[1].buffered_frames, 1);
+        assert_eq!(stats[1].flushed_frames, 0);
+        assert_eq!(logger.buffered_bytes(), 6 * 2 * 8);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_buffer_limits_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        // All frames are in the file, across several data blocks
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups[0].channels()[0].values().unwrap().len(), 25);
+        assert_eq!(groups[1].channels()[0].values().unwrap().len(), 1);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_flush_interval() {
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .flush_interval_us(1_000_000)
+            .store_raw_frames(true)
+            .build()
+            .unwrap();
+
+        // Frames at 0.0 s .. 2.5 s, in intervals [0, 1), [1, 2) and [2, 3)
+        for i in 0..6u64 {
+            assert!(logger.log(256, 500_000 * i, &[0x40, 0x1F, 0, 0, 0, 0, 0, 0]));
+        }
+        let stats = logger.buffer_stats();
+        assert_eq!(stats[0].flushed_frames, 4);
+        assert_eq!(stats[0].buffered_frames, 2);
+        assert_eq!(logger.raw_frame_count(), 2);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_flush_interval_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        for group in mdf.channel_groups().iter() {
+            assert_eq!(group.channels()[0].values().unwrap().len(), 6);
+        }
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_e2e_check() {
+        use crate::can::E2eProfile;
+
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Brake : 4 ECM
+ SG_ Brake_CRC : 0|8@1+ (1,0) [0|255] "" Vector__XXX
+ SG_ Brake_Counter : 8|4@1+ (1,0) [0|15] "" Vector__XXX
+ SG_ Pressure : 16|16@1+ (0.1,0) [0|6553.5] "bar" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let profile = E2eProfile::Profile1 { data_id: 0x0100 };
+        let mut logger = CanDbcLogger::builder(dbc.clone())
+            .e2e_check(E2eCheck::new("Brake", "Brake_Counter").with_crc("Brake_CRC", profile))
+            .build()
+            .unwrap();
+        let frame = |counter: u8, pressure: u8| {
+            let mut data = [0, counter, pressure, 0];
+            data[0] = profile.checksum(&data, 0..1, counter as u64) as u8;
+            data
+        };
+
+        assert!(logger.log(256, 1000, &frame(0, 10)));
+        assert!(logger.log(256, 2000, &frame(1, 11)));
+        // Lost frame, then a corrupted frame that is still decoded
+        assert!(logger.log(256, 3000, &frame(3, 12)));
+        let mut corrupted = frame(4, 13);
+        corrupted[2] ^= 0xFF;
+        assert!(logger.log(256, 4000, &corrupted));
+        assert!(logger.log(256, 5000, &frame(4, 14)));
+        assert_eq!(logger.e2e_error_count(256), Some(2));
+        assert_eq!(logger.e2e_error_count(0x200), None);
+        assert_eq!(logger.frame_count(256), 5);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_e2e_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let e2e = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("Brake_E2E"))
+            .unwrap();
+        let channels = e2e.channels();
+        let text = |names: &[&str]| -> Vec<_> {
+            names
+                .iter()
+                .map(|name| Some(crate::DecodedValue::String(String::from(*name))))
+                .collect()
+        };
+        assert_eq!(
+            channels[1].values().unwrap(),
+            text(&["Valid", "Valid", "Invalid", "Invalid", "Valid"])
+        );
+        assert_eq!(
+            channels[2].values().unwrap(),
+            text(&["Initial", "Ok", "WrongSequence", "WrongCrc", "Ok"])
+        );
+        assert_eq!(
+            channels[3].values().unwrap(),
+            [0, 0, 1, 2, 2]
+                .map(|count| Some(crate::DecodedValue::UnsignedInteger(count)))
+                .to_vec()
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+
+        // Unknown signals are rejected
+        assert!(
+            CanDbcLogger::builder(dbc)
+                .e2e_check(E2eCheck::new("Brake", "Missing"))
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bus_statistics() {
+        let dbc = dbc_rs::Dbc::parse(
+            r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+"#,
+        )
+        .unwrap();
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .bus_statistics(250_000)
+            .build()
+            .unwrap();
+        assert_eq!(logger.config().bus_statistics_bitrate, Some(250_000));
+
+        // Unknown frames count towards the bus load as well
+        for i in 0..10 {
+            assert!(logger.log(256, i * 50_000, &[0x40, 0x1F, 0, 0, 0, 0, 0, 0]));
+            assert!(!logger.log_extended(0x1234, i * 50_000 + 1000, &[0; 4]));
+        }
+        logger.log_error_frame(600_000);
+        logger.log(256, 1_200_000, &[0; 8]);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_bus_statistics_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let stats = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("CAN_BusStatistics"))
+            .unwrap();
+        let channels = stats.channels();
+        assert_eq!(
+            channels[2].values().unwrap(),
+            alloc::vec![
+                Some(crate::DecodedValue::Float(20.0)),
+                Some(crate::DecodedValue::Float(1.0)),
+            ]
+        );
+        assert_eq!(
+            channels[3].values().unwrap(),
+            alloc::vec![
+                Some(crate::DecodedValue::UnsignedInteger(1)),
+                Some(crate::DecodedValue::UnsignedInteger(0)),
+            ]
+        );
+        let bits = 10.0 * 111.0 + 10.0 * (67.0 + 32.0) + 20.0;
+        match channels[1].values().unwrap()[0] {
+            Some(crate::DecodedValue::Float(load)) => {
+                assert!((load - bits / 250_000.0 * 100.0).abs() < 1e-9)
+            }
+            ref other => panic!("unexpected bus load {:?}", other),
+        }
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_dbc_metadata() {
+        let text = r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+ SG_ Mode : 16|2@1+ (1,0) [0|3] "" Vector__XXX
+
+CM_ BO_ 256 "Engine status";
+CM_ SG_ 256 RPM "Crankshaft speed";
+BA_DEF_ BO_ "GenMsgCycleTime" INT 0 65535;
+BA_DEF_DEF_ "GenMsgCycleTime" 100;
+BA_ "GenMsgCycleTime" BO_ 256 10;
+VAL_ 256 Mode 0 "Off" 1 "On" ;
+"#;
+        let dbc = dbc_rs::Dbc::parse(text).unwrap();
+        let mut logger = CanDbcLogger::builder(dbc).dbc_source(text).build().unwrap();
+        logger.log(256, 1000, &[0x40, 0x1F, 0x01, 0, 0, 0, 0, 0]);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_metadata_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let group = &mdf.channel_groups()[0];
+        assert_eq!(
+            group.comment().unwrap().as_deref(),
+            Some(
+                "<CGcomment><TX>Engine status</TX><common_properties>\
+                 <e name=\"GenMsgCycleTime\">10</e></common_properties></CGcomment>"
+            )
+        );
+        let channels = group.channels();
+        assert_eq!(
+            channels[1].comment().unwrap().as_deref(),
+            Some("Crankshaft speed")
+        );
+        let mode = channels[2].comment().unwrap().unwrap();
+        assert!(mode.contains(
+            "<tree name=\"ValueTable\"><e name=\"0\">Off</e><e name=\"1\">On</e></tree>"
+        ));
+
+        std::fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_attach_dbc() {
+        let text = r#"VERSION "1.0"
+
+BU_: ECM
+
+ BO_ 256 Engine : 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" Vector__XXX
+"#;
+        let dbc = dbc_rs::Dbc::parse(text).unwrap();
+        assert!(
+            CanDbcLogger::builder(dbc.clone())
+                .attach_dbc(true)
+                .build()
+                .is_err()
+        );
+
+        let mut logger = CanDbcLogger::builder(dbc)
+            .dbc_source(text)
+            .attach_dbc(true)
+            .build()
+            .unwrap();
+        logger.log(256, 1000, &[0x40, 0x1F, 0, 0, 0, 0, 0, 0]);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("dbc_attach_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let path = temp_path.to_str().unwrap();
+        let index = crate::MdfIndex::from_file(path).unwrap();
+        assert_eq!(index.attachments.len(), 1);
+        let attachment = &index.attachments[0];
+        assert_eq!(attachment.file_name.as_deref(), Some("database.dbc"));
+        assert_eq!(attachment.mime_type.as_deref(), Some("application/x-dbc"));
+        assert!(attachment.md5.is_some());
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        assert_eq!(
+            index.read_attachment(0, &mut reader).unwrap(),
+            text.as_bytes()
+        );
+
+        std::fs::remove_file(temp_path).ok();
+    }
+
     #[test]
     fn test_streaming_with_bytes_policy() {
         use crate::FlushPolicy;
@@ -37,8 +37,11 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 
+use super::{CanDbcLogger, CanDbcLoggerConfig};
+use crate::bus_logging::{CaptureGroup, MergedFrames, capture_timestamp_us};
 use crate::index::{ByteRangeReader, IndexedChannelGroup, MdfIndex};
-use crate::{DecodedValue, Error, Result};
+use crate::writer::MdfWrite;
+use crate::{DataType, DecodedValue, Error, MdfWriter, Result};
 
 /// A decoded CAN frame with all signal values.
 #[derive(Debug, Clone)]
@@ -199,78 +202,42 @@ impl<'dbc> DbcOverlayReader<'dbc> {
     /// Read all raw frames from the MDF file.
     ///
     /// Returns a vector of (timestamp_us, can_id, is_extended, data) tuples.
+    /// For large captures prefer [`raw_frames_iter()`](Self::raw_frames_iter),
+    /// which does not hold all frames in memory.
     #[allow(clippy::type_complexity)]
     pub fn read_raw_frames<R: ByteRangeReader<Error = Error>>(
         &self,
         reader: &mut R,
     ) -> Result<Vec<(u64, u32, bool, Vec<u8>)>> {
-        let mut frames = Vec::new();
-
-        for asam_group in &self.asam_groups {
-            // Read timestamp and dataframe channels
-            let timestamps = self.index.read_channel_values(
-                asam_group.group_index,
-                asam_group.timestamp_channel,
-                reader,
-            )?;
-            let dataframes = self.index.read_channel_values(
-                asam_group.group_index,
-                asam_group.dataframe_channel,
-                reader,
-            )?;
-
-            // Combine into frames
-            for (ts_val, df_val) in timestamps.iter().zip(dataframes.iter()) {
-                // Parse timestamp (Float64 seconds -> u64 microseconds)
-                let timestamp_us = match ts_val {
-                    Some(DecodedValue::Float(secs)) => (*secs * 1_000_000.0) as u64,
-                    Some(DecodedValue::UnsignedInteger(us)) => *us,
-                    Some(DecodedValue::SignedInteger(us)) => *us as u64,
-                    _ => continue,
-                };
-
-                // Parse CAN_DataFrame ByteArray
-                let bytes = match df_val {
-                    Some(DecodedValue::ByteArray(b)) => b,
-                    _ => continue,
-                };
-
-                if bytes.len() < 5 {
-                    continue;
-                }
-
-                // Parse: ID(4 bytes LE) + DLC(1 byte) + Data(N bytes)
-                let raw_id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-                let is_extended = (raw_id & 0x8000_0000) != 0;
-                let can_id = raw_id & 0x1FFF_FFFF;
-                let dlc = bytes[4];
-                let data_len = super::fd::dlc_to_len(dlc).min(bytes.len() - 5);
-                let data = bytes[5..5 + data_len].to_vec();
-
-                frames.push((timestamp_us, can_id, is_extended, data));
-            }
-        }
+        let mut frames = self.raw_frames_iter(reader).collect::<Result<Vec<_>>>()?;
 
-        // Sort by timestamp
+        // Sort by timestamp, in case a group is not in time order
         frames.sort_by_key(|(ts, _, _, _)| *ts);
 
         Ok(frames)
     }
 
-    /// Read and decode all frames for a specific DBC message.
+    /// Iterate over all raw frames of the MDF file in timestamp order.
     ///
-    /// # Arguments
-    /// * `message_name` - The message name as defined in the DBC file
-    /// * `reader` - A byte range reader for the MDF file
+    /// Only one data block per channel group is held in memory at a time.
+    /// Each channel group is expected to be in time order, as written by
+    /// the loggers.
     ///
-    /// # Returns
-    /// A vector of decoded frames with all signal values.
-    pub fn frames<R: ByteRangeReader<Error = Error>>(
-        &self,
-        message_name: &str,
-        reader: &mut R,
-    ) -> Result<Vec<DecodedFrame>> {
-        // Find the message in the DBC by name
+    /// Yields (timestamp_us, can_id, is_extended, data) tuples like
+    /// [`read_raw_frames()`](Self::read_raw_frames).
+    pub fn raw_frames_iter<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        reader: &'a mut R,
+    ) -> RawFrameIter<'a, R> {
+        RawFrameIter {
+            frames: MergedFrames::new(&self.index, reader, &self.asam_groups, |_, bytes| {
+                parse_dataframe(bytes)
+            }),
+        }
+    }
+
+    /// Find a DBC message by name, returning its CAN ID and extended flag.
+    fn message_id(&self, message_name: &str) -> Result<(u32, bool)> {
         let message = self
             .dbc
             .messages()
@@ -284,35 +251,60 @@ impl<'dbc> DbcOverlayReader<'dbc> {
             })?;
 
         let msg_id = message.id();
-        let msg_is_extended = (msg_id & 0x8000_0000) != 0;
-        let msg_can_id = msg_id & 0x1FFF_FFFF;
+        Ok((msg_id & 0x1FFF_FFFF, (msg_id & 0x8000_0000) != 0))
+    }
 
-        let raw_frames = self.read_raw_frames(reader)?;
-        let mut decoded_frames = Vec::new();
+    /// Find the DBC message containing a signal, returning its CAN ID and
+    /// extended flag.
+    fn signal_message_id(&self, signal_name: &str) -> Result<(u32, bool)> {
+        let message = self
+            .dbc
+            .messages()
+            .iter()
+            .find(|msg| msg.signals().iter().any(|s| s.name() == signal_name))
+            .ok_or_else(|| {
+                Error::BlockSerializationError(alloc::format!(
+                    "Signal '{}' not found in DBC",
+                    signal_name
+                ))
+            })?;
 
-        for (timestamp, can_id, is_extended, data) in raw_frames {
-            // Match CAN ID and extended flag
-            if can_id != msg_can_id || is_extended != msg_is_extended {
-                continue;
-            }
+        let msg_id = message.id();
+        Ok((msg_id & 0x1FFF_FFFF, (msg_id & 0x8000_0000) != 0))
+    }
 
-            // Decode using DBC
-            if let Ok(decoded_signals) = self.dbc.decode(can_id, &data, is_extended) {
-                let signals: Vec<(String, f64)> = decoded_signals
-                    .iter()
-                    .map(|s| (String::from(s.name), s.value))
-                    .collect();
-
-                decoded_frames.push(DecodedFrame {
-                    timestamp_us: timestamp,
-                    can_id,
-                    is_extended,
-                    signals,
-                });
-            }
-        }
+    /// Read and decode all frames for a specific DBC message.
+    ///
+    /// # Arguments
+    /// * `message_name` - The message name as defined in the DBC file
+    /// * `reader` - A byte range reader for the MDF file
+    ///
+    /// # Returns
+    /// A vector of decoded frames with all signal values.
+    pub fn frames<R: ByteRangeReader<Error = Error>>(
+        &self,
+        message_name: &str,
+        reader: &mut R,
+    ) -> Result<Vec<DecodedFrame>> {
+        self.frames_iter(message_name, reader)?.collect()
+    }
 
-        Ok(decoded_frames)
+    /// Iterate over the decoded frames of a specific DBC message.
+    ///
+    /// Streaming counterpart of [`frames()`](Self::frames), built on
+    /// [`raw_frames_iter()`](Self::raw_frames_iter).
+    pub fn frames_iter<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        message_name: &str,
+        reader: &'a mut R,
+    ) -> Result<FrameIter<'a, 'dbc, R>> {
+        let (can_id, is_extended) = self.message_id(message_name)?;
+        Ok(FrameIter {
+            raw: self.raw_frames_iter(reader),
+            dbc: self.dbc,
+            can_id,
+            is_extended,
+        })
     }
 
     /// Read all values for a specific signal across the entire capture.
@@ -328,58 +320,212 @@ impl<'dbc> DbcOverlayReader<'dbc> {
         signal_name: &str,
         reader: &mut R,
     ) -> Result<Vec<SignalValue>> {
-        // Find which message contains this signal
-        let (message, _signal) = self
-            .dbc
-            .messages()
-            .iter()
-            .find_map(|msg| {
-                msg.signals()
+        self.signal_values_iter(signal_name, reader)?.collect()
+    }
+
+    /// Iterate over the values of a specific signal across the capture.
+    ///
+    /// Streaming counterpart of [`signal_values()`](Self::signal_values),
+    /// built on [`raw_frames_iter()`](Self::raw_frames_iter).
+    pub fn signal_values_iter<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        signal_name: &str,
+        reader: &'a mut R,
+    ) -> Result<SignalValueIter<'a, 'dbc, R>> {
+        let (can_id, is_extended) = self.signal_message_id(signal_name)?;
+        Ok(SignalValueIter {
+            frames: FrameIter {
+                raw: self.raw_frames_iter(reader),
+                dbc: self.dbc,
+                can_id,
+                is_extended,
+            },
+            signal_name: String::from(signal_name),
+        })
+    }
+
+    /// Read the raw frames with a timestamp in `[t0_us, t1_us]`.
+    ///
+    /// The time window is resolved per channel group with
+    /// [`MdfIndex::read_channels_in_time_range()`], which binary searches the
+    /// master channel, so only the records in the window are read instead
+    /// of the whole capture. Groups whose Timestamp channel is not the master
+    /// channel are read completely and filtered.
+    ///
+    /// Returns (timestamp_us, can_id, is_extended, data) tuples sorted by
+    /// timestamp, like [`read_raw_frames()`](Self::read_raw_frames).
+    #[allow(clippy::type_complexity)]
+    pub fn read_raw_frames_in_range<R: ByteRangeReader<Error = Error>>(
+        &self,
+        t0_us: u64,
+        t1_us: u64,
+        reader: &mut R,
+    ) -> Result<Vec<(u64, u32, bool, Vec<u8>)>> {
+        let mut frames = Vec::new();
+        if t0_us > t1_us {
+            return Ok(frames);
+        }
+
+        for asam_group in &self.asam_groups {
+            let group = &self.index.channel_groups[asam_group.group_index];
+            let timestamp = &group.channels[asam_group.timestamp_channel];
+            let channels = [asam_group.timestamp_channel, asam_group.dataframe_channel];
+
+            let columns = if timestamp.channel_type == 2 {
+                // Widen the window by a microsecond so that rounding of
+                // float seconds cannot drop frames at its edges
+                let (t0, t1) =
+                    if matches!(timestamp.data_type, DataType::FloatLE | DataType::FloatBE) {
+                        (
+                            t0_us.saturating_sub(1) as f64 / 1_000_000.0,
+                            t1_us.saturating_add(1) as f64 / 1_000_000.0,
+                        )
+                    } else {
+                        (t0_us as f64, t1_us as f64)
+                    };
+                self.index.read_channels_in_time_range(
+                    asam_group.group_index,
+                    &channels,
+                    t0,
+                    t1,
+                    reader,
+                )?
+            } else {
+                self.index
+                    .read_channels(asam_group.group_index, &channels, reader)?
+            };
+
+            frames.extend(
+                columns[0]
                     .iter()
-                    .find(|s| s.name() == signal_name)
-                    .map(|sig| (msg, sig))
-            })
-            .ok_or_else(|| {
-                Error::BlockSerializationError(alloc::format!(
-                    "Signal '{}' not found in DBC",
-                    signal_name
-                ))
-            })?;
+                    .zip(&columns[1])
+                    .filter_map(|(ts_val, df_val)| parse_raw_frame(ts_val, df_val))
+                    .filter(|(ts, _, _, _)| (t0_us..=t1_us).contains(ts)),
+            );
+        }
 
-        let msg_id = message.id();
-        let msg_is_extended = (msg_id & 0x8000_0000) != 0;
-        let msg_can_id = msg_id & 0x1FFF_FFFF;
+        // Sort by timestamp
+        frames.sort_by_key(|(ts, _, _, _)| *ts);
 
-        let raw_frames = self.read_raw_frames(reader)?;
-        let mut values = Vec::new();
+        Ok(frames)
+    }
 
-        for (timestamp, can_id, is_extended, data) in raw_frames {
-            // Match CAN ID and extended flag
-            if can_id != msg_can_id || is_extended != msg_is_extended {
-                continue;
-            }
+    /// Read and decode the frames of a DBC message with a timestamp in
+    /// `[t0_us, t1_us]`.
+    ///
+    /// Like [`frames()`](Self::frames), but reads only the records in the
+    /// time window (see [`read_raw_frames_in_range()`](Self::read_raw_frames_in_range)).
+    pub fn frames_in_range<R: ByteRangeReader<Error = Error>>(
+        &self,
+        message_name: &str,
+        t0_us: u64,
+        t1_us: u64,
+        reader: &mut R,
+    ) -> Result<Vec<DecodedFrame>> {
+        let (msg_can_id, msg_is_extended) = self.message_id(message_name)?;
 
-            // Decode using DBC
-            if let Ok(decoded_signals) = self.dbc.decode(can_id, &data, is_extended) {
-                if let Some(sig) = decoded_signals.iter().find(|s| s.name == signal_name) {
-                    values.push(SignalValue {
-                        timestamp_us: timestamp,
-                        value: sig.value,
-                        raw_value: sig.raw_value,
-                    });
-                }
-            }
+        let raw_frames = self.read_raw_frames_in_range(t0_us, t1_us, reader)?;
+        Ok(raw_frames
+            .iter()
+            .filter(|(_, can_id, is_extended, _)| {
+                *can_id == msg_can_id && *is_extended == msg_is_extended
+            })
+            .filter_map(|(ts, can_id, is_extended, data)| {
+                decode_frame(self.dbc, *ts, *can_id, *is_extended, data)
+            })
+            .collect())
+    }
+
+    /// Read the values of a DBC signal with a timestamp in `[t0_us, t1_us]`.
+    ///
+    /// Like [`signal_values()`](Self::signal_values), but reads only the
+    /// records in the time window (see
+    /// [`read_raw_frames_in_range()`](Self::read_raw_frames_in_range)).
+    pub fn signal_values_in_range<R: ByteRangeReader<Error = Error>>(
+        &self,
+        signal_name: &str,
+        t0_us: u64,
+        t1_us: u64,
+        reader: &mut R,
+    ) -> Result<Vec<SignalValue>> {
+        let (msg_can_id, msg_is_extended) = self.signal_message_id(signal_name)?;
+
+        let raw_frames = self.read_raw_frames_in_range(t0_us, t1_us, reader)?;
+        Ok(raw_frames
+            .iter()
+            .filter(|(_, can_id, is_extended, _)| {
+                *can_id == msg_can_id && *is_extended == msg_is_extended
+            })
+            .filter_map(|frame| decode_signal(self.dbc, frame, signal_name))
+            .collect())
+    }
+
+    /// Decode the whole capture into a new MDF file with one channel group
+    /// per DBC message.
+    ///
+    /// Every raw frame is logged through a [`CanDbcLogger`](super::CanDbcLogger)
+    /// built with `config`, so the output has the same layout as a capture
+    /// logged with the DBC in the first place, with units, conversions,
+    /// limits and value descriptions as configured. Frames of messages the
+    /// DBC does not define are dropped unless
+    /// [`store_raw_frames`](CanDbcLoggerConfig::store_raw_frames) is set.
+    ///
+    /// The frames are streamed with [`raw_frames_iter()`](Self::raw_frames_iter);
+    /// set a buffer limit such as
+    /// [`max_buffered_frames`](CanDbcLoggerConfig::max_buffered_frames) to
+    /// bound memory for large captures.
+    ///
+    /// # Arguments
+    /// * `writer` - A new writer, not yet initialized
+    /// * `config` - The decoded output configuration
+    /// * `reader` - A byte range reader for the raw MDF file
+    ///
+    /// # Returns
+    /// The finalized writer, e.g. to take the bytes of an in-memory writer.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use mdf4_rs::can::{CanDbcLoggerConfig, DbcOverlayReader};
+    /// use mdf4_rs::{FileRangeReader, MdfWriter};
+    ///
+    /// let overlay = DbcOverlayReader::from_file("raw_capture.mf4", &dbc)?;
+    /// let mut reader = FileRangeReader::new("raw_capture.mf4")?;
+    /// let config = CanDbcLoggerConfig {
+    ///     max_buffered_frames: Some(10_000),
+    ///     ..Default::default()
+    /// };
+    /// overlay.transcode_to(MdfWriter::new("decoded.mf4")?, config, &mut reader)?;
+    /// ```
+    pub fn transcode_to<W: MdfWrite, R: ByteRangeReader<Error = Error>>(
+        &self,
+        writer: MdfWriter<W>,
+        config: CanDbcLoggerConfig,
+        reader: &mut R,
+    ) -> Result<MdfWriter<W>> {
+        let mut logger = CanDbcLogger::with_config(self.dbc.clone(), writer, config);
+
+        for frame in self.raw_frames_iter(reader) {
+            let (timestamp_us, can_id, is_extended, data) = frame?;
+            match (is_extended, data.len() > 8) {
+                (false, false) => logger.log(can_id, timestamp_us, &data),
+                (true, false) => logger.log_extended(can_id, timestamp_us, &data),
+                (false, true) => logger.log_fd(can_id, timestamp_us, &data),
+                (true, true) => logger.log_fd_extended(can_id, timestamp_us, &data),
+            };
         }
 
-        Ok(values)
+        logger.into_writer()
     }
 
     /// Get all unique CAN IDs found in the raw capture.
     pub fn can_ids<R: ByteRangeReader<Error = Error>>(&self, reader: &mut R) -> Result<Vec<u32>> {
         use alloc::collections::BTreeSet;
 
-        let frames = self.read_raw_frames(reader)?;
-        let ids: BTreeSet<u32> = frames.iter().map(|(_, id, _, _)| *id).collect();
+        let mut ids = BTreeSet::new();
+        for frame in self.raw_frames_iter(reader) {
+            ids.insert(frame?.1);
+        }
         Ok(ids.into_iter().collect())
     }
 
@@ -388,19 +534,20 @@ impl<'dbc> DbcOverlayReader<'dbc> {
         &self,
         reader: &mut R,
     ) -> Result<OverlayStatistics> {
-        let frames = self.read_raw_frames(reader)?;
-
-        let total_frames = frames.len();
-        let unique_ids: alloc::collections::BTreeSet<u32> =
-            frames.iter().map(|(_, id, _, _)| *id).collect();
-
-        let (min_timestamp, max_timestamp) = if frames.is_empty() {
-            (0, 0)
-        } else {
-            let min = frames.iter().map(|(ts, _, _, _)| *ts).min().unwrap_or(0);
-            let max = frames.iter().map(|(ts, _, _, _)| *ts).max().unwrap_or(0);
-            (min, max)
-        };
+        let mut total_frames = 0;
+        let mut unique_ids = alloc::collections::BTreeSet::new();
+        let mut min_timestamp = u64::MAX;
+        let mut max_timestamp = 0;
+        for frame in self.raw_frames_iter(reader) {
+            let (ts, id, _, _) = frame?;
+            total_frames += 1;
+            unique_ids.insert(id);
+            min_timestamp = min_timestamp.min(ts);
+            max_timestamp = max_timestamp.max(ts);
+        }
+        if total_frames == 0 {
+            min_timestamp = 0;
+        }
 
         // Count how many messages from DBC are present
         let mut dbc_messages_found = 0;
@@ -482,6 +629,164 @@ pub struct OverlayStatistics {
     pub duration_us: u64,
 }
 
+/// Parse a Timestamp and CAN_DataFrame value pair into a raw frame.
+///
+/// The frame is ID(4 bytes LE, bit 31 = extended) + DLC(1 byte) + Data.
+fn parse_raw_frame(
+    ts_val: &Option<DecodedValue>,
+    df_val: &Option<DecodedValue>,
+) -> Option<(u64, u32, bool, Vec<u8>)> {
+    let timestamp_us = capture_timestamp_us(ts_val)?;
+    let Some(DecodedValue::ByteArray(bytes)) = df_val else {
+        return None;
+    };
+    let (can_id, is_extended, data) = parse_dataframe(bytes)?;
+    Some((timestamp_us, can_id, is_extended, data))
+}
+
+/// Parse CAN_DataFrame bytes into (can_id, is_extended, data).
+fn parse_dataframe(bytes: &[u8]) -> Option<(u32, bool, Vec<u8>)> {
+    if bytes.len() < 5 {
+        return None;
+    }
+
+    let raw_id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let is_extended = (raw_id & 0x8000_0000) != 0;
+    let can_id = raw_id & 0x1FFF_FFFF;
+    let dlc = bytes[4];
+    let data_len = super::fd::dlc_to_len(dlc).min(bytes.len() - 5);
+    let data = bytes[5..5 + data_len].to_vec();
+
+    Some((can_id, is_extended, data))
+}
+
+/// Decode a raw frame with the DBC, or `None` if the DBC cannot decode it.
+fn decode_frame(
+    dbc: &dbc_rs::Dbc,
+    timestamp_us: u64,
+    can_id: u32,
+    is_extended: bool,
+    data: &[u8],
+) -> Option<DecodedFrame> {
+    let decoded_signals = dbc.decode(can_id, data, is_extended).ok()?;
+    let signals: Vec<(String, f64)> = decoded_signals
+        .iter()
+        .map(|s| (String::from(s.name), s.value))
+        .collect();
+
+    Some(DecodedFrame {
+        timestamp_us,
+        can_id,
+        is_extended,
+        signals,
+    })
+}
+
+/// Decode one signal of a raw frame with the DBC.
+fn decode_signal(
+    dbc: &dbc_rs::Dbc,
+    (timestamp_us, can_id, is_extended, data): &(u64, u32, bool, Vec<u8>),
+    signal_name: &str,
+) -> Option<SignalValue> {
+    let decoded_signals = dbc.decode(*can_id, data, *is_extended).ok()?;
+    let sig = decoded_signals.iter().find(|s| s.name == signal_name)?;
+    Some(SignalValue {
+        timestamp_us: *timestamp_us,
+        value: sig.value,
+        raw_value: sig.raw_value,
+    })
+}
+
+impl CaptureGroup for AsamCanGroup {
+    fn group_index(&self) -> usize {
+        self.group_index
+    }
+
+    fn channels(&self) -> [usize; 2] {
+        [self.timestamp_channel, self.dataframe_channel]
+    }
+}
+
+/// Streaming iterator over the raw frames of a capture, in timestamp order.
+///
+/// Created by [`DbcOverlayReader::raw_frames_iter()`]. Yields
+/// (timestamp_us, can_id, is_extended, data) tuples. After an error the
+/// iterator is exhausted.
+pub struct RawFrameIter<'a, R> {
+    frames: MergedFrames<'a, R, &'a AsamCanGroup, (u32, bool, Vec<u8>)>,
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for RawFrameIter<'_, R> {
+    type Item = Result<(u64, u32, bool, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.frames.next()?;
+        Some(frame.map(|(ts, (can_id, is_extended, data))| (ts, can_id, is_extended, data)))
+    }
+}
+
+/// Streaming iterator over the decoded frames of one DBC message.
+///
+/// Created by [`DbcOverlayReader::frames_iter()`]. Frames the DBC fails to
+/// decode are skipped.
+pub struct FrameIter<'a, 'dbc, R> {
+    raw: RawFrameIter<'a, R>,
+    dbc: &'dbc dbc_rs::Dbc,
+    can_id: u32,
+    is_extended: bool,
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for FrameIter<'_, '_, R> {
+    type Item = Result<DecodedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (timestamp, can_id, is_extended, data) = match self.raw.next()? {
+                Ok(frame) => frame,
+                Err(e) => return Some(Err(e)),
+            };
+
+            // Match CAN ID and extended flag
+            if can_id != self.can_id || is_extended != self.is_extended {
+                continue;
+            }
+
+            if let Some(frame) = decode_frame(self.dbc, timestamp, can_id, is_extended, &data) {
+                return Some(Ok(frame));
+            }
+        }
+    }
+}
+
+/// Streaming iterator over the values of one DBC signal.
+///
+/// Created by [`DbcOverlayReader::signal_values_iter()`].
+pub struct SignalValueIter<'a, 'dbc, R> {
+    frames: FrameIter<'a, 'dbc, R>,
+    signal_name: String,
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for SignalValueIter<'_, '_, R> {
+    type Item = Result<SignalValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let frame = match self.frames.raw.next()? {
+                Ok(frame) => frame,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if frame.1 != self.frames.can_id || frame.2 != self.frames.is_extended {
+                continue;
+            }
+
+            if let Some(value) = decode_signal(self.frames.dbc, &frame, &self.signal_name) {
+                return Some(Ok(value));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -573,6 +878,201 @@ BU_: ECM
         std::fs::remove_file(&temp_path).ok();
     }
 
+    #[test]
+    fn test_overlay_streaming_iterators() {
+        use crate::can::RawCanLogger;
+
+        // Standard and extended frames go to separate groups; flushing
+        // between batches gives each group several data blocks
+        let mut logger = RawCanLogger::new().unwrap();
+        for batch in 0..3u64 {
+            for i in 0..10u64 {
+                let ts = batch * 10_000 + i * 1000;
+                let rpm = ((batch * 10 + i) * 4) as u16;
+                let [lo, hi] = rpm.to_le_bytes();
+                logger.log(256, ts, &[lo, hi, 0x5A, 0, 0, 0, 0, 0]);
+                logger.log_extended(0x0CF0_0401, ts + 500, &[0; 8]);
+            }
+            logger.flush().unwrap();
+        }
+        let mdf_bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("overlay_streaming.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let dbc = create_test_dbc();
+        let overlay = DbcOverlayReader::from_file(temp_path.to_str().unwrap(), &dbc).unwrap();
+        assert_eq!(overlay.raw_group_count(), 2);
+        let mut reader = crate::FileRangeReader::new(temp_path.to_str().unwrap()).unwrap();
+
+        let streamed: Vec<_> = overlay
+            .raw_frames_iter(&mut reader)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(streamed.len(), 60);
+        assert!(streamed.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert_eq!(streamed, overlay.read_raw_frames(&mut reader).unwrap());
+
+        let engine: Vec<_> = overlay
+            .frames_iter("Engine", &mut reader)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(engine.len(), 30);
+        let engine_timestamps: Vec<_> = streamed
+            .iter()
+            .filter(|(_, id, _, _)| *id == 256)
+            .map(|(ts, _, _, _)| *ts)
+            .collect();
+        assert!(
+            engine
+                .iter()
+                .map(|f| f.timestamp_us)
+                .eq(engine_timestamps.iter().copied())
+        );
+
+        let rpm: Vec<_> = overlay
+            .signal_values_iter("RPM", &mut reader)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(rpm.len(), 30);
+        for (i, value) in rpm.iter().enumerate() {
+            assert!((value.value - i as f64).abs() < 0.01);
+        }
+
+        let stats = overlay.statistics(&mut reader).unwrap();
+        assert_eq!(stats.total_frames, 60);
+        assert_eq!(stats.min_timestamp_us, 0);
+        assert_eq!(stats.max_timestamp_us, streamed[59].0);
+
+        assert!(overlay.frames_iter("NonExistent", &mut reader).is_err());
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_overlay_time_range() {
+        use crate::can::RawCanLogger;
+
+        let mut logger = RawCanLogger::new().unwrap();
+        for i in 0..100u64 {
+            let [lo, hi] = ((i * 4) as u16).to_le_bytes();
+            logger.log(256, i * 1000, &[lo, hi, 0x5A, 0, 0, 0, 0, 0]);
+            logger.log(512, i * 1000 + 500, &[0x03, 0x88, 0x13, 0, 0, 0, 0, 0]);
+            if i % 25 == 24 {
+                logger.flush().unwrap();
+            }
+        }
+        let mdf_bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("overlay_time_range.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let dbc = create_test_dbc();
+        let overlay = DbcOverlayReader::from_file(temp_path.to_str().unwrap(), &dbc).unwrap();
+        let mut reader = crate::FileRangeReader::new(temp_path.to_str().unwrap()).unwrap();
+
+        let all = overlay.read_raw_frames(&mut reader).unwrap();
+        let (t0, t1) = (all[40].0, all[90].0);
+        let window = overlay
+            .read_raw_frames_in_range(t0, t1, &mut reader)
+            .unwrap();
+        assert_eq!(window, all[40..=90]);
+
+        let engine = overlay
+            .frames_in_range("Engine", t0, t1, &mut reader)
+            .unwrap();
+        let expected: Vec<u64> = overlay
+            .frames("Engine", &mut reader)
+            .unwrap()
+            .iter()
+            .map(|f| f.timestamp_us)
+            .filter(|ts| (t0..=t1).contains(ts))
+            .collect();
+        assert_eq!(engine.len(), 26);
+        assert!(engine.iter().map(|f| f.timestamp_us).eq(expected));
+
+        let rpm = overlay
+            .signal_values_in_range("RPM", t0, t1, &mut reader)
+            .unwrap();
+        assert_eq!(rpm.len(), 26);
+        assert!((rpm[0].value - 20.0).abs() < 0.01);
+
+        // Empty and inverted windows
+        let after = all[199].0 + 1;
+        assert!(
+            overlay
+                .frames_in_range("Engine", after, after + 1000, &mut reader)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            overlay
+                .read_raw_frames_in_range(t1, t0, &mut reader)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            overlay
+                .frames_in_range("NonExistent", t0, t1, &mut reader)
+                .is_err()
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_overlay_transcode() {
+        use crate::can::RawCanLogger;
+
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log(256, 1000, &[0x40, 0x1F, 0x5A, 0, 0, 0, 0, 0]); // RPM=2000, Temp=50
+        logger.log(512, 1500, &[0x03, 0x88, 0x13, 0, 0, 0, 0, 0]); // Gear=3, Speed=50
+        logger.log(0x300, 1700, &[1, 2, 3]); // Not in the DBC
+        logger.log(256, 2000, &[0x80, 0x3E, 0x64, 0, 0, 0, 0, 0]); // RPM=4000, Temp=60
+        let raw_path = std::env::temp_dir().join("overlay_transcode_raw.mf4");
+        std::fs::write(&raw_path, logger.finalize().unwrap()).unwrap();
+
+        let dbc = create_test_dbc();
+        let overlay = DbcOverlayReader::from_file(raw_path.to_str().unwrap(), &dbc).unwrap();
+        let mut reader = crate::FileRangeReader::new(raw_path.to_str().unwrap()).unwrap();
+
+        let config = CanDbcLoggerConfig {
+            max_buffered_frames: Some(1),
+            ..Default::default()
+        };
+        let writer = overlay
+            .transcode_to(MdfWriter::in_memory(), config, &mut reader)
+            .unwrap();
+        let decoded_path = std::env::temp_dir().join("overlay_transcode_decoded.mf4");
+        std::fs::write(&decoded_path, writer.into_inner().into_inner()).unwrap();
+
+        let mdf = crate::MDF::from_file(decoded_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups.len(), 2);
+        let engine = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("Engine"))
+            .unwrap();
+        let channels = engine.channels();
+        let rpm = channels
+            .iter()
+            .find(|ch| ch.name().unwrap().as_deref() == Some("RPM"))
+            .unwrap();
+        assert_eq!(rpm.unit().unwrap().as_deref(), Some("rpm"));
+        assert_eq!(
+            rpm.values().unwrap(),
+            alloc::vec![
+                Some(DecodedValue::Float(2000.0)),
+                Some(DecodedValue::Float(4000.0))
+            ]
+        );
+
+        std::fs::remove_file(&raw_path).ok();
+        std::fs::remove_file(&decoded_path).ok();
+    }
+
     #[test]
     fn test_overlay_signal_not_found() {
         use crate::can::RawCanLogger;
@@ -0,0 +1,578 @@
+//! Comments and attributes read from the text of a DBC file.
+//!
+//! The parsed DBC only covers what is needed to decode frames, so
+//! [`DbcText`] reads the `CM_`, `BA_DEF_`, `BA_DEF_DEF_`, `BA_` and
+//! `SG_MUL_VAL_` statements directly from the file. Message IDs are kept as written in
+//! the DBC, with bit 31 set for extended IDs.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Definition of an attribute from `BA_DEF_` and `BA_DEF_DEF_`.
+#[derive(Debug, Clone, Default)]
+struct AttributeDefinition {
+    /// Object type such as `BO_` or `SG_`; `None` for network attributes
+    object: Option<String>,
+    /// Labels of an `ENUM` attribute
+    enum_values: Vec<String>,
+    /// Value from `BA_DEF_DEF_`
+    default: Option<Token>,
+}
+
+/// A word or quoted string of a statement.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+/// Inclusive value ranges of a multiplexor switch.
+type ValueRanges = Vec<(u64, u64)>;
+
+/// Multiplexing of a signal as written in its `SG_` line.
+#[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+#[derive(Debug, Clone, Copy)]
+pub(super) struct SignalMux<'a> {
+    /// Signal name
+    pub(super) name: &'a str,
+    /// Bit length of the signal
+    pub(super) length: u16,
+    /// True for a multiplexor switch (`M`)
+    pub(super) is_switch: bool,
+    /// Multiplexor value of a multiplexed signal (`m<value>`)
+    pub(super) switch_value: Option<u64>,
+}
+
+/// Channel group partition of a multiplexed message.
+#[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct MultiplexGroups {
+    /// Index of the multiplexor switch signal that selects the group
+    pub(super) switch_index: usize,
+    /// Indices of the signals present for each value of the switch
+    pub(super) groups: BTreeMap<u64, Vec<usize>>,
+}
+
+/// Comments and attributes of the messages and signals of a DBC file.
+#[derive(Debug, Clone, Default)]
+pub(super) struct DbcText {
+    /// Message IDs in file order
+    message_ids: Vec<u32>,
+    message_comments: BTreeMap<u32, String>,
+    signal_comments: BTreeMap<(u32, String), String>,
+    definitions: BTreeMap<String, AttributeDefinition>,
+    message_attributes: BTreeMap<u32, BTreeMap<String, Token>>,
+    signal_attributes: BTreeMap<(u32, String), BTreeMap<String, Token>>,
+    /// Multiplexor switch and value ranges of signals from `SG_MUL_VAL_`
+    signal_multiplexing: BTreeMap<(u32, String), (String, ValueRanges)>,
+}
+
+impl DbcText {
+    /// Read the message IDs, comments and attributes of `text`.
+    ///
+    /// Statements that cannot be read are skipped.
+    pub(super) fn parse(text: &str) -> Self {
+        let mut dbc = Self::default();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let line = rest.trim_start();
+            let keyword = line
+                .split(|c: char| c.is_whitespace() || c == ':')
+                .next()
+                .unwrap_or("");
+            rest = match keyword {
+                "BO_" => {
+                    let (line, next) = split_line(line);
+                    if let Some(id) = line
+                        .split_whitespace()
+                        .nth(1)
+                        .and_then(|id| id.parse().ok())
+                    {
+                        dbc.message_ids.push(id);
+                    }
+                    next
+                }
+                "CM_" | "BA_DEF_" | "BA_DEF_DEF_" | "BA_" | "SG_MUL_VAL_" => {
+                    let (tokens, next) = statement_tokens(&line[keyword.len()..]);
+                    dbc.add_statement(keyword, tokens);
+                    next
+                }
+                _ => split_line(line).1,
+            };
+        }
+        dbc
+    }
+
+    fn add_statement(&mut self, keyword: &str, tokens: Vec<Token>) {
+        let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        match (keyword, words.as_slice()) {
+            ("CM_", ["BO_", id, _]) => {
+                if let Ok(id) = id.parse() {
+                    self.message_comments.insert(id, tokens[2].text.clone());
+                }
+            }
+            ("CM_", ["SG_", id, signal, _]) => {
+                if let Ok(id) = id.parse() {
+                    let key = (id, String::from(*signal));
+                    self.signal_comments.insert(key, tokens[3].text.clone());
+                }
+            }
+            ("BA_DEF_", [object, ..]) if !tokens[0].quoted => {
+                self.add_definition(Some(object), &tokens[1..]);
+            }
+            ("BA_DEF_", _) => self.add_definition(None, &tokens),
+            ("BA_DEF_DEF_", [name, _]) => {
+                self.definitions
+                    .entry(String::from(*name))
+                    .or_default()
+                    .default = Some(tokens[1].clone());
+            }
+            ("BA_", [name, "BO_", id, _]) => {
+                if let Ok(id) = id.parse() {
+                    self.message_attributes
+                        .entry(id)
+                        .or_default()
+                        .insert(String::from(*name), tokens[3].clone());
+                }
+            }
+            ("BA_", [name, "SG_", id, signal, _]) => {
+                if let Ok(id) = id.parse() {
+                    self.signal_attributes
+                        .entry((id, String::from(*signal)))
+                        .or_default()
+                        .insert(String::from(*name), tokens[4].clone());
+                }
+            }
+            ("SG_MUL_VAL_", [id, signal, switch, ranges @ ..]) => {
+                let ranges: Option<ValueRanges> = ranges
+                    .iter()
+                    .filter(|range| **range != ",")
+                    .map(|range| {
+                        let (min, max) = range.split_once('-')?;
+                        Some((min.parse().ok()?, max.parse().ok()?))
+                    })
+                    .collect();
+                if let (Ok(id), Some(ranges)) = (id.parse(), ranges) {
+                    self.signal_multiplexing
+                        .insert((id, String::from(*signal)), (String::from(*switch), ranges));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record `BA_DEF_ [object] "name" TYPE ...`, given the tokens after
+    /// the object type.
+    fn add_definition(&mut self, object: Option<&str>, tokens: &[Token]) {
+        let Some(name) = tokens.first() else {
+            return;
+        };
+        let definition = self.definitions.entry(name.text.clone()).or_default();
+        definition.object = object.map(String::from);
+        if tokens.get(1).is_some_and(|t| t.text == "ENUM") {
+            definition.enum_values = tokens[2..]
+                .iter()
+                .filter(|t| t.text != ",")
+                .map(|t| t.text.clone())
+                .collect();
+        }
+    }
+
+    /// IDs of the messages defined with `BO_`, in file order.
+    pub(super) fn message_ids(&self) -> &[u32] {
+        &self.message_ids
+    }
+
+    /// Comment of a message from `CM_ BO_`.
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+    pub(super) fn message_comment(&self, message_id: u32) -> Option<&str> {
+        self.message_comments.get(&message_id).map(String::as_str)
+    }
+
+    /// Comment of a signal from `CM_ SG_`.
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+    pub(super) fn signal_comment(&self, message_id: u32, signal: &str) -> Option<&str> {
+        self.signal_comments
+            .get(&(message_id, String::from(signal)))
+            .map(String::as_str)
+    }
+
+    /// Value of a message attribute, or its default.
+    pub(super) fn message_attribute(&self, message_id: u32, name: &str) -> Option<String> {
+        let value = self
+            .message_attributes
+            .get(&message_id)
+            .and_then(|attributes| attributes.get(name));
+        self.attribute_value(name, value)
+    }
+
+    /// All attributes defined for messages with their values for a message,
+    /// ordered by name. Attributes without value or default are left out.
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+    pub(super) fn message_attributes(&self, message_id: u32) -> Vec<(String, String)> {
+        self.object_attributes("BO_", self.message_attributes.get(&message_id))
+    }
+
+    /// All attributes defined for signals with their values for a signal,
+    /// ordered by name. Attributes without value or default are left out.
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+    pub(super) fn signal_attributes(&self, message_id: u32, signal: &str) -> Vec<(String, String)> {
+        let values = self
+            .signal_attributes
+            .get(&(message_id, String::from(signal)));
+        self.object_attributes("SG_", values)
+    }
+
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))]
+    fn object_attributes(
+        &self,
+        object: &str,
+        values: Option<&BTreeMap<String, Token>>,
+    ) -> Vec<(String, String)> {
+        let mut names: Vec<&String> = self
+            .definitions
+            .iter()
+            .filter(|(_, definition)| definition.object.as_deref() == Some(object))
+            .map(|(name, _)| name)
+            .collect();
+        // Values of attributes without BA_DEF_ are kept as well
+        if let Some(values) = values {
+            names.extend(
+                values
+                    .keys()
+                    .filter(|name| !self.definitions.contains_key(*name)),
+            );
+            names.sort();
+        }
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let value = values.and_then(|values| values.get(name));
+                Some((name.clone(), self.attribute_value(name, value)?))
+            })
+            .collect()
+    }
+
+    /// Multiplexor switch and value ranges of a signal from `SG_MUL_VAL_`.
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+    pub(super) fn signal_multiplexing(
+        &self,
+        message_id: u32,
+        signal: &str,
+    ) -> Option<(&str, &[(u64, u64)])> {
+        self.signal_multiplexing
+            .get(&(message_id, String::from(signal)))
+            .map(|(switch, ranges)| (switch.as_str(), ranges.as_slice()))
+    }
+
+    /// Partition the signals of a message into one channel group per value
+    /// of its multiplexor switch.
+    ///
+    /// Without `SG_MUL_VAL_` statements for the message this follows the
+    /// `M`/`m<value>` notation: a group per multiplexor value with the
+    /// signals of that value, the switch and the plain signals.
+    ///
+    /// With extended multiplexing, the groups are selected by the switch
+    /// that is not multiplexed itself. A signal belongs to the groups of
+    /// the values in its `SG_MUL_VAL_` ranges; a signal multiplexed by
+    /// another (nested) switch belongs to the groups of that switch. Values
+    /// beyond the range of the switch signal are ignored.
+    ///
+    /// # Returns
+    /// `None` if the message is not multiplexed
+    #[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+    pub(super) fn multiplex_groups(
+        &self,
+        message_id: u32,
+        signals: &[SignalMux<'_>],
+    ) -> Option<MultiplexGroups> {
+        let extended: BTreeMap<&str, _> = signals
+            .iter()
+            .filter_map(|signal| {
+                let multiplexing = self.signal_multiplexing(message_id, signal.name)?;
+                Some((signal.name, multiplexing))
+            })
+            .collect();
+
+        let switch_index = if extended.is_empty() {
+            signals.iter().position(|signal| signal.is_switch)?
+        } else {
+            signals.iter().position(|signal| {
+                !extended.contains_key(signal.name)
+                    && extended.values().any(|(switch, _)| *switch == signal.name)
+            })?
+        };
+        let switch = signals[switch_index];
+        let max_value = match switch.length {
+            0..=63 => (1u64 << switch.length) - 1,
+            _ => u64::MAX,
+        };
+
+        let mut values = BTreeSet::new();
+        for signal in signals {
+            match extended.get(signal.name) {
+                Some((name, ranges)) if *name == switch.name => {
+                    for &(min, max) in *ranges {
+                        values.extend(min..=max.min(max_value));
+                    }
+                }
+                Some(_) => {}
+                None => values.extend(signal.switch_value),
+            }
+        }
+        if values.is_empty() {
+            return None;
+        }
+
+        // Whether a signal is present for a value of the switch; nesting
+        // deeper than the number of signals is a cycle
+        let present = |signal: &SignalMux<'_>, value: u64| {
+            let mut name = signal.name;
+            for _ in 0..signals.len() {
+                match extended.get(name) {
+                    Some((switch_name, ranges)) if *switch_name == switch.name => {
+                        return ranges
+                            .iter()
+                            .any(|&(min, max)| (min..=max).contains(&value));
+                    }
+                    Some((switch_name, _)) => name = switch_name,
+                    None => {
+                        let plain = signals.iter().find(|s| s.name == name);
+                        return plain
+                            .and_then(|s| s.switch_value)
+                            .is_none_or(|v| v == value);
+                    }
+                }
+            }
+            false
+        };
+
+        let groups = values
+            .into_iter()
+            .map(|value| {
+                let indices = (0..signals.len())
+                    .filter(|&idx| idx == switch_index || present(&signals[idx], value))
+                    .collect();
+                (value, indices)
+            })
+            .collect();
+        Some(MultiplexGroups {
+            switch_index,
+            groups,
+        })
+    }
+
+    /// `value` or the default of attribute `name`, with enum indices
+    /// replaced by their labels.
+    fn attribute_value(&self, name: &str, value: Option<&Token>) -> Option<String> {
+        let definition = self.definitions.get(name);
+        let token = value.or_else(|| definition?.default.as_ref())?;
+        let label = match definition {
+            Some(definition) if !token.quoted => token
+                .text
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| definition.enum_values.get(index)),
+            _ => None,
+        };
+        Some(label.unwrap_or(&token.text).clone())
+    }
+}
+
+/// Split `text` after its first line.
+fn split_line(text: &str) -> (&str, &str) {
+    match text.find('\n') {
+        Some(end) => (&text[..end], &text[end + 1..]),
+        None => (text, ""),
+    }
+}
+
+/// Tokens of a statement up to its terminating `;`, which may be several
+/// lines away, and the text after it. Quoted strings may contain `;`, line
+/// breaks and `\"` escapes.
+fn statement_tokens(text: &str) -> (Vec<Token>, &str) {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            ';' => return (tokens, &text[start + 1..]),
+            '"' => {
+                let mut value = String::new();
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        _ => value.push(c),
+                    }
+                }
+                tokens.push(Token {
+                    text: value,
+                    quoted: true,
+                });
+            }
+            ',' => tokens.push(Token {
+                text: String::from(","),
+                quoted: false,
+            }),
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut end = text.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, ';' | '"' | ',') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push(Token {
+                    text: String::from(&text[start..end]),
+                    quoted: false,
+                });
+            }
+        }
+    }
+    (tokens, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_comments_and_attributes() {
+        let text = r#"VERSION ""
+
+BO_ 256 Engine: 8 ECM
+ SG_ RPM : 0|16@1+ (0.25,0) [0|8000] "rpm" ECM
+ SG_ Mode : 16|2@1+ (1,0) [0|3] "" ECM
+
+BO_ 2566844672 J1939: 8 ECM
+
+CM_ "Network comment";
+CM_ BO_ 256 "Engine status; sent by the ECM";
+CM_ SG_ 256 RPM "Crankshaft speed,
+filtered over 4 \"samples\"";
+BA_DEF_ BO_ "GenMsgCycleTime" INT 0 65535;
+BA_DEF_ BO_ "GenMsgSendType" ENUM "Cyclic","Event";
+BA_DEF_ SG_ "GenSigStartValue" INT 0 65535;
+BA_DEF_ "BusType" STRING ;
+BA_DEF_DEF_ "GenMsgCycleTime" 100;
+BA_DEF_DEF_ "GenMsgSendType" "Cyclic";
+BA_ "BusType" "CAN";
+BA_ "GenMsgCycleTime" BO_ 256 10;
+BA_ "GenMsgSendType" BO_ 2566844672 1;
+BA_ "GenSigStartValue" SG_ 256 RPM 800;
+"#;
+        let dbc = DbcText::parse(text);
+
+        assert_eq!(dbc.message_ids(), [256, 2_566_844_672]);
+        assert_eq!(
+            dbc.message_comment(256),
+            Some("Engine status; sent by the ECM")
+        );
+        assert_eq!(
+            dbc.signal_comment(256, "RPM"),
+            Some("Crankshaft speed,\nfiltered over 4 \"samples\"")
+        );
+        assert_eq!(dbc.signal_comment(256, "Mode"), None);
+
+        let pair = |name: &str, value: &str| (String::from(name), String::from(value));
+        assert_eq!(
+            dbc.message_attributes(256),
+            [
+                pair("GenMsgCycleTime", "10"),
+                pair("GenMsgSendType", "Cyclic")
+            ]
+        );
+        assert_eq!(
+            dbc.message_attributes(2_566_844_672),
+            [
+                pair("GenMsgCycleTime", "100"),
+                pair("GenMsgSendType", "Event")
+            ]
+        );
+        assert_eq!(
+            dbc.signal_attributes(256, "RPM"),
+            [pair("GenSigStartValue", "800")]
+        );
+        assert!(dbc.signal_attributes(256, "Mode").is_empty());
+        assert_eq!(
+            dbc.message_attribute(512, "GenMsgCycleTime").as_deref(),
+            Some("100")
+        );
+    }
+
+    #[test]
+    fn test_multiplex_groups() {
+        let text = r#"
+BO_ 256 Diag: 8 ECM
+ SG_ Service M : 0|8@1+ (1,0) [0|255] "" ECM
+ SG_ Sub m1M : 8|8@1+ (1,0) [0|255] "" ECM
+ SG_ Speed m0 : 16|16@1+ (1,0) [0|65535] "" ECM
+ SG_ Detail m2 : 16|8@1+ (1,0) [0|255] "" ECM
+ SG_ Counter : 56|8@1+ (1,0) [0|255] "" ECM
+
+BO_ 512 Simple: 8 ECM
+
+SG_MUL_VAL_ 256 Sub Service 1-1;
+SG_MUL_VAL_ 256 Speed Service 0-0, 3-4;
+SG_MUL_VAL_ 256 Detail Sub 2-2;
+"#;
+        let dbc = DbcText::parse(text);
+        assert_eq!(
+            dbc.signal_multiplexing(256, "Speed"),
+            Some(("Service", &[(0, 0), (3, 4)][..]))
+        );
+
+        let signal = |name, length, is_switch, switch_value| SignalMux {
+            name,
+            length,
+            is_switch,
+            switch_value,
+        };
+        let extended = [
+            signal("Service", 8, true, None),
+            signal("Sub", 8, true, Some(1)),
+            signal("Speed", 16, false, Some(0)),
+            signal("Detail", 8, false, Some(2)),
+            signal("Counter", 8, false, None),
+        ];
+        let groups = dbc.multiplex_groups(256, &extended).unwrap();
+        assert_eq!(groups.switch_index, 0);
+        assert_eq!(
+            groups.groups,
+            BTreeMap::from([
+                (0, vec![0, 2, 4]),
+                (1, vec![0, 1, 3, 4]),
+                (3, vec![0, 2, 4]),
+                (4, vec![0, 2, 4]),
+            ])
+        );
+
+        // m<value> notation without SG_MUL_VAL_
+        let simple = [
+            signal("Mode", 2, true, None),
+            signal("A", 8, false, Some(0)),
+            signal("B", 8, false, Some(1)),
+            signal("C", 8, false, None),
+        ];
+        let groups = dbc.multiplex_groups(512, &simple).unwrap();
+        assert_eq!(groups.switch_index, 0);
+        assert_eq!(
+            groups.groups,
+            BTreeMap::from([(0, vec![0, 1, 3]), (1, vec![0, 2, 3])])
+        );
+
+        // Ranges are limited to the values of the switch
+        let dbc = DbcText::parse("SG_MUL_VAL_ 512 A Mode 2-10;");
+        let groups = dbc.multiplex_groups(512, &simple).unwrap();
+        assert_eq!(groups.groups.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+        assert!(dbc.multiplex_groups(768, &simple[1..]).is_none());
+    }
+}
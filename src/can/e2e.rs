@@ -0,0 +1,507 @@
+//! End-to-end (E2E) protection checks for the CAN loggers.
+//!
+//! Safety-relevant messages carry a rolling counter and often a checksum
+//! computed as defined by an AUTOSAR E2E profile. An [`E2eCheck`] names the
+//! counter and checksum signals of a DBC message; with
+//! [`CanDbcLoggerBuilder::e2e_check`](super::CanDbcLoggerBuilder::e2e_check)
+//! every frame of the message is checked and the result is written to a
+//! `{Message}_E2E` channel group:
+//! - `Timestamp`: time of the frame in seconds (master channel)
+//! - `Valid`: 1 if the frame passed the checks, with a value-to-text
+//!   conversion
+//! - `Status`: the [`E2eStatus`] of the frame, with a value-to-text
+//!   conversion
+//! - `ErrorCount`: number of failed checks of the message so far
+//!
+//! Supported checksums are those of E2E profiles 1, 2 and 5. The checksum
+//! covers all data bytes except those of the checksum signal, so the
+//! signal layout of the DBC decides the byte positions.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::can::{CanDbcLogger, E2eCheck, E2eProfile};
+//!
+//! let mut logger = CanDbcLogger::builder(dbc)
+//!     .e2e_check(
+//!         E2eCheck::new("BrakeStatus", "BrakeStatus_Counter")
+//!             .with_crc("BrakeStatus_CRC", E2eProfile::Profile1 { data_id: 0x123 }),
+//!     )
+//!     .build()?;
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::writer::MdfWrite;
+use crate::{DataType, DecodedValue, MdfWriter, Result};
+
+/// Checksum algorithm and data ID of an AUTOSAR E2E profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum E2eProfile {
+    /// Profile 1: CRC-8 SAE J1850 (polynomial 0x1D) over the low and high
+    /// byte of the data ID, then the data; 4-bit counter from 0 to 14
+    Profile1 {
+        /// Data ID, included in the checksum with both bytes
+        data_id: u16,
+    },
+    /// Profile 2: CRC-8H2F (polynomial 0x2F) over the data, then the entry
+    /// of the data ID list selected by the counter; 4-bit counter from 0
+    /// to 15
+    Profile2 {
+        /// Data IDs indexed by the counter value
+        data_id_list: [u8; 16],
+    },
+    /// Profile 5: CRC-16 CCITT (polynomial 0x1021) over the data, then the
+    /// low and high byte of the data ID; 8-bit counter
+    Profile5 {
+        /// Data ID, included in the checksum with both bytes
+        data_id: u16,
+    },
+}
+
+impl E2eProfile {
+    /// Number of counter values: the counter wraps to 0 after
+    /// `counter_range() - 1`.
+    pub fn counter_range(&self) -> u64 {
+        match self {
+            Self::Profile1 { .. } => 15,
+            Self::Profile2 { .. } => 16,
+            Self::Profile5 { .. } => 256,
+        }
+    }
+
+    /// Compute the checksum of a frame.
+    ///
+    /// The checksum covers all bytes of `data` except `crc_bytes`, the
+    /// bytes of the checksum signal.
+    pub fn checksum(&self, data: &[u8], crc_bytes: Range<usize>, counter: u64) -> u64 {
+        let covered = data
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !crc_bytes.contains(i))
+            .map(|(_, &byte)| byte);
+        match *self {
+            Self::Profile1 { data_id } => {
+                let [id_low, id_high] = data_id.to_le_bytes();
+                let crc = [id_low, id_high].into_iter().chain(covered);
+                crc8(0x1D, 0x00, crc) as u64
+            }
+            Self::Profile2 { data_id_list } => {
+                let data_id = data_id_list[(counter & 0x0F) as usize];
+                (crc8(0x2F, 0xFF, covered.chain([data_id])) ^ 0xFF) as u64
+            }
+            Self::Profile5 { data_id } => crc16(covered.chain(data_id.to_le_bytes())) as u64,
+        }
+    }
+}
+
+/// Result of the E2E check of one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum E2eStatus {
+    /// The counter incremented by one and the checksum matches
+    Ok = 0,
+    /// First frame of the message; the counter is not checked
+    Initial = 1,
+    /// Some frames were lost, but no more than the configured maximum
+    OkSomeLost = 2,
+    /// The counter did not change
+    Repeated = 3,
+    /// Frames were lost, or the counter went back or is out of range
+    WrongSequence = 4,
+    /// The checksum does not match the frame
+    WrongCrc = 5,
+}
+
+impl E2eStatus {
+    /// All states with their names, as stored in the value-to-text
+    /// conversion of the `Status` channel.
+    pub const NAMES: [(E2eStatus, &'static str); 6] = [
+        (Self::Ok, "Ok"),
+        (Self::Initial, "Initial"),
+        (Self::OkSomeLost, "OkSomeLost"),
+        (Self::Repeated, "Repeated"),
+        (Self::WrongSequence, "WrongSequence"),
+        (Self::WrongCrc, "WrongCrc"),
+    ];
+
+    /// Returns true if the frame passed the checks.
+    pub fn is_valid(self) -> bool {
+        matches!(self, Self::Ok | Self::Initial | Self::OkSomeLost)
+    }
+}
+
+/// E2E check of a DBC message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct E2eCheck {
+    /// Name of the DBC message
+    pub message: String,
+    /// Name of the rolling counter signal
+    pub counter_signal: String,
+    /// Name of the checksum signal and the profile computing it
+    pub crc: Option<(String, E2eProfile)>,
+    /// Largest counter increment still accepted as [`E2eStatus::OkSomeLost`]
+    pub max_delta_counter: u64,
+}
+
+impl E2eCheck {
+    /// Check the rolling counter `counter_signal` of `message`.
+    ///
+    /// Without a checksum profile, the counter wraps after the largest
+    /// value of the signal.
+    pub fn new(message: &str, counter_signal: &str) -> Self {
+        Self {
+            message: String::from(message),
+            counter_signal: String::from(counter_signal),
+            crc: None,
+            max_delta_counter: 1,
+        }
+    }
+
+    /// Also check the checksum signal `crc_signal`, computed as defined by
+    /// `profile`. The counter range of the profile applies.
+    pub fn with_crc(mut self, crc_signal: &str, profile: E2eProfile) -> Self {
+        self.crc = Some((String::from(crc_signal), profile));
+        self
+    }
+
+    /// Accept counter increments up to `max_delta_counter` as
+    /// [`E2eStatus::OkSomeLost`].
+    ///
+    /// Default: 1 (every lost frame is a sequence error)
+    pub fn with_max_delta_counter(mut self, max_delta_counter: u64) -> Self {
+        self.max_delta_counter = max_delta_counter;
+        self
+    }
+}
+
+/// Bytes of a DBC signal within the frame data.
+///
+/// `start_bit` is the DBC start bit: the least significant bit for
+/// little-endian (Intel) signals and the most significant bit for
+/// big-endian (Motorola) signals.
+#[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+pub(super) fn signal_bytes(start_bit: u16, length: u16, little_endian: bool) -> Range<usize> {
+    let first = (start_bit / 8) as usize;
+    let length = length.max(1) as usize;
+    let last = if little_endian {
+        (start_bit as usize + length - 1) / 8
+    } else {
+        // Motorola signals continue at the MSB of the following bytes
+        let first_byte_bits = (start_bit % 8) as usize + 1;
+        first + length.saturating_sub(first_byte_bits).div_ceil(8)
+    };
+    first..last + 1
+}
+
+/// E2E state of one message: the check, the last counter and the records
+/// not yet written.
+#[derive(Debug)]
+#[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+pub(super) struct E2eMonitor {
+    /// Name of the DBC message
+    message: String,
+    /// Index of the counter signal in the message
+    pub(super) counter_index: usize,
+    /// Number of counter values
+    counter_range: u64,
+    max_delta_counter: u64,
+    /// Index of the checksum signal in the message, its bytes and profile
+    pub(super) crc: Option<(usize, Range<usize>, E2eProfile)>,
+    last_counter: Option<u64>,
+    error_count: u64,
+    /// Timestamp, status and error count of the frames not yet written
+    records: Vec<(u64, E2eStatus, u64)>,
+    /// Channel group ID of the E2E channels
+    group: Option<String>,
+}
+
+#[cfg_attr(not(all(feature = "std", feature = "dbc")), allow(dead_code))] // Used by CanDbcLogger
+impl E2eMonitor {
+    /// Create the monitor of `check` for a message with the counter signal
+    /// at `counter_index`, `counter_length` bits long, and the checksum
+    /// signal at `crc_index` with the bytes `crc_bytes`.
+    pub(super) fn new(
+        check: &E2eCheck,
+        counter_index: usize,
+        counter_length: u16,
+        crc: Option<(usize, Range<usize>)>,
+    ) -> Self {
+        let profile = check.crc.as_ref().map(|(_, profile)| *profile);
+        let counter_range = match profile {
+            Some(profile) => profile.counter_range(),
+            None => 1u64.checked_shl(counter_length as u32).unwrap_or(u64::MAX),
+        };
+        Self {
+            message: check.message.clone(),
+            counter_index,
+            counter_range,
+            max_delta_counter: check.max_delta_counter,
+            crc: crc
+                .zip(profile)
+                .map(|((index, bytes), p)| (index, bytes, p)),
+            last_counter: None,
+            error_count: 0,
+            records: Vec::new(),
+            group: None,
+        }
+    }
+
+    /// Check a frame with the raw `counter` and `crc` signal values.
+    pub(super) fn check(
+        &mut self,
+        timestamp_us: u64,
+        data: &[u8],
+        counter: u64,
+        crc: Option<u64>,
+    ) -> E2eStatus {
+        let crc_ok = match (&self.crc, crc) {
+            (Some((_, bytes, profile)), Some(crc)) => {
+                profile.checksum(data, bytes.clone(), counter) == crc
+            }
+            _ => true,
+        };
+        let status = if !crc_ok {
+            // The counter of a corrupted frame is not trusted
+            E2eStatus::WrongCrc
+        } else if counter >= self.counter_range {
+            E2eStatus::WrongSequence
+        } else {
+            let status = match self.last_counter {
+                None => E2eStatus::Initial,
+                Some(last) => match (counter + self.counter_range - last) % self.counter_range {
+                    0 => E2eStatus::Repeated,
+                    1 => E2eStatus::Ok,
+                    delta if delta <= self.max_delta_counter => E2eStatus::OkSomeLost,
+                    _ => E2eStatus::WrongSequence,
+                },
+            };
+            self.last_counter = Some(counter);
+            status
+        };
+        if !status.is_valid() {
+            self.error_count += 1;
+        }
+        self.records.push((timestamp_us, status, self.error_count));
+        status
+    }
+
+    /// Number of failed checks so far.
+    pub(super) fn error_count(&self) -> u64 {
+        self.error_count
+    }
+
+    /// Number of records not yet written.
+    pub(super) fn buffered(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Create the `{Message}_E2E` channel group.
+    pub(super) fn init_group<W: MdfWrite>(
+        &mut self,
+        writer: &mut MdfWriter<W>,
+        bus_name: &str,
+    ) -> Result<()> {
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.set_channel_group_name(&cg, &alloc::format!("{}_E2E", self.message))?;
+        let source = crate::blocks::SourceBlock::can_bus();
+        writer.set_channel_group_source(&cg, &source, Some(bus_name))?;
+
+        let time_ch = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(String::from("Timestamp"));
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&time_ch)?;
+        writer.set_channel_unit(&time_ch, "s")?;
+
+        let valid_ch = writer.add_channel(&cg, Some(&time_ch), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from("Valid"));
+            ch.bit_count = 8;
+        })?;
+        writer.add_value_to_text_conversion(
+            &[(0, "Invalid"), (1, "Valid")],
+            "",
+            Some(&valid_ch),
+        )?;
+
+        let status_ch = writer.add_channel(&cg, Some(&valid_ch), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from("Status"));
+            ch.bit_count = 8;
+        })?;
+        let names: Vec<(i64, &str)> = E2eStatus::NAMES
+            .iter()
+            .map(|&(status, name)| (status as i64, name))
+            .collect();
+        writer.add_value_to_text_conversion(&names, "", Some(&status_ch))?;
+
+        writer.add_channel(&cg, Some(&status_ch), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from("ErrorCount"));
+            ch.bit_count = 32;
+        })?;
+
+        self.group = Some(cg);
+        Ok(())
+    }
+
+    /// Write the buffered records as one data block. Does nothing before
+    /// [`init_group`](Self::init_group) or without records.
+    pub(super) fn write<W: MdfWrite>(&mut self, writer: &mut MdfWriter<W>) -> Result<()> {
+        let Some(cg) = &self.group else {
+            return Ok(());
+        };
+        if self.records.is_empty() {
+            return Ok(());
+        }
+
+        writer.start_data_block_for_cg(cg, 0)?;
+        for (timestamp_us, status, error_count) in self.records.drain(..) {
+            let values = [
+                DecodedValue::Float(crate::bus_logging::timestamp_to_seconds(timestamp_us)),
+                DecodedValue::UnsignedInteger(status.is_valid() as u64),
+                DecodedValue::UnsignedInteger(status as u64),
+                DecodedValue::UnsignedInteger(error_count),
+            ];
+            writer.write_record(cg, &values)?;
+        }
+        writer.finish_data_block(cg)
+    }
+}
+
+/// Bitwise CRC-8 without reflection or final XOR.
+fn crc8(polynomial: u8, init: u8, bytes: impl Iterator<Item = u8>) -> u8 {
+    bytes.fold(init, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ polynomial
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// CRC-16 CCITT (polynomial 0x1021, start value 0xFFFF, no final XOR).
+fn crc16(bytes: impl Iterator<Item = u8>) -> u16 {
+    bytes.fold(0xFFFF, |mut crc, byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc_check_values() {
+        let check = b"123456789".iter().copied();
+        // CRC-8 SAE J1850, CRC-8H2F and CRC-16 CCITT-FALSE check values
+        assert_eq!(crc8(0x1D, 0xFF, check.clone()) ^ 0xFF, 0x4B);
+        assert_eq!(crc8(0x2F, 0xFF, check.clone()) ^ 0xFF, 0xDF);
+        assert_eq!(crc16(check), 0x29B1);
+    }
+
+    #[test]
+    fn test_profile_checksums() {
+        let data = [0x00, 0x01, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+        let profile1 = E2eProfile::Profile1 { data_id: 0x1234 };
+        let covered = [0x34, 0x12, 0x01, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+        assert_eq!(
+            profile1.checksum(&data, 0..1, 1),
+            crc8(0x1D, 0x00, covered.into_iter()) as u64
+        );
+
+        // The data ID is selected by the counter
+        let mut data_id_list = [0u8; 16];
+        data_id_list[1] = 0x5A;
+        let profile2 = E2eProfile::Profile2 { data_id_list };
+        let covered = [0x01, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0x5A];
+        assert_eq!(
+            profile2.checksum(&data, 0..1, 1),
+            (crc8(0x2F, 0xFF, covered.into_iter()) ^ 0xFF) as u64
+        );
+        assert_ne!(
+            profile2.checksum(&data, 0..1, 1),
+            profile2.checksum(&data, 0..1, 2)
+        );
+
+        let profile5 = E2eProfile::Profile5 { data_id: 0x1234 };
+        let covered = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0x34, 0x12];
+        assert_eq!(
+            profile5.checksum(&data, 0..2, 1),
+            crc16(covered.into_iter()) as u64
+        );
+    }
+
+    #[test]
+    fn test_signal_bytes() {
+        assert_eq!(signal_bytes(0, 8, true), 0..1);
+        assert_eq!(signal_bytes(12, 8, true), 1..3);
+        assert_eq!(signal_bytes(16, 16, true), 2..4);
+        // Motorola: MSB at bit 7, continues in byte 1
+        assert_eq!(signal_bytes(7, 16, false), 0..2);
+        assert_eq!(signal_bytes(3, 4, false), 0..1);
+        assert_eq!(signal_bytes(3, 8, false), 0..2);
+    }
+
+    #[test]
+    fn test_monitor_counter_and_crc() {
+        let profile = E2eProfile::Profile1 { data_id: 0x0100 };
+        let check = E2eCheck::new("Brake", "Counter")
+            .with_crc("CRC", profile)
+            .with_max_delta_counter(2);
+        let mut monitor = E2eMonitor::new(&check, 1, 4, Some((0, 0..1)));
+        let frame = |counter: u8| {
+            let mut data = [0, counter, 0xAA, 0x55];
+            data[0] = profile.checksum(&data, 0..1, counter as u64) as u8;
+            data
+        };
+        let mut check_frame = |timestamp_us: u64, data: [u8; 4]| {
+            monitor.check(timestamp_us, &data, data[1] as u64, Some(data[0] as u64))
+        };
+
+        assert_eq!(check_frame(0, frame(13)), E2eStatus::Initial);
+        assert_eq!(check_frame(10, frame(14)), E2eStatus::Ok);
+        // Profile 1 counters wrap after 14
+        assert_eq!(check_frame(20, frame(0)), E2eStatus::Ok);
+        assert_eq!(check_frame(30, frame(0)), E2eStatus::Repeated);
+        assert_eq!(check_frame(40, frame(2)), E2eStatus::OkSomeLost);
+        assert_eq!(check_frame(50, frame(6)), E2eStatus::WrongSequence);
+        let mut corrupted = frame(7);
+        corrupted[2] ^= 0x01;
+        assert_eq!(check_frame(60, corrupted), E2eStatus::WrongCrc);
+        assert_eq!(check_frame(70, frame(15)), E2eStatus::WrongSequence);
+        assert_eq!(check_frame(80, frame(7)), E2eStatus::Ok);
+
+        assert_eq!(monitor.error_count(), 4);
+        assert_eq!(monitor.buffered(), 9);
+        assert_eq!(monitor.records[6], (60, E2eStatus::WrongCrc, 3));
+    }
+
+    #[test]
+    fn test_monitor_counter_only() {
+        let check = E2eCheck::new("Status", "AliveCounter");
+        let mut monitor = E2eMonitor::new(&check, 0, 2, None);
+        for (i, counter) in [2, 3, 0, 1].into_iter().enumerate() {
+            let status = monitor.check(i as u64, &[counter], counter as u64, None);
+            assert!(status.is_valid());
+        }
+        assert_eq!(monitor.check(4, &[3], 3, None), E2eStatus::WrongSequence);
+        assert_eq!(monitor.error_count(), 1);
+    }
+}
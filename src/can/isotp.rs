@@ -0,0 +1,481 @@
+//! ISO-TP (ISO 15765-2) reassembly and UDS logging.
+//!
+//! Diagnostic protocols such as UDS (ISO 14229) send payloads of up to 4095
+//! bytes (more with CAN FD) over CAN by splitting them into a first frame
+//! and consecutive frames. [`IsoTpReassembler`] turns the raw frames back
+//! into complete payloads, and [`UdsLogger`] logs them into a `UDS` channel
+//! group so diagnostic sessions can be analyzed from the MDF file.
+//!
+//! Normal addressing is assumed: the first data byte of every frame is the
+//! protocol control information. Flow control frames are recognized and
+//! skipped.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::can::isotp::UdsLogger;
+//!
+//! let mut logger = UdsLogger::new();
+//! for (timestamp_us, can_id, data) in frames {
+//!     logger.log(can_id, timestamp_us, &data);
+//! }
+//! let mdf_bytes = logger.finalize()?;
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::bus_logging::timestamp_to_seconds;
+
+/// Default time allowed between two frames of a transfer (N_Cr of ISO
+/// 15765-2).
+pub const DEFAULT_ISOTP_TIMEOUT_US: u64 = 1_000_000;
+
+/// Default largest payload accepted from a first frame. Escaped first frames
+/// announce up to 4 GiB, far more than diagnostic transfers carry.
+pub const DEFAULT_ISOTP_MAX_MESSAGE_SIZE: usize = 1 << 20;
+
+/// Largest payload of a first frame without escape sequence.
+const MAX_UNESCAPED_SIZE: usize = 4095;
+
+/// UDS service ID of negative responses.
+pub const UDS_NEGATIVE_RESPONSE: u8 = 0x7F;
+
+/// Protocol control information types (high nibble of the first byte).
+const PCI_SINGLE_FRAME: u8 = 0;
+const PCI_FIRST_FRAME: u8 = 1;
+const PCI_CONSECUTIVE_FRAME: u8 = 2;
+
+/// A complete ISO-TP payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsoTpMessage {
+    /// Timestamp in microseconds of the last frame of the message
+    pub timestamp_us: u64,
+    /// CAN ID the message was sent with
+    pub can_id: u32,
+    /// Reassembled payload
+    pub data: Vec<u8>,
+}
+
+impl IsoTpMessage {
+    /// UDS service ID: the first payload byte.
+    pub fn service_id(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Returns `true` if the payload is a UDS response.
+    ///
+    /// Positive responses carry the request service ID plus 0x40, negative
+    /// responses 0x7F; request service IDs never have bit 6 set.
+    pub fn is_response(&self) -> bool {
+        self.service_id().is_some_and(|sid| sid & 0x40 != 0)
+    }
+}
+
+/// A transfer in progress.
+struct Transfer {
+    size: usize,
+    next_sequence: u8,
+    last_timestamp_us: u64,
+    data: Vec<u8>,
+}
+
+/// Reassembles ISO-TP transfers from raw CAN frames.
+///
+/// Feed every frame of the diagnostic CAN IDs to
+/// [`process()`](Self::process); single frames are returned right away,
+/// segmented transfers once their last consecutive frame arrives. Each CAN
+/// ID carries at most one transfer at a time. A transfer is dropped when a
+/// consecutive frame is out of sequence or later than the timeout, or when
+/// a new first or single frame interrupts it. First frames announcing more
+/// than the maximum message size are dropped as well.
+pub struct IsoTpReassembler {
+    transfers: BTreeMap<u32, Transfer>,
+    timeout_us: u64,
+    max_message_size: usize,
+    dropped: usize,
+}
+
+impl Default for IsoTpReassembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IsoTpReassembler {
+    /// Create a reassembler with the default timeout of 1 s.
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_ISOTP_TIMEOUT_US)
+    }
+
+    /// Create a reassembler that drops transfers whose frames are more than
+    /// `timeout_us` microseconds apart.
+    pub fn with_timeout(timeout_us: u64) -> Self {
+        Self {
+            transfers: BTreeMap::new(),
+            timeout_us,
+            max_message_size: DEFAULT_ISOTP_MAX_MESSAGE_SIZE,
+            dropped: 0,
+        }
+    }
+
+    /// Set the largest payload in bytes a first frame may announce.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.set_max_message_size(max_message_size);
+        self
+    }
+
+    /// Set the largest payload in bytes a first frame may announce.
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
+    /// Number of transfers in progress.
+    pub fn pending_transfers(&self) -> usize {
+        self.transfers.len()
+    }
+
+    /// Number of transfers dropped as incomplete or oversized.
+    pub fn dropped_transfers(&self) -> usize {
+        self.dropped
+    }
+
+    /// Process a CAN (FD) frame.
+    ///
+    /// # Returns
+    /// The payload this frame completes, if any.
+    pub fn process(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> Option<IsoTpMessage> {
+        let pci = *data.first()?;
+        match pci >> 4 {
+            PCI_SINGLE_FRAME => {
+                self.interrupt(can_id);
+                // CAN FD single frames with more than 7 bytes escape the length
+                let (len, start) = match pci & 0x0F {
+                    0 => (*data.get(1)? as usize, 2),
+                    len => (len as usize, 1),
+                };
+                let payload = data.get(start..start + len)?;
+                Some(IsoTpMessage {
+                    timestamp_us,
+                    can_id,
+                    data: payload.to_vec(),
+                })
+            }
+            PCI_FIRST_FRAME => {
+                self.interrupt(can_id);
+                let len = (((pci & 0x0F) as usize) << 8) | *data.get(1)? as usize;
+                // Payloads over 4095 bytes use a 32-bit length
+                let (size, start) = match len {
+                    0 => {
+                        let bytes = data.get(2..6)?;
+                        (u32::from_be_bytes(bytes.try_into().ok()?) as usize, 6)
+                    }
+                    len => (len, 2),
+                };
+                if size > self.max_message_size {
+                    self.dropped += 1;
+                    return None;
+                }
+                // The announced size is not trusted for the allocation
+                let mut transfer = Transfer {
+                    size,
+                    next_sequence: 1,
+                    last_timestamp_us: timestamp_us,
+                    data: Vec::with_capacity(size.min(MAX_UNESCAPED_SIZE)),
+                };
+                transfer
+                    .data
+                    .extend_from_slice(&data[start.min(data.len())..]);
+                self.transfers.insert(can_id, transfer);
+                None
+            }
+            PCI_CONSECUTIVE_FRAME => {
+                let transfer = self.transfers.get_mut(&can_id)?;
+                let in_time =
+                    timestamp_us.saturating_sub(transfer.last_timestamp_us) <= self.timeout_us;
+                if pci & 0x0F != transfer.next_sequence || !in_time {
+                    self.transfers.remove(&can_id);
+                    self.dropped += 1;
+                    return None;
+                }
+                transfer.data.extend_from_slice(&data[1..]);
+                transfer.next_sequence = (transfer.next_sequence + 1) & 0x0F;
+                transfer.last_timestamp_us = timestamp_us;
+                if transfer.data.len() < transfer.size {
+                    return None;
+                }
+                let mut transfer = self.transfers.remove(&can_id)?;
+                transfer.data.truncate(transfer.size);
+                Some(IsoTpMessage {
+                    timestamp_us,
+                    can_id,
+                    data: transfer.data,
+                })
+            }
+            // Flow control frames and reserved types
+            _ => None,
+        }
+    }
+
+    /// Drop the transfer of `can_id`, if any, because a new one starts.
+    fn interrupt(&mut self, can_id: u32) {
+        if self.transfers.remove(&can_id).is_some() {
+            self.dropped += 1;
+        }
+    }
+}
+
+/// Logs reassembled UDS payloads.
+///
+/// All payloads go to one `UDS` channel group with the channels:
+/// - `Timestamp` - Float64 seconds (master)
+/// - `CAN_ID` - UInt32 CAN ID of the message
+/// - `Response` - UInt8, 1 for responses and 0 for requests
+/// - `ServiceID` - UInt8 first payload byte (0x7F for negative responses)
+/// - `Payload` - complete payload as variable length ByteArray (VLSD)
+///
+/// Messages are buffered in memory and written by
+/// [`finalize()`](Self::finalize).
+pub struct UdsLogger {
+    reassembler: IsoTpReassembler,
+    messages: Vec<IsoTpMessage>,
+}
+
+impl Default for UdsLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UdsLogger {
+    /// Create a logger with the default ISO-TP timeout.
+    pub fn new() -> Self {
+        Self::with_reassembler(IsoTpReassembler::new())
+    }
+
+    /// Create a logger using the given reassembler, e.g. with a custom
+    /// timeout.
+    pub fn with_reassembler(reassembler: IsoTpReassembler) -> Self {
+        Self {
+            reassembler,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Log a frame of a diagnostic CAN ID.
+    ///
+    /// # Returns
+    /// `true` if the frame completed a payload.
+    pub fn log(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        match self.reassembler.process(can_id, timestamp_us, data) {
+            Some(message) if !message.data.is_empty() => {
+                self.messages.push(message);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of payloads logged.
+    pub fn message_count(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// The ISO-TP reassembler.
+    pub fn reassembler(&self) -> &IsoTpReassembler {
+        &self.reassembler
+    }
+
+    /// Write all payloads and return the MDF bytes.
+    pub fn finalize(self) -> crate::Result<Vec<u8>> {
+        use crate::{DataType, DecodedValue};
+
+        let mut writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
+        writer.init_mdf_file()?;
+        if !self.messages.is_empty() {
+            let cg = writer.add_channel_group(None, |_| {})?;
+            writer.set_channel_group_name(&cg, "UDS")?;
+            let source = crate::blocks::SourceBlock::can_bus();
+            writer.set_channel_group_source(&cg, &source, Some("ISO-TP"))?;
+
+            let time_ch = writer.add_channel(&cg, None, |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.name = Some(String::from("Timestamp"));
+                ch.bit_count = 64;
+            })?;
+            writer.set_time_channel(&time_ch)?;
+            writer.set_channel_unit(&time_ch, "s")?;
+            let mut prev = time_ch;
+            for (name, bits) in [("CAN_ID", 32), ("Response", 8), ("ServiceID", 8)] {
+                prev = writer.add_channel(&cg, Some(&prev), |ch| {
+                    ch.data_type = DataType::UnsignedIntegerLE;
+                    ch.name = Some(String::from(name));
+                    ch.bit_count = bits;
+                })?;
+            }
+            writer.add_vlsd_channel(&cg, Some(&prev), |ch| {
+                ch.data_type = DataType::ByteArray;
+                ch.name = Some(String::from("Payload"));
+            })?;
+
+            writer.start_data_block_for_cg(&cg, 0)?;
+            for message in self.messages {
+                let values = [
+                    DecodedValue::Float(timestamp_to_seconds(message.timestamp_us)),
+                    DecodedValue::UnsignedInteger(message.can_id as u64),
+                    DecodedValue::UnsignedInteger(message.is_response() as u64),
+                    DecodedValue::UnsignedInteger(message.service_id().unwrap_or(0) as u64),
+                    DecodedValue::ByteArray(message.data),
+                ];
+                writer.write_record(&cg, &values)?;
+            }
+            writer.finish_data_block(&cg)?;
+        }
+        writer.finalize()?;
+        Ok(writer.into_inner().into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_frames() {
+        let mut tp = IsoTpReassembler::new();
+        // ReadDataByIdentifier 0xF190 (VIN)
+        let message = tp
+            .process(0x7E0, 100, &[0x03, 0x22, 0xF1, 0x90, 0, 0, 0, 0])
+            .unwrap();
+        assert_eq!(message.data, [0x22, 0xF1, 0x90]);
+        assert_eq!(message.service_id(), Some(0x22));
+        assert!(!message.is_response());
+
+        // CAN FD single frame with escaped length
+        let mut fd = alloc::vec![0x00, 10, 0x62];
+        fd.extend_from_slice(&[0xAA; 9]);
+        let message = tp.process(0x7E8, 200, &fd).unwrap();
+        assert_eq!(message.data.len(), 10);
+        assert!(message.is_response());
+
+        // Negative response
+        let message = tp.process(0x7E8, 300, &[0x03, 0x7F, 0x22, 0x31]).unwrap();
+        assert_eq!(message.service_id(), Some(UDS_NEGATIVE_RESPONSE));
+        assert!(message.is_response());
+
+        // Flow control
+        assert!(
+            tp.process(0x7E0, 400, &[0x30, 0, 0, 0, 0, 0, 0, 0])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_segmented_transfer() {
+        let mut tp = IsoTpReassembler::new();
+        // 20-byte VIN response: FF with 6 bytes, then 2 CFs with 7 bytes each
+        let ff = [0x10, 20, 0x62, 0xF1, 0x90, b'W', b'D', b'B'];
+        assert!(tp.process(0x7E8, 1000, &ff).is_none());
+        assert!(
+            tp.process(0x7E0, 1100, &[0x30, 0, 0, 0, 0, 0, 0, 0])
+                .is_none()
+        );
+        let cf1 = [0x21, b'1', b'2', b'3', b'4', b'5', b'6', b'7'];
+        assert!(tp.process(0x7E8, 1200, &cf1).is_none());
+        assert_eq!(tp.pending_transfers(), 1);
+        let cf2 = [0x22, b'8', b'9', b'0', b'1', b'2', b'3', b'4'];
+        let message = tp.process(0x7E8, 1300, &cf2).unwrap();
+
+        assert_eq!(message.timestamp_us, 1300);
+        assert_eq!(message.data.len(), 20);
+        assert_eq!(&message.data[..3], [0x62, 0xF1, 0x90]);
+        assert_eq!(&message.data[3..], b"WDB12345678901234");
+        assert_eq!(tp.pending_transfers(), 0);
+    }
+
+    #[test]
+    fn test_dropped_transfers() {
+        let mut tp = IsoTpReassembler::with_timeout(1000);
+        let ff = [0x10, 20, 1, 2, 3, 4, 5, 6];
+
+        // Out of sequence
+        tp.process(0x7E8, 0, &ff);
+        assert!(
+            tp.process(0x7E8, 100, &[0x22, 0, 0, 0, 0, 0, 0, 0])
+                .is_none()
+        );
+        assert_eq!(tp.dropped_transfers(), 1);
+
+        // Timeout
+        tp.process(0x7E8, 200, &ff);
+        assert!(
+            tp.process(0x7E8, 5000, &[0x21, 0, 0, 0, 0, 0, 0, 0])
+                .is_none()
+        );
+        assert_eq!(tp.dropped_transfers(), 2);
+
+        // Interrupted by a new first frame
+        tp.process(0x7E8, 6000, &ff);
+        tp.process(0x7E8, 6100, &ff);
+        assert_eq!(tp.dropped_transfers(), 3);
+        assert_eq!(tp.pending_transfers(), 1);
+    }
+
+    #[test]
+    fn test_oversized_first_frame() {
+        let mut tp = IsoTpReassembler::new();
+
+        // Escaped first frame announcing 4 GiB
+        let ff = [0x10, 0x00, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert!(tp.process(0x7E8, 0, &ff).is_none());
+        assert_eq!(tp.pending_transfers(), 0);
+        assert_eq!(tp.dropped_transfers(), 1);
+
+        // Escaped first frame within a custom limit
+        let mut tp = IsoTpReassembler::new().with_max_message_size(5000);
+        let ff = [0x10, 0x00, 0x00, 0x00, 0x13, 0x88, 0x62, 0xF1];
+        assert!(tp.process(0x7E8, 0, &ff).is_none());
+        assert_eq!(tp.pending_transfers(), 1);
+        assert_eq!(tp.dropped_transfers(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_uds_logger_round_trip() {
+        let mut logger = UdsLogger::new();
+        assert!(logger.log(0x7E0, 1_000_000, &[0x03, 0x22, 0xF1, 0x90, 0, 0, 0, 0]));
+        assert!(!logger.log(0x7E8, 1_010_000, &[0x10, 11, 0x62, 0xF1, 0x90, 1, 2, 3]));
+        assert!(logger.log(0x7E8, 1_020_000, &[0x21, 4, 5, 6, 7, 8, 0xAA, 0xAA]));
+        assert_eq!(logger.message_count(), 2);
+        let bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&bytes).is_ok());
+
+        let temp_path = std::env::temp_dir().join("test_uds_logger.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups[0].name().unwrap().as_deref(), Some("UDS"));
+        let channels = groups[0].channels();
+        use crate::DecodedValue::{ByteArray, UnsignedInteger};
+        assert_eq!(
+            channels[2].values().unwrap(),
+            [Some(UnsignedInteger(0)), Some(UnsignedInteger(1))]
+        );
+        assert_eq!(
+            channels[3].values().unwrap(),
+            [Some(UnsignedInteger(0x22)), Some(UnsignedInteger(0x62))]
+        );
+        assert_eq!(
+            channels[4].values().unwrap(),
+            [
+                Some(ByteArray(alloc::vec![0x22, 0xF1, 0x90])),
+                Some(ByteArray(alloc::vec![
+                    0x62, 0xF1, 0x90, 1, 2, 3, 4, 5, 6, 7, 8
+                ])),
+            ]
+        );
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+}
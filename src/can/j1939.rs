@@ -0,0 +1,682 @@
+//! SAE J1939 support: identifiers, transport protocol and logging.
+//!
+//! J1939 networks use 29-bit CAN IDs that carry a priority, a Parameter Group
+//! Number (PGN) and source and destination addresses. Messages longer than 8
+//! bytes are split by the transport protocol into a connection management
+//! frame (TP.CM, PGN 0xEC00) followed by data transfer frames (TP.DT, PGN
+//! 0xEB00), either broadcast (BAM) or peer-to-peer (RTS/CTS).
+//!
+//! - [`J1939Id`] splits 29-bit IDs into their fields
+//! - [`TransportReassembler`] turns a frame stream into complete
+//!   [`J1939Message`]s
+//! - [`J1939Logger`] logs the payload of every PGN to its own channel group
+//! - [`J1939DbcLogger`] (`dbc` feature) decodes the parameters of complete
+//!   messages with a J1939 DBC, matching messages by PGN
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::can::j1939::J1939Logger;
+//!
+//! let mut logger = J1939Logger::new()?;
+//! for (timestamp_us, can_id, data) in frames {
+//!     logger.log(can_id, timestamp_us, &data);
+//! }
+//! let mdf_bytes = logger.finalize()?;
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::bus_logging::timestamp_to_seconds;
+
+/// PGN of transport protocol connection management frames (TP.CM).
+pub const PGN_TP_CM: u32 = 0xEC00;
+
+/// PGN of transport protocol data transfer frames (TP.DT).
+pub const PGN_TP_DT: u32 = 0xEB00;
+
+/// Destination address of broadcast messages.
+pub const GLOBAL_ADDRESS: u8 = 0xFF;
+
+/// Largest message the transport protocol can carry (255 packets of 7 bytes).
+pub const MAX_TP_MESSAGE_SIZE: usize = 1785;
+
+/// Default time allowed between two packets of a transfer (T1 of J1939-21).
+pub const DEFAULT_TP_TIMEOUT_US: u64 = 750_000;
+
+/// TP.CM control byte: request to send.
+const TP_CM_RTS: u8 = 16;
+/// TP.CM control byte: broadcast announce message.
+const TP_CM_BAM: u8 = 32;
+/// TP.CM control byte: connection abort.
+const TP_CM_ABORT: u8 = 255;
+
+/// Fields of a 29-bit J1939 CAN ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct J1939Id {
+    /// Priority (0 = highest, 7 = lowest)
+    pub priority: u8,
+    /// Parameter Group Number; the PDU specific byte is zero for PDU1 PGNs
+    pub pgn: u32,
+    /// Address of the sender
+    pub source_address: u8,
+    /// Address of the receiver, [`GLOBAL_ADDRESS`] for PDU2 (broadcast)
+    /// PGNs
+    pub destination_address: u8,
+}
+
+impl J1939Id {
+    /// Split a 29-bit CAN ID.
+    ///
+    /// For PDU1 formats (PDU format below 240) the PDU specific byte is the
+    /// destination address; for PDU2 formats it is part of the PGN.
+    pub fn from_can_id(can_id: u32) -> Self {
+        let can_id = can_id & 0x1FFF_FFFF;
+        let priority = (can_id >> 26) as u8 & 0x07;
+        let pdu_format = (can_id >> 16) as u8;
+        let pdu_specific = (can_id >> 8) as u8;
+        let source_address = can_id as u8;
+        let (pgn, destination_address) = if pdu_format < 240 {
+            ((can_id >> 8) & 0x3FF00, pdu_specific)
+        } else {
+            ((can_id >> 8) & 0x3FFFF, GLOBAL_ADDRESS)
+        };
+        Self {
+            priority,
+            pgn,
+            source_address,
+            destination_address,
+        }
+    }
+
+    /// Build the 29-bit CAN ID.
+    pub fn to_can_id(&self) -> u32 {
+        let pdu_specific = if self.is_pdu1() {
+            self.destination_address as u32
+        } else {
+            self.pgn & 0xFF
+        };
+        ((self.priority as u32 & 0x07) << 26)
+            | ((self.pgn & 0x3FF00) << 8)
+            | (pdu_specific << 8)
+            | self.source_address as u32
+    }
+
+    /// Returns `true` for PDU1 (destination specific) PGNs.
+    pub fn is_pdu1(&self) -> bool {
+        ((self.pgn >> 8) as u8) < 240
+    }
+}
+
+/// A complete J1939 message, either a single frame or reassembled from a
+/// transport protocol transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct J1939Message {
+    /// Timestamp in microseconds of the last frame of the message
+    pub timestamp_us: u64,
+    /// Priority of the (first) frame
+    pub priority: u8,
+    /// Parameter Group Number
+    pub pgn: u32,
+    /// Address of the sender
+    pub source_address: u8,
+    /// Address of the receiver, [`GLOBAL_ADDRESS`] for broadcasts
+    pub destination_address: u8,
+    /// Message payload
+    pub data: Vec<u8>,
+}
+
+impl J1939Message {
+    /// The CAN ID of a single frame carrying this message.
+    pub fn can_id(&self) -> u32 {
+        J1939Id {
+            priority: self.priority,
+            pgn: self.pgn,
+            source_address: self.source_address,
+            destination_address: self.destination_address,
+        }
+        .to_can_id()
+    }
+}
+
+/// A transfer in progress.
+struct Session {
+    priority: u8,
+    pgn: u32,
+    size: usize,
+    packets: u8,
+    next_sequence: u8,
+    last_timestamp_us: u64,
+    data: Vec<u8>,
+}
+
+/// Reassembles J1939 transport protocol transfers.
+///
+/// Feed every received frame to [`process()`](Self::process). Single-frame
+/// messages are returned right away, transfers once their last TP.DT packet
+/// arrives. Transfers are keyed by source and destination address, so
+/// concurrent transfers of different nodes are reassembled independently.
+///
+/// A transfer is dropped when a packet is out of sequence, when packets are
+/// further apart than the timeout, when it is aborted, or when the sender
+/// announces a new transfer.
+pub struct TransportReassembler {
+    sessions: BTreeMap<(u8, u8), Session>,
+    timeout_us: u64,
+    dropped: usize,
+}
+
+impl Default for TransportReassembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportReassembler {
+    /// Create a reassembler with the default timeout of 750 ms.
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_TP_TIMEOUT_US)
+    }
+
+    /// Create a reassembler that drops transfers whose packets are more than
+    /// `timeout_us` microseconds apart.
+    pub fn with_timeout(timeout_us: u64) -> Self {
+        Self {
+            sessions: BTreeMap::new(),
+            timeout_us,
+            dropped: 0,
+        }
+    }
+
+    /// Number of transfers in progress.
+    pub fn pending_transfers(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Number of transfers dropped as incomplete.
+    pub fn dropped_transfers(&self) -> usize {
+        self.dropped
+    }
+
+    /// Process a frame with a 29-bit CAN ID.
+    ///
+    /// # Returns
+    /// The complete message this frame finishes, if any. Transport protocol
+    /// frames themselves are not returned.
+    pub fn process(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> Option<J1939Message> {
+        let id = J1939Id::from_can_id(can_id);
+        let key = (id.source_address, id.destination_address);
+        match id.pgn {
+            PGN_TP_CM => {
+                self.connection_management(id, key, timestamp_us, data);
+                None
+            }
+            PGN_TP_DT => self.data_transfer(key, timestamp_us, data),
+            _ => Some(J1939Message {
+                timestamp_us,
+                priority: id.priority,
+                pgn: id.pgn,
+                source_address: id.source_address,
+                destination_address: id.destination_address,
+                data: data.to_vec(),
+            }),
+        }
+    }
+
+    fn connection_management(
+        &mut self,
+        id: J1939Id,
+        key: (u8, u8),
+        timestamp_us: u64,
+        data: &[u8],
+    ) {
+        if data.len() < 8 {
+            return;
+        }
+        match data[0] {
+            TP_CM_BAM | TP_CM_RTS => {
+                let size = u16::from_le_bytes([data[1], data[2]]) as usize;
+                let packets = data[3];
+                let pgn = u32::from_le_bytes([data[5], data[6], data[7], 0]);
+                if size > MAX_TP_MESSAGE_SIZE || (packets as usize) * 7 < size {
+                    return;
+                }
+                let session = Session {
+                    priority: id.priority,
+                    pgn,
+                    size,
+                    packets,
+                    next_sequence: 1,
+                    last_timestamp_us: timestamp_us,
+                    data: Vec::with_capacity(packets as usize * 7),
+                };
+                if self.sessions.insert(key, session).is_some() {
+                    self.dropped += 1;
+                }
+            }
+            TP_CM_ABORT => {
+                // The abort may come from either end of the connection
+                let reverse = (key.1, key.0);
+                if self.sessions.remove(&key).is_some() || self.sessions.remove(&reverse).is_some()
+                {
+                    self.dropped += 1;
+                }
+            }
+            // CTS and end of message acknowledgments are sent by the receiver
+            _ => {}
+        }
+    }
+
+    fn data_transfer(
+        &mut self,
+        key: (u8, u8),
+        timestamp_us: u64,
+        data: &[u8],
+    ) -> Option<J1939Message> {
+        let session = self.sessions.get_mut(&key)?;
+        let in_time = timestamp_us.saturating_sub(session.last_timestamp_us) <= self.timeout_us;
+        if data.is_empty() || data[0] != session.next_sequence || !in_time {
+            self.sessions.remove(&key);
+            self.dropped += 1;
+            return None;
+        }
+        session.data.extend_from_slice(&data[1..data.len().min(8)]);
+        session.next_sequence = session.next_sequence.wrapping_add(1);
+        session.last_timestamp_us = timestamp_us;
+        if data[0] < session.packets {
+            return None;
+        }
+
+        let mut session = self.sessions.remove(&key)?;
+        if session.data.len() < session.size {
+            self.dropped += 1;
+            return None;
+        }
+        session.data.truncate(session.size);
+        Some(J1939Message {
+            timestamp_us,
+            priority: session.priority,
+            pgn: session.pgn,
+            source_address: key.0,
+            destination_address: key.1,
+            data: session.data,
+        })
+    }
+}
+
+/// Logs J1939 messages without a DBC.
+///
+/// Transport protocol transfers are reassembled, and every PGN gets a
+/// channel group `PGN_<pgn>` (decimal) with the channels:
+/// - `Timestamp` - Float64 seconds (master)
+/// - `Priority`, `SourceAddress`, `DestinationAddress` - UInt8
+/// - `DataLength` - UInt16 payload length
+/// - `Data` - ByteArray payload, zero-padded to the longest message of the
+///   PGN
+///
+/// Messages are buffered in memory and written by
+/// [`finalize()`](Self::finalize).
+pub struct J1939Logger {
+    reassembler: TransportReassembler,
+    /// Buffered messages by PGN
+    messages: BTreeMap<u32, Vec<J1939Message>>,
+}
+
+impl J1939Logger {
+    /// Create a logger with the default transport protocol timeout.
+    pub fn new() -> crate::Result<Self> {
+        Ok(Self::with_reassembler(TransportReassembler::new()))
+    }
+
+    /// Create a logger using the given reassembler, e.g. with a custom
+    /// timeout.
+    pub fn with_reassembler(reassembler: TransportReassembler) -> Self {
+        Self {
+            reassembler,
+            messages: BTreeMap::new(),
+        }
+    }
+
+    /// Log a frame with a 29-bit CAN ID.
+    ///
+    /// # Returns
+    /// `true` if the frame completed a message.
+    pub fn log(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        match self.reassembler.process(can_id, timestamp_us, data) {
+            Some(message) => {
+                self.log_message(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Log a complete message.
+    pub fn log_message(&mut self, message: J1939Message) {
+        self.messages.entry(message.pgn).or_default().push(message);
+    }
+
+    /// Number of messages logged for a PGN.
+    pub fn message_count(&self, pgn: u32) -> usize {
+        self.messages.get(&pgn).map_or(0, Vec::len)
+    }
+
+    /// Total number of messages logged.
+    pub fn total_message_count(&self) -> usize {
+        self.messages.values().map(Vec::len).sum()
+    }
+
+    /// The transport protocol reassembler.
+    pub fn reassembler(&self) -> &TransportReassembler {
+        &self.reassembler
+    }
+
+    /// Write all messages and return the MDF bytes.
+    pub fn finalize(self) -> crate::Result<Vec<u8>> {
+        use crate::{DataType, DecodedValue};
+
+        let mut writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
+        writer.init_mdf_file()?;
+        for (pgn, messages) in &self.messages {
+            let data_len = messages.iter().map(|m| m.data.len()).max().unwrap_or(0);
+            let cg = writer.add_channel_group(None, |_| {})?;
+            writer.set_channel_group_name(&cg, &alloc::format!("PGN_{}", pgn))?;
+            let source = crate::blocks::SourceBlock::can_bus();
+            writer.set_channel_group_source(&cg, &source, Some("J1939"))?;
+
+            let time_ch = writer.add_channel(&cg, None, |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.name = Some(String::from("Timestamp"));
+                ch.bit_count = 64;
+            })?;
+            writer.set_time_channel(&time_ch)?;
+            writer.set_channel_unit(&time_ch, "s")?;
+            let mut prev = time_ch;
+            for (name, bits) in [
+                ("Priority", 8),
+                ("SourceAddress", 8),
+                ("DestinationAddress", 8),
+                ("DataLength", 16),
+            ] {
+                prev = writer.add_channel(&cg, Some(&prev), |ch| {
+                    ch.data_type = DataType::UnsignedIntegerLE;
+                    ch.name = Some(String::from(name));
+                    ch.bit_count = bits;
+                })?;
+            }
+            if data_len > 0 {
+                writer.add_channel(&cg, Some(&prev), |ch| {
+                    ch.data_type = DataType::ByteArray;
+                    ch.name = Some(String::from("Data"));
+                    ch.bit_count = (data_len * 8) as u32;
+                })?;
+            }
+
+            writer.start_data_block_for_cg(&cg, 0)?;
+            for message in messages {
+                let mut values = alloc::vec![
+                    DecodedValue::Float(timestamp_to_seconds(message.timestamp_us)),
+                    DecodedValue::UnsignedInteger(message.priority as u64),
+                    DecodedValue::UnsignedInteger(message.source_address as u64),
+                    DecodedValue::UnsignedInteger(message.destination_address as u64),
+                    DecodedValue::UnsignedInteger(message.data.len() as u64),
+                ];
+                if data_len > 0 {
+                    let mut data = message.data.clone();
+                    data.resize(data_len, 0);
+                    values.push(DecodedValue::ByteArray(data));
+                }
+                writer.write_record(&cg, &values)?;
+            }
+            writer.finish_data_block(&cg)?;
+        }
+        writer.finalize()?;
+        Ok(writer.into_inner().into_inner())
+    }
+}
+
+/// Logs decoded J1939 parameters with a J1939 DBC.
+///
+/// Transport protocol transfers are reassembled before decoding, so
+/// multi-packet PGNs such as DM1 decode like single frames. A message is
+/// decoded with the DBC message of its exact CAN ID if there is one, and
+/// otherwise with the first DBC message of the same PGN, since J1939 DBCs
+/// usually list each PGN once with an arbitrary source address.
+#[cfg(all(feature = "std", feature = "dbc"))]
+pub struct J1939DbcLogger {
+    reassembler: TransportReassembler,
+    logger: super::CanDbcLogger<crate::writer::VecWriter>,
+    /// DBC message ID (without extended flag) by PGN
+    pgn_ids: BTreeMap<u32, u32>,
+}
+
+#[cfg(all(feature = "std", feature = "dbc"))]
+impl J1939DbcLogger {
+    /// Create a logger decoding with `dbc`.
+    pub fn new(dbc: dbc_rs::Dbc) -> crate::Result<Self> {
+        let mut pgn_ids = BTreeMap::new();
+        for message in dbc.messages().iter() {
+            let id = message.id();
+            if id & 0x8000_0000 != 0 {
+                let raw_id = id & 0x1FFF_FFFF;
+                pgn_ids
+                    .entry(J1939Id::from_can_id(raw_id).pgn)
+                    .or_insert(raw_id);
+            }
+        }
+        Ok(Self {
+            reassembler: TransportReassembler::new(),
+            logger: super::CanDbcLogger::new(dbc)?,
+            pgn_ids,
+        })
+    }
+
+    /// Log a frame with a 29-bit CAN ID.
+    ///
+    /// # Returns
+    /// `true` if the frame completed a message that was decoded.
+    pub fn log(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        match self.reassembler.process(can_id, timestamp_us, data) {
+            Some(message) => self.log_message(&message),
+            None => false,
+        }
+    }
+
+    /// Decode and log a complete message.
+    ///
+    /// # Returns
+    /// `true` if the DBC has a message for its CAN ID or PGN and decoding
+    /// succeeded.
+    pub fn log_message(&mut self, message: &J1939Message) -> bool {
+        let can_id = message.can_id();
+        if self
+            .logger
+            .log_extended(can_id, message.timestamp_us, &message.data)
+        {
+            return true;
+        }
+        match self.pgn_ids.get(&message.pgn) {
+            Some(&dbc_id) if dbc_id != can_id => {
+                self.logger
+                    .log_extended(dbc_id, message.timestamp_us, &message.data)
+            }
+            _ => false,
+        }
+    }
+
+    /// The transport protocol reassembler.
+    pub fn reassembler(&self) -> &TransportReassembler {
+        &self.reassembler
+    }
+
+    /// Finalize the MDF file and return the bytes.
+    pub fn finalize(self) -> crate::Result<Vec<u8>> {
+        self.logger.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CAN ID of a frame with priority 7 from `source` to `destination`.
+    fn tp_id(pgn: u32, source: u8, destination: u8) -> u32 {
+        J1939Id {
+            priority: 7,
+            pgn,
+            source_address: source,
+            destination_address: destination,
+        }
+        .to_can_id()
+    }
+
+    #[test]
+    fn test_j1939_id() {
+        // EEC1 (PGN 61444) from the engine, priority 3
+        let id = J1939Id::from_can_id(0x0CF00400);
+        assert_eq!(id.priority, 3);
+        assert_eq!(id.pgn, 61444);
+        assert_eq!(id.source_address, 0x00);
+        assert_eq!(id.destination_address, GLOBAL_ADDRESS);
+        assert!(!id.is_pdu1());
+        assert_eq!(id.to_can_id(), 0x0CF00400);
+
+        // Request (PGN 59904) from 0xF9 to 0x00
+        let id = J1939Id::from_can_id(0x18EA00F9);
+        assert_eq!(id.pgn, 59904);
+        assert_eq!(id.source_address, 0xF9);
+        assert_eq!(id.destination_address, 0x00);
+        assert!(id.is_pdu1());
+        assert_eq!(id.to_can_id(), 0x18EA00F9);
+    }
+
+    #[test]
+    fn test_bam_reassembly() {
+        let mut tp = TransportReassembler::new();
+        // DM1 (PGN 65226) of 10 bytes in 2 packets
+        let cm = [TP_CM_BAM, 10, 0, 2, 0xFF, 0xCA, 0xFE, 0x00];
+        assert!(
+            tp.process(tp_id(PGN_TP_CM, 0x00, 0xFF), 1000, &cm)
+                .is_none()
+        );
+        assert_eq!(tp.pending_transfers(), 1);
+        let dt1 = [1, 1, 2, 3, 4, 5, 6, 7];
+        assert!(
+            tp.process(tp_id(PGN_TP_DT, 0x00, 0xFF), 2000, &dt1)
+                .is_none()
+        );
+        let dt2 = [2, 8, 9, 10, 0xFF, 0xFF, 0xFF, 0xFF];
+        let message = tp
+            .process(tp_id(PGN_TP_DT, 0x00, 0xFF), 3000, &dt2)
+            .unwrap();
+
+        assert_eq!(message.pgn, 65226);
+        assert_eq!(message.timestamp_us, 3000);
+        assert_eq!(message.source_address, 0x00);
+        assert_eq!(message.destination_address, GLOBAL_ADDRESS);
+        assert_eq!(message.data, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(tp.pending_transfers(), 0);
+    }
+
+    #[test]
+    fn test_rts_reassembly_and_errors() {
+        let mut tp = TransportReassembler::with_timeout(1000);
+        let rts = [TP_CM_RTS, 9, 0, 2, 0xFF, 0x00, 0xEF, 0x00];
+        tp.process(tp_id(PGN_TP_CM, 0x21, 0x00), 0, &rts);
+        // The receiver's CTS does not disturb the transfer
+        let cts = [17, 2, 1, 0xFF, 0xFF, 0x00, 0xEF, 0x00];
+        tp.process(tp_id(PGN_TP_CM, 0x00, 0x21), 100, &cts);
+        tp.process(tp_id(PGN_TP_DT, 0x21, 0x00), 200, &[1, 1, 2, 3, 4, 5, 6, 7]);
+        let message = tp
+            .process(tp_id(PGN_TP_DT, 0x21, 0x00), 300, &[2, 8, 9, 0, 0, 0, 0, 0])
+            .unwrap();
+        assert_eq!(message.pgn, 0xEF00);
+        assert_eq!(message.destination_address, 0x00);
+        assert_eq!(message.data.len(), 9);
+
+        // Out of sequence
+        tp.process(tp_id(PGN_TP_CM, 0x21, 0x00), 400, &rts);
+        assert!(
+            tp.process(tp_id(PGN_TP_DT, 0x21, 0x00), 500, &[2; 8])
+                .is_none()
+        );
+        assert_eq!(tp.dropped_transfers(), 1);
+
+        // Timeout
+        tp.process(tp_id(PGN_TP_CM, 0x21, 0x00), 600, &rts);
+        assert!(
+            tp.process(tp_id(PGN_TP_DT, 0x21, 0x00), 5000, &[1; 8])
+                .is_none()
+        );
+        assert_eq!(tp.dropped_transfers(), 2);
+
+        // Abort by the receiver
+        tp.process(tp_id(PGN_TP_CM, 0x21, 0x00), 6000, &rts);
+        let abort = [TP_CM_ABORT, 1, 0xFF, 0xFF, 0xFF, 0x00, 0xEF, 0x00];
+        tp.process(tp_id(PGN_TP_CM, 0x00, 0x21), 6100, &abort);
+        assert_eq!(tp.pending_transfers(), 0);
+        assert_eq!(tp.dropped_transfers(), 3);
+    }
+
+    #[test]
+    fn test_logger() {
+        let mut logger = J1939Logger::new().unwrap();
+        assert!(logger.log(0x0CF00400, 1000, &[0; 8]));
+        assert!(logger.log(0x0CF00400, 2000, &[1; 8]));
+        let cm = [TP_CM_BAM, 10, 0, 2, 0xFF, 0xCA, 0xFE, 0x00];
+        assert!(!logger.log(tp_id(PGN_TP_CM, 0x00, 0xFF), 3000, &cm));
+        assert!(!logger.log(tp_id(PGN_TP_DT, 0x00, 0xFF), 4000, &[1; 8]));
+        assert!(logger.log(tp_id(PGN_TP_DT, 0x00, 0xFF), 5000, &[2; 8]));
+
+        assert_eq!(logger.message_count(61444), 2);
+        assert_eq!(logger.message_count(65226), 1);
+        assert_eq!(logger.total_message_count(), 3);
+
+        let bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&bytes).is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_logger_round_trip() {
+        let mut logger = J1939Logger::new().unwrap();
+        logger.log(0x0CF00400, 1_000_000, &[0x11; 8]);
+        let cm = [TP_CM_BAM, 10, 0, 2, 0xFF, 0xCA, 0xFE, 0x00];
+        logger.log(tp_id(PGN_TP_CM, 0x03, 0xFF), 2_000_000, &cm);
+        logger.log(
+            tp_id(PGN_TP_DT, 0x03, 0xFF),
+            2_010_000,
+            &[1, 1, 2, 3, 4, 5, 6, 7],
+        );
+        logger.log(
+            tp_id(PGN_TP_DT, 0x03, 0xFF),
+            2_020_000,
+            &[2, 8, 9, 10, 0, 0, 0, 0],
+        );
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("test_j1939_logger.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name().unwrap().as_deref(), Some("PGN_61444"));
+        assert_eq!(groups[1].name().unwrap().as_deref(), Some("PGN_65226"));
+
+        let dm1 = groups[1].channels();
+        assert_eq!(
+            dm1[2].values().unwrap(),
+            [Some(crate::DecodedValue::UnsignedInteger(0x03))]
+        );
+        assert_eq!(
+            dm1[5].values().unwrap(),
+            [Some(crate::DecodedValue::ByteArray(alloc::vec![
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10
+            ]))]
+        );
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+}
@@ -6,6 +6,17 @@
 //! 1. **With DBC**: Use [`CanDbcLogger`] for full signal decoding with metadata
 //! 2. **Without DBC**: Use [`RawCanLogger`] for raw frame capture
 //! 3. **Post-processing**: Use [`DbcOverlayReader`] to decode raw captures with DBC
+//! 4. **Import**: Use [`AscReader`] to convert Vector ASC logs
+//! 5. **J1939**: Use [`j1939`] to reassemble transport protocol messages and
+//!    log them per PGN
+//! 6. **Diagnostics**: Use [`isotp`] to reassemble ISO-TP payloads and log
+//!    UDS sessions
+//! 7. **Replay**: Use [`replay`] to stream frames of a capture back out in
+//!    time order
+//! 8. **SocketCAN**: Use `socketcan` (feature `socketcan`) to log Linux
+//!    SocketCAN frames and capture interfaces
+//! 9. **E2E checks**: Use [`e2e`] to validate rolling counters and AUTOSAR
+//!    E2E checksums while logging with [`CanDbcLogger`]
 //!
 //! # Features
 //!
@@ -17,6 +28,10 @@
 //! - Full metadata preservation (units, conversions, limits)
 //! - Raw value storage with conversion blocks for maximum precision
 //! - **CAN FD support**: Up to 64 bytes per frame with BRS/ESI flags
+//! - **CAN XL support**: Up to 2048 bytes per frame with SDU type and
+//!   acceptance field, see [`xl`]
+//! - Error and remote frames in the ASAM `CAN_ErrorFrame` and
+//!   `CAN_RemoteFrame` channel groups
 //!
 //! # Example with DBC
 //!
@@ -53,6 +68,10 @@
 //! let mdf_bytes = logger.finalize()?;
 //! ```
 
+pub mod analysis;
+#[cfg(feature = "std")]
+mod asc;
+mod bus_statistics;
 // CanDbcLogger uses FastDbc which requires std + dbc
 #[cfg(all(feature = "std", feature = "dbc"))]
 mod dbc_compat;
@@ -60,21 +79,41 @@ mod dbc_compat;
 mod dbc_logger;
 #[cfg(all(feature = "std", feature = "dbc"))]
 mod dbc_overlay;
+mod dbc_text;
+pub mod e2e;
 pub mod fd;
+pub mod isotp;
+pub mod j1939;
 mod raw_logger;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "socketcan")]
+pub mod socketcan;
 mod timestamped_frame;
+pub mod xl;
 
 #[cfg(all(feature = "std", feature = "dbc"))]
-pub use dbc_logger::{CanDbcLogger, CanDbcLoggerBuilder, CanDbcLoggerConfig};
+pub use asc::asc_to_mdf_with_dbc;
+#[cfg(feature = "std")]
+pub use asc::{AscFrame, AscReader, asc_to_mdf};
 #[cfg(all(feature = "std", feature = "dbc"))]
-pub use dbc_overlay::{DbcOverlayReader, DecodedFrame, OverlayStatistics, SignalValue};
+pub use dbc_logger::{
+    BufferStats, CanDbcLogger, CanDbcLoggerBuilder, CanDbcLoggerConfig, TimestampFormat,
+};
+#[cfg(all(feature = "std", feature = "dbc"))]
+pub use dbc_overlay::{
+    DbcOverlayReader, DecodedFrame, FrameIter, OverlayStatistics, RawFrameIter, SignalValue,
+    SignalValueIter,
+};
+pub use e2e::{E2eCheck, E2eProfile, E2eStatus};
 // FD constants and flags are always available
 pub use fd::{FdFlags, MAX_FD_DATA_LEN, dlc_to_len, len_to_dlc};
 // FD frame trait and implementation require embedded_can
 #[cfg(feature = "can")]
 pub use fd::{FdFrame, SimpleFdFrame};
-pub use raw_logger::RawCanLogger;
+pub use raw_logger::{CanDirection, CanErrorType, CanFrameMeta, RawCanLogger};
 pub use timestamped_frame::TimestampedFrame;
+pub use xl::{MAX_XL_DATA_LEN, XlFrame};
 
 // Re-export commonly used dbc-rs types (requires dbc feature)
 #[cfg(feature = "dbc")]
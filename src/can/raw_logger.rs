@@ -6,10 +6,19 @@
 //! # Features
 //!
 //! - ASAM MDF4 Bus Logging compliant format
-//! - `CAN_DataFrame` channel with composite ByteArray (ID + DLC + Data)
+//! - `CAN_DataFrame` channel with composite ByteArray (ID + DLC + Data) and
+//!   composition members (`CAN_DataFrame.ID`, `.IDE`, `.Dir`, `.DLC`,
+//!   `.DataLength`, `.DataBytes`, `.BusChannel`, and `.BRS`/`.ESI` for CAN
+//!   FD) for viewers
+//! - Frame direction (Tx/Rx) and bus channel number per frame
 //! - Timestamp as Float64 in seconds
 //! - Supports both Standard (11-bit) and Extended (29-bit) CAN IDs
 //! - CAN FD support with BRS/ESI flags
+//! - `CAN_ErrorFrame` channel group for bus errors
+//! - `CAN_RemoteFrame` channel group for remote transmission requests
+//! - `CAN_XLFrame` channel group for CAN XL frames with up to 2048 bytes
+//! - Optional `BusStatistics` channel group with bus load, frame rate and
+//!   error counts per second
 //! - Source metadata (CAN bus name/path)
 //! - Compatible with Vector CANalyzer, PEAK tools, CSS Electronics, etc.
 //!
@@ -26,10 +35,22 @@
 //! // Log extended 29-bit ID frame
 //! logger.log_extended(0x18FEF100, timestamp_us, &data);
 //!
+//! // Log a transmitted frame, or set direction and bus channel explicitly
+//! use mdf4_rs::can::CanFrameMeta;
+//! logger.log_tx(0x101, timestamp_us, &data);
+//! logger.log_with_meta(0x102, timestamp_us, &data, CanFrameMeta::rx().with_bus_channel(2));
+//!
 //! // Log CAN FD frame with flags
 //! use mdf4_rs::can::FdFlags;
 //! logger.log_fd(0x200, timestamp_us, &fd_data, FdFlags::new(true, false));
 //!
+//! // Log a bus error reported by the interface
+//! use mdf4_rs::can::CanErrorType;
+//! logger.log_error_frame(timestamp_us, CanErrorType::Crc, 0);
+//!
+//! // Log a remote frame requesting 8 bytes
+//! logger.log_remote(0x300, timestamp_us, 8, false);
+//!
 //! // Get MDF bytes
 //! let mdf_bytes = logger.finalize()?;
 //! ```
@@ -37,14 +58,183 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 
+use super::bus_statistics::{BusStatistics, frame_bits, xl_frame_bits};
 #[cfg(feature = "can")]
 use super::fd::FdFrame;
 use super::fd::{FdFlags, MAX_FD_DATA_LEN};
-use crate::bus_logging::timestamp_to_seconds;
+use super::xl::XlFrame;
+use crate::blocks::SourceBlock;
+use crate::bus_logging::{
+    BusChannel, BusFrame, BusLoggerConfig, BusLoggerCore, TimestampedFrame, init_bus_channel_group,
+    timestamp_to_seconds,
+};
+
+/// CAN_ErrorFrame size in bytes: ErrorType(1) + Flags(1).
+const ERROR_FRAME_SIZE: usize = 2;
+
+/// CAN_RemoteFrame size in bytes: ID(4) + DLC(1).
+const REMOTE_FRAME_SIZE: usize = 5;
+
+/// CAN_XLFrame size in bytes: PriorityID(2) + Flags(1) + SDT(1) + VCID(1) +
+/// reserved(1) + DataLength(2) + AF(4).
+const XL_FRAME_SIZE: usize = 12;
+
+/// Kind of CAN bus error, numbered as the `ErrorType` of the ASAM
+/// `CAN_ErrorFrame`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CanErrorType {
+    /// Error of unknown kind
+    #[default]
+    Unknown = 0,
+    /// Bit error: the bus level differs from the transmitted bit
+    Bit = 1,
+    /// Form error: a fixed-form bit field holds an illegal bit
+    Form = 2,
+    /// Stuff error: more than five equal bits in a row
+    Stuff = 3,
+    /// CRC error: the received CRC does not match
+    Crc = 4,
+    /// Acknowledgment error: no node acknowledged the frame
+    Ack = 5,
+}
+
+impl CanErrorType {
+    /// Create from the raw `ErrorType` value; unknown values map to
+    /// [`CanErrorType::Unknown`].
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Bit,
+            2 => Self::Form,
+            3 => Self::Stuff,
+            4 => Self::Crc,
+            5 => Self::Ack,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Direction of a CAN frame, stored in the `CAN_DataFrame.Dir` member.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CanDirection {
+    /// Received frame
+    #[default]
+    Rx,
+    /// Transmitted frame
+    Tx,
+}
+
+/// Direction and bus channel of a logged CAN frame.
+///
+/// Frames logged without metadata are received frames without a bus
+/// channel number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CanFrameMeta {
+    /// Frame direction
+    pub direction: CanDirection,
+    /// Bus channel number (e.g. 1 for CAN1), stored as 0 if not set
+    pub bus_channel: Option<u8>,
+}
+
+impl CanFrameMeta {
+    /// Metadata of a received frame.
+    pub fn rx() -> Self {
+        Self::default()
+    }
+
+    /// Metadata of a transmitted frame.
+    pub fn tx() -> Self {
+        Self {
+            direction: CanDirection::Tx,
+            bus_channel: None,
+        }
+    }
+
+    /// Set the bus channel number.
+    pub fn with_bus_channel(mut self, bus_channel: u8) -> Self {
+        self.bus_channel = Some(bus_channel);
+        self
+    }
+}
+
+/// A CAN error frame in ASAM format.
+#[derive(Clone, Copy)]
+struct ErrorFrame {
+    error_type: CanErrorType,
+    /// Interface specific flags, stored unchanged
+    flags: u8,
+}
+
+/// A CAN remote frame (RTR) in ASAM format.
+#[derive(Clone, Copy)]
+struct RemoteFrame {
+    /// CAN ID (11 or 29 bits)
+    can_id: u32,
+    /// Requested Data Length Code
+    dlc: u8,
+    /// True if this frame uses a 29-bit extended ID
+    is_extended: bool,
+}
+
+impl BusFrame for RemoteFrame {
+    const CHANNEL_NAME: &'static str = "CAN_RemoteFrame";
+    const RECORD_SIZE: usize = REMOTE_FRAME_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::can_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "RemoteFrame"
+    }
+
+    /// Build the CAN_RemoteFrame ByteArray:
+    /// - Bytes 0-3: CAN ID (little-endian, bit 31 set for extended ID)
+    /// - Byte 4: DLC
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        let id_with_flags = if self.is_extended {
+            self.can_id | 0x8000_0000
+        } else {
+            self.can_id
+        };
+        let mut bytes = Vec::with_capacity(REMOTE_FRAME_SIZE);
+        bytes.extend_from_slice(&id_with_flags.to_le_bytes());
+        bytes.push(self.dlc);
+        bytes
+    }
+
+    fn mdf_size(&self) -> usize {
+        REMOTE_FRAME_SIZE
+    }
+}
+
+impl BusFrame for ErrorFrame {
+    const CHANNEL_NAME: &'static str = "CAN_ErrorFrame";
+    const RECORD_SIZE: usize = ERROR_FRAME_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::can_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "ErrorFrame"
+    }
+
+    /// Build the CAN_ErrorFrame ByteArray:
+    /// - Byte 0: ErrorType
+    /// - Byte 1: Flags
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        alloc::vec![self.error_type as u8, self.flags]
+    }
+
+    fn mdf_size(&self) -> usize {
+        ERROR_FRAME_SIZE
+    }
+}
 
 /// Frame type classification for ASAM channel grouping.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum FrameType {
+pub(super) enum FrameType {
     /// Classic CAN with 11-bit standard ID
     Classic,
     /// Classic CAN with 29-bit extended ID
@@ -61,7 +251,7 @@ enum FrameType {
 
 impl FrameType {
     /// All frame type variants for zero-allocation iteration.
-    const ALL: [Self; 6] = [
+    pub(super) const ALL: [Self; 6] = [
         Self::Classic,
         Self::ClassicExtended,
         Self::FdSmall,
@@ -95,7 +285,19 @@ impl FrameType {
         }
     }
 
-    fn from_frame(is_extended: bool, is_fd: bool, data_len: usize) -> Self {
+    /// True if the CAN_DataFrame stores the FD flags (DLC > 8).
+    fn has_fd_flags(&self) -> bool {
+        matches!(self, FrameType::FdLarge | FrameType::FdLargeExtended)
+    }
+
+    /// CAN_DataFrame size in bytes: ID(4) + DLC(1) + [FD_flags(1)] +
+    /// Data(max_data_len) + BusChannel(1).
+    fn dataframe_size(&self) -> usize {
+        let fd_flags = if self.has_fd_flags() { 1 } else { 0 };
+        4 + 1 + fd_flags + self.max_data_len() + 1
+    }
+
+    pub(super) fn from_frame(is_extended: bool, is_fd: bool, data_len: usize) -> Self {
         match (is_extended, is_fd, data_len > 8) {
             (false, false, _) => FrameType::Classic,
             (true, false, _) => FrameType::ClassicExtended,
@@ -109,7 +311,7 @@ impl FrameType {
 
 /// A buffered raw CAN frame in ASAM format.
 #[derive(Clone)]
-struct RawFrame {
+pub(super) struct RawFrame {
     /// Timestamp in seconds (ASAM uses float64 seconds)
     timestamp_s: f64,
     /// CAN ID (11 or 29 bits)
@@ -126,10 +328,14 @@ struct RawFrame {
     is_extended: bool,
     /// True if this is a CAN FD frame
     is_fd: bool,
+    /// Frame direction
+    direction: CanDirection,
+    /// Bus channel number, 0 if unknown
+    bus_channel: u8,
 }
 
 impl RawFrame {
-    fn new_classic(
+    pub(super) fn new_classic(
         timestamp_us: u64,
         can_id: u32,
         dlc: u8,
@@ -148,10 +354,12 @@ impl RawFrame {
             fd_flags: FdFlags::default(),
             is_extended,
             is_fd: false,
+            direction: CanDirection::Rx,
+            bus_channel: 0,
         }
     }
 
-    fn new_fd(
+    pub(super) fn new_fd(
         timestamp_us: u64,
         can_id: u32,
         dlc: u8,
@@ -171,62 +379,362 @@ impl RawFrame {
             fd_flags: flags,
             is_extended,
             is_fd: true,
+            direction: CanDirection::Rx,
+            bus_channel: 0,
         }
     }
 
-    fn frame_type(&self) -> FrameType {
+    /// Set the direction and bus channel of the frame.
+    pub(super) fn with_meta(mut self, meta: CanFrameMeta) -> Self {
+        self.direction = meta.direction;
+        self.bus_channel = meta.bus_channel.unwrap_or(0);
+        self
+    }
+
+    pub(super) fn frame_type(&self) -> FrameType {
         FrameType::from_frame(self.is_extended, self.is_fd, self.data_len)
     }
 
     /// Build the CAN_DataFrame ByteArray in ASAM format.
     ///
-    /// Format for classic CAN (13 bytes):
-    /// - Bytes 0-3: CAN ID (little-endian, bit 31 set for extended ID)
+    /// Format for classic CAN (14 bytes):
+    /// - Bytes 0-3: CAN ID (little-endian, bit 30 set for transmitted
+    ///   frames, bit 31 set for extended ID)
     /// - Byte 4: DLC
     /// - Bytes 5-12: Data (8 bytes, zero-padded)
+    /// - Byte 13: Bus channel
     ///
     /// Format for CAN FD with DLC > 8:
-    /// - Bytes 0-3: CAN ID (little-endian, bit 31 set for extended ID)
+    /// - Bytes 0-3: CAN ID (little-endian, bit 30 set for transmitted
+    ///   frames, bit 31 set for extended ID)
     /// - Byte 4: DLC
     /// - Byte 5: FD flags (bit 0 = BRS, bit 1 = ESI)
-    /// - Bytes 6+: Data (up to 64 bytes)
+    /// - Bytes 6-69: Data (64 bytes, zero-padded)
+    /// - Byte 70: Bus channel
     fn to_dataframe_bytes(&self) -> Vec<u8> {
-        let max_data = self.frame_type().max_data_len();
-        let has_fd_flags = self.is_fd && self.data_len > 8;
-
-        // Calculate total size: ID(4) + DLC(1) + [FD_flags(1)] + Data(max_data)
-        let total_size = if has_fd_flags {
-            4 + 1 + 1 + max_data
-        } else {
-            4 + 1 + max_data
-        };
+        let frame_type = self.frame_type();
+        let max_data = frame_type.max_data_len();
+        let total_size = frame_type.dataframe_size();
 
         let mut bytes = Vec::with_capacity(total_size);
 
-        // CAN ID with extended flag in bit 31
-        let id_with_flags = if self.is_extended {
-            self.can_id | 0x8000_0000
-        } else {
-            self.can_id
-        };
+        // CAN ID with direction flag in bit 30 and extended flag in bit 31
+        let mut id_with_flags = self.can_id;
+        if self.direction == CanDirection::Tx {
+            id_with_flags |= 0x4000_0000;
+        }
+        if self.is_extended {
+            id_with_flags |= 0x8000_0000;
+        }
         bytes.extend_from_slice(&id_with_flags.to_le_bytes());
 
         // DLC
         bytes.push(self.dlc);
 
         // FD flags (only for FD frames with DLC > 8)
-        if has_fd_flags {
+        if frame_type.has_fd_flags() {
             bytes.push(self.fd_flags.to_byte());
         }
 
         // Data (zero-padded to max_data)
         bytes.extend_from_slice(&self.data[..self.data_len.min(max_data)]);
-        bytes.resize(total_size, 0);
+        bytes.resize(total_size - 1, 0);
 
+        bytes.push(self.bus_channel);
         bytes
     }
 }
 
+/// Timestamp in microseconds of a loaded `Timestamp` value (seconds as
+/// f64, or integer microseconds).
+#[cfg(feature = "std")]
+pub(super) fn timestamp_us(value: &Option<crate::DecodedValue>) -> Option<u64> {
+    match value {
+        Some(crate::DecodedValue::Float(s)) => Some((*s * 1_000_000.0) as u64),
+        Some(crate::DecodedValue::UnsignedInteger(us)) => Some(*us),
+        _ => None,
+    }
+}
+
+/// Direction and bus channel of a loaded CAN_DataFrame ByteArray. Files
+/// written before the bus channel was stored lack its byte.
+#[cfg(feature = "std")]
+fn dataframe_meta(bytes: &[u8], frame_type: FrameType) -> CanFrameMeta {
+    let direction = if bytes[3] & 0x40 != 0 {
+        CanDirection::Tx
+    } else {
+        CanDirection::Rx
+    };
+    let size = frame_type.dataframe_size();
+    let bus_channel = match bytes.get(size - 1) {
+        Some(&channel) if bytes.len() == size && channel != 0 => Some(channel),
+        _ => None,
+    };
+    CanFrameMeta {
+        direction,
+        bus_channel,
+    }
+}
+
+/// Describe the fields of a CAN_DataFrame channel by ASAM composition
+/// members, so viewers show them as separate signals:
+/// - `CAN_DataFrame.ID`: 29-bit CAN ID
+/// - `CAN_DataFrame.IDE`: extended ID flag
+/// - `CAN_DataFrame.Dir`: frame direction, shown as `Rx` or `Tx`
+/// - `CAN_DataFrame.DLC`: Data Length Code
+/// - `CAN_DataFrame.DataLength`: number of data bytes, derived from the
+///   DLC by a table conversion
+/// - `CAN_DataFrame.BRS` and `CAN_DataFrame.ESI`: CAN FD flags, in
+///   groups that store them (DLC > 8)
+/// - `CAN_DataFrame.DataBytes`: the zero-padded data bytes
+/// - `CAN_DataFrame.BusChannel`: bus channel number, 0 if unknown
+///
+/// The members overlay the ByteArray, whose layout is unchanged.
+fn add_dataframe_members<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    df_ch: &str,
+    frame_type: FrameType,
+) -> crate::Result<()> {
+    use crate::DataType;
+    use crate::blocks::ConversionBlock;
+
+    let has_fd_flags = frame_type.has_fd_flags();
+    let is_fd = !matches!(frame_type, FrameType::Classic | FrameType::ClassicExtended);
+
+    let id = writer.add_component_channel(df_ch, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("CAN_DataFrame.ID"));
+        ch.bit_count = 29;
+    })?;
+    let ide = writer.add_component_channel(df_ch, Some(&id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("CAN_DataFrame.IDE"));
+        ch.byte_offset = 3;
+        ch.bit_offset = 7;
+        ch.bit_count = 1;
+    })?;
+    let dir = writer.add_component_channel(df_ch, Some(&ide), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("CAN_DataFrame.Dir"));
+        ch.byte_offset = 3;
+        ch.bit_offset = 6;
+        ch.bit_count = 1;
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "Rx"), (1, "Tx")], "", Some(&dir))?;
+    let dlc = writer.add_component_channel(df_ch, Some(&dir), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("CAN_DataFrame.DLC"));
+        ch.byte_offset = 4;
+        ch.bit_count = 4;
+    })?;
+    let data_length = writer.add_component_channel(df_ch, Some(&dlc), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("CAN_DataFrame.DataLength"));
+        ch.byte_offset = 4;
+        ch.bit_count = 4;
+    })?;
+    let lengths: Vec<(f64, f64)> = (0..=15u8)
+        .map(|code| {
+            let len = if is_fd {
+                super::fd::dlc_to_len(code)
+            } else {
+                code.min(8) as usize
+            };
+            (code as f64, len as f64)
+        })
+        .collect();
+    writer.set_channel_conversion(&data_length, &ConversionBlock::value_to_value(&lengths))?;
+    writer.set_channel_unit(&data_length, "byte")?;
+
+    let mut prev = data_length;
+    let mut data_offset = 5;
+    if has_fd_flags {
+        let brs = writer.add_component_channel(df_ch, Some(&prev), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from("CAN_DataFrame.BRS"));
+            ch.byte_offset = 5;
+            ch.bit_count = 1;
+        })?;
+        prev = writer.add_component_channel(df_ch, Some(&brs), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from("CAN_DataFrame.ESI"));
+            ch.byte_offset = 5;
+            ch.bit_offset = 1;
+            ch.bit_count = 1;
+        })?;
+        data_offset = 6;
+    }
+    let data_bytes = writer.add_component_channel(df_ch, Some(&prev), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("CAN_DataFrame.DataBytes"));
+        ch.byte_offset = data_offset;
+        ch.bit_count = (frame_type.max_data_len() * 8) as u32;
+    })?;
+    writer.add_component_channel(df_ch, Some(&data_bytes), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("CAN_DataFrame.BusChannel"));
+        ch.byte_offset = (frame_type.dataframe_size() - 1) as u32;
+        ch.bit_count = 8;
+    })?;
+    Ok(())
+}
+
+/// Create the channel group of one CAN_DataFrame frame type, named after
+/// the bus, with a `Timestamp` and a `CAN_DataFrame` channel.
+pub(super) fn init_dataframe_group<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    bus_name: &str,
+    frame_type: FrameType,
+) -> crate::Result<String> {
+    use crate::DataType;
+
+    let group_name = frame_type.group_name(bus_name);
+    let dataframe_size = frame_type.dataframe_size();
+
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, &group_name)?;
+
+    // Set source information (ASAM requires this for bus logging)
+    let source = crate::blocks::SourceBlock::can_bus();
+    writer.set_channel_group_source(&cg, &source, Some(bus_name))?;
+
+    // Add Timestamp channel (Float64 in seconds - ASAM standard)
+    let time_ch = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some(alloc::string::String::from("Timestamp"));
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_ch)?;
+    writer.set_channel_unit(&time_ch, "s")?;
+
+    // Add CAN_DataFrame channel (ByteArray - ASAM composite format)
+    let df_ch = writer.add_channel(&cg, Some(&time_ch), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(alloc::string::String::from(frame_type.channel_name()));
+        ch.bit_count = (dataframe_size * 8) as u32;
+    })?;
+    add_dataframe_members(writer, &df_ch, frame_type)?;
+
+    Ok(cg)
+}
+
+/// Write buffered frames to their CAN_DataFrame channel group as one data
+/// block. Does nothing if there are no frames.
+pub(super) fn write_dataframes<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    cg: &str,
+    frames: &[RawFrame],
+) -> crate::Result<()> {
+    use crate::DecodedValue;
+
+    if frames.is_empty() {
+        return Ok(());
+    }
+
+    writer.start_data_block_for_cg(cg, 0)?;
+    for frame in frames {
+        let values = [
+            DecodedValue::Float(frame.timestamp_s),
+            DecodedValue::ByteArray(frame.to_dataframe_bytes()),
+        ];
+        writer.write_record(cg, &values)?;
+    }
+    writer.finish_data_block(cg)
+}
+
+/// Build the CAN_XLFrame ByteArray; the data is stored separately.
+/// - Bytes 0-1: Priority ID (little-endian, 11 bits)
+/// - Byte 2: Flags (bit 0 = SEC)
+/// - Byte 3: SDU type
+/// - Byte 4: VCID
+/// - Byte 5: reserved
+/// - Bytes 6-7: Data length (little-endian)
+/// - Bytes 8-11: Acceptance field (little-endian)
+fn xl_frame_bytes(frame: &XlFrame) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(XL_FRAME_SIZE);
+    bytes.extend_from_slice(&frame.priority_id().to_le_bytes());
+    bytes.push(frame.sec() as u8);
+    bytes.push(frame.sdu_type());
+    bytes.push(frame.vcid());
+    bytes.push(0);
+    bytes.extend_from_slice(&(frame.data().len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&frame.acceptance_field().to_le_bytes());
+    bytes
+}
+
+/// Create the `{bus_name}_XLFrame` channel group with a `Timestamp`, a
+/// `CAN_XLFrame` channel with composition members for its fields, and a
+/// variable length `CAN_XLFrame_DataBytes` channel.
+fn init_xl_group<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    bus_name: &str,
+) -> crate::Result<String> {
+    use crate::DataType;
+
+    let config = BusLoggerConfig {
+        source_name: String::from(bus_name),
+        group_name: alloc::format!("{}_XLFrame", bus_name),
+        data_channel_name: String::from("CAN_XLFrame"),
+        data_channel_bits: (XL_FRAME_SIZE * 8) as u32,
+        source_block: crate::blocks::SourceBlock::can_bus(),
+    };
+    let (cg, xl_ch) = init_bus_channel_group(writer, &config)?;
+
+    let members: [(&str, u32, u8, u32); 6] = [
+        ("CAN_XLFrame.PriorityID", 0, 0, 11),
+        ("CAN_XLFrame.SEC", 2, 0, 1),
+        ("CAN_XLFrame.SDT", 3, 0, 8),
+        ("CAN_XLFrame.VCID", 4, 0, 8),
+        ("CAN_XLFrame.DataLength", 6, 0, 16),
+        ("CAN_XLFrame.AF", 8, 0, 32),
+    ];
+    let mut prev: Option<String> = None;
+    for (name, byte_offset, bit_offset, bit_count) in members {
+        let member = writer.add_component_channel(&xl_ch, prev.as_deref(), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from(name));
+            ch.byte_offset = byte_offset;
+            ch.bit_offset = bit_offset;
+            ch.bit_count = bit_count;
+        })?;
+        if name == "CAN_XLFrame.DataLength" {
+            writer.set_channel_unit(&member, "byte")?;
+        }
+        prev = Some(member);
+    }
+
+    writer.add_vlsd_channel(&cg, Some(&xl_ch), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("CAN_XLFrame_DataBytes"));
+    })?;
+    Ok(cg)
+}
+
+/// Write buffered CAN XL frames to their channel group as one data block.
+/// Does nothing if there are no frames.
+fn write_xl_frames<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    cg: &str,
+    frames: &[TimestampedFrame<XlFrame>],
+) -> crate::Result<()> {
+    use crate::DecodedValue;
+
+    if frames.is_empty() {
+        return Ok(());
+    }
+
+    writer.start_data_block_for_cg(cg, 0)?;
+    for entry in frames {
+        let values = [
+            DecodedValue::Float(entry.timestamp_s),
+            DecodedValue::ByteArray(xl_frame_bytes(&entry.frame)),
+            DecodedValue::ByteArray(entry.frame.data().to_vec()),
+        ];
+        writer.write_record(cg, &values)?;
+    }
+    writer.finish_data_block(cg)
+}
+
 /// Raw CAN frame logger using ASAM MDF4 Bus Logging format.
 ///
 /// This logger captures raw CAN frames using the industry-standard
@@ -245,6 +753,11 @@ impl RawFrame {
 /// - `CAN_DataFrame_FD_IDE` - Extended ID, CAN FD (DLC <= 8)
 /// - `CAN_DataFrame_FD_DLC_over_8` - Standard ID, CAN FD (DLC > 8)
 /// - `CAN_DataFrame_FD_IDE_DLC_over_8` - Extended ID, CAN FD (DLC > 8)
+/// - `CAN_ErrorFrame` - Bus errors (see [`log_error_frame`](Self::log_error_frame))
+/// - `CAN_RemoteFrame` - Remote frames (see [`log_remote`](Self::log_remote))
+/// - `CAN_XLFrame` - CAN XL frames (see [`log_xl`](Self::log_xl))
+/// - `BusStatistics` - Bus load per second (see
+///   [`enable_bus_statistics`](Self::enable_bus_statistics))
 ///
 /// ## CAN_DataFrame Format
 ///
@@ -253,14 +766,19 @@ impl RawFrame {
 /// - Byte 4: DLC
 /// - Bytes 5+: Data (padded to 8 or 64 bytes)
 pub struct RawCanLogger<W: crate::writer::MdfWrite> {
-    writer: crate::MdfWriter<W>,
-    /// CAN bus name for source metadata
-    bus_name: String,
+    core: BusLoggerCore<W>,
     /// Buffered frames by type
     buffers: alloc::collections::BTreeMap<FrameType, Vec<RawFrame>>,
     /// Channel group IDs by frame type
     channel_groups: alloc::collections::BTreeMap<FrameType, String>,
-    initialized: bool,
+    error_frames: BusChannel<ErrorFrame>,
+    remote_frames: BusChannel<RemoteFrame>,
+    /// Buffered CAN XL frames
+    xl_frames: Vec<TimestampedFrame<XlFrame>>,
+    /// Channel group ID of the CAN XL frames
+    xl_group: Option<String>,
+    /// Bus statistics, if enabled
+    statistics: Option<BusStatistics>,
 }
 
 impl RawCanLogger<crate::writer::VecWriter> {
@@ -275,13 +793,7 @@ impl RawCanLogger<crate::writer::VecWriter> {
     /// Examples: "CAN", "CAN1", "Vehicle_CAN", etc.
     pub fn with_source_name(source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
-        Ok(Self {
-            writer,
-            bus_name: String::from(source_name),
-            buffers: alloc::collections::BTreeMap::new(),
-            channel_groups: alloc::collections::BTreeMap::new(),
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw CAN logger with a custom bus name.
@@ -295,19 +807,13 @@ impl RawCanLogger<crate::writer::VecWriter> {
     pub fn with_capacity(capacity: usize) -> crate::Result<Self> {
         let writer =
             crate::MdfWriter::from_writer(crate::writer::VecWriter::with_capacity(capacity));
-        Ok(Self {
-            writer,
-            bus_name: String::from("CAN"),
-            buffers: alloc::collections::BTreeMap::new(),
-            channel_groups: alloc::collections::BTreeMap::new(),
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, "CAN"))
     }
 
     /// Finalize the MDF file and return the bytes.
     pub fn finalize(mut self) -> crate::Result<Vec<u8>> {
         self.flush_and_finalize()?;
-        Ok(self.writer.into_inner().into_inner())
+        Ok(self.core.into_writer().into_inner().into_inner())
     }
 }
 
@@ -321,13 +827,7 @@ impl RawCanLogger<crate::writer::FileWriter> {
     /// Create a new raw CAN logger that writes to a file with custom source name.
     pub fn new_file_with_source_name(path: &str, source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::new(path)?;
-        Ok(Self {
-            writer,
-            bus_name: String::from(source_name),
-            buffers: alloc::collections::BTreeMap::new(),
-            channel_groups: alloc::collections::BTreeMap::new(),
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw CAN logger that writes to a file with custom bus name.
@@ -380,37 +880,116 @@ impl RawCanLogger<crate::writer::VecWriter> {
 
         let mut logger = Self::with_source_name(source_name)?;
 
-        // Find ASAM CAN_DataFrame channel groups
+        // Find ASAM CAN_DataFrame, CAN_ErrorFrame and CAN_RemoteFrame channel groups
         for (group_idx, group) in index.channel_groups.iter().enumerate() {
-            // Look for Timestamp and CAN_DataFrame channels
+            // Look for Timestamp, CAN_DataFrame, CAN_ErrorFrame and CAN_RemoteFrame channels
             let mut timestamp_ch = None;
             let mut dataframe_ch = None;
+            let mut errorframe_ch = None;
+            let mut remoteframe_ch = None;
+            let mut xlframe_ch = None;
+            let mut xldata_ch = None;
 
             for (ch_idx, channel) in group.channels.iter().enumerate() {
                 if let Some(name) = &channel.name {
                     match name.as_str() {
                         "Timestamp" => timestamp_ch = Some(ch_idx),
                         "CAN_DataFrame" => dataframe_ch = Some(ch_idx),
+                        "CAN_ErrorFrame" => errorframe_ch = Some(ch_idx),
+                        "CAN_RemoteFrame" => remoteframe_ch = Some(ch_idx),
+                        "CAN_XLFrame" => xlframe_ch = Some(ch_idx),
+                        "CAN_XLFrame_DataBytes" => xldata_ch = Some(ch_idx),
                         _ => {}
                     }
                 }
             }
 
+            if let (Some(ts_ch), Some(ef_ch)) = (timestamp_ch, errorframe_ch) {
+                let timestamps = index.read_channel_values(group_idx, ts_ch, &mut reader)?;
+                let errorframes = index.read_channel_values(group_idx, ef_ch, &mut reader)?;
+                for (ts_val, ef_val) in timestamps.iter().zip(errorframes.iter()) {
+                    let Some(timestamp_us) = timestamp_us(ts_val) else {
+                        continue;
+                    };
+                    // Parse: ErrorType(1 byte) + Flags(1 byte)
+                    let bytes = match ef_val {
+                        Some(DecodedValue::ByteArray(b)) if b.len() >= ERROR_FRAME_SIZE => b,
+                        _ => continue,
+                    };
+                    logger.log_error_frame(timestamp_us, CanErrorType::from_u8(bytes[0]), bytes[1]);
+                }
+                continue;
+            }
+
+            if let (Some(ts_ch), Some(rf_ch)) = (timestamp_ch, remoteframe_ch) {
+                let timestamps = index.read_channel_values(group_idx, ts_ch, &mut reader)?;
+                let remoteframes = index.read_channel_values(group_idx, rf_ch, &mut reader)?;
+                for (ts_val, rf_val) in timestamps.iter().zip(remoteframes.iter()) {
+                    let Some(timestamp_us) = timestamp_us(ts_val) else {
+                        continue;
+                    };
+                    // Parse: ID(4 bytes LE) + DLC(1 byte)
+                    let bytes = match rf_val {
+                        Some(DecodedValue::ByteArray(b)) if b.len() >= REMOTE_FRAME_SIZE => b,
+                        _ => continue,
+                    };
+                    let raw_id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    logger.log_remote(
+                        raw_id & 0x1FFF_FFFF,
+                        timestamp_us,
+                        bytes[4],
+                        raw_id & 0x8000_0000 != 0,
+                    );
+                }
+                continue;
+            }
+
+            if let (Some(ts_ch), Some(xl_ch), Some(data_ch)) = (timestamp_ch, xlframe_ch, xldata_ch)
+            {
+                let timestamps = index.read_channel_values(group_idx, ts_ch, &mut reader)?;
+                let xlframes = index.read_channel_values(group_idx, xl_ch, &mut reader)?;
+                let payloads = index.read_channel_values(group_idx, data_ch, &mut reader)?;
+                for ((ts_val, xl_val), data_val) in
+                    timestamps.iter().zip(xlframes.iter()).zip(payloads.iter())
+                {
+                    let Some(timestamp_us) = timestamp_us(ts_val) else {
+                        continue;
+                    };
+                    // Parse: PriorityID(2) + Flags(1) + SDT(1) + VCID(1) +
+                    // reserved(1) + DataLength(2) + AF(4)
+                    let (bytes, data) = match (xl_val, data_val) {
+                        (Some(DecodedValue::ByteArray(b)), Some(DecodedValue::ByteArray(data)))
+                            if b.len() >= XL_FRAME_SIZE =>
+                        {
+                            (b, data)
+                        }
+                        _ => continue,
+                    };
+                    let priority_id = u16::from_le_bytes([bytes[0], bytes[1]]);
+                    let acceptance_field =
+                        u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+                    if let Some(frame) = XlFrame::new(priority_id, bytes[3], acceptance_field, data)
+                    {
+                        let frame = frame.with_sec(bytes[2] & 0x01 != 0).with_vcid(bytes[4]);
+                        logger.log_xl_frame(timestamp_us, &frame);
+                    }
+                }
+                continue;
+            }
+
             let (ts_ch, df_ch) = match (timestamp_ch, dataframe_ch) {
                 (Some(t), Some(d)) => (t, d),
                 _ => continue, // Not an ASAM CAN group
             };
 
             // Read all records from this group
+            let fd_group = super::replay::is_fd_group(group.name.as_deref());
             let timestamps = index.read_channel_values(group_idx, ts_ch, &mut reader)?;
             let dataframes = index.read_channel_values(group_idx, df_ch, &mut reader)?;
 
             for (ts_val, df_val) in timestamps.iter().zip(dataframes.iter()) {
-                // Parse timestamp (seconds as f64 -> microseconds)
-                let timestamp_us = match ts_val {
-                    Some(DecodedValue::Float(s)) => (*s * 1_000_000.0) as u64,
-                    Some(DecodedValue::UnsignedInteger(us)) => *us,
-                    _ => continue,
+                let Some(timestamp_us) = timestamp_us(ts_val) else {
+                    continue;
                 };
 
                 // Parse CAN_DataFrame ByteArray
@@ -419,26 +998,12 @@ impl RawCanLogger<crate::writer::VecWriter> {
                     _ => continue,
                 };
 
-                if bytes.len() < 5 {
+                let Some((can_id, is_extended, is_fd, fd_flags, data)) =
+                    super::replay::parse_dataframe(bytes, fd_group)
+                else {
                     continue;
-                }
-
-                // Parse: ID(4 bytes LE) + DLC(1 byte) + Data(N bytes)
-                let raw_id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-                let is_extended = (raw_id & 0x8000_0000) != 0;
-                let can_id = raw_id & 0x1FFF_FFFF;
-                let dlc = bytes[4];
-                let data_len = super::fd::dlc_to_len(dlc).min(bytes.len() - 5);
-
-                // Check for FD flags (present if data_len > 8 and there's an extra byte)
-                let (fd_flags, data_start) = if data_len > 8 && bytes.len() > 6 {
-                    (FdFlags::from_byte(bytes[5]), 6)
-                } else {
-                    (FdFlags::default(), 5)
                 };
-
-                let data = &bytes[data_start..data_start + data_len.min(bytes.len() - data_start)];
-                let is_fd = data_len > 8 || fd_flags.brs() || fd_flags.esi();
+                let dlc = bytes[4];
 
                 // Create frame and add to appropriate buffer
                 let frame = if is_fd {
@@ -446,6 +1011,8 @@ impl RawCanLogger<crate::writer::VecWriter> {
                 } else {
                     RawFrame::new_classic(timestamp_us, can_id, dlc, data, is_extended)
                 };
+                let meta = dataframe_meta(bytes, frame.frame_type());
+                let frame = frame.with_meta(meta);
 
                 logger
                     .buffers
@@ -473,23 +1040,44 @@ impl RawCanLogger<crate::writer::VecWriter> {
         self.buffers
             .values()
             .flat_map(|frames| frames.iter())
-            .map(|f| (f.timestamp_s * 1_000_000.0) as u64)
+            .map(|f| f.timestamp_s)
+            .chain(self.error_frames.frames().iter().map(|e| e.timestamp_s))
+            .chain(self.remote_frames.frames().iter().map(|r| r.timestamp_s))
+            .chain(self.xl_frames.iter().map(|x| x.timestamp_s))
+            .map(|s| (s * 1_000_000.0) as u64)
             .max()
             .unwrap_or(0)
     }
 
-    /// Get the total number of frames loaded from file.
+    /// Get the total number of frames loaded from file, including error,
+    /// remote and CAN XL frames.
     pub fn loaded_frame_count(&self) -> usize {
-        self.buffers.values().map(|b| b.len()).sum()
+        self.buffers.values().map(|b| b.len()).sum::<usize>()
+            + self.error_frames.buffered()
+            + self.remote_frames.buffered()
+            + self.xl_frames.len()
     }
 }
 
 impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
+    fn with_writer(writer: crate::MdfWriter<W>, source_name: &str) -> Self {
+        Self {
+            core: BusLoggerCore::new(writer, source_name),
+            buffers: alloc::collections::BTreeMap::new(),
+            channel_groups: alloc::collections::BTreeMap::new(),
+            error_frames: BusChannel::new(),
+            remote_frames: BusChannel::new(),
+            xl_frames: Vec::new(),
+            xl_group: None,
+            statistics: None,
+        }
+    }
+
     /// Set the source name for metadata.
     ///
     /// Must be called before logging any frames.
     pub fn set_source_name(&mut self, name: &str) {
-        self.bus_name = String::from(name);
+        self.core.set_source_name(name);
     }
 
     /// Set the CAN bus name for source metadata.
@@ -499,8 +1087,32 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
         self.set_source_name(name);
     }
 
+    /// Add a `{source_name}_BusStatistics` channel group with the bus load,
+    /// frame rate and error frame count of every one-second window.
+    ///
+    /// The bus load is estimated from the nominal length of the logged
+    /// frames at `bitrate` bits per second. Must be called before the first
+    /// flush.
+    pub fn enable_bus_statistics(&mut self, bitrate: u32) {
+        self.statistics = Some(BusStatistics::new(bitrate));
+    }
+
+    /// Buffer a data frame and count it in the bus statistics.
+    fn push_frame(&mut self, timestamp_us: u64, frame: RawFrame) {
+        if let Some(statistics) = &mut self.statistics {
+            let bits = frame_bits(frame.is_extended, frame.is_fd, frame.data_len);
+            statistics.record_frame(timestamp_us, bits);
+        }
+        self.buffers
+            .entry(frame.frame_type())
+            .or_default()
+            .push(frame);
+    }
+
     /// Log a raw CAN frame with standard 11-bit ID (classic CAN, up to 8 bytes).
     ///
+    /// The frame is logged as received, without a bus channel number.
+    ///
     /// # Arguments
     /// * `can_id` - The CAN message ID (11-bit standard)
     /// * `timestamp_us` - Timestamp in microseconds
@@ -510,12 +1122,46 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
     /// Always returns `true` (raw logging never rejects frames)
     #[inline]
     pub fn log(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        self.log_with_meta(can_id, timestamp_us, data, CanFrameMeta::rx())
+    }
+
+    /// Log a transmitted CAN frame with standard 11-bit ID.
+    #[inline]
+    pub fn log_tx(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        self.log_with_meta(can_id, timestamp_us, data, CanFrameMeta::tx())
+    }
+
+    /// Log a received CAN frame with standard 11-bit ID.
+    #[inline]
+    pub fn log_rx(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        self.log_with_meta(can_id, timestamp_us, data, CanFrameMeta::rx())
+    }
+
+    /// Log a raw CAN frame with standard 11-bit ID, direction and bus
+    /// channel.
+    ///
+    /// The direction and bus channel are stored in the
+    /// `CAN_DataFrame.Dir` and `CAN_DataFrame.BusChannel` members.
+    ///
+    /// # Arguments
+    /// * `can_id` - The CAN message ID (11-bit standard)
+    /// * `timestamp_us` - Timestamp in microseconds
+    /// * `data` - Raw frame data (up to 8 bytes for classic CAN)
+    /// * `meta` - Direction and bus channel of the frame
+    ///
+    /// # Returns
+    /// Always returns `true` (raw logging never rejects frames)
+    #[inline]
+    pub fn log_with_meta(
+        &mut self,
+        can_id: u32,
+        timestamp_us: u64,
+        data: &[u8],
+        meta: CanFrameMeta,
+    ) -> bool {
         let dlc = data.len().min(8) as u8;
-        let frame = RawFrame::new_classic(timestamp_us, can_id, dlc, data, false);
-        self.buffers
-            .entry(frame.frame_type())
-            .or_default()
-            .push(frame);
+        let frame = RawFrame::new_classic(timestamp_us, can_id, dlc, data, false).with_meta(meta);
+        self.push_frame(timestamp_us, frame);
         true
     }
 
@@ -530,12 +1176,24 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
     /// Always returns `true` (raw logging never rejects frames)
     #[inline]
     pub fn log_extended(&mut self, can_id: u32, timestamp_us: u64, data: &[u8]) -> bool {
+        self.log_extended_with_meta(can_id, timestamp_us, data, CanFrameMeta::rx())
+    }
+
+    /// Log a raw CAN frame with extended 29-bit ID, direction and bus
+    /// channel.
+    ///
+    /// See [`log_with_meta`](Self::log_with_meta).
+    #[inline]
+    pub fn log_extended_with_meta(
+        &mut self,
+        can_id: u32,
+        timestamp_us: u64,
+        data: &[u8],
+        meta: CanFrameMeta,
+    ) -> bool {
         let dlc = data.len().min(8) as u8;
-        let frame = RawFrame::new_classic(timestamp_us, can_id, dlc, data, true);
-        self.buffers
-            .entry(frame.frame_type())
-            .or_default()
-            .push(frame);
+        let frame = RawFrame::new_classic(timestamp_us, can_id, dlc, data, true).with_meta(meta);
+        self.push_frame(timestamp_us, frame);
         true
     }
 
@@ -551,12 +1209,25 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
     /// Always returns `true` (raw logging never rejects frames)
     #[inline]
     pub fn log_fd(&mut self, can_id: u32, timestamp_us: u64, data: &[u8], flags: FdFlags) -> bool {
+        self.log_fd_with_meta(can_id, timestamp_us, data, flags, CanFrameMeta::rx())
+    }
+
+    /// Log a CAN FD frame with standard 11-bit ID, direction and bus
+    /// channel.
+    ///
+    /// See [`log_with_meta`](Self::log_with_meta).
+    #[inline]
+    pub fn log_fd_with_meta(
+        &mut self,
+        can_id: u32,
+        timestamp_us: u64,
+        data: &[u8],
+        flags: FdFlags,
+        meta: CanFrameMeta,
+    ) -> bool {
         let dlc = super::fd::len_to_dlc(data.len());
-        let frame = RawFrame::new_fd(timestamp_us, can_id, dlc, data, flags, false);
-        self.buffers
-            .entry(frame.frame_type())
-            .or_default()
-            .push(frame);
+        let frame = RawFrame::new_fd(timestamp_us, can_id, dlc, data, flags, false).with_meta(meta);
+        self.push_frame(timestamp_us, frame);
         true
     }
 
@@ -577,13 +1248,145 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
         timestamp_us: u64,
         data: &[u8],
         flags: FdFlags,
+    ) -> bool {
+        self.log_fd_extended_with_meta(can_id, timestamp_us, data, flags, CanFrameMeta::rx())
+    }
+
+    /// Log a CAN FD frame with extended 29-bit ID, direction and bus
+    /// channel.
+    ///
+    /// See [`log_with_meta`](Self::log_with_meta).
+    #[inline]
+    pub fn log_fd_extended_with_meta(
+        &mut self,
+        can_id: u32,
+        timestamp_us: u64,
+        data: &[u8],
+        flags: FdFlags,
+        meta: CanFrameMeta,
     ) -> bool {
         let dlc = super::fd::len_to_dlc(data.len());
-        let frame = RawFrame::new_fd(timestamp_us, can_id, dlc, data, flags, true);
-        self.buffers
-            .entry(frame.frame_type())
-            .or_default()
-            .push(frame);
+        let frame = RawFrame::new_fd(timestamp_us, can_id, dlc, data, flags, true).with_meta(meta);
+        self.push_frame(timestamp_us, frame);
+        true
+    }
+
+    /// Log a CAN error frame reported by the interface.
+    ///
+    /// Error frames are stored in the `{source_name}_ErrorFrame` channel
+    /// group as a 2-byte `CAN_ErrorFrame` ByteArray:
+    /// - Byte 0: Error type ([`CanErrorType`])
+    /// - Byte 1: Flags (interface specific, e.g. error passive or bus off)
+    ///
+    /// # Arguments
+    /// * `timestamp_us` - Timestamp in microseconds
+    /// * `error_type` - Kind of bus error
+    /// * `flags` - Interface specific error flags, stored unchanged
+    ///
+    /// # Returns
+    /// Always returns `true` (raw logging never rejects frames)
+    #[inline]
+    pub fn log_error_frame(
+        &mut self,
+        timestamp_us: u64,
+        error_type: CanErrorType,
+        flags: u8,
+    ) -> bool {
+        let frame = ErrorFrame { error_type, flags };
+        self.error_frames.push(timestamp_us, frame);
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_error(timestamp_us);
+        }
+        true
+    }
+
+    /// Log a CAN remote frame (remote transmission request).
+    ///
+    /// Remote frames carry no data; they are stored in the
+    /// `{source_name}_RemoteFrame` channel group as a 5-byte
+    /// `CAN_RemoteFrame` ByteArray:
+    /// - Bytes 0-3: CAN ID (little-endian, bit 31 = extended flag)
+    /// - Byte 4: Requested DLC
+    ///
+    /// # Arguments
+    /// * `can_id` - The CAN message ID (11-bit standard or 29-bit extended)
+    /// * `timestamp_us` - Timestamp in microseconds
+    /// * `dlc` - Data Length Code of the requested frame
+    /// * `is_extended` - True for a 29-bit extended ID
+    ///
+    /// # Returns
+    /// Always returns `true` (raw logging never rejects frames)
+    #[inline]
+    pub fn log_remote(
+        &mut self,
+        can_id: u32,
+        timestamp_us: u64,
+        dlc: u8,
+        is_extended: bool,
+    ) -> bool {
+        let frame = RemoteFrame {
+            can_id,
+            dlc,
+            is_extended,
+        };
+        self.remote_frames.push(timestamp_us, frame);
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_frame(timestamp_us, frame_bits(is_extended, false, 0));
+        }
+        true
+    }
+
+    /// Log a CAN XL frame.
+    ///
+    /// CAN XL frames are stored in the `{source_name}_XLFrame` channel
+    /// group: a 12-byte `CAN_XLFrame` ByteArray with the header fields
+    /// (`CAN_XLFrame.PriorityID`, `.SEC`, `.SDT`, `.VCID`, `.DataLength` and
+    /// `.AF` members) and the data in the variable length
+    /// `CAN_XLFrame_DataBytes` channel. The VCID is 0 and the SEC flag
+    /// cleared; use [`log_xl_frame`](Self::log_xl_frame) to set them.
+    ///
+    /// # Arguments
+    /// * `priority_id` - The 11-bit priority ID
+    /// * `timestamp_us` - Timestamp in microseconds
+    /// * `data` - Frame data (1 to 2048 bytes)
+    /// * `sdu_type` - SDU type of the payload
+    /// * `acceptance_field` - The 32-bit acceptance field
+    ///
+    /// # Returns
+    /// `false` if the priority ID exceeds 11 bits or the data length is
+    /// out of range, and the frame was not logged
+    #[inline]
+    pub fn log_xl(
+        &mut self,
+        priority_id: u16,
+        timestamp_us: u64,
+        data: &[u8],
+        sdu_type: u8,
+        acceptance_field: u32,
+    ) -> bool {
+        match XlFrame::new(priority_id, sdu_type, acceptance_field, data) {
+            Some(frame) => self.push_xl_frame(timestamp_us, frame),
+            None => false,
+        }
+    }
+
+    /// Log a CAN XL frame with all header fields.
+    ///
+    /// See [`log_xl`](Self::log_xl) for the storage format.
+    ///
+    /// # Returns
+    /// Always returns `true` (frames are validated by [`XlFrame::new`])
+    #[inline]
+    pub fn log_xl_frame(&mut self, timestamp_us: u64, frame: &XlFrame) -> bool {
+        self.push_xl_frame(timestamp_us, frame.clone())
+    }
+
+    fn push_xl_frame(&mut self, timestamp_us: u64, frame: XlFrame) -> bool {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_frame(timestamp_us, xl_frame_bits(frame.data().len()));
+        }
+        self.xl_frames
+            .push(TimestampedFrame::new(timestamp_us, frame));
         true
     }
 
@@ -633,17 +1436,65 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
         }
     }
 
+    /// Log a batch of embedded-can frames in timestamp order.
+    ///
+    /// The batch is sorted by timestamp first, so frames read in chunks
+    /// from a DMA ring buffer or an IPC queue can be passed in one call.
+    /// Frames with equal timestamps keep their order.
+    ///
+    /// # Returns
+    /// The number of frames logged
+    #[cfg(feature = "can")]
+    pub fn log_batch<F: embedded_can::Frame>(
+        &mut self,
+        frames: &[super::TimestampedFrame<F>],
+    ) -> usize {
+        super::timestamped_frame::in_time_order(frames)
+            .into_iter()
+            .filter(|entry| self.log_frame(entry.timestamp_us, &entry.frame))
+            .count()
+    }
+
+    /// Log a batch of CAN FD frames in timestamp order, like
+    /// [`log_batch`](Self::log_batch).
+    ///
+    /// # Returns
+    /// The number of frames logged
+    #[cfg(feature = "can")]
+    pub fn log_fd_batch<F: FdFrame>(&mut self, frames: &[super::TimestampedFrame<F>]) -> usize {
+        super::timestamped_frame::in_time_order(frames)
+            .into_iter()
+            .filter(|entry| self.log_fd_frame(entry.timestamp_us, &entry.frame))
+            .count()
+    }
+
     /// Flush buffered data to the MDF writer.
+    ///
+    /// Channel groups are created when the first frames of their kind are
+    /// written.
     pub fn flush(&mut self) -> crate::Result<()> {
-        if !self.initialized {
-            self.initialize_mdf()?;
-        }
+        self.core.ensure_initialized()?;
 
         // Write data for each frame type (zero-allocation iteration)
         for frame_type in FrameType::ALL {
-            if self.buffers.contains_key(&frame_type) {
-                self.write_frames(frame_type)?;
-            }
+            self.write_frames(frame_type)?;
+        }
+
+        self.error_frames.write(&mut self.core)?;
+        self.remote_frames.write(&mut self.core)?;
+
+        let (writer, bus_name) = self.core.writer_and_source_name();
+        if !self.xl_frames.is_empty() {
+            let cg = match &self.xl_group {
+                Some(cg) => cg,
+                None => self.xl_group.insert(init_xl_group(writer, bus_name)?),
+            };
+            write_xl_frames(writer, cg, &self.xl_frames)?;
+            self.xl_frames.clear();
+        }
+        if let Some(statistics) = &mut self.statistics {
+            statistics.init_group(writer, bus_name)?;
+            statistics.write(writer)?;
         }
 
         // Clear all buffers
@@ -654,89 +1505,31 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
         Ok(())
     }
 
-    /// Initialize the MDF file structure with ASAM-compliant channel groups.
-    fn initialize_mdf(&mut self) -> crate::Result<()> {
-        use crate::DataType;
-
-        self.writer.init_mdf_file()?;
-
-        // Create a channel group for each frame type that has data
-        for &frame_type in self.buffers.keys() {
-            let group_name = frame_type.group_name(&self.bus_name);
-            let max_data_len = frame_type.max_data_len();
-
-            // Calculate CAN_DataFrame size: ID(4) + DLC(1) + Data(max_data_len)
-            // For FD with large data, add FD_flags byte
-            let dataframe_size =
-                if matches!(frame_type, FrameType::FdLarge | FrameType::FdLargeExtended) {
-                    4 + 1 + 1 + max_data_len // ID + DLC + FD_flags + Data
-                } else {
-                    4 + 1 + max_data_len // ID + DLC + Data
-                };
-
-            let cg = self.writer.add_channel_group(None, |_| {})?;
-            self.writer.set_channel_group_name(&cg, &group_name)?;
-
-            // Set source information (ASAM requires this for bus logging)
-            let source = crate::blocks::SourceBlock::can_bus();
-            self.writer
-                .set_channel_group_source(&cg, &source, Some(&self.bus_name))?;
-
-            // Add Timestamp channel (Float64 in seconds - ASAM standard)
-            let time_ch = self.writer.add_channel(&cg, None, |ch| {
-                ch.data_type = DataType::FloatLE;
-                ch.name = Some(alloc::string::String::from("Timestamp"));
-                ch.bit_count = 64;
-            })?;
-            self.writer.set_time_channel(&time_ch)?;
-            self.writer.set_channel_unit(&time_ch, "s")?;
-
-            // Add CAN_DataFrame channel (ByteArray - ASAM composite format)
-            let _df_ch = self.writer.add_channel(&cg, Some(&time_ch), |ch| {
-                ch.data_type = DataType::ByteArray;
-                ch.name = Some(alloc::string::String::from(frame_type.channel_name()));
-                ch.bit_count = (dataframe_size * 8) as u32;
-            })?;
-
-            self.channel_groups.insert(frame_type, cg);
-        }
-
-        self.initialized = true;
-        Ok(())
-    }
-
-    /// Write frames for a specific frame type.
+    /// Write frames for a specific frame type, creating its
+    /// ASAM-compliant channel group on first use.
     fn write_frames(&mut self, frame_type: FrameType) -> crate::Result<()> {
-        use crate::DecodedValue;
-
-        let cg = match self.channel_groups.get(&frame_type) {
-            Some(cg) => cg.clone(),
-            None => return Ok(()),
-        };
-
         let frames = match self.buffers.get(&frame_type) {
-            Some(f) if !f.is_empty() => f,
+            Some(frames) if !frames.is_empty() => frames,
             _ => return Ok(()),
         };
-
-        self.writer.start_data_block_for_cg(&cg, 0)?;
-
-        for frame in frames {
-            let values = [
-                DecodedValue::Float(frame.timestamp_s),
-                DecodedValue::ByteArray(frame.to_dataframe_bytes()),
-            ];
-            self.writer.write_record(&cg, &values)?;
-        }
-
-        self.writer.finish_data_block(&cg)?;
-        Ok(())
+        let (writer, bus_name) = self.core.writer_and_source_name();
+        let cg = match self.channel_groups.get(&frame_type) {
+            Some(cg) => cg,
+            None => {
+                let cg = init_dataframe_group(writer, bus_name, frame_type)?;
+                self.channel_groups.entry(frame_type).or_insert(cg)
+            }
+        };
+        write_dataframes(writer, cg, frames)
     }
 
     /// Flush and finalize the MDF file.
     fn flush_and_finalize(&mut self) -> crate::Result<()> {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.close_window();
+        }
         self.flush()?;
-        self.writer.finalize()
+        self.core.finalize()
     }
 
     /// Get the number of frames logged for a specific CAN ID.
@@ -748,7 +1541,26 @@ impl<W: crate::writer::MdfWrite> RawCanLogger<W> {
             .count()
     }
 
-    /// Get the total number of frames logged.
+    /// Get the number of error frames logged.
+    pub fn error_frame_count(&self) -> usize {
+        self.error_frames.count()
+    }
+
+    /// Get the number of remote frames logged.
+    pub fn remote_frame_count(&self) -> usize {
+        self.remote_frames.count()
+    }
+
+    /// Get the number of CAN XL frames logged.
+    pub fn xl_frame_count(&self) -> usize {
+        self.xl_frames.len()
+    }
+
+    /// Get the total number of classic and FD data frames logged (error,
+    /// remote and CAN XL frames are counted by
+    /// [`error_frame_count`](Self::error_frame_count),
+    /// [`remote_frame_count`](Self::remote_frame_count) and
+    /// [`xl_frame_count`](Self::xl_frame_count)).
     pub fn total_frame_count(&self) -> usize {
         self.buffers.values().map(|b| b.len()).sum()
     }
@@ -1019,17 +1831,370 @@ mod tests {
         assert_eq!(flags.to_byte(), 0x03);
     }
 
+    #[test]
+    fn test_error_frames() {
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log(0x100, 1000, &[0x01, 0x02]);
+        assert!(logger.log_error_frame(1500, CanErrorType::Crc, 0x02));
+        assert!(logger.log_error_frame(2500, CanErrorType::from_u8(9), 0));
+
+        assert_eq!(logger.error_frame_count(), 2);
+        assert_eq!(logger.total_frame_count(), 1);
+        assert_eq!(logger.unique_id_count(), 1);
+
+        let frame = ErrorFrame {
+            error_type: CanErrorType::Ack,
+            flags: 0x80,
+        };
+        assert_eq!(frame.to_mdf_bytes(), [5, 0x80]);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        assert_eq!(&mdf_bytes[0..3], b"MDF");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_frames_round_trip() {
+        let mut logger = RawCanLogger::with_source_name("CAN1").unwrap();
+        logger.log(0x100, 1_000_000, &[0x01]);
+        logger.log_error_frame(2_000_000, CanErrorType::Stuff, 0x01);
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("test_can_error_frames.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let path = temp_path.to_str().unwrap();
+
+        let mdf = crate::MDF::from_file(path).unwrap();
+        let groups = mdf.channel_groups();
+        let errors = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("CAN1_ErrorFrame"))
+            .unwrap();
+        let values = errors.channels()[1].values().unwrap();
+        assert_eq!(
+            values,
+            [Some(crate::DecodedValue::ByteArray(alloc::vec![3, 0x01]))]
+        );
+
+        let logger = RawCanLogger::from_file(path).unwrap();
+        assert_eq!(logger.loaded_frame_count(), 2);
+        assert_eq!(logger.error_frame_count(), 1);
+        assert_eq!(logger.last_timestamp_us(), 2_000_000);
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bus_statistics() {
+        use crate::DecodedValue;
+
+        let mut logger = RawCanLogger::with_source_name("CAN1").unwrap();
+        logger.enable_bus_statistics(500_000);
+        // 100 classic frames of 111 bits and one error frame in the first
+        // second, silence in the second, one remote frame in the third
+        for i in 0..100 {
+            logger.log(0x100, i * 10_000, &[0; 8]);
+        }
+        logger.log_error_frame(500_000, CanErrorType::Crc, 0);
+        logger.flush().unwrap();
+        logger.log_remote(0x200, 2_500_000, 8, false);
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("test_can_bus_statistics.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let stats = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("CAN1_BusStatistics"))
+            .unwrap();
+        let channels = stats.channels();
+        let floats = |index: usize| -> Vec<f64> {
+            channels[index]
+                .values()
+                .unwrap()
+                .iter()
+                .map(|v| match v {
+                    Some(DecodedValue::Float(f)) => *f,
+                    other => panic!("unexpected value {:?}", other),
+                })
+                .collect()
+        };
+
+        assert_eq!(floats(0), [0.0, 1.0, 2.0]);
+        let load = floats(1);
+        assert!((load[0] - (100.0 * 111.0 + 20.0) / 500_000.0 * 100.0).abs() < 1e-9);
+        assert_eq!(load[1], 0.0);
+        assert!((load[2] - 47.0 / 500_000.0 * 100.0).abs() < 1e-9);
+        assert_eq!(floats(2), [100.0, 0.0, 1.0]);
+        assert_eq!(
+            channels[3].values().unwrap(),
+            [
+                Some(DecodedValue::UnsignedInteger(1)),
+                Some(DecodedValue::UnsignedInteger(0)),
+                Some(DecodedValue::UnsignedInteger(0)),
+            ]
+        );
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_remote_frames() {
+        let mut logger = RawCanLogger::new().unwrap();
+        assert!(logger.log_remote(0x300, 1000, 8, false));
+        assert!(logger.log_remote(0x18DA00F1, 2000, 4, true));
+
+        assert_eq!(logger.remote_frame_count(), 2);
+        assert_eq!(logger.total_frame_count(), 0);
+
+        let frame = RemoteFrame {
+            can_id: 0x18DA00F1,
+            dlc: 4,
+            is_extended: true,
+        };
+        assert_eq!(frame.to_mdf_bytes(), [0xF1, 0x00, 0xDA, 0x98, 4]);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        assert_eq!(&mdf_bytes[0..3], b"MDF");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_remote_frames_round_trip() {
+        let mut logger = RawCanLogger::with_source_name("CAN1").unwrap();
+        logger.log_remote(0x123, 1_000_000, 2, false);
+        logger.log_remote(0x1ABCDE, 3_000_000, 8, true);
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("test_can_remote_frames.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let path = temp_path.to_str().unwrap();
+
+        let mdf = crate::MDF::from_file(path).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].name().unwrap().as_deref(),
+            Some("CAN1_RemoteFrame")
+        );
+        let values = groups[0].channels()[1].values().unwrap();
+        assert_eq!(
+            values[0],
+            Some(crate::DecodedValue::ByteArray(alloc::vec![
+                0x23, 0x01, 0, 0, 2
+            ]))
+        );
+
+        let logger = RawCanLogger::from_file(path).unwrap();
+        assert_eq!(logger.remote_frame_count(), 2);
+        assert_eq!(logger.remote_frames.frames()[1].frame.can_id, 0x1ABCDE);
+        assert!(logger.remote_frames.frames()[1].frame.is_extended);
+        assert_eq!(logger.last_timestamp_us(), 3_000_000);
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_xl_frames_round_trip() {
+        let mut logger = RawCanLogger::with_source_name("CAN1").unwrap();
+        logger.enable_bus_statistics(500_000);
+        assert!(logger.log_xl(0x123, 1_000, &[1, 2, 3], 0x03, 0xDEAD_BEEF));
+        let frame = XlFrame::new(0x7FF, 0x04, 0x42, &[0x55; 2048])
+            .unwrap()
+            .with_vcid(9)
+            .with_sec(true);
+        assert!(logger.log_xl_frame(2_000, &frame));
+        assert!(!logger.log_xl(0x800, 3_000, &[0], 0, 0));
+        assert!(!logger.log_xl(0x100, 3_000, &[], 0, 0));
+        assert_eq!(logger.xl_frame_count(), 2);
+        assert_eq!(logger.total_frame_count(), 0);
+        let bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&bytes).is_ok());
+
+        let temp_path = std::env::temp_dir().join("test_can_xl_frames.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let path = temp_path.to_str().unwrap();
+
+        let mdf = crate::MDF::from_file(path).unwrap();
+        let groups = mdf.channel_groups();
+        let group = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("CAN1_XLFrame"))
+            .unwrap();
+        let channels = group.channels();
+        assert_eq!(
+            channels[1].values().unwrap()[0],
+            Some(crate::DecodedValue::ByteArray(alloc::vec![
+                0x23, 0x01, 0, 0x03, 0, 0, 3, 0, 0xEF, 0xBE, 0xAD, 0xDE
+            ]))
+        );
+        assert_eq!(
+            channels[2].values().unwrap()[1],
+            Some(crate::DecodedValue::ByteArray(alloc::vec![0x55; 2048]))
+        );
+
+        let logger = RawCanLogger::from_file(path).unwrap();
+        assert_eq!(logger.xl_frame_count(), 2);
+        assert_eq!(logger.xl_frames[1].frame, frame);
+        assert_eq!(logger.last_timestamp_us(), 2_000);
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dataframe_direction_and_bus_channel() {
+        let frame = RawFrame::new_classic(1000, 0x123, 2, &[1, 2], false)
+            .with_meta(CanFrameMeta::tx().with_bus_channel(2));
+        let bytes = frame.to_dataframe_bytes();
+        assert_eq!(bytes.len(), 14);
+        // Direction in bit 30 of the ID
+        let id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        assert_eq!(id, 0x4000_0123);
+        assert_eq!(bytes[13], 2);
+
+        let frame = RawFrame::new_fd(1000, 0x18FE_F100, 9, &[0xAA; 12], FdFlags::default(), true)
+            .with_meta(CanFrameMeta::rx().with_bus_channel(3));
+        let bytes = frame.to_dataframe_bytes();
+        assert_eq!(bytes.len(), 71);
+        let id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        assert_eq!(id, 0x98FE_F100);
+        assert_eq!(bytes[70], 3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_direction_and_bus_channel_round_trip() {
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log_tx(0x100, 1000, &[1, 2]);
+        logger.log_rx(0x100, 2000, &[3, 4]);
+        logger.log_extended_with_meta(0x1234, 3000, &[5], CanFrameMeta::tx().with_bus_channel(2));
+        logger.log_fd_with_meta(
+            0x200,
+            4000,
+            &[0xAA; 16],
+            FdFlags::new(true, false),
+            CanFrameMeta::rx().with_bus_channel(1),
+        );
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("test_can_direction.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let logger = RawCanLogger::from_file(temp_path.to_str().unwrap()).unwrap();
+
+        let meta = |frame_type: FrameType| -> Vec<CanFrameMeta> {
+            logger.buffers[&frame_type]
+                .iter()
+                .map(|f| CanFrameMeta {
+                    direction: f.direction,
+                    bus_channel: (f.bus_channel != 0).then_some(f.bus_channel),
+                })
+                .collect()
+        };
+        assert_eq!(
+            meta(FrameType::Classic),
+            [CanFrameMeta::tx(), CanFrameMeta::rx()]
+        );
+        assert_eq!(
+            meta(FrameType::ClassicExtended),
+            [CanFrameMeta::tx().with_bus_channel(2)]
+        );
+        assert_eq!(
+            meta(FrameType::FdLarge),
+            [CanFrameMeta::rx().with_bus_channel(1)]
+        );
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_dataframe_members() {
+        use crate::blocks::{BlockParse, ChannelBlock, TextBlock};
+
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log(0x123, 1000, &[1, 2, 3]);
+        logger.log_fd(0x456, 2000, &[0xAA; 12], FdFlags::new(true, false));
+        let bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&bytes).is_ok());
+
+        let temp_path = std::env::temp_dir().join("test_can_dataframe_members.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+
+        let members = |group: usize| {
+            let channels = mdf.channel_groups()[group].channels();
+            let mut members = Vec::new();
+            let mut address = channels[1].block().component_addr;
+            while address != 0 {
+                let member = ChannelBlock::from_bytes(&bytes[address as usize..]).unwrap();
+                let name = TextBlock::from_bytes(&bytes[member.name_addr as usize..])
+                    .unwrap()
+                    .text;
+                members.push((name, member.byte_offset));
+                address = member.next_ch_addr;
+            }
+            members
+        };
+        let names = |members: &[(String, u32)]| {
+            members
+                .iter()
+                .map(|(name, _)| name.trim_start_matches("CAN_DataFrame.").to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Classic frames: the fields start after the 8-byte timestamp
+        let classic = members(0);
+        assert_eq!(
+            names(&classic),
+            [
+                "ID",
+                "IDE",
+                "Dir",
+                "DLC",
+                "DataLength",
+                "DataBytes",
+                "BusChannel"
+            ]
+        );
+        assert_eq!(classic[5].1, 8 + 5);
+        assert_eq!(classic[6].1, 8 + 13);
+
+        // CAN FD frames with DLC > 8 also store BRS and ESI
+        let fd = members(1);
+        assert_eq!(
+            names(&fd),
+            [
+                "ID",
+                "IDE",
+                "Dir",
+                "DLC",
+                "DataLength",
+                "BRS",
+                "ESI",
+                "DataBytes",
+                "BusChannel"
+            ]
+        );
+        assert_eq!(fd[7].1, 8 + 6);
+        assert_eq!(fd[8].1, 8 + 70);
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
     #[test]
     fn test_bus_name() {
         let logger = RawCanLogger::with_bus_name("Vehicle_CAN").unwrap();
-        assert_eq!(logger.bus_name, "Vehicle_CAN");
+        assert_eq!(logger.core.source_name(), "Vehicle_CAN");
     }
 
     #[test]
     fn test_source_name_alias() {
         // with_source_name should work the same as with_bus_name
         let logger = RawCanLogger::with_source_name("Vehicle_CAN").unwrap();
-        assert_eq!(logger.bus_name, "Vehicle_CAN");
+        assert_eq!(logger.core.source_name(), "Vehicle_CAN");
     }
 
     #[cfg(feature = "std")]
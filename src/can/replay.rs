@@ -0,0 +1,294 @@
+//! Replay of raw CAN captures.
+//!
+//! [`frames`] streams the frames of the ASAM `CAN_DataFrame` channel groups
+//! of an MDF file back out in time order, e.g. to replay a capture onto
+//! hardware or to feed a simulator. [`frames_from_mdf`] does the same for
+//! a file opened with [`MDF`].
+//!
+//! Frames are yielded as [`ReplayFrame`] tuples of
+//! `(timestamp_us, can_id, is_extended, is_fd, flags, data)`. The FD flags
+//! are only stored for CAN FD frames with more than 8 data bytes and are
+//! cleared for all others.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::can::replay;
+//! use mdf4_rs::{FileRangeReader, MdfIndex};
+//!
+//! let index = MdfIndex::from_file("capture.mf4")?;
+//! let mut reader = FileRangeReader::new("capture.mf4")?;
+//!
+//! for frame in replay::frames(&index, &mut reader) {
+//!     let (timestamp_us, can_id, is_extended, is_fd, flags, data) = frame?;
+//!     // Wait until timestamp_us, then send the frame
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use super::fd::{FdFlags, dlc_to_len};
+use super::raw_logger::timestamp_us;
+use crate::bus_logging::{CaptureGroup, MergedFrames};
+use crate::index::{ByteRangeReader, IndexedChannelGroup, MdfIndex};
+use crate::{DecodedValue, Error, MDF, Result};
+
+/// A replayed frame: (timestamp_us, can_id, is_extended, is_fd, flags, data).
+///
+/// The CAN ID is given without the extended flag in bit 31.
+pub type ReplayFrame = (u64, u32, bool, bool, FdFlags, Vec<u8>);
+
+/// A replayed frame without its timestamp.
+type ReplayData = (u32, bool, bool, FdFlags, Vec<u8>);
+
+/// Iterate over the frames of all ASAM CAN_DataFrame channel groups of an
+/// MDF file in timestamp order.
+///
+/// Only one data block per channel group is held in memory at a time, so
+/// long captures can be replayed. Each channel group is expected to be in
+/// time order, as written by the loggers. Groups without `Timestamp` and
+/// `CAN_DataFrame` channels are skipped.
+pub fn frames<'a, R: ByteRangeReader<Error = Error>>(
+    index: &'a MdfIndex,
+    reader: &'a mut R,
+) -> ReplayIter<'a, R> {
+    let groups = index
+        .channel_groups
+        .iter()
+        .enumerate()
+        .filter_map(|(group_index, group)| ReplayGroup::detect(group_index, group));
+    ReplayIter {
+        frames: MergedFrames::new(index, reader, groups, |group, bytes| {
+            replay_data(bytes, group.fd_group)
+        }),
+    }
+}
+
+/// Read the frames of all ASAM CAN_DataFrame channel groups of a parsed
+/// MDF file, sorted by timestamp.
+///
+/// Unlike [`frames`], this holds all frames in memory.
+pub fn frames_from_mdf(mdf: &MDF) -> Result<Vec<ReplayFrame>> {
+    let mut frames = Vec::new();
+    for group in mdf.channel_groups() {
+        let mut timestamps = None;
+        let mut dataframes = None;
+        for channel in group.channels() {
+            match channel.name()?.as_deref() {
+                Some("Timestamp") => timestamps = Some(channel.values()?),
+                Some("CAN_DataFrame") => dataframes = Some(channel.values()?),
+                _ => {}
+            }
+        }
+        let (Some(timestamps), Some(dataframes)) = (timestamps, dataframes) else {
+            continue;
+        };
+        let fd_group = is_fd_group(group.name()?.as_deref());
+        frames.extend(
+            timestamps
+                .iter()
+                .zip(&dataframes)
+                .filter_map(|(ts_val, df_val)| replay_frame(ts_val, df_val, fd_group)),
+        );
+    }
+    frames.sort_by_key(|frame| frame.0);
+    Ok(frames)
+}
+
+/// Parse a CAN_DataFrame ByteArray.
+///
+/// The frame is ID(4 bytes LE, bit 31 = extended) + DLC(1 byte) +
+/// [FD flags(1 byte), for DLC > 8] + Data. Frames of FD groups, with more
+/// than 8 data bytes or with FD flags set are FD frames.
+///
+/// # Returns
+/// (can_id, is_extended, is_fd, flags, data), or `None` if the ByteArray
+/// is shorter than its header
+pub(super) fn parse_dataframe(
+    bytes: &[u8],
+    fd_group: bool,
+) -> Option<(u32, bool, bool, FdFlags, &[u8])> {
+    if bytes.len() < 5 {
+        return None;
+    }
+    let raw_id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let data_len = dlc_to_len(bytes[4]).min(bytes.len() - 5);
+
+    // FD flags are present if data_len > 8 and there's an extra byte
+    let (flags, data_start) = if data_len > 8 && bytes.len() > 6 {
+        (FdFlags::from_byte(bytes[5]), 6)
+    } else {
+        (FdFlags::default(), 5)
+    };
+    let data = &bytes[data_start..data_start + data_len.min(bytes.len() - data_start)];
+    let is_fd = fd_group || data_len > 8 || flags.brs() || flags.esi();
+
+    Some((
+        raw_id & 0x1FFF_FFFF,
+        raw_id & 0x8000_0000 != 0,
+        is_fd,
+        flags,
+        data,
+    ))
+}
+
+/// Whether a channel group named `name` holds CAN FD frames, as the
+/// `{bus}_DataFrame_FD...` groups of the loggers do.
+pub(super) fn is_fd_group(name: Option<&str>) -> bool {
+    name.is_some_and(|name| name.contains("_DataFrame_FD"))
+}
+
+fn replay_frame(
+    ts_val: &Option<DecodedValue>,
+    df_val: &Option<DecodedValue>,
+    fd_group: bool,
+) -> Option<ReplayFrame> {
+    let timestamp_us = timestamp_us(ts_val)?;
+    let Some(DecodedValue::ByteArray(bytes)) = df_val else {
+        return None;
+    };
+    let (can_id, is_extended, is_fd, flags, data) = replay_data(bytes, fd_group)?;
+    Some((timestamp_us, can_id, is_extended, is_fd, flags, data))
+}
+
+fn replay_data(bytes: &[u8], fd_group: bool) -> Option<ReplayData> {
+    let (can_id, is_extended, is_fd, flags, data) = parse_dataframe(bytes, fd_group)?;
+    Some((can_id, is_extended, is_fd, flags, data.to_vec()))
+}
+
+/// An ASAM CAN_DataFrame channel group.
+#[derive(Debug)]
+struct ReplayGroup {
+    /// Index in the MdfIndex channel_groups
+    group_index: usize,
+    /// Timestamp channel index
+    timestamp_channel: usize,
+    /// CAN_DataFrame channel index (ByteArray)
+    dataframe_channel: usize,
+    /// Whether the group holds CAN FD frames
+    fd_group: bool,
+}
+
+impl ReplayGroup {
+    fn detect(group_index: usize, group: &IndexedChannelGroup) -> Option<Self> {
+        let channel = |name: &str| {
+            group
+                .channels
+                .iter()
+                .position(|channel| channel.name.as_deref() == Some(name))
+        };
+        Some(Self {
+            group_index,
+            timestamp_channel: channel("Timestamp")?,
+            dataframe_channel: channel("CAN_DataFrame")?,
+            fd_group: is_fd_group(group.name.as_deref()),
+        })
+    }
+}
+
+impl CaptureGroup for ReplayGroup {
+    fn group_index(&self) -> usize {
+        self.group_index
+    }
+
+    fn channels(&self) -> [usize; 2] {
+        [self.timestamp_channel, self.dataframe_channel]
+    }
+}
+
+/// Streaming iterator over the frames of a capture, in timestamp order.
+///
+/// Created by [`frames()`]. After an error the iterator is exhausted.
+pub struct ReplayIter<'a, R> {
+    frames: MergedFrames<'a, R, ReplayGroup, ReplayData>,
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for ReplayIter<'_, R> {
+    type Item = Result<ReplayFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.frames.next()?;
+        Some(
+            frame.map(|(ts, (can_id, is_extended, is_fd, flags, data))| {
+                (ts, can_id, is_extended, is_fd, flags, data)
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::can::RawCanLogger;
+
+    #[test]
+    fn test_replay_frames() {
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log(0x100, 1000, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        logger.log_extended(0x18FE_F100, 1500, &[9, 10]);
+        logger.log_fd(0x200, 2000, &[0xAA; 8], FdFlags::new(true, false));
+        logger.log_fd_extended(0x1234, 2500, &[0xBB; 12], FdFlags::new(true, true));
+        logger.log(0x100, 3000, &[8, 7, 6, 5, 4, 3, 2, 1]);
+        let mdf_bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("replay_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+        let path = temp_path.to_str().unwrap();
+
+        let index = MdfIndex::from_file(path).unwrap();
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        let replayed = frames(&index, &mut reader)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        let no_flags = FdFlags::default();
+        assert_eq!(
+            replayed,
+            [
+                (
+                    1000,
+                    0x100,
+                    false,
+                    false,
+                    no_flags,
+                    vec![1, 2, 3, 4, 5, 6, 7, 8]
+                ),
+                (1500, 0x18FE_F100, true, false, no_flags, vec![9, 10]),
+                // Flags are not stored for FD frames of up to 8 bytes
+                (2000, 0x200, false, true, no_flags, vec![0xAA; 8]),
+                (
+                    2500,
+                    0x1234,
+                    true,
+                    true,
+                    FdFlags::new(true, true),
+                    vec![0xBB; 12]
+                ),
+                (
+                    3000,
+                    0x100,
+                    false,
+                    false,
+                    no_flags,
+                    vec![8, 7, 6, 5, 4, 3, 2, 1]
+                ),
+            ]
+        );
+
+        let mdf = MDF::from_file(path).unwrap();
+        assert_eq!(frames_from_mdf(&mdf).unwrap(), replayed);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_parse_dataframe() {
+        // Classic frame with a DLC of 2, zero-padded to 8 bytes
+        let bytes = [0x00, 0x01, 0x00, 0x80, 2, 0xAA, 0xBB, 0, 0, 0, 0, 0, 0];
+        assert_eq!(
+            parse_dataframe(&bytes, false),
+            Some((0x100, true, false, FdFlags::default(), &[0xAA, 0xBB][..]))
+        );
+        assert_eq!(parse_dataframe(&bytes[..4], false), None);
+    }
+}
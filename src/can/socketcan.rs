@@ -0,0 +1,274 @@
+//! Linux SocketCAN integration.
+//!
+//! Requires the `socketcan` feature. [`SocketCanLogger`] logs the frames
+//! of the [`socketcan`](::socketcan) crate directly into a
+//! [`RawCanLogger`] or [`CanDbcLogger`](super::CanDbcLogger), and
+//! [`capture`] reads an interface until it is stopped.
+//!
+//! Timestamps are passed through unchanged, so hardware timestamps (e.g.
+//! from `SO_TIMESTAMPING`) can be logged when frames are read with another
+//! API. [`capture`] uses software read times instead, as the `socketcan`
+//! crate does not return the receive timestamps of the kernel.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use mdf4_rs::can::{RawCanLogger, socketcan::capture};
+//!
+//! static STOP: AtomicBool = AtomicBool::new(false);
+//!
+//! let mut logger = RawCanLogger::new()?;
+//! // Set STOP from another thread or a signal handler
+//! let frames = capture("can0", &mut logger, &STOP)?;
+//! let mdf_bytes = logger.finalize()?;
+//! ```
+
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use ::socketcan::{
+    CanAnyFrame, CanErrorFrame, CanFdFrame, CanFdSocket, CanFrame, Socket, SocketOptions,
+};
+use embedded_can::{Frame as EmbeddedFrame, Id};
+
+use super::fd::FdFlags;
+use super::raw_logger::{CanErrorType, RawCanLogger};
+
+/// Interval at which [`capture`] checks its stop flag.
+pub const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Error classes of the CAN ID of Linux error frames (linux/can/error.h)
+const CAN_ERR_PROT: u32 = 0x0000_0008;
+const CAN_ERR_ACK: u32 = 0x0000_0020;
+
+// Protocol violation types in byte 2 and locations in byte 3
+const CAN_ERR_PROT_BIT: u8 = 0x01;
+const CAN_ERR_PROT_FORM: u8 = 0x02;
+const CAN_ERR_PROT_STUFF: u8 = 0x04;
+const CAN_ERR_PROT_BIT0: u8 = 0x08;
+const CAN_ERR_PROT_BIT1: u8 = 0x10;
+const CAN_ERR_PROT_LOC_CRC_SEQ: u8 = 0x08;
+const CAN_ERR_PROT_LOC_CRC_DEL: u8 = 0x18;
+
+/// Loggers that accept SocketCAN frames.
+pub trait SocketCanLogger {
+    /// Log a classic CAN data, remote or error frame.
+    ///
+    /// # Returns
+    /// `true` if the frame was logged
+    fn log_socketcan_frame(&mut self, timestamp_us: u64, frame: &CanFrame) -> bool;
+
+    /// Log a CAN FD frame.
+    ///
+    /// # Returns
+    /// `true` if the frame was logged
+    fn log_socketcan_fd_frame(&mut self, timestamp_us: u64, frame: &CanFdFrame) -> bool;
+
+    /// Log any frame read from a [`CanFdSocket`].
+    ///
+    /// # Returns
+    /// `true` if the frame was logged
+    fn log_socketcan_any(&mut self, timestamp_us: u64, frame: &CanAnyFrame) -> bool {
+        match frame {
+            CanAnyFrame::Normal(frame) => {
+                self.log_socketcan_frame(timestamp_us, &CanFrame::Data(*frame))
+            }
+            CanAnyFrame::Remote(frame) => {
+                self.log_socketcan_frame(timestamp_us, &CanFrame::Remote(*frame))
+            }
+            CanAnyFrame::Error(frame) => {
+                self.log_socketcan_frame(timestamp_us, &CanFrame::Error(*frame))
+            }
+            CanAnyFrame::Fd(frame) => self.log_socketcan_fd_frame(timestamp_us, frame),
+        }
+    }
+}
+
+/// Raw logging of SocketCAN frames.
+///
+/// Data frames are logged like [`RawCanLogger::log_frame`], remote frames
+/// with [`RawCanLogger::log_remote`] and error frames with
+/// [`RawCanLogger::log_error_frame`]. The error type is derived from the
+/// error class and protocol violation bytes of the frame; the flags byte
+/// holds the low 8 bits of the error class (e.g. `0x40` for bus off).
+impl<W: crate::writer::MdfWrite> SocketCanLogger for RawCanLogger<W> {
+    fn log_socketcan_frame(&mut self, timestamp_us: u64, frame: &CanFrame) -> bool {
+        match frame {
+            CanFrame::Data(frame) => self.log_frame(timestamp_us, frame),
+            CanFrame::Remote(frame) => {
+                let (can_id, is_extended) = raw_id(EmbeddedFrame::id(frame));
+                let dlc = EmbeddedFrame::dlc(frame) as u8;
+                self.log_remote(can_id, timestamp_us, dlc, is_extended)
+            }
+            CanFrame::Error(frame) => {
+                let (error_type, flags) = error_frame_type(frame);
+                self.log_error_frame(timestamp_us, error_type, flags)
+            }
+        }
+    }
+
+    fn log_socketcan_fd_frame(&mut self, timestamp_us: u64, frame: &CanFdFrame) -> bool {
+        let flags = FdFlags::new(frame.is_brs(), frame.is_esi());
+        let data = EmbeddedFrame::data(frame);
+        match raw_id(EmbeddedFrame::id(frame)) {
+            (can_id, false) => self.log_fd(can_id, timestamp_us, data, flags),
+            (can_id, true) => self.log_fd_extended(can_id, timestamp_us, data, flags),
+        }
+    }
+}
+
+/// Decoded logging of SocketCAN frames.
+///
+/// Data and FD frames are decoded like [`CanDbcLogger::log_frame`](super::CanDbcLogger::log_frame)
+/// and [`CanDbcLogger::log_fd`](super::CanDbcLogger::log_fd). Error frames
+/// are counted with [`CanDbcLogger::log_error_frame`](super::CanDbcLogger::log_error_frame);
+/// remote frames carry no signals and are not logged.
+#[cfg(feature = "dbc")]
+impl<W: crate::writer::MdfWrite> SocketCanLogger for super::CanDbcLogger<W> {
+    fn log_socketcan_frame(&mut self, timestamp_us: u64, frame: &CanFrame) -> bool {
+        match frame {
+            CanFrame::Data(frame) => self.log_frame(timestamp_us, frame),
+            CanFrame::Remote(_) => false,
+            CanFrame::Error(_) => {
+                self.log_error_frame(timestamp_us);
+                true
+            }
+        }
+    }
+
+    fn log_socketcan_fd_frame(&mut self, timestamp_us: u64, frame: &CanFdFrame) -> bool {
+        let data = EmbeddedFrame::data(frame);
+        match raw_id(EmbeddedFrame::id(frame)) {
+            (can_id, false) => self.log_fd(can_id, timestamp_us, data),
+            (can_id, true) => self.log_fd_extended(can_id, timestamp_us, data),
+        }
+    }
+}
+
+/// Log the frames of a SocketCAN interface until `stop` is set.
+///
+/// Opens `interface` (e.g. `can0` or `vcan0`) for classic and FD frames
+/// including error frames, and logs every frame with
+/// [`SocketCanLogger::log_socketcan_any`]. The stop flag is checked after
+/// every frame and at least every [`CAPTURE_POLL_INTERVAL`].
+///
+/// # Timestamps
+///
+/// Timestamps are software read times: the microseconds since the call at
+/// which `read_frame()` returned the frame, not the receive time of the
+/// kernel or the controller. They are late by the scheduling latency of the
+/// capture thread, and frames queued in the socket while the thread was
+/// not running get nearly equal timestamps. Where the timing matters, read
+/// the socket with `SO_TIMESTAMP` or `SO_TIMESTAMPING` and log the frames
+/// with [`SocketCanLogger`] directly.
+///
+/// The logger is not flushed; call `finalize()` afterwards.
+///
+/// # Returns
+/// The number of frames read
+///
+/// # Errors
+/// Returns an I/O error if the interface cannot be opened or read.
+pub fn capture<L: SocketCanLogger>(
+    interface: &str,
+    logger: &mut L,
+    stop: &AtomicBool,
+) -> crate::Result<u64> {
+    let socket = CanFdSocket::open(interface)?;
+    socket.set_error_filter_accept_all()?;
+    socket.set_read_timeout(CAPTURE_POLL_INTERVAL)?;
+
+    let start = Instant::now();
+    let mut frames = 0;
+    while !stop.load(Ordering::Relaxed) {
+        match socket.read_frame() {
+            Ok(frame) => {
+                let timestamp_us = start.elapsed().as_micros() as u64;
+                logger.log_socketcan_any(timestamp_us, &frame);
+                frames += 1;
+            }
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+                ) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(frames)
+}
+
+/// Raw ID and extended flag of an embedded-can ID.
+fn raw_id(id: Id) -> (u32, bool) {
+    match id {
+        Id::Standard(id) => (id.as_raw() as u32, false),
+        Id::Extended(id) => (id.as_raw(), true),
+    }
+}
+
+fn error_frame_type(frame: &CanErrorFrame) -> (CanErrorType, u8) {
+    let class = ::socketcan::Frame::error_bits(frame);
+    error_type(class, EmbeddedFrame::data(frame))
+}
+
+/// Error type and flags of a Linux error frame with error `class` and
+/// `data`.
+fn error_type(class: u32, data: &[u8]) -> (CanErrorType, u8) {
+    let flags = class as u8;
+    if class & CAN_ERR_ACK != 0 {
+        return (CanErrorType::Ack, flags);
+    }
+    if class & CAN_ERR_PROT == 0 {
+        return (CanErrorType::Unknown, flags);
+    }
+    let violation = data.get(2).copied().unwrap_or(0);
+    let location = data.get(3).copied().unwrap_or(0);
+    let error_type = if violation & (CAN_ERR_PROT_BIT | CAN_ERR_PROT_BIT0 | CAN_ERR_PROT_BIT1) != 0
+    {
+        CanErrorType::Bit
+    } else if violation & CAN_ERR_PROT_FORM != 0 {
+        CanErrorType::Form
+    } else if violation & CAN_ERR_PROT_STUFF != 0 {
+        CanErrorType::Stuff
+    } else if matches!(
+        location,
+        CAN_ERR_PROT_LOC_CRC_SEQ | CAN_ERR_PROT_LOC_CRC_DEL
+    ) {
+        CanErrorType::Crc
+    } else {
+        CanErrorType::Unknown
+    };
+    (error_type, flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_type() {
+        // No acknowledgement, reported with the bus error class
+        assert_eq!(error_type(0xA0, &[0; 8]), (CanErrorType::Ack, 0xA0));
+        assert_eq!(
+            error_type(0x88, &[0, 0, CAN_ERR_PROT_STUFF, 0, 0, 0, 0, 0]),
+            (CanErrorType::Stuff, 0x88)
+        );
+        assert_eq!(
+            error_type(0x08, &[0, 0, CAN_ERR_PROT_BIT1, 0, 0, 0, 0, 0]),
+            (CanErrorType::Bit, 0x08)
+        );
+        assert_eq!(
+            error_type(0x08, &[0, 0, CAN_ERR_PROT_FORM, 0, 0, 0, 0, 0]),
+            (CanErrorType::Form, 0x08)
+        );
+        assert_eq!(
+            error_type(0x08, &[0, 0, 0, CAN_ERR_PROT_LOC_CRC_SEQ, 0, 0, 0, 0]),
+            (CanErrorType::Crc, 0x08)
+        );
+        // Bus off: no protocol violation
+        assert_eq!(error_type(0x40, &[0; 8]), (CanErrorType::Unknown, 0x40));
+        assert_eq!(error_type(0x08, &[]), (CanErrorType::Unknown, 0x08));
+    }
+}
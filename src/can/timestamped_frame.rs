@@ -1,5 +1,8 @@
 //! Timestamped CAN frame container.
 
+#[cfg(feature = "can")]
+use alloc::vec::Vec;
+
 /// A CAN frame with timestamp for logging.
 ///
 /// This is a simple container that pairs a CAN frame with a timestamp.
@@ -21,3 +24,15 @@ impl<F> TimestampedFrame<F> {
         }
     }
 }
+
+/// The frames of a batch in timestamp order.
+///
+/// Frames with equal timestamps keep their order in the batch.
+#[cfg(feature = "can")]
+pub(super) fn in_time_order<F>(frames: &[TimestampedFrame<F>]) -> Vec<&TimestampedFrame<F>> {
+    let mut sorted: Vec<&TimestampedFrame<F>> = frames.iter().collect();
+    if !sorted.is_sorted_by_key(|frame| frame.timestamp_us) {
+        sorted.sort_by_key(|frame| frame.timestamp_us);
+    }
+    sorted
+}
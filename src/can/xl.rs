@@ -0,0 +1,126 @@
+//! CAN XL support.
+//!
+//! CAN XL extends CAN FD with:
+//! - Data payloads of 1 to 2048 bytes
+//! - An 11-bit priority ID that only arbitrates; addressing moves to the
+//!   32-bit acceptance field
+//! - An SDU type describing the payload (e.g. tunneled Ethernet or CAN
+//!   frames, see CiA 611-1)
+//! - A virtual CAN network ID (VCID) and the simple extended content (SEC)
+//!   flag for CAN XL security
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::can::{RawCanLogger, XlFrame};
+//!
+//! let frame = XlFrame::new(0x123, 0x03, 0x1234_5678, &payload)
+//!     .unwrap()
+//!     .with_vcid(2);
+//! logger.log_xl_frame(timestamp_us, &frame);
+//! ```
+
+use alloc::vec::Vec;
+
+/// Maximum CAN XL data length in bytes.
+pub const MAX_XL_DATA_LEN: usize = 2048;
+
+/// Largest 11-bit CAN XL priority ID.
+pub const MAX_XL_PRIORITY_ID: u16 = 0x7FF;
+
+/// A CAN XL data frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XlFrame {
+    priority_id: u16,
+    sdu_type: u8,
+    vcid: u8,
+    acceptance_field: u32,
+    sec: bool,
+    data: Vec<u8>,
+}
+
+impl XlFrame {
+    /// Create a CAN XL frame with VCID 0 and the SEC flag cleared.
+    ///
+    /// Returns `None` if the priority ID exceeds 11 bits or the data is
+    /// empty or longer than [`MAX_XL_DATA_LEN`] bytes.
+    pub fn new(priority_id: u16, sdu_type: u8, acceptance_field: u32, data: &[u8]) -> Option<Self> {
+        if priority_id > MAX_XL_PRIORITY_ID || data.is_empty() || data.len() > MAX_XL_DATA_LEN {
+            return None;
+        }
+        Some(Self {
+            priority_id,
+            sdu_type,
+            vcid: 0,
+            acceptance_field,
+            sec: false,
+            data: data.to_vec(),
+        })
+    }
+
+    /// Set the virtual CAN network ID.
+    pub fn with_vcid(mut self, vcid: u8) -> Self {
+        self.vcid = vcid;
+        self
+    }
+
+    /// Set the simple extended content (SEC) flag.
+    pub fn with_sec(mut self, sec: bool) -> Self {
+        self.sec = sec;
+        self
+    }
+
+    /// The 11-bit priority ID.
+    pub fn priority_id(&self) -> u16 {
+        self.priority_id
+    }
+
+    /// The SDU type of the payload.
+    pub fn sdu_type(&self) -> u8 {
+        self.sdu_type
+    }
+
+    /// The virtual CAN network ID.
+    pub fn vcid(&self) -> u8 {
+        self.vcid
+    }
+
+    /// The 32-bit acceptance field.
+    pub fn acceptance_field(&self) -> u32 {
+        self.acceptance_field
+    }
+
+    /// Returns true if the simple extended content flag is set.
+    pub fn sec(&self) -> bool {
+        self.sec
+    }
+
+    /// The frame data (1 to 2048 bytes).
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xl_frame() {
+        let frame = XlFrame::new(0x123, 0x03, 0xDEAD_BEEF, &[1, 2, 3])
+            .unwrap()
+            .with_vcid(7)
+            .with_sec(true);
+        assert_eq!(frame.priority_id(), 0x123);
+        assert_eq!(frame.sdu_type(), 0x03);
+        assert_eq!(frame.vcid(), 7);
+        assert_eq!(frame.acceptance_field(), 0xDEAD_BEEF);
+        assert!(frame.sec());
+        assert_eq!(frame.data(), &[1, 2, 3]);
+
+        assert!(XlFrame::new(0x800, 0, 0, &[0]).is_none());
+        assert!(XlFrame::new(0x7FF, 0, 0, &[]).is_none());
+        assert!(XlFrame::new(0, 0, 0, &[0; MAX_XL_DATA_LEN]).is_some());
+        assert!(XlFrame::new(0, 0, 0, &[0; MAX_XL_DATA_LEN + 1]).is_none());
+    }
+}
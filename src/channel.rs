@@ -1,6 +1,6 @@
 use crate::{
     Result,
-    blocks::{ChannelBlock, read_string_block},
+    blocks::{ChannelBlock, read_string_block, xml_element_text},
     parsing::{
         RawChannel, RawChannelGroup, RawDataGroup, SourceInfo,
         decoder::{DecodedValue, decode_channel_value_with_validity},
@@ -61,6 +61,15 @@ impl<'a> Channel<'a> {
         read_string_block(self.mmap, self.block.comment_addr)
     }
 
+    /// Retrieve the display name stored in the channel's XML comment.
+    ///
+    /// Returns `Ok(None)` when the comment is missing, is a plain text block,
+    /// or has no `<display>` element.
+    pub fn display_name(&self) -> Result<Option<String>> {
+        Ok(read_string_block(self.mmap, self.block.comment_addr)?
+            .and_then(|xml| xml_element_text(&xml, "display")))
+    }
+
     /// Get the acquisition source for this channel if available.
     pub fn source(&self) -> Result<Option<SourceInfo>> {
         let addr = self.block.source_addr;
@@ -1,37 +1,170 @@
 use crate::{
-    Result,
-    blocks::ChannelBlock,
+    Error, Result,
+    blocks::{
+        AttachmentBlock, BlockHeader, BlockParse, ChannelBlock, EventBlock, EventSyncType,
+        read_string_block, u64_to_usize,
+    },
     parsing::{
-        MdfFile,
+        MdfFile, RawChannelGroup, RawDataGroup,
         decoder::{DecodedValue, decode_channel_value},
     },
-    writer::MdfWriter,
+    writer::{MdfWrite, MdfWriter},
 };
+use std::ops::Range;
 
-// Helper to fetch the next set of raw records from parallel iterators.
-// Returns `Ok(Some(records))` when a full set was read,
-// `Ok(None)` if any iterator was exhausted, or an error propagated from
-// the iterators.
-fn next_record_set<'a, I>(iters: &mut [I]) -> Result<Option<Vec<&'a [u8]>>>
-where
-    I: Iterator<Item = Result<&'a [u8]>>,
-{
-    let mut rec = Vec::with_capacity(iters.len());
-    for it in iters.iter_mut() {
-        match it.next() {
-            Some(Ok(r)) => rec.push(r),
-            Some(Err(e)) => return Err(e),
-            None => return Ok(None),
+/// Call `f` with each raw record (including the record ID) of a channel
+/// group.
+///
+/// The data blocks are walked like the reader does, following DL lists and
+/// decompressing DZ blocks (which requires the `compression` feature).
+/// Records may continue from one block of a list into the next. `f` returns
+/// `false` to stop early.
+fn for_each_record(
+    dg: &RawDataGroup,
+    cg: &RawChannelGroup,
+    mmap: &[u8],
+    mut f: impl FnMut(&[u8]) -> Result<bool>,
+) -> Result<()> {
+    let mut records = dg.group_records(cg, mmap)?;
+    while let Some(record) = records.next_record()? {
+        if !f(record)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Options selecting the structure kept by [`cut_mdf_with_options()`].
+///
+/// By default all channel groups and channels are kept. Selecting channels
+/// or groups drops the rest of the structure, e.g. to slim a recording of
+/// many buses down for sharing.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::cut::{CutOptions, cut_mdf_with_options};
+///
+/// let options = CutOptions::new()
+///     .with_groups(&["Powertrain"])
+///     .with_channels(&["EngineSpeed", "Throttle"]);
+/// cut_mdf_with_options("input.mf4", "slim.mf4", &[(10.0, 20.0)], &options)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CutOptions {
+    /// Names of the channels to keep; `None` keeps all
+    channels: Option<Vec<String>>,
+    /// Names of the channel groups to keep; `None` keeps all
+    groups: Option<Vec<String>>,
+}
+
+impl CutOptions {
+    /// Options keeping all channel groups and channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only the channels with these names.
+    ///
+    /// The time channel of a group is kept along with any of its channels,
+    /// and groups without kept channels are dropped. Channels are packed
+    /// into smaller records.
+    pub fn with_channels(mut self, names: &[&str]) -> Self {
+        self.set_channels(names);
+        self
+    }
+
+    /// Keep only the channels with these names (see
+    /// [`with_channels()`](Self::with_channels)).
+    pub fn set_channels(&mut self, names: &[&str]) {
+        self.channels = Some(names.iter().map(|name| name.to_string()).collect());
+    }
+
+    /// Keep only the channel groups with these (acquisition) names.
+    pub fn with_groups(mut self, names: &[&str]) -> Self {
+        self.set_groups(names);
+        self
+    }
+
+    /// Keep only the channel groups with these (acquisition) names.
+    pub fn set_groups(&mut self, names: &[&str]) {
+        self.groups = Some(names.iter().map(|name| name.to_string()).collect());
+    }
+
+    /// Positions of the kept channels of a group; empty if the group is
+    /// dropped.
+    pub(crate) fn kept_channels(
+        &self,
+        group_name: Option<&str>,
+        channels: &[ChannelBlock],
+        time_idx: Option<usize>,
+    ) -> Vec<usize> {
+        let group_kept =
+            |groups: &Vec<String>| group_name.is_some_and(|name| groups.iter().any(|g| g == name));
+        if !self.groups.as_ref().is_none_or(group_kept) {
+            return Vec::new();
         }
+        let Some(names) = &self.channels else {
+            return (0..channels.len()).collect();
+        };
+        let mut kept: Vec<usize> = channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| {
+                ch.name
+                    .as_deref()
+                    .is_some_and(|name| names.iter().any(|n| n == name))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        // Position at which the time channel is missing, if any
+        let missing_time = time_idx
+            .filter(|_| !kept.is_empty())
+            .and_then(|t| kept.binary_search(&t).err().map(|pos| (pos, t)));
+        if let Some((pos, time_idx)) = missing_time {
+            kept.insert(pos, time_idx);
+        }
+        kept
+    }
+}
+
+/// Position of the time (master) channel of a channel group.
+pub(crate) fn time_channel_index(cg: &RawChannelGroup) -> Option<usize> {
+    cg.raw_channels
+        .iter()
+        .position(|ch| ch.block.channel_type == 2 && ch.block.sync_type == 1)
+}
+
+/// Decode and convert the value of `ch` from a record (or VLSD value) slice.
+fn decode_value(
+    mdf: &MdfFile,
+    dg: &RawDataGroup,
+    ch: &ChannelBlock,
+    slice: &[u8],
+) -> Result<DecodedValue> {
+    let dv = decode_channel_value(slice, dg.block.record_id_size as usize, ch)
+        .unwrap_or(DecodedValue::Unknown);
+    ch.apply_conversion_value(dv, &mdf.mmap)
+}
+
+/// Time in seconds of a decoded master value; `None` if it is not numeric.
+fn time_value(value: DecodedValue) -> Option<f64> {
+    match value {
+        DecodedValue::Float(f) => Some(f),
+        DecodedValue::UnsignedInteger(u) => Some(u as f64),
+        DecodedValue::SignedInteger(i) => Some(i as f64),
+        _ => None,
     }
-    Ok(Some(rec))
 }
 
 /// Cut a segment of an MDF file based on time stamps.
 ///
 /// The input file is scanned for a master time channel (channel type `2` and
 /// sync type `1`). Only records whose time value lies in the inclusive range
-/// `[start_time, end_time]` are copied to the new file.
+/// `[start_time, end_time]` are copied to the new file. Data stored in DL
+/// lists and DZ blocks is read like by the reader; compressed data requires
+/// the `compression` feature. All attachments are copied, as are the events
+/// synchronized on time that fall within the segment.
 ///
 /// # Arguments
 /// * `input_path` - Path to the source MF4 file
@@ -47,97 +180,618 @@ pub fn cut_mdf_by_time(
     start_time: f64,
     end_time: f64,
 ) -> Result<()> {
-    let mdf = MdfFile::parse_from_file(input_path)?;
+    cut_mdf_by_times(input_path, output_path, &[(start_time, end_time)])
+}
+
+/// Cut several time windows of an MDF file into one file.
+///
+/// Works like [`cut_mdf_by_time()`] with a list of inclusive
+/// `(start_time, end_time)` windows, all extracted in a single pass over the
+/// source. The records of all windows are written in source order; records
+/// in overlapping windows are written once. Time values are copied
+/// unchanged, so the windows keep their timestamps relative to the start of
+/// the recording. Events within any window and all attachments are copied.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Destination path for the trimmed file
+/// * `windows` - Time windows in seconds
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading or writing fails.
+pub fn cut_mdf_by_times(input_path: &str, output_path: &str, windows: &[(f64, f64)]) -> Result<()> {
+    cut_mdf_with_options(input_path, output_path, windows, &CutOptions::default())
+}
+
+/// Cut time windows of an MDF file, keeping only the channel groups and
+/// channels selected by `options`.
+///
+/// Works like [`cut_mdf_by_times()`] otherwise.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Destination path for the trimmed file
+/// * `windows` - Time windows in seconds
+/// * `options` - Channel groups and channels to keep
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading or writing fails.
+pub fn cut_mdf_with_options(
+    input_path: &str,
+    output_path: &str,
+    windows: &[(f64, f64)],
+    options: &CutOptions,
+) -> Result<()> {
     let mut writer = MdfWriter::new(output_path)?;
-    writer.init_mdf_file()?;
+    cut_mdf_to_writer(input_path, &mut writer, windows, options)
+}
+
+/// Cut time windows of an MDF file into any [`MdfWriter`] backend.
+///
+/// Works like [`cut_mdf_with_options()`], but writes through `writer`
+/// instead of to a path, so the result can go to memory, a socket or an
+/// uploader. `writer` must be new; the file is initialized and finalized
+/// here.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::MdfWriter;
+/// use mdf4_rs::cut::{CutOptions, cut_mdf_to_writer};
+///
+/// let mut writer = MdfWriter::in_memory();
+/// cut_mdf_to_writer("input.mf4", &mut writer, &[(1.0, 2.0)], &CutOptions::new())?;
+/// let bytes = writer.into_inner().into_inner();
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `writer` - Destination of the trimmed file
+/// * `windows` - Time windows in seconds
+/// * `options` - Channel groups and channels to keep
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading or writing fails.
+pub fn cut_mdf_to_writer<W: MdfWrite>(
+    input_path: &str,
+    writer: &mut MdfWriter<W>,
+    windows: &[(f64, f64)],
+    options: &CutOptions,
+) -> Result<()> {
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    cut_segments(&mdf, std::slice::from_mut(writer), windows, None, options)
+}
 
+/// Cut several time windows of an MDF file into one file per window.
+///
+/// Like [`cut_mdf_by_times()`], but the records of `windows[i]` are written
+/// to `output_paths[i]`, along with the events within that window. All
+/// files are written in a single pass over the source.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_paths` - Destination path for each window
+/// * `windows` - Time windows in seconds
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if the number of paths and
+/// windows differs or reading or writing fails.
+pub fn cut_mdf_by_times_to_files(
+    input_path: &str,
+    output_paths: &[&str],
+    windows: &[(f64, f64)],
+) -> Result<()> {
+    if output_paths.len() != windows.len() {
+        return Err(Error::BlockSerializationError(format!(
+            "{} output paths for {} time windows",
+            output_paths.len(),
+            windows.len()
+        )));
+    }
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    let mut writers = output_paths
+        .iter()
+        .map(|path| MdfWriter::new(path))
+        .collect::<Result<Vec<_>>>()?;
+    cut_segments(&mdf, &mut writers, windows, None, &CutOptions::default())
+}
+
+/// Cut a range of records of a channel group from an MDF file.
+///
+/// Records `start..start + count` of the channel group `group_index`
+/// (counted over all data groups in file order) are copied. Other channel
+/// groups keep the records within the time span of the copied records,
+/// like [`cut_mdf_by_time()`] would; if the group has no time channel, only
+/// groups without a time channel keep records.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Destination path for the trimmed file
+/// * `group_index` - Channel group whose records are selected
+/// * `start` - Index of the first record to copy
+/// * `count` - Number of records to copy
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if the group does not exist or
+/// reading or writing fails.
+pub fn cut_mdf_by_records(
+    input_path: &str,
+    output_path: &str,
+    group_index: usize,
+    start: u64,
+    count: u64,
+) -> Result<()> {
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    let (dg, cg) = channel_group(&mdf, group_index)?;
+    let end = start.saturating_add(count);
+    let runs = record_runs(&mdf, dg, cg, end, |index, _| Ok(index >= start))?;
+    cut_selected_runs(&mdf, output_path, group_index, runs)
+}
+
+/// Cut the records of an MDF file for which a channel value satisfies a
+/// condition.
+///
+/// The records of the channel group holding `channel_name` whose converted
+/// value satisfies `predicate` are copied, e.g. the records where `Speed`
+/// exceeds 100:
+///
+/// ```no_run
+/// use mdf4_rs::cut::cut_mdf_where;
+///
+/// cut_mdf_where("input.mf4", "fast.mf4", "Speed", |speed| {
+///     speed.as_f64().is_some_and(|speed| speed > 100.0)
+/// })?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// Other channel groups keep the records within the time spans of the runs
+/// of consecutive selected records (see [`cut_mdf_by_records()`]).
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Destination path for the trimmed file
+/// * `channel_name` - Channel whose values are tested; the first channel
+///   with this name is used
+/// * `predicate` - Condition on the channel value of a record
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if the channel does not exist
+/// or has variable length values, or reading or writing fails.
+pub fn cut_mdf_where<F>(
+    input_path: &str,
+    output_path: &str,
+    channel_name: &str,
+    mut predicate: F,
+) -> Result<()>
+where
+    F: FnMut(&DecodedValue) -> bool,
+{
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    let mut found = None;
+    let groups = mdf
+        .data_groups
+        .iter()
+        .flat_map(|dg| dg.channel_groups.iter().map(move |cg| (dg, cg)));
+    'search: for (group_index, (dg, cg)) in groups.enumerate() {
+        for ch in &cg.raw_channels {
+            let mut block = ch.block.clone();
+            block.resolve_name(&mdf.mmap)?;
+            if block.name.as_deref() == Some(channel_name) {
+                found = Some((group_index, dg, cg, block));
+                break 'search;
+            }
+        }
+    }
+    let (group_index, dg, cg, channel) = found.ok_or_else(|| {
+        Error::BlockSerializationError(format!("Channel '{}' not found", channel_name))
+    })?;
+    if channel.channel_type == 1 && channel.data_addr != 0 {
+        return Err(Error::BlockSerializationError(format!(
+            "Channel '{}' has variable length values",
+            channel_name
+        )));
+    }
+
+    let runs = record_runs(&mdf, dg, cg, u64::MAX, |_, record| {
+        Ok(predicate(&decode_value(&mdf, dg, &channel, record)?))
+    })?;
+    cut_selected_runs(&mdf, output_path, group_index, runs)
+}
+
+/// The data group and channel group of a channel group index counted over
+/// all data groups.
+fn channel_group(mdf: &MdfFile, group_index: usize) -> Result<(&RawDataGroup, &RawChannelGroup)> {
+    mdf.data_groups
+        .iter()
+        .flat_map(|dg| dg.channel_groups.iter().map(move |cg| (dg, cg)))
+        .nth(group_index)
+        .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))
+}
+
+/// Consecutive records of a channel group, with the time values of the first
+/// and last record if the group has a time channel.
+type RecordRun = (Range<u64>, Option<(f64, f64)>);
+
+/// Runs of consecutive records of a channel group for which `keep` holds.
+/// Records from `end` on are not visited.
+fn record_runs(
+    mdf: &MdfFile,
+    dg: &RawDataGroup,
+    cg: &RawChannelGroup,
+    end: u64,
+    mut keep: impl FnMut(u64, &[u8]) -> Result<bool>,
+) -> Result<Vec<RecordRun>> {
+    let time_channel = time_channel_index(cg).map(|i| &cg.raw_channels[i].block);
+    let mut runs: Vec<RecordRun> = Vec::new();
+    let mut index = 0u64;
+    for_each_record(dg, cg, &mdf.mmap, |record| {
+        if index >= end {
+            return Ok(false);
+        }
+        if keep(index, record)? {
+            let time = match time_channel {
+                Some(ch) => time_value(decode_value(mdf, dg, ch, record)?),
+                None => None,
+            };
+            match runs.last_mut() {
+                Some((records, times)) if records.end == index => {
+                    records.end += 1;
+                    if let (Some((_, last)), Some(time)) = (times.as_mut(), time) {
+                        *last = time;
+                    }
+                }
+                _ => runs.push((index..index + 1, time.map(|t| (t, t)))),
+            }
+        }
+        index += 1;
+        Ok(true)
+    })?;
+    Ok(runs)
+}
+
+/// Write the record runs of a channel group, and the records of the other
+/// groups within the time spans of the runs, to `output_path`.
+fn cut_selected_runs(
+    mdf: &MdfFile,
+    output_path: &str,
+    group_index: usize,
+    runs: Vec<RecordRun>,
+) -> Result<()> {
+    let windows: Vec<(f64, f64)> = runs.iter().filter_map(|(_, times)| *times).collect();
+    let records: Vec<Range<u64>> = runs.into_iter().map(|(records, _)| records).collect();
+    let mut writers = [MdfWriter::new(output_path)?];
+    cut_segments(
+        mdf,
+        &mut writers,
+        &windows,
+        Some((group_index, &records)),
+        &CutOptions::default(),
+    )
+}
+
+/// Copy the records of `mdf` within `windows` to `writers`.
+///
+/// With a single writer, records within any window are written to it;
+/// otherwise `writers[i]` receives the records of `windows[i]`. Records of
+/// channel groups without a time channel are written to all writers.
+///
+/// `selected` optionally names a channel group (counted over all data
+/// groups) and sorted record ranges of it, which are copied to the single
+/// writer instead of the records within the windows. Only the channel groups
+/// and channels selected by `options` are written.
+fn cut_segments<W: MdfWrite>(
+    mdf: &MdfFile,
+    writers: &mut [MdfWriter<W>],
+    windows: &[(f64, f64)],
+    selected: Option<(usize, &[Range<u64>])>,
+    options: &CutOptions,
+) -> Result<()> {
+    let per_window = writers.len() > 1;
+    // Records are assumed to be sorted by time, so nothing follows this
+    let last_end = windows
+        .iter()
+        .map(|&(_, end)| end)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    for writer in writers.iter_mut() {
+        writer.init_mdf_file()?;
+        writer.set_start_time_ns(mdf.header.start_time_ns)?;
+    }
+
+    let mut targets = Vec::with_capacity(writers.len());
+    let mut group_index = 0;
     for dg in &mdf.data_groups {
-        let mut prev_cg: Option<String> = None;
+        let mut prev_cgs: Vec<Option<String>> = vec![None; writers.len()];
         for cg in &dg.channel_groups {
-            let cg_id = writer.add_channel_group(prev_cg.as_deref(), |_| {})?;
-            prev_cg = Some(cg_id.clone());
-
-            let mut prev_cn: Option<String> = None;
             let mut channel_blocks: Vec<ChannelBlock> = Vec::new();
             for ch in &cg.raw_channels {
                 let mut block = ch.block.clone();
                 block.resolve_name(&mdf.mmap)?;
-                let id = writer.add_channel(&cg_id, prev_cn.as_deref(), |c| {
-                    *c = block.clone();
-                })?;
-                prev_cn = Some(id);
                 channel_blocks.push(block);
             }
 
-            // Prepare iterators over raw records for each channel
-            let mut iters = Vec::new();
-            for ch in &cg.raw_channels {
-                iters.push(ch.records(dg, cg, &mdf.mmap)?);
+            let time_idx = time_channel_index(cg);
+            // Record ranges selected in this group instead of time windows
+            let selected_runs = selected
+                .filter(|(group, _)| *group == group_index)
+                .map(|(_, runs)| runs);
+            group_index += 1;
+
+            let group_name = read_string_block(&mdf.mmap, cg.block.acq_name_addr)?;
+            let kept = options.kept_channels(group_name.as_deref(), &channel_blocks, time_idx);
+            if kept.is_empty() {
+                continue;
             }
+            // Dropped channels leave no gaps in the records
+            let pack = kept.len() < channel_blocks.len();
 
-            // Identify the time (master) channel index
-            let mut time_idx: Option<usize> = None;
-            for (idx, ch) in cg.raw_channels.iter().enumerate() {
-                if ch.block.channel_type == 2 && ch.block.sync_type == 1 {
-                    time_idx = Some(idx);
-                    break;
+            let mut cg_ids = Vec::with_capacity(writers.len());
+            for (writer, prev_cg) in writers.iter_mut().zip(&mut prev_cgs) {
+                let cg_id = writer.add_channel_group(prev_cg.as_deref(), |_| {})?;
+                *prev_cg = Some(cg_id.clone());
+                if let Some(name) = &group_name {
+                    writer.set_channel_group_name(&cg_id, name)?;
                 }
-            }
-            let time_idx = match time_idx {
-                Some(i) => i,
-                None => {
-                    // No time channel found; copy all records
-                    writer.start_data_block_for_cg(&cg_id, dg.block.record_id_size)?;
-                    while let Some(rec) = next_record_set(&mut iters)? {
-                        let mut vals = Vec::new();
-                        for (slice, ch) in rec.into_iter().zip(channel_blocks.iter()) {
-                            let dv =
-                                decode_channel_value(slice, dg.block.record_id_size as usize, ch)
-                                    .unwrap_or(DecodedValue::Unknown);
-                            vals.push(ch.apply_conversion_value(dv, &mdf.mmap)?);
+
+                let mut prev_cn: Option<String> = None;
+                for &i in &kept {
+                    let id = writer.add_channel(&cg_id, prev_cn.as_deref(), |c| {
+                        *c = channel_blocks[i].clone();
+                        if pack {
+                            c.byte_offset = 0;
                         }
-                        writer.write_record(&cg_id, &vals)?;
-                    }
-                    writer.finish_data_block(&cg_id)?;
-                    continue;
+                    })?;
+                    prev_cn = Some(id);
                 }
-            };
+                writer.start_data_block_for_cg(&cg_id, dg.block.record_id_size)?;
+                cg_ids.push(cg_id);
+            }
 
-            writer.start_data_block_for_cg(&cg_id, dg.block.record_id_size)?;
-
-            while let Some(rec) = next_record_set(&mut iters)? {
-                // Decode time value
-                let time_val = {
-                    let ch = &channel_blocks[time_idx];
-                    let dv =
-                        decode_channel_value(rec[time_idx], dg.block.record_id_size as usize, ch)
-                            .unwrap_or(DecodedValue::Unknown);
-                    match ch.apply_conversion_value(dv, &mdf.mmap)? {
-                        DecodedValue::Float(f) => f,
-                        DecodedValue::UnsignedInteger(u) => u as f64,
-                        DecodedValue::SignedInteger(i) => i as f64,
-                        _ => continue,
+            // Values of VLSD channels are read from their signal data
+            let mut vlsd_iters = Vec::with_capacity(cg.raw_channels.len());
+            for (i, ch) in cg.raw_channels.iter().enumerate() {
+                let is_vlsd = ch.block.channel_type == 1 && ch.block.data_addr != 0;
+                vlsd_iters.push(if is_vlsd && kept.contains(&i) {
+                    Some(ch.records(dg, cg, &mdf.mmap)?)
+                } else {
+                    None
+                });
+            }
+            let mut record_index = 0u64;
+            let mut run = 0;
+
+            for_each_record(dg, cg, &mdf.mmap, |record| {
+                let mut slices = Vec::with_capacity(vlsd_iters.len());
+                for iter in &mut vlsd_iters {
+                    match iter {
+                        Some(iter) => match iter.next() {
+                            Some(slice) => slices.push(slice?),
+                            None => return Ok(false),
+                        },
+                        None => slices.push(record),
                     }
-                };
+                }
 
-                if time_val < start_time {
-                    continue;
+                targets.clear();
+                match (selected_runs, time_idx) {
+                    (Some(runs), _) => {
+                        while runs.get(run).is_some_and(|r| r.end <= record_index) {
+                            run += 1;
+                        }
+                        let Some(current) = runs.get(run) else {
+                            return Ok(false);
+                        };
+                        if current.contains(&record_index) {
+                            targets.push(0);
+                        }
+                        record_index += 1;
+                    }
+                    (None, Some(time_idx)) => {
+                        let value =
+                            decode_value(mdf, dg, &channel_blocks[time_idx], slices[time_idx])?;
+                        let Some(time_val) = time_value(value) else {
+                            return Ok(true);
+                        };
+                        if time_val - last_end > f64::EPSILON {
+                            return Ok(false);
+                        }
+                        select_targets(time_val, windows, per_window, &mut targets);
+                    }
+                    // No time channel found; copy all records
+                    (None, None) => targets.extend(0..writers.len()),
                 }
-                if time_val - end_time > f64::EPSILON {
-                    break;
+                if targets.is_empty() {
+                    return Ok(true);
                 }
 
-                let mut vals = Vec::new();
-                for (slice, ch) in rec.into_iter().zip(channel_blocks.iter()) {
-                    let dv = decode_channel_value(slice, dg.block.record_id_size as usize, ch)
-                        .unwrap_or(DecodedValue::Unknown);
-                    vals.push(ch.apply_conversion_value(dv, &mdf.mmap)?);
+                let vals = kept
+                    .iter()
+                    .map(|&i| decode_value(mdf, dg, &channel_blocks[i], slices[i]))
+                    .collect::<Result<Vec<_>>>()?;
+                for &target in &targets {
+                    writers[target].write_record(&cg_ids[target], &vals)?;
                 }
-                writer.write_record(&cg_id, &vals)?;
+                Ok(true)
+            })?;
+            for (writer, cg_id) in writers.iter_mut().zip(&cg_ids) {
+                writer.finish_data_block(cg_id)?;
             }
-            writer.finish_data_block(&cg_id)?;
         }
     }
 
-    writer.finalize()
+    copy_attachments_and_events(mdf, writers, windows, per_window)?;
+    for writer in writers.iter_mut() {
+        writer.finalize()?;
+    }
+    Ok(())
+}
+
+/// Add the writers receiving values at `time` to `targets`.
+fn select_targets(time: f64, windows: &[(f64, f64)], per_window: bool, targets: &mut Vec<usize>) {
+    let in_window = |&(start, end): &(f64, f64)| time >= start && time - end <= f64::EPSILON;
+    if per_window {
+        targets.extend(
+            windows
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| in_window(w))
+                .map(|(i, _)| i),
+        );
+    } else if windows.iter().any(in_window) {
+        targets.push(0);
+    }
+}
+
+/// The source bytes of the block at `addr`.
+pub(crate) fn block_bytes(mmap: &[u8], addr: u64) -> Result<&[u8]> {
+    let offset = u64_to_usize(addr, "block address")?;
+    let too_short = |expected| Error::TooShortBuffer {
+        actual: mmap.len(),
+        expected,
+        file: file!(),
+        line: line!(),
+    };
+    let header = mmap
+        .get(offset..offset + 24)
+        .ok_or_else(|| too_short(offset + 24))?;
+    let length = u64_to_usize(BlockHeader::from_bytes(header)?.length, "block length")?;
+    mmap.get(offset..offset + length)
+        .ok_or_else(|| too_short(offset + length))
+}
+
+/// Copy the text or metadata block at `addr` to `writer` and return its new
+/// address; 0 stays 0. These blocks have no links, so they are copied as is.
+pub(crate) fn copy_text_block<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    mmap: &[u8],
+    addr: u64,
+) -> Result<u64> {
+    if addr == 0 {
+        return Ok(0);
+    }
+    writer.write_block(block_bytes(mmap, addr)?)
+}
+
+/// Parse the blocks of the list starting at `first`, which continues at the
+/// address returned by `next`.
+fn block_list<'a, B: BlockParse<'a>>(
+    mmap: &'a [u8],
+    first: u64,
+    next: impl Fn(&B) -> u64,
+) -> Result<Vec<(u64, B)>> {
+    let mut blocks: Vec<(u64, B)> = Vec::new();
+    let mut addr = first;
+    // Guard against cyclic lists in corrupted files
+    while addr != 0 && !blocks.iter().any(|(a, _)| *a == addr) {
+        let block = B::from_bytes(block_bytes(mmap, addr)?)?;
+        let next_addr = next(&block);
+        blocks.push((addr, block));
+        addr = next_addr;
+    }
+    Ok(blocks)
+}
+
+/// Copy all attachments of the source to every writer, and the events
+/// synchronized on time that fall within the windows of each writer.
+///
+/// Events keep their synchronization values, which (like the copied time
+/// values) are relative to the unchanged start time. References to other
+/// events are kept if those are copied too; channel and group scopes are
+/// dropped.
+pub(crate) fn copy_attachments_and_events<W: MdfWrite>(
+    mdf: &MdfFile,
+    writers: &mut [MdfWriter<W>],
+    windows: &[(f64, f64)],
+    per_window: bool,
+) -> Result<()> {
+    let mmap = &mdf.mmap;
+    let attachments = block_list(
+        mmap,
+        mdf.header.first_attachment_addr,
+        |at: &AttachmentBlock| at.next_at_addr,
+    )?;
+    let events = block_list(mmap, mdf.header.first_event_addr, |ev: &EventBlock| {
+        ev.next_ev_addr
+    })?;
+    if attachments.is_empty() && events.is_empty() {
+        return Ok(());
+    }
+
+    // Writers receiving each event
+    let mut event_targets = Vec::with_capacity(events.len());
+    for (_, event) in &events {
+        let mut targets = Vec::new();
+        if event.sync_type == EventSyncType::Time {
+            select_targets(event.sync_value(), windows, per_window, &mut targets);
+        }
+        event_targets.push(targets);
+    }
+
+    for (w, writer) in writers.iter_mut().enumerate() {
+        let hd_addr = writer
+            .get_block_position("hd_block")
+            .ok_or_else(|| Error::BlockSerializationError("Missing header block".to_string()))?;
+
+        // Link of the list's first block, then of the previous block's next link
+        let mut prev_link = hd_addr + 48;
+        let mut at_addrs = Vec::with_capacity(attachments.len());
+        for (_, attachment) in &attachments {
+            let mut block = attachment.clone();
+            block.next_at_addr = 0;
+            block.filename_addr = copy_text_block(writer, mmap, attachment.filename_addr)?;
+            block.mimetype_addr = copy_text_block(writer, mmap, attachment.mimetype_addr)?;
+            block.comment_addr = copy_text_block(writer, mmap, attachment.comment_addr)?;
+            let addr = writer.write_block(&block.to_bytes()?)?;
+            writer.update_link(prev_link, addr)?;
+            prev_link = addr + 24;
+            at_addrs.push(addr);
+        }
+
+        let mut prev_link = hd_addr + 56;
+        let mut ev_addrs: Vec<Option<u64>> = vec![None; events.len()];
+        for (i, (_, event)) in events.iter().enumerate() {
+            if !event_targets[i].contains(&w) {
+                continue;
+            }
+            let mut block = event.clone();
+            block.next_ev_addr = 0;
+            block.parent_ev_addr = 0;
+            block.range_ev_addr = 0;
+            block.name_addr = copy_text_block(writer, mmap, event.name_addr)?;
+            block.comment_addr = copy_text_block(writer, mmap, event.comment_addr)?;
+            block.scope_addrs.clear();
+            block.scope_count = 0;
+            block.attachment_addrs = event
+                .attachment_addrs
+                .iter()
+                .filter_map(|addr| {
+                    let i = attachments.iter().position(|(a, _)| a == addr)?;
+                    Some(at_addrs[i])
+                })
+                .collect();
+            block.attachment_count = block.attachment_addrs.len() as u16;
+            let addr = writer.write_block(&block.to_bytes()?)?;
+            writer.update_link(prev_link, addr)?;
+            prev_link = addr + 24;
+            ev_addrs[i] = Some(addr);
+        }
+
+        // Parent and range links, now that all copied events have addresses
+        let copied_at = |addr: u64| {
+            let i = events.iter().position(|(a, _)| *a == addr)?;
+            ev_addrs[i]
+        };
+        for (i, (_, event)) in events.iter().enumerate() {
+            let Some(addr) = ev_addrs[i] else {
+                continue;
+            };
+            if let Some(parent) = copied_at(event.parent_ev_addr) {
+                writer.update_link(addr + 32, parent)?;
+            }
+            if let Some(range) = copied_at(event.range_ev_addr) {
+                writer.update_link(addr + 40, range)?;
+            }
+        }
+    }
+    Ok(())
 }
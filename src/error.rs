@@ -98,6 +98,19 @@ pub enum Error {
     /// Failed to serialize a block to bytes.
     BlockSerializationError(String),
 
+    /// Writing a record would exceed the writer's buffer limit.
+    ///
+    /// Returned when a `BufferLimit::ErrorAt` limit is configured; flush the
+    /// writer before retrying the write.
+    BufferLimitExceeded {
+        /// Record bytes written since the last flush
+        buffered: u64,
+        /// Size of the rejected write
+        incoming: u64,
+        /// The configured limit
+        limit: u64,
+    },
+
     /// A conversion chain exceeded the maximum allowed depth.
     ///
     /// MDF supports chained conversions where one conversion references another.
@@ -114,6 +127,12 @@ pub enum Error {
         /// The address where the cycle was detected
         address: u64,
     },
+
+    /// An index does not describe the file it is used with.
+    ///
+    /// The file was modified, replaced or has grown since the index was
+    /// built; rebuild (or refresh) the index.
+    IndexMismatch(String),
 }
 
 impl fmt::Display for Error {
@@ -150,6 +169,14 @@ impl fmt::Display for Error {
             Error::InvalidVersionString(s) => write!(f, "Invalid version string: {s}"),
             Error::BlockLinkError(s) => write!(f, "Block linking error: {s}"),
             Error::BlockSerializationError(s) => write!(f, "Block serialization error: {s}"),
+            Error::BufferLimitExceeded {
+                buffered,
+                incoming,
+                limit,
+            } => write!(
+                f,
+                "Buffer limit exceeded: {buffered} bytes buffered + {incoming} bytes > {limit} bytes"
+            ),
             Error::ConversionChainTooDeep { max_depth } => {
                 write!(
                     f,
@@ -162,6 +189,7 @@ impl fmt::Display for Error {
                     "Conversion chain cycle detected at block address {address:#x}"
                 )
             }
+            Error::IndexMismatch(s) => write!(f, "Index does not match source: {s}"),
         }
     }
 }
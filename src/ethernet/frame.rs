@@ -5,6 +5,7 @@
 
 use alloc::vec::Vec;
 
+use crate::blocks::SourceBlock;
 use crate::bus_logging::BusFrame;
 
 /// Maximum Ethernet payload size (standard MTU).
@@ -14,6 +15,9 @@ pub const MAX_ETHERNET_PAYLOAD: usize = 1500;
 /// 6 (dst MAC) + 6 (src MAC) + 2 (EtherType) + 1500 (payload) = 1514 bytes
 pub const MAX_ETHERNET_FRAME: usize = 1514;
 
+/// ETH_Frame header size: flags(1) + length(2).
+pub(super) const ETH_FRAME_HEADER_SIZE: usize = 3;
+
 /// Jumbo frame maximum payload size.
 pub const MAX_JUMBO_PAYLOAD: usize = 9000;
 
@@ -114,6 +118,11 @@ impl EthernetFlags {
         self.0 & Self::VLAN_TAGGED != 0
     }
 
+    /// Check if the frame has a CRC error or was truncated.
+    pub fn has_error(self) -> bool {
+        self.0 & (Self::CRC_ERROR | Self::TRUNCATED) != 0
+    }
+
     /// Set the transmit flag.
     pub fn with_tx(self, tx: bool) -> Self {
         if tx {
@@ -132,6 +141,24 @@ impl EthernetFlags {
         }
     }
 
+    /// Set the truncated flag.
+    pub fn with_truncated(self, truncated: bool) -> Self {
+        if truncated {
+            Self(self.0 | Self::TRUNCATED)
+        } else {
+            Self(self.0 & !Self::TRUNCATED)
+        }
+    }
+
+    /// Set the CRC error flag.
+    pub fn with_crc_error(self, error: bool) -> Self {
+        if error {
+            Self(self.0 | Self::CRC_ERROR)
+        } else {
+            Self(self.0 & !Self::CRC_ERROR)
+        }
+    }
+
     /// Set the VLAN tagged flag.
     pub fn with_vlan_tagged(self, tagged: bool) -> Self {
         if tagged {
@@ -337,16 +364,49 @@ impl EthernetFrame {
         self.flags = self.flags.with_fcs_valid(valid);
         self
     }
+
+    /// Set the CRC error flag, for frames received with a bad FCS.
+    pub fn with_crc_error(mut self, error: bool) -> Self {
+        self.flags = self.flags.with_crc_error(error);
+        self
+    }
+
+    /// Set the truncated flag, for frames captured only in part.
+    pub fn with_truncated(mut self, truncated: bool) -> Self {
+        self.flags = self.flags.with_truncated(truncated);
+        self
+    }
 }
 
 impl BusFrame for EthernetFrame {
+    const CHANNEL_NAME: &'static str = "ETH_Frame";
+    const RECORD_SIZE: usize = ETH_FRAME_HEADER_SIZE + MAX_ETHERNET_FRAME;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::ethernet()
+    }
+
+    /// Build the ETH_Frame ByteArray:
+    /// - Byte 0: Flags
+    /// - Bytes 1-2: Frame length (little-endian)
+    /// - Bytes 3+: Frame data, cut to [`MAX_ETHERNET_FRAME`] bytes with the
+    ///   truncated flag set
     fn to_mdf_bytes(&self) -> Vec<u8> {
-        self.to_bytes()
+        let data = self.to_bytes();
+        let truncated = data.len() > MAX_ETHERNET_FRAME;
+        let mut bytes = Vec::with_capacity(Self::RECORD_SIZE);
+        bytes.push(
+            self.flags
+                .with_truncated(truncated || self.flags.is_truncated())
+                .to_byte(),
+        );
+        bytes.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&data[..data.len().min(MAX_ETHERNET_FRAME)]);
+        bytes
     }
 
     fn mdf_size(&self) -> usize {
-        // ETH_Frame header (4 bytes) + Ethernet header (14) + payload
-        4 + ETH_HEADER_SIZE + self.payload.len()
+        ETH_FRAME_HEADER_SIZE + self.len().min(MAX_ETHERNET_FRAME)
     }
 }
 
@@ -394,6 +454,16 @@ mod tests {
         assert!(flags.is_tx());
         assert!(flags.fcs_valid());
         assert!(flags.has_vlan_tag());
+        assert!(!flags.has_error());
+
+        let flags = EthernetFlags::rx().with_crc_error(true);
+        assert!(flags.has_crc_error());
+        assert!(flags.has_error());
+        assert!(!flags.with_crc_error(false).has_error());
+
+        let flags = EthernetFlags::rx().with_truncated(true);
+        assert!(flags.is_truncated());
+        assert!(flags.has_error());
     }
 
     #[test]
@@ -470,4 +540,34 @@ mod tests {
         assert_eq!(frame.vlan_tci, Some(0xA064));
         assert!(frame.flags.has_vlan_tag());
     }
+
+    #[test]
+    fn test_frame_to_mdf_bytes() {
+        let frame = EthernetFrame::new(
+            MacAddress::broadcast(),
+            MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            ethertype::IPV4,
+            vec![0xAB; 10],
+        )
+        .with_tx();
+        let bytes = frame.to_mdf_bytes();
+        assert_eq!(bytes.len(), frame.mdf_size());
+        assert_eq!(bytes[0], frame.flags.to_byte());
+        assert_eq!(u16::from_le_bytes([bytes[1], bytes[2]]), 24);
+        assert_eq!(&bytes[ETH_FRAME_HEADER_SIZE..], frame.to_bytes());
+
+        let jumbo = EthernetFrame::new(
+            MacAddress::broadcast(),
+            MacAddress::broadcast(),
+            ethertype::IPV4,
+            vec![0; MAX_JUMBO_PAYLOAD],
+        );
+        let bytes = jumbo.to_mdf_bytes();
+        assert_eq!(bytes.len(), EthernetFrame::RECORD_SIZE);
+        assert!(EthernetFlags::from_byte(bytes[0]).is_truncated());
+        assert_eq!(
+            u16::from_le_bytes([bytes[1], bytes[2]]) as usize,
+            ETH_HEADER_SIZE + MAX_JUMBO_PAYLOAD
+        );
+    }
 }
@@ -0,0 +1,517 @@
+//! IP, UDP and TCP header decomposition of Ethernet frames.
+//!
+//! [`IpPacket`] reads the IPv4 or IPv6 header and the UDP or TCP ports of a
+//! frame, so network traffic can be filtered by address, protocol and port
+//! without exporting it to pcap first. It works on frames being logged as
+//! well as on frames read back from an MDF file.
+//!
+//! [`RawEthernetLogger`](super::RawEthernetLogger) uses it to log the
+//! headers of every IP frame into a `{source_name}_ETH_IP` channel group
+//! when [`set_ip_decomposition`](super::RawEthernetLogger::set_ip_decomposition)
+//! is enabled.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::ethernet::{IpPacket, ip_protocol};
+//!
+//! if let Some(packet) = IpPacket::from_frame_bytes(&frame_bytes) {
+//!     if packet.protocol == ip_protocol::UDP && packet.destination_port == Some(30490) {
+//!         println!("SOME/IP-SD from {}: {:02X?}", packet.source, packet.payload);
+//!     }
+//! }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use super::frame::{ETH_HEADER_SIZE, EthernetFlags, EthernetFrame, ethertype};
+use crate::bus_logging::timestamp_to_seconds;
+
+/// Common IP protocol numbers (IANA).
+pub mod ip_protocol {
+    /// Internet Control Message Protocol
+    pub const ICMP: u8 = 1;
+    /// Transmission Control Protocol
+    pub const TCP: u8 = 6;
+    /// User Datagram Protocol
+    pub const UDP: u8 = 17;
+    /// ICMP for IPv6
+    pub const ICMPV6: u8 = 58;
+}
+
+/// IPv4 header size without options.
+const IPV4_HEADER_SIZE: usize = 20;
+/// IPv6 fixed header size.
+const IPV6_HEADER_SIZE: usize = 40;
+/// UDP header size.
+const UDP_HEADER_SIZE: usize = 8;
+/// TCP header size without options.
+const TCP_HEADER_SIZE: usize = 20;
+
+/// IPv6 extension headers skipped to find the transport protocol.
+const IPV6_HOP_BY_HOP: u8 = 0;
+const IPV6_ROUTING: u8 = 43;
+const IPV6_FRAGMENT: u8 = 44;
+const IPV6_DESTINATION_OPTIONS: u8 = 60;
+
+/// The IP and transport headers of an Ethernet frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpPacket<'a> {
+    /// IP version, 4 or 6
+    pub version: u8,
+    /// Source address
+    pub source: IpAddr,
+    /// Destination address
+    pub destination: IpAddr,
+    /// Transport protocol (see [`ip_protocol`]); for IPv6 the header after
+    /// the extension headers
+    pub protocol: u8,
+    /// UDP or TCP source port
+    pub source_port: Option<u16>,
+    /// UDP or TCP destination port
+    pub destination_port: Option<u16>,
+    /// UDP or TCP payload, or the IP payload of other protocols
+    pub payload: &'a [u8],
+}
+
+impl<'a> IpPacket<'a> {
+    /// Decompose raw Ethernet frame bytes (Dst MAC + Src MAC + EtherType +
+    /// Payload), skipping an 802.1Q VLAN tag.
+    ///
+    /// Returns `None` if the frame does not carry a valid IPv4 or IPv6
+    /// packet.
+    pub fn from_frame_bytes(frame: &'a [u8]) -> Option<Self> {
+        if frame.len() < ETH_HEADER_SIZE {
+            return None;
+        }
+        let ether_type = u16::from_be_bytes([frame[12], frame[13]]);
+        if ether_type == ethertype::VLAN {
+            let tagged = frame.get(ETH_HEADER_SIZE + 2..ETH_HEADER_SIZE + 4)?;
+            let ether_type = u16::from_be_bytes([tagged[0], tagged[1]]);
+            return Self::parse(ether_type, &frame[ETH_HEADER_SIZE + 4..]);
+        }
+        Self::parse(ether_type, &frame[ETH_HEADER_SIZE..])
+    }
+
+    /// Decompose the payload of a frame with the given EtherType.
+    ///
+    /// Ethernet padding after the IP packet is ignored. Ports are only read
+    /// from the first fragment of a fragmented packet.
+    pub fn parse(ether_type: u16, packet: &'a [u8]) -> Option<Self> {
+        match ether_type {
+            ethertype::IPV4 => Self::parse_ipv4(packet),
+            ethertype::IPV6 => Self::parse_ipv6(packet),
+            _ => None,
+        }
+    }
+
+    fn parse_ipv4(packet: &'a [u8]) -> Option<Self> {
+        if packet.len() < IPV4_HEADER_SIZE || packet[0] >> 4 != 4 {
+            return None;
+        }
+        let header_len = (packet[0] & 0x0F) as usize * 4;
+        let total_len = u16::from_be_bytes([packet[2], packet[3]]) as usize;
+        if header_len < IPV4_HEADER_SIZE || total_len < header_len {
+            return None;
+        }
+        let fragment_offset = u16::from_be_bytes([packet[6], packet[7]]) & 0x1FFF;
+        let protocol = packet[9];
+        let source = Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]);
+        let destination = Ipv4Addr::new(packet[16], packet[17], packet[18], packet[19]);
+
+        // Truncated captures keep the bytes that are there
+        let end = total_len.min(packet.len());
+        let payload = packet.get(header_len..end)?;
+        Some(Self::with_transport(
+            4,
+            IpAddr::V4(source),
+            IpAddr::V4(destination),
+            protocol,
+            payload,
+            fragment_offset == 0,
+        ))
+    }
+
+    fn parse_ipv6(packet: &'a [u8]) -> Option<Self> {
+        if packet.len() < IPV6_HEADER_SIZE || packet[0] >> 4 != 6 {
+            return None;
+        }
+        let payload_len = u16::from_be_bytes([packet[4], packet[5]]) as usize;
+        let source: [u8; 16] = packet[8..24].try_into().ok()?;
+        let destination: [u8; 16] = packet[24..40].try_into().ok()?;
+
+        let end = (IPV6_HEADER_SIZE + payload_len).min(packet.len());
+        let mut payload = &packet[IPV6_HEADER_SIZE..end];
+        let mut next_header = packet[6];
+        let mut first_fragment = true;
+        loop {
+            let header_len = match next_header {
+                IPV6_HOP_BY_HOP | IPV6_ROUTING | IPV6_DESTINATION_OPTIONS => {
+                    (*payload.get(1)? as usize + 1) * 8
+                }
+                IPV6_FRAGMENT => {
+                    let offset = u16::from_be_bytes([*payload.get(2)?, *payload.get(3)?]) >> 3;
+                    first_fragment = offset == 0;
+                    8
+                }
+                _ => break,
+            };
+            next_header = *payload.first()?;
+            payload = payload.get(header_len..)?;
+        }
+
+        Some(Self::with_transport(
+            6,
+            IpAddr::V6(Ipv6Addr::from(source)),
+            IpAddr::V6(Ipv6Addr::from(destination)),
+            next_header,
+            payload,
+            first_fragment,
+        ))
+    }
+
+    /// Read the UDP or TCP header at the start of the IP payload.
+    fn with_transport(
+        version: u8,
+        source: IpAddr,
+        destination: IpAddr,
+        protocol: u8,
+        ip_payload: &'a [u8],
+        has_transport_header: bool,
+    ) -> Self {
+        let header_len = match protocol {
+            ip_protocol::UDP if ip_payload.len() >= UDP_HEADER_SIZE => Some(UDP_HEADER_SIZE),
+            ip_protocol::TCP if ip_payload.len() >= TCP_HEADER_SIZE => {
+                let data_offset = (ip_payload[12] >> 4) as usize * 4;
+                Some(data_offset.clamp(TCP_HEADER_SIZE, ip_payload.len()))
+            }
+            _ => None,
+        };
+        let mut packet = Self {
+            version,
+            source,
+            destination,
+            protocol,
+            source_port: None,
+            destination_port: None,
+            payload: ip_payload,
+        };
+        if let (Some(header_len), true) = (header_len, has_transport_header) {
+            packet.source_port = Some(u16::from_be_bytes([ip_payload[0], ip_payload[1]]));
+            packet.destination_port = Some(u16::from_be_bytes([ip_payload[2], ip_payload[3]]));
+            packet.payload = &ip_payload[header_len..];
+        }
+        packet
+    }
+}
+
+impl EthernetFrame {
+    /// Decompose the IP and transport headers of the frame.
+    ///
+    /// See [`IpPacket::parse`].
+    pub fn ip_packet(&self) -> Option<IpPacket<'_>> {
+        IpPacket::parse(self.ethertype, &self.payload)
+    }
+}
+
+/// Address as stored in the `IP.Source` and `IP.Destination` channels:
+/// IPv6 addresses as they are, IPv4 addresses IPv4-mapped (`::ffff:a.b.c.d`).
+fn address_bytes(address: IpAddr) -> [u8; 16] {
+    match address {
+        IpAddr::V4(address) => address.to_ipv6_mapped().octets(),
+        IpAddr::V6(address) => address.octets(),
+    }
+}
+
+/// A decomposed packet waiting to be written.
+struct IpRecord {
+    timestamp_us: u64,
+    tx: bool,
+    version: u8,
+    protocol: u8,
+    source: [u8; 16],
+    destination: [u8; 16],
+    source_port: u16,
+    destination_port: u16,
+    payload: Vec<u8>,
+}
+
+/// Buffered packets of the `{source_name}_ETH_IP` channel group.
+///
+/// The channel group holds the channels:
+/// - `Timestamp` - Float64 seconds (master)
+/// - `Dir` - UInt8 frame direction, shown as `Rx` or `Tx`
+/// - `IP.Version` - UInt8 IP version
+/// - `IP.Protocol` - UInt8 transport protocol, shown as `TCP`, `UDP`, ...
+/// - `IP.Source`, `IP.Destination` - 16 byte addresses, IPv4 addresses
+///   IPv4-mapped
+/// - `IP.SourcePort`, `IP.DestinationPort` - UInt16 ports, 0 for packets
+///   without UDP or TCP header
+/// - `IP.Payload` - UDP or TCP payload as variable length ByteArray (VLSD)
+///
+/// It is created by the first flush with buffered packets.
+pub(super) struct IpGroup {
+    records: Vec<IpRecord>,
+    /// Packets logged, including packets already flushed
+    pub(super) count: usize,
+    /// Channel group ID
+    channel_group: Option<String>,
+}
+
+impl IpGroup {
+    pub(super) fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            count: 0,
+            channel_group: None,
+        }
+    }
+
+    /// Decompose a logged frame and buffer it if it carries an IP packet.
+    pub(super) fn process(&mut self, timestamp_us: u64, frame: &[u8], flags: EthernetFlags) {
+        let Some(packet) = IpPacket::from_frame_bytes(frame) else {
+            return;
+        };
+        self.records.push(IpRecord {
+            timestamp_us,
+            tx: flags.is_tx(),
+            version: packet.version,
+            protocol: packet.protocol,
+            source: address_bytes(packet.source),
+            destination: address_bytes(packet.destination),
+            source_port: packet.source_port.unwrap_or(0),
+            destination_port: packet.destination_port.unwrap_or(0),
+            payload: packet.payload.to_vec(),
+        });
+        self.count += 1;
+    }
+
+    /// Number of packets buffered.
+    pub(super) fn buffered(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Forget the channel group, so the next write creates it in a new file.
+    #[cfg(feature = "std")]
+    pub(super) fn reset_group(&mut self) {
+        self.channel_group = None;
+    }
+
+    /// Write and clear the buffered packets, creating the channel group on
+    /// first use.
+    pub(super) fn write<W: crate::writer::MdfWrite>(
+        &mut self,
+        writer: &mut crate::MdfWriter<W>,
+        source_name: &str,
+        port_name: Option<&str>,
+    ) -> crate::Result<()> {
+        use crate::DecodedValue;
+
+        if self.records.is_empty() {
+            return Ok(());
+        }
+        let cg = match &self.channel_group {
+            Some(cg) => cg,
+            None => self
+                .channel_group
+                .insert(init_ip_group(writer, source_name, port_name)?),
+        };
+
+        writer.start_data_block_for_cg(cg, 0)?;
+        for record in self.records.drain(..) {
+            let values = [
+                DecodedValue::Float(timestamp_to_seconds(record.timestamp_us)),
+                DecodedValue::UnsignedInteger(record.tx as u64),
+                DecodedValue::UnsignedInteger(record.version as u64),
+                DecodedValue::UnsignedInteger(record.protocol as u64),
+                DecodedValue::ByteArray(record.source.to_vec()),
+                DecodedValue::ByteArray(record.destination.to_vec()),
+                DecodedValue::UnsignedInteger(record.source_port as u64),
+                DecodedValue::UnsignedInteger(record.destination_port as u64),
+                DecodedValue::ByteArray(record.payload),
+            ];
+            writer.write_record(cg, &values)?;
+        }
+        writer.finish_data_block(cg)
+    }
+}
+
+/// Create the `{source_name}_ETH_IP` channel group.
+fn init_ip_group<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    source_name: &str,
+    port_name: Option<&str>,
+) -> crate::Result<String> {
+    use crate::DataType;
+
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, &alloc::format!("{}_ETH_IP", source_name))?;
+    let source = crate::blocks::SourceBlock::ethernet();
+    writer.set_channel_group_source_with_path(&cg, &source, Some(source_name), port_name)?;
+
+    let time_ch = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some(String::from("Timestamp"));
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_ch)?;
+    writer.set_channel_unit(&time_ch, "s")?;
+
+    let dir = writer.add_channel(&cg, Some(&time_ch), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("Dir"));
+        ch.bit_count = 8;
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "Rx"), (1, "Tx")], "", Some(&dir))?;
+
+    let version = writer.add_channel(&cg, Some(&dir), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("IP.Version"));
+        ch.bit_count = 8;
+    })?;
+    let protocol = writer.add_channel(&cg, Some(&version), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("IP.Protocol"));
+        ch.bit_count = 8;
+    })?;
+    writer.add_value_to_text_conversion(
+        &[
+            (ip_protocol::ICMP as i64, "ICMP"),
+            (ip_protocol::TCP as i64, "TCP"),
+            (ip_protocol::UDP as i64, "UDP"),
+            (ip_protocol::ICMPV6 as i64, "ICMPv6"),
+        ],
+        "",
+        Some(&protocol),
+    )?;
+
+    let mut prev = protocol;
+    for name in ["IP.Source", "IP.Destination"] {
+        prev = writer.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = DataType::ByteArray;
+            ch.name = Some(String::from(name));
+            ch.bit_count = 128;
+        })?;
+    }
+    for name in ["IP.SourcePort", "IP.DestinationPort"] {
+        prev = writer.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from(name));
+            ch.bit_count = 16;
+        })?;
+    }
+    writer.add_vlsd_channel(&cg, Some(&prev), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("IP.Payload"));
+    })?;
+    Ok(cg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ethernet header + IPv4 header + UDP header + payload.
+    fn udp_frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&[0xFF; 6]);
+        frame.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        frame.extend_from_slice(&ethertype::IPV4.to_be_bytes());
+
+        let total_len = (IPV4_HEADER_SIZE + UDP_HEADER_SIZE + payload.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0x00]);
+        frame.extend_from_slice(&total_len.to_be_bytes());
+        frame.extend_from_slice(&[0x00, 0x00, 0x40, 0x00, 64, ip_protocol::UDP, 0x00, 0x00]);
+        frame.extend_from_slice(&[192, 168, 1, 10]);
+        frame.extend_from_slice(&[224, 224, 224, 245]);
+
+        frame.extend_from_slice(&30501u16.to_be_bytes());
+        frame.extend_from_slice(&30490u16.to_be_bytes());
+        frame.extend_from_slice(&((UDP_HEADER_SIZE + payload.len()) as u16).to_be_bytes());
+        frame.extend_from_slice(&[0x00, 0x00]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[test]
+    fn test_ipv4_udp() {
+        let mut frame = udp_frame(&[1, 2, 3, 4]);
+        // Ethernet padding is not part of the payload
+        frame.resize(60, 0);
+
+        let packet = IpPacket::from_frame_bytes(&frame).unwrap();
+        assert_eq!(packet.version, 4);
+        assert_eq!(packet.protocol, ip_protocol::UDP);
+        assert_eq!(packet.source, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)));
+        assert_eq!(
+            packet.destination,
+            IpAddr::V4(Ipv4Addr::new(224, 224, 224, 245))
+        );
+        assert_eq!(packet.source_port, Some(30501));
+        assert_eq!(packet.destination_port, Some(30490));
+        assert_eq!(packet.payload, [1, 2, 3, 4]);
+
+        let frame = EthernetFrame::from_bytes(&frame).unwrap();
+        assert_eq!(frame.ip_packet().unwrap().destination_port, Some(30490));
+
+        // Other EtherTypes
+        assert!(IpPacket::parse(ethertype::ARP, &[0; 28]).is_none());
+    }
+
+    #[test]
+    fn test_vlan_tagged_ipv6_tcp() {
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&[0xFF; 12]);
+        frame.extend_from_slice(&ethertype::VLAN.to_be_bytes());
+        frame.extend_from_slice(&[0x00, 0x05]);
+        frame.extend_from_slice(&ethertype::IPV6.to_be_bytes());
+
+        let data = b"GET";
+        // Hop-by-hop options header (8 bytes) + TCP header (20 bytes) + data
+        let payload_len = (8 + TCP_HEADER_SIZE + data.len()) as u16;
+        frame.extend_from_slice(&[0x60, 0, 0, 0]);
+        frame.extend_from_slice(&payload_len.to_be_bytes());
+        frame.extend_from_slice(&[IPV6_HOP_BY_HOP, 64]);
+        frame.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        frame.extend_from_slice(&Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 2).octets());
+        frame.extend_from_slice(&[ip_protocol::TCP, 0, 0, 0, 0, 0, 0, 0]);
+
+        let mut tcp = [0u8; TCP_HEADER_SIZE];
+        tcp[0..2].copy_from_slice(&13400u16.to_be_bytes());
+        tcp[2..4].copy_from_slice(&80u16.to_be_bytes());
+        tcp[12] = 5 << 4;
+        frame.extend_from_slice(&tcp);
+        frame.extend_from_slice(data);
+
+        let packet = IpPacket::from_frame_bytes(&frame).unwrap();
+        assert_eq!(packet.version, 6);
+        assert_eq!(packet.protocol, ip_protocol::TCP);
+        assert_eq!(packet.source, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!(packet.source_port, Some(13400));
+        assert_eq!(packet.destination_port, Some(80));
+        assert_eq!(packet.payload, data);
+    }
+
+    #[test]
+    fn test_fragments_and_invalid_packets() {
+        // Later fragment: no transport header
+        let mut frame = udp_frame(&[0xAA; 8]);
+        frame[ETH_HEADER_SIZE + 6..ETH_HEADER_SIZE + 8].copy_from_slice(&0x0002u16.to_be_bytes());
+        let packet = IpPacket::from_frame_bytes(&frame).unwrap();
+        assert_eq!(packet.source_port, None);
+        assert_eq!(packet.payload.len(), UDP_HEADER_SIZE + 8);
+
+        // Wrong version and short header
+        let mut frame = udp_frame(&[]);
+        frame[ETH_HEADER_SIZE] = 0x65;
+        assert!(IpPacket::from_frame_bytes(&frame).is_none());
+        assert!(IpPacket::parse(ethertype::IPV4, &[0x45; 10]).is_none());
+
+        assert_eq!(
+            address_bytes(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, 10, 0, 0, 1]
+        );
+    }
+}
@@ -8,8 +8,14 @@
 //! - ASAM MDF4 Bus Logging compliant `ETH_Frame` format
 //! - Support for standard and jumbo frames
 //! - Direction tracking (Tx/Rx)
+//! - CRC error and truncated frame flags, with ASAM composition members
+//! - Port name and link speed metadata
+//! - Streaming to file with bounded memory and size/time file rotation
 //! - VLAN tag support (802.1Q)
+//! - IP/UDP/TCP header decomposition of logged and read frames
 //! - Common EtherType constants
+//! - Reading `ETH_Frame` groups of any MDF file in timestamp order (std only)
+//! - Import of pcap and pcapng captures and export to pcapng (std only)
 //!
 //! # Example
 //!
@@ -40,6 +46,11 @@
 //! ```
 
 pub mod frame;
+pub mod ip;
+#[cfg(feature = "std")]
+mod overlay;
+#[cfg(feature = "std")]
+mod pcap;
 mod raw_logger;
 
 // Re-export frame types
@@ -58,5 +69,16 @@ pub use frame::{
     ethertype,
 };
 
+// Re-export IP decomposition
+pub use ip::{IpPacket, ip_protocol};
+
 // Re-export logger
-pub use raw_logger::RawEthernetLogger;
+pub use raw_logger::{DEFAULT_MAX_BUFFERED_FRAMES, RawEthernetLogger};
+
+// Re-export overlay reader
+#[cfg(feature = "std")]
+pub use overlay::{EthernetFrameIter, EthernetOverlayReader};
+
+// Re-export capture import and export
+#[cfg(feature = "std")]
+pub use pcap::{PcapPacket, PcapReader, export_pcapng, import_pcap, write_pcapng};
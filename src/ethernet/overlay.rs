@@ -0,0 +1,327 @@
+//! Reader for raw Ethernet captures stored in MDF4 files.
+//!
+//! This module provides [`EthernetOverlayReader`], which finds the
+//! `ETH_Frame` channel groups of an MDF4 file and yields the logged frames
+//! as [`EthernetFrame`]s in timestamp order, whether the file was written by
+//! [`RawEthernetLogger`](super::RawEthernetLogger) or by another tool using
+//! the same layout. Standard and jumbo frame groups are merged.
+//!
+//! The IP and UDP/TCP headers of the frames read can be decomposed with
+//! [`EthernetFrame::ip_packet`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::ethernet::EthernetOverlayReader;
+//! use mdf4_rs::FileRangeReader;
+//!
+//! let overlay = EthernetOverlayReader::from_file("eth_capture.mf4")?;
+//! let mut reader = FileRangeReader::new("eth_capture.mf4")?;
+//!
+//! for frame in overlay.frames_iter(&mut reader) {
+//!     let (timestamp_us, frame) = frame?;
+//!     if let Some(packet) = frame.ip_packet() {
+//!         println!(
+//!             "{}: {} -> {} {:?}",
+//!             timestamp_us, packet.source, packet.destination, packet.destination_port
+//!         );
+//!     }
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use super::frame::{ETH_FRAME_HEADER_SIZE, ETH_HEADER_SIZE, EthernetFlags, EthernetFrame};
+use crate::bus_logging::{CaptureGroup, MergedFrames};
+use crate::index::{ByteRangeReader, IndexedChannelGroup, MdfIndex};
+use crate::{DataType, Error, Result};
+
+/// Information about an `ETH_Frame` channel group.
+#[derive(Debug)]
+struct EthernetGroup {
+    /// Index in the MdfIndex channel_groups
+    group_index: usize,
+    /// Timestamp channel index
+    timestamp_channel: usize,
+    /// ETH_Frame channel index (ByteArray)
+    frame_channel: usize,
+}
+
+/// Reader for raw Ethernet frames stored in MDF4 files.
+///
+/// # Storage Format
+///
+/// The reader expects the ASAM MDF4 Bus Logging `ETH_Frame` format, as
+/// written by [`RawEthernetLogger`](super::RawEthernetLogger):
+/// - Timestamp (Float64 seconds, or integer microseconds). Groups without a
+///   channel named `Timestamp` fall back to their master channel.
+/// - ETH_Frame (ByteArray): Flags(1) + FrameLength(2 bytes LE) +
+///   Frame(Dst MAC + Src MAC + EtherType + Payload)
+///
+/// Bytes past the frame length are padding and ignored. Frames longer than
+/// the stored bytes are cut and flagged as truncated. Records too short for
+/// an Ethernet header are skipped.
+pub struct EthernetOverlayReader {
+    /// The MDF index for efficient reading
+    index: MdfIndex,
+    /// Detected ETH_Frame channel groups
+    groups: Vec<EthernetGroup>,
+}
+
+impl EthernetOverlayReader {
+    /// Create a new reader from an MDF file path.
+    ///
+    /// # Returns
+    /// A new reader, or an error if the file cannot be read or contains no
+    /// `ETH_Frame` channel group.
+    pub fn from_file(mdf_path: &str) -> Result<Self> {
+        let index = MdfIndex::from_file(mdf_path)?;
+        Self::from_index(index)
+    }
+
+    /// Create a new reader from an existing MdfIndex.
+    pub fn from_index(index: MdfIndex) -> Result<Self> {
+        let groups: Vec<EthernetGroup> = index
+            .channel_groups
+            .iter()
+            .enumerate()
+            .filter_map(|(group_idx, group)| Self::try_parse_group(group_idx, group))
+            .collect();
+
+        if groups.is_empty() {
+            return Err(Error::BlockSerializationError(
+                "No ETH_Frame channel groups found in MDF file".into(),
+            ));
+        }
+
+        Ok(Self { index, groups })
+    }
+
+    /// Try to parse a channel group as `ETH_Frame` format.
+    fn try_parse_group(group_index: usize, group: &IndexedChannelGroup) -> Option<EthernetGroup> {
+        let mut timestamp_channel = None;
+        let mut master_channel = None;
+        let mut frame_channel = None;
+
+        for (ch_idx, channel) in group.channels.iter().enumerate() {
+            if channel.channel_type == 2 && master_channel.is_none() {
+                master_channel = Some(ch_idx);
+            }
+            match channel.name.as_deref() {
+                Some("Timestamp") => timestamp_channel = Some(ch_idx),
+                // Variable length frames are stored out of record and cannot
+                // be read block by block
+                Some("ETH_Frame")
+                    if channel.data_type == DataType::ByteArray
+                        && channel.vlsd_data_address.is_none() =>
+                {
+                    frame_channel = Some(ch_idx)
+                }
+                _ => {}
+            }
+        }
+
+        Some(EthernetGroup {
+            group_index,
+            timestamp_channel: timestamp_channel.or(master_channel)?,
+            frame_channel: frame_channel?,
+        })
+    }
+
+    /// Get the number of `ETH_Frame` channel groups found.
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Get the underlying MDF index.
+    pub fn index(&self) -> &MdfIndex {
+        &self.index
+    }
+
+    /// Read all frames from the MDF file.
+    ///
+    /// Returns a vector of (timestamp_us, frame) tuples sorted by timestamp.
+    /// For large captures prefer [`frames_iter()`](Self::frames_iter), which
+    /// does not hold all frames in memory.
+    pub fn read_frames<R: ByteRangeReader<Error = Error>>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Vec<(u64, EthernetFrame)>> {
+        let mut frames = self.frames_iter(reader).collect::<Result<Vec<_>>>()?;
+
+        // Sort by timestamp, in case a group is not in time order
+        frames.sort_by_key(|(ts, _)| *ts);
+
+        Ok(frames)
+    }
+
+    /// Read all frames of one EtherType from the MDF file, in timestamp
+    /// order.
+    ///
+    /// The EtherType of VLAN tagged frames is the one after the tag.
+    pub fn read_ethertype<R: ByteRangeReader<Error = Error>>(
+        &self,
+        ether_type: u16,
+        reader: &mut R,
+    ) -> Result<Vec<(u64, EthernetFrame)>> {
+        let mut frames = Vec::new();
+        for frame in self.frames_iter(reader) {
+            let frame = frame?;
+            if frame.1.ethertype == ether_type {
+                frames.push(frame);
+            }
+        }
+        frames.sort_by_key(|(ts, _)| *ts);
+        Ok(frames)
+    }
+
+    /// Iterate over all frames of the MDF file in timestamp order.
+    ///
+    /// Standard and jumbo frame groups are merged one data block at a time,
+    /// so memory use does not grow with the capture size.
+    pub fn frames_iter<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        reader: &'a mut R,
+    ) -> EthernetFrameIter<'a, R> {
+        EthernetFrameIter {
+            frames: MergedFrames::new(&self.index, reader, &self.groups, |_, bytes| {
+                parse_eth_frame(bytes)
+            }),
+        }
+    }
+}
+
+impl CaptureGroup for EthernetGroup {
+    fn group_index(&self) -> usize {
+        self.group_index
+    }
+
+    fn channels(&self) -> [usize; 2] {
+        [self.timestamp_channel, self.frame_channel]
+    }
+}
+
+/// Parse the bytes of an `ETH_Frame` value.
+fn parse_eth_frame(bytes: &[u8]) -> Option<EthernetFrame> {
+    if bytes.len() < ETH_FRAME_HEADER_SIZE + ETH_HEADER_SIZE {
+        return None;
+    }
+    let flags = EthernetFlags::from_byte(bytes[0]);
+    let frame_len = u16::from_le_bytes([bytes[1], bytes[2]]) as usize;
+    let data = &bytes[ETH_FRAME_HEADER_SIZE..];
+    let truncated = frame_len > data.len();
+
+    let mut frame = EthernetFrame::from_bytes(&data[..frame_len.min(data.len())])?;
+    frame.flags = EthernetFlags::from_byte(flags.to_byte() | frame.flags.to_byte())
+        .with_truncated(flags.is_truncated() || truncated);
+    Some(frame)
+}
+
+/// Streaming iterator over the Ethernet frames of a capture, in timestamp
+/// order.
+///
+/// Created by [`EthernetOverlayReader::frames_iter()`]. Yields
+/// (timestamp_us, frame) tuples. After a read error the error is yielded
+/// once and the iteration ends.
+pub struct EthernetFrameIter<'a, R> {
+    frames: MergedFrames<'a, R, &'a EthernetGroup, EthernetFrame>,
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for EthernetFrameIter<'_, R> {
+    type Item = Result<(u64, EthernetFrame)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.frames.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethernet::{MacAddress, RawEthernetLogger, ethertype};
+
+    fn frame(ether_type: u16, payload_len: usize) -> EthernetFrame {
+        EthernetFrame::new(
+            MacAddress::broadcast(),
+            MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            ether_type,
+            (0..payload_len).map(|i| i as u8).collect(),
+        )
+    }
+
+    #[test]
+    fn test_read_raw_capture() {
+        let temp_path = std::env::temp_dir().join("ethernet_overlay_test.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger = RawEthernetLogger::new_file_with_source_name(path, "ADAS").unwrap();
+        logger.log_frame(1000, frame(ethertype::IPV4, 46));
+        logger.log_frame(3000, frame(ethertype::ARP, 28).with_tx());
+        // Jumbo frame in its own channel group
+        logger.log_frame(2000, frame(ethertype::IPV4, 4000));
+        logger.log_crc_error(4000, &frame(ethertype::SOMEIP, 100).to_bytes());
+        let mut tagged = frame(ethertype::IPV4, 50);
+        tagged.vlan_tci = Some(0x0005);
+        logger.log_frame(5000, tagged);
+        logger.finalize_file().unwrap();
+
+        let overlay = EthernetOverlayReader::from_file(path).unwrap();
+        assert_eq!(overlay.group_count(), 2);
+
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        let frames = overlay.read_frames(&mut reader).unwrap();
+        let timestamps: Vec<u64> = frames.iter().map(|(ts, _)| *ts).collect();
+        assert_eq!(timestamps, [1000, 2000, 3000, 4000, 5000]);
+
+        // Padding is dropped
+        assert_eq!(frames[0].1.payload.len(), 46);
+        assert_eq!(
+            frames[0].1.src_mac.as_bytes(),
+            &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]
+        );
+        assert_eq!(frames[1].1.payload.len(), 4000);
+        assert_eq!(frames[1].1.payload[3999], (3999 % 256) as u8);
+        assert!(frames[2].1.flags.is_tx());
+        assert_eq!(frames[2].1.ethertype, ethertype::ARP);
+        assert!(frames[3].1.flags.has_crc_error());
+        assert_eq!(frames[4].1.vlan_tci, Some(0x0005));
+        assert!(frames[4].1.flags.has_vlan_tag());
+
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        let ipv4 = overlay
+            .read_ethertype(ethertype::IPV4, &mut reader)
+            .unwrap();
+        assert_eq!(ipv4.len(), 3);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_truncated_frame_bytes() {
+        // Length field beyond the stored bytes
+        let mut bytes = alloc::vec![0x00, 0x00, 0x08];
+        bytes.extend_from_slice(&frame(ethertype::IPV4, 10).to_bytes());
+        let parsed = parse_eth_frame(&bytes).unwrap();
+        assert!(parsed.flags.is_truncated());
+        assert_eq!(parsed.payload.len(), 10);
+
+        // Too short for an Ethernet header
+        assert!(parse_eth_frame(&[0x00, 0x0E, 0x00, 0xFF]).is_none());
+    }
+
+    #[test]
+    fn test_no_ethernet_groups() {
+        use crate::can::RawCanLogger;
+
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log(0x100, 1000, &[0x01]);
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("ethernet_overlay_no_groups.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+
+        assert!(EthernetOverlayReader::from_file(temp_path.to_str().unwrap()).is_err());
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+}
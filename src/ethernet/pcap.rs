@@ -0,0 +1,874 @@
+//! Import and export of pcap and pcapng Ethernet captures.
+//!
+//! [`PcapReader`] reads the Ethernet packets of captures written by
+//! Wireshark, tcpdump and most other capture tools, both in the classic
+//! pcap format (microsecond and nanosecond variants, either byte order) and
+//! in the pcapng format. The packets can then be logged with a
+//! [`RawEthernetLogger`] into ASAM `ETH_Frame` channel groups;
+//! [`import_pcap()`] does both in one call.
+//!
+//! Packets of link types other than Ethernet are skipped, as are pcapng
+//! simple packet blocks, which carry no time stamp.
+//!
+//! The other way round, [`export_pcapng()`] writes the `ETH_Frame` channel
+//! groups of an MDF file as a pcapng capture for Wireshark.
+
+use super::{ETH_HEADER_SIZE, EthernetFlags, MacAddress, RawEthernetLogger, ethertype};
+use crate::{DecodedValue, Error, MDF, Result, writer::MdfWrite};
+use std::io::{BufReader, BufWriter, Read, Write};
+
+/// Link type of Ethernet captures.
+const LINKTYPE_ETHERNET: u32 = 1;
+
+/// Magic number of classic pcap files with microsecond time stamps.
+const PCAP_MAGIC_MICROS: u32 = 0xA1B2_C3D4;
+
+/// Magic number of classic pcap files with nanosecond time stamps.
+const PCAP_MAGIC_NANOS: u32 = 0xA1B2_3C4D;
+
+/// pcapng block types.
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+const INTERFACE_DESCRIPTION_BLOCK: u32 = 1;
+const ENHANCED_PACKET_BLOCK: u32 = 6;
+
+/// Byte order magic of pcapng section headers.
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+/// pcapng option codes.
+const OPT_END: u16 = 0;
+const EPB_FLAGS: u16 = 2;
+const IF_NAME: u16 = 2;
+const IF_TSRESOL: u16 = 9;
+const IF_FCSLEN: u16 = 13;
+const IF_TSOFFSET: u16 = 14;
+
+/// Largest accepted packet or block, guarding against corrupt length fields.
+const MAX_BLOCK_SIZE: usize = 16 * 1024 * 1024;
+
+/// An Ethernet packet read from a pcap or pcapng capture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PcapPacket {
+    /// Capture time stamp in microseconds since the Unix epoch
+    pub timestamp_us: u64,
+    /// Interface the packet was captured on (always 0 in classic pcap files)
+    pub interface: u32,
+    /// Length of the packet on the wire; larger than the captured data if
+    /// the capture truncated the packet
+    pub original_len: u32,
+    /// Frame data (Dst MAC + Src MAC + EtherType + Payload) without FCS
+    pub data: Vec<u8>,
+    /// Direction, VLAN and truncation flags
+    pub flags: EthernetFlags,
+}
+
+impl PcapPacket {
+    /// Log this packet with a [`RawEthernetLogger`], with its time stamp
+    /// relative to `start_us` microseconds since the Unix epoch.
+    pub fn log<W: MdfWrite>(&self, logger: &mut RawEthernetLogger<W>, start_us: u64) -> bool {
+        let timestamp_us = self.timestamp_us.saturating_sub(start_us);
+        logger.log_with_flags(timestamp_us, &self.data, self.flags)
+    }
+}
+
+/// Time stamp resolution of a capture interface.
+#[derive(Clone, Copy)]
+enum Resolution {
+    /// Units of 10^-n seconds
+    Decimal(u8),
+    /// Units of 2^-n seconds
+    Binary(u8),
+}
+
+/// A capture interface; classic pcap files have exactly one.
+#[derive(Clone, Copy)]
+struct Interface {
+    link_type: u32,
+    resolution: Resolution,
+    /// Offset of the time stamps in seconds
+    offset_s: i64,
+    /// Number of FCS bytes at the end of the packets
+    fcs_len: usize,
+}
+
+impl Interface {
+    /// Microseconds since the Unix epoch of a raw time stamp.
+    fn micros(&self, timestamp: u64) -> u64 {
+        let micros = match self.resolution {
+            Resolution::Decimal(exp) if exp >= 6 => {
+                timestamp / 10u64.checked_pow(u32::from(exp - 6)).unwrap_or(u64::MAX)
+            }
+            Resolution::Decimal(exp) => timestamp.saturating_mul(10u64.pow(u32::from(6 - exp))),
+            Resolution::Binary(exp) => ((u128::from(timestamp) * 1_000_000) >> exp) as u64,
+        };
+        micros.saturating_add_signed(self.offset_s.saturating_mul(1_000_000))
+    }
+}
+
+/// Whether the file is a classic pcap or a pcapng file.
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Pcap,
+    PcapNg,
+}
+
+/// Reader of the Ethernet packets of a pcap or pcapng capture.
+///
+/// Iterates over the packets in the order of the file. Reading stops at the
+/// first malformed record, which is returned as an error.
+///
+/// Every packet is tagged with a direction:
+/// - pcapng captures record the direction of a packet in its `epb_flags`
+///   option; outbound packets are logged as Tx
+/// - otherwise packets sent by one of the MAC addresses registered with
+///   [`with_local_mac()`](Self::with_local_mac) are Tx
+/// - all other packets are Rx
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::ethernet::{MacAddress, PcapReader, RawEthernetLogger};
+///
+/// let mut logger = RawEthernetLogger::new_file("capture.mf4")?;
+/// let reader = PcapReader::from_file("capture.pcapng")?
+///     .with_local_mac(MacAddress::new([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]));
+/// let mut start_us = None;
+/// for packet in reader {
+///     let packet = packet?;
+///     let start_us = *start_us.get_or_insert(packet.timestamp_us);
+///     packet.log(&mut logger, start_us);
+/// }
+/// logger.finalize_file()?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+pub struct PcapReader<R> {
+    reader: R,
+    format: Format,
+    big_endian: bool,
+    /// Interfaces of the current pcapng section, or the one of a pcap file
+    interfaces: Vec<Interface>,
+    /// Packets sent from these addresses are Tx
+    local_macs: Vec<MacAddress>,
+    done: bool,
+}
+
+impl PcapReader<BufReader<std::fs::File>> {
+    /// Open a pcap or pcapng file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(Error::IOError)?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> PcapReader<R> {
+    /// Read a capture, detecting the format from its header.
+    ///
+    /// # Errors
+    /// Returns an error if the data is neither a pcap nor a pcapng capture.
+    pub fn new(reader: R) -> Result<Self> {
+        let mut this = Self {
+            reader,
+            format: Format::Pcap,
+            big_endian: false,
+            interfaces: Vec::new(),
+            local_macs: Vec::new(),
+            done: false,
+        };
+        let mut magic = [0u8; 4];
+        if !this.fill(&mut magic)? {
+            return Err(invalid("Empty capture file"));
+        }
+
+        let magic_le = u32::from_le_bytes(magic);
+        if magic_le == SECTION_HEADER_BLOCK {
+            this.format = Format::PcapNg;
+            let mut length = [0u8; 4];
+            this.fill_exact(&mut length)?;
+            this.section_header(length)?;
+            return Ok(this);
+        }
+
+        let (big_endian, decimals) = match magic_le {
+            PCAP_MAGIC_MICROS => (false, 6),
+            PCAP_MAGIC_NANOS => (false, 9),
+            m if m.swap_bytes() == PCAP_MAGIC_MICROS => (true, 6),
+            m if m.swap_bytes() == PCAP_MAGIC_NANOS => (true, 9),
+            _ => return Err(invalid("Not a pcap or pcapng file")),
+        };
+        this.big_endian = big_endian;
+
+        // Version, time zone, sigfigs, snap length and link type
+        let mut header = [0u8; 20];
+        this.fill_exact(&mut header)?;
+        let link = this.u32_at(&header, 16);
+        // Bit 28 flags the FCS length in bits 29-31, in units of 16 bits
+        let fcs_len = if link & 0x1000_0000 != 0 {
+            ((link >> 29) as usize) * 2
+        } else {
+            0
+        };
+        this.interfaces.push(Interface {
+            link_type: link & 0xFFFF,
+            resolution: Resolution::Decimal(decimals),
+            offset_s: 0,
+            fcs_len,
+        });
+        Ok(this)
+    }
+
+    /// Tag packets sent from `mac` as Tx unless the capture records their
+    /// direction.
+    pub fn with_local_mac(mut self, mac: MacAddress) -> Self {
+        self.local_macs.push(mac);
+        self
+    }
+
+    fn u16_at(&self, bytes: &[u8], offset: usize) -> u16 {
+        let raw = [bytes[offset], bytes[offset + 1]];
+        if self.big_endian {
+            u16::from_be_bytes(raw)
+        } else {
+            u16::from_le_bytes(raw)
+        }
+    }
+
+    fn u32_at(&self, bytes: &[u8], offset: usize) -> u32 {
+        let mut raw = [0u8; 4];
+        raw.copy_from_slice(&bytes[offset..offset + 4]);
+        if self.big_endian {
+            u32::from_be_bytes(raw)
+        } else {
+            u32::from_le_bytes(raw)
+        }
+    }
+
+    fn u64_at(&self, bytes: &[u8], offset: usize) -> u64 {
+        let mut raw = [0u8; 8];
+        raw.copy_from_slice(&bytes[offset..offset + 8]);
+        if self.big_endian {
+            u64::from_be_bytes(raw)
+        } else {
+            u64::from_le_bytes(raw)
+        }
+    }
+
+    /// Fill `buf`; `false` at the end of the file, an error if it ends
+    /// within `buf`.
+    fn fill(&mut self, buf: &mut [u8]) -> Result<bool> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.reader.read(&mut buf[read..]) {
+                Ok(0) if read == 0 => return Ok(false),
+                Ok(0) => return Err(invalid("Capture file ends within a record")),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::IOError(e)),
+            }
+        }
+        Ok(true)
+    }
+
+    fn fill_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        if self.fill(buf)? {
+            Ok(())
+        } else {
+            Err(invalid("Capture file ends within a record"))
+        }
+    }
+
+    /// Read the rest of a pcapng section header block, whose type and raw
+    /// length have been read, and start a new section.
+    fn section_header(&mut self, length: [u8; 4]) -> Result<()> {
+        let mut magic = [0u8; 4];
+        self.fill_exact(&mut magic)?;
+        self.big_endian = match u32::from_le_bytes(magic) {
+            BYTE_ORDER_MAGIC => false,
+            m if m.swap_bytes() == BYTE_ORDER_MAGIC => true,
+            _ => return Err(invalid("Invalid pcapng byte order magic")),
+        };
+        let length = self.u32_at(&length, 0) as usize;
+        if length < 28 || !length.is_multiple_of(4) || length > MAX_BLOCK_SIZE {
+            return Err(invalid("Invalid pcapng block length"));
+        }
+        // Version, section length, options and trailing block length
+        let mut rest = vec![0u8; length - 12];
+        self.fill_exact(&mut rest)?;
+        self.interfaces.clear();
+        Ok(())
+    }
+
+    /// Options of a pcapng block as `(code, value)` pairs.
+    fn options<'b>(&self, mut bytes: &'b [u8]) -> Vec<(u16, &'b [u8])> {
+        let mut options = Vec::new();
+        while bytes.len() >= 4 {
+            let code = self.u16_at(bytes, 0);
+            let len = self.u16_at(bytes, 2) as usize;
+            if code == OPT_END || 4 + len > bytes.len() {
+                break;
+            }
+            options.push((code, &bytes[4..4 + len]));
+            let padded = (4 + len).div_ceil(4) * 4;
+            bytes = &bytes[padded.min(bytes.len())..];
+        }
+        options
+    }
+
+    fn interface_description(&self, body: &[u8]) -> Result<Interface> {
+        if body.len() < 8 {
+            return Err(invalid("Invalid pcapng interface description block"));
+        }
+        let mut interface = Interface {
+            link_type: u32::from(self.u16_at(body, 0)),
+            resolution: Resolution::Decimal(6),
+            offset_s: 0,
+            fcs_len: 0,
+        };
+        for (code, value) in self.options(&body[8..]) {
+            match (code, value.len()) {
+                (IF_TSRESOL, 1) => {
+                    interface.resolution = if value[0] & 0x80 != 0 {
+                        Resolution::Binary(value[0] & 0x7F)
+                    } else {
+                        Resolution::Decimal(value[0])
+                    };
+                }
+                (IF_FCSLEN, 1) => interface.fcs_len = value[0] as usize,
+                (IF_TSOFFSET, 8) => interface.offset_s = self.u64_at(value, 0) as i64,
+                _ => {}
+            }
+        }
+        Ok(interface)
+    }
+
+    fn enhanced_packet(&self, body: &[u8]) -> Result<Option<PcapPacket>> {
+        if body.len() < 20 {
+            return Err(invalid("Invalid pcapng enhanced packet block"));
+        }
+        let interface = self.u32_at(body, 0);
+        let timestamp = (u64::from(self.u32_at(body, 4)) << 32) | u64::from(self.u32_at(body, 8));
+        let captured = self.u32_at(body, 12) as usize;
+        let original_len = self.u32_at(body, 16);
+        if 20 + captured > body.len() {
+            return Err(invalid("Invalid pcapng enhanced packet block"));
+        }
+        let data = body[20..20 + captured].to_vec();
+
+        // Bits 0-1 of epb_flags: 1 = inbound, 2 = outbound
+        let options_start = (20 + captured).div_ceil(4) * 4;
+        let tx = self
+            .options(&body[options_start.min(body.len())..])
+            .into_iter()
+            .find(|&(code, value)| code == EPB_FLAGS && value.len() == 4)
+            .and_then(|(_, value)| match self.u32_at(value, 0) & 0x03 {
+                1 => Some(false),
+                2 => Some(true),
+                _ => None,
+            });
+        self.packet(interface, timestamp, original_len, data, tx)
+    }
+
+    /// Build a packet, or `None` if it is not an Ethernet frame.
+    fn packet(
+        &self,
+        interface: u32,
+        timestamp: u64,
+        original_len: u32,
+        mut data: Vec<u8>,
+        tx: Option<bool>,
+    ) -> Result<Option<PcapPacket>> {
+        let info = self
+            .interfaces
+            .get(interface as usize)
+            .ok_or_else(|| invalid("Packet of an undeclared pcapng interface"))?;
+        if info.link_type != LINKTYPE_ETHERNET {
+            return Ok(None);
+        }
+
+        let truncated = data.len() < original_len as usize;
+        if !truncated {
+            data.truncate(data.len().saturating_sub(info.fcs_len));
+        }
+        if data.len() < ETH_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let tx = tx.unwrap_or_else(|| {
+            self.local_macs
+                .iter()
+                .any(|mac| mac.as_bytes()[..] == data[6..12])
+        });
+        let vlan = u16::from_be_bytes([data[12], data[13]]) == ethertype::VLAN;
+        let mut flags = EthernetFlags::rx().with_tx(tx).with_vlan_tagged(vlan);
+        if truncated {
+            flags = EthernetFlags::from_byte(flags.to_byte() | EthernetFlags::TRUNCATED);
+        }
+
+        Ok(Some(PcapPacket {
+            timestamp_us: info.micros(timestamp),
+            interface,
+            original_len,
+            data,
+            flags,
+        }))
+    }
+
+    /// Read records up to the next Ethernet packet.
+    fn next_packet(&mut self) -> Result<Option<PcapPacket>> {
+        loop {
+            let packet = match self.format {
+                Format::Pcap => self.next_pcap_record()?,
+                Format::PcapNg => self.next_pcapng_block()?,
+            };
+            match packet {
+                Some(Some(packet)) => return Ok(Some(packet)),
+                Some(None) => {}
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Read a classic pcap record; `None` at the end of the file.
+    fn next_pcap_record(&mut self) -> Result<Option<Option<PcapPacket>>> {
+        let mut header = [0u8; 16];
+        if !self.fill(&mut header)? {
+            return Ok(None);
+        }
+        let seconds = u64::from(self.u32_at(&header, 0));
+        let fraction = u64::from(self.u32_at(&header, 4));
+        let captured = self.u32_at(&header, 8) as usize;
+        let original_len = self.u32_at(&header, 12);
+        if captured > MAX_BLOCK_SIZE {
+            return Err(invalid("Invalid pcap record length"));
+        }
+        let mut data = vec![0u8; captured];
+        self.fill_exact(&mut data)?;
+
+        let timestamp = match self.interfaces[0].resolution {
+            Resolution::Decimal(9) => seconds * 1_000_000_000 + fraction,
+            _ => seconds * 1_000_000 + fraction,
+        };
+        self.packet(0, timestamp, original_len, data, None)
+            .map(Some)
+    }
+
+    /// Read a pcapng block; `None` at the end of the file.
+    fn next_pcapng_block(&mut self) -> Result<Option<Option<PcapPacket>>> {
+        let mut header = [0u8; 8];
+        if !self.fill(&mut header)? {
+            return Ok(None);
+        }
+        let block_type = self.u32_at(&header, 0);
+        if block_type == SECTION_HEADER_BLOCK {
+            self.section_header([header[4], header[5], header[6], header[7]])?;
+            return Ok(Some(None));
+        }
+
+        let length = self.u32_at(&header, 4) as usize;
+        if length < 12 || !length.is_multiple_of(4) || length > MAX_BLOCK_SIZE {
+            return Err(invalid("Invalid pcapng block length"));
+        }
+        let mut block = vec![0u8; length - 8];
+        self.fill_exact(&mut block)?;
+        // Without the trailing block length
+        let body = &block[..length - 12];
+
+        match block_type {
+            INTERFACE_DESCRIPTION_BLOCK => {
+                let interface = self.interface_description(body)?;
+                self.interfaces.push(interface);
+                Ok(Some(None))
+            }
+            ENHANCED_PACKET_BLOCK => self.enhanced_packet(body).map(Some),
+            _ => Ok(Some(None)),
+        }
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = Result<PcapPacket>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let packet = self.next_packet().transpose();
+        if !matches!(packet, Some(Ok(_))) {
+            self.done = true;
+        }
+        packet
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::BlockSerializationError(message.to_string())
+}
+
+/// Convert a pcap or pcapng capture into an in-memory MDF file of raw
+/// Ethernet frames.
+///
+/// Every Ethernet packet is logged with a [`RawEthernetLogger`] into ASAM
+/// `ETH_Frame` channel groups. The capture time of the earliest packet
+/// becomes the start time of the file, and the frame time stamps are
+/// relative to it. Directions are taken from the capture where recorded
+/// (see [`PcapReader`]); VLAN tagged and truncated packets are flagged.
+///
+/// # Example
+/// ```no_run
+/// let mdf_bytes = mdf4_rs::ethernet::import_pcap("capture.pcapng")?;
+/// std::fs::write("capture.mf4", mdf_bytes)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Returns
+/// The bytes of the MDF file, or an [`crate::Error`] if the capture is
+/// malformed or writing fails.
+pub fn import_pcap(path: &str) -> Result<Vec<u8>> {
+    let packets = PcapReader::from_file(path)?.collect::<Result<Vec<_>>>()?;
+    let mut logger = RawEthernetLogger::new()?;
+    if let Some(start_us) = packets.iter().map(|p| p.timestamp_us).min() {
+        logger.set_start_time_ns(start_us.saturating_mul(1000))?;
+        for packet in &packets {
+            packet.log(&mut logger, start_us);
+        }
+    }
+    logger.finalize()
+}
+
+/// A little endian pcapng block with its body padded to 32 bits.
+fn block(block_type: u32, body: &[u8]) -> Vec<u8> {
+    let padded = body.len().div_ceil(4) * 4;
+    let length = (padded + 12) as u32;
+    let mut bytes = Vec::with_capacity(padded + 12);
+    bytes.extend_from_slice(&block_type.to_le_bytes());
+    bytes.extend_from_slice(&length.to_le_bytes());
+    bytes.extend_from_slice(body);
+    bytes.resize(padded + 8, 0);
+    bytes.extend_from_slice(&length.to_le_bytes());
+    bytes
+}
+
+/// A little endian pcapng option with its value padded to 32 bits.
+fn option(code: u16, value: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(4 + value.len().div_ceil(4) * 4);
+    bytes.extend_from_slice(&code.to_le_bytes());
+    bytes.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(value);
+    bytes.resize(4 + value.len().div_ceil(4) * 4, 0);
+    bytes
+}
+
+/// Write the `ETH_Frame` channel groups of an MDF file as a pcapng capture.
+///
+/// Every channel group with an `ETH_Frame` byte array channel, as written
+/// by [`RawEthernetLogger`], becomes an Ethernet interface named after the
+/// group. The frames are written in the order of the groups with
+/// nanosecond time stamps, the start time of the file plus the master
+/// channel value, and with their direction as `epb_flags` (Tx frames are
+/// outbound). Frames without a valid time stamp are skipped.
+///
+/// Frames are streamed; only one record is held in memory at a time.
+///
+/// # Returns
+/// The number of written frames, or an [`crate::Error`] if decoding or
+/// writing fails.
+pub fn write_pcapng<W: Write>(mdf: &MDF, writer: W) -> Result<usize> {
+    let mut writer = BufWriter::new(writer);
+    let mut shb = Vec::with_capacity(16);
+    shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+    // Version 1.0 and unknown section length
+    shb.extend_from_slice(&[1, 0, 0, 0]);
+    shb.extend_from_slice(&u64::MAX.to_le_bytes());
+    writer
+        .write_all(&block(SECTION_HEADER_BLOCK, &shb))
+        .map_err(Error::IOError)?;
+
+    let start_ns = mdf.raw().header.start_time_ns;
+    let mut interface = 0u32;
+    let mut count = 0;
+    for group in mdf.channel_groups() {
+        let channels = group.channels();
+        let mut frame_channel = None;
+        for (i, channel) in channels.iter().enumerate() {
+            if channel.name()?.as_deref() == Some("ETH_Frame") {
+                frame_channel = Some(i);
+                break;
+            }
+        }
+        let Some(frame_channel) = frame_channel else {
+            continue;
+        };
+        let Some(master) = channels
+            .iter()
+            .position(|ch| matches!(ch.block().channel_type, 2 | 3))
+        else {
+            continue;
+        };
+
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&(LINKTYPE_ETHERNET as u16).to_le_bytes());
+        // Reserved and unlimited snap length
+        idb.extend_from_slice(&[0; 6]);
+        if let Some(name) = group.name()? {
+            idb.extend(option(IF_NAME, name.as_bytes()));
+        }
+        idb.extend(option(IF_TSRESOL, &[9]));
+        idb.extend(option(OPT_END, &[]));
+        writer
+            .write_all(&block(INTERFACE_DESCRIPTION_BLOCK, &idb))
+            .map_err(Error::IOError)?;
+
+        let times = channels[master].iter_values()?;
+        let frames = channels[frame_channel].iter_values()?;
+        for (time, frame) in times.zip(frames) {
+            let (Some(time), Some(DecodedValue::ByteArray(bytes))) = (time?, frame?) else {
+                continue;
+            };
+            let Some(seconds) = time.as_f64() else {
+                continue;
+            };
+            if bytes.len() < 3 {
+                continue;
+            }
+            // ETH_Frame: flags(1) + length(2) + frame data
+            let flags = EthernetFlags::from_byte(bytes[0]);
+            let len = usize::from(u16::from_le_bytes([bytes[1], bytes[2]]));
+            let data = &bytes[3..(3 + len).min(bytes.len())];
+            let timestamp = start_ns.saturating_add_signed((seconds * 1e9).round() as i64);
+
+            let mut epb = Vec::with_capacity(32 + data.len());
+            epb.extend_from_slice(&interface.to_le_bytes());
+            epb.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+            epb.extend_from_slice(&(timestamp as u32).to_le_bytes());
+            epb.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            epb.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            epb.extend_from_slice(data);
+            epb.resize(20 + data.len().div_ceil(4) * 4, 0);
+            let direction: u32 = if flags.is_tx() { 2 } else { 1 };
+            epb.extend(option(EPB_FLAGS, &direction.to_le_bytes()));
+            epb.extend(option(OPT_END, &[]));
+            writer
+                .write_all(&block(ENHANCED_PACKET_BLOCK, &epb))
+                .map_err(Error::IOError)?;
+            count += 1;
+        }
+        interface += 1;
+    }
+    writer.flush().map_err(Error::IOError)?;
+    Ok(count)
+}
+
+/// Export the `ETH_Frame` channel groups of an MDF file to a pcapng file,
+/// e.g. for analysis in Wireshark.
+///
+/// See [`write_pcapng()`] for the layout of the capture.
+///
+/// # Example
+/// ```no_run
+/// let frames = mdf4_rs::ethernet::export_pcapng("capture.mf4", "capture.pcapng")?;
+/// println!("{} frames exported", frames);
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Returns
+/// The number of exported frames, or an [`crate::Error`] if reading or
+/// writing fails.
+pub fn export_pcapng(mdf_path: &str, pcapng_path: &str) -> Result<usize> {
+    let mdf = MDF::from_file(mdf_path)?;
+    let file = std::fs::File::create(pcapng_path).map_err(Error::IOError)?;
+    write_pcapng(&mdf, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL: [u8; 6] = [0x02, 0, 0, 0, 0, 0x01];
+
+    fn frame(src: [u8; 6], ethertype: u16, payload_len: usize) -> Vec<u8> {
+        let mut frame = vec![0xFF; 6];
+        frame.extend_from_slice(&src);
+        frame.extend_from_slice(&ethertype.to_be_bytes());
+        frame.extend((0..payload_len).map(|i| i as u8));
+        frame
+    }
+
+    fn pcap(big_endian: bool, records: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+        let u32_bytes = |v: u32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&u32_bytes(PCAP_MAGIC_MICROS));
+        // Version, time zone and accuracy are not read
+        bytes.extend_from_slice(&[0; 12]);
+        bytes.extend_from_slice(&u32_bytes(65535));
+        bytes.extend_from_slice(&u32_bytes(LINKTYPE_ETHERNET));
+        for (seconds, micros, data) in records {
+            bytes.extend_from_slice(&u32_bytes(*seconds));
+            bytes.extend_from_slice(&u32_bytes(*micros));
+            bytes.extend_from_slice(&u32_bytes(data.len() as u32));
+            bytes.extend_from_slice(&u32_bytes(data.len() as u32));
+            bytes.extend_from_slice(data);
+        }
+        bytes
+    }
+
+    fn enhanced_packet(interface: u32, timestamp: u64, data: &[u8], flags: Option<u32>) -> Vec<u8> {
+        let mut body = interface.to_le_bytes().to_vec();
+        body.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+        body.extend_from_slice(&(timestamp as u32).to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        body.resize(body.len().div_ceil(4) * 4, 0);
+        if let Some(flags) = flags {
+            body.extend(option(EPB_FLAGS, &flags.to_le_bytes()));
+            body.extend([0; 4]);
+        }
+        block(ENHANCED_PACKET_BLOCK, &body)
+    }
+
+    fn pcapng() -> Vec<u8> {
+        let mut shb = BYTE_ORDER_MAGIC.to_le_bytes().to_vec();
+        shb.extend_from_slice(&[1, 0, 0, 0]);
+        shb.extend_from_slice(&u64::MAX.to_le_bytes());
+        let mut bytes = block(SECTION_HEADER_BLOCK, &shb);
+
+        // Interface 0: Ethernet with nanosecond time stamps and FCS
+        let mut idb = vec![1, 0, 0, 0, 0, 0, 0, 0];
+        idb.extend(option(IF_TSRESOL, &[9]));
+        idb.extend(option(IF_FCSLEN, &[4]));
+        idb.extend([0; 4]);
+        bytes.extend(block(INTERFACE_DESCRIPTION_BLOCK, &idb));
+        // Interface 1: Linux cooked capture, skipped
+        bytes.extend(block(
+            INTERFACE_DESCRIPTION_BLOCK,
+            &[113, 0, 0, 0, 0, 0, 0, 0],
+        ));
+
+        let mut with_fcs = frame([0x02, 0, 0, 0, 0, 0x02], ethertype::IPV4, 46);
+        with_fcs.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        bytes.extend(enhanced_packet(
+            0,
+            1_700_000_000_123_456_789,
+            &with_fcs,
+            Some(2),
+        ));
+        bytes.extend(enhanced_packet(
+            1,
+            1_700_000_000_200_000_000,
+            &[0; 20],
+            None,
+        ));
+        bytes.extend(enhanced_packet(
+            0,
+            1_700_000_000_300_000_000,
+            &frame(LOCAL, ethertype::ARP, 28),
+            Some(1),
+        ));
+        bytes
+    }
+
+    #[test]
+    fn test_read_pcap() {
+        let mut vlan = frame([0x02, 0, 0, 0, 0, 0x02], ethertype::VLAN, 4);
+        vlan.extend_from_slice(&[0x08, 0x00, 0x45, 0x00]);
+        let records = [
+            (1_700_000_000, 500_000, frame(LOCAL, ethertype::IPV4, 46)),
+            (1_700_000_001, 0, vlan),
+            (1_700_000_002, 0, vec![0; 10]),
+        ];
+
+        for big_endian in [false, true] {
+            let packets = PcapReader::new(&pcap(big_endian, &records)[..])
+                .unwrap()
+                .with_local_mac(MacAddress::new(LOCAL))
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(packets.len(), 2);
+            assert_eq!(packets[0].timestamp_us, 1_700_000_000_500_000);
+            assert!(packets[0].flags.is_tx());
+            assert!(!packets[0].flags.has_vlan_tag());
+            assert_eq!(packets[0].data, records[0].2);
+            assert_eq!(packets[1].timestamp_us, 1_700_000_001_000_000);
+            assert!(packets[1].flags.is_rx());
+            assert!(packets[1].flags.has_vlan_tag());
+        }
+
+        assert!(PcapReader::new(&b"not a capture"[..]).is_err());
+        let mut truncated = pcap(false, &records);
+        truncated.truncate(truncated.len() - 30);
+        let results: Vec<_> = PcapReader::new(&truncated[..]).unwrap().collect();
+        assert!(results[0].is_ok() && results[1].is_err());
+    }
+
+    #[test]
+    fn test_read_pcapng() {
+        let packets = PcapReader::new(&pcapng()[..])
+            .unwrap()
+            .with_local_mac(MacAddress::new(LOCAL))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(packets.len(), 2);
+
+        assert_eq!(packets[0].timestamp_us, 1_700_000_000_123_456);
+        assert_eq!(packets[0].data.len(), ETH_HEADER_SIZE + 46);
+        assert!(packets[0].flags.is_tx());
+
+        // The recorded direction wins over the local MAC address
+        assert_eq!(packets[1].timestamp_us, 1_700_000_000_300_000);
+        assert!(packets[1].flags.is_rx());
+    }
+
+    #[test]
+    fn test_import_pcap() {
+        let path = std::env::temp_dir().join("mdf4_rs_import_pcap_test.pcapng");
+        std::fs::write(&path, pcapng()).unwrap();
+        let mdf_bytes = import_pcap(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(mdf_bytes.starts_with(b"MDF"));
+
+        let mut logger = RawEthernetLogger::new().unwrap();
+        for packet in PcapReader::new(&pcapng()[..]).unwrap() {
+            assert!(packet.unwrap().log(&mut logger, 1_700_000_000_000_000));
+        }
+        assert_eq!(logger.tx_frame_count(), 1);
+        assert_eq!(logger.rx_frame_count(), 1);
+    }
+
+    #[test]
+    fn test_export_pcapng_round_trip() {
+        let dir = std::env::temp_dir();
+        let mdf_path = dir.join("mdf4_rs_export_pcapng_test.mf4");
+        let pcapng_path = dir.join("mdf4_rs_export_pcapng_test.pcapng");
+        let original: Vec<_> = PcapReader::new(&pcapng()[..])
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+
+        let mut logger = RawEthernetLogger::new_file(mdf_path.to_str().unwrap()).unwrap();
+        logger.set_start_time_ns(1_700_000_000_000_000_000).unwrap();
+        for packet in &original {
+            packet.log(&mut logger, 1_700_000_000_000_000);
+        }
+        logger.finalize_file().unwrap();
+
+        let count =
+            export_pcapng(mdf_path.to_str().unwrap(), pcapng_path.to_str().unwrap()).unwrap();
+        let exported: Vec<_> = PcapReader::from_file(pcapng_path.to_str().unwrap())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        std::fs::remove_file(&mdf_path).unwrap();
+        std::fs::remove_file(&pcapng_path).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(exported.len(), 2);
+        for (exported, original) in exported.iter().zip(&original) {
+            assert_eq!(exported.timestamp_us, original.timestamp_us);
+            assert_eq!(exported.data, original.data);
+            assert_eq!(exported.flags.is_tx(), original.flags.is_tx());
+        }
+    }
+}
@@ -11,6 +11,11 @@
 //! - Source metadata (Ethernet interface name)
 //! - Supports standard and jumbo frames
 //! - Direction tracking (Tx/Rx)
+//! - CRC error and truncated frame flags
+//! - ASAM composition members (`ETH_Frame.Dir`, `.CRCError`, `.Source`,
+//!   `.EtherType`, `.DataBytes`, ...) for per-field analysis
+//! - Port name and link speed metadata
+//! - IP/UDP/TCP header decomposition into an `ETH_IP` channel group
 //!
 //! # Example
 //!
@@ -34,15 +39,58 @@
 //! // Get MDF bytes
 //! let mdf_bytes = logger.finalize()?;
 //! ```
+//!
+//! # Bounded Memory and File Rotation
+//!
+//! Frames are buffered until `flush()` or `finalize()`. A gigabit link
+//! carries more than 80,000 full-size frames per second, so captures should
+//! use [`RawEthernetLogger::new_file_streaming`], which writes the buffered
+//! frames to the MDF writer every [`DEFAULT_MAX_BUFFERED_FRAMES`] frames and
+//! flushes the written records to disk according to a [`FlushPolicy`].
+//!
+//! [`RawEthernetLogger::new_file_rotating`] additionally starts a new file
+//! according to a [`SplitPolicy`](crate::split::SplitPolicy), using the part
+//! names of [`split_mdf`](crate::split::split_mdf), so a rotated capture
+//! looks like a split one and can be merged back:
+//!
+//! ```ignore
+//! use mdf4_rs::FlushPolicy;
+//! use mdf4_rs::ethernet::RawEthernetLogger;
+//! use mdf4_rs::split::SplitPolicy;
+//!
+//! // adas_001.mf4, adas_002.mf4, ... of at most 512 MiB each
+//! let mut logger = RawEthernetLogger::new_file_rotating(
+//!     "adas.mf4",
+//!     "ADAS_ETH",
+//!     SplitPolicy::MaxFileSize(512 * 1024 * 1024),
+//! )?;
+//! logger.set_flush_policy(FlushPolicy::EveryNBytes(4 * 1024 * 1024));
+//!
+//! // ... log frames for hours ...
+//!
+//! let parts = logger.finalize_parts()?;
+//! ```
 
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
 
 use super::frame::{
-    ETH_HEADER_SIZE, EthernetFlags, EthernetFrame, MAX_ETHERNET_FRAME, MAX_JUMBO_PAYLOAD,
-    MacAddress,
+    ETH_FRAME_HEADER_SIZE, ETH_HEADER_SIZE, EthernetFlags, EthernetFrame, MAC_ADDR_SIZE,
+    MAX_ETHERNET_FRAME, MAX_JUMBO_PAYLOAD, MacAddress,
 };
-use crate::bus_logging::{BusLoggerConfig, init_bus_channel_group, timestamp_to_seconds};
+use super::ip::IpGroup;
+use crate::bus_logging::{BusLoggerCore, timestamp_to_seconds};
+#[cfg(feature = "std")]
+use crate::split::SplitPolicy;
+use crate::writer::FlushPolicy;
+
+/// Buffer limit of [`RawEthernetLogger::new_file_streaming`] and
+/// [`RawEthernetLogger::new_file_rotating`], in frames.
+///
+/// With standard frames padded to 1525 byte records this bounds the
+/// buffered record data to about 1.5 MB.
+pub const DEFAULT_MAX_BUFFERED_FRAMES: usize = 1_000;
 
 /// Frame size classification for ASAM channel grouping.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -78,6 +126,97 @@ impl FrameSize {
             FrameSize::Standard
         }
     }
+
+    /// Record size in bytes: Timestamp(8) + ETH_Frame.
+    #[cfg(feature = "std")]
+    fn record_size(&self) -> u64 {
+        (8 + ETH_FRAME_HEADER_SIZE + self.max_frame_size()) as u64
+    }
+}
+
+/// Running frame counts, kept across flushes and file rotations.
+#[derive(Debug, Clone, Copy, Default)]
+struct FrameCounts {
+    /// Standard frames
+    standard: usize,
+    /// Jumbo frames
+    jumbo: usize,
+    /// Transmitted frames
+    tx: usize,
+    /// Frames with a CRC error
+    crc_errors: usize,
+    /// Truncated frames
+    truncated: usize,
+}
+
+impl FrameCounts {
+    fn record(&mut self, frame: &RawEthFrame) {
+        match frame.frame_size() {
+            FrameSize::Standard => self.standard += 1,
+            FrameSize::Jumbo => self.jumbo += 1,
+        }
+        self.tx += frame.flags.is_tx() as usize;
+        self.crc_errors += frame.flags.has_crc_error() as usize;
+        self.truncated += frame.flags.is_truncated() as usize;
+    }
+
+    fn total(&self) -> usize {
+        self.standard + self.jumbo
+    }
+}
+
+/// File rotation state of [`RawEthernetLogger::new_file_rotating`].
+#[cfg(feature = "std")]
+struct Rotation<W: crate::writer::MdfWrite> {
+    policy: SplitPolicy,
+    /// Path the part paths are derived from
+    base_path: String,
+    /// Opens the writer of the next part
+    open: fn(&str) -> crate::Result<crate::MdfWriter<W>>,
+    /// Paths of the parts started so far, the last one being written
+    parts: Vec<String>,
+    /// Timestamp of the first frame, start of the rotation intervals
+    start_us: Option<u64>,
+    /// Rotation interval of the current part
+    interval: u64,
+    /// Frames logged to the current part
+    part_frames: u64,
+}
+
+#[cfg(feature = "std")]
+impl<W: crate::writer::MdfWrite> Rotation<W> {
+    /// Interval of a timestamp for [`SplitPolicy::EveryNSeconds`], counted
+    /// from the first frame like `split_mdf` does.
+    fn interval_of(&self, timestamp_us: u64) -> u64 {
+        match (self.policy, self.start_us) {
+            (SplitPolicy::EveryNSeconds(seconds), Some(start_us)) => {
+                (timestamp_us.saturating_sub(start_us) as f64 / 1e6 / seconds).floor() as u64
+            }
+            _ => 0,
+        }
+    }
+
+    /// Whether a new part must be started before logging a frame, given
+    /// the size the current part would reach with it.
+    ///
+    /// A part always takes at least one frame.
+    fn is_due(&self, timestamp_us: u64, part_size: u64) -> bool {
+        if self.part_frames == 0 {
+            return false;
+        }
+        match self.policy {
+            SplitPolicy::EveryNSeconds(_) => self.interval_of(timestamp_us) > self.interval,
+            SplitPolicy::EveryNRecords(records) => self.part_frames >= records,
+            SplitPolicy::MaxFileSize(max_size) => part_size > max_size,
+        }
+    }
+
+    /// Account a frame logged to the current part.
+    fn record(&mut self, timestamp_us: u64) {
+        self.start_us.get_or_insert(timestamp_us);
+        self.interval = self.interval.max(self.interval_of(timestamp_us));
+        self.part_frames += 1;
+    }
 }
 
 /// A buffered raw Ethernet frame with timestamp.
@@ -110,15 +249,21 @@ impl RawEthFrame {
     /// - Byte 0: Flags (direction, FCS valid, etc.)
     /// - Bytes 1-2: Frame length (little-endian)
     /// - Bytes 3+: Frame data (Dst MAC + Src MAC + EtherType + Payload)
+    ///
+    /// Frames longer than `max_size` are cut and flagged as truncated; the
+    /// length field keeps their full length.
     fn to_frame_bytes(&self, max_size: usize) -> Vec<u8> {
-        // ASAM format: flags(1) + length(2) + frame data
-        let header_size = 3;
-        let total_size = header_size + max_size;
+        let total_size = ETH_FRAME_HEADER_SIZE + max_size;
 
         let mut bytes = Vec::with_capacity(total_size);
 
         // Flags byte
-        bytes.push(self.flags.to_byte());
+        let truncated = self.data.len() > max_size;
+        bytes.push(
+            self.flags
+                .with_truncated(truncated || self.flags.is_truncated())
+                .to_byte(),
+        );
 
         // Frame length (little-endian u16)
         let frame_len = self.data.len() as u16;
@@ -148,18 +293,60 @@ impl RawEthFrame {
 /// ## ETH_Frame Format
 ///
 /// Each frame is stored as a ByteArray:
-/// - Byte 0: Flags (bit 0 = Tx/Rx, bit 1 = FCS valid, etc.)
+/// - Byte 0: Flags (bit 0 = Tx/Rx, bit 1 = FCS valid, bit 2 = truncated,
+///   bit 3 = CRC error, bit 4 = VLAN tagged)
 /// - Bytes 1-2: Frame length (little-endian u16)
 /// - Bytes 3+: Frame data (Dst MAC + Src MAC + EtherType + Payload)
+///
+/// The fields are also described by composition members of the
+/// `ETH_Frame` channel, so viewers show them as separate signals.
+///
+/// ## Port Metadata
+///
+/// The source of each channel group is named after the source name. The
+/// port name set with [`set_port_name`](Self::set_port_name) is stored as
+/// the source path, and the link speed set with
+/// [`set_link_speed`](Self::set_link_speed) as the `LinkSpeed` property of
+/// the channel group comment, in bit/s.
+///
+/// ## IP Decomposition
+///
+/// With [`set_ip_decomposition`](Self::set_ip_decomposition) the IP and
+/// UDP/TCP headers of the logged frames are also stored in a channel group
+/// `{source_name}_ETH_IP` with one channel per field and the payload as a
+/// variable length ByteArray (see [`ip`](super::ip)). The MDF writer keeps
+/// variable length data until the file is finalized, so long captures with
+/// IP decomposition should rotate files.
+///
+/// ## Streaming and Rotation
+///
+/// With [`set_max_buffered_frames`](Self::set_max_buffered_frames) the
+/// buffered frames are written to the MDF writer once the limit is reached.
+/// The channel group of a frame size is created when its first frames are
+/// written, so jumbo frames may start at any time of the capture. Files
+/// created with [`new_file_rotating`](RawEthernetLogger::new_file_rotating)
+/// are finalized and replaced by the next part according to a
+/// [`SplitPolicy`](crate::split::SplitPolicy); every part has the same
+/// start time and metadata.
 pub struct RawEthernetLogger<W: crate::writer::MdfWrite> {
-    writer: crate::MdfWriter<W>,
-    /// Source name for metadata
-    source_name: String,
+    core: BusLoggerCore<W>,
+    /// Port name, stored as source path
+    port_name: Option<String>,
+    /// Link speed in bit/s
+    link_speed: Option<u64>,
+    /// Start time, repeated in every rotated part
+    start_time_ns: Option<u64>,
     /// Buffered frames by size category
-    buffers: alloc::collections::BTreeMap<FrameSize, Vec<RawEthFrame>>,
+    buffers: BTreeMap<FrameSize, Vec<RawEthFrame>>,
+    /// Counts of all frames logged, including frames already flushed
+    counts: FrameCounts,
     /// Channel group IDs by frame size
-    channel_groups: alloc::collections::BTreeMap<FrameSize, String>,
-    initialized: bool,
+    channel_groups: BTreeMap<FrameSize, String>,
+    /// Decomposed IP packets, if enabled
+    ip: Option<IpGroup>,
+    /// File rotation state
+    #[cfg(feature = "std")]
+    rotation: Option<Rotation<W>>,
 }
 
 impl RawEthernetLogger<crate::writer::VecWriter> {
@@ -174,13 +361,7 @@ impl RawEthernetLogger<crate::writer::VecWriter> {
     /// Examples: "ETH", "eth0", "Ethernet1", "Vehicle_ETH", etc.
     pub fn with_source_name(source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
-        Ok(Self {
-            writer,
-            source_name: String::from(source_name),
-            buffers: alloc::collections::BTreeMap::new(),
-            channel_groups: alloc::collections::BTreeMap::new(),
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw Ethernet logger with a custom interface name.
@@ -194,19 +375,13 @@ impl RawEthernetLogger<crate::writer::VecWriter> {
     pub fn with_capacity(capacity: usize) -> crate::Result<Self> {
         let writer =
             crate::MdfWriter::from_writer(crate::writer::VecWriter::with_capacity(capacity));
-        Ok(Self {
-            writer,
-            source_name: String::from("ETH"),
-            buffers: alloc::collections::BTreeMap::new(),
-            channel_groups: alloc::collections::BTreeMap::new(),
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, "ETH"))
     }
 
     /// Finalize the MDF file and return the bytes.
     pub fn finalize(mut self) -> crate::Result<Vec<u8>> {
         self.flush_and_finalize()?;
-        Ok(self.writer.into_inner().into_inner())
+        Ok(self.core.into_writer().into_inner().into_inner())
     }
 }
 
@@ -220,13 +395,7 @@ impl RawEthernetLogger<crate::writer::FileWriter> {
     /// Create a new raw Ethernet logger that writes to a file with custom source name.
     pub fn new_file_with_source_name(path: &str, source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::new(path)?;
-        Ok(Self {
-            writer,
-            source_name: String::from(source_name),
-            buffers: alloc::collections::BTreeMap::new(),
-            channel_groups: alloc::collections::BTreeMap::new(),
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw Ethernet logger that writes to a file with custom interface name.
@@ -236,18 +405,111 @@ impl RawEthernetLogger<crate::writer::FileWriter> {
         Self::new_file_with_source_name(path, interface_name)
     }
 
+    /// Create a new raw Ethernet logger that streams to a file.
+    ///
+    /// The logger writes its buffered frames to the MDF writer every
+    /// [`DEFAULT_MAX_BUFFERED_FRAMES`] frames, and the MDF writer flushes the
+    /// written records to disk according to `policy`, so memory stays bounded
+    /// for captures of any length. Use
+    /// [`set_max_buffered_frames`](Self::set_max_buffered_frames) to change
+    /// the buffer limit.
+    pub fn new_file_streaming(
+        path: &str,
+        source_name: &str,
+        policy: FlushPolicy,
+    ) -> crate::Result<Self> {
+        let mut writer = crate::MdfWriter::new(path)?;
+        writer.set_flush_policy(policy);
+        let mut logger = Self::with_writer(writer, source_name);
+        logger.set_max_buffered_frames(DEFAULT_MAX_BUFFERED_FRAMES);
+        Ok(logger)
+    }
+
+    /// Create a new raw Ethernet logger that streams to a sequence of files,
+    /// starting a new file according to `rotation`.
+    ///
+    /// The parts are named like the parts of
+    /// [`split_mdf`](crate::split::split_mdf): `capture.mf4` is written as
+    /// `capture_001.mf4`, `capture_002.mf4`, ... Intervals of
+    /// [`SplitPolicy::EveryNSeconds`] are counted from the first frame, and
+    /// [`SplitPolicy::MaxFileSize`] is checked against the size of the
+    /// written and buffered records; the blocks written on finalization add a
+    /// few hundred bytes, and the IP payloads of
+    /// [`set_ip_decomposition`](Self::set_ip_decomposition) are not counted.
+    /// Every part takes at least one frame.
+    ///
+    /// Buffering is limited to [`DEFAULT_MAX_BUFFERED_FRAMES`] frames as with
+    /// [`new_file_streaming`](Self::new_file_streaming); the flush policy is
+    /// set with [`set_flush_policy`](Self::set_flush_policy) and applies to
+    /// all parts.
+    ///
+    /// # Errors
+    /// Returns an error if the policy is invalid or the first part cannot be
+    /// created.
+    pub fn new_file_rotating(
+        path: &str,
+        source_name: &str,
+        rotation: SplitPolicy,
+    ) -> crate::Result<Self> {
+        rotation.validate()?;
+        let first = crate::split::part_path(path, 0);
+        let writer = crate::MdfWriter::new(&first)?;
+        let mut logger = Self::with_writer(writer, source_name);
+        logger.set_max_buffered_frames(DEFAULT_MAX_BUFFERED_FRAMES);
+        logger.rotation = Some(Rotation {
+            policy: rotation,
+            base_path: String::from(path),
+            open: crate::MdfWriter::new,
+            parts: alloc::vec![first],
+            start_us: None,
+            interval: 0,
+            part_frames: 0,
+        });
+        Ok(logger)
+    }
+
     /// Finalize and close the MDF file.
+    ///
+    /// For a rotating logger this finalizes the last part.
     pub fn finalize_file(mut self) -> crate::Result<()> {
         self.flush_and_finalize()
     }
+
+    /// Finalize and close the MDF file and return the paths of all parts
+    /// written by a rotating logger, in order.
+    ///
+    /// Returns an empty list for a logger without rotation.
+    pub fn finalize_parts(mut self) -> crate::Result<Vec<String>> {
+        self.flush_and_finalize()?;
+        Ok(self
+            .rotation
+            .take()
+            .map(|rotation| rotation.parts)
+            .unwrap_or_default())
+    }
 }
 
 impl<W: crate::writer::MdfWrite> RawEthernetLogger<W> {
+    fn with_writer(writer: crate::MdfWriter<W>, source_name: &str) -> Self {
+        Self {
+            core: BusLoggerCore::new(writer, source_name),
+            port_name: None,
+            link_speed: None,
+            start_time_ns: None,
+            buffers: BTreeMap::new(),
+            counts: FrameCounts::default(),
+            channel_groups: BTreeMap::new(),
+            ip: None,
+            #[cfg(feature = "std")]
+            rotation: None,
+        }
+    }
+
     /// Set the source name for metadata.
     ///
     /// Must be called before logging any frames.
     pub fn set_source_name(&mut self, name: &str) {
-        self.source_name = String::from(name);
+        self.core.set_source_name(name);
     }
 
     /// Set the Ethernet interface name for source metadata.
@@ -257,6 +519,88 @@ impl<W: crate::writer::MdfWrite> RawEthernetLogger<W> {
         self.set_source_name(name);
     }
 
+    /// Set the name of the port the frames are captured on, e.g.
+    /// "Switch1/Port3".
+    ///
+    /// Stored as the path of the channel group sources. Must be called
+    /// before the first flush.
+    pub fn set_port_name(&mut self, port_name: &str) {
+        self.port_name = Some(String::from(port_name));
+    }
+
+    /// Set the link speed of the port in bit/s, e.g. 100_000_000 for
+    /// 100BASE-T1.
+    ///
+    /// Stored as the `LinkSpeed` property of the channel group comments.
+    /// Must be called before the first flush.
+    pub fn set_link_speed(&mut self, bits_per_second: u64) {
+        self.link_speed = Some(bits_per_second);
+    }
+
+    /// Decompose the IP and UDP/TCP headers of the logged frames into the
+    /// `{source_name}_ETH_IP` channel group.
+    ///
+    /// Frames without an IPv4 or IPv6 packet are only logged as
+    /// `ETH_Frame`. Must be called before logging any frames.
+    ///
+    /// Default: disabled
+    pub fn set_ip_decomposition(&mut self, enabled: bool) {
+        self.ip = enabled.then(IpGroup::new);
+    }
+
+    /// Set the absolute start time of the recording in nanoseconds since
+    /// the Unix epoch.
+    ///
+    /// Logged timestamps are relative to this start time. Rotated parts
+    /// repeat it, so their timestamps continue across files.
+    pub fn set_start_time_ns(&mut self, start_time_ns: u64) -> crate::Result<()> {
+        self.start_time_ns = Some(start_time_ns);
+        self.core.writer_mut().set_start_time_ns(start_time_ns)
+    }
+
+    /// Write the buffered frames to the MDF writer once `frames` frames are
+    /// buffered.
+    ///
+    /// This keeps memory bounded without calling `flush()`. An error of an
+    /// automatic flush is returned by the next call to
+    /// [`flush`](Self::flush).
+    ///
+    /// Default: no limit, or [`DEFAULT_MAX_BUFFERED_FRAMES`] for
+    /// [`new_file_streaming`](RawEthernetLogger::new_file_streaming) and
+    /// [`new_file_rotating`](RawEthernetLogger::new_file_rotating)
+    pub fn set_max_buffered_frames(&mut self, frames: usize) {
+        self.core.set_max_buffered_frames(frames);
+    }
+
+    /// Set the flush policy of the MDF writer.
+    ///
+    /// The policy decides when records written to the MDF writer are flushed
+    /// to disk. Combine it with
+    /// [`set_max_buffered_frames`](Self::set_max_buffered_frames) to stream
+    /// long captures to a file with bounded memory.
+    pub fn set_flush_policy(&mut self, policy: FlushPolicy) {
+        self.core.set_flush_policy(policy);
+    }
+
+    /// Get the number of frames and IP packets buffered and not yet written
+    /// to the MDF writer.
+    pub fn buffered_frame_count(&self) -> usize {
+        self.buffers.values().map(Vec::len).sum::<usize>()
+            + self.ip.as_ref().map_or(0, IpGroup::buffered)
+    }
+
+    /// Get the paths of the parts started so far by a rotating logger, the
+    /// last one being the part currently written.
+    ///
+    /// Empty for a logger without rotation.
+    #[cfg(feature = "std")]
+    pub fn part_paths(&self) -> &[String] {
+        self.rotation
+            .as_ref()
+            .map(|rotation| rotation.parts.as_slice())
+            .unwrap_or_default()
+    }
+
     /// Log a raw Ethernet frame from bytes.
     ///
     /// # Arguments
@@ -286,13 +630,38 @@ impl<W: crate::writer::MdfWrite> RawEthernetLogger<W> {
         }
 
         let frame = RawEthFrame::new(timestamp_us, frame_bytes.to_vec(), flags);
+        #[cfg(feature = "std")]
+        self.maybe_rotate(timestamp_us, frame.frame_size());
+        self.counts.record(&frame);
+        if let Some(ip) = &mut self.ip {
+            ip.process(timestamp_us, frame_bytes, flags);
+        }
         self.buffers
             .entry(frame.frame_size())
             .or_default()
             .push(frame);
+        self.maybe_flush();
         true
     }
 
+    /// Log a received frame with a bad FCS (CRC error).
+    pub fn log_crc_error(&mut self, timestamp_us: u64, frame_bytes: &[u8]) -> bool {
+        self.log_with_flags(
+            timestamp_us,
+            frame_bytes,
+            EthernetFlags::rx().with_crc_error(true),
+        )
+    }
+
+    /// Log a received frame that was captured only in part.
+    pub fn log_truncated(&mut self, timestamp_us: u64, frame_bytes: &[u8]) -> bool {
+        self.log_with_flags(
+            timestamp_us,
+            frame_bytes,
+            EthernetFlags::rx().with_truncated(true),
+        )
+    }
+
     /// Log a transmitted frame.
     pub fn log_tx(&mut self, timestamp_us: u64, frame_bytes: &[u8]) -> bool {
         self.log_with_flags(timestamp_us, frame_bytes, EthernetFlags::tx())
@@ -343,17 +712,88 @@ impl<W: crate::writer::MdfWrite> RawEthernetLogger<W> {
         )
     }
 
+    /// Flush if the buffered frames reached the buffer limit.
+    fn maybe_flush(&mut self) {
+        if self.core.is_full(self.buffered_frame_count()) {
+            let result = self.flush_buffers();
+            self.core.record_auto_flush(result);
+        }
+    }
+
+    /// Start the next part if the rotation policy requires it before
+    /// logging a frame of `frame_size` at `timestamp_us`.
+    #[cfg(feature = "std")]
+    fn maybe_rotate(&mut self, timestamp_us: u64, frame_size: FrameSize) {
+        let due = match &self.rotation {
+            Some(rotation) => {
+                let buffered: u64 = self
+                    .buffers
+                    .iter()
+                    .map(|(size, frames)| size.record_size() * frames.len() as u64)
+                    .sum();
+                let part_size = self.core.writer().offset() + buffered + frame_size.record_size();
+                rotation.is_due(timestamp_us, part_size)
+            }
+            None => return,
+        };
+        if due {
+            let result = self.rotate();
+            self.core.record_auto_flush(result);
+        }
+        if let Some(rotation) = &mut self.rotation {
+            rotation.record(timestamp_us);
+        }
+    }
+
+    /// Finalize the current part and continue in the next one.
+    #[cfg(feature = "std")]
+    fn rotate(&mut self) -> crate::Result<()> {
+        self.flush_buffers()?;
+        self.core.finalize()?;
+
+        let Some(rotation) = self.rotation.as_mut() else {
+            return Ok(());
+        };
+        let path = crate::split::part_path(&rotation.base_path, rotation.parts.len());
+        let mut writer = (rotation.open)(&path)?;
+        rotation.parts.push(path);
+        rotation.part_frames = 0;
+
+        writer.set_flush_policy(self.core.writer().flush_policy().clone());
+        if let Some(start_time_ns) = self.start_time_ns {
+            writer.set_start_time_ns(start_time_ns)?;
+        }
+        self.core.replace_writer(writer);
+        self.channel_groups.clear();
+        if let Some(ip) = &mut self.ip {
+            ip.reset_group();
+        }
+        Ok(())
+    }
+
     /// Flush buffered data to the MDF writer.
+    ///
+    /// If an automatic flush or file rotation failed since the last call,
+    /// its error is returned instead.
     pub fn flush(&mut self) -> crate::Result<()> {
-        if !self.initialized {
-            self.initialize_mdf()?;
-        }
+        self.core.take_auto_flush_error()?;
+        self.flush_buffers()
+    }
+
+    /// Write and clear the buffered frames.
+    ///
+    /// Channel groups are created by [`write_frames`](Self::write_frames)
+    /// when the first frames of their size are written.
+    fn flush_buffers(&mut self) -> crate::Result<()> {
+        self.core.ensure_initialized()?;
 
         // Write data for each frame size category (zero-allocation iteration)
         for frame_size in FrameSize::ALL {
-            if self.buffers.contains_key(&frame_size) {
-                self.write_frames(frame_size)?;
-            }
+            self.write_frames(frame_size)?;
+        }
+        if let Some(ip) = &mut self.ip {
+            let (writer, source_name) = self.core.writer_and_source_name();
+            ip.write(writer, source_name, self.port_name.as_deref())?;
         }
 
         // Clear all buffers
@@ -364,86 +804,103 @@ impl<W: crate::writer::MdfWrite> RawEthernetLogger<W> {
         Ok(())
     }
 
-    /// Initialize the MDF file structure with ASAM-compliant channel groups.
-    fn initialize_mdf(&mut self) -> crate::Result<()> {
-        self.writer.init_mdf_file()?;
-
-        // Create a channel group for each frame size that has data
-        for &frame_size in self.buffers.keys() {
-            let max_frame_size = frame_size.max_frame_size();
-            // ETH_Frame size: flags(1) + length(2) + frame_data(max_frame_size)
-            let frame_channel_size = 3 + max_frame_size;
-
-            let config = BusLoggerConfig {
-                source_name: self.source_name.clone(),
-                group_name: frame_size.group_name(&self.source_name),
-                data_channel_name: String::from("ETH_Frame"),
-                data_channel_bits: (frame_channel_size * 8) as u32,
-                source_block: crate::blocks::SourceBlock::ethernet(),
-            };
-
-            let (cg, _data_ch) = init_bus_channel_group(&mut self.writer, &config)?;
-            self.channel_groups.insert(frame_size, cg);
+    /// Create the ETH_Frame channel group of one frame size, with port
+    /// metadata and composition members.
+    fn init_frame_group(&mut self, frame_size: FrameSize) -> crate::Result<String> {
+        use crate::DataType;
+        use crate::blocks::CommonProperties;
+
+        let (writer, source_name) = self.core.writer_and_source_name();
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.set_channel_group_name(&cg, &frame_size.group_name(source_name))?;
+        writer.set_channel_group_source_with_path(
+            &cg,
+            &crate::blocks::SourceBlock::ethernet(),
+            Some(source_name),
+            self.port_name.as_deref(),
+        )?;
+        if let Some(speed) = self.link_speed {
+            let properties =
+                CommonProperties::new().with_value("LinkSpeed", &alloc::format!("{}", speed));
+            writer.set_channel_group_properties(&cg, &properties)?;
         }
 
-        self.initialized = true;
-        Ok(())
+        // Add Timestamp channel (Float64 in seconds - ASAM standard)
+        let time_ch = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(String::from("Timestamp"));
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&time_ch)?;
+        writer.set_channel_unit(&time_ch, "s")?;
+
+        // ETH_Frame size: flags(1) + length(2) + frame_data(max_frame_size)
+        let frame_ch = writer.add_channel(&cg, Some(&time_ch), |ch| {
+            ch.data_type = DataType::ByteArray;
+            ch.name = Some(String::from("ETH_Frame"));
+            ch.bit_count = ((ETH_FRAME_HEADER_SIZE + frame_size.max_frame_size()) * 8) as u32;
+        })?;
+        add_frame_members(writer, &frame_ch, frame_size)?;
+
+        Ok(cg)
     }
 
     /// Write frames for a specific frame size category.
     fn write_frames(&mut self, frame_size: FrameSize) -> crate::Result<()> {
         use crate::DecodedValue;
 
+        match self.buffers.get(&frame_size) {
+            Some(frames) if !frames.is_empty() => {}
+            _ => return Ok(()),
+        }
+
         let cg = match self.channel_groups.get(&frame_size) {
             Some(cg) => cg.clone(),
-            None => return Ok(()),
+            None => {
+                let cg = self.init_frame_group(frame_size)?;
+                self.channel_groups.insert(frame_size, cg.clone());
+                cg
+            }
         };
 
-        let frames = match self.buffers.get(&frame_size) {
-            Some(f) if !f.is_empty() => f,
-            _ => return Ok(()),
-        };
+        let frames = &self.buffers[&frame_size];
 
         let max_size = frame_size.max_frame_size();
-        self.writer.start_data_block_for_cg(&cg, 0)?;
+        let writer = self.core.writer_mut();
+        writer.start_data_block_for_cg(&cg, 0)?;
 
         for frame in frames {
             let values = [
                 DecodedValue::Float(frame.timestamp_s),
                 DecodedValue::ByteArray(frame.to_frame_bytes(max_size)),
             ];
-            self.writer.write_record(&cg, &values)?;
+            writer.write_record(&cg, &values)?;
         }
 
-        self.writer.finish_data_block(&cg)?;
+        writer.finish_data_block(&cg)?;
         Ok(())
     }
 
     /// Flush and finalize the MDF file.
     fn flush_and_finalize(&mut self) -> crate::Result<()> {
         self.flush()?;
-        self.writer.finalize()
+        self.core.finalize()
     }
 
-    /// Get the total number of frames logged.
+    /// Get the total number of frames logged, including frames already
+    /// flushed.
     pub fn total_frame_count(&self) -> usize {
-        self.buffers.values().map(|b| b.len()).sum()
+        self.counts.total()
     }
 
     /// Get the number of standard frames logged.
     pub fn standard_frame_count(&self) -> usize {
-        self.buffers
-            .get(&FrameSize::Standard)
-            .map(|b| b.len())
-            .unwrap_or(0)
+        self.counts.standard
     }
 
     /// Get the number of jumbo frames logged.
     pub fn jumbo_frame_count(&self) -> usize {
-        self.buffers
-            .get(&FrameSize::Jumbo)
-            .map(|b| b.len())
-            .unwrap_or(0)
+        self.counts.jumbo
     }
 
     /// Check if any jumbo frames have been logged.
@@ -453,21 +910,100 @@ impl<W: crate::writer::MdfWrite> RawEthernetLogger<W> {
 
     /// Get count of transmitted frames.
     pub fn tx_frame_count(&self) -> usize {
-        self.buffers
-            .values()
-            .flat_map(|frames| frames.iter())
-            .filter(|f| f.flags.is_tx())
-            .count()
+        self.counts.tx
     }
 
     /// Get count of received frames.
     pub fn rx_frame_count(&self) -> usize {
-        self.buffers
-            .values()
-            .flat_map(|frames| frames.iter())
-            .filter(|f| f.flags.is_rx())
-            .count()
+        self.counts.total() - self.counts.tx
+    }
+
+    /// Get count of frames with a CRC error.
+    pub fn crc_error_count(&self) -> usize {
+        self.counts.crc_errors
+    }
+
+    /// Get count of truncated frames.
+    pub fn truncated_frame_count(&self) -> usize {
+        self.counts.truncated
+    }
+
+    /// Get the number of IP packets decomposed, including packets already
+    /// flushed.
+    pub fn ip_packet_count(&self) -> usize {
+        self.ip.as_ref().map_or(0, |ip| ip.count)
+    }
+}
+
+/// Describe the fields of an ETH_Frame channel by ASAM composition
+/// members, so viewers show them as separate signals:
+/// - `ETH_Frame.Dir`: frame direction, shown as `Rx` or `Tx`
+/// - `ETH_Frame.FCSValid`, `ETH_Frame.Truncated` and `ETH_Frame.CRCError`:
+///   the FCS and error flags
+/// - `ETH_Frame.DataLength`: frame length in bytes
+/// - `ETH_Frame.Destination` and `ETH_Frame.Source`: MAC addresses
+/// - `ETH_Frame.EtherType`: EtherType, or the VLAN TPID of tagged frames
+/// - `ETH_Frame.DataBytes`: the zero-padded bytes after the EtherType
+///
+/// The members overlay the ByteArray, whose layout is unchanged.
+fn add_frame_members<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    frame_ch: &str,
+    frame_size: FrameSize,
+) -> crate::Result<()> {
+    use crate::DataType;
+
+    let dir = writer.add_component_channel(frame_ch, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("ETH_Frame.Dir"));
+        ch.bit_count = 1;
+    })?;
+    writer.add_value_to_text_conversion(&[(0, "Rx"), (1, "Tx")], "", Some(&dir))?;
+
+    let mut prev = dir;
+    for (name, bit) in [("FCSValid", 1), ("Truncated", 2), ("CRCError", 3)] {
+        prev = writer.add_component_channel(frame_ch, Some(&prev), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(alloc::format!("ETH_Frame.{}", name));
+            ch.bit_offset = bit;
+            ch.bit_count = 1;
+        })?;
     }
+
+    let data_length = writer.add_component_channel(frame_ch, Some(&prev), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(String::from("ETH_Frame.DataLength"));
+        ch.byte_offset = 1;
+        ch.bit_count = 16;
+    })?;
+    writer.set_channel_unit(&data_length, "byte")?;
+
+    let mac_offset = ETH_FRAME_HEADER_SIZE as u32;
+    let destination = writer.add_component_channel(frame_ch, Some(&data_length), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("ETH_Frame.Destination"));
+        ch.byte_offset = mac_offset;
+        ch.bit_count = (MAC_ADDR_SIZE * 8) as u32;
+    })?;
+    let source = writer.add_component_channel(frame_ch, Some(&destination), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("ETH_Frame.Source"));
+        ch.byte_offset = mac_offset + MAC_ADDR_SIZE as u32;
+        ch.bit_count = (MAC_ADDR_SIZE * 8) as u32;
+    })?;
+    let ethertype = writer.add_component_channel(frame_ch, Some(&source), |ch| {
+        ch.data_type = DataType::UnsignedIntegerBE;
+        ch.name = Some(String::from("ETH_Frame.EtherType"));
+        ch.byte_offset = mac_offset + 2 * MAC_ADDR_SIZE as u32;
+        ch.bit_count = 16;
+    })?;
+    writer.add_component_channel(frame_ch, Some(&ethertype), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("ETH_Frame.DataBytes"));
+        ch.byte_offset = (ETH_FRAME_HEADER_SIZE + ETH_HEADER_SIZE) as u32;
+        ch.bit_count = ((frame_size.max_frame_size() - ETH_HEADER_SIZE) * 8) as u32;
+    })?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -588,13 +1124,13 @@ mod tests {
     #[test]
     fn test_source_name() {
         let logger = RawEthernetLogger::with_source_name("eth0").unwrap();
-        assert_eq!(logger.source_name, "eth0");
+        assert_eq!(logger.core.source_name(), "eth0");
     }
 
     #[test]
     fn test_interface_name_alias() {
         let logger = RawEthernetLogger::with_interface_name("eth0").unwrap();
-        assert_eq!(logger.source_name, "eth0");
+        assert_eq!(logger.core.source_name(), "eth0");
     }
 
     #[test]
@@ -612,4 +1148,306 @@ mod tests {
         // Check frame data starts at byte 3
         assert_eq!(&bytes[3..3 + frame_data.len()], &frame_data[..]);
     }
+
+    #[test]
+    fn test_error_flags() {
+        let mut logger = RawEthernetLogger::new().unwrap();
+        let frame = create_test_frame(100);
+
+        assert!(logger.log(1000, &frame));
+        assert!(logger.log_crc_error(2000, &frame));
+        assert!(logger.log_truncated(3000, &frame[..60]));
+        let frame_struct = EthernetFrame::from_bytes(&frame)
+            .unwrap()
+            .with_crc_error(true);
+        assert!(logger.log_frame(4000, frame_struct));
+
+        assert_eq!(logger.total_frame_count(), 4);
+        assert_eq!(logger.crc_error_count(), 2);
+        assert_eq!(logger.truncated_frame_count(), 1);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&mdf_bytes).is_ok());
+    }
+
+    #[test]
+    fn test_oversized_frame_is_flagged_truncated() {
+        let raw_frame = RawEthFrame::new(0, create_test_frame(20), EthernetFlags::rx());
+        let bytes = raw_frame.to_frame_bytes(ETH_HEADER_SIZE + 10);
+
+        assert_eq!(bytes.len(), ETH_FRAME_HEADER_SIZE + ETH_HEADER_SIZE + 10);
+        assert!(EthernetFlags::from_byte(bytes[0]).is_truncated());
+        // The length field keeps the full frame length
+        assert_eq!(u16::from_le_bytes([bytes[1], bytes[2]]), 34);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_members_and_port_metadata() {
+        use crate::blocks::{BlockParse, ChannelBlock, TextBlock};
+
+        let mut logger = RawEthernetLogger::with_source_name("ADAS_ETH").unwrap();
+        logger.set_port_name("Switch1/Port3");
+        logger.set_link_speed(100_000_000);
+        logger.log_tx(1000, &create_test_frame(46));
+        logger.log_crc_error(2000, &create_test_frame(46));
+
+        let mdf_bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&mdf_bytes).is_ok());
+        let temp_path = std::env::temp_dir().join("test_eth_members.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let group = &mdf.channel_groups()[0];
+        assert_eq!(group.name().unwrap().as_deref(), Some("ADAS_ETH_ETH_Frame"));
+        let source = group.source().unwrap().unwrap();
+        assert_eq!(source.name.as_deref(), Some("ADAS_ETH"));
+        assert_eq!(source.path.as_deref(), Some("Switch1/Port3"));
+        assert!(group.comment().unwrap().unwrap().contains("100000000"));
+
+        // Members follow the composition link of the ETH_Frame channel
+        let channels = group.channels();
+        let mut members = Vec::new();
+        let mut address = channels[1].block().component_addr;
+        while address != 0 {
+            let member = ChannelBlock::from_bytes(&mdf_bytes[address as usize..]).unwrap();
+            let name = TextBlock::from_bytes(&mdf_bytes[member.name_addr as usize..])
+                .unwrap()
+                .text;
+            members.push((name, member.byte_offset, member.bit_offset));
+            address = member.next_ch_addr;
+        }
+        let names: Vec<&str> = members.iter().map(|(name, _, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "ETH_Frame.Dir",
+                "ETH_Frame.FCSValid",
+                "ETH_Frame.Truncated",
+                "ETH_Frame.CRCError",
+                "ETH_Frame.DataLength",
+                "ETH_Frame.Destination",
+                "ETH_Frame.Source",
+                "ETH_Frame.EtherType",
+                "ETH_Frame.DataBytes",
+            ]
+        );
+        // Offsets in the record: Timestamp(8) + flags(1) + length(2)
+        assert_eq!(members[3].1, 8);
+        assert_eq!(members[3].2, 3);
+        assert_eq!(members[7].1, 8 + 3 + 12);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    /// Number of records of each channel group of an MDF file, by name.
+    #[cfg(feature = "std")]
+    fn record_counts(path: &str) -> Vec<(String, usize)> {
+        let mdf = crate::MDF::from_file(path).unwrap();
+        mdf.channel_groups()
+            .iter()
+            .map(|group| {
+                let name = group.name().unwrap().unwrap_or_default();
+                (name, group.channels()[1].values().unwrap().len())
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_streaming_with_buffer_limit() {
+        let temp_path = std::env::temp_dir().join("ethernet_streaming_test.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger =
+            RawEthernetLogger::new_file_streaming(path, "ETH", FlushPolicy::EveryNRecords(10))
+                .unwrap();
+        logger.set_max_buffered_frames(10);
+
+        for i in 0..95u64 {
+            logger.log(i * 100, &create_test_frame(46 + i as usize));
+            assert!(logger.buffered_frame_count() < 10);
+        }
+        // The jumbo group is created after the first flushes
+        logger.log_tx(9500, &create_test_frame(4000));
+        logger.log_crc_error(9600, &create_test_frame(46));
+
+        // Counts include the frames already written to the file
+        assert_eq!(logger.buffered_frame_count(), 7);
+        assert_eq!(logger.total_frame_count(), 97);
+        assert_eq!(logger.standard_frame_count(), 96);
+        assert_eq!(logger.jumbo_frame_count(), 1);
+        assert_eq!(logger.tx_frame_count(), 1);
+        assert_eq!(logger.rx_frame_count(), 96);
+        assert_eq!(logger.crc_error_count(), 1);
+
+        logger.finalize_file().unwrap();
+
+        let counts = record_counts(path);
+        assert_eq!(
+            counts,
+            [
+                (String::from("ETH_ETH_Frame"), 96),
+                (String::from("ETH_ETH_Frame_Jumbo"), 1),
+            ]
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_rotation_by_time() {
+        let temp_path = std::env::temp_dir().join("ethernet_rotation_time.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger =
+            RawEthernetLogger::new_file_rotating(path, "ETH", SplitPolicy::EveryNSeconds(1.0))
+                .unwrap();
+        logger.set_start_time_ns(1_700_000_000_000_000_000).unwrap();
+        logger.set_port_name("Port1");
+
+        // 3.5 s of frames every 100 ms, starting at 0.25 s
+        for i in 0..35u64 {
+            assert!(logger.log(250_000 + i * 100_000, &create_test_frame(46)));
+        }
+        assert_eq!(logger.part_paths().len(), 4);
+        assert_eq!(logger.total_frame_count(), 35);
+
+        let parts = logger.finalize_parts().unwrap();
+        assert_eq!(parts.len(), 4);
+        assert!(parts[0].ends_with("ethernet_rotation_time_001.mf4"));
+        assert!(parts[3].ends_with("ethernet_rotation_time_004.mf4"));
+
+        let per_part: Vec<usize> = parts.iter().map(|part| record_counts(part)[0].1).collect();
+        assert_eq!(per_part, [10, 10, 10, 5]);
+
+        // Every part has the same start time and port metadata
+        for part in &parts {
+            let mdf = crate::MDF::from_file(part).unwrap();
+            assert_eq!(mdf.raw().header.start_time_ns, 1_700_000_000_000_000_000);
+            let source = mdf.channel_groups()[0].source().unwrap().unwrap();
+            assert_eq!(source.path.as_deref(), Some("Port1"));
+            std::fs::remove_file(part).ok();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_rotation_by_size() {
+        const MAX_SIZE: u64 = 64 * 1024;
+
+        let temp_path = std::env::temp_dir().join("ethernet_rotation_size.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger =
+            RawEthernetLogger::new_file_rotating(path, "ETH", SplitPolicy::MaxFileSize(MAX_SIZE))
+                .unwrap();
+        logger.set_max_buffered_frames(16);
+        for i in 0..200u64 {
+            logger.log(i * 10, &create_test_frame(1000));
+        }
+        let parts = logger.finalize_parts().unwrap();
+        assert!(parts.len() >= 4);
+
+        let mut total = 0;
+        for part in &parts {
+            let size = std::fs::metadata(part).unwrap().len();
+            assert!(size <= MAX_SIZE + 1024, "{} is {} bytes", part, size);
+            total += record_counts(part)[0].1;
+            std::fs::remove_file(part).ok();
+        }
+        assert_eq!(total, 200);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_rotation_rejects_invalid_policy() {
+        let temp_path = std::env::temp_dir().join("ethernet_rotation_invalid.mf4");
+        let path = temp_path.to_str().unwrap();
+        assert!(
+            RawEthernetLogger::new_file_rotating(path, "ETH", SplitPolicy::EveryNRecords(0))
+                .is_err()
+        );
+        assert!(
+            RawEthernetLogger::new_file_rotating(path, "ETH", SplitPolicy::EveryNSeconds(0.0))
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ip_decomposition() {
+        use crate::DecodedValue::{ByteArray, UnsignedInteger};
+
+        let mut logger = RawEthernetLogger::with_source_name("ADAS_ETH").unwrap();
+        logger.set_ip_decomposition(true);
+
+        // IPv4/UDP frame: 192.168.1.10:30501 -> 192.168.1.20:30490
+        let mut udp = Vec::new();
+        udp.extend_from_slice(&[0x45, 0x00, 0x00, 32, 0, 0, 0x40, 0x00, 64, 17, 0, 0]);
+        udp.extend_from_slice(&[192, 168, 1, 10, 192, 168, 1, 20]);
+        udp.extend_from_slice(&[0x77, 0x25, 0x77, 0x1A, 0x00, 12, 0x00, 0x00]);
+        udp.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        let frame = EthernetFrame::new(
+            MacAddress::broadcast(),
+            MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            ethertype::IPV4,
+            udp,
+        );
+        assert!(logger.log_frame_ref(1000, &frame));
+        assert!(logger.log_tx(2000, &frame.to_bytes()));
+        // Not an IP frame
+        assert!(logger.log(3000, &create_test_frame(46)));
+
+        assert_eq!(logger.total_frame_count(), 3);
+        assert_eq!(logger.ip_packet_count(), 2);
+        assert_eq!(logger.buffered_frame_count(), 5);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("test_eth_ip.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let ip = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("ADAS_ETH_ETH_IP"))
+            .unwrap();
+        let channels = ip.channels();
+        let names: Vec<String> = channels
+            .iter()
+            .map(|ch| ch.name().unwrap().unwrap_or_default())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Timestamp",
+                "Dir",
+                "IP.Version",
+                "IP.Protocol",
+                "IP.Source",
+                "IP.Destination",
+                "IP.SourcePort",
+                "IP.DestinationPort",
+                "IP.Payload",
+            ]
+        );
+
+        let source = channels[4].values().unwrap();
+        let mut mapped = [0u8; 16];
+        mapped[10..12].copy_from_slice(&[0xFF, 0xFF]);
+        mapped[12..].copy_from_slice(&[192, 168, 1, 10]);
+        assert_eq!(source[0], Some(ByteArray(mapped.to_vec())));
+        assert_eq!(
+            channels[7].values().unwrap(),
+            [Some(UnsignedInteger(30490)), Some(UnsignedInteger(30490))]
+        );
+        assert_eq!(
+            channels[8].values().unwrap()[1],
+            Some(ByteArray(alloc::vec![0xDE, 0xAD, 0xBE, 0xEF]))
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
 }
@@ -0,0 +1,22 @@
+//! Export of channel groups to other file formats.
+//!
+//! - [`ndjson`]: newline-delimited JSON, one file per channel group
+//! - [`gps`]: GPX or KML tracks of latitude and longitude channels
+//!
+//! # Feature Flags
+//!
+//! - `parquet`: Enables [`parquet`](mod@parquet) for one Parquet file per
+//!   channel group
+//! - `matlab`: Enables [`matlab`] for MATLAB v7.3 MAT-files with one struct
+//!   per channel group
+//! - `xlsx`: Enables [`xlsx`] for Excel workbooks with one worksheet per
+//!   channel group
+
+pub mod gps;
+#[cfg(feature = "matlab")]
+pub mod matlab;
+pub mod ndjson;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
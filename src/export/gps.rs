@@ -0,0 +1,449 @@
+//! GPX and KML export of GPS tracks.
+//!
+//! Channel groups with latitude and longitude channels, e.g. the GNSS
+//! groups of vehicle test recordings, are exported as tracks with time
+//! stamps. The channels are either named explicitly or detected by name
+//! and unit, which finds `Latitude`, `GPS_Lat`, `PosLon` or `gnss.lng`
+//! with a degree unit (or none), but not the lateral acceleration `AccLat`
+//! in m/s².
+
+use crate::{ChannelGroup, DecodedValue, Error, MDF, Result, blocks::xml_escape};
+use std::io::{BufWriter, Write};
+
+/// Output format of [`export_gps()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpsFormat {
+    /// GPS Exchange Format 1.1, one `<trk>` per track
+    Gpx,
+    /// Keyhole Markup Language, one `<gx:Track>` placemark per track
+    Kml,
+}
+
+/// Options for [`gps_tracks()`] and [`export_gps()`].
+///
+/// By default latitude, longitude and altitude channels are detected by
+/// name and unit.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::export::gps::{GpsFormat, GpsOptions, export_gps};
+///
+/// let options = GpsOptions::new()
+///     .with_channels("GNSS_Latitude", "GNSS_Longitude")
+///     .with_altitude("GNSS_Height");
+/// export_gps("drive.mf4", "drive.gpx", GpsFormat::Gpx, &options)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpsOptions {
+    /// Names of the latitude and longitude channels; `None` detects them
+    channels: Option<(String, String)>,
+    /// Name of the altitude channel; `None` detects it
+    altitude: Option<String>,
+}
+
+impl GpsOptions {
+    /// Options detecting the position channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the channels with these names as latitude and longitude.
+    ///
+    /// Only groups holding both channels are exported.
+    pub fn with_channels(mut self, latitude: &str, longitude: &str) -> Self {
+        self.set_channels(latitude, longitude);
+        self
+    }
+
+    /// Use the channels with these names as latitude and longitude (see
+    /// [`with_channels()`](Self::with_channels)).
+    pub fn set_channels(&mut self, latitude: &str, longitude: &str) {
+        self.channels = Some((latitude.to_string(), longitude.to_string()));
+    }
+
+    /// Names of the latitude and longitude channels; `None` if they are
+    /// detected.
+    pub fn channels(&self) -> Option<(&str, &str)> {
+        self.channels
+            .as_ref()
+            .map(|(lat, lon)| (lat.as_str(), lon.as_str()))
+    }
+
+    /// Use the channel with this name as altitude in meters.
+    pub fn with_altitude(mut self, altitude: &str) -> Self {
+        self.set_altitude(altitude);
+        self
+    }
+
+    /// Use the channel with this name as altitude in meters.
+    pub fn set_altitude(&mut self, altitude: &str) {
+        self.altitude = Some(altitude.to_string());
+    }
+
+    /// Name of the altitude channel; `None` if it is detected.
+    pub fn altitude(&self) -> Option<&str> {
+        self.altitude.as_deref()
+    }
+}
+
+/// A position of a [`GpsTrack`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsPoint {
+    /// Absolute time in nanoseconds since 1970-01-01 (UTC); `None` for
+    /// groups without a time master channel
+    pub time_ns: Option<u64>,
+    /// Latitude in degrees
+    pub latitude: f64,
+    /// Longitude in degrees
+    pub longitude: f64,
+    /// Altitude in meters, if an altitude channel was found
+    pub altitude: Option<f64>,
+}
+
+/// The positions of one channel group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpsTrack {
+    /// Acquisition name of the channel group
+    pub name: Option<String>,
+    /// Valid positions in record order
+    pub points: Vec<GpsPoint>,
+}
+
+/// Kind of position channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coordinate {
+    Latitude,
+    Longitude,
+    Altitude,
+}
+
+/// Lowercase words of a channel name, split at separators and at
+/// lowercase-uppercase transitions (`GPS_Lat`, `PosLat` and `gnss.lat` all
+/// end with `lat`).
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if (!c.is_alphanumeric() || (prev_lower && c.is_uppercase())) && !word.is_empty() {
+            words.push(core::mem::take(&mut word));
+        }
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+        prev_lower = c.is_lowercase();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// The coordinate a channel holds, judged by its name and unit.
+fn detect_coordinate(name: &str, unit: Option<&str>) -> Option<Coordinate> {
+    let words = name_words(name);
+    let has = |candidates: &[&str]| words.iter().any(|w| candidates.contains(&w.as_str()));
+    let unit = unit.map(|u| u.trim().to_lowercase()).unwrap_or_default();
+    let degrees = matches!(
+        unit.as_str(),
+        "" | "deg" | "degree" | "degrees" | "°" | "grad"
+    );
+    if degrees && has(&["lat", "latitude"]) {
+        Some(Coordinate::Latitude)
+    } else if degrees && has(&["lon", "lng", "long", "longitude"]) {
+        Some(Coordinate::Longitude)
+    } else if matches!(unit.as_str(), "" | "m") && has(&["alt", "altitude"]) {
+        Some(Coordinate::Altitude)
+    } else {
+        None
+    }
+}
+
+/// Positions of the latitude, longitude and altitude channels of a group.
+type PositionChannels = (usize, usize, Option<usize>);
+
+/// The position channels of a group, if it has latitude and longitude.
+fn position_channels(
+    group: &ChannelGroup<'_>,
+    options: &GpsOptions,
+) -> Result<Option<PositionChannels>> {
+    let (mut lat, mut lon, mut alt) = (None, None, None);
+    for (i, channel) in group.channels().iter().enumerate() {
+        let Some(name) = channel.name()? else {
+            continue;
+        };
+        let detected = detect_coordinate(&name, channel.unit()?.as_deref());
+        let coordinate = match options.channels() {
+            Some((latitude, _)) if name == latitude => Some(Coordinate::Latitude),
+            Some((_, longitude)) if name == longitude => Some(Coordinate::Longitude),
+            Some(_) => None,
+            None => detected.filter(|&c| c != Coordinate::Altitude),
+        };
+        let coordinate = match options.altitude() {
+            Some(altitude) if name == altitude => Some(Coordinate::Altitude),
+            Some(_) => coordinate,
+            None => coordinate.or(detected.filter(|&c| c == Coordinate::Altitude)),
+        };
+        // The first matching channel of each kind is used
+        let slot = match coordinate {
+            Some(Coordinate::Latitude) => &mut lat,
+            Some(Coordinate::Longitude) => &mut lon,
+            Some(Coordinate::Altitude) => &mut alt,
+            None => continue,
+        };
+        slot.get_or_insert(i);
+    }
+    Ok(lat.zip(lon).map(|(lat, lon)| (lat, lon, alt)))
+}
+
+/// Numeric value of a sample; `None` if invalid or not numeric.
+fn sample_f64(value: &Option<DecodedValue>) -> Option<f64> {
+    value
+        .as_ref()
+        .and_then(DecodedValue::as_f64)
+        .filter(|v| v.is_finite())
+}
+
+/// Read the GPS tracks of an MDF file.
+///
+/// Each channel group with latitude and longitude channels (see
+/// [`GpsOptions`]) becomes one track. Records with an invalid position, or
+/// one outside ±90° latitude and ±180° longitude, are skipped. Times are
+/// the values of the time master channel added to the start time of the
+/// file.
+///
+/// # Returns
+/// The tracks in channel group order, or an [`crate::Error`] if decoding
+/// fails.
+pub fn gps_tracks(mdf: &MDF, options: &GpsOptions) -> Result<Vec<GpsTrack>> {
+    let start_ns = mdf.raw().header.start_time_ns;
+    let mut tracks = Vec::new();
+    for group in mdf.channel_groups() {
+        let Some((lat, lon, alt)) = position_channels(&group, options)? else {
+            continue;
+        };
+        let channels = group.channels();
+        let master = channels
+            .iter()
+            .position(|ch| matches!(ch.block().channel_type, 2 | 3) && ch.block().sync_type == 1);
+        let times = master.map(|m| channels[m].values()).transpose()?;
+        let latitudes = channels[lat].values()?;
+        let longitudes = channels[lon].values()?;
+        let altitudes = alt.map(|a| channels[a].values()).transpose()?;
+
+        let mut points = Vec::with_capacity(latitudes.len());
+        for (i, (latitude, longitude)) in latitudes.iter().zip(&longitudes).enumerate() {
+            let (Some(latitude), Some(longitude)) = (sample_f64(latitude), sample_f64(longitude))
+            else {
+                continue;
+            };
+            if latitude.abs() > 90.0 || longitude.abs() > 180.0 {
+                continue;
+            }
+            let time_ns = times
+                .as_ref()
+                .and_then(|times| sample_f64(times.get(i)?))
+                .map(|t| start_ns.saturating_add_signed((t * 1e9).round() as i64));
+            let altitude = altitudes.as_ref().and_then(|a| sample_f64(a.get(i)?));
+            points.push(GpsPoint {
+                time_ns,
+                latitude,
+                longitude,
+                altitude,
+            });
+        }
+        tracks.push(GpsTrack {
+            name: group.name()?,
+            points,
+        });
+    }
+    Ok(tracks)
+}
+
+/// Format nanoseconds since 1970-01-01 as an ISO 8601 UTC time with
+/// milliseconds, e.g. `2024-05-17T09:30:00.250Z`.
+pub(crate) fn format_time(time_ns: u64) -> String {
+    let millis = time_ns / 1_000_000;
+    let secs = millis / 1000;
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        millis % 1000
+    )
+}
+
+/// Write tracks as a GPX 1.1 document.
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if writing fails.
+pub fn write_gpx<W: Write>(tracks: &[GpsTrack], writer: W) -> Result<()> {
+    let mut out = BufWriter::new(writer);
+    out.write_all(
+        b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+          <gpx version=\"1.1\" creator=\"mdf4-rs\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    )?;
+    for track in tracks {
+        out.write_all(b"  <trk>\n")?;
+        if let Some(name) = &track.name {
+            writeln!(out, "    <name>{}</name>", xml_escape(name))?;
+        }
+        out.write_all(b"    <trkseg>\n")?;
+        for point in &track.points {
+            write!(
+                out,
+                "      <trkpt lat=\"{}\" lon=\"{}\">",
+                point.latitude, point.longitude
+            )?;
+            if let Some(altitude) = point.altitude {
+                write!(out, "<ele>{}</ele>", altitude)?;
+            }
+            if let Some(time_ns) = point.time_ns {
+                write!(out, "<time>{}</time>", format_time(time_ns))?;
+            }
+            out.write_all(b"</trkpt>\n")?;
+        }
+        out.write_all(b"    </trkseg>\n  </trk>\n")?;
+    }
+    out.write_all(b"</gpx>\n")?;
+    out.flush().map_err(Error::IOError)
+}
+
+/// Write tracks as a KML document with one `<gx:Track>` placemark per
+/// track.
+///
+/// Tracks without time stamps are written as `<LineString>` paths instead,
+/// since `<gx:Track>` needs a time for every position.
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if writing fails.
+pub fn write_kml<W: Write>(tracks: &[GpsTrack], writer: W) -> Result<()> {
+    let mut out = BufWriter::new(writer);
+    out.write_all(
+        b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+          <kml xmlns=\"http://www.opengis.net/kml/2.2\" xmlns:gx=\"http://www.google.com/kml/ext/2.2\">\n\
+          <Document>\n",
+    )?;
+    for track in tracks {
+        out.write_all(b"  <Placemark>\n")?;
+        if let Some(name) = &track.name {
+            writeln!(out, "    <name>{}</name>", xml_escape(name))?;
+        }
+        let coordinates = |point: &GpsPoint, separator: &str| {
+            format!(
+                "{}{sep}{}{sep}{}",
+                point.longitude,
+                point.latitude,
+                point.altitude.unwrap_or(0.0),
+                sep = separator
+            )
+        };
+        if track.points.iter().all(|p| p.time_ns.is_some()) {
+            out.write_all(b"    <gx:Track>\n")?;
+            for time_ns in track.points.iter().filter_map(|p| p.time_ns) {
+                writeln!(out, "      <when>{}</when>", format_time(time_ns))?;
+            }
+            for point in &track.points {
+                writeln!(
+                    out,
+                    "      <gx:coord>{}</gx:coord>",
+                    coordinates(point, " ")
+                )?;
+            }
+            out.write_all(b"    </gx:Track>\n")?;
+        } else {
+            out.write_all(b"    <LineString>\n      <coordinates>\n")?;
+            for point in &track.points {
+                writeln!(out, "        {}", coordinates(point, ","))?;
+            }
+            out.write_all(b"      </coordinates>\n    </LineString>\n")?;
+        }
+        out.write_all(b"  </Placemark>\n")?;
+    }
+    out.write_all(b"</Document>\n</kml>\n")?;
+    out.flush().map_err(Error::IOError)
+}
+
+/// Export the GPS tracks of an MDF file to a GPX or KML file.
+///
+/// See [`gps_tracks()`] for how tracks are found and [`write_gpx()`] and
+/// [`write_kml()`] for the formats.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Path of the GPX or KML file
+/// * `format` - Output format
+/// * `options` - Position channels, or detection
+///
+/// # Returns
+/// The number of written positions, or an [`crate::Error`] if the file has
+/// no latitude and longitude channels or reading or writing fails.
+pub fn export_gps(
+    input_path: &str,
+    output_path: &str,
+    format: GpsFormat,
+    options: &GpsOptions,
+) -> Result<usize> {
+    let mdf = MDF::from_file(input_path)?;
+    let tracks = gps_tracks(&mdf, options)?;
+    if tracks.is_empty() {
+        return Err(Error::BlockSerializationError(
+            "No latitude and longitude channels found".to_string(),
+        ));
+    }
+    let file = std::fs::File::create(output_path)?;
+    match format {
+        GpsFormat::Gpx => write_gpx(&tracks, file)?,
+        GpsFormat::Kml => write_kml(&tracks, file)?,
+    }
+    Ok(tracks.iter().map(|t| t.points.len()).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_position_channels() {
+        let detect = |name, unit| detect_coordinate(name, unit);
+        assert_eq!(detect("Latitude", Some("deg")), Some(Coordinate::Latitude));
+        assert_eq!(detect("GPS_Lat", None), Some(Coordinate::Latitude));
+        assert_eq!(detect("PosLon", Some("°")), Some(Coordinate::Longitude));
+        assert_eq!(detect("gnss.lng", None), Some(Coordinate::Longitude));
+        assert_eq!(
+            detect("GNSS_Altitude", Some("m")),
+            Some(Coordinate::Altitude)
+        );
+        assert_eq!(detect("AccLat", Some("m/s^2")), None);
+        assert_eq!(detect("Plate", None), None);
+        assert_eq!(detect("Longitudinal", None), None);
+    }
+
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_time(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_time(1_715_938_200_250_000_000),
+            "2024-05-17T09:30:00.250Z"
+        );
+    }
+}
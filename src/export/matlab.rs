@@ -0,0 +1,238 @@
+//! MATLAB v7.3 MAT-file export (`matlab` feature).
+//!
+//! MAT-files of version 7.3 are HDF5 files with a 512 byte header block,
+//! readable with `load` in MATLAB and with HDF5 libraries elsewhere. Every
+//! channel group becomes a struct variable holding the time vector, one
+//! column vector per numeric channel and the units of the channels.
+
+use crate::{ChannelGroup, DecodedValue, Error, MDF, Result};
+use hdf5::{File, Location, types::FixedAscii};
+use std::io::{Seek, SeekFrom, Write};
+
+/// Size of the MAT-file header block in front of the HDF5 data.
+const USERBLOCK_SIZE: u64 = 512;
+
+/// Maximum length of MATLAB identifiers.
+const MAX_NAME_LENGTH: usize = 63;
+
+/// Struct fields of a group that channel fields must not take.
+const RESERVED_FIELDS: [&str; 2] = ["time", "units"];
+
+fn hdf5_error(e: hdf5::Error) -> Error {
+    Error::BlockSerializationError(format!("MAT export failed: {}", e))
+}
+
+/// The 128 byte MAT-file header: description text, subsystem data offset,
+/// version 0x0200 and the little endian indicator `IM`.
+fn mat_header() -> [u8; 128] {
+    let mut header = [b' '; 128];
+    let text = "MATLAB 7.3 MAT-file, Platform: mdf4-rs, Created by: mdf4-rs HDF5 schema 1.00 .";
+    header[..text.len()].copy_from_slice(text.as_bytes());
+    header[116..124].fill(0);
+    header[124..126].copy_from_slice(&0x0200u16.to_le_bytes());
+    header[126..128].copy_from_slice(b"IM");
+    header
+}
+
+/// A valid MATLAB identifier for `name` that is not in `taken`.
+///
+/// Characters other than ASCII letters, digits and `_` become `_`, names
+/// not starting with a letter get an `x` prefix, and repeated names a
+/// `_1`, `_2`, ... suffix.
+fn field_name(name: &str, taken: &[String]) -> String {
+    let mut base: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        base.insert(0, 'x');
+    }
+    base.truncate(MAX_NAME_LENGTH);
+
+    let mut candidate = base.clone();
+    let mut n = 0;
+    while taken.contains(&candidate) {
+        n += 1;
+        let suffix = format!("_{}", n);
+        let mut stem = base.clone();
+        stem.truncate(MAX_NAME_LENGTH - suffix.len());
+        candidate = stem + &suffix;
+    }
+    candidate
+}
+
+/// Set the `MATLAB_class` attribute of a dataset or group.
+fn set_class<const N: usize>(location: &Location, class: &str) -> Result<()> {
+    let value = FixedAscii::<N>::from_ascii(class.as_bytes())
+        .map_err(|e| Error::BlockSerializationError(format!("MAT export failed: {}", e)))?;
+    location
+        .new_attr::<FixedAscii<N>>()
+        .create("MATLAB_class")
+        .and_then(|attr| attr.write_scalar(&value))
+        .map_err(hdf5_error)
+}
+
+/// Write `values` as a MATLAB column vector of doubles.
+fn write_column(group: &hdf5::Group, name: &str, values: &[f64]) -> Result<()> {
+    // HDF5 dimensions are the reverse of MATLAB's: [1, n] is an n x 1 vector
+    let dataset = group
+        .new_dataset::<f64>()
+        .shape([1, values.len()])
+        .create(name)
+        .map_err(hdf5_error)?;
+    dataset.write_raw(values).map_err(hdf5_error)?;
+    set_class::<6>(&dataset, "double")
+}
+
+/// Write `text` as a MATLAB character row vector (UTF-16 code units).
+fn write_text(group: &hdf5::Group, name: &str, text: &str) -> Result<()> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    let dataset = group
+        .new_dataset::<u16>()
+        .shape([units.len(), 1])
+        .create(name)
+        .map_err(hdf5_error)?;
+    dataset.write_raw(&units).map_err(hdf5_error)?;
+    set_class::<4>(&dataset, "char")?;
+    dataset
+        .new_attr::<i32>()
+        .create("MATLAB_int_decode")
+        .and_then(|attr| attr.write_scalar(&2))
+        .map_err(hdf5_error)
+}
+
+/// Write a channel group as a struct variable named `name`.
+///
+/// The master channel becomes the `time` field. Numeric channels become
+/// fields named after the channels, with invalid samples as `NaN`; other
+/// channels are skipped. The `units` field is a struct with the unit of
+/// every written field that has one.
+fn write_group(file: &File, name: &str, group: &ChannelGroup<'_>) -> Result<()> {
+    let variable = file.create_group(name).map_err(hdf5_error)?;
+    set_class::<6>(&variable, "struct")?;
+
+    let mut taken: Vec<String> = RESERVED_FIELDS.iter().map(|f| f.to_string()).collect();
+    let mut units = Vec::new();
+    let mut has_time = false;
+    for (i, channel) in group.channels().iter().enumerate() {
+        let values = channel.values()?;
+        if values.iter().flatten().any(|v| {
+            !matches!(
+                v,
+                DecodedValue::UnsignedInteger(_)
+                    | DecodedValue::SignedInteger(_)
+                    | DecodedValue::Float(_)
+            )
+        }) {
+            continue;
+        }
+        let is_master = matches!(channel.block().channel_type, 2 | 3);
+        let field = if is_master && !has_time {
+            has_time = true;
+            "time".to_string()
+        } else {
+            let channel_name = channel.name()?.unwrap_or_else(|| format!("channel_{}", i));
+            let field = field_name(&channel_name, &taken);
+            taken.push(field.clone());
+            field
+        };
+
+        let column: Vec<f64> = values
+            .iter()
+            .map(|v| {
+                v.as_ref()
+                    .and_then(DecodedValue::as_f64)
+                    .unwrap_or(f64::NAN)
+            })
+            .collect();
+        write_column(&variable, &field, &column)?;
+        if let Some(unit) = channel.unit()?.filter(|u| !u.is_empty()) {
+            units.push((field, unit));
+        }
+    }
+
+    if !units.is_empty() {
+        let unit_struct = variable.create_group("units").map_err(hdf5_error)?;
+        set_class::<6>(&unit_struct, "struct")?;
+        for (field, unit) in &units {
+            write_text(&unit_struct, field, unit)?;
+        }
+    }
+    Ok(())
+}
+
+/// Export an MDF file to a MATLAB v7.3 MAT-file.
+///
+/// Every channel group becomes a struct variable named after the group
+/// (`cg1`, `cg2`, ... for unnamed groups), with names turned into valid
+/// MATLAB identifiers:
+/// - `time`: the master channel, as a column vector
+/// - one column vector of doubles per numeric channel, with conversions
+///   applied and invalid samples as `NaN`
+/// - `units`: a struct with the unit string of every field that has one
+///
+/// Text and byte array channels are not exported. Each channel is read and
+/// written on its own, so only one channel is held in memory at a time.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::export::matlab::export_mat;
+///
+/// export_mat("recording.mf4", "recording.mat")?;
+/// // In MATLAB: load recording.mat; plot(Engine.time, Engine.Speed)
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Path of the MAT-file to create
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading or writing fails.
+pub fn export_mat(input_path: &str, output_path: &str) -> Result<()> {
+    let mdf = MDF::from_file(input_path)?;
+    {
+        let file = File::with_options()
+            .with_fcpl(|fcpl| fcpl.userblock(USERBLOCK_SIZE))
+            .create(output_path)
+            .map_err(hdf5_error)?;
+        let mut taken = Vec::new();
+        for (i, group) in mdf.channel_groups().iter().enumerate() {
+            let group_name = group.name()?.unwrap_or_else(|| format!("cg{}", i + 1));
+            let name = field_name(&group_name, &taken);
+            write_group(&file, &name, group)?;
+            taken.push(name);
+        }
+        file.close().map_err(hdf5_error)?;
+    }
+
+    // HDF5 leaves the user block alone; fill in the MAT-file header
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(output_path)
+        .map_err(Error::IOError)?;
+    file.seek(SeekFrom::Start(0)).map_err(Error::IOError)?;
+    file.write_all(&mat_header()).map_err(Error::IOError)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makes_matlab_identifiers() {
+        let taken = vec!["time".to_string(), "Speed".to_string()];
+        assert_eq!(
+            field_name("Engine.Speed [rpm]", &taken),
+            "Engine_Speed__rpm_"
+        );
+        assert_eq!(field_name("1st gear", &taken), "x1st_gear");
+        assert_eq!(field_name("time", &taken), "time_1");
+        assert_eq!(field_name(&"a".repeat(80), &taken).len(), MAX_NAME_LENGTH);
+
+        let header = mat_header();
+        assert!(header.starts_with(b"MATLAB 7.3 MAT-file"));
+        assert_eq!(&header[124..], &[0x00, 0x02, b'I', b'M']);
+    }
+}
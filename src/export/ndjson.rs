@@ -0,0 +1,260 @@
+//! Newline-delimited JSON export of channel groups.
+//!
+//! Every record of a channel group becomes one line holding a JSON object,
+//! e.g. `{"t":0.01,"Speed":1200,"Gear":"D"}`, the input format of log
+//! shippers such as Logstash, Vector or Promtail.
+
+use crate::{ChannelGroup, DecodedValue, Error, MDF, Result};
+use serde_json::Value;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Options for [`export_ndjson()`] and [`write_group_ndjson()`].
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::export::ndjson::{NdjsonOptions, export_ndjson};
+///
+/// let options = NdjsonOptions::new()
+///     .with_channels(&["EngineSpeed", "Throttle"])
+///     .with_time_window(10.0, 20.0);
+/// export_ndjson("input.mf4", "out", &options)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NdjsonOptions {
+    /// Names of the channels to export; `None` exports all
+    channels: Option<Vec<String>>,
+    /// Time window `[start, end]` in seconds; `None` exports all records
+    time_window: Option<(f64, f64)>,
+}
+
+impl NdjsonOptions {
+    /// Options exporting all channels and records.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Export only the channels with these names.
+    ///
+    /// The time stamp `t` is written along with the selected channels, and
+    /// groups without selected channels are skipped.
+    pub fn with_channels(mut self, names: &[&str]) -> Self {
+        self.set_channels(names);
+        self
+    }
+
+    /// Export only the channels with these names (see
+    /// [`with_channels()`](Self::with_channels)).
+    pub fn set_channels(&mut self, names: &[&str]) {
+        self.channels = Some(names.iter().map(|name| name.to_string()).collect());
+    }
+
+    /// Names of the exported channels; `None` if all are exported.
+    pub fn channels(&self) -> Option<&[String]> {
+        self.channels.as_deref()
+    }
+
+    /// Export only records with a time stamp within `[start, end]` seconds.
+    ///
+    /// Records without a valid time stamp, including all records of groups
+    /// without a master channel, are skipped.
+    pub fn with_time_window(mut self, start: f64, end: f64) -> Self {
+        self.set_time_window(start, end);
+        self
+    }
+
+    /// Export only records within `[start, end]` seconds (see
+    /// [`with_time_window()`](Self::with_time_window)).
+    pub fn set_time_window(&mut self, start: f64, end: f64) {
+        self.time_window = Some((start, end));
+    }
+
+    /// Time window of the exported records; `None` if all are exported.
+    pub fn time_window(&self) -> Option<(f64, f64)> {
+        self.time_window
+    }
+}
+
+/// JSON value of a sample; invalid samples and non-finite numbers are
+/// `null`, byte arrays hex strings.
+fn json_value(value: Option<DecodedValue>) -> Value {
+    match value {
+        Some(DecodedValue::UnsignedInteger(v)) => Value::from(v),
+        Some(DecodedValue::SignedInteger(v)) => Value::from(v),
+        Some(DecodedValue::Float(v)) => Value::from(v),
+        Some(DecodedValue::String(s)) => Value::String(s),
+        Some(
+            DecodedValue::ByteArray(bytes)
+            | DecodedValue::MimeSample(bytes)
+            | DecodedValue::MimeStream(bytes),
+        ) => Value::String(bytes.iter().map(|b| format!("{:02x}", b)).collect()),
+        Some(DecodedValue::Unknown) | None => Value::Null,
+    }
+}
+
+/// Position of the master channel of a group and positions and names of
+/// the selected other channels.
+type GroupColumns = (Option<usize>, Vec<(usize, String)>);
+
+/// Columns of a group exported with `options`.
+fn group_columns(group: &ChannelGroup<'_>, options: &NdjsonOptions) -> Result<GroupColumns> {
+    let channels = group.channels();
+    let master = channels
+        .iter()
+        .position(|ch| matches!(ch.block().channel_type, 2 | 3));
+    let mut columns = Vec::new();
+    for (i, channel) in channels.iter().enumerate() {
+        if Some(i) == master {
+            continue;
+        }
+        let name = channel.name()?.unwrap_or_else(|| format!("channel_{}", i));
+        let selected = options.channels().is_none_or(|names| names.contains(&name));
+        if selected {
+            columns.push((i, name));
+        }
+    }
+    Ok((master, columns))
+}
+
+/// Write the records of a channel group as newline-delimited JSON.
+///
+/// Each line is an object with the time stamp of the master channel as `t`
+/// (omitted for groups without one) followed by the selected channels by
+/// name (`channel_<n>` for unnamed ones), with conversions applied. Invalid
+/// samples are `null`. Nothing is written if none of the selected channels
+/// is in the group.
+///
+/// Records are streamed; only one record is held in memory at a time.
+///
+/// # Returns
+/// The number of written lines, or an [`crate::Error`] if decoding or
+/// writing fails.
+pub fn write_group_ndjson<W: Write>(
+    group: &ChannelGroup<'_>,
+    writer: W,
+    options: &NdjsonOptions,
+) -> Result<u64> {
+    let (master, selected) = group_columns(group, options)?;
+    if options.channels().is_some() && selected.is_empty() {
+        return Ok(0);
+    }
+    let channels = group.channels();
+    let mut time_values = master.map(|m| channels[m].iter_values()).transpose()?;
+    let mut columns = Vec::with_capacity(selected.len());
+    for (i, name) in &selected {
+        // Keys are serialized once
+        let key = serde_json::to_string(name).map_err(json_error)?;
+        columns.push((key, channels[*i].iter_values()?));
+    }
+    let iter_count = columns.len() + usize::from(time_values.is_some());
+
+    let mut writer = BufWriter::new(writer);
+    let mut lines = 0;
+    let mut line = String::new();
+    let mut values = Vec::with_capacity(columns.len());
+    loop {
+        let mut ended = 0;
+        let mut time = None;
+        if let Some(iter) = &mut time_values {
+            match iter.next() {
+                Some(value) => time = value?,
+                None => ended += 1,
+            }
+        }
+        values.clear();
+        for (_, iter) in &mut columns {
+            match iter.next() {
+                Some(value) => values.push(value?),
+                None => ended += 1,
+            }
+        }
+        if ended == iter_count {
+            break;
+        }
+        if ended > 0 {
+            return Err(Error::BlockSerializationError(
+                "Channels of a group have different numbers of values".to_string(),
+            ));
+        }
+
+        if let Some((start, end)) = options.time_window() {
+            let in_window = time
+                .as_ref()
+                .and_then(DecodedValue::as_f64)
+                .is_some_and(|t| t >= start && t <= end);
+            if !in_window {
+                continue;
+            }
+        }
+
+        line.clear();
+        line.push('{');
+        if master.is_some() {
+            line.push_str("\"t\":");
+            line.push_str(&json_value(time).to_string());
+        }
+        for ((key, _), value) in columns.iter().zip(values.drain(..)) {
+            if line.len() > 1 {
+                line.push(',');
+            }
+            line.push_str(key);
+            line.push(':');
+            line.push_str(&json_value(value).to_string());
+        }
+        line.push_str("}\n");
+        writer.write_all(line.as_bytes()).map_err(Error::IOError)?;
+        lines += 1;
+    }
+    writer.flush().map_err(Error::IOError)?;
+    Ok(lines)
+}
+
+fn json_error(e: serde_json::Error) -> Error {
+    Error::BlockSerializationError(format!("NDJSON export failed: {}", e))
+}
+
+/// Export every channel group of an MDF file to its own NDJSON file.
+///
+/// The files are written to `output_dir` as `<name>_cg0.ndjson`,
+/// `<name>_cg1.ndjson`, ... in the order of the channel groups, where
+/// `<name>` is the file stem of the input. Groups without selected
+/// channels are skipped. See [`write_group_ndjson()`] for the format of the
+/// lines.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_dir` - Existing directory for the NDJSON files
+/// * `options` - Channel selection and time window
+///
+/// # Returns
+/// The paths of the written files, or an [`crate::Error`] if reading or
+/// writing fails.
+pub fn export_ndjson(
+    input_path: &str,
+    output_dir: &str,
+    options: &NdjsonOptions,
+) -> Result<Vec<String>> {
+    let mdf = MDF::from_file(input_path)?;
+    let stem = Path::new(input_path)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+
+    let mut paths = Vec::new();
+    for (i, group) in mdf.channel_groups().iter().enumerate() {
+        let (_, selected) = group_columns(group, options)?;
+        if options.channels().is_some() && selected.is_empty() {
+            continue;
+        }
+        let path = Path::new(output_dir)
+            .join(format!("{}_cg{}.ndjson", stem, i))
+            .to_string_lossy()
+            .into_owned();
+        let file = std::fs::File::create(&path).map_err(Error::IOError)?;
+        write_group_ndjson(group, file, options)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
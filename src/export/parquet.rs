@@ -0,0 +1,172 @@
+//! Parquet export of channel groups (`parquet` feature).
+//!
+//! Every channel group becomes one Parquet file with a column per channel,
+//! the master (time) channel first. Invalid samples are written as nulls.
+//! Column types follow [`MdfIndex::arrow_schema()`](crate::MdfIndex::arrow_schema),
+//! except that 16 bit floats are widened to `Float32`.
+
+use crate::{
+    ChannelGroup, Error, MDF, Result,
+    index::arrow::{to_array, value_type},
+};
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType as ArrowType, Field, Schema};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use std::{collections::HashMap, io::Write, path::Path, sync::Arc};
+
+/// Records per row group and per batch read from the source.
+const ROWS_PER_BATCH: usize = 65_536;
+
+/// Arrow schema of a channel group, with the channel indices in column
+/// order.
+fn group_schema(group: &ChannelGroup<'_>) -> Result<(Schema, Vec<usize>)> {
+    let channels = group.channels();
+    let mut order: Vec<usize> = (0..channels.len()).collect();
+    // Master channels first; the sort is stable
+    order.sort_by_key(|&i| !matches!(channels[i].block().channel_type, 2 | 3));
+
+    let mut fields = Vec::with_capacity(order.len());
+    for &i in &order {
+        let channel = &channels[i];
+        let block = channel.block();
+        let conversion = block.conversion.as_ref();
+        let data_type = match value_type(block.data_type, block.bit_count, conversion) {
+            ArrowType::Float16 => ArrowType::Float32,
+            data_type => data_type,
+        };
+        let name = channel.name()?.unwrap_or_else(|| format!("channel_{}", i));
+
+        let mut metadata = HashMap::new();
+        if let Some(unit) = channel.unit()? {
+            metadata.insert("unit".to_string(), unit);
+        }
+        if let Some(comment) = channel.comment()? {
+            metadata.insert("comment".to_string(), comment);
+        }
+        if matches!(block.channel_type, 2 | 3) {
+            metadata.insert("mdf.master".to_string(), "true".to_string());
+        }
+        fields.push(Field::new(name, data_type, true).with_metadata(metadata));
+    }
+
+    let mut metadata = HashMap::new();
+    if let Some(name) = group.name()? {
+        metadata.insert("mdf.group_name".to_string(), name);
+    }
+    if let Some(comment) = group.comment()? {
+        metadata.insert("mdf.group_comment".to_string(), comment);
+    }
+    Ok((Schema::new_with_metadata(fields, metadata), order))
+}
+
+/// Write a channel group as Parquet to `writer`.
+///
+/// Columns are the channels of the group, master (time) channel first,
+/// named after the channels (`channel_<n>` for unnamed ones). Invalid
+/// samples are nulls. Field metadata holds the `unit` and `comment` of the
+/// channel and `mdf.master` for master channels; file metadata holds
+/// `mdf.group_name` and `mdf.group_comment`. Both are stored in the Arrow
+/// schema embedded in the file.
+///
+/// Records are read in batches of 65536, each written as a Snappy
+/// compressed row group.
+///
+/// # Arguments
+/// * `group` - Channel group to export
+/// * `writer` - Destination of the Parquet data
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if decoding or writing fails.
+pub fn write_channel_group<W: Write + Send>(group: &ChannelGroup<'_>, writer: W) -> Result<()> {
+    let (schema, order) = group_schema(group)?;
+    let schema = Arc::new(schema);
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_max_row_group_size(ROWS_PER_BATCH)
+        .build();
+    let parquet_error = |e: parquet::errors::ParquetError| {
+        Error::BlockSerializationError(format!("Parquet export failed: {}", e))
+    };
+    let mut writer =
+        ArrowWriter::try_new(writer, schema.clone(), Some(properties)).map_err(parquet_error)?;
+
+    let channels = group.channels();
+    let mut iters = order
+        .iter()
+        .map(|&i| channels[i].iter_values())
+        .collect::<Result<Vec<_>>>()?;
+    let mut column = Vec::with_capacity(ROWS_PER_BATCH);
+    loop {
+        let mut columns = Vec::with_capacity(iters.len());
+        let mut rows = None;
+        for (iter, field) in iters.iter_mut().zip(schema.fields()) {
+            column.clear();
+            for value in iter.by_ref().take(ROWS_PER_BATCH) {
+                column.push(value?);
+            }
+            if *rows.get_or_insert(column.len()) != column.len() {
+                return Err(Error::BlockSerializationError(format!(
+                    "Channel {:?} has a different number of values",
+                    field.name()
+                )));
+            }
+            columns.push(to_array(&column, field.data_type()));
+        }
+        let rows = rows.unwrap_or(0);
+        if rows == 0 {
+            break;
+        }
+        let batch = RecordBatch::try_new(schema.clone(), columns)
+            .map_err(|e| Error::BlockSerializationError(format!("Parquet export failed: {}", e)))?;
+        writer.write(&batch).map_err(parquet_error)?;
+        if rows < ROWS_PER_BATCH {
+            break;
+        }
+    }
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+/// Export every channel group of an MDF file to its own Parquet file.
+///
+/// The files are written to `output_dir` as `<name>_cg0.parquet`,
+/// `<name>_cg1.parquet`, ... in the order of the channel groups, where
+/// `<name>` is the file stem of the input. See [`write_channel_group()`]
+/// for the layout of the files.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::export::parquet::export_parquet;
+///
+/// let files = export_parquet("recording.mf4", "lake/raw")?;
+/// println!("Wrote {} Parquet files", files.len());
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_dir` - Existing directory for the Parquet files
+///
+/// # Returns
+/// The paths of the written files, or an [`crate::Error`] if reading or
+/// writing fails.
+pub fn export_parquet(input_path: &str, output_dir: &str) -> Result<Vec<String>> {
+    let mdf = MDF::from_file(input_path)?;
+    let stem = Path::new(input_path)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+
+    let mut paths = Vec::new();
+    for (i, group) in mdf.channel_groups().iter().enumerate() {
+        let path = Path::new(output_dir)
+            .join(format!("{}_cg{}.parquet", stem, i))
+            .to_string_lossy()
+            .into_owned();
+        let file = std::fs::File::create(&path).map_err(Error::IOError)?;
+        write_channel_group(group, std::io::BufWriter::new(file))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
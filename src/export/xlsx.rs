@@ -0,0 +1,258 @@
+//! Excel workbook export (`xlsx` feature).
+//!
+//! Every channel group becomes one worksheet with a column per channel, the
+//! master (time) channel first. The header row holds the channel names with
+//! their units, and numbers carry a cell format from the precision of the
+//! channel, so the sheets are readable without further formatting.
+
+use crate::{ChannelGroup, DecodedValue, Error, MDF, Result, blocks::ChannelBlock};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::io::{Seek, Write};
+
+/// Rows of a worksheet, including the header row.
+const MAX_ROWS: usize = 1_048_576;
+
+/// Columns of a worksheet.
+const MAX_COLUMNS: usize = 16_384;
+
+/// Maximum length of worksheet names.
+const MAX_SHEET_NAME_LENGTH: usize = 31;
+
+/// Characters Excel does not allow in worksheet names.
+const INVALID_SHEET_CHARS: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+
+/// Number format of master channels without a precision: microseconds.
+const TIME_FORMAT: &str = "0.000000";
+
+fn xlsx_error(e: XlsxError) -> Error {
+    Error::BlockSerializationError(format!("XLSX export failed: {}", e))
+}
+
+/// A valid worksheet name for `name` that is not in `taken`.
+///
+/// Characters Excel rejects become `_`, leading and trailing apostrophes
+/// are dropped and names are cut to 31 characters. Repeated names, which
+/// Excel compares case-insensitively, get a ` (2)`, ` (3)`, ... suffix.
+fn sheet_name(name: &str, taken: &[String]) -> String {
+    let base: String = name
+        .trim_matches('\'')
+        .chars()
+        .map(|c| {
+            if INVALID_SHEET_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(MAX_SHEET_NAME_LENGTH)
+        .collect();
+    let is_taken = |candidate: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(candidate));
+
+    let mut candidate = base.clone();
+    let mut n = 1;
+    while is_taken(&candidate) {
+        n += 1;
+        let suffix = format!(" ({})", n);
+        let stem: String = base
+            .chars()
+            .take(MAX_SHEET_NAME_LENGTH - suffix.len())
+            .collect();
+        candidate = stem + &suffix;
+    }
+    candidate
+}
+
+/// Number format with `decimals` decimal places, e.g. `0.000` for 3.
+fn decimal_format(decimals: u8) -> String {
+    match decimals {
+        0 => "0".to_string(),
+        n => format!("0.{}", "0".repeat(n as usize)),
+    }
+}
+
+/// Number formats of a channel column: one for floats and one for
+/// integers, `None` for Excel's `General` format.
+///
+/// A valid precision of the channel applies to both, except `0xFF`
+/// (infinite precision). Without one, integers have no decimal places and
+/// master channels show microseconds.
+fn number_formats(block: &ChannelBlock) -> (Option<String>, Option<String>) {
+    let has_precision = block.flags & ChannelBlock::FLAG_PRECISION_VALID != 0;
+    match block.precision {
+        p if has_precision && p != 0xFF => (Some(decimal_format(p)), Some(decimal_format(p))),
+        _ if matches!(block.channel_type, 2 | 3) => {
+            (Some(TIME_FORMAT.to_string()), Some(decimal_format(0)))
+        }
+        _ => (None, Some(decimal_format(0))),
+    }
+}
+
+/// Header cell of a channel: its name and, if it has one, the unit in
+/// brackets, e.g. `Speed [rpm]`.
+fn header(name: &str, unit: Option<&str>) -> String {
+    match unit.filter(|u| !u.is_empty()) {
+        Some(unit) => format!("{} [{}]", name, unit),
+        None => name.to_string(),
+    }
+}
+
+/// Write a channel group to a worksheet.
+///
+/// Numbers are written as numbers with the formats of
+/// [`number_formats()`], text as text and byte arrays as hex strings.
+/// Invalid samples and non-finite floats leave the cell empty.
+fn write_group(worksheet: &mut Worksheet, group: &ChannelGroup<'_>) -> Result<()> {
+    let channels = group.channels();
+    if channels.len() > MAX_COLUMNS {
+        return Err(Error::BlockSerializationError(format!(
+            "Channel group has {} channels, more than the {} columns of a worksheet",
+            channels.len(),
+            MAX_COLUMNS
+        )));
+    }
+    let mut order: Vec<usize> = (0..channels.len()).collect();
+    // Master channels first; the sort is stable
+    order.sort_by_key(|&i| !matches!(channels[i].block().channel_type, 2 | 3));
+
+    let bold = Format::new().set_bold();
+    for (column, &i) in order.iter().enumerate() {
+        let channel = &channels[i];
+        let col = column as u16;
+        let name = channel.name()?.unwrap_or_else(|| format!("channel_{}", i));
+        let title = header(&name, channel.unit()?.as_deref());
+        worksheet
+            .write_string_with_format(0, col, &title, &bold)
+            .map_err(xlsx_error)?;
+        let width = title.chars().count().clamp(10, 60);
+        worksheet
+            .set_column_width(col, width as f64 + 2.0)
+            .map_err(xlsx_error)?;
+
+        let (float_format, integer_format) = number_formats(channel.block());
+        let float_format = float_format.map(|f| Format::new().set_num_format(f));
+        let integer_format = integer_format.map(|f| Format::new().set_num_format(f));
+        for (n, value) in channel.iter_values()?.enumerate() {
+            let row = n + 1;
+            if row >= MAX_ROWS {
+                return Err(Error::BlockSerializationError(format!(
+                    "Channel group has more than the {} records of a worksheet",
+                    MAX_ROWS - 1
+                )));
+            }
+            let row = row as u32;
+            let (number, format) = match value? {
+                None | Some(DecodedValue::Unknown) => continue,
+                Some(DecodedValue::Float(v)) if !v.is_finite() => continue,
+                Some(DecodedValue::Float(v)) => (v, float_format.as_ref()),
+                Some(DecodedValue::UnsignedInteger(v)) => (v as f64, integer_format.as_ref()),
+                Some(DecodedValue::SignedInteger(v)) => (v as f64, integer_format.as_ref()),
+                Some(DecodedValue::String(text)) => {
+                    worksheet.write_string(row, col, text).map_err(xlsx_error)?;
+                    continue;
+                }
+                Some(
+                    DecodedValue::ByteArray(bytes)
+                    | DecodedValue::MimeSample(bytes)
+                    | DecodedValue::MimeStream(bytes),
+                ) => {
+                    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                    worksheet.write_string(row, col, hex).map_err(xlsx_error)?;
+                    continue;
+                }
+            };
+            let written = match format {
+                Some(format) => worksheet.write_number_with_format(row, col, number, format),
+                None => worksheet.write_number(row, col, number),
+            };
+            written.map_err(xlsx_error)?;
+        }
+    }
+    worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+    Ok(())
+}
+
+/// Build the workbook of an MDF file.
+fn workbook(mdf: &MDF) -> Result<Workbook> {
+    let mut workbook = Workbook::new();
+    let mut taken = Vec::new();
+    for (i, group) in mdf.channel_groups().iter().enumerate() {
+        let group_name = group
+            .name()?
+            .filter(|name| !name.trim_matches('\'').is_empty())
+            .unwrap_or_else(|| format!("cg{}", i + 1));
+        let name = sheet_name(&group_name, &taken);
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(&name).map_err(xlsx_error)?;
+        write_group(worksheet, group)?;
+        taken.push(name);
+    }
+    Ok(workbook)
+}
+
+/// Write an MDF file as an Excel workbook to `writer`.
+///
+/// See [`export_xlsx()`] for the layout of the workbook.
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if decoding or writing fails.
+pub fn write_xlsx<W: Write + Seek + Send>(mdf: &MDF, writer: W) -> Result<()> {
+    workbook(mdf)?.save_to_writer(writer).map_err(xlsx_error)
+}
+
+/// Export an MDF file to an Excel workbook.
+///
+/// Every channel group becomes a worksheet named after the group (`cg1`,
+/// `cg2`, ... for unnamed groups), with names made valid for Excel:
+/// - a bold, frozen header row with the channel names and units, e.g.
+///   `Speed [rpm]`, master (time) channel first
+/// - one row per record, with conversions applied; invalid samples are
+///   empty cells
+/// - floats formatted with the precision of the channel if it has one,
+///   otherwise as `General` (microseconds for the time channel); integers
+///   without decimal places
+///
+/// The workbook is built in memory and a worksheet holds at most 1048575
+/// records, so large recordings should be cut or decimated first.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::export::xlsx::export_xlsx;
+///
+/// export_xlsx("summary.mf4", "summary.xlsx")?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Path of the workbook to create
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if a group does not fit a
+/// worksheet or reading or writing fails.
+pub fn export_xlsx(input_path: &str, output_path: &str) -> Result<()> {
+    let mdf = MDF::from_file(input_path)?;
+    workbook(&mdf)?.save(output_path).map_err(xlsx_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makes_sheet_names() {
+        let taken = vec!["Engine".to_string(), "a".repeat(31)];
+        assert_eq!(sheet_name("CAN1: Engine/Body", &taken), "CAN1_ Engine_Body");
+        assert_eq!(sheet_name("'quoted'", &taken), "quoted");
+        assert_eq!(sheet_name("engine", &taken), "engine (2)");
+        let long = sheet_name(&"a".repeat(40), &taken);
+        assert_eq!(long, format!("{} (2)", "a".repeat(27)));
+    }
+
+    #[test]
+    fn formats_headers_and_numbers() {
+        assert_eq!(header("Speed", Some("rpm")), "Speed [rpm]");
+        assert_eq!(header("Gear", Some("")), "Gear");
+        assert_eq!(decimal_format(0), "0");
+        assert_eq!(decimal_format(3), "0.000");
+    }
+}
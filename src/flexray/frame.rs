@@ -5,6 +5,7 @@
 
 use alloc::vec::Vec;
 
+use crate::blocks::SourceBlock;
 use crate::bus_logging::BusFrame;
 
 /// Maximum FlexRay payload size (254 bytes, 127 words × 2).
@@ -382,6 +383,14 @@ impl Default for FlexRayFrame {
 }
 
 impl BusFrame for FlexRayFrame {
+    const CHANNEL_NAME: &'static str = "FLEXRAY_Frame";
+    /// Header and maximum payload; shorter payloads are zero-padded
+    const RECORD_SIZE: usize = FLEXRAY_HEADER_SIZE + MAX_FLEXRAY_PAYLOAD;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::flexray()
+    }
+
     fn to_mdf_bytes(&self) -> Vec<u8> {
         self.to_bytes()
     }
@@ -12,6 +12,9 @@
 //! - Null frame handling
 //! - Direction tracking (Tx/Rx)
 //! - Error flag tracking
+//! - Streaming to file with bounded memory
+//! - PDU extraction with cycle multiplexing (FIBEX base cycle and repetition)
+//! - Reading `FLEXRAY_Frame` captures back with `FlexRayOverlayReader` (std only)
 //!
 //! # FlexRay Protocol Overview
 //!
@@ -61,6 +64,9 @@
 //! ```
 
 pub mod frame;
+#[cfg(feature = "std")]
+mod overlay;
+pub mod pdu;
 mod raw_logger;
 
 // Re-export frame types
@@ -69,5 +75,12 @@ pub use frame::{
     MAX_FLEXRAY_PAYLOAD, MAX_SLOT_ID,
 };
 
+// Re-export PDU types
+pub use pdu::FlexRayPdu;
+
 // Re-export logger
-pub use raw_logger::RawFlexRayLogger;
+pub use raw_logger::{DEFAULT_MAX_BUFFERED_FRAMES, RawFlexRayLogger};
+
+// Re-export reader
+#[cfg(feature = "std")]
+pub use overlay::{FlexRayFrameIter, FlexRayOverlayReader};
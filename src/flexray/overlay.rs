@@ -0,0 +1,277 @@
+//! Reader for raw FlexRay captures stored in MDF4 files.
+//!
+//! This module provides [`FlexRayOverlayReader`], which finds the
+//! `FLEXRAY_Frame` channel groups of an MDF4 file and yields the logged
+//! frames as [`FlexRayFrame`]s in timestamp order, whether the file was
+//! written by [`RawFlexRayLogger`](super::RawFlexRayLogger) or by another
+//! tool using the same layout.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::flexray::FlexRayOverlayReader;
+//! use mdf4_rs::FileRangeReader;
+//!
+//! let overlay = FlexRayOverlayReader::from_file("flexray_capture.mf4")?;
+//! let mut reader = FileRangeReader::new("flexray_capture.mf4")?;
+//!
+//! for frame in overlay.frames_iter(&mut reader) {
+//!     let (timestamp_us, frame) = frame?;
+//!     println!(
+//!         "{}: slot {} cycle {} {:?} {:02X?}",
+//!         timestamp_us, frame.slot_id, frame.cycle, frame.channel, frame.payload
+//!     );
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use super::frame::FlexRayFrame;
+use crate::bus_logging::{CaptureGroup, MergedFrames};
+use crate::index::{ByteRangeReader, IndexedChannelGroup, MdfIndex};
+use crate::{DataType, Error, Result};
+
+/// Information about a `FLEXRAY_Frame` channel group.
+#[derive(Debug)]
+struct FlexRayGroup {
+    /// Index in the MdfIndex channel_groups
+    group_index: usize,
+    /// Timestamp channel index
+    timestamp_channel: usize,
+    /// FLEXRAY_Frame channel index (ByteArray)
+    frame_channel: usize,
+}
+
+/// Reader for raw FlexRay frames stored in MDF4 files.
+///
+/// # Storage Format
+///
+/// The reader expects the ASAM MDF4 Bus Logging `FLEXRAY_Frame` format, as
+/// written by [`RawFlexRayLogger`](super::RawFlexRayLogger):
+/// - Timestamp (Float64 seconds, or integer microseconds). Groups without a
+///   channel named `Timestamp` fall back to their master channel.
+/// - FLEXRAY_Frame (ByteArray): SlotID(2 bytes LE) + Cycle(1) + Channel(1) +
+///   Flags(2 bytes LE) + HeaderCRC(1) + PayloadLength(1) + Payload(N bytes)
+///
+/// Bytes past the payload length are padding and ignored. Records too short
+/// for their payload length are skipped.
+pub struct FlexRayOverlayReader {
+    /// The MDF index for efficient reading
+    index: MdfIndex,
+    /// Detected FLEXRAY_Frame channel groups
+    groups: Vec<FlexRayGroup>,
+}
+
+impl FlexRayOverlayReader {
+    /// Create a new reader from an MDF file path.
+    ///
+    /// # Returns
+    /// A new reader, or an error if the file cannot be read or contains no
+    /// `FLEXRAY_Frame` channel group.
+    pub fn from_file(mdf_path: &str) -> Result<Self> {
+        let index = MdfIndex::from_file(mdf_path)?;
+        Self::from_index(index)
+    }
+
+    /// Create a new reader from an existing MdfIndex.
+    pub fn from_index(index: MdfIndex) -> Result<Self> {
+        let groups: Vec<FlexRayGroup> = index
+            .channel_groups
+            .iter()
+            .enumerate()
+            .filter_map(|(group_idx, group)| Self::try_parse_group(group_idx, group))
+            .collect();
+
+        if groups.is_empty() {
+            return Err(Error::BlockSerializationError(
+                "No FLEXRAY_Frame channel groups found in MDF file".into(),
+            ));
+        }
+
+        Ok(Self { index, groups })
+    }
+
+    /// Try to parse a channel group as `FLEXRAY_Frame` format.
+    fn try_parse_group(group_index: usize, group: &IndexedChannelGroup) -> Option<FlexRayGroup> {
+        let mut timestamp_channel = None;
+        let mut master_channel = None;
+        let mut frame_channel = None;
+
+        for (ch_idx, channel) in group.channels.iter().enumerate() {
+            if channel.channel_type == 2 && master_channel.is_none() {
+                master_channel = Some(ch_idx);
+            }
+            match channel.name.as_deref() {
+                Some("Timestamp") => timestamp_channel = Some(ch_idx),
+                // Variable length frames are stored out of record and cannot
+                // be read block by block
+                Some("FLEXRAY_Frame")
+                    if channel.data_type == DataType::ByteArray
+                        && channel.vlsd_data_address.is_none() =>
+                {
+                    frame_channel = Some(ch_idx)
+                }
+                _ => {}
+            }
+        }
+
+        Some(FlexRayGroup {
+            group_index,
+            timestamp_channel: timestamp_channel.or(master_channel)?,
+            frame_channel: frame_channel?,
+        })
+    }
+
+    /// Get the number of `FLEXRAY_Frame` channel groups found.
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Get the underlying MDF index.
+    pub fn index(&self) -> &MdfIndex {
+        &self.index
+    }
+
+    /// Read all frames from the MDF file.
+    ///
+    /// Returns a vector of (timestamp_us, frame) tuples sorted by timestamp.
+    /// For large captures prefer [`frames_iter()`](Self::frames_iter), which
+    /// does not hold all frames in memory.
+    pub fn read_frames<R: ByteRangeReader<Error = Error>>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Vec<(u64, FlexRayFrame)>> {
+        let mut frames = self.frames_iter(reader).collect::<Result<Vec<_>>>()?;
+
+        // Sort by timestamp, in case a group is not in time order
+        frames.sort_by_key(|(ts, _)| *ts);
+
+        Ok(frames)
+    }
+
+    /// Read all frames of one slot from the MDF file, in timestamp order.
+    pub fn read_slot<R: ByteRangeReader<Error = Error>>(
+        &self,
+        slot_id: u16,
+        reader: &mut R,
+    ) -> Result<Vec<(u64, FlexRayFrame)>> {
+        let mut frames = Vec::new();
+        for frame in self.frames_iter(reader) {
+            let frame = frame?;
+            if frame.1.slot_id == slot_id {
+                frames.push(frame);
+            }
+        }
+        frames.sort_by_key(|(ts, _)| *ts);
+        Ok(frames)
+    }
+
+    /// Iterate over all frames of the MDF file in timestamp order.
+    ///
+    /// Only one data block per channel group is held in memory at a time.
+    pub fn frames_iter<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        reader: &'a mut R,
+    ) -> FlexRayFrameIter<'a, R> {
+        FlexRayFrameIter {
+            frames: MergedFrames::new(&self.index, reader, &self.groups, |_, bytes| {
+                FlexRayFrame::from_bytes(bytes)
+            }),
+        }
+    }
+}
+
+impl CaptureGroup for FlexRayGroup {
+    fn group_index(&self) -> usize {
+        self.group_index
+    }
+
+    fn channels(&self) -> [usize; 2] {
+        [self.timestamp_channel, self.frame_channel]
+    }
+}
+
+/// Streaming iterator over the FlexRay frames of a capture, in timestamp order.
+///
+/// Created by [`FlexRayOverlayReader::frames_iter()`]. Yields
+/// (timestamp_us, frame) tuples. After a read error the error is yielded
+/// once and the iteration ends.
+pub struct FlexRayFrameIter<'a, R> {
+    frames: MergedFrames<'a, R, &'a FlexRayGroup, FlexRayFrame>,
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for FlexRayFrameIter<'_, R> {
+    type Item = Result<(u64, FlexRayFrame)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.frames.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_raw_capture() {
+        use crate::flexray::{FlexRayChannel, RawFlexRayLogger};
+
+        let temp_path = std::env::temp_dir().join("flexray_overlay_test.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger = RawFlexRayLogger::new_file_with_cluster_name(path, "Chassis").unwrap();
+        logger.log_channel_a(100, 0, 1000, &[0x01, 0x02, 0x03, 0x04]);
+        logger.log_channel_b(101, 0, 1500, &[0xAA; 16]);
+        logger.log(100, 1, FlexRayChannel::AB, 3000, &[0x05, 0x06]);
+        let frame = FlexRayFrame::channel_a(102, 2, alloc::vec![0x11, 0x22]).with_tx();
+        logger.log_frame(2000, frame);
+        logger.finalize_file().unwrap();
+
+        let overlay = FlexRayOverlayReader::from_file(path).unwrap();
+        assert_eq!(overlay.group_count(), 1);
+
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        let frames = overlay.read_frames(&mut reader).unwrap();
+        assert_eq!(frames.len(), 4);
+
+        let timestamps: Vec<u64> = frames.iter().map(|(ts, _)| *ts).collect();
+        assert_eq!(timestamps, [1000, 1500, 2000, 3000]);
+
+        assert_eq!(frames[0].1.slot_id, 100);
+        assert_eq!(frames[0].1.cycle, 0);
+        assert_eq!(frames[0].1.channel, FlexRayChannel::A);
+        assert_eq!(frames[0].1.payload, [0x01, 0x02, 0x03, 0x04]);
+
+        assert_eq!(frames[1].1.channel, FlexRayChannel::B);
+        assert_eq!(frames[1].1.payload, [0xAA; 16]);
+
+        assert_eq!(frames[2].1.slot_id, 102);
+        assert!(frames[2].1.flags.is_tx());
+
+        assert_eq!(frames[3].1.cycle, 1);
+        assert_eq!(frames[3].1.channel, FlexRayChannel::AB);
+
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        let slot = overlay.read_slot(100, &mut reader).unwrap();
+        assert_eq!(slot.len(), 2);
+        assert_eq!(slot[1].1.payload, [0x05, 0x06]);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    fn test_no_flexray_groups() {
+        use crate::can::RawCanLogger;
+
+        let mut logger = RawCanLogger::new().unwrap();
+        logger.log(0x100, 1000, &[0x01]);
+        let bytes = logger.finalize().unwrap();
+
+        let temp_path = std::env::temp_dir().join("flexray_overlay_no_groups.mf4");
+        std::fs::write(&temp_path, &bytes).unwrap();
+
+        assert!(FlexRayOverlayReader::from_file(temp_path.to_str().unwrap()).is_err());
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+}
@@ -0,0 +1,365 @@
+//! FlexRay PDU extraction with cycle multiplexing.
+//!
+//! A FlexRay frame payload often carries several PDUs, and a slot may carry
+//! different PDUs in different cycles. FIBEX describes this with a PDU
+//! triggering per PDU: the slot, the cycle timing (`BASE-CYCLE` and
+//! `CYCLE-REPETITION`), the position of the PDU in the payload and an
+//! optional update bit. [`FlexRayPdu`] holds such a triggering, and
+//! [`RawFlexRayLogger::add_pdu`](super::RawFlexRayLogger::add_pdu) logs each
+//! PDU into its own channel group, so cycle-multiplexed content of a slot
+//! is kept apart.
+//!
+//! A PDU is sent in cycle `c` when `c % cycle_repetition == base_cycle`;
+//! `cycle_repetition` is a power of two from 1 to 64.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::flexray::{FlexRayPdu, RawFlexRayLogger};
+//!
+//! let mut logger = RawFlexRayLogger::with_cluster_name("Chassis_FR")?;
+//!
+//! // Slot 42 carries WheelSpeeds in even cycles and BrakeStatus in odd ones
+//! logger.add_pdu(FlexRayPdu::new("WheelSpeeds", 42, 0, 16).with_cycle(0, 2))?;
+//! logger.add_pdu(FlexRayPdu::new("BrakeStatus", 42, 0, 8).with_cycle(1, 2))?;
+//!
+//! // Frames are logged as usual; matching PDUs are extracted while logging
+//! logger.log_channel_a(42, cycle, timestamp_us, &payload);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::frame::{FlexRayChannel, FlexRayFrame, MAX_CYCLE_COUNT, MAX_FLEXRAY_PAYLOAD};
+use crate::bus_logging::timestamp_to_seconds;
+use crate::{Error, Result};
+
+/// A PDU triggering: where and when a PDU is sent in a FlexRay frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlexRayPdu {
+    /// PDU name, used for the channel group name
+    pub name: String,
+    /// Slot ID of the frame carrying the PDU
+    pub slot_id: u16,
+    /// Channel the PDU is sent on, or `None` for any channel
+    pub channel: Option<FlexRayChannel>,
+    /// First cycle the PDU is sent in (FIBEX `BASE-CYCLE`)
+    pub base_cycle: u8,
+    /// Cycle period of the PDU (FIBEX `CYCLE-REPETITION`)
+    pub cycle_repetition: u8,
+    /// Offset of the PDU in the frame payload in bytes
+    pub byte_offset: usize,
+    /// PDU length in bytes
+    pub length: usize,
+    /// Bit position of the update bit in the frame payload, counting from
+    /// the least significant bit of the first byte
+    pub update_bit: Option<u16>,
+}
+
+impl FlexRayPdu {
+    /// Create a PDU sent in every cycle on any channel.
+    pub fn new(name: &str, slot_id: u16, byte_offset: usize, length: usize) -> Self {
+        Self {
+            name: String::from(name),
+            slot_id,
+            channel: None,
+            base_cycle: 0,
+            cycle_repetition: 1,
+            byte_offset,
+            length,
+            update_bit: None,
+        }
+    }
+
+    /// Set the cycle timing: the PDU is sent in cycle `base_cycle` and then
+    /// every `cycle_repetition` cycles.
+    pub fn with_cycle(mut self, base_cycle: u8, cycle_repetition: u8) -> Self {
+        self.base_cycle = base_cycle;
+        self.cycle_repetition = cycle_repetition;
+        self
+    }
+
+    /// Only extract the PDU from frames on `channel`.
+    ///
+    /// Frames logged on both channels ([`FlexRayChannel::AB`]) match any
+    /// channel.
+    pub fn with_channel(mut self, channel: FlexRayChannel) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Only extract the PDU while its update bit is set.
+    pub fn with_update_bit(mut self, bit_position: u16) -> Self {
+        self.update_bit = Some(bit_position);
+        self
+    }
+
+    /// Check the cycle timing and the PDU position.
+    pub fn validate(&self) -> Result<()> {
+        let repetition = self.cycle_repetition;
+        if !repetition.is_power_of_two() || repetition > MAX_CYCLE_COUNT + 1 {
+            return Err(Error::BlockSerializationError(alloc::format!(
+                "PDU '{}': cycle repetition {} is not a power of two from 1 to 64",
+                self.name,
+                repetition
+            )));
+        }
+        if self.base_cycle >= repetition {
+            return Err(Error::BlockSerializationError(alloc::format!(
+                "PDU '{}': base cycle {} is not below cycle repetition {}",
+                self.name,
+                self.base_cycle,
+                repetition
+            )));
+        }
+        if self.length == 0 || self.byte_offset + self.length > MAX_FLEXRAY_PAYLOAD {
+            return Err(Error::BlockSerializationError(alloc::format!(
+                "PDU '{}': {} bytes at offset {} do not fit in a FlexRay payload",
+                self.name,
+                self.length,
+                self.byte_offset
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check whether the PDU is sent in cycle `cycle`.
+    pub fn in_cycle(&self, cycle: u8) -> bool {
+        self.cycle_repetition != 0 && cycle % self.cycle_repetition == self.base_cycle
+    }
+
+    /// Extract the PDU from a frame.
+    ///
+    /// # Returns
+    /// The PDU bytes, or `None` if the frame is a null frame, is for another
+    /// slot, channel or cycle, is too short for the PDU, or has the update
+    /// bit cleared
+    pub fn extract<'a>(&self, frame: &'a FlexRayFrame) -> Option<&'a [u8]> {
+        if frame.slot_id != self.slot_id
+            || frame.flags.is_null_frame()
+            || !self.in_cycle(frame.cycle)
+        {
+            return None;
+        }
+        match (self.channel, frame.channel) {
+            (None, _) | (_, FlexRayChannel::AB) => {}
+            (Some(channel), frame_channel) if channel == frame_channel => {}
+            _ => return None,
+        }
+        if let Some(bit) = self.update_bit {
+            let byte = frame.payload.get(bit as usize / 8)?;
+            if byte & (1 << (bit % 8)) == 0 {
+                return None;
+            }
+        }
+        frame
+            .payload
+            .get(self.byte_offset..self.byte_offset + self.length)
+    }
+}
+
+/// A PDU extracted from a frame.
+struct PduRecord {
+    timestamp_us: u64,
+    cycle: u8,
+    channel: FlexRayChannel,
+    data: Vec<u8>,
+}
+
+/// Buffered PDUs of one [`FlexRayPdu`], logged into the channel group
+/// `{source_name}_{pdu_name}`.
+pub(super) struct PduGroup {
+    pub(super) pdu: FlexRayPdu,
+    records: Vec<PduRecord>,
+    /// PDUs logged, including PDUs already flushed
+    pub(super) count: usize,
+    /// Channel group ID
+    channel_group: Option<String>,
+}
+
+impl PduGroup {
+    pub(super) fn new(pdu: FlexRayPdu) -> Self {
+        Self {
+            pdu,
+            records: Vec::new(),
+            count: 0,
+            channel_group: None,
+        }
+    }
+
+    /// Buffer the PDU if the frame carries it.
+    pub(super) fn process(&mut self, timestamp_us: u64, frame: &FlexRayFrame) {
+        if let Some(data) = self.pdu.extract(frame) {
+            self.records.push(PduRecord {
+                timestamp_us,
+                cycle: frame.cycle,
+                channel: frame.channel,
+                data: data.to_vec(),
+            });
+            self.count += 1;
+        }
+    }
+
+    /// Number of PDUs buffered.
+    pub(super) fn buffered(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Write and clear the buffered PDUs, creating the channel group on
+    /// first use.
+    pub(super) fn write<W: crate::writer::MdfWrite>(
+        &mut self,
+        writer: &mut crate::MdfWriter<W>,
+        source_name: &str,
+    ) -> Result<()> {
+        use crate::DecodedValue;
+
+        if self.records.is_empty() {
+            return Ok(());
+        }
+        let cg = match &self.channel_group {
+            Some(cg) => cg,
+            None => self
+                .channel_group
+                .insert(init_pdu_group(writer, source_name, &self.pdu)?),
+        };
+
+        writer.start_data_block_for_cg(cg, 0)?;
+        for record in self.records.drain(..) {
+            let values = [
+                DecodedValue::Float(timestamp_to_seconds(record.timestamp_us)),
+                DecodedValue::UnsignedInteger(record.cycle as u64),
+                DecodedValue::UnsignedInteger(record.channel as u64),
+                DecodedValue::ByteArray(record.data),
+            ];
+            writer.write_record(cg, &values)?;
+        }
+        writer.finish_data_block(cg)
+    }
+}
+
+/// Create the `{source_name}_{pdu_name}` channel group with Timestamp,
+/// Cycle, Channel and a fixed size `FLEXRAY_Pdu` ByteArray.
+fn init_pdu_group<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    source_name: &str,
+    pdu: &FlexRayPdu,
+) -> Result<String> {
+    use crate::DataType;
+
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, &alloc::format!("{}_{}", source_name, pdu.name))?;
+    let source = crate::blocks::SourceBlock::flexray();
+    writer.set_channel_group_source(&cg, &source, Some(source_name))?;
+
+    let time_ch = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some(String::from("Timestamp"));
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_ch)?;
+    writer.set_channel_unit(&time_ch, "s")?;
+    let mut prev = time_ch;
+    for name in ["Cycle", "Channel"] {
+        prev = writer.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from(name));
+            ch.bit_count = 8;
+        })?;
+    }
+    writer.add_channel(&cg, Some(&prev), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("FLEXRAY_Pdu"));
+        ch.bit_count = (pdu.length * 8) as u32;
+    })?;
+    Ok(cg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_multiplexing() {
+        let even = FlexRayPdu::new("Even", 42, 0, 2).with_cycle(0, 2);
+        let third = FlexRayPdu::new("Third", 42, 2, 2).with_cycle(1, 4);
+
+        let frame = |cycle| FlexRayFrame::channel_a(42, cycle, alloc::vec![1, 2, 3, 4]);
+        assert_eq!(even.extract(&frame(0)), Some(&[1, 2][..]));
+        assert_eq!(even.extract(&frame(1)), None);
+        assert_eq!(even.extract(&frame(62)), Some(&[1, 2][..]));
+        assert_eq!(third.extract(&frame(1)), Some(&[3, 4][..]));
+        assert_eq!(third.extract(&frame(5)), Some(&[3, 4][..]));
+        assert_eq!(third.extract(&frame(3)), None);
+
+        // Other slot, null frame, short payload
+        assert_eq!(
+            even.extract(&FlexRayFrame::channel_a(43, 0, alloc::vec![1, 2])),
+            None
+        );
+        assert_eq!(
+            even.extract(&FlexRayFrame::null_frame(42, 0, FlexRayChannel::A)),
+            None
+        );
+        assert_eq!(
+            third.extract(&FlexRayFrame::channel_a(42, 1, alloc::vec![1, 2, 3])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_channel_and_update_bit() {
+        let pdu = FlexRayPdu::new("Status", 7, 1, 1)
+            .with_channel(FlexRayChannel::B)
+            .with_update_bit(3);
+
+        let updated = alloc::vec![0x08, 0xAA];
+        let stale = alloc::vec![0x00, 0xAA];
+        assert_eq!(
+            pdu.extract(&FlexRayFrame::channel_b(7, 0, updated.clone())),
+            Some(&[0xAA][..])
+        );
+        assert_eq!(
+            pdu.extract(&FlexRayFrame::new(
+                7,
+                0,
+                FlexRayChannel::AB,
+                updated.clone()
+            )),
+            Some(&[0xAA][..])
+        );
+        assert_eq!(pdu.extract(&FlexRayFrame::channel_a(7, 0, updated)), None);
+        assert_eq!(pdu.extract(&FlexRayFrame::channel_b(7, 0, stale)), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(FlexRayPdu::new("A", 1, 0, 8).validate().is_ok());
+        assert!(
+            FlexRayPdu::new("A", 1, 0, 8)
+                .with_cycle(63, 64)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            FlexRayPdu::new("A", 1, 0, 8)
+                .with_cycle(0, 3)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            FlexRayPdu::new("A", 1, 0, 8)
+                .with_cycle(4, 4)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            FlexRayPdu::new("A", 1, 0, 8)
+                .with_cycle(0, 128)
+                .validate()
+                .is_err()
+        );
+        assert!(FlexRayPdu::new("A", 1, 250, 8).validate().is_err());
+        assert!(FlexRayPdu::new("A", 1, 0, 0).validate().is_err());
+    }
+}
@@ -12,6 +12,8 @@
 //! - Channel A/B/AB support
 //! - Static and dynamic segment frames
 //! - Startup and sync frame support
+//! - Streaming to file with bounded memory
+//! - PDU extraction with cycle multiplexing, one channel group per PDU
 //!
 //! # Example
 //!
@@ -30,12 +32,72 @@
 //! // Get MDF bytes
 //! let mdf_bytes = logger.finalize()?;
 //! ```
+//!
+//! # Bounded Memory
+//!
+//! Frames are buffered until `flush()` or `finalize()`. A 10 Mbit/s cluster
+//! produces tens of thousands of frames per second, so long captures should
+//! use [`RawFlexRayLogger::new_file_streaming`], which writes the buffered
+//! frames to the MDF writer every [`DEFAULT_MAX_BUFFERED_FRAMES`] frames and
+//! flushes the written records to disk according to a [`FlushPolicy`]:
+//!
+//! ```ignore
+//! use mdf4_rs::FlushPolicy;
+//! use mdf4_rs::flexray::RawFlexRayLogger;
+//!
+//! let mut logger = RawFlexRayLogger::new_file_streaming(
+//!     "chassis.mf4",
+//!     "Chassis_FR",
+//!     FlushPolicy::EveryNBytes(4 * 1024 * 1024),
+//! )?;
+//!
+//! // ... log frames for hours ...
+//!
+//! logger.finalize_file()?;
+//! ```
 
-use alloc::string::String;
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 
-use super::frame::{FLEXRAY_HEADER_SIZE, FlexRayChannel, FlexRayFrame, MAX_FLEXRAY_PAYLOAD};
-use crate::bus_logging::{BusLoggerConfig, TimestampedFrame, init_bus_channel_group};
+use super::frame::{FlexRayChannel, FlexRayFrame};
+use super::pdu::{FlexRayPdu, PduGroup};
+use crate::bus_logging::{BusChannel, BusLoggerCore};
+use crate::writer::FlushPolicy;
+
+/// Buffer limit of [`RawFlexRayLogger::new_file_streaming`], in frames.
+///
+/// With frames padded to 262 bytes this bounds the buffered record data to
+/// about 2.6 MB.
+pub const DEFAULT_MAX_BUFFERED_FRAMES: usize = 10_000;
+
+/// Running frame counts, kept across flushes.
+#[derive(Debug, Clone, Default)]
+struct FrameCounts {
+    /// Frames per slot ID
+    per_slot: BTreeMap<u16, usize>,
+    /// Frames per channel, indexed by [`FlexRayChannel`] value
+    per_channel: [usize; 3],
+    /// Transmitted frames
+    tx: usize,
+    /// Startup frames
+    startup: usize,
+    /// Null frames
+    null: usize,
+}
+
+impl FrameCounts {
+    fn record(&mut self, frame: &FlexRayFrame) {
+        *self.per_slot.entry(frame.slot_id).or_insert(0) += 1;
+        self.per_channel[frame.channel as usize] += 1;
+        self.tx += frame.flags.is_tx() as usize;
+        self.startup += frame.flags.is_startup() as usize;
+        self.null += frame.flags.is_null_frame() as usize;
+    }
+
+    fn total(&self) -> usize {
+        self.per_channel.iter().sum()
+    }
+}
 
 /// Raw FlexRay frame logger using ASAM MDF4 Bus Logging format.
 ///
@@ -47,6 +109,11 @@ use crate::bus_logging::{BusLoggerConfig, TimestampedFrame, init_bus_channel_gro
 /// All FlexRay frames are stored in a single channel group:
 /// - `{source_name}_FLEXRAY_Frame` - FlexRay frames with slot, cycle, channel, and payload
 ///
+/// PDUs added with [`add_pdu`](Self::add_pdu) are extracted from the logged
+/// frames into a channel group each:
+/// - `{source_name}_{pdu_name}` - Timestamp, Cycle, Channel and the PDU
+///   bytes as a `FLEXRAY_Pdu` ByteArray (see [`pdu`](super::pdu))
+///
 /// ## FLEXRAY_Frame Format
 ///
 /// Each frame is stored as a ByteArray:
@@ -58,19 +125,15 @@ use crate::bus_logging::{BusLoggerConfig, TimestampedFrame, init_bus_channel_gro
 /// - Byte 7: Payload length
 /// - Bytes 8+: Payload data (padded to max size)
 pub struct RawFlexRayLogger<W: crate::writer::MdfWrite> {
-    writer: crate::MdfWriter<W>,
-    /// Source name for metadata
-    source_name: String,
+    core: BusLoggerCore<W>,
     /// Buffered frames
-    buffer: Vec<TimestampedFrame<FlexRayFrame>>,
-    /// Channel group ID
-    channel_group: Option<String>,
-    initialized: bool,
+    frames: BusChannel<FlexRayFrame>,
+    /// Counts of all frames logged, including frames already flushed
+    counts: FrameCounts,
+    /// PDUs extracted from the logged frames
+    pdus: Vec<PduGroup>,
 }
 
-/// Frame size including header and max payload.
-const FLEXRAY_FRAME_SIZE: usize = FLEXRAY_HEADER_SIZE + MAX_FLEXRAY_PAYLOAD;
-
 impl RawFlexRayLogger<crate::writer::VecWriter> {
     /// Create a new raw FlexRay logger with in-memory output.
     pub fn new() -> crate::Result<Self> {
@@ -83,13 +146,7 @@ impl RawFlexRayLogger<crate::writer::VecWriter> {
     /// Examples: "FlexRay", "Chassis_FR", "Powertrain_FR", etc.
     pub fn with_source_name(source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
-        Ok(Self {
-            writer,
-            source_name: String::from(source_name),
-            buffer: Vec::new(),
-            channel_group: None,
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw FlexRay logger with a custom cluster name.
@@ -103,19 +160,13 @@ impl RawFlexRayLogger<crate::writer::VecWriter> {
     pub fn with_capacity(capacity: usize) -> crate::Result<Self> {
         let writer =
             crate::MdfWriter::from_writer(crate::writer::VecWriter::with_capacity(capacity));
-        Ok(Self {
-            writer,
-            source_name: String::from("FlexRay"),
-            buffer: Vec::new(),
-            channel_group: None,
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, "FlexRay"))
     }
 
     /// Finalize the MDF file and return the bytes.
     pub fn finalize(mut self) -> crate::Result<Vec<u8>> {
         self.flush_and_finalize()?;
-        Ok(self.writer.into_inner().into_inner())
+        Ok(self.core.into_writer().into_inner().into_inner())
     }
 }
 
@@ -129,13 +180,7 @@ impl RawFlexRayLogger<crate::writer::FileWriter> {
     /// Create a new raw FlexRay logger that writes to a file with custom source name.
     pub fn new_file_with_source_name(path: &str, source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::new(path)?;
-        Ok(Self {
-            writer,
-            source_name: String::from(source_name),
-            buffer: Vec::new(),
-            channel_group: None,
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw FlexRay logger that writes to a file with custom cluster name.
@@ -145,6 +190,26 @@ impl RawFlexRayLogger<crate::writer::FileWriter> {
         Self::new_file_with_source_name(path, cluster_name)
     }
 
+    /// Create a new raw FlexRay logger that streams to a file.
+    ///
+    /// The logger writes its buffered frames to the MDF writer every
+    /// [`DEFAULT_MAX_BUFFERED_FRAMES`] frames, and the MDF writer flushes the
+    /// written records to disk according to `policy`, so memory stays bounded
+    /// for captures of any length. Use
+    /// [`set_max_buffered_frames`](Self::set_max_buffered_frames) to change
+    /// the buffer limit.
+    pub fn new_file_streaming(
+        path: &str,
+        source_name: &str,
+        policy: FlushPolicy,
+    ) -> crate::Result<Self> {
+        let mut writer = crate::MdfWriter::new(path)?;
+        writer.set_flush_policy(policy);
+        let mut logger = Self::with_writer(writer, source_name);
+        logger.set_max_buffered_frames(DEFAULT_MAX_BUFFERED_FRAMES);
+        Ok(logger)
+    }
+
     /// Finalize and close the MDF file.
     pub fn finalize_file(mut self) -> crate::Result<()> {
         self.flush_and_finalize()
@@ -152,11 +217,20 @@ impl RawFlexRayLogger<crate::writer::FileWriter> {
 }
 
 impl<W: crate::writer::MdfWrite> RawFlexRayLogger<W> {
+    fn with_writer(writer: crate::MdfWriter<W>, source_name: &str) -> Self {
+        Self {
+            core: BusLoggerCore::new(writer, source_name),
+            frames: BusChannel::new(),
+            counts: FrameCounts::default(),
+            pdus: Vec::new(),
+        }
+    }
+
     /// Set the source name for metadata.
     ///
     /// Must be called before logging any frames.
     pub fn set_source_name(&mut self, name: &str) {
-        self.source_name = String::from(name);
+        self.core.set_source_name(name);
     }
 
     /// Set the FlexRay cluster name for source metadata.
@@ -166,6 +240,62 @@ impl<W: crate::writer::MdfWrite> RawFlexRayLogger<W> {
         self.set_source_name(name);
     }
 
+    /// Write the buffered frames to the MDF writer once `frames` frames are
+    /// buffered.
+    ///
+    /// This keeps memory bounded without calling `flush()`. An error of an
+    /// automatic flush is returned by the next call to
+    /// [`flush`](Self::flush).
+    ///
+    /// Default: no limit, or [`DEFAULT_MAX_BUFFERED_FRAMES`] for
+    /// [`new_file_streaming`](RawFlexRayLogger::new_file_streaming)
+    pub fn set_max_buffered_frames(&mut self, frames: usize) {
+        self.core.set_max_buffered_frames(frames);
+    }
+
+    /// Set the flush policy of the MDF writer.
+    ///
+    /// The policy decides when records written to the MDF writer are flushed
+    /// to disk. Combine it with
+    /// [`set_max_buffered_frames`](Self::set_max_buffered_frames) to stream
+    /// long captures to a file with bounded memory.
+    pub fn set_flush_policy(&mut self, policy: FlushPolicy) {
+        self.core.set_flush_policy(policy);
+    }
+
+    /// Get the number of frames and PDUs buffered and not yet written to the
+    /// MDF writer.
+    pub fn buffered_frame_count(&self) -> usize {
+        self.frames.buffered() + self.pdus.iter().map(PduGroup::buffered).sum::<usize>()
+    }
+
+    /// Extract a PDU from the frames logged from now on into its own channel
+    /// group `{source_name}_{pdu_name}`.
+    ///
+    /// # Errors
+    /// Returns an error if the PDU is invalid (see [`FlexRayPdu::validate`])
+    /// or a PDU of the same name was already added.
+    pub fn add_pdu(&mut self, pdu: FlexRayPdu) -> crate::Result<()> {
+        pdu.validate()?;
+        if self.pdus.iter().any(|group| group.pdu.name == pdu.name) {
+            return Err(crate::Error::BlockSerializationError(alloc::format!(
+                "PDU '{}' already added",
+                pdu.name
+            )));
+        }
+        self.pdus.push(PduGroup::new(pdu));
+        Ok(())
+    }
+
+    /// Get the number of PDUs extracted for the PDU named `name`, including
+    /// PDUs already flushed.
+    pub fn pdu_count(&self, name: &str) -> usize {
+        self.pdus
+            .iter()
+            .find(|group| group.pdu.name == name)
+            .map_or(0, |group| group.count)
+    }
+
     /// Log a raw FlexRay frame.
     ///
     /// # Arguments
@@ -249,7 +379,12 @@ impl<W: crate::writer::MdfWrite> RawFlexRayLogger<W> {
     /// * `timestamp_us` - Timestamp in microseconds
     /// * `frame` - The FlexRay frame to log (consumed)
     pub fn log_frame(&mut self, timestamp_us: u64, frame: FlexRayFrame) -> bool {
-        self.buffer.push(TimestampedFrame::new(timestamp_us, frame));
+        self.counts.record(&frame);
+        for pdu in &mut self.pdus {
+            pdu.process(timestamp_us, &frame);
+        }
+        self.frames.push(timestamp_us, frame);
+        self.maybe_flush();
         true
     }
 
@@ -289,146 +424,92 @@ impl<W: crate::writer::MdfWrite> RawFlexRayLogger<W> {
         )
     }
 
-    /// Flush buffered data to the MDF writer.
-    pub fn flush(&mut self) -> crate::Result<()> {
-        if !self.initialized {
-            self.initialize_mdf()?;
-        }
-
-        if self.buffer.is_empty() {
-            return Ok(());
+    /// Flush if the buffered frames reached the buffer limit.
+    fn maybe_flush(&mut self) {
+        if self.core.is_full(self.buffered_frame_count()) {
+            let result = self.flush_buffers();
+            self.core.record_auto_flush(result);
         }
-
-        self.write_frames()?;
-        self.buffer.clear();
-
-        Ok(())
     }
 
-    /// Initialize the MDF file structure.
-    fn initialize_mdf(&mut self) -> crate::Result<()> {
-        self.writer.init_mdf_file()?;
-
-        let config = BusLoggerConfig {
-            source_name: self.source_name.clone(),
-            group_name: alloc::format!("{}_FLEXRAY_Frame", self.source_name),
-            data_channel_name: String::from("FLEXRAY_Frame"),
-            data_channel_bits: (FLEXRAY_FRAME_SIZE * 8) as u32,
-            source_block: crate::blocks::SourceBlock::flexray(),
-        };
-
-        let (cg, _data_ch) = init_bus_channel_group(&mut self.writer, &config)?;
-
-        self.channel_group = Some(cg);
-        self.initialized = true;
-        Ok(())
+    /// Flush buffered data to the MDF writer.
+    ///
+    /// If an automatic flush failed since the last call, its error is
+    /// returned instead.
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.core.take_auto_flush_error()?;
+        self.flush_buffers()
     }
 
-    /// Write frames to the MDF file.
+    /// Write and clear the buffered frames.
     ///
     /// FlexRay frames are padded to max payload size for fixed record size.
-    fn write_frames(&mut self) -> crate::Result<()> {
-        use crate::DecodedValue;
-
-        let cg = match &self.channel_group {
-            Some(cg) => cg.clone(),
-            None => return Ok(()),
-        };
-
-        self.writer.start_data_block_for_cg(&cg, 0)?;
+    fn flush_buffers(&mut self) -> crate::Result<()> {
+        self.core.ensure_initialized()?;
 
-        for entry in &self.buffer {
-            // Pad frame bytes to fixed size
-            let mut frame_bytes = entry.frame.to_bytes();
-            frame_bytes.resize(FLEXRAY_FRAME_SIZE, 0);
-
-            let values = [
-                DecodedValue::Float(entry.timestamp_s),
-                DecodedValue::ByteArray(frame_bytes),
-            ];
-            self.writer.write_record(&cg, &values)?;
+        self.frames.write(&mut self.core)?;
+        let (writer, source_name) = self.core.writer_and_source_name();
+        for pdu in &mut self.pdus {
+            pdu.write(writer, source_name)?;
         }
-
-        self.writer.finish_data_block(&cg)?;
         Ok(())
     }
 
     /// Flush and finalize the MDF file.
     fn flush_and_finalize(&mut self) -> crate::Result<()> {
         self.flush()?;
-        self.writer.finalize()
+        self.core.finalize()
     }
 
-    /// Get the total number of frames logged.
+    /// Get the total number of frames logged, including frames already
+    /// flushed.
     pub fn total_frame_count(&self) -> usize {
-        self.buffer.len()
+        self.counts.total()
     }
 
     /// Get the number of unique slot IDs.
     pub fn unique_slot_count(&self) -> usize {
-        let mut slots = alloc::collections::BTreeSet::new();
-        for entry in &self.buffer {
-            slots.insert(entry.frame.slot_id);
-        }
-        slots.len()
+        self.counts.per_slot.len()
     }
 
     /// Get the number of frames for a specific slot ID.
     pub fn frame_count_for_slot(&self, slot_id: u16) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| e.frame.slot_id == slot_id)
-            .count()
+        self.counts.per_slot.get(&slot_id).copied().unwrap_or(0)
     }
 
     /// Get count of transmitted frames.
     pub fn tx_frame_count(&self) -> usize {
-        self.buffer.iter().filter(|e| e.frame.flags.is_tx()).count()
+        self.counts.tx
     }
 
     /// Get count of received frames.
     pub fn rx_frame_count(&self) -> usize {
-        self.buffer.iter().filter(|e| e.frame.flags.is_rx()).count()
+        self.counts.total() - self.counts.tx
     }
 
     /// Get count of channel A frames (includes AB).
     pub fn channel_a_count(&self) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| matches!(e.frame.channel, FlexRayChannel::A | FlexRayChannel::AB))
-            .count()
+        self.channel_frame_count(FlexRayChannel::A) + self.channel_frame_count(FlexRayChannel::AB)
     }
 
     /// Get count of channel B frames (includes AB).
     pub fn channel_b_count(&self) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| matches!(e.frame.channel, FlexRayChannel::B | FlexRayChannel::AB))
-            .count()
+        self.channel_frame_count(FlexRayChannel::B) + self.channel_frame_count(FlexRayChannel::AB)
     }
 
     /// Get count of startup frames.
     pub fn startup_frame_count(&self) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| e.frame.flags.is_startup())
-            .count()
+        self.counts.startup
     }
 
     /// Get count of null frames.
     pub fn null_frame_count(&self) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| e.frame.flags.is_null_frame())
-            .count()
+        self.counts.null
     }
 
     /// Get count of frames for a specific channel.
     pub fn channel_frame_count(&self, channel: FlexRayChannel) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| e.frame.channel == channel)
-            .count()
+        self.counts.per_channel[channel as usize]
     }
 }
 
@@ -526,12 +607,128 @@ mod tests {
     #[test]
     fn test_source_name() {
         let logger = RawFlexRayLogger::with_source_name("Chassis_FR").unwrap();
-        assert_eq!(logger.source_name, "Chassis_FR");
+        assert_eq!(logger.core.source_name(), "Chassis_FR");
     }
 
     #[test]
     fn test_cluster_name_alias() {
         let logger = RawFlexRayLogger::with_cluster_name("Chassis_FR").unwrap();
-        assert_eq!(logger.source_name, "Chassis_FR");
+        assert_eq!(logger.core.source_name(), "Chassis_FR");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_streaming_with_buffer_limit() {
+        let temp_path = std::env::temp_dir().join("flexray_streaming_test.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger =
+            RawFlexRayLogger::new_file_streaming(path, "Chassis", FlushPolicy::EveryNRecords(10))
+                .unwrap();
+        logger.set_max_buffered_frames(10);
+
+        for i in 0..95u64 {
+            let slot = 100 + (i % 3) as u16;
+            logger.log_channel_a(slot, (i % 64) as u8, i * 250, &[i as u8; 16]);
+            assert!(logger.buffered_frame_count() < 10);
+        }
+        logger.log_startup(1, 0, FlexRayChannel::AB, 95 * 250, &[0x00; 8]);
+
+        // Counts include the frames already written to the file
+        assert_eq!(logger.buffered_frame_count(), 6);
+        assert_eq!(logger.total_frame_count(), 96);
+        assert_eq!(logger.unique_slot_count(), 4);
+        assert_eq!(logger.frame_count_for_slot(100), 32);
+        assert_eq!(logger.channel_a_count(), 96);
+        assert_eq!(logger.channel_b_count(), 1);
+        assert_eq!(logger.startup_frame_count(), 1);
+
+        logger.flush().unwrap();
+        logger.finalize_file().unwrap();
+
+        let overlay = crate::flexray::FlexRayOverlayReader::from_file(path).unwrap();
+        let mut reader = crate::FileRangeReader::new(path).unwrap();
+        let frames = overlay.read_frames(&mut reader).unwrap();
+        assert_eq!(frames.len(), 96);
+        assert!(frames.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(frames[94].1.payload, [94; 16]);
+        assert!(frames[95].1.flags.is_startup());
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_pdu_extraction() {
+        use crate::DecodedValue::{ByteArray, UnsignedInteger};
+
+        let mut logger = RawFlexRayLogger::with_cluster_name("Chassis").unwrap();
+        logger
+            .add_pdu(FlexRayPdu::new("WheelSpeeds", 42, 0, 4).with_cycle(0, 2))
+            .unwrap();
+        logger
+            .add_pdu(FlexRayPdu::new("BrakeStatus", 42, 4, 2).with_cycle(1, 2))
+            .unwrap();
+        assert!(
+            logger
+                .add_pdu(FlexRayPdu::new("WheelSpeeds", 43, 0, 4))
+                .is_err()
+        );
+        assert!(
+            logger
+                .add_pdu(FlexRayPdu::new("Bad", 42, 0, 4).with_cycle(0, 3))
+                .is_err()
+        );
+
+        for cycle in 0..4u8 {
+            let payload = [cycle; 8];
+            logger.log_channel_a(42, cycle, 1000 * (cycle as u64 + 1), &payload);
+        }
+        logger.log_channel_a(43, 0, 5000, &[0xFF; 8]);
+
+        assert_eq!(logger.total_frame_count(), 5);
+        assert_eq!(logger.pdu_count("WheelSpeeds"), 2);
+        assert_eq!(logger.pdu_count("BrakeStatus"), 2);
+        assert_eq!(logger.pdu_count("Unknown"), 0);
+        assert_eq!(logger.buffered_frame_count(), 9);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&mdf_bytes).is_ok());
+        let temp_path = std::env::temp_dir().join("flexray_pdu_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let group = |name: &str| {
+            groups
+                .iter()
+                .find(|g| g.name().unwrap().as_deref() == Some(name))
+                .unwrap()
+        };
+
+        let wheel = group("Chassis_WheelSpeeds").channels();
+        assert_eq!(wheel[3].name().unwrap().as_deref(), Some("FLEXRAY_Pdu"));
+        assert_eq!(
+            wheel[1].values().unwrap(),
+            [Some(UnsignedInteger(0)), Some(UnsignedInteger(2))]
+        );
+        assert_eq!(
+            wheel[3].values().unwrap(),
+            [
+                Some(ByteArray(alloc::vec![0; 4])),
+                Some(ByteArray(alloc::vec![2; 4])),
+            ]
+        );
+
+        let brake = group("Chassis_BrakeStatus").channels();
+        assert_eq!(
+            brake[3].values().unwrap(),
+            [
+                Some(ByteArray(alloc::vec![1; 2])),
+                Some(ByteArray(alloc::vec![3; 2])),
+            ]
+        );
+
+        std::fs::remove_file(&temp_path).ok();
     }
 }
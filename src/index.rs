@@ -2,7 +2,8 @@
 //!
 //! This module provides a lightweight indexing system for MDF4 files, enabling
 //! efficient random access to channel data without loading the entire file into
-//! memory. Indexes can be serialized to JSON for caching and reuse.
+//! memory. Indexes can be serialized to JSON or to a compact binary format
+//! for caching and reuse.
 //!
 //! # Overview
 //!
@@ -25,6 +26,13 @@
 //! - Channel group metadata (names, record sizes, record counts)
 //! - Channel metadata (names, data types, byte offsets, conversions)
 //! - Data block locations (file offsets and sizes)
+//! - The master channel of each group and the first and last master value
+//!   of each data block
+//! - Signal data block locations of VLSD channels (strings, byte arrays)
+//! - Optionally, value statistics per channel and data block
+//! - A fingerprint of the source file to detect stale indexes
+//! - Attachment and event summaries (names, block locations, sizes, sync
+//!   values)
 //!
 //! # Performance Comparison
 //!
@@ -53,6 +61,12 @@
 //!     let mut reader = FileRangeReader::new("large_file.mf4")?;
 //!     let values = index.read_channel_values_by_name("Temperature", &mut reader)?;
 //!
+//!     // Numeric channels can also be read as plain columns
+//!     let (temperature, valid) = index.read_channel_f64(0, 1, &mut reader)?;
+//!
+//!     // Or downsampled for a plot, here one min/max pair per pixel column
+//!     let preview = index.read_channel_min_max(0, 1, 0.0, 60.0, 800, &mut reader)?;
+//!
 //!     Ok(())
 //! }
 //! ```
@@ -63,12 +77,25 @@
 //!
 //! - [`FileRangeReader`]: Direct file access (simple, low memory)
 //! - [`BufferedRangeReader`]: Buffered file access (better for sequential reads)
+//! - [`BlockCacheReader`]: Wraps another reader and caches decompressed
+//!   DZ blocks for repeated reads of compressed channel groups
+//! - [`PrefetchingRangeReader`]: Fetches planned ranges concurrently on worker
+//!   threads to hide the latency of remote sources
+//! - [`MultiFileRangeReader`]: Reads a set of files through an index
+//!   combined with [`MdfIndex::merge()`]
 //! - Custom implementations: HTTP range requests, cloud storage, etc.
 //!
 //! # Feature Flags
 //!
 //! - `serde`: Enables index serialization/deserialization
 //! - `serde_json`: Enables JSON file save/load methods
+//! - `object-store`: Enables [`ObjectStoreRangeReader`] for S3-compatible storage
+//! - `arrow`: Enables [`MdfIndex::arrow_schema()`] for Apache Arrow schemas
+//!   and [`MdfIndex::arrow_reader()`] for reading Arrow record batches
+//!
+//! The binary format ([`MdfIndex::save_to_file_binary()`]) has no extra
+//! dependencies and is always available. It is much smaller and faster to
+//! load than JSON, which remains useful for inspecting indexes by hand.
 
 #[cfg(feature = "compression")]
 use crate::blocks::DzBlock;
@@ -77,12 +104,50 @@ use crate::{
     blocks::{
         BlockHeader, BlockParse, ChannelBlock, ChannelGroupBlock, ConversionBlock, ConversionType,
         DataGroupBlock, DataListBlock, DataType, HeaderBlock, HlBlock, IdentificationBlock,
-        TextBlock, u64_to_usize, validate_buffer_size,
+        MetadataBlock, TextBlock, u64_to_usize, validate_buffer_size,
     },
     parsing::decoder::{DecodedValue, decode_channel_value_with_validity},
 };
 use std::collections::BTreeMap;
 use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+mod attachments;
+mod binary;
+mod bounds;
+mod cache;
+mod downsample;
+mod events;
+mod fingerprint;
+mod iter;
+mod merge;
+mod prefetch;
+mod stats;
+mod typed;
+pub use attachments::IndexedAttachment;
+pub use binary::BINARY_INDEX_VERSION;
+pub use cache::{BlockCacheReader, DEFAULT_BLOCK_CACHE_BUDGET};
+pub use events::IndexedEvent;
+pub use fingerprint::SourceFingerprint;
+pub use iter::IndexedValuesIter;
+pub use merge::MultiFileRangeReader;
+pub use prefetch::PrefetchingRangeReader;
+pub use stats::ChannelStats;
+pub use typed::BitVec;
+
+#[cfg(feature = "arrow")]
+pub(crate) mod arrow;
+#[cfg(feature = "arrow")]
+mod arrow_reader;
+#[cfg(feature = "arrow")]
+pub use arrow_reader::{ArrowChannelGroupReader, DEFAULT_ARROW_BATCH_SIZE};
+
+#[cfg(feature = "object-store")]
+mod object_store;
+#[cfg(feature = "object-store")]
+pub use object_store::{
+    DEFAULT_MAX_GAP, HttpTransport, ObjectStoreCredentials, ObjectStoreRangeReader,
+};
 
 /// Location and metadata for a data block within the MDF file.
 ///
@@ -106,6 +171,12 @@ pub struct DataBlockInfo {
     /// Whether this block contains compressed data (DZ block).
     /// Compressed blocks require decompression before reading values.
     pub is_compressed: bool,
+    /// Master (e.g. time) values of the first and last record in this
+    /// block, if the group has a master channel. Not recorded for
+    /// compressed blocks and unsorted groups unless computed with
+    /// [`MdfIndex::compute_time_bounds()`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time_bounds: Option<(f64, f64)>,
 }
 
 /// Metadata for a single channel, containing all information needed to decode values.
@@ -156,6 +227,35 @@ pub struct IndexedChannel {
     pub conversion: Option<ConversionBlock>,
     /// For VLSD channels: file address of signal data blocks
     pub vlsd_data_address: Option<u64>,
+    /// For VLSD channels: locations of the signal data blocks, in stream order.
+    /// Empty for other channels and for indexes created by older versions,
+    /// in which case the blocks are located when reading.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vlsd_blocks: Vec<SignalDataBlockInfo>,
+    /// Value statistics over all records, if computed with
+    /// [`MdfIndex::compute_statistics()`] and the channel is numeric.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stats: Option<ChannelStats>,
+    /// Value statistics per data block of the group (same order as
+    /// [`IndexedChannelGroup::data_blocks`]); empty if not computed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub block_stats: Vec<Option<ChannelStats>>,
+}
+
+/// Location of a signal data (SD) block holding values of a VLSD channel.
+///
+/// The values of a VLSD channel form one stream of `[u32 length][value bytes]`
+/// entries, which may be split over several SD blocks. Records of the channel
+/// group store the offset of their value within that stream.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalDataBlockInfo {
+    /// Absolute file offset where the block header starts.
+    pub file_offset: u64,
+    /// Total size of the block including the 24-byte header.
+    pub size: u64,
+    /// Offset of the first data byte of this block within the signal data stream.
+    pub data_offset: u64,
 }
 
 /// Metadata and layout for a channel group (measurement data collection).
@@ -191,6 +291,38 @@ pub struct IndexedChannelGroup {
     pub channels: Vec<IndexedChannel>,
     /// Data block locations containing this group's records
     pub data_blocks: Vec<DataBlockInfo>,
+    /// Record ID identifying this group's records in an unsorted data group
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub record_id: u64,
+    /// For unsorted data groups, in which several channel groups share the
+    /// data blocks and records are told apart by record ID: the record
+    /// layouts of all channel groups of the data group. Empty for sorted
+    /// groups.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unsorted_records: Vec<RecordIdLayout>,
+    /// Index of the master channel (channel type 2 or 3) in `channels`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub master_channel: Option<usize>,
+}
+
+impl IndexedChannelGroup {
+    /// Returns `true` if the data blocks also hold records of other channel
+    /// groups, which are skipped by record ID when reading.
+    pub fn is_unsorted(&self) -> bool {
+        !self.unsorted_records.is_empty()
+    }
+}
+
+/// Record layout of one channel group in an unsorted data group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordIdLayout {
+    /// Record ID of the channel group
+    pub record_id: u64,
+    /// Length of a record after the record ID (data and invalidation bytes),
+    /// or `None` for VLSD channel groups, whose records hold a `u32` length
+    /// followed by that many bytes
+    pub length: Option<u32>,
 }
 
 /// Complete index of an MDF file for efficient random access.
@@ -232,8 +364,19 @@ pub struct IndexedChannelGroup {
 pub struct MdfIndex {
     /// Original file size in bytes (for validation)
     pub file_size: u64,
+    /// Fingerprint of the indexed file, checked by
+    /// [`validate_against()`](Self::validate_against). `None` for indexes
+    /// created by older versions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fingerprint: Option<SourceFingerprint>,
     /// All channel groups in the file
     pub channel_groups: Vec<IndexedChannelGroup>,
+    /// Attachments (AT blocks) of the file
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub attachments: Vec<IndexedAttachment>,
+    /// Events (EV blocks) of the file
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub events: Vec<IndexedEvent>,
 }
 
 /// Trait for reading arbitrary byte ranges from a data source.
@@ -285,6 +428,184 @@ pub trait ByteRangeReader {
         offset: u64,
         length: u64,
     ) -> core::result::Result<Vec<u8>, Self::Error>;
+
+    /// Read several `(offset, length)` ranges.
+    ///
+    /// The default implementation calls [`read_range()`](Self::read_range)
+    /// for each range. Readers with a high per-request cost override it to
+    /// coalesce nearby ranges into fewer requests.
+    ///
+    /// # Returns
+    /// The bytes of each range, in the order of `ranges`.
+    fn read_ranges(
+        &mut self,
+        ranges: &[(u64, u64)],
+    ) -> core::result::Result<Vec<Vec<u8>>, Self::Error> {
+        ranges
+            .iter()
+            .map(|&(offset, length)| self.read_range(offset, length))
+            .collect()
+    }
+
+    /// Look up the decompressed payload of the compressed data block at
+    /// `offset`.
+    ///
+    /// The default implementation caches nothing. [`BlockCacheReader`] keeps
+    /// recently decompressed blocks, so reading several channels of a
+    /// compressed channel group decompresses each block only once. The
+    /// payload is shared with the cache, not copied.
+    fn cached_block(&mut self, _offset: u64) -> Option<Arc<[u8]>> {
+        None
+    }
+
+    /// Offer the decompressed payload of the compressed data block at
+    /// `offset` for caching. The default implementation discards it.
+    fn cache_block(&mut self, _offset: u64, _data: &Arc<[u8]>) {}
+}
+
+/// Payload of a data block: read for one use, or shared with a block cache.
+#[derive(Debug, Clone)]
+pub(crate) enum BlockPayload {
+    Owned(Vec<u8>),
+    #[cfg_attr(not(feature = "compression"), allow(dead_code))] // Only DZ payloads are cached
+    Shared(Arc<[u8]>),
+}
+
+impl BlockPayload {
+    /// The payload as a vector, copied only if it is shared.
+    pub(crate) fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Owned(data) => data,
+            Self::Shared(data) => data.to_vec(),
+        }
+    }
+
+    /// Mutable access to the payload, copying it first if it is shared.
+    pub(crate) fn to_mut(&mut self) -> &mut Vec<u8> {
+        if let Self::Shared(data) = self {
+            *self = Self::Owned(data.to_vec());
+        }
+        match self {
+            Self::Owned(data) => data,
+            Self::Shared(_) => unreachable!(),
+        }
+    }
+}
+
+impl Default for BlockPayload {
+    fn default() -> Self {
+        Self::Owned(Vec::new())
+    }
+}
+
+impl core::ops::Deref for BlockPayload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Owned(data) => data,
+            Self::Shared(data) => data,
+        }
+    }
+}
+
+/// Position of a requested range within coalesced ranges:
+/// (index of the merged range, start offset within it).
+type RangePlacement = (usize, usize);
+
+/// Merge `(offset, length)` ranges that overlap or are at most `max_gap`
+/// bytes apart.
+///
+/// Returns the merged ranges sorted by offset, and for each input range the
+/// index of the merged range containing it and its start within that range.
+fn coalesce_ranges(ranges: &[(u64, u64)], max_gap: u64) -> (Vec<(u64, u64)>, Vec<RangePlacement>) {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by_key(|&i| ranges[i].0);
+
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    let mut placements = vec![(0, 0); ranges.len()];
+    for i in order {
+        let (offset, length) = ranges[i];
+        match merged.last_mut() {
+            Some((start, len)) if offset <= (*start + *len).saturating_add(max_gap) => {
+                *len = (*len).max(offset + length - *start);
+            }
+            _ => merged.push((offset, length)),
+        }
+        let (start, _) = merged[merged.len() - 1];
+        placements[i] = (merged.len() - 1, (offset - start) as usize);
+    }
+    (merged, placements)
+}
+
+/// Match `text` against a glob pattern.
+///
+/// `*` matches any sequence of characters, `?` any single character, and
+/// `[...]` one character of a set such as `[abc]`, `[0-9]` or `[!xy]`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(p + 1),
+            Some('[') => match_char_class(&pattern[p..], text[t]).map(|len| p + len),
+            Some(&c) if c == text[t] => Some(p + 1),
+            _ => None,
+        };
+        match (step, backtrack) {
+            (Some(next), _) => {
+                p = next;
+                t += 1;
+            }
+            (None, Some((star_p, star_t))) => {
+                p = star_p;
+                t = star_t + 1;
+                backtrack = Some((star_p, star_t + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match `c` against the character class at the start of `pattern`.
+///
+/// Returns the length of the class if `c` is in it. An unterminated `[` is
+/// matched literally.
+fn match_char_class(pattern: &[char], c: char) -> Option<usize> {
+    let Some(end) = pattern
+        .iter()
+        .skip(2)
+        .position(|&x| x == ']')
+        .map(|i| i + 2)
+    else {
+        return (c == '[').then_some(1);
+    };
+    let (negated, body) = match pattern[1] {
+        '!' | '^' => (true, &pattern[2..end]),
+        _ => (false, &pattern[1..end]),
+    };
+    let mut found = false;
+    let mut i = 0;
+    while i < body.len() {
+        if i + 2 < body.len() && body[i + 1] == '-' {
+            found |= (body[i]..=body[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= body[i] == c;
+            i += 1;
+        }
+    }
+    (found != negated).then_some(end + 1)
 }
 
 /// Simple file reader that seeks and reads for each request.
@@ -340,16 +661,31 @@ impl ByteRangeReader for FileRangeReader {
     }
 }
 
+/// Largest single read made for planned ranges (16 MiB).
+const MAX_PLANNED_READ: u64 = 16 * 1024 * 1024;
+
 /// Buffered file reader with read-ahead caching for better I/O performance.
 ///
 /// This reader maintains an internal buffer and prefetches data to minimize
 /// system calls when reading many small ranges sequentially.
+///
+/// Ranges that will be read soon can be announced with
+/// [`plan()`](Self::plan), e.g. from [`MdfIndex::get_channel_byte_ranges()`]
+/// or [`MdfIndex::get_channel_read_plan()`]. Planned ranges closer than the
+/// [maximum gap](Self::with_max_gap) are merged, and a read that touches a
+/// planned range loads the rest of it with one large sequential read instead
+/// of refilling the buffer one capacity at a time. This keeps strided channel
+/// access from thrashing the buffer. [`read_ranges()`](ByteRangeReader::read_ranges)
+/// merges its ranges the same way.
 pub struct BufferedRangeReader {
     file: std::fs::File,
     buffer: Vec<u8>,
     buffer_start: u64,
     buffer_end: u64,
     buffer_capacity: usize,
+    /// Upcoming ranges announced with `plan()`, merged and sorted by offset
+    plan: Vec<(u64, u64)>,
+    max_gap: u64,
 }
 
 impl BufferedRangeReader {
@@ -359,6 +695,8 @@ impl BufferedRangeReader {
     }
 
     /// Create a new buffered reader with a custom buffer size.
+    ///
+    /// The maximum gap between merged ranges defaults to the buffer size.
     pub fn with_capacity(file_path: &str, capacity: usize) -> Result<Self> {
         let file = std::fs::File::open(file_path).map_err(Error::IOError)?;
         Ok(Self {
@@ -367,22 +705,58 @@ impl BufferedRangeReader {
             buffer_start: 0,
             buffer_end: 0,
             buffer_capacity: capacity,
+            plan: Vec::new(),
+            max_gap: capacity as u64,
         })
     }
 
-    /// Fill the internal buffer starting at the given offset.
-    fn fill_buffer(&mut self, offset: u64) -> Result<()> {
+    /// Set the largest gap in bytes between two ranges that are merged into
+    /// one read.
+    pub fn with_max_gap(mut self, max_gap: u64) -> Self {
+        self.set_max_gap(max_gap);
+        self
+    }
+
+    /// Set the largest gap in bytes between two ranges that are merged into
+    /// one read.
+    pub fn set_max_gap(&mut self, max_gap: u64) {
+        self.max_gap = max_gap;
+    }
+
+    /// Announce ranges `(offset, length)` that will be read soon.
+    ///
+    /// The ranges are added to those planned before. Planned ranges are
+    /// dropped once they have been read past their end.
+    pub fn plan(&mut self, ranges: &[(u64, u64)]) {
+        let mut planned = core::mem::take(&mut self.plan);
+        planned.extend(ranges.iter().filter(|(_, length)| *length > 0));
+        self.plan = coalesce_ranges(&planned, self.max_gap).0;
+    }
+
+    /// The merged planned ranges that have not been read yet.
+    pub fn planned_ranges(&self) -> &[(u64, u64)] {
+        &self.plan
+    }
+
+    /// Forget all planned ranges.
+    pub fn clear_plan(&mut self) {
+        self.plan.clear();
+    }
+
+    /// Fill the internal buffer with up to `size` bytes starting at the
+    /// given offset.
+    fn fill_buffer(&mut self, offset: u64, size: u64) -> Result<()> {
         self.file
             .seek(SeekFrom::Start(offset))
             .map_err(Error::IOError)?;
 
         self.buffer.clear();
-        self.buffer.resize(self.buffer_capacity, 0);
-
-        let bytes_read = self.file.read(&mut self.buffer).map_err(Error::IOError)?;
-        self.buffer.truncate(bytes_read);
+        (&mut self.file)
+            .take(size)
+            .read_to_end(&mut self.buffer)
+            .map_err(Error::IOError)?;
         self.buffer_start = offset;
-        self.buffer_end = offset + bytes_read as u64;
+        self.buffer_end = offset + self.buffer.len() as u64;
 
         Ok(())
     }
@@ -405,19 +779,31 @@ impl ByteRangeReader for BufferedRangeReader {
             return Ok(self.buffer[start_idx..end_idx].to_vec());
         }
 
-        // If the request is larger than our buffer, read directly
-        if length as usize > self.buffer_capacity {
+        let planned = self
+            .plan
+            .iter()
+            .find(|&&(start, len)| start < end && offset < start + len);
+        if let Some(&(start, len)) = planned {
+            // Load the rest of the planned range with one read
+            let fill_end = (start + len)
+                .max(end)
+                .min(offset + MAX_PLANNED_READ.max(length));
+            self.fill_buffer(offset, fill_end - offset)?;
+            let buffer_end = self.buffer_end;
+            self.plan.retain(|&(start, len)| start + len > buffer_end);
+        } else if length as usize > self.buffer_capacity {
+            // If the request is larger than our buffer, read directly
             self.file
                 .seek(SeekFrom::Start(offset))
                 .map_err(Error::IOError)?;
             let mut buffer = vec![0u8; length as usize];
             self.file.read_exact(&mut buffer).map_err(Error::IOError)?;
             return Ok(buffer);
+        } else {
+            // Fill buffer starting at the requested offset
+            self.fill_buffer(offset, self.buffer_capacity as u64)?;
         }
 
-        // Fill buffer starting at the requested offset
-        self.fill_buffer(offset)?;
-
         // Now read from buffer
         if end <= self.buffer_end {
             let start_idx = (offset - self.buffer_start) as usize;
@@ -433,6 +819,24 @@ impl ByteRangeReader for BufferedRangeReader {
             })
         }
     }
+
+    fn read_ranges(
+        &mut self,
+        ranges: &[(u64, u64)],
+    ) -> core::result::Result<Vec<Vec<u8>>, Self::Error> {
+        let (merged, placements) = coalesce_ranges(ranges, self.max_gap);
+        let mut bodies = Vec::with_capacity(merged.len());
+        for &(offset, length) in &merged {
+            bodies.push(self.read_range(offset, length)?);
+        }
+        Ok(ranges
+            .iter()
+            .zip(placements)
+            .map(|(&(_, length), (merged_index, start))| {
+                bodies[merged_index][start..start + length as usize].to_vec()
+            })
+            .collect())
+    }
 }
 
 /// Example HTTP range reader (would be implemented in production)
@@ -528,12 +932,16 @@ impl MdfIndex {
                     } else {
                         None
                     },
+                    vlsd_blocks: Vec::new(),
+                    stats: None,
+                    block_stats: Vec::new(),
                 };
                 indexed_channels.push(indexed_channel);
             }
 
             // Get data block information
             let data_blocks = Self::extract_data_blocks(&group)?;
+            let master_channel = Self::master_channel_index(&indexed_channels);
 
             let indexed_group = IndexedChannelGroup {
                 name: group.name()?,
@@ -544,13 +952,42 @@ impl MdfIndex {
                 record_count: group.raw_channel_group().block.cycle_count,
                 channels: indexed_channels,
                 data_blocks,
+                record_id: group.raw_channel_group().block.record_id,
+                unsorted_records: Self::unsorted_record_layouts(
+                    group.raw_data_group().block.record_id_size,
+                    group
+                        .raw_data_group()
+                        .channel_groups
+                        .iter()
+                        .map(|cg| &cg.block),
+                ),
+                master_channel,
             };
             indexed_groups.push(indexed_group);
         }
 
+        // Locate the signal data blocks of VLSD channels
+        let mut reader = FileRangeReader::new(file_path)?;
+        let fingerprint = SourceFingerprint::compute(&mut reader, file_size)?;
+        for group in &mut indexed_groups {
+            for channel in &mut group.channels {
+                if let Some(addr) = channel.vlsd_data_address {
+                    channel.vlsd_blocks = Self::index_vlsd_blocks(addr, &mut reader)?;
+                }
+            }
+            Self::index_time_bounds(group, &mut reader, false)?;
+        }
+
+        let header = HeaderBlock::from_bytes(&reader.read_range(64, 104)?)?;
+        let attachments = Self::index_attachments(header.first_attachment_addr, &mut reader)?;
+        let events = Self::index_events(header.first_event_addr, &attachments, &mut reader)?;
+
         Ok(MdfIndex {
             file_size,
+            fingerprint: Some(fingerprint),
             channel_groups: indexed_groups,
+            attachments,
+            events,
         })
     }
 
@@ -605,87 +1042,273 @@ impl MdfIndex {
             let dg_block = DataGroupBlock::from_bytes(&dg_bytes)?;
 
             // Follow the CG chain within this DG
-            let mut cg_addr = dg_block.first_cg_addr;
-            while cg_addr != 0 {
-                // Read CG block (104 bytes)
-                let cg_bytes = reader.read_range(cg_addr, 104)?;
-                let cg_block = ChannelGroupBlock::from_bytes(&cg_bytes)?;
-
-                // Read CG name if present
-                let cg_name = Self::read_text_block(reader, cg_block.acq_name_addr)?;
-                let cg_comment = Self::read_text_block(reader, cg_block.comment_addr)?;
-
-                // Follow the CN chain within this CG
-                let mut indexed_channels = Vec::new();
-                let mut cn_addr = cg_block.first_ch_addr;
-                while cn_addr != 0 {
-                    // Read CN block (160 bytes)
-                    let cn_bytes = reader.read_range(cn_addr, 160)?;
-                    let cn_block = ChannelBlock::from_bytes(&cn_bytes)?;
-
-                    // Read channel name
-                    let ch_name = Self::read_text_block(reader, cn_block.name_addr)?;
-
-                    // Read unit
-                    let ch_unit = Self::read_text_block(reader, cn_block.unit_addr)?;
-
-                    // Read and resolve conversion block if present
-                    let conversion =
-                        Self::read_conversion_block_streaming(reader, cn_block.conversion_addr)?;
-
-                    let indexed_channel = IndexedChannel {
-                        name: ch_name,
-                        unit: ch_unit,
-                        data_type: cn_block.data_type,
-                        byte_offset: cn_block.byte_offset,
-                        bit_offset: cn_block.bit_offset,
-                        bit_count: cn_block.bit_count,
-                        channel_type: cn_block.channel_type,
-                        flags: cn_block.flags,
-                        pos_invalidation_bit: cn_block.pos_invalidation_bit,
-                        conversion,
-                        vlsd_data_address: if cn_block.channel_type == 1 && cn_block.data_addr != 0
-                        {
-                            Some(cn_block.data_addr)
-                        } else {
-                            None
-                        },
-                    };
-                    indexed_channels.push(indexed_channel);
-
-                    cn_addr = cn_block.next_ch_addr;
-                }
-
-                // Extract data block info for this CG
-                let data_blocks =
-                    Self::extract_data_blocks_streaming(reader, dg_block.data_block_addr)?;
-
-                let indexed_group = IndexedChannelGroup {
-                    name: cg_name,
-                    comment: cg_comment,
-                    record_id_size: dg_block.record_id_size,
-                    record_size: cg_block.record_size,
-                    invalidation_bytes: cg_block.invalidation_size,
-                    record_count: cg_block.cycle_count,
-                    channels: indexed_channels,
-                    data_blocks,
-                };
+            let cg_blocks = Self::read_channel_group_blocks(reader, &dg_block)?;
+            let layouts = Self::unsorted_record_layouts(dg_block.record_id_size, &cg_blocks);
+            for cg_block in &cg_blocks {
+                let mut indexed_group = Self::index_channel_group(reader, &dg_block, cg_block)?;
+                indexed_group.unsorted_records = layouts.clone();
+                Self::index_time_bounds(&mut indexed_group, reader, false)?;
                 indexed_groups.push(indexed_group);
-
-                cg_addr = cg_block.next_cg_addr;
             }
 
             dg_addr = dg_block.next_dg_addr;
         }
 
+        let attachments = Self::index_attachments(header.first_attachment_addr, reader)?;
+        let events = Self::index_events(header.first_event_addr, &attachments, reader)?;
+
+        let fingerprint = SourceFingerprint::compute(reader, file_size)?;
         Ok(MdfIndex {
             file_size,
+            fingerprint: Some(fingerprint),
             channel_groups: indexed_groups,
+            attachments,
+            events,
+        })
+    }
+
+    /// Update the index of a file that has grown since the index was built.
+    ///
+    /// Loggers append records while analysis runs. Instead of rebuilding the
+    /// whole index, this re-reads the DG and CG blocks to update record
+    /// counts and follows the data lists of each group, reading only the
+    /// headers of data blocks that are not indexed yet (plus the last known
+    /// block, which may still be growing). Channel groups added to the file
+    /// are indexed completely, and the signal data blocks of VLSD channels
+    /// are located again.
+    ///
+    /// Value statistics of grown groups, if computed, are updated for the
+    /// new and grown data blocks; groups added to the file get none.
+    ///
+    /// The channel layout of already indexed groups is assumed unchanged.
+    /// `file_size` is raised to the end of the last indexed block. The
+    /// [`fingerprint`](Self::fingerprint) no longer matches and is cleared;
+    /// use [`update_fingerprint()`](Self::update_fingerprint) once the file
+    /// is complete. The reader must return the current file contents; a
+    /// [`BufferedRangeReader`] used before the file grew may serve stale
+    /// data, so use a fresh one.
+    ///
+    /// # Returns
+    /// The number of records added over all channel groups.
+    pub fn refresh<R: ByteRangeReader<Error = Error>>(&mut self, reader: &mut R) -> Result<u64> {
+        let hd_bytes = reader.read_range(64, 104)?;
+        let header = HeaderBlock::from_bytes(&hd_bytes)?;
+
+        let mut added = 0u64;
+        let mut group_index = 0;
+        let mut dg_addr = header.first_dg_addr;
+        while dg_addr != 0 {
+            let dg_bytes = reader.read_range(dg_addr, 64)?;
+            let dg_block = DataGroupBlock::from_bytes(&dg_bytes)?;
+
+            let cg_blocks = Self::read_channel_group_blocks(reader, &dg_block)?;
+            let layouts = Self::unsorted_record_layouts(dg_block.record_id_size, &cg_blocks);
+            for cg_block in &cg_blocks {
+                match self.channel_groups.get_mut(group_index) {
+                    Some(group) => {
+                        // All known blocks but the last are complete
+                        let complete = group.data_blocks.len().saturating_sub(1);
+                        let known: BTreeMap<u64, DataBlockInfo> = group
+                            .data_blocks
+                            .iter()
+                            .take(complete)
+                            .map(|block| (block.file_offset, block.clone()))
+                            .collect();
+                        let data_blocks = Self::extract_data_blocks_reusing(
+                            reader,
+                            dg_block.data_block_addr,
+                            &known,
+                        )?;
+                        let unchanged = group
+                            .data_blocks
+                            .iter()
+                            .take(complete)
+                            .zip(&data_blocks)
+                            .take_while(|(old, new)| {
+                                old.file_offset == new.file_offset && old.size == new.size
+                            })
+                            .count();
+                        let grown = cg_block.cycle_count != group.record_count;
+                        group.data_blocks = data_blocks;
+                        added += cg_block.cycle_count.saturating_sub(group.record_count);
+                        group.record_count = cg_block.cycle_count;
+                        group.unsorted_records = layouts.clone();
+
+                        for channel in &mut group.channels {
+                            if let Some(addr) = channel.vlsd_data_address {
+                                channel.vlsd_blocks = Self::index_vlsd_blocks(addr, reader)?;
+                            }
+                        }
+                        Self::index_time_bounds(group, reader, false)?;
+
+                        // Statistics of blocks that grew or were added
+                        let has_stats = group.channels.iter().any(|ch| !ch.block_stats.is_empty());
+                        if grown && has_stats {
+                            Self::update_group_statistics(group, reader, unchanged)?;
+                        }
+                    }
+                    None => {
+                        let mut group = Self::index_channel_group(reader, &dg_block, cg_block)?;
+                        group.unsorted_records = layouts.clone();
+                        Self::index_time_bounds(&mut group, reader, false)?;
+                        added += group.record_count;
+                        self.channel_groups.push(group);
+                    }
+                }
+
+                group_index += 1;
+            }
+
+            dg_addr = dg_block.next_dg_addr;
+        }
+
+        self.attachments = Self::index_attachments(header.first_attachment_addr, reader)?;
+        self.events = Self::index_events(header.first_event_addr, &self.attachments, reader)?;
+
+        let data_end = self
+            .channel_groups
+            .iter()
+            .flat_map(|group| {
+                let vlsd = group.channels.iter().flat_map(|ch| &ch.vlsd_blocks);
+                group
+                    .data_blocks
+                    .iter()
+                    .map(|b| b.file_offset + b.size)
+                    .chain(vlsd.map(|b| b.file_offset + b.size))
+            })
+            .max()
+            .unwrap_or(0);
+        self.file_size = self.file_size.max(data_end);
+        self.fingerprint = None;
+
+        Ok(added)
+    }
+
+    /// Index a channel group and its channels from its DG and CG blocks.
+    fn index_channel_group<R: ByteRangeReader<Error = Error>>(
+        reader: &mut R,
+        dg_block: &DataGroupBlock,
+        cg_block: &ChannelGroupBlock,
+    ) -> Result<IndexedChannelGroup> {
+        // Read CG name if present
+        let cg_name = Self::read_text_block(reader, cg_block.acq_name_addr)?;
+        let cg_comment = Self::read_text_block(reader, cg_block.comment_addr)?;
+
+        // Follow the CN chain within this CG
+        let mut indexed_channels = Vec::new();
+        let mut cn_addr = cg_block.first_ch_addr;
+        while cn_addr != 0 {
+            // Read CN block (160 bytes)
+            let cn_bytes = reader.read_range(cn_addr, 160)?;
+            let cn_block = ChannelBlock::from_bytes(&cn_bytes)?;
+
+            // Read channel name
+            let ch_name = Self::read_text_block(reader, cn_block.name_addr)?;
+
+            // Read unit
+            let ch_unit = Self::read_text_block(reader, cn_block.unit_addr)?;
+
+            // Read and resolve conversion block if present
+            let conversion =
+                Self::read_conversion_block_streaming(reader, cn_block.conversion_addr)?;
+
+            let vlsd_data_address = if cn_block.channel_type == 1 && cn_block.data_addr != 0 {
+                Some(cn_block.data_addr)
+            } else {
+                None
+            };
+            let vlsd_blocks = match vlsd_data_address {
+                Some(addr) => Self::index_vlsd_blocks(addr, reader)?,
+                None => Vec::new(),
+            };
+
+            let indexed_channel = IndexedChannel {
+                name: ch_name,
+                unit: ch_unit,
+                data_type: cn_block.data_type,
+                byte_offset: cn_block.byte_offset,
+                bit_offset: cn_block.bit_offset,
+                bit_count: cn_block.bit_count,
+                channel_type: cn_block.channel_type,
+                flags: cn_block.flags,
+                pos_invalidation_bit: cn_block.pos_invalidation_bit,
+                conversion,
+                vlsd_data_address,
+                vlsd_blocks,
+                stats: None,
+                block_stats: Vec::new(),
+            };
+            indexed_channels.push(indexed_channel);
+
+            cn_addr = cn_block.next_ch_addr;
+        }
+
+        // Extract data block info for this CG
+        let data_blocks = Self::extract_data_blocks_streaming(reader, dg_block.data_block_addr)?;
+        let master_channel = Self::master_channel_index(&indexed_channels);
+
+        Ok(IndexedChannelGroup {
+            name: cg_name,
+            comment: cg_comment,
+            record_id_size: dg_block.record_id_size,
+            record_size: cg_block.record_size,
+            invalidation_bytes: cg_block.invalidation_size,
+            record_count: cg_block.cycle_count,
+            channels: indexed_channels,
+            data_blocks,
+            record_id: cg_block.record_id,
+            unsorted_records: Vec::new(),
+            master_channel,
         })
     }
 
+    /// Position of the master channel (type 2 or 3) among `channels`.
+    fn master_channel_index(channels: &[IndexedChannel]) -> Option<usize> {
+        channels
+            .iter()
+            .position(|ch| ch.channel_type == 2 || ch.channel_type == 3)
+    }
+
+    /// Record layouts of the channel groups of a data group, if its data
+    /// blocks interleave records of several channel groups.
+    fn unsorted_record_layouts<'a>(
+        record_id_size: u8,
+        cg_blocks: impl IntoIterator<Item = &'a ChannelGroupBlock>,
+    ) -> Vec<RecordIdLayout> {
+        let layouts: Vec<RecordIdLayout> = cg_blocks
+            .into_iter()
+            .map(|cg| RecordIdLayout {
+                record_id: cg.record_id,
+                // cg_flags bit 0: VLSD channel group
+                length: (cg.flags & 0x01 == 0).then_some(cg.record_size + cg.invalidation_size),
+            })
+            .collect();
+        if record_id_size == 0 || layouts.len() < 2 {
+            return Vec::new();
+        }
+        layouts
+    }
+
+    /// Read the channel group blocks of a data group.
+    pub(crate) fn read_channel_group_blocks<R: ByteRangeReader<Error = Error>>(
+        reader: &mut R,
+        dg_block: &DataGroupBlock,
+    ) -> Result<Vec<ChannelGroupBlock>> {
+        let mut cg_blocks = Vec::new();
+        let mut cg_addr = dg_block.first_cg_addr;
+        while cg_addr != 0 {
+            // Read CG block (104 bytes, 112 with a remote master link)
+            let header = BlockHeader::from_bytes(&reader.read_range(cg_addr, 24)?)?;
+            let cg_bytes = reader.read_range(cg_addr, header.length.max(104))?;
+            let cg_block = ChannelGroupBlock::from_bytes(&cg_bytes)?;
+            cg_addr = cg_block.next_cg_addr;
+            cg_blocks.push(cg_block);
+        }
+        Ok(cg_blocks)
+    }
+
     /// Read a text block at the given address, returning None if address is 0.
-    fn read_text_block<R: ByteRangeReader<Error = Error>>(
+    pub(crate) fn read_text_block<R: ByteRangeReader<Error = Error>>(
         reader: &mut R,
         addr: u64,
     ) -> Result<Option<String>> {
@@ -704,8 +1327,26 @@ impl MdfIndex {
         Ok(Some(text_block.text))
     }
 
+    /// Read a comment, which may be a text (TX) or XML metadata (MD) block.
+    pub(crate) fn read_comment_block<R: ByteRangeReader<Error = Error>>(
+        reader: &mut R,
+        addr: u64,
+    ) -> Result<Option<String>> {
+        if addr == 0 {
+            return Ok(None);
+        }
+
+        let header_bytes = reader.read_range(addr, 24)?;
+        let header = BlockHeader::from_bytes(&header_bytes)?;
+        if header.id != "##MD" {
+            return Self::read_text_block(reader, addr);
+        }
+        let block_bytes = reader.read_range(addr, header.length)?;
+        Ok(Some(MetadataBlock::from_bytes(&block_bytes)?.xml))
+    }
+
     /// Read and parse a conversion block at the given address.
-    fn read_conversion_block_streaming<R: ByteRangeReader<Error = Error>>(
+    pub(crate) fn read_conversion_block_streaming<R: ByteRangeReader<Error = Error>>(
         reader: &mut R,
         addr: u64,
     ) -> Result<Option<ConversionBlock>> {
@@ -774,9 +1415,20 @@ impl MdfIndex {
     }
 
     /// Extract data block information using streaming reads.
-    fn extract_data_blocks_streaming<R: ByteRangeReader<Error = Error>>(
+    pub(crate) fn extract_data_blocks_streaming<R: ByteRangeReader<Error = Error>>(
+        reader: &mut R,
+        data_addr: u64,
+    ) -> Result<Vec<DataBlockInfo>> {
+        Self::extract_data_blocks_reusing(reader, data_addr, &BTreeMap::new())
+    }
+
+    /// Like [`extract_data_blocks_streaming()`](Self::extract_data_blocks_streaming),
+    /// but takes list fragments found in `known` (by file offset) from there
+    /// instead of reading their headers.
+    fn extract_data_blocks_reusing<R: ByteRangeReader<Error = Error>>(
         reader: &mut R,
         data_addr: u64,
+        known: &BTreeMap<u64, DataBlockInfo>,
     ) -> Result<Vec<DataBlockInfo>> {
         let mut data_blocks = Vec::new();
         let mut current_addr = data_addr;
@@ -792,6 +1444,7 @@ impl MdfIndex {
                         file_offset: current_addr,
                         size: header.length,
                         is_compressed: false,
+                        time_bounds: None,
                     });
                     current_addr = 0;
                 }
@@ -800,6 +1453,7 @@ impl MdfIndex {
                         file_offset: current_addr,
                         size: header.length,
                         is_compressed: true,
+                        time_bounds: None,
                     });
                     current_addr = 0;
                 }
@@ -813,6 +1467,10 @@ impl MdfIndex {
                         if fragment_addr == 0 {
                             continue;
                         }
+                        if let Some(block) = known.get(&fragment_addr) {
+                            data_blocks.push(block.clone());
+                            continue;
+                        }
                         let mut frag_pos = fragment_addr;
                         loop {
                             let frag_hdr_bytes = reader.read_range(frag_pos, 24)?;
@@ -822,6 +1480,7 @@ impl MdfIndex {
                                     file_offset: frag_pos,
                                     size: frag_hdr.length,
                                     is_compressed: frag_hdr.id == "##DZ",
+                                    time_bounds: None,
                                 });
                                 break;
                             }
@@ -869,6 +1528,7 @@ impl MdfIndex {
                         file_offset: current_block_address,
                         size: block_header.length,
                         is_compressed: false,
+                        time_bounds: None,
                     };
                     data_blocks.push(data_block_info);
                     // No list to follow, we're done
@@ -880,6 +1540,7 @@ impl MdfIndex {
                         file_offset: current_block_address,
                         size: block_header.length,
                         is_compressed: true,
+                        time_bounds: None,
                     };
                     data_blocks.push(data_block_info);
                     current_block_address = 0;
@@ -901,6 +1562,7 @@ impl MdfIndex {
                             file_offset: frag_addr,
                             size: fragment_header.length,
                             is_compressed,
+                            time_bounds: None,
                         };
                         data_blocks.push(data_block_info);
                     }
@@ -986,208 +1648,727 @@ impl MdfIndex {
         self.read_regular_channel_values(group, channel, reader)
     }
 
-    /// Read values for a regular (non-VLSD) channel using byte range reader
-    fn read_regular_channel_values<R: ByteRangeReader<Error = Error>>(
+    /// Read several channels of one group in a single pass.
+    ///
+    /// Reading each channel with [`read_channel_values()`](Self::read_channel_values)
+    /// fetches the records of the group once per channel. This method reads
+    /// each data block once and decodes all requested channels from it.
+    ///
+    /// # Returns
+    /// One column per entry of `channel_indices`, in the same order, each
+    /// holding the same values as [`read_channel_values()`](Self::read_channel_values).
+    /// VLSD channels are read from their signal data blocks as usual.
+    pub fn read_channels<R: ByteRangeReader<Error = Error>>(
         &self,
-        group: &IndexedChannelGroup,
-        channel: &IndexedChannel,
+        group_index: usize,
+        channel_indices: &[usize],
         reader: &mut R,
-    ) -> Result<Vec<Option<DecodedValue>>> {
-        // Record structure: record_id + data_bytes + invalidation_bytes
-        let record_size = group.record_id_size as usize
-            + group.record_size as usize
-            + group.invalidation_bytes as usize;
-        let mut values = Vec::new();
-
-        // Read from each data block
-        for data_block in &group.data_blocks {
-            // Get the block data, decompressing if needed
-            let block_data: Vec<u8> = if data_block.is_compressed {
-                #[cfg(feature = "compression")]
-                {
-                    // Read the full DZ block (header + compressed data)
-                    let dz_bytes = reader.read_range(data_block.file_offset, data_block.size)?;
-                    let dz_block = DzBlock::from_bytes(&dz_bytes)?;
-                    dz_block.decompress()?
-                }
-                #[cfg(not(feature = "compression"))]
-                {
-                    return Err(Error::BlockSerializationError(
-                        "Compressed blocks require the 'compression' feature".to_string(),
-                    ));
-                }
-            } else {
-                // Read the block data (skip 24-byte block header)
-                reader.read_range(data_block.file_offset + 24, data_block.size - 24)?
-            };
+    ) -> Result<Vec<Vec<Option<DecodedValue>>>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
 
-            // Process records in this block
-            let record_count = block_data.len() / record_size;
-            for i in 0..record_count {
-                let record_start = i * record_size;
-                let record_end = record_start + record_size;
-                let record = &block_data[record_start..record_end];
-
-                // Create a ChannelBlock for decoding
-                let temp_channel_block = ChannelBlock {
-                    header: BlockHeader {
-                        id: "##CN".to_string(),
-                        reserved: 0,
-                        length: 160,
-                        link_count: 8,
-                    },
-                    next_ch_addr: 0,
-                    component_addr: 0,
-                    name_addr: 0,
-                    source_addr: 0,
-                    conversion_addr: 0,
-                    data_addr: 0,
-                    unit_addr: 0,
-                    comment_addr: 0,
-                    channel_type: channel.channel_type,
-                    sync_type: 0,
-                    data_type: channel.data_type,
-                    bit_offset: channel.bit_offset,
-                    byte_offset: channel.byte_offset,
-                    bit_count: channel.bit_count,
-                    flags: channel.flags,
-                    pos_invalidation_bit: channel.pos_invalidation_bit,
-                    precision: 0,
-                    reserved1: 0,
-                    attachment_count: 0,
-                    min_raw_value: 0.0,
-                    max_raw_value: 0.0,
-                    lower_limit: 0.0,
-                    upper_limit: 0.0,
-                    lower_ext_limit: 0.0,
-                    upper_ext_limit: 0.0,
-                    name: channel.name.clone(),
-                    conversion: channel.conversion.clone(),
-                };
+        let mut channels = Vec::with_capacity(channel_indices.len());
+        for &channel_index in channel_indices {
+            let channel = group.channels.get(channel_index).ok_or_else(|| {
+                Error::BlockSerializationError("Invalid channel index".to_string())
+            })?;
+            channels.push(channel);
+        }
 
-                // Decode with validity checking
-                if let Some(decoded) = decode_channel_value_with_validity(
-                    record,
-                    group.record_id_size as usize,
-                    group.record_size,
-                    &temp_channel_block,
-                ) {
-                    if decoded.is_valid {
-                        // Apply conversion if present
-                        let final_value = if let Some(conversion) = &channel.conversion {
-                            conversion.apply_decoded(decoded.value, &[])?
-                        } else {
-                            decoded.value
-                        };
-                        values.push(Some(final_value));
-                    } else {
-                        // Invalid sample
-                        values.push(None);
+        let is_vlsd = |ch: &IndexedChannel| ch.channel_type == 1 && ch.vlsd_data_address.is_some();
+        let mut columns = vec![Vec::new(); channels.len()];
+        if channels.iter().any(|ch| !is_vlsd(ch)) {
+            let record_size = Self::record_size(group);
+            for data_block in &group.data_blocks {
+                let block_data = Self::read_block_records(group, data_block, reader)?;
+                for record in block_data.chunks_exact(record_size) {
+                    for (column, channel) in columns.iter_mut().zip(&channels) {
+                        if !is_vlsd(channel) {
+                            column.push(Self::decode_record_value(group, channel, record)?);
+                        }
                     }
-                } else {
-                    // Decoding failed
-                    values.push(None);
                 }
             }
         }
 
-        Ok(values)
+        for (column, channel) in columns.iter_mut().zip(&channels) {
+            if is_vlsd(channel) {
+                *column = self.read_vlsd_channel_values(group, channel, reader)?;
+            }
+        }
+
+        Ok(columns)
     }
 
-    /// Read values for a VLSD channel.
+    /// Read several channels of one group from a single data block.
     ///
-    /// VLSD channels store variable-length data in separate Signal Data (SD) blocks,
-    /// rather than in the regular channel group data blocks. Each record has the format:
-    /// `[u32 length][value bytes]`.
-    fn read_vlsd_channel_values<R: ByteRangeReader<Error = Error>>(
+    /// Like [`read_channels()`](Self::read_channels), but only for the
+    /// records of `group.data_blocks[block_index]`, so long recordings can
+    /// be processed block by block with bounded memory. VLSD channels are
+    /// not supported.
+    pub fn read_block_channels<R: ByteRangeReader<Error = Error>>(
         &self,
-        _group: &IndexedChannelGroup,
-        channel: &IndexedChannel,
+        group_index: usize,
+        block_index: usize,
+        channel_indices: &[usize],
         reader: &mut R,
-    ) -> Result<Vec<Option<DecodedValue>>> {
-        let vlsd_addr = channel.vlsd_data_address.ok_or_else(|| {
-            Error::BlockSerializationError("VLSD channel has no data address".to_string())
+    ) -> Result<Vec<Vec<Option<DecodedValue>>>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+        let data_block = group.data_blocks.get(block_index).ok_or_else(|| {
+            Error::BlockSerializationError("Invalid data block index".to_string())
         })?;
 
-        if vlsd_addr == 0 {
-            return Ok(Vec::new());
+        let mut channels = Vec::with_capacity(channel_indices.len());
+        for &channel_index in channel_indices {
+            let channel = group.channels.get(channel_index).ok_or_else(|| {
+                Error::BlockSerializationError("Invalid channel index".to_string())
+            })?;
+            if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
+                return Err(Error::BlockSerializationError(
+                    "VLSD channels cannot be read per data block".to_string(),
+                ));
+            }
+            channels.push(channel);
         }
 
-        let mut values = Vec::new();
-
-        // Collect all SD block addresses (may be direct SD or via DL chain)
-        let sd_addresses = self.collect_vlsd_block_addresses(vlsd_addr, reader)?;
+        let mut columns = vec![Vec::new(); channels.len()];
+        if !channels.is_empty() {
+            let record_size = Self::record_size(group);
+            let block_data = Self::read_block_records(group, data_block, reader)?;
+            for record in block_data.chunks_exact(record_size) {
+                for (column, channel) in columns.iter_mut().zip(&channels) {
+                    column.push(Self::decode_record_value(group, channel, record)?);
+                }
+            }
+        }
 
-        // Process each SD block
-        for sd_addr in sd_addresses {
-            // Read the SD block header first to get its size
-            let header_bytes = reader.read_range(sd_addr, 24)?;
-            let header = BlockHeader::from_bytes(&header_bytes)?;
+        Ok(columns)
+    }
 
-            if header.id != "##SD" {
-                return Err(Error::BlockIDError {
-                    actual: header.id,
-                    expected: "##SD".to_string(),
-                });
+    /// Read every `every_nth` value of a channel, starting with the first.
+    ///
+    /// Only the records of the selected values are fetched, with a single
+    /// [`ByteRangeReader::read_ranges()`] call, so readers that coalesce
+    /// ranges (such as object store readers) need few requests. This makes
+    /// preview plots of very long recordings cheap over high-latency
+    /// sources. Groups with compressed or unsorted data blocks and VLSD
+    /// channels are supported, but read completely before decimating.
+    ///
+    /// # Returns
+    /// The values at positions `0, every_nth, 2 * every_nth, ...` of
+    /// [`read_channel_values()`](Self::read_channel_values).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::{FileRangeReader, MdfIndex};
+    ///
+    /// let index = MdfIndex::from_file_streaming("long_recording.mf4")?;
+    /// let mut reader = FileRangeReader::new("long_recording.mf4")?;
+    /// // About 1000 points for a preview plot
+    /// let every_nth = (index.channel_groups[0].record_count / 1000).max(1);
+    /// let preview = index.read_channel_values_decimated(0, 1, every_nth, &mut reader)?;
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    pub fn read_channel_values_decimated<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        every_nth: u64,
+        reader: &mut R,
+    ) -> Result<Vec<Option<DecodedValue>>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        if every_nth == 0 {
+            return Err(Error::BlockSerializationError(
+                "every_nth must be at least 1".to_string(),
+            ));
+        }
+
+        let record_size = Self::record_size(group) as u64;
+        let is_vlsd = channel.channel_type == 1 && channel.vlsd_data_address.is_some();
+        if is_vlsd
+            || record_size == 0
+            || group.is_unsorted()
+            || group.data_blocks.iter().any(|b| b.is_compressed)
+        {
+            let values = self.read_channel_values(group_index, channel_index, reader)?;
+            return Ok(values.into_iter().step_by(every_nth as usize).collect());
+        }
+
+        // Byte range of each selected record
+        let mut ranges = Vec::new();
+        let mut block_start_record = 0u64;
+        let mut next_record = 0u64;
+        for data_block in &group.data_blocks {
+            let block_end_record = block_start_record + (data_block.size - 24) / record_size;
+            while next_record < block_end_record {
+                let offset =
+                    data_block.file_offset + 24 + (next_record - block_start_record) * record_size;
+                ranges.push((offset, record_size));
+                next_record += every_nth;
             }
+            block_start_record = block_end_record;
+        }
 
-            // Read the full SD block data (after header)
-            let data_size = header.length.saturating_sub(24) as usize;
-            if data_size == 0 {
-                continue;
+        reader
+            .read_ranges(&ranges)?
+            .iter()
+            .map(|record| Self::decode_record_value(group, channel, record))
+            .collect()
+    }
+
+    /// Read the values of a channel whose master (time) value lies in `[t0, t1]`.
+    ///
+    /// The record bounds are found by binary search over the master channel
+    /// of the group, reading one record per probe, and only the records in
+    /// the window are read afterwards. With a remote [`ByteRangeReader`] this
+    /// needs `O(log n)` small requests plus one request per data block
+    /// overlapping the window, instead of downloading the whole channel.
+    ///
+    /// The master channel must be monotonically non-decreasing. Groups with
+    /// compressed data blocks and VLSD channels are supported, but their
+    /// data is read completely before the window is selected.
+    ///
+    /// # Returns
+    /// The same values as [`read_channel_values()`](Self::read_channel_values)
+    /// for the records in the window; empty if `t0 > t1` or no record lies in
+    /// the window.
+    pub fn read_channel_values_in_time_range<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        t0: f64,
+        t1: f64,
+        reader: &mut R,
+    ) -> Result<Vec<Option<DecodedValue>>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        let records = self.find_record_range_for_time(group_index, t0, t1, reader)?;
+        if records.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let is_vlsd = channel.channel_type == 1 && channel.vlsd_data_address.is_some();
+        if is_vlsd || group.is_unsorted() || group.data_blocks.iter().any(|b| b.is_compressed) {
+            let mut values = self.read_channel_values(group_index, channel_index, reader)?;
+            values.truncate(records.end as usize);
+            values.drain(..(records.start as usize).min(values.len()));
+            return Ok(values);
+        }
+
+        let record_size = Self::record_size(group);
+        let data = Self::read_records(group, records, reader)?;
+        data.chunks_exact(record_size)
+            .map(|record| Self::decode_record_value(group, channel, record))
+            .collect()
+    }
+
+    /// Read several channels of one group whose master (time) value lies in
+    /// `[t0, t1]`.
+    ///
+    /// Combines [`read_channels()`](Self::read_channels) with
+    /// [`read_channel_values_in_time_range()`](Self::read_channel_values_in_time_range):
+    /// the window is searched once and its records are read once for all
+    /// requested channels.
+    ///
+    /// # Returns
+    /// One column per entry of `channel_indices`, in the same order.
+    pub fn read_channels_in_time_range<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_indices: &[usize],
+        t0: f64,
+        t1: f64,
+        reader: &mut R,
+    ) -> Result<Vec<Vec<Option<DecodedValue>>>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let mut channels = Vec::with_capacity(channel_indices.len());
+        for &channel_index in channel_indices {
+            let channel = group.channels.get(channel_index).ok_or_else(|| {
+                Error::BlockSerializationError("Invalid channel index".to_string())
+            })?;
+            channels.push(channel);
+        }
+
+        let records = self.find_record_range_for_time(group_index, t0, t1, reader)?;
+        if records.is_empty() {
+            return Ok(vec![Vec::new(); channels.len()]);
+        }
+
+        let any_vlsd = channels
+            .iter()
+            .any(|ch| ch.channel_type == 1 && ch.vlsd_data_address.is_some());
+        if any_vlsd || group.is_unsorted() || group.data_blocks.iter().any(|b| b.is_compressed) {
+            let mut columns = self.read_channels(group_index, channel_indices, reader)?;
+            for values in &mut columns {
+                values.truncate(records.end as usize);
+                values.drain(..(records.start as usize).min(values.len()));
             }
-            let sd_data = reader.read_range(sd_addr + 24, data_size as u64)?;
-
-            // Parse VLSD records: [u32 length][value bytes]...
-            let mut pos = 0;
-            while pos + 4 <= sd_data.len() {
-                // Read the length prefix (u32 little-endian)
-                let len = u32::from_le_bytes([
-                    sd_data[pos],
-                    sd_data[pos + 1],
-                    sd_data[pos + 2],
-                    sd_data[pos + 3],
-                ]) as usize;
-
-                let value_start = pos + 4;
-                let value_end = value_start + len;
-
-                if value_end > sd_data.len() {
-                    // Truncated record - stop parsing
-                    break;
+            return Ok(columns);
+        }
+
+        let record_size = Self::record_size(group);
+        let data = Self::read_records(group, records, reader)?;
+        let mut columns = vec![Vec::with_capacity(data.len() / record_size); channels.len()];
+        for record in data.chunks_exact(record_size) {
+            for (column, channel) in columns.iter_mut().zip(&channels) {
+                column.push(Self::decode_record_value(group, channel, record)?);
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Find the records of a channel group whose master value lies in `[t0, t1]`.
+    ///
+    /// Performs a binary search over the master channel (see
+    /// [`read_channel_values_in_time_range()`](Self::read_channel_values_in_time_range)).
+    /// The returned record range can be used with
+    /// [`get_channel_byte_ranges_for_records()`](Self::get_channel_byte_ranges_for_records).
+    pub fn find_record_range_for_time<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        t0: f64,
+        t1: f64,
+        reader: &mut R,
+    ) -> Result<core::ops::Range<u64>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let master = group
+            .channels
+            .iter()
+            .find(|ch| ch.channel_type == 2 || ch.channel_type == 3)
+            .ok_or_else(|| {
+                Error::BlockSerializationError("Channel group has no master channel".to_string())
+            })?;
+
+        // t0 > t1 and NaN bounds select nothing
+        if t0.partial_cmp(&t1).is_none_or(|o| o.is_gt()) {
+            return Ok(0..0);
+        }
+
+        let compressed = group.data_blocks.iter().any(|b| b.is_compressed);
+        let record_count = group.record_count;
+        if (compressed || group.is_unsorted()) && master.channel_type == 2 {
+            // Records of compressed or unsorted blocks cannot be read individually
+            let times = self.read_regular_channel_values(group, master, reader)?;
+            let time_at = |i: usize| {
+                times[i]
+                    .as_ref()
+                    .and_then(DecodedValue::as_f64)
+                    .ok_or_else(|| Self::invalid_master_value(i as u64))
+            };
+            let start =
+                Self::partition_records(times.len() as u64, |i| Ok(time_at(i as usize)? < t0))?;
+            let end =
+                Self::partition_records(times.len() as u64, |i| Ok(time_at(i as usize)? <= t1))?;
+            return Ok(start..end.max(start));
+        }
+
+        let mut time_at = |record: u64| -> Result<f64> {
+            let value = if master.channel_type == 3 {
+                // Virtual master: the raw value is the record index
+                let raw = DecodedValue::UnsignedInteger(record);
+                match &master.conversion {
+                    Some(conversion) => Some(conversion.apply_decoded(raw, &[])?),
+                    None => Some(raw),
                 }
+            } else {
+                let data = Self::read_records(group, record..record + 1, reader)?;
+                Self::decode_record_value(group, master, &data)?
+            };
+            value
+                .as_ref()
+                .and_then(DecodedValue::as_f64)
+                .ok_or_else(|| Self::invalid_master_value(record))
+        };
+        let start = Self::partition_records(record_count, |i| Ok(time_at(i)? < t0))?;
+        let end = Self::partition_records(record_count, |i| Ok(time_at(i)? <= t1))?;
+        Ok(start..end.max(start))
+    }
 
-                let record = &sd_data[value_start..value_end];
+    /// Index of the first record in `0..count` for which `pred` is false,
+    /// assuming `pred` is true for a prefix of the records.
+    fn partition_records(count: u64, mut pred: impl FnMut(u64) -> Result<bool>) -> Result<u64> {
+        let (mut lo, mut hi) = (0u64, count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if pred(mid)? {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(lo)
+    }
 
-                // Decode the VLSD value
-                if let Some(decoded) = self.decode_vlsd_value(record, channel) {
-                    // Apply conversion if present
-                    let final_value = if let Some(conversion) = &channel.conversion {
-                        match conversion.apply_decoded(decoded.clone(), &[]) {
-                            Ok(v) => v,
-                            Err(_) => decoded, // Fall back to raw value on conversion error
-                        }
-                    } else {
-                        decoded
-                    };
-                    values.push(Some(final_value));
-                } else {
-                    values.push(None);
+    fn invalid_master_value(record: u64) -> Error {
+        Error::BlockSerializationError(format!(
+            "Master channel value of record {} is invalid or not numeric",
+            record
+        ))
+    }
+
+    /// Size of a complete record: record_id + data_bytes + invalidation_bytes
+    fn record_size(group: &IndexedChannelGroup) -> usize {
+        group.record_id_size as usize
+            + group.record_size as usize
+            + group.invalidation_bytes as usize
+    }
+
+    /// Read the raw bytes of a range of records from uncompressed data blocks.
+    fn read_records<R: ByteRangeReader<Error = Error>>(
+        group: &IndexedChannelGroup,
+        records: core::ops::Range<u64>,
+        reader: &mut R,
+    ) -> Result<Vec<u8>> {
+        if group.is_unsorted() {
+            return Err(Error::BlockSerializationError(
+                "Unsorted channel groups cannot be read by record range".to_string(),
+            ));
+        }
+        let record_size = Self::record_size(group) as u64;
+        let mut data = Vec::with_capacity(((records.end - records.start) * record_size) as usize);
+        let mut block_start_record = 0u64;
+
+        for data_block in &group.data_blocks {
+            if data_block.is_compressed {
+                return Err(Error::BlockSerializationError(
+                    "Compressed blocks cannot be read by record range".to_string(),
+                ));
+            }
+            let records_in_block = (data_block.size - 24) / record_size;
+            let block_end_record = block_start_record + records_in_block;
+
+            let need_start = records.start.max(block_start_record);
+            let need_end = records.end.min(block_end_record);
+            if need_start < need_end {
+                let offset =
+                    data_block.file_offset + 24 + (need_start - block_start_record) * record_size;
+                data.extend(reader.read_range(offset, (need_end - need_start) * record_size)?);
+            }
+
+            block_start_record = block_end_record;
+            if block_start_record >= records.end {
+                break;
+            }
+        }
+
+        if data.len() as u64 != (records.end - records.start) * record_size {
+            return Err(Error::BlockSerializationError(format!(
+                "Records {}..{} exceed the data blocks of the channel group",
+                records.start, records.end
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Read values for a regular (non-VLSD) channel using byte range reader
+    fn read_regular_channel_values<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group: &IndexedChannelGroup,
+        channel: &IndexedChannel,
+        reader: &mut R,
+    ) -> Result<Vec<Option<DecodedValue>>> {
+        let record_size = Self::record_size(group);
+        let mut values = Vec::new();
+
+        // Read from each data block
+        for data_block in &group.data_blocks {
+            let block_data = Self::read_block_records(group, data_block, reader)?;
+
+            // Process records in this block
+            for record in block_data.chunks_exact(record_size) {
+                values.push(Self::decode_record_value(group, channel, record)?);
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Read the record data of a data block, decompressing it if needed.
+    ///
+    /// Decompressed payloads are shared with the block cache of `reader`.
+    pub(crate) fn read_block_data<R: ByteRangeReader<Error = Error>>(
+        data_block: &DataBlockInfo,
+        reader: &mut R,
+    ) -> Result<BlockPayload> {
+        if data_block.is_compressed {
+            #[cfg(feature = "compression")]
+            {
+                if let Some(data) = reader.cached_block(data_block.file_offset) {
+                    return Ok(BlockPayload::Shared(data));
                 }
+                // Read the full DZ block (header + compressed data)
+                let dz_bytes = reader.read_range(data_block.file_offset, data_block.size)?;
+                let dz_block = DzBlock::from_bytes(&dz_bytes)?;
+                let data: Arc<[u8]> = dz_block.decompress()?.into();
+                reader.cache_block(data_block.file_offset, &data);
+                Ok(BlockPayload::Shared(data))
+            }
+            #[cfg(not(feature = "compression"))]
+            {
+                Err(Error::BlockSerializationError(
+                    "Compressed blocks require the 'compression' feature".to_string(),
+                ))
+            }
+        } else {
+            // Read the block data (skip 24-byte block header)
+            let data = reader.read_range(data_block.file_offset + 24, data_block.size - 24)?;
+            Ok(BlockPayload::Owned(data))
+        }
+    }
+
+    /// Read the records of `group` in a data block.
+    ///
+    /// For unsorted groups, the records of other channel groups are skipped
+    /// by their record ID, like direct reads do.
+    fn read_block_records<R: ByteRangeReader<Error = Error>>(
+        group: &IndexedChannelGroup,
+        data_block: &DataBlockInfo,
+        reader: &mut R,
+    ) -> Result<BlockPayload> {
+        let data = Self::read_block_data(data_block, reader)?;
+        if !group.is_unsorted() {
+            return Ok(data);
+        }
+
+        let id_size = group.record_id_size as usize;
+        let record_size = Self::record_size(group);
+        let mut records = Vec::new();
+        let mut pos = 0;
+        while let Some(id_bytes) = data.get(pos..pos + id_size) {
+            let mut id = [0u8; 8];
+            id[..id_size.min(8)].copy_from_slice(&id_bytes[..id_size.min(8)]);
+            let record_id = u64::from_le_bytes(id);
+
+            let Some(layout) = group
+                .unsorted_records
+                .iter()
+                .find(|l| l.record_id == record_id)
+            else {
+                // Unknown record ID: resynchronize at the next byte
+                pos += 1;
+                continue;
+            };
+            let length = match layout.length {
+                Some(length) => length as usize,
+                None => match data.get(pos + id_size..pos + id_size + 4) {
+                    Some(len) => 4 + u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize,
+                    None => break,
+                },
+            };
+            if pos + id_size + length > data.len() {
+                break;
+            }
+            if record_id == group.record_id {
+                records.extend_from_slice(&data[pos..pos + record_size]);
+            }
+            pos += id_size + length;
+        }
+        Ok(BlockPayload::Owned(records))
+    }
+
+    /// Decode and convert the value of `channel` in a single record.
+    ///
+    /// Returns `None` if the invalidation bit is set or decoding fails.
+    fn decode_record_value(
+        group: &IndexedChannelGroup,
+        channel: &IndexedChannel,
+        record: &[u8],
+    ) -> Result<Option<DecodedValue>> {
+        // Create a ChannelBlock for decoding
+        let temp_channel_block = ChannelBlock {
+            header: BlockHeader {
+                id: "##CN".to_string(),
+                reserved: 0,
+                length: 160,
+                link_count: 8,
+            },
+            next_ch_addr: 0,
+            component_addr: 0,
+            name_addr: 0,
+            source_addr: 0,
+            conversion_addr: 0,
+            data_addr: 0,
+            unit_addr: 0,
+            comment_addr: 0,
+            channel_type: channel.channel_type,
+            sync_type: 0,
+            data_type: channel.data_type,
+            bit_offset: channel.bit_offset,
+            byte_offset: channel.byte_offset,
+            bit_count: channel.bit_count,
+            flags: channel.flags,
+            pos_invalidation_bit: channel.pos_invalidation_bit,
+            precision: 0,
+            reserved1: 0,
+            attachment_count: 0,
+            min_raw_value: 0.0,
+            max_raw_value: 0.0,
+            lower_limit: 0.0,
+            upper_limit: 0.0,
+            lower_ext_limit: 0.0,
+            upper_ext_limit: 0.0,
+            name: channel.name.clone(),
+            conversion: channel.conversion.clone(),
+        };
 
-                pos = value_end;
+        // Decode with validity checking
+        match decode_channel_value_with_validity(
+            record,
+            group.record_id_size as usize,
+            group.record_size,
+            &temp_channel_block,
+        ) {
+            Some(decoded) if decoded.is_valid => {
+                // Apply conversion if present
+                let final_value = if let Some(conversion) = &channel.conversion {
+                    conversion.apply_decoded(decoded.value, &[])?
+                } else {
+                    decoded.value
+                };
+                Ok(Some(final_value))
             }
+            // Invalid sample or decoding failed
+            _ => Ok(None),
+        }
+    }
+
+    /// Read values for a VLSD channel.
+    ///
+    /// VLSD channels store variable-length data in separate Signal Data (SD) blocks,
+    /// rather than in the regular channel group data blocks. Each record has the format:
+    /// `[u32 length][value bytes]`.
+    ///
+    /// The SD blocks recorded in the index are fetched with a single
+    /// [`ByteRangeReader::read_ranges()`] call, so readers that coalesce
+    /// ranges need few requests. Indexes without recorded blocks locate them
+    /// first.
+    fn read_vlsd_channel_values<R: ByteRangeReader<Error = Error>>(
+        &self,
+        _group: &IndexedChannelGroup,
+        channel: &IndexedChannel,
+        reader: &mut R,
+    ) -> Result<Vec<Option<DecodedValue>>> {
+        let vlsd_addr = channel.vlsd_data_address.ok_or_else(|| {
+            Error::BlockSerializationError("VLSD channel has no data address".to_string())
+        })?;
+
+        if vlsd_addr == 0 {
+            return Ok(Vec::new());
+        }
+
+        let located;
+        let sd_blocks = if channel.vlsd_blocks.is_empty() {
+            located = Self::index_vlsd_blocks(vlsd_addr, reader)?;
+            &located
+        } else {
+            &channel.vlsd_blocks
+        };
+
+        // Read the data of all SD blocks (after their headers)
+        let ranges: Vec<(u64, u64)> = sd_blocks
+            .iter()
+            .filter(|block| block.size > 24)
+            .map(|block| (block.file_offset + 24, block.size - 24))
+            .collect();
+        let mut values = Vec::new();
+
+        // Values may span block boundaries, so parse the joined stream
+        let sd_data = reader.read_ranges(&ranges)?.concat();
+        // Parse VLSD records: [u32 length][value bytes]...
+        let mut pos = 0;
+        while pos + 4 <= sd_data.len() {
+            // Read the length prefix (u32 little-endian)
+            let len = u32::from_le_bytes([
+                sd_data[pos],
+                sd_data[pos + 1],
+                sd_data[pos + 2],
+                sd_data[pos + 3],
+            ]) as usize;
+
+            let value_start = pos + 4;
+            let value_end = value_start + len;
+
+            if value_end > sd_data.len() {
+                // Truncated record - stop parsing
+                break;
+            }
+
+            let record = &sd_data[value_start..value_end];
+            values.push(Self::convert_vlsd_value(record, channel));
+
+            pos = value_end;
         }
 
         Ok(values)
     }
 
+    /// Locate the SD blocks of a VLSD channel and their offsets within the
+    /// signal data stream.
+    pub(crate) fn index_vlsd_blocks<R: ByteRangeReader<Error = Error>>(
+        vlsd_addr: u64,
+        reader: &mut R,
+    ) -> Result<Vec<SignalDataBlockInfo>> {
+        let mut blocks = Vec::new();
+        let mut data_offset = 0u64;
+
+        for sd_addr in Self::collect_vlsd_block_addresses(vlsd_addr, reader)? {
+            // Read the SD block header to get its size
+            let header_bytes = reader.read_range(sd_addr, 24)?;
+            let header = BlockHeader::from_bytes(&header_bytes)?;
+
+            if header.id != "##SD" {
+                return Err(Error::BlockIDError {
+                    actual: header.id,
+                    expected: "##SD".to_string(),
+                });
+            }
+
+            blocks.push(SignalDataBlockInfo {
+                file_offset: sd_addr,
+                size: header.length,
+                data_offset,
+            });
+            data_offset += header.length.saturating_sub(24);
+        }
+
+        Ok(blocks)
+    }
+
     /// Collect all SD block addresses from a VLSD data address.
     ///
     /// The address may point directly to an SD block, or to a DL (Data List) block
     /// that chains multiple SD blocks together.
     fn collect_vlsd_block_addresses<R: ByteRangeReader<Error = Error>>(
-        &self,
         start_addr: u64,
         reader: &mut R,
     ) -> Result<Vec<u64>> {
@@ -1248,8 +2429,21 @@ impl MdfIndex {
         Ok(addresses)
     }
 
+    /// Decode a VLSD value and apply the channel conversion, if any.
+    fn convert_vlsd_value(record: &[u8], channel: &IndexedChannel) -> Option<DecodedValue> {
+        let decoded = Self::decode_vlsd_value(record, channel)?;
+        // Apply conversion if present
+        Some(match &channel.conversion {
+            Some(conversion) => match conversion.apply_decoded(decoded.clone(), &[]) {
+                Ok(v) => v,
+                Err(_) => decoded, // Fall back to raw value on conversion error
+            },
+            None => decoded,
+        })
+    }
+
     /// Decode a VLSD value from its raw bytes.
-    fn decode_vlsd_value(&self, record: &[u8], channel: &IndexedChannel) -> Option<DecodedValue> {
+    fn decode_vlsd_value(record: &[u8], channel: &IndexedChannel) -> Option<DecodedValue> {
         if record.is_empty() {
             return None;
         }
@@ -1386,6 +2580,7 @@ impl MdfIndex {
     ///
     /// Returns a vector of (file_offset, length) tuples representing the byte ranges
     /// that need to be read from the file to get all data for the specified channel.
+    /// For VLSD channels these are the data sections of their signal data blocks.
     ///
     /// # Arguments
     /// * `group_index` - Index of the channel group
@@ -1409,11 +2604,19 @@ impl MdfIndex {
             .get(channel_index)
             .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
 
-        // Handle VLSD channels differently
+        // VLSD values are stored in the signal data blocks
         if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
-            return Err(Error::BlockSerializationError(
-                "VLSD channels not yet supported for byte range calculation".to_string(),
-            ));
+            if channel.vlsd_blocks.is_empty() {
+                return Err(Error::BlockSerializationError(
+                    "Index has no signal data blocks for this VLSD channel".to_string(),
+                ));
+            }
+            return Ok(channel
+                .vlsd_blocks
+                .iter()
+                .filter(|block| block.size > 24)
+                .map(|block| (block.file_offset + 24, block.size - 24))
+                .collect());
         }
 
         // For regular channels, calculate byte ranges from data blocks
@@ -1511,6 +2714,16 @@ impl MdfIndex {
                 .max(1)
         };
 
+        if group.is_unsorted() {
+            // The position of a record depends on the records of the other
+            // channel groups before it, which is only known after reading.
+            return Err(Error::BlockSerializationError(
+                "Unsorted channel groups cannot be accessed via byte ranges. \
+                 Use read_channel_values() instead."
+                    .to_string(),
+            ));
+        }
+
         let mut byte_ranges = Vec::new();
         let mut records_processed = 0u64;
 
@@ -1662,6 +2875,68 @@ impl MdfIndex {
         matches
     }
 
+    /// Find all channels whose name matches a glob pattern
+    ///
+    /// `*` matches any sequence of characters, `?` a single character and
+    /// `[...]` a character set (`[abc]`, `[0-9]`, `[!x]`). Matching is case
+    /// sensitive. For regular expressions or other criteria, use
+    /// [`find_channels_where()`](Self::find_channels_where).
+    ///
+    /// # Arguments
+    /// * `pattern` - Glob pattern, e.g. `"Engine*"` or `"Wheel?_Speed"`
+    ///
+    /// # Returns
+    /// * `Vec<(group_index, channel_index)>` - All matching channels
+    pub fn find_channels_matching(&self, pattern: &str) -> Vec<(usize, usize)> {
+        self.find_channels_where(|channel| {
+            channel
+                .name
+                .as_deref()
+                .is_some_and(|name| glob_match(pattern, name))
+        })
+    }
+
+    /// Find all channels with a given physical unit
+    ///
+    /// # Arguments
+    /// * `unit` - Unit to find, e.g. `"°C"` (compared exactly)
+    ///
+    /// # Returns
+    /// * `Vec<(group_index, channel_index)>` - All matching channels
+    pub fn find_channels_by_unit(&self, unit: &str) -> Vec<(usize, usize)> {
+        self.find_channels_where(|channel| channel.unit.as_deref() == Some(unit))
+    }
+
+    /// Find all channels for which a predicate returns `true`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::MdfIndex;
+    ///
+    /// let index = MdfIndex::load_from_file_binary("recording.mdfidx")?;
+    /// let wheel_speeds = index.find_channels_where(|ch| {
+    ///     ch.name.as_deref().is_some_and(|n| n.to_lowercase().contains("wheel"))
+    ///         && ch.unit.as_deref() == Some("km/h")
+    /// });
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    pub fn find_channels_where(
+        &self,
+        mut predicate: impl FnMut(&IndexedChannel) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let mut matches = Vec::new();
+
+        for (group_index, group) in self.channel_groups.iter().enumerate() {
+            for (channel_index, channel) in group.channels.iter().enumerate() {
+                if predicate(channel) {
+                    matches.push((group_index, channel_index));
+                }
+            }
+        }
+
+        matches
+    }
+
     /// Read channel values by name using a byte range reader
     ///
     /// Convenience method that finds the channel by name and reads its values.
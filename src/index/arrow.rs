@@ -0,0 +1,287 @@
+//! Apache Arrow schemas of indexed channel groups.
+
+use super::{IndexedChannel, MdfIndex};
+use crate::{
+    DecodedValue, Error, Result,
+    blocks::{ConversionBlock, ConversionType, DataType},
+};
+use arrow_array::{
+    ArrayRef, ArrowPrimitiveType, BinaryArray, Float32Array, Float64Array, PrimitiveArray,
+    StringArray,
+    types::{
+        Int8Type, Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+    },
+};
+use arrow_schema::{DataType as ArrowType, Field, Schema};
+use std::{collections::HashMap, sync::Arc};
+
+/// Arrow type of the values read from a channel.
+///
+/// Follows [`MdfIndex::read_channel_values()`]: numeric conversions produce
+/// `Float64`, text conversions `Utf8`, and unconverted channels the
+/// narrowest type holding their raw values.
+fn arrow_type(channel: &IndexedChannel) -> ArrowType {
+    value_type(
+        channel.data_type,
+        channel.bit_count,
+        channel.conversion.as_ref(),
+    )
+}
+
+/// Arrow type of the physical values of a channel with the given data type,
+/// bit count and conversion.
+pub(crate) fn value_type(
+    data_type: DataType,
+    bit_count: u32,
+    conversion: Option<&ConversionBlock>,
+) -> ArrowType {
+    let conversion = conversion.filter(|c| !c.is_identity());
+    if let Some(conversion) = conversion {
+        match conversion.conversion_type {
+            ConversionType::ValueToText
+            | ConversionType::RangeToText
+            | ConversionType::TextToText
+            | ConversionType::BitfieldText => return ArrowType::Utf8,
+            ConversionType::Unknown(_) => {}
+            _ => return ArrowType::Float64,
+        }
+    }
+
+    let bits = bit_count;
+    match data_type {
+        DataType::UnsignedIntegerLE | DataType::UnsignedIntegerBE => match bits {
+            0..=8 => ArrowType::UInt8,
+            9..=16 => ArrowType::UInt16,
+            17..=32 => ArrowType::UInt32,
+            _ => ArrowType::UInt64,
+        },
+        DataType::SignedIntegerLE | DataType::SignedIntegerBE => match bits {
+            0..=8 => ArrowType::Int8,
+            9..=16 => ArrowType::Int16,
+            17..=32 => ArrowType::Int32,
+            _ => ArrowType::Int64,
+        },
+        DataType::FloatLE | DataType::FloatBE => match bits {
+            16 => ArrowType::Float16,
+            32 => ArrowType::Float32,
+            _ => ArrowType::Float64,
+        },
+        DataType::StringLatin1
+        | DataType::StringUtf8
+        | DataType::StringUtf16LE
+        | DataType::StringUtf16BE => ArrowType::Utf8,
+        _ => ArrowType::Binary,
+    }
+}
+
+/// Column of an integer type; values that do not fit become null.
+fn integers<T>(values: &[Option<DecodedValue>]) -> ArrayRef
+where
+    T: ArrowPrimitiveType,
+    T::Native: TryFrom<u64> + TryFrom<i64>,
+{
+    let array: PrimitiveArray<T> = values
+        .iter()
+        .map(|value| match value {
+            Some(DecodedValue::UnsignedInteger(v)) => T::Native::try_from(*v).ok(),
+            Some(DecodedValue::SignedInteger(v)) => T::Native::try_from(*v).ok(),
+            _ => None,
+        })
+        .collect();
+    Arc::new(array)
+}
+
+/// Column of `data_type` holding `values`.
+///
+/// Values that do not match the column type, such as the text fallback of a
+/// numeric conversion, become null. Text columns hold numbers as text.
+/// Types other than those of [`value_type()`] give a `Float64` column, so
+/// `Float16` fields must be widened to `Float32` by the caller.
+pub(crate) fn to_array(values: &[Option<DecodedValue>], data_type: &ArrowType) -> ArrayRef {
+    match data_type {
+        ArrowType::UInt8 => integers::<UInt8Type>(values),
+        ArrowType::UInt16 => integers::<UInt16Type>(values),
+        ArrowType::UInt32 => integers::<UInt32Type>(values),
+        ArrowType::UInt64 => integers::<UInt64Type>(values),
+        ArrowType::Int8 => integers::<Int8Type>(values),
+        ArrowType::Int16 => integers::<Int16Type>(values),
+        ArrowType::Int32 => integers::<Int32Type>(values),
+        ArrowType::Int64 => integers::<Int64Type>(values),
+        ArrowType::Float32 => {
+            Arc::new(Float32Array::from_iter(values.iter().map(|v| {
+                v.as_ref().and_then(DecodedValue::as_f64).map(|f| f as f32)
+            })))
+        }
+        ArrowType::Utf8 => Arc::new(StringArray::from_iter(values.iter().map(|v| match v {
+            Some(DecodedValue::String(s)) => Some(s.clone()),
+            Some(v) if v.is_integer() || v.is_float() => Some(v.to_string()),
+            _ => None,
+        }))),
+        ArrowType::Binary => Arc::new(BinaryArray::from_iter(values.iter().map(|v| match v {
+            Some(
+                DecodedValue::ByteArray(b)
+                | DecodedValue::MimeSample(b)
+                | DecodedValue::MimeStream(b),
+            ) => Some(b.as_slice()),
+            _ => None,
+        }))),
+        _ => Arc::new(Float64Array::from_iter(
+            values
+                .iter()
+                .map(|v| v.as_ref().and_then(DecodedValue::as_f64)),
+        )),
+    }
+}
+
+impl MdfIndex {
+    /// Arrow schema of a channel group, with one field per channel.
+    ///
+    /// Fields are named after the channels (`channel_<n>` for unnamed ones)
+    /// and typed after the values read by
+    /// [`read_channel_values()`](Self::read_channel_values). All fields are
+    /// nullable, since invalid samples are read as `None`.
+    ///
+    /// Field metadata holds the MDF details:
+    /// - `unit`: physical unit, if any
+    /// - `mdf.data_type`: MDF data type code
+    /// - `mdf.channel_type`: MDF channel type code
+    /// - `mdf.master`: `"true"` for master (e.g. time) channels
+    ///
+    /// Schema metadata holds `mdf.group_name`, `mdf.group_comment` (if
+    /// present) and `mdf.record_count`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::MdfIndex;
+    ///
+    /// let index = MdfIndex::from_file_streaming("recording.mf4")?;
+    /// let schema = index.arrow_schema(0)?;
+    /// for field in schema.fields() {
+    ///     println!("{}: {}", field.name(), field.data_type());
+    /// }
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    pub fn arrow_schema(&self, group_index: usize) -> Result<Schema> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let fields: Vec<Field> = group
+            .channels
+            .iter()
+            .enumerate()
+            .map(|(i, channel)| {
+                let name = channel
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("channel_{}", i));
+                let mut metadata = HashMap::new();
+                if let Some(unit) = &channel.unit {
+                    metadata.insert("unit".to_string(), unit.clone());
+                }
+                metadata.insert(
+                    "mdf.data_type".to_string(),
+                    channel.data_type.to_u8().to_string(),
+                );
+                metadata.insert(
+                    "mdf.channel_type".to_string(),
+                    channel.channel_type.to_string(),
+                );
+                if matches!(channel.channel_type, 2 | 3) {
+                    metadata.insert("mdf.master".to_string(), "true".to_string());
+                }
+                Field::new(name, arrow_type(channel), true).with_metadata(metadata)
+            })
+            .collect();
+
+        let mut metadata = HashMap::new();
+        if let Some(name) = &group.name {
+            metadata.insert("mdf.group_name".to_string(), name.clone());
+        }
+        if let Some(comment) = &group.comment {
+            metadata.insert("mdf.group_comment".to_string(), comment.clone());
+        }
+        metadata.insert(
+            "mdf.record_count".to_string(),
+            group.record_count.to_string(),
+        );
+        Ok(Schema::new_with_metadata(fields, metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, UInt8Array};
+
+    fn channel(data_type: DataType, bit_count: u32) -> IndexedChannel {
+        IndexedChannel {
+            name: None,
+            unit: None,
+            data_type,
+            byte_offset: 0,
+            bit_offset: 0,
+            bit_count,
+            channel_type: 0,
+            flags: 0,
+            pos_invalidation_bit: 0,
+            conversion: None,
+            vlsd_data_address: None,
+            vlsd_blocks: Vec::new(),
+            stats: None,
+            block_stats: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn maps_data_types_and_conversions() {
+        assert_eq!(
+            arrow_type(&channel(DataType::UnsignedIntegerLE, 12)),
+            ArrowType::UInt16
+        );
+        assert_eq!(
+            arrow_type(&channel(DataType::SignedIntegerBE, 64)),
+            ArrowType::Int64
+        );
+        assert_eq!(
+            arrow_type(&channel(DataType::FloatLE, 32)),
+            ArrowType::Float32
+        );
+        assert_eq!(
+            arrow_type(&channel(DataType::StringUtf16LE, 64)),
+            ArrowType::Utf8
+        );
+        assert_eq!(
+            arrow_type(&channel(DataType::ByteArray, 64)),
+            ArrowType::Binary
+        );
+
+        let mut scaled = channel(DataType::UnsignedIntegerLE, 16);
+        scaled.conversion = Some(ConversionBlock::linear(0.5, 2.0));
+        assert_eq!(arrow_type(&scaled), ArrowType::Float64);
+    }
+
+    #[test]
+    fn converts_values_to_columns() {
+        let values = vec![
+            Some(DecodedValue::UnsignedInteger(7)),
+            None,
+            Some(DecodedValue::UnsignedInteger(300)),
+        ];
+        let array = to_array(&values, &ArrowType::UInt8);
+        let array = array.as_any().downcast_ref::<UInt8Array>().unwrap();
+        assert_eq!(array.value(0), 7);
+        assert!(array.is_null(1));
+        assert!(array.is_null(2));
+
+        let values = vec![
+            Some(DecodedValue::String("Off".to_string())),
+            Some(DecodedValue::Float(2.5)),
+        ];
+        let array = to_array(&values, &ArrowType::Utf8);
+        let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(array.value(0), "Off");
+        assert_eq!(array.value(1), "2.5");
+    }
+}
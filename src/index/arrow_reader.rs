@@ -0,0 +1,211 @@
+//! Arrow record batches of indexed channel groups.
+
+use super::{
+    BlockPayload, ByteRangeReader, IndexedChannel, IndexedChannelGroup, MdfIndex, arrow::to_array,
+};
+use crate::{Error, Result};
+use arrow_array::{RecordBatch, RecordBatchOptions, RecordBatchReader};
+use arrow_schema::{ArrowError, DataType as ArrowType, Field, Schema, SchemaRef};
+use std::sync::Arc;
+
+/// Whether the values of a channel are stored outside the records.
+fn is_vlsd(channel: &IndexedChannel) -> bool {
+    channel.channel_type == 1 && channel.vlsd_data_address.is_some()
+}
+
+/// Default number of records per [`RecordBatch`].
+pub const DEFAULT_ARROW_BATCH_SIZE: usize = 65_536;
+
+/// Reader of the records of a channel group as Arrow [`RecordBatch`]es,
+/// created by [`MdfIndex::arrow_reader()`].
+///
+/// Data blocks are fetched one at a time through the byte range reader and
+/// decoded straight into the Arrow columns, so memory use is bounded by the
+/// batch size and the largest data block. The reader implements
+/// [`RecordBatchReader`], which is accepted by polars, DataFusion and other
+/// Arrow based engines.
+///
+/// The schema is that of [`MdfIndex::arrow_schema()`] for the selected
+/// channels, except that 16 bit floats are widened to `Float32`. VLSD
+/// channels are stored outside the records and cannot be selected; by
+/// default all other channels are read.
+pub struct ArrowChannelGroupReader<'a, R> {
+    group: &'a IndexedChannelGroup,
+    reader: &'a mut R,
+    /// Fields of all channels of the group
+    fields: Vec<Field>,
+    /// Indices of the selected channels, in column order
+    channels: Vec<usize>,
+    schema: SchemaRef,
+    batch_size: usize,
+    /// Index of the next block to fetch
+    next_block: usize,
+    /// Records of the current block
+    buffer: BlockPayload,
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, R: ByteRangeReader<Error = Error>> ArrowChannelGroupReader<'a, R> {
+    /// Read only the given channels, in the given order.
+    ///
+    /// # Errors
+    /// Returns an error for invalid channel indices and VLSD channels.
+    pub fn with_channels(mut self, channel_indices: &[usize]) -> Result<Self> {
+        for &channel_index in channel_indices {
+            let channel = self.group.channels.get(channel_index).ok_or_else(|| {
+                Error::BlockSerializationError("Invalid channel index".to_string())
+            })?;
+            if is_vlsd(channel) {
+                return Err(Error::BlockSerializationError(format!(
+                    "VLSD channel {:?} cannot be read as record batches",
+                    channel.name
+                )));
+            }
+        }
+        let fields = channel_indices.iter().map(|&c| self.fields[c].clone());
+        let schema =
+            Schema::new_with_metadata(fields.collect::<Vec<_>>(), self.schema.metadata().clone());
+        self.channels = channel_indices.to_vec();
+        self.schema = Arc::new(schema);
+        Ok(self)
+    }
+
+    /// Set the maximum number of records per batch (at least 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Maximum number of records per batch.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Decode the next batch; `None` after the last record.
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let record_size = MdfIndex::record_size(self.group);
+        if self.done || record_size == 0 {
+            return Ok(None);
+        }
+        let mut columns = vec![Vec::new(); self.channels.len()];
+        let mut rows = 0;
+        while rows < self.batch_size {
+            let end = self.pos + record_size;
+            if end > self.buffer.len() {
+                let Some(block) = self.group.data_blocks.get(self.next_block) else {
+                    self.done = true;
+                    break;
+                };
+                self.buffer = MdfIndex::read_block_records(self.group, block, self.reader)?;
+                self.next_block += 1;
+                self.pos = 0;
+                continue;
+            }
+            let record = &self.buffer[self.pos..end];
+            for (column, &c) in columns.iter_mut().zip(&self.channels) {
+                let channel = &self.group.channels[c];
+                column.push(MdfIndex::decode_record_value(self.group, channel, record)?);
+            }
+            self.pos = end;
+            rows += 1;
+        }
+        if rows == 0 {
+            return Ok(None);
+        }
+
+        let arrays = columns
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(values, field)| to_array(values, field.data_type()))
+            .collect();
+        let options = RecordBatchOptions::new().with_row_count(Some(rows));
+        let batch = RecordBatch::try_new_with_options(self.schema.clone(), arrays, &options)
+            .map_err(|e| Error::BlockSerializationError(format!("Arrow error: {}", e)))?;
+        Ok(Some(batch))
+    }
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for ArrowChannelGroupReader<'_, R> {
+    type Item = core::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_batch() {
+            Ok(batch) => batch.map(Ok),
+            Err(e) => {
+                self.done = true;
+                Some(Err(ArrowError::ExternalError(Box::new(e))))
+            }
+        }
+    }
+}
+
+impl<R: ByteRangeReader<Error = Error>> RecordBatchReader for ArrowChannelGroupReader<'_, R> {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl MdfIndex {
+    /// Read the records of a channel group as Arrow record batches.
+    ///
+    /// Batches hold up to [`DEFAULT_ARROW_BATCH_SIZE`] records; see
+    /// [`ArrowChannelGroupReader`] for selecting channels and the batch size.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::{FileRangeReader, MdfIndex};
+    ///
+    /// let index = MdfIndex::from_file_streaming("recording.mf4")?;
+    /// let mut reader = FileRangeReader::new("recording.mf4")?;
+    /// let batches = index
+    ///     .arrow_reader(0, &mut reader)?
+    ///     .with_channels(&[0, 2])?
+    ///     .with_batch_size(10_000);
+    /// for batch in batches {
+    ///     println!("{} rows", batch?.num_rows());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn arrow_reader<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        group_index: usize,
+        reader: &'a mut R,
+    ) -> Result<ArrowChannelGroupReader<'a, R>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let full = self.arrow_schema(group_index)?;
+        let fields: Vec<Field> = full
+            .fields()
+            .iter()
+            .map(|field| match field.data_type() {
+                ArrowType::Float16 => field.as_ref().clone().with_data_type(ArrowType::Float32),
+                _ => field.as_ref().clone(),
+            })
+            .collect();
+        let channels: Vec<usize> = (0..group.channels.len())
+            .filter(|&c| !is_vlsd(&group.channels[c]))
+            .collect();
+        let selected = channels
+            .iter()
+            .map(|&c| fields[c].clone())
+            .collect::<Vec<_>>();
+        let schema = Schema::new_with_metadata(selected, full.metadata().clone());
+
+        Ok(ArrowChannelGroupReader {
+            group,
+            reader,
+            fields,
+            channels,
+            schema: Arc::new(schema),
+            batch_size: DEFAULT_ARROW_BATCH_SIZE,
+            next_block: 0,
+            buffer: BlockPayload::default(),
+            pos: 0,
+            done: false,
+        })
+    }
+}
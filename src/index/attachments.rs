@@ -0,0 +1,141 @@
+//! Attachment (AT block) summaries.
+
+use super::{ByteRangeReader, MdfIndex};
+use crate::{
+    Error, Result,
+    blocks::{AT_HEADER_SIZE, AttachmentFlags, BlockHeader, validate_buffer_size},
+};
+
+/// Summary of an attachment (AT block): an embedded file or a reference to
+/// an external one.
+///
+/// Holds everything needed to list attachments and to fetch the data of a
+/// single one with [`MdfIndex::read_attachment()`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexedAttachment {
+    /// Absolute file offset of the AT block
+    pub file_offset: u64,
+    /// File name (embedded) or path/URL (external)
+    pub file_name: Option<String>,
+    /// MIME type, e.g. "application/x-dbc"
+    pub mime_type: Option<String>,
+    /// Comment (plain text or XML)
+    pub comment: Option<String>,
+    /// Whether the data is embedded in the MDF file
+    pub embedded: bool,
+    /// Whether the embedded data is zlib compressed
+    pub compressed: bool,
+    /// Size of the original (uncompressed) data in bytes
+    pub original_size: u64,
+    /// Size of the embedded (possibly compressed) data in bytes
+    pub embedded_size: u64,
+    /// MD5 checksum of the original data, if recorded
+    pub md5: Option<[u8; 16]>,
+}
+
+impl IndexedAttachment {
+    /// Byte range `(offset, length)` of the embedded data; `None` for
+    /// external attachments.
+    pub fn data_range(&self) -> Option<(u64, u64)> {
+        self.embedded
+            .then_some((self.file_offset + AT_HEADER_SIZE as u64, self.embedded_size))
+    }
+}
+
+impl MdfIndex {
+    /// Read the data of an embedded attachment.
+    ///
+    /// Only the embedded data is read. Compressed data is decompressed,
+    /// which requires the `compression` feature.
+    ///
+    /// # Errors
+    /// Returns an error for invalid indices and external attachments.
+    pub fn read_attachment<R: ByteRangeReader<Error = Error>>(
+        &self,
+        attachment_index: usize,
+        reader: &mut R,
+    ) -> Result<Vec<u8>> {
+        let attachment = self.attachments.get(attachment_index).ok_or_else(|| {
+            Error::BlockSerializationError("Invalid attachment index".to_string())
+        })?;
+        let (offset, length) = attachment.data_range().ok_or_else(|| {
+            Error::BlockSerializationError(format!(
+                "Attachment {:?} is not embedded",
+                attachment.file_name
+            ))
+        })?;
+        let data = reader.read_range(offset, length)?;
+        if !attachment.compressed {
+            return Ok(data);
+        }
+
+        #[cfg(feature = "compression")]
+        {
+            let decompressed =
+                miniz_oxide::inflate::decompress_to_vec_zlib(&data).map_err(|e| {
+                    Error::BlockSerializationError(format!("AT decompression failed: {:?}", e))
+                })?;
+            if decompressed.len() as u64 != attachment.original_size {
+                return Err(Error::BlockSerializationError(format!(
+                    "AT decompressed size mismatch: expected {}, got {}",
+                    attachment.original_size,
+                    decompressed.len()
+                )));
+            }
+            Ok(decompressed)
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            Err(Error::BlockSerializationError(
+                "Compressed attachments require the 'compression' feature".to_string(),
+            ))
+        }
+    }
+
+    /// Read the summaries of the AT blocks in the list starting at `at_addr`.
+    ///
+    /// Only the fixed part of each block is read, not the embedded data.
+    pub(super) fn index_attachments<R: ByteRangeReader<Error = Error>>(
+        at_addr: u64,
+        reader: &mut R,
+    ) -> Result<Vec<IndexedAttachment>> {
+        let mut attachments = Vec::new();
+        let mut addr = at_addr;
+        while addr != 0 {
+            let bytes = reader.read_range(addr, AT_HEADER_SIZE as u64)?;
+            validate_buffer_size(&bytes, AT_HEADER_SIZE)?;
+            let header = BlockHeader::from_bytes(&bytes)?;
+            if header.id != "##AT" {
+                return Err(Error::BlockIDError {
+                    actual: header.id,
+                    expected: "##AT".to_string(),
+                });
+            }
+            let u64_at = |pos: usize| u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap());
+            let flags = AttachmentFlags::from_u16(u16::from_le_bytes([bytes[56], bytes[57]]));
+
+            attachments.push(IndexedAttachment {
+                file_offset: addr,
+                file_name: Self::read_text_block(reader, u64_at(32))?,
+                mime_type: Self::read_text_block(reader, u64_at(40))?,
+                comment: Self::read_comment_block(reader, u64_at(48))?,
+                embedded: flags.is_embedded(),
+                compressed: flags.is_compressed(),
+                original_size: u64_at(80),
+                embedded_size: u64_at(88),
+                md5: flags
+                    .is_md5_valid()
+                    .then(|| bytes[64..80].try_into().unwrap()),
+            });
+
+            // Guard against cyclic lists in corrupted files
+            let next = u64_at(24);
+            if attachments.iter().any(|a| a.file_offset == next) {
+                break;
+            }
+            addr = next;
+        }
+        Ok(attachments)
+    }
+}
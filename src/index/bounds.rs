@@ -0,0 +1,164 @@
+//! Master channel (time) bounds of data blocks and channel groups.
+
+use super::{ByteRangeReader, IndexedChannel, IndexedChannelGroup, MdfIndex};
+use crate::{Error, Result, parsing::decoder::DecodedValue};
+
+/// Combine bounds, treating `None` as no values.
+fn merge_bounds(a: Option<(f64, f64)>, b: (f64, f64)) -> (f64, f64) {
+    match a {
+        Some((first, last)) => (first.min(b.0), last.max(b.1)),
+        None => b,
+    }
+}
+
+impl IndexedChannelGroup {
+    /// The master channel of this group, if any.
+    pub fn master(&self) -> Option<&IndexedChannel> {
+        self.channels.get(self.master_channel?)
+    }
+
+    /// Smallest and largest master value of the group's records, from the
+    /// bounds recorded per data block.
+    ///
+    /// Returns `None` if the group has no data blocks or some block has no
+    /// recorded bounds.
+    pub fn time_bounds(&self) -> Option<(f64, f64)> {
+        self.data_blocks
+            .iter()
+            .try_fold(None, |acc, block| {
+                Some(Some(merge_bounds(acc, block.time_bounds?)))
+            })
+            .flatten()
+    }
+}
+
+impl MdfIndex {
+    /// Smallest and largest master (time) value over all channel groups.
+    ///
+    /// Answers "what time range does this file cover?" from the index alone.
+    /// Groups without recorded bounds (see
+    /// [`IndexedChannelGroup::time_bounds()`]) are ignored; `None` if no
+    /// group has bounds.
+    pub fn time_bounds(&self) -> Option<(f64, f64)> {
+        self.channel_groups
+            .iter()
+            .filter_map(IndexedChannelGroup::time_bounds)
+            .fold(None, |acc, bounds| Some(merge_bounds(acc, bounds)))
+    }
+
+    /// Record the first and last master value of every data block,
+    /// including compressed blocks and blocks of unsorted groups.
+    ///
+    /// Indexes record these bounds when they are created, but skip blocks
+    /// that would have to be read completely. This reads (and decompresses)
+    /// those blocks once.
+    pub fn compute_time_bounds<R: ByteRangeReader<Error = Error>>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<()> {
+        for group in &mut self.channel_groups {
+            for block in &mut group.data_blocks {
+                block.time_bounds = None;
+            }
+            Self::index_time_bounds(group, reader, true)?;
+        }
+        Ok(())
+    }
+
+    /// Fill in the missing time bounds of the data blocks of a group.
+    ///
+    /// Uncompressed blocks of sorted groups need two record reads each (all
+    /// issued with one [`ByteRangeReader::read_ranges()`] call), and none
+    /// for virtual master channels. Other blocks are read completely if
+    /// `read_full_blocks` is set and skipped otherwise.
+    pub(super) fn index_time_bounds<R: ByteRangeReader<Error = Error>>(
+        group: &mut IndexedChannelGroup,
+        reader: &mut R,
+        read_full_blocks: bool,
+    ) -> Result<()> {
+        let Some(master) = group.master() else {
+            return Ok(());
+        };
+        let record_size = Self::record_size(group);
+        if record_size == 0 {
+            return Ok(());
+        }
+        let time_at = |record_index: u64, record: &[u8]| -> Result<Option<f64>> {
+            let value = if master.channel_type == 3 {
+                // Virtual master: the raw value is the record index
+                let raw = DecodedValue::UnsignedInteger(record_index);
+                match &master.conversion {
+                    Some(conversion) => Some(conversion.apply_decoded(raw, &[])?),
+                    None => Some(raw),
+                }
+            } else {
+                Self::decode_record_value(group, master, record)?
+            };
+            Ok(value
+                .as_ref()
+                .and_then(DecodedValue::as_f64)
+                .filter(|t| !t.is_nan()))
+        };
+        let bounds_of = |first: Option<f64>, last: Option<f64>| first.zip(last);
+
+        let mut bounds: Vec<_> = group.data_blocks.iter().map(|b| b.time_bounds).collect();
+        // Blocks whose first and last records are read below
+        let mut probed = Vec::new();
+        let mut ranges = Vec::new();
+        // Index of the first record of the current block, while known
+        let mut first_record = Some(0u64);
+
+        for (i, block) in group.data_blocks.iter().enumerate() {
+            let direct = !block.is_compressed && !group.is_unsorted();
+            let mut records = direct.then(|| (block.size - 24) / record_size as u64);
+            if bounds[i].is_none() {
+                match records {
+                    Some(0) => {}
+                    Some(n) if master.channel_type == 3 => {
+                        if let Some(first) = first_record {
+                            bounds[i] =
+                                bounds_of(time_at(first, &[])?, time_at(first + n - 1, &[])?);
+                        }
+                    }
+                    Some(n) => {
+                        let data_start = block.file_offset + 24;
+                        probed.push(i);
+                        ranges.push((data_start, record_size as u64));
+                        ranges.push((
+                            data_start + (n - 1) * record_size as u64,
+                            record_size as u64,
+                        ));
+                    }
+                    None if read_full_blocks => {
+                        let data = Self::read_block_records(group, block, reader)?;
+                        let n = (data.len() / record_size) as u64;
+                        // Only virtual master values depend on the record index
+                        let first = first_record.or((master.channel_type != 3).then_some(0));
+                        if let (Some(first), true) = (first, n > 0) {
+                            let last = &data[(n as usize - 1) * record_size..][..record_size];
+                            bounds[i] = bounds_of(
+                                time_at(first, &data[..record_size])?,
+                                time_at(first + n - 1, last)?,
+                            );
+                        }
+                        records = Some(n);
+                    }
+                    None => {}
+                }
+            }
+            first_record = first_record.zip(records).map(|(first, n)| first + n);
+        }
+
+        if !ranges.is_empty() {
+            let data = reader.read_ranges(&ranges)?;
+            for (&i, pair) in probed.iter().zip(data.chunks_exact(2)) {
+                bounds[i] = bounds_of(time_at(0, &pair[0])?, time_at(0, &pair[1])?);
+            }
+        }
+
+        for (block, bounds) in group.data_blocks.iter_mut().zip(bounds) {
+            block.time_bounds = bounds;
+        }
+        Ok(())
+    }
+}
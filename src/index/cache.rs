@@ -0,0 +1,261 @@
+//! LRU cache of decompressed data blocks.
+
+use super::ByteRangeReader;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Default byte budget of a [`BlockCacheReader`] (64 MiB).
+pub const DEFAULT_BLOCK_CACHE_BUDGET: usize = 64 * 1024 * 1024;
+
+struct CachedBlock {
+    data: Arc<[u8]>,
+    /// Value of the use counter at the last access
+    last_used: u64,
+}
+
+/// Byte range reader that caches decompressed DZ blocks.
+///
+/// Every indexed read of a compressed channel group decompresses all of its
+/// blocks, so reading several channels one after another repeats the same
+/// work. Wrapping the reader in a `BlockCacheReader` keeps the most recently
+/// used decompressed payloads up to a byte budget; blocks larger than the
+/// budget are not cached. Uncompressed reads pass through unchanged.
+/// Cached payloads are shared with the reads that use them, so a cache hit
+/// does not copy the block.
+///
+/// The cache is keyed by file offset, so use one `BlockCacheReader` per
+/// source file.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::{FileRangeReader, MdfIndex};
+/// use mdf4_rs::index::BlockCacheReader;
+///
+/// let index = MdfIndex::from_file_streaming("compressed.mf4")?;
+/// let mut reader = BlockCacheReader::new(FileRangeReader::new("compressed.mf4")?)
+///     .with_budget(256 * 1024 * 1024);
+/// for channel in 0..index.channel_groups[0].channels.len() {
+///     let values = index.read_channel_values(0, channel, &mut reader)?;
+/// }
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+pub struct BlockCacheReader<R> {
+    inner: R,
+    budget: usize,
+    used: usize,
+    blocks: BTreeMap<u64, CachedBlock>,
+    /// File offsets of the cached blocks by `last_used`
+    lru: BTreeMap<u64, u64>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<R> BlockCacheReader<R> {
+    /// Wrap a reader with a cache of [`DEFAULT_BLOCK_CACHE_BUDGET`] bytes.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            budget: DEFAULT_BLOCK_CACHE_BUDGET,
+            used: 0,
+            blocks: BTreeMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Set the maximum number of decompressed bytes to keep.
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.set_budget(budget);
+        self
+    }
+
+    /// Set the maximum number of decompressed bytes to keep, evicting the
+    /// least recently used blocks if the cache exceeds it.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict(0);
+    }
+
+    /// Maximum number of decompressed bytes kept.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Number of decompressed bytes currently cached.
+    pub fn cached_bytes(&self) -> usize {
+        self.used
+    }
+
+    /// Number of blocks currently cached.
+    pub fn cached_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Number of block lookups served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of block lookups that required decompression.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Drop all cached blocks, e.g. after the source file changed.
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.lru.clear();
+        self.used = 0;
+    }
+
+    /// The wrapped reader.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consume the cache and return the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Evict least recently used blocks until `incoming` more bytes fit.
+    fn evict(&mut self, incoming: usize) {
+        while self.used + incoming > self.budget {
+            let Some((_, offset)) = self.lru.pop_first() else {
+                break;
+            };
+            if let Some(block) = self.blocks.remove(&offset) {
+                self.used -= block.data.len();
+            }
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+impl<R: ByteRangeReader> ByteRangeReader for BlockCacheReader<R> {
+    type Error = R::Error;
+
+    fn read_range(
+        &mut self,
+        offset: u64,
+        length: u64,
+    ) -> core::result::Result<Vec<u8>, Self::Error> {
+        self.inner.read_range(offset, length)
+    }
+
+    fn read_ranges(
+        &mut self,
+        ranges: &[(u64, u64)],
+    ) -> core::result::Result<Vec<Vec<u8>>, Self::Error> {
+        self.inner.read_ranges(ranges)
+    }
+
+    fn cached_block(&mut self, offset: u64) -> Option<Arc<[u8]>> {
+        let now = self.tick();
+        match self.blocks.get_mut(&offset) {
+            Some(block) => {
+                self.lru.remove(&block.last_used);
+                self.lru.insert(now, offset);
+                block.last_used = now;
+                self.hits += 1;
+                Some(Arc::clone(&block.data))
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn cache_block(&mut self, offset: u64, data: &Arc<[u8]>) {
+        if data.len() > self.budget {
+            return;
+        }
+        if let Some(old) = self.blocks.remove(&offset) {
+            self.lru.remove(&old.last_used);
+            self.used -= old.data.len();
+        }
+        self.evict(data.len());
+
+        let now = self.tick();
+        self.lru.insert(now, offset);
+        self.blocks.insert(
+            offset,
+            CachedBlock {
+                data: Arc::clone(data),
+                last_used: now,
+            },
+        );
+        self.used += data.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader over an in-memory buffer
+    struct MemoryReader(Vec<u8>);
+
+    impl ByteRangeReader for MemoryReader {
+        type Error = crate::Error;
+
+        fn read_range(&mut self, offset: u64, length: u64) -> crate::Result<Vec<u8>> {
+            Ok(self.0[offset as usize..(offset + length) as usize].to_vec())
+        }
+    }
+
+    fn block(value: u8, len: usize) -> Arc<[u8]> {
+        vec![value; len].into()
+    }
+
+    #[test]
+    fn evicts_least_recently_used_blocks() {
+        let mut cache = BlockCacheReader::new(MemoryReader(Vec::new())).with_budget(300);
+        cache.cache_block(0, &block(0, 100));
+        cache.cache_block(1000, &block(1, 100));
+        cache.cache_block(2000, &block(2, 100));
+        assert_eq!(cache.cached_bytes(), 300);
+
+        // Use the oldest block, so the second one is evicted next
+        assert_eq!(cache.cached_block(0), Some(block(0, 100)));
+        cache.cache_block(3000, &block(3, 100));
+        assert_eq!(cache.cached_block(1000), None);
+        assert!(cache.cached_block(0).is_some());
+        assert!(cache.cached_block(2000).is_some());
+        assert!(cache.cached_block(3000).is_some());
+        assert_eq!((cache.hits(), cache.misses()), (4, 1));
+
+        // Blocks over budget are not cached
+        cache.cache_block(4000, &block(4, 301));
+        assert_eq!(cache.cached_block(4000), None);
+        assert_eq!(cache.cached_blocks(), 3);
+
+        cache.set_budget(150);
+        assert_eq!(cache.cached_blocks(), 1);
+        assert!(cache.cached_bytes() <= 150);
+
+        cache.clear();
+        assert_eq!(cache.cached_bytes(), 0);
+    }
+
+    #[test]
+    fn replaces_block_at_same_offset() {
+        let mut cache = BlockCacheReader::new(MemoryReader(vec![7; 16]));
+        cache.cache_block(8, &block(1, 10));
+        cache.cache_block(8, &block(2, 20));
+        assert_eq!(cache.cached_bytes(), 20);
+        let data = cache.cached_block(8).unwrap();
+        assert_eq!(data, block(2, 20));
+        // Hits share the cached payload
+        assert!(Arc::ptr_eq(&data, &cache.cached_block(8).unwrap()));
+        assert_eq!(cache.read_range(4, 4).unwrap(), vec![7; 4]);
+    }
+}
@@ -0,0 +1,396 @@
+//! Downsampling of channels for plotting.
+
+use super::{BlockPayload, ByteRangeReader, IndexedChannel, IndexedChannelGroup, MdfIndex};
+use crate::{Error, Result, parsing::decoder::DecodedValue};
+use std::collections::VecDeque;
+
+/// Reduction of the records of a window.
+#[derive(Debug, Clone, Copy)]
+enum Method {
+    /// Largest-triangle-three-buckets with this number of points
+    Lttb(u64),
+    /// Minimum and maximum of this number of buckets
+    MinMax(u64),
+}
+
+/// A sample as `(time, value)`.
+type Sample = (f64, f64);
+
+/// Area of the triangle `a`, `b`, `c` (times two).
+fn triangle_area(a: Sample, b: Sample, c: Sample) -> f64 {
+    ((a.0 - c.0) * (b.1 - a.1) - (a.0 - b.0) * (c.1 - a.1)).abs()
+}
+
+/// Streaming largest-triangle-three-buckets.
+///
+/// Only the current and the next bucket are held in memory.
+struct Lttb {
+    /// Bucket index and samples of the buckets not yet reduced
+    buckets: VecDeque<(u64, Vec<Sample>)>,
+    /// Last selected sample
+    selected: Option<Sample>,
+    /// Most recent sample, held back as it may be the last one
+    pending: Option<(u64, Sample)>,
+    output: Vec<Sample>,
+}
+
+impl Lttb {
+    fn new() -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            selected: None,
+            pending: None,
+            output: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, bucket: u64, sample: Sample) {
+        if self.selected.is_none() {
+            self.selected = Some(sample);
+            self.output.push(sample);
+            return;
+        }
+        if let Some((bucket, sample)) = self.pending.replace((bucket, sample)) {
+            match self.buckets.back_mut() {
+                Some((last, samples)) if *last == bucket => samples.push(sample),
+                _ => self.buckets.push_back((bucket, vec![sample])),
+            }
+            // The second bucket is complete once a third one starts
+            if self.buckets.len() > 2 {
+                let next = average(&self.buckets[1].1);
+                self.reduce_front(next);
+            }
+        }
+    }
+
+    /// Select the sample of the front bucket with the largest triangle
+    /// between the last selected sample and `next`.
+    fn reduce_front(&mut self, next: Sample) {
+        let (Some((_, samples)), Some(selected)) = (self.buckets.pop_front(), self.selected) else {
+            return;
+        };
+        let best = samples.into_iter().max_by(|&a, &b| {
+            triangle_area(selected, a, next).total_cmp(&triangle_area(selected, b, next))
+        });
+        if let Some(best) = best {
+            self.selected = Some(best);
+            self.output.push(best);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Sample> {
+        let Some((_, last)) = self.pending.take() else {
+            return self.output;
+        };
+        while self.buckets.len() > 1 {
+            let next = average(&self.buckets[1].1);
+            self.reduce_front(next);
+        }
+        self.reduce_front(last);
+        self.output.push(last);
+        self.output
+    }
+}
+
+/// Mean time and value of `samples`.
+fn average(samples: &[Sample]) -> Sample {
+    let n = samples.len() as f64;
+    let (t, v) = samples
+        .iter()
+        .fold((0.0, 0.0), |(t, v), s| (t + s.0, v + s.1));
+    (t / n, v / n)
+}
+
+/// Streaming minimum/maximum per bucket.
+struct MinMax {
+    /// Bucket index and its (minimum, maximum) samples in time order
+    current: Option<(u64, Sample, Sample)>,
+    output: Vec<Sample>,
+}
+
+impl MinMax {
+    fn push(&mut self, bucket: u64, sample: Sample) {
+        match &mut self.current {
+            Some((b, min, max)) if *b == bucket => {
+                if sample.1 < min.1 {
+                    *min = sample;
+                }
+                if sample.1 > max.1 {
+                    *max = sample;
+                }
+            }
+            _ => {
+                self.flush();
+                self.current = Some((bucket, sample, sample));
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some((_, min, max)) = self.current.take() {
+            let (first, second) = if min.0 <= max.0 {
+                (min, max)
+            } else {
+                (max, min)
+            };
+            self.output.push(first);
+            if second != first {
+                self.output.push(second);
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<Sample> {
+        self.flush();
+        self.output
+    }
+}
+
+impl MdfIndex {
+    /// Read a numeric channel downsampled with largest-triangle-three-buckets.
+    ///
+    /// The records whose master value lies in `[t0, t1]` are found like
+    /// [`read_channel_values_in_time_range()`](Self::read_channel_values_in_time_range)
+    /// does. The first and last sample are kept, and the records in between
+    /// are split into `points - 2` buckets of equal record counts, of which
+    /// the sample spanning the largest triangle with the samples selected
+    /// around it is kept. This preserves the visual shape of a line plot
+    /// with few points. If the window has no more than `points` records,
+    /// all its samples are returned.
+    ///
+    /// Records are streamed one data block (or, for uncompressed blocks,
+    /// the part of a block within the window) at a time, so memory use does
+    /// not grow with the size of the window. Samples with invalid or
+    /// non-numeric time stamps or values are skipped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::{FileRangeReader, MdfIndex};
+    ///
+    /// let index = MdfIndex::load_from_file_binary("recording.mdfidx")?;
+    /// let mut reader = FileRangeReader::new("recording.mf4")?;
+    /// // 500 points of the first minute
+    /// let series = index.read_channel_lttb(0, 1, 0.0, 60.0, 500, &mut reader)?;
+    /// for (time, value) in series {
+    ///     println!("{time}: {value}");
+    /// }
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    ///
+    /// # Returns
+    /// The selected samples as `(time, value)` pairs in time order, or an
+    /// [`crate::Error`] if `points` is less than 3, the group has no master
+    /// channel, the channel has variable length values or reading fails.
+    pub fn read_channel_lttb<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        t0: f64,
+        t1: f64,
+        points: usize,
+        reader: &mut R,
+    ) -> Result<Vec<(f64, f64)>> {
+        if points < 3 {
+            return Err(Error::BlockSerializationError(
+                "LTTB downsampling needs at least 3 points".to_string(),
+            ));
+        }
+        let method = Method::Lttb(points as u64);
+        self.read_channel_downsampled(group_index, channel_index, (t0, t1), method, reader)
+    }
+
+    /// Read the minimum and maximum of a numeric channel per bucket, e.g.
+    /// per pixel column of a plot.
+    ///
+    /// The records whose master value lies in `[t0, t1]` are split into
+    /// `buckets` buckets of equal record counts, which correspond to equal
+    /// time spans for regularly sampled channels. Of each bucket, the
+    /// samples with the smallest and largest value are returned in time
+    /// order (once if they are the same), so no peak is lost. If the window
+    /// has no more than `2 * buckets` records, all its samples are
+    /// returned.
+    ///
+    /// Records are streamed like by
+    /// [`read_channel_lttb()`](Self::read_channel_lttb).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::{FileRangeReader, MdfIndex};
+    ///
+    /// let index = MdfIndex::load_from_file_binary("recording.mdfidx")?;
+    /// let mut reader = FileRangeReader::new("recording.mf4")?;
+    /// // A 1200 pixel wide plot of the whole recording
+    /// let series =
+    ///     index.read_channel_min_max(0, 1, f64::NEG_INFINITY, f64::INFINITY, 1200, &mut reader)?;
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    ///
+    /// # Returns
+    /// The samples as `(time, value)` pairs in time order, or an
+    /// [`crate::Error`] if `buckets` is 0, the group has no master channel,
+    /// the channel has variable length values or reading fails.
+    pub fn read_channel_min_max<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        t0: f64,
+        t1: f64,
+        buckets: usize,
+        reader: &mut R,
+    ) -> Result<Vec<(f64, f64)>> {
+        if buckets == 0 {
+            return Err(Error::BlockSerializationError(
+                "Min/max downsampling needs at least 1 bucket".to_string(),
+            ));
+        }
+        let method = Method::MinMax(buckets as u64);
+        self.read_channel_downsampled(group_index, channel_index, (t0, t1), method, reader)
+    }
+
+    /// Downsample the samples of a channel within a time window.
+    fn read_channel_downsampled<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        (t0, t1): (f64, f64),
+        method: Method,
+        reader: &mut R,
+    ) -> Result<Vec<(f64, f64)>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
+            return Err(Error::BlockSerializationError(
+                "Channels with variable length values cannot be downsampled".to_string(),
+            ));
+        }
+
+        let records = self.find_record_range_for_time(group_index, t0, t1, reader)?;
+        let count = records.end - records.start;
+        let master = group.master().ok_or_else(|| {
+            Error::BlockSerializationError("Channel group has no master channel".to_string())
+        })?;
+        let start = records.start;
+
+        match method {
+            Method::Lttb(points) if count > points => {
+                // First and last sample are kept; the rest fills the buckets
+                let buckets = points - 2;
+                let mut lttb = Lttb::new();
+                Self::for_each_sample(
+                    group,
+                    master,
+                    channel,
+                    records,
+                    reader,
+                    |record, sample| lttb.push((record - start) * buckets / count, sample),
+                )?;
+                Ok(lttb.finish())
+            }
+            Method::MinMax(buckets) if count > 2 * buckets => {
+                let mut min_max = MinMax {
+                    current: None,
+                    output: Vec::new(),
+                };
+                Self::for_each_sample(
+                    group,
+                    master,
+                    channel,
+                    records,
+                    reader,
+                    |record, sample| min_max.push((record - start) * buckets / count, sample),
+                )?;
+                Ok(min_max.finish())
+            }
+            _ => {
+                let mut samples = Vec::with_capacity(count as usize);
+                Self::for_each_sample(group, master, channel, records, reader, |_, sample| {
+                    samples.push(sample)
+                })?;
+                Ok(samples)
+            }
+        }
+    }
+
+    /// Call `f` with the record index and `(time, value)` sample of each
+    /// record of `group` in `records` with a numeric time and value.
+    fn for_each_sample<R: ByteRangeReader<Error = Error>>(
+        group: &IndexedChannelGroup,
+        master: &IndexedChannel,
+        channel: &IndexedChannel,
+        records: core::ops::Range<u64>,
+        reader: &mut R,
+        mut f: impl FnMut(u64, Sample),
+    ) -> Result<()> {
+        let record_size = Self::record_size(group);
+        if record_size == 0 || records.is_empty() {
+            return Ok(());
+        }
+        let numeric = |value: Option<DecodedValue>| {
+            value
+                .as_ref()
+                .and_then(DecodedValue::as_f64)
+                .filter(|v| !v.is_nan())
+        };
+
+        // Index of the first record of the current block
+        let mut block_start = 0u64;
+        for block in &group.data_blocks {
+            if block_start >= records.end {
+                break;
+            }
+            let direct = !block.is_compressed && !group.is_unsorted();
+            // Records of the block and the index of the first one
+            let (data, first) = if direct {
+                let block_records = (block.size - 24) / record_size as u64;
+                let block_end = block_start + block_records;
+                let start = records.start.max(block_start);
+                let end = records.end.min(block_end);
+                let first = start;
+                block_start = block_end;
+                if start >= end {
+                    continue;
+                }
+                let offset = block.file_offset
+                    + 24
+                    + (start - (block_end - block_records)) * record_size as u64;
+                let data = reader.read_range(offset, (end - start) * record_size as u64)?;
+                (BlockPayload::Owned(data), first)
+            } else {
+                let data = Self::read_block_records(group, block, reader)?;
+                let first = block_start;
+                block_start += (data.len() / record_size) as u64;
+                (data, first)
+            };
+
+            for (i, record) in data.chunks_exact(record_size).enumerate() {
+                let index = first + i as u64;
+                if !records.contains(&index) {
+                    continue;
+                }
+                let time = if master.channel_type == 3 {
+                    // Virtual master: the raw value is the record index
+                    let raw = DecodedValue::UnsignedInteger(index);
+                    match &master.conversion {
+                        Some(conversion) => Some(conversion.apply_decoded(raw, &[])?),
+                        None => Some(raw),
+                    }
+                } else {
+                    Self::decode_record_value(group, master, record)?
+                };
+                let value = Self::decode_record_value(group, channel, record)?;
+                if let (Some(time), Some(value)) = (numeric(time), numeric(value)) {
+                    f(index, (time, value));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! Event (EV block) summaries.
+
+use super::{ByteRangeReader, IndexedAttachment, MdfIndex};
+use crate::{
+    Error, Result,
+    blocks::{
+        BlockHeader, BlockParse, EventBlock, EventCause, EventRangeType, EventSyncType, EventType,
+    },
+};
+
+/// Summary of an event (EV block), such as a trigger or a user marker.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexedEvent {
+    /// Absolute file offset of the EV block
+    pub file_offset: u64,
+    /// Event name
+    pub name: Option<String>,
+    /// Comment (plain text or XML)
+    pub comment: Option<String>,
+    /// Kind of event
+    pub event_type: EventType,
+    /// Domain of the synchronization value (time, angle, ...)
+    pub sync_type: EventSyncType,
+    /// Point event or begin/end of a range
+    pub range_type: EventRangeType,
+    /// What caused the event
+    pub cause: EventCause,
+    /// Synchronization value, e.g. the time in seconds
+    pub sync_value: f64,
+    /// Position of the parent event in [`MdfIndex::events`]
+    pub parent: Option<usize>,
+    /// For range end events: position of the range begin event in
+    /// [`MdfIndex::events`]
+    pub range_begin: Option<usize>,
+    /// Positions of the attached files in [`MdfIndex::attachments`]
+    pub attachments: Vec<usize>,
+}
+
+impl MdfIndex {
+    /// Events whose synchronization value lies within `[start, end]`.
+    pub fn events_in_range(&self, start: f64, end: f64) -> Vec<&IndexedEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.sync_value >= start && event.sync_value <= end)
+            .collect()
+    }
+
+    /// Read the summaries of the EV blocks in the list starting at `ev_addr`.
+    ///
+    /// References to other events and to attachments are resolved to their
+    /// positions in the returned list and in `attachments`.
+    pub(super) fn index_events<R: ByteRangeReader<Error = Error>>(
+        ev_addr: u64,
+        attachments: &[IndexedAttachment],
+        reader: &mut R,
+    ) -> Result<Vec<IndexedEvent>> {
+        let mut blocks = Vec::new();
+        let mut addr = ev_addr;
+        while addr != 0 {
+            let header = BlockHeader::from_bytes(&reader.read_range(addr, 24)?)?;
+            let block = EventBlock::from_bytes(&reader.read_range(addr, header.length)?)?;
+            // Guard against cyclic lists in corrupted files
+            let next = block.next_ev_addr;
+            blocks.push((addr, block));
+            if blocks.iter().any(|(a, _)| *a == next) {
+                break;
+            }
+            addr = next;
+        }
+
+        let event_at = |addr: u64| blocks.iter().position(|(a, _)| *a == addr);
+        let mut events = Vec::with_capacity(blocks.len());
+        for (addr, block) in &blocks {
+            events.push(IndexedEvent {
+                file_offset: *addr,
+                name: Self::read_text_block(reader, block.name_addr)?,
+                comment: Self::read_comment_block(reader, block.comment_addr)?,
+                event_type: block.event_type,
+                sync_type: block.sync_type,
+                range_type: block.range_type,
+                cause: block.cause,
+                sync_value: block.sync_value(),
+                parent: event_at(block.parent_ev_addr),
+                range_begin: event_at(block.range_ev_addr),
+                attachments: block
+                    .attachment_addrs
+                    .iter()
+                    .filter_map(|&at| attachments.iter().position(|a| a.file_offset == at))
+                    .collect(),
+            });
+        }
+        Ok(events)
+    }
+}
@@ -0,0 +1,125 @@
+//! Fingerprint of the indexed file, to detect stale cached indexes.
+
+use super::{ByteRangeReader, MdfIndex};
+use crate::{Error, Result};
+
+/// Number of bytes hashed at the start and at the end of the file.
+const FINGERPRINT_SPAN: u64 = 64 * 1024;
+
+/// Content fingerprint of the file an index was built from.
+///
+/// Together with [`MdfIndex::file_size`], the hashes of the first and last
+/// 64 KiB identify the file: the start holds the identification, header and
+/// usually most metadata blocks, the end the most recently written data.
+/// The hashes are FNV-1a, which detects changes but is not collision
+/// resistant against deliberate tampering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceFingerprint {
+    /// Hash of the first 64 KiB (or the whole file if smaller)
+    pub head_hash: u64,
+    /// Hash of the last 64 KiB (or the whole file if smaller)
+    pub tail_hash: u64,
+}
+
+impl SourceFingerprint {
+    /// Compute the fingerprint of a source of `file_size` bytes.
+    pub fn compute<R: ByteRangeReader<Error = Error>>(
+        reader: &mut R,
+        file_size: u64,
+    ) -> Result<Self> {
+        let span = FINGERPRINT_SPAN.min(file_size);
+        let head_hash = fnv1a(&reader.read_range(0, span)?);
+        let tail_hash = if file_size <= FINGERPRINT_SPAN {
+            head_hash
+        } else {
+            fnv1a(&reader.read_range(file_size - span, span)?)
+        };
+        Ok(Self {
+            head_hash,
+            tail_hash,
+        })
+    }
+}
+
+/// 64-bit FNV-1a hash.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl MdfIndex {
+    /// Record the current size and contents of the source.
+    ///
+    /// Use this after [`refresh()`](Self::refresh) once the file is complete.
+    ///
+    /// # Arguments
+    /// * `reader` - Reader for the indexed file
+    /// * `file_size` - Current total size of the file in bytes
+    pub fn update_fingerprint<R: ByteRangeReader<Error = Error>>(
+        &mut self,
+        reader: &mut R,
+        file_size: u64,
+    ) -> Result<()> {
+        self.fingerprint = Some(SourceFingerprint::compute(reader, file_size)?);
+        self.file_size = file_size;
+        Ok(())
+    }
+
+    /// Check that this index describes the data returned by `reader`.
+    ///
+    /// Cached indexes become stale when the file is rewritten, replaced or
+    /// appended to; reading with a stale index silently decodes the wrong
+    /// bytes. This compares the file size and the stored
+    /// [`fingerprint`](Self::fingerprint) with the source, reading at most
+    /// 128 KiB.
+    ///
+    /// Indexes without a fingerprint (created by older versions) are only
+    /// checked for their size.
+    ///
+    /// # Errors
+    /// [`Error::IndexMismatch`] if the source differs from the indexed file.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::{FileRangeReader, MdfIndex};
+    ///
+    /// let mut reader = FileRangeReader::new("recording.mf4")?;
+    /// let index = match MdfIndex::load_from_file_binary("recording.mdfidx") {
+    ///     Ok(index) if index.validate_against(&mut reader).is_ok() => index,
+    ///     _ => MdfIndex::from_file_streaming("recording.mf4")?,
+    /// };
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    pub fn validate_against<R: ByteRangeReader<Error = Error>>(
+        &self,
+        reader: &mut R,
+    ) -> Result<()> {
+        // A source with data after the indexed size has grown
+        if matches!(reader.read_range(self.file_size, 1), Ok(b) if !b.is_empty()) {
+            return Err(Error::IndexMismatch(format!(
+                "source is larger than the indexed {} bytes",
+                self.file_size
+            )));
+        }
+
+        let last_byte = self.file_size.saturating_sub(1);
+        if self.file_size > 0 && reader.read_range(last_byte, 1).is_err() {
+            return Err(Error::IndexMismatch(format!(
+                "source is smaller than the indexed {} bytes",
+                self.file_size
+            )));
+        }
+
+        if let Some(expected) = self.fingerprint {
+            let actual = SourceFingerprint::compute(reader, self.file_size)?;
+            if actual != expected {
+                return Err(Error::IndexMismatch(
+                    "source content differs from the indexed file".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
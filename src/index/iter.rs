@@ -0,0 +1,198 @@
+//! Streaming iteration over indexed channel values.
+
+use super::{
+    BlockPayload, ByteRangeReader, DataBlockInfo, IndexedChannel, IndexedChannelGroup, MdfIndex,
+    SignalDataBlockInfo,
+};
+use crate::{Error, Result, parsing::decoder::DecodedValue};
+use std::borrow::Cow;
+
+/// Blocks an [`IndexedValuesIter`] reads its values from.
+enum Source<'a> {
+    /// Records in the data blocks of the channel group
+    Records(&'a [DataBlockInfo]),
+    /// `[u32 length][value bytes]` entries in the signal data blocks of a
+    /// VLSD channel
+    SignalData(Cow<'a, [SignalDataBlockInfo]>),
+}
+
+/// Iterator over the values of one channel, created by
+/// [`MdfIndex::iter_channel_values()`].
+///
+/// Only one data block is held in memory at a time. Each item is the same
+/// value [`MdfIndex::read_channel_values()`] would return at that position,
+/// or the error that occurred while fetching or decoding a block; iteration
+/// ends after the first error.
+pub struct IndexedValuesIter<'a, R> {
+    group: &'a IndexedChannelGroup,
+    channel: &'a IndexedChannel,
+    reader: &'a mut R,
+    source: Source<'a>,
+    /// Index of the next block to fetch
+    next_block: usize,
+    /// Data of the current block (for VLSD channels, including a value
+    /// carried over from the previous block)
+    buffer: BlockPayload,
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, R: ByteRangeReader<Error = Error>> IndexedValuesIter<'a, R> {
+    fn block_count(&self) -> usize {
+        match &self.source {
+            Source::Records(blocks) => blocks.len(),
+            Source::SignalData(blocks) => blocks.len(),
+        }
+    }
+
+    /// Fetch the next block into the buffer; returns `false` at the end.
+    fn fetch_block(&mut self) -> Result<bool> {
+        if self.next_block >= self.block_count() {
+            return Ok(false);
+        }
+        let index = self.next_block;
+        self.next_block += 1;
+
+        match &self.source {
+            Source::Records(blocks) => {
+                self.buffer =
+                    MdfIndex::read_block_records(self.group, &blocks[index], self.reader)?;
+                self.pos = 0;
+            }
+            Source::SignalData(blocks) => {
+                let block = &blocks[index];
+                // Keep an incomplete value from the previous block
+                let buffer = self.buffer.to_mut();
+                buffer.drain(..self.pos);
+                self.pos = 0;
+                if block.size > 24 {
+                    let data = self
+                        .reader
+                        .read_range(block.file_offset + 24, block.size - 24)?;
+                    buffer.extend_from_slice(&data);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Decode the next value from the buffer, if it holds a complete one.
+    fn next_buffered(&mut self) -> Result<Option<Option<DecodedValue>>> {
+        match self.source {
+            Source::Records(_) => {
+                let record_size = MdfIndex::record_size(self.group);
+                let end = self.pos + record_size;
+                if record_size == 0 || end > self.buffer.len() {
+                    return Ok(None);
+                }
+                let record = &self.buffer[self.pos..end];
+                self.pos = end;
+                MdfIndex::decode_record_value(self.group, self.channel, record).map(Some)
+            }
+            Source::SignalData(_) => {
+                let Some(prefix) = self.buffer.get(self.pos..self.pos + 4) else {
+                    return Ok(None);
+                };
+                let len = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]);
+                let start = self.pos + 4;
+                let end = start + len as usize;
+                if end > self.buffer.len() {
+                    return Ok(None);
+                }
+                self.pos = end;
+                Ok(Some(MdfIndex::convert_vlsd_value(
+                    &self.buffer[start..end],
+                    self.channel,
+                )))
+            }
+        }
+    }
+}
+
+impl<R: ByteRangeReader<Error = Error>> Iterator for IndexedValuesIter<'_, R> {
+    type Item = Result<Option<DecodedValue>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.next_buffered() {
+                Ok(Some(value)) => return Some(Ok(value)),
+                Ok(None) => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+            match self.fetch_block() {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl MdfIndex {
+    /// Iterate over the values of a channel, fetching one data block at a time.
+    ///
+    /// Unlike [`read_channel_values()`](Self::read_channel_values), memory use
+    /// does not grow with the number of records, so recordings with billions
+    /// of samples can be processed. VLSD channels are streamed from their
+    /// signal data blocks the same way.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::{FileRangeReader, MdfIndex};
+    ///
+    /// let index = MdfIndex::from_file_streaming("long_recording.mf4")?;
+    /// let mut reader = FileRangeReader::new("long_recording.mf4")?;
+    /// let mut sum = 0.0;
+    /// for value in index.iter_channel_values(0, 1, &mut reader)? {
+    ///     if let Some(v) = value?.and_then(|v| v.as_f64()) {
+    ///         sum += v;
+    ///     }
+    /// }
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    pub fn iter_channel_values<'a, R: ByteRangeReader<Error = Error>>(
+        &'a self,
+        group_index: usize,
+        channel_index: usize,
+        reader: &'a mut R,
+    ) -> Result<IndexedValuesIter<'a, R>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        let source = match channel.vlsd_data_address {
+            Some(addr) if channel.channel_type == 1 => {
+                if channel.vlsd_blocks.is_empty() && addr != 0 {
+                    Source::SignalData(Cow::Owned(Self::index_vlsd_blocks(addr, reader)?))
+                } else {
+                    Source::SignalData(Cow::Borrowed(&channel.vlsd_blocks))
+                }
+            }
+            _ => Source::Records(&group.data_blocks),
+        };
+
+        Ok(IndexedValuesIter {
+            group,
+            channel,
+            reader,
+            source,
+            next_block: 0,
+            buffer: BlockPayload::default(),
+            pos: 0,
+            done: false,
+        })
+    }
+}
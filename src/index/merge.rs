@@ -0,0 +1,338 @@
+//! Combined indexes of file sets and reading across files.
+
+use super::{
+    ByteRangeReader, DataBlockInfo, FileRangeReader, IndexedAttachment, IndexedChannel,
+    IndexedChannelGroup, IndexedEvent, MdfIndex,
+};
+use crate::{Error, Result};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Number of low offset bits holding the offset within a file; the file
+/// identifier is stored above them.
+const FILE_ID_SHIFT: u32 = 48;
+const FILE_OFFSET_MASK: u64 = (1 << FILE_ID_SHIFT) - 1;
+
+/// Offset of `offset` in file `file_id` in a merged index.
+fn virtual_offset(file_id: u16, offset: u64) -> Result<u64> {
+    if offset > FILE_OFFSET_MASK {
+        return Err(Error::BlockSerializationError(format!(
+            "File offset {} is too large for a merged index",
+            offset
+        )));
+    }
+    Ok(((file_id as u64) << FILE_ID_SHIFT) | offset)
+}
+
+impl DataBlockInfo {
+    /// Identifier of the file holding this block in a merged index (see
+    /// [`MdfIndex::merge()`]); 0 for indexes of a single file.
+    pub fn file_id(&self) -> u16 {
+        (self.file_offset >> FILE_ID_SHIFT) as u16
+    }
+}
+
+/// Check that the channels of `group` decode like those of `first`.
+fn check_same_layout(first: &IndexedChannelGroup, group: &IndexedChannelGroup) -> Result<()> {
+    let same_channel = |a: &IndexedChannel, b: &IndexedChannel| {
+        a.name == b.name
+            && a.data_type == b.data_type
+            && a.byte_offset == b.byte_offset
+            && a.bit_offset == b.bit_offset
+            && a.bit_count == b.bit_count
+            && a.channel_type == b.channel_type
+            && a.flags == b.flags
+            && a.pos_invalidation_bit == b.pos_invalidation_bit
+    };
+    let same = first.record_id_size == group.record_id_size
+        && first.record_size == group.record_size
+        && first.invalidation_bytes == group.invalidation_bytes
+        && first.record_id == group.record_id
+        && first.unsorted_records == group.unsorted_records
+        && first.channels.len() == group.channels.len()
+        && first
+            .channels
+            .iter()
+            .zip(&group.channels)
+            .all(|(a, b)| same_channel(a, b));
+    if same {
+        Ok(())
+    } else {
+        Err(Error::BlockSerializationError(format!(
+            "Channel group {:?} has a different layout in the merged files",
+            first.name
+        )))
+    }
+}
+
+impl MdfIndex {
+    /// Combine the indexes of a set of files into one virtual index.
+    ///
+    /// Measurement campaigns are often split into many sequential files
+    /// with the same channel layout. The merged index has the channel groups
+    /// of the first index, with the data blocks (and VLSD signal data
+    /// blocks) of all files appended in the given order, so reading a
+    /// channel returns the values of the whole campaign. Read it with a
+    /// [`MultiFileRangeReader`] that maps each of the `file_ids` to a reader
+    /// for the corresponding file.
+    ///
+    /// Block offsets of the merged index are virtual: the file identifier
+    /// is stored in the upper 16 bits (see [`DataBlockInfo::file_id()`]).
+    /// Statistics are merged if all indexes have them, and the attachments
+    /// and events of all files are listed; the merged index has no
+    /// fingerprint.
+    ///
+    /// # Errors
+    /// Returns an error if the number of indexes and file identifiers
+    /// differs, a file identifier is repeated, or the files do not have the
+    /// same channel groups and channel layouts.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use mdf4_rs::MdfIndex;
+    /// use mdf4_rs::index::MultiFileRangeReader;
+    ///
+    /// let files = ["part1.mf4", "part2.mf4", "part3.mf4"];
+    /// let indices = files
+    ///     .iter()
+    ///     .map(|f| MdfIndex::from_file_streaming(f))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let index = MdfIndex::merge(&indices, &[0, 1, 2])?;
+    /// let mut reader = MultiFileRangeReader::from_files(&files)?;
+    /// let speed = index.read_channel_values_by_name("Speed", &mut reader)?;
+    /// # Ok::<(), mdf4_rs::Error>(())
+    /// ```
+    pub fn merge(indices: &[MdfIndex], file_ids: &[u16]) -> Result<MdfIndex> {
+        if indices.len() != file_ids.len() {
+            return Err(Error::BlockSerializationError(format!(
+                "{} indexes but {} file identifiers",
+                indices.len(),
+                file_ids.len()
+            )));
+        }
+        for (i, id) in file_ids.iter().enumerate() {
+            if file_ids[..i].contains(id) {
+                return Err(Error::BlockSerializationError(format!(
+                    "File identifier {} is used twice",
+                    id
+                )));
+            }
+        }
+        let Some(first) = indices.first() else {
+            return Err(Error::BlockSerializationError(
+                "No indexes to merge".to_string(),
+            ));
+        };
+        if let Some(index) = indices
+            .iter()
+            .find(|index| index.channel_groups.len() != first.channel_groups.len())
+        {
+            return Err(Error::BlockSerializationError(format!(
+                "Cannot merge indexes with {} and {} channel groups",
+                first.channel_groups.len(),
+                index.channel_groups.len()
+            )));
+        }
+
+        let mut channel_groups = Vec::with_capacity(first.channel_groups.len());
+        for (g, first_group) in first.channel_groups.iter().enumerate() {
+            let mut merged = first_group.clone();
+            merged.record_count = 0;
+            merged.data_blocks.clear();
+            for channel in &mut merged.channels {
+                channel.vlsd_blocks.clear();
+                channel.block_stats.clear();
+            }
+            let mut with_stats = vec![true; merged.channels.len()];
+
+            for (index, &file_id) in indices.iter().zip(file_ids) {
+                let group = &index.channel_groups[g];
+                check_same_layout(first_group, group)?;
+                merged.record_count += group.record_count;
+                for block in &group.data_blocks {
+                    merged.data_blocks.push(DataBlockInfo {
+                        file_offset: virtual_offset(file_id, block.file_offset)?,
+                        ..block.clone()
+                    });
+                }
+
+                let channels = merged.channels.iter_mut().zip(&group.channels);
+                for ((channel, source), with_stats) in channels.zip(&mut with_stats) {
+                    if source.vlsd_data_address.is_some_and(|addr| addr != 0) {
+                        if source.vlsd_blocks.is_empty() {
+                            return Err(Error::BlockSerializationError(format!(
+                                "VLSD channel {:?} has no located signal data blocks",
+                                source.name
+                            )));
+                        }
+                        // The merged stream continues after the previous file's data
+                        let stream_offset = channel
+                            .vlsd_blocks
+                            .last()
+                            .map_or(0, |b| b.data_offset + b.size.saturating_sub(24));
+                        for block in &source.vlsd_blocks {
+                            let mut block = block.clone();
+                            block.file_offset = virtual_offset(file_id, block.file_offset)?;
+                            block.data_offset += stream_offset;
+                            channel.vlsd_blocks.push(block);
+                        }
+                        // Any non-zero address marks the channel as having signal data
+                        channel.vlsd_data_address = Some(channel.vlsd_blocks[0].file_offset);
+                    }
+
+                    let has_stats = source.block_stats.len() == group.data_blocks.len();
+                    *with_stats &= has_stats;
+                    if *with_stats {
+                        channel.block_stats.extend_from_slice(&source.block_stats);
+                    }
+                }
+            }
+
+            for (channel, with_stats) in merged.channels.iter_mut().zip(with_stats) {
+                if with_stats {
+                    channel.stats = channel
+                        .block_stats
+                        .iter()
+                        .flatten()
+                        .copied()
+                        .reduce(|a, b| a.merge(&b));
+                } else {
+                    channel.stats = None;
+                    channel.block_stats.clear();
+                }
+            }
+            channel_groups.push(merged);
+        }
+
+        // Attachments and events of all files, with references shifted to
+        // the combined lists
+        let mut attachments = Vec::new();
+        let mut events = Vec::new();
+        for (index, &file_id) in indices.iter().zip(file_ids) {
+            let (at_base, ev_base) = (attachments.len(), events.len());
+            for attachment in &index.attachments {
+                attachments.push(IndexedAttachment {
+                    file_offset: virtual_offset(file_id, attachment.file_offset)?,
+                    ..attachment.clone()
+                });
+            }
+            for event in &index.events {
+                events.push(IndexedEvent {
+                    file_offset: virtual_offset(file_id, event.file_offset)?,
+                    parent: event.parent.map(|i| i + ev_base),
+                    range_begin: event.range_begin.map(|i| i + ev_base),
+                    attachments: event.attachments.iter().map(|i| i + at_base).collect(),
+                    ..event.clone()
+                });
+            }
+        }
+
+        Ok(MdfIndex {
+            file_size: indices.iter().map(|index| index.file_size).sum(),
+            fingerprint: None,
+            channel_groups,
+            attachments,
+            events,
+        })
+    }
+}
+
+/// Byte range reader over a set of files, for indexes created by
+/// [`MdfIndex::merge()`].
+///
+/// Reads are dispatched to the reader of the file identified by the upper
+/// 16 bits of the offset, at the offset given by the lower 48 bits.
+pub struct MultiFileRangeReader<R> {
+    files: BTreeMap<u16, R>,
+}
+
+impl<R> Default for MultiFileRangeReader<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> MultiFileRangeReader<R> {
+    /// Create a reader without files.
+    pub fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Add the reader for a file identifier.
+    pub fn with_file(mut self, file_id: u16, reader: R) -> Self {
+        self.add_file(file_id, reader);
+        self
+    }
+
+    /// Add the reader for a file identifier, replacing any previous one.
+    pub fn add_file(&mut self, file_id: u16, reader: R) {
+        self.files.insert(file_id, reader);
+    }
+
+    /// The reader of a file identifier.
+    pub fn file(&self, file_id: u16) -> Option<&R> {
+        self.files.get(&file_id)
+    }
+
+    /// Number of files.
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+}
+
+impl MultiFileRangeReader<FileRangeReader> {
+    /// Open files with the identifiers 0, 1, 2, ... in the given order.
+    pub fn from_files(file_paths: &[&str]) -> Result<Self> {
+        let mut reader = Self::new();
+        for (file_id, path) in file_paths.iter().enumerate() {
+            let file_id = u16::try_from(file_id).map_err(|_| {
+                Error::BlockSerializationError("Too many files to merge".to_string())
+            })?;
+            reader.add_file(file_id, FileRangeReader::new(path)?);
+        }
+        Ok(reader)
+    }
+}
+
+impl<R: ByteRangeReader<Error = Error>> ByteRangeReader for MultiFileRangeReader<R> {
+    type Error = Error;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
+        let file_id = (offset >> FILE_ID_SHIFT) as u16;
+        let reader = self.files.get_mut(&file_id).ok_or_else(|| {
+            Error::BlockSerializationError(format!("No reader for file identifier {}", file_id))
+        })?;
+        reader.read_range(offset & FILE_OFFSET_MASK, length)
+    }
+
+    fn read_ranges(&mut self, ranges: &[(u64, u64)]) -> Result<Vec<Vec<u8>>> {
+        // Pass consecutive ranges of the same file on together, so the file
+        // readers can coalesce them
+        let mut results = Vec::with_capacity(ranges.len());
+        for run in ranges.chunk_by(|a, b| a.0 >> FILE_ID_SHIFT == b.0 >> FILE_ID_SHIFT) {
+            let file_id = (run[0].0 >> FILE_ID_SHIFT) as u16;
+            let reader = self.files.get_mut(&file_id).ok_or_else(|| {
+                Error::BlockSerializationError(format!("No reader for file identifier {}", file_id))
+            })?;
+            let local: Vec<_> = run
+                .iter()
+                .map(|&(offset, length)| (offset & FILE_OFFSET_MASK, length))
+                .collect();
+            results.extend(reader.read_ranges(&local)?);
+        }
+        Ok(results)
+    }
+
+    fn cached_block(&mut self, offset: u64) -> Option<Arc<[u8]>> {
+        let reader = self.files.get_mut(&((offset >> FILE_ID_SHIFT) as u16))?;
+        reader.cached_block(offset & FILE_OFFSET_MASK)
+    }
+
+    fn cache_block(&mut self, offset: u64, data: &Arc<[u8]>) {
+        if let Some(reader) = self.files.get_mut(&((offset >> FILE_ID_SHIFT) as u16)) {
+            reader.cache_block(offset & FILE_OFFSET_MASK, data);
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Concurrent prefetching of planned byte ranges.
+
+use super::{ByteRangeReader, FileRangeReader, MdfIndex};
+use crate::{Error, Result};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// A range fetch for a worker thread.
+struct Job {
+    offset: u64,
+    length: u64,
+    result: Sender<Result<Vec<u8>>>,
+}
+
+/// A prefetched range, possibly still being fetched.
+struct Entry {
+    length: u64,
+    state: EntryState,
+}
+
+enum EntryState {
+    Pending(Receiver<Result<Vec<u8>>>),
+    Ready(Vec<u8>),
+}
+
+impl Entry {
+    /// Wait until the data has been fetched.
+    fn wait(&mut self) -> Result<&[u8]> {
+        if let EntryState::Pending(rx) = &self.state {
+            let data = rx.recv().map_err(|_| worker_gone())??;
+            self.state = EntryState::Ready(data);
+        }
+        match &self.state {
+            EntryState::Ready(data) => Ok(data),
+            EntryState::Pending(_) => unreachable!(),
+        }
+    }
+}
+
+fn worker_gone() -> Error {
+    Error::BlockSerializationError("Prefetch worker thread stopped".to_string())
+}
+
+/// Byte range reader that fetches ranges concurrently on worker threads.
+///
+/// Give it the ranges that will be read next with
+/// [`prefetch()`](Self::prefetch), e.g. the plan from
+/// [`MdfIndex::get_channel_read_plan()`]. The ranges are fetched in the
+/// background while earlier data is decoded, and a read that lies within a
+/// prefetched range waits for it instead of issuing a new request. This
+/// hides most of the latency of HTTP or network file system sources.
+/// [`read_ranges()`](ByteRangeReader::read_ranges) also fetches all of its
+/// ranges concurrently.
+///
+/// Each worker thread owns one reader, so the workers can fetch
+/// independently. A prefetched range is released once it has been read up
+/// to its end, or once a read from a later prefetched range has moved past
+/// it, whether it was read in part or not at all.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::MdfIndex;
+/// use mdf4_rs::index::PrefetchingRangeReader;
+///
+/// let index = MdfIndex::from_file_streaming("recording.mf4")?;
+/// let mut reader = PrefetchingRangeReader::from_file("recording.mf4", 4)?;
+/// reader.prefetch(&index.get_channel_read_plan(0, 1)?);
+/// let values = index.read_channel_values(0, 1, &mut reader)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+pub struct PrefetchingRangeReader {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+    /// Prefetched ranges by offset
+    entries: BTreeMap<u64, Entry>,
+    hits: u64,
+    misses: u64,
+}
+
+impl PrefetchingRangeReader {
+    /// Fetch with one worker thread per reader.
+    ///
+    /// All readers must read the same source.
+    ///
+    /// # Errors
+    /// Returns an error if `readers` is empty or a thread cannot be spawned.
+    pub fn new<R>(readers: Vec<R>) -> Result<Self>
+    where
+        R: ByteRangeReader<Error = Error> + Send + 'static,
+    {
+        if readers.is_empty() {
+            return Err(Error::BlockSerializationError(
+                "PrefetchingRangeReader needs at least one reader".to_string(),
+            ));
+        }
+
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        let mut workers = Vec::with_capacity(readers.len());
+        for (i, mut reader) in readers.into_iter().enumerate() {
+            let queue = Arc::clone(&queue);
+            let worker = std::thread::Builder::new()
+                .name(format!("mdf-prefetch-{}", i))
+                .spawn(move || {
+                    loop {
+                        // Hold the lock only while taking a job
+                        let job = match queue.lock() {
+                            Ok(queue) => queue.recv(),
+                            Err(_) => return,
+                        };
+                        let Ok(job) = job else {
+                            return;
+                        };
+                        let _ = job.result.send(reader.read_range(job.offset, job.length));
+                    }
+                })
+                .map_err(Error::IOError)?;
+            workers.push(worker);
+        }
+
+        Ok(Self {
+            jobs: Some(jobs),
+            workers,
+            entries: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    /// Fetch from a file with `threads` worker threads.
+    pub fn from_file(file_path: &str, threads: usize) -> Result<Self> {
+        let readers = (0..threads.max(1))
+            .map(|_| FileRangeReader::new(file_path))
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(readers)
+    }
+
+    /// Start fetching ranges that will be read soon.
+    ///
+    /// Ranges that are already prefetched (at the same offset) are skipped.
+    pub fn prefetch(&mut self, ranges: &[(u64, u64)]) {
+        for &(offset, length) in ranges {
+            if length == 0 || self.entries.contains_key(&offset) {
+                continue;
+            }
+            let state = EntryState::Pending(self.submit(offset, length));
+            self.entries.insert(offset, Entry { length, state });
+        }
+    }
+
+    /// Number of ranges prefetched and not yet read to their end.
+    pub fn prefetched_ranges(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Number of reads served from prefetched ranges.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of reads that had to be fetched on demand.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Drop all prefetched ranges. Fetches in progress still complete.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn submit(&self, offset: u64, length: u64) -> Receiver<Result<Vec<u8>>> {
+        let (result, rx) = mpsc::channel();
+        if let Some(jobs) = &self.jobs {
+            // If all workers stopped, the dropped sender is reported on recv
+            let _ = jobs.send(Job {
+                offset,
+                length,
+                result,
+            });
+        }
+        rx
+    }
+
+    /// Offset of the prefetched range containing `offset..offset + length`.
+    fn find_entry(&self, offset: u64, length: u64) -> Option<u64> {
+        let (&start, entry) = self.entries.range(..=offset).next_back()?;
+        (offset + length <= start + entry.length).then_some(start)
+    }
+
+    /// Serve a read from the prefetched range at `start`.
+    fn read_prefetched(&mut self, start: u64, offset: u64, length: u64) -> Result<Vec<u8>> {
+        self.hits += 1;
+        let entry = self.entries.get_mut(&start).expect("prefetched range");
+        let end = offset + length;
+        let entry_end = start + entry.length;
+        let result = entry.wait().and_then(|data| {
+            data.get((offset - start) as usize..(end - start) as usize)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| Error::TooShortBuffer {
+                    actual: data.len(),
+                    expected: (end - start) as usize,
+                    file: file!(),
+                    line: line!(),
+                })
+        });
+        // Release ranges that have been read to their end or failed
+        if result.is_err() || end == entry_end {
+            self.entries.remove(&start);
+        }
+        // Release earlier ranges the reads have moved past
+        let passed: Vec<u64> = self
+            .entries
+            .range(..start)
+            .filter(|&(&s, entry)| s + entry.length <= offset)
+            .map(|(&s, _)| s)
+            .collect();
+        for s in passed {
+            self.entries.remove(&s);
+        }
+        result
+    }
+}
+
+impl ByteRangeReader for PrefetchingRangeReader {
+    type Error = Error;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
+        if let Some(start) = self.find_entry(offset, length) {
+            return self.read_prefetched(start, offset, length);
+        }
+        self.misses += 1;
+        self.submit(offset, length)
+            .recv()
+            .map_err(|_| worker_gone())?
+    }
+
+    fn read_ranges(&mut self, ranges: &[(u64, u64)]) -> Result<Vec<Vec<u8>>> {
+        // Start all missing fetches before waiting for any of them
+        let pending: Vec<_> = ranges
+            .iter()
+            .map(|&(offset, length)| match self.find_entry(offset, length) {
+                Some(_) => None,
+                None => Some(self.submit(offset, length)),
+            })
+            .collect();
+
+        ranges
+            .iter()
+            .zip(pending)
+            .map(|(&(offset, length), rx)| match rx {
+                Some(rx) => {
+                    self.misses += 1;
+                    rx.recv().map_err(|_| worker_gone())?
+                }
+                None => match self.find_entry(offset, length) {
+                    Some(start) => self.read_prefetched(start, offset, length),
+                    None => self.read_range(offset, length),
+                },
+            })
+            .collect()
+    }
+}
+
+impl Drop for PrefetchingRangeReader {
+    fn drop(&mut self) {
+        // Closing the queue stops the workers once their current job is done
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl MdfIndex {
+    /// Byte ranges that [`read_channel_values()`](Self::read_channel_values)
+    /// requests for a channel, in order.
+    ///
+    /// Unlike [`get_channel_byte_ranges()`](Self::get_channel_byte_ranges),
+    /// which returns the bytes holding the channel's values, these are the
+    /// exact reads made by the indexed read methods: whole data block
+    /// payloads (and whole compressed blocks), or the signal data of VLSD
+    /// channels. Pass them to [`PrefetchingRangeReader::prefetch()`].
+    ///
+    /// For VLSD channels of indexes without located signal data blocks the
+    /// plan is empty.
+    pub fn get_channel_read_plan(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+    ) -> Result<Vec<(u64, u64)>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
+            return Ok(channel
+                .vlsd_blocks
+                .iter()
+                .filter(|block| block.size > 24)
+                .map(|block| (block.file_offset + 24, block.size - 24))
+                .collect());
+        }
+
+        Ok(group
+            .data_blocks
+            .iter()
+            .map(|block| match block.is_compressed {
+                true => (block.file_offset, block.size),
+                false => (block.file_offset + 24, block.size - 24),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader over an in-memory buffer
+    #[derive(Clone)]
+    struct MemoryReader(Arc<Vec<u8>>);
+
+    impl ByteRangeReader for MemoryReader {
+        type Error = Error;
+
+        fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
+            self.0
+                .get(offset as usize..(offset + length) as usize)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| Error::BlockSerializationError("out of range".to_string()))
+        }
+    }
+
+    fn reader(threads: usize) -> PrefetchingRangeReader {
+        let data = Arc::new((0..=255u8).cycle().take(4096).collect::<Vec<_>>());
+        PrefetchingRangeReader::new(vec![MemoryReader(data); threads]).unwrap()
+    }
+
+    #[test]
+    fn serves_reads_within_prefetched_ranges() {
+        let mut reader = reader(3);
+        reader.prefetch(&[(0, 1024), (2048, 1024)]);
+        assert_eq!(reader.prefetched_ranges(), 2);
+
+        assert_eq!(reader.read_range(10, 4).unwrap(), vec![10, 11, 12, 13]);
+        assert_eq!(reader.read_range(1000, 24).unwrap().len(), 24);
+        // Read to its end, so the first range is released
+        assert_eq!(reader.prefetched_ranges(), 1);
+        assert_eq!(
+            reader.read_range(1000, 4).unwrap(),
+            vec![232, 233, 234, 235]
+        );
+        assert_eq!(reader.hits(), 2);
+        assert_eq!(reader.misses(), 1);
+
+        let ranges = reader
+            .read_ranges(&[(2048, 512), (100, 2), (2560, 512)])
+            .unwrap();
+        assert_eq!(ranges[1], vec![100, 101]);
+        assert_eq!(ranges[2][0], 0);
+        assert_eq!(reader.prefetched_ranges(), 0);
+    }
+
+    #[test]
+    fn releases_ranges_that_reads_moved_past() {
+        let mut reader = reader(2);
+        reader.prefetch(&[(0, 512), (512, 512), (1024, 512), (2048, 512)]);
+        assert_eq!(reader.read_range(0, 16).unwrap().len(), 16);
+        assert_eq!(reader.prefetched_ranges(), 4);
+
+        // Skips the rest of the first range and all of the second
+        assert_eq!(reader.read_range(1024, 16).unwrap()[0], 0);
+        assert_eq!(reader.prefetched_ranges(), 2);
+        // Reads outside of prefetched ranges release nothing
+        assert_eq!(reader.read_range(4000, 4).unwrap().len(), 4);
+        assert_eq!(reader.prefetched_ranges(), 2);
+        assert_eq!(reader.read_range(2048, 16).unwrap().len(), 16);
+        assert_eq!(reader.prefetched_ranges(), 1);
+        assert_eq!(reader.misses(), 1);
+    }
+
+    #[test]
+    fn reports_fetch_errors() {
+        let mut reader = reader(1);
+        reader.prefetch(&[(4000, 200)]);
+        assert!(reader.read_range(4000, 10).is_err());
+        assert_eq!(reader.prefetched_ranges(), 0);
+        assert!(reader.read_ranges(&[(0, 1), (5000, 1)]).is_err());
+        assert!(PrefetchingRangeReader::new(Vec::<MemoryReader>::new()).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Per-channel and per-block value statistics.
+//!
+//! Statistics are stored in the index, so dashboards can show value ranges
+//! and readers can skip data blocks that cannot contain interesting values
+//! (block skipping) without touching the measurement data.
+
+use super::{ByteRangeReader, IndexedChannel, IndexedChannelGroup, MdfIndex};
+use crate::{Error, Result, blocks::DataType, parsing::decoder::DecodedValue};
+
+/// Range and number of the valid numeric values of a channel.
+///
+/// Values are physical values, i.e. after applying the channel conversion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelStats {
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+    /// Number of valid values
+    pub count: u64,
+}
+
+impl ChannelStats {
+    fn from_value(value: f64) -> Self {
+        Self {
+            min: value,
+            max: value,
+            count: 1,
+        }
+    }
+
+    /// Combine the statistics of two sets of values.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            count: self.count + other.count,
+        }
+    }
+
+    /// Returns `true` if some value may lie within `[min, max]`.
+    pub fn overlaps(&self, min: f64, max: f64) -> bool {
+        self.min <= max && self.max >= min
+    }
+}
+
+/// Merge optional statistics, treating `None` as no values.
+fn merge_stats(a: Option<ChannelStats>, b: Option<ChannelStats>) -> Option<ChannelStats> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.merge(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Whether statistics are computed for `channel`.
+fn has_numeric_values(channel: &IndexedChannel) -> bool {
+    channel.channel_type != 1
+        && matches!(
+            channel.data_type,
+            DataType::UnsignedIntegerLE
+                | DataType::UnsignedIntegerBE
+                | DataType::SignedIntegerLE
+                | DataType::SignedIntegerBE
+                | DataType::FloatLE
+                | DataType::FloatBE
+        )
+}
+
+impl MdfIndex {
+    /// Create an index with streaming reads and compute value statistics.
+    ///
+    /// Equivalent to [`from_file_streaming()`](Self::from_file_streaming)
+    /// followed by [`compute_statistics()`](Self::compute_statistics). This
+    /// reads all record data once, so it takes as long as reading every
+    /// channel, but the statistics are then available from the saved index.
+    pub fn from_file_streaming_with_stats(file_path: &str) -> Result<Self> {
+        let mut index = Self::from_file_streaming(file_path)?;
+        let mut reader = super::BufferedRangeReader::new(file_path)?;
+        index.compute_statistics(&mut reader)?;
+        Ok(index)
+    }
+
+    /// Compute min/max/count of every numeric channel, overall and per data
+    /// block, and store them in [`IndexedChannel::stats`] and
+    /// [`IndexedChannel::block_stats`].
+    ///
+    /// Each data block is read once. Channels with non-numeric values
+    /// (strings, byte arrays, VLSD and text conversions) get no statistics.
+    pub fn compute_statistics<R: ByteRangeReader<Error = Error>>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<()> {
+        for group in &mut self.channel_groups {
+            Self::compute_group_statistics(group, reader)?;
+        }
+        Ok(())
+    }
+
+    fn compute_group_statistics<R: ByteRangeReader<Error = Error>>(
+        group: &mut IndexedChannelGroup,
+        reader: &mut R,
+    ) -> Result<()> {
+        Self::update_group_statistics(group, reader, 0)
+    }
+
+    /// Recompute the statistics of a group, reusing the block statistics of
+    /// its first `keep` data blocks, e.g. the blocks that were complete
+    /// before [`refresh()`](Self::refresh). All blocks are read if some
+    /// channel has no statistics for them.
+    pub(super) fn update_group_statistics<R: ByteRangeReader<Error = Error>>(
+        group: &mut IndexedChannelGroup,
+        reader: &mut R,
+        keep: usize,
+    ) -> Result<()> {
+        let numeric: Vec<usize> = (0..group.channels.len())
+            .filter(|&i| has_numeric_values(&group.channels[i]))
+            .collect();
+        let keep = if numeric
+            .iter()
+            .all(|&ch| group.channels[ch].block_stats.len() >= keep)
+        {
+            keep.min(group.data_blocks.len())
+        } else {
+            0
+        };
+        let mut block_stats: Vec<Vec<Option<ChannelStats>>> = numeric
+            .iter()
+            .map(|&ch| {
+                let mut stats = Vec::with_capacity(group.data_blocks.len());
+                stats.extend_from_slice(&group.channels[ch].block_stats[..keep]);
+                stats
+            })
+            .collect();
+
+        let record_size = Self::record_size(group);
+        if !numeric.is_empty() && record_size > 0 {
+            for data_block in group.data_blocks.iter().skip(keep) {
+                let block_data = Self::read_block_records(group, data_block, reader)?;
+                let mut stats: Vec<Option<ChannelStats>> = vec![None; numeric.len()];
+                for record in block_data.chunks_exact(record_size) {
+                    for (stat, &ch) in stats.iter_mut().zip(&numeric) {
+                        let value = Self::decode_record_value(group, &group.channels[ch], record)?;
+                        let value = value.as_ref().and_then(DecodedValue::as_f64);
+                        if let Some(v) = value.filter(|v| !v.is_nan()) {
+                            *stat = merge_stats(*stat, Some(ChannelStats::from_value(v)));
+                        }
+                    }
+                }
+                for (column, stat) in block_stats.iter_mut().zip(stats) {
+                    column.push(stat);
+                }
+            }
+        }
+
+        for channel in &mut group.channels {
+            channel.stats = None;
+            channel.block_stats = Vec::new();
+        }
+        for (&ch, stats) in numeric.iter().zip(block_stats) {
+            let channel = &mut group.channels[ch];
+            channel.stats = stats.iter().copied().fold(None, merge_stats);
+            channel.block_stats = stats;
+        }
+        Ok(())
+    }
+
+    /// Indices of the data blocks of a group that may hold values of a
+    /// channel within `[min, max]`.
+    ///
+    /// Blocks whose statistics show they cannot contain such values are
+    /// left out; without statistics all blocks are returned. Read the
+    /// remaining blocks with
+    /// [`read_channel_values_in_blocks()`](Self::read_channel_values_in_blocks).
+    pub fn blocks_in_value_range(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        min: f64,
+        max: f64,
+    ) -> Result<Vec<usize>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        if channel.block_stats.len() != group.data_blocks.len() {
+            return Ok((0..group.data_blocks.len()).collect());
+        }
+        Ok(channel
+            .block_stats
+            .iter()
+            .enumerate()
+            .filter(|(_, stats)| stats.is_some_and(|s| s.overlaps(min, max)))
+            .map(|(i, _)| i)
+            .collect())
+    }
+
+    /// Read the values of a channel from selected data blocks of its group.
+    ///
+    /// `block_indices` refer to [`IndexedChannelGroup::data_blocks`]; values
+    /// are returned block by block in the given order.
+    pub fn read_channel_values_in_blocks<R: ByteRangeReader<Error = Error>>(
+        &self,
+        group_index: usize,
+        channel_index: usize,
+        block_indices: &[usize],
+        reader: &mut R,
+    ) -> Result<Vec<Option<DecodedValue>>> {
+        let group = self
+            .channel_groups
+            .get(group_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid group index".to_string()))?;
+
+        let channel = group
+            .channels
+            .get(channel_index)
+            .ok_or_else(|| Error::BlockSerializationError("Invalid channel index".to_string()))?;
+
+        if channel.channel_type == 1 && channel.vlsd_data_address.is_some() {
+            return Err(Error::BlockSerializationError(
+                "VLSD channels cannot be read by data block".to_string(),
+            ));
+        }
+
+        let record_size = Self::record_size(group);
+        let mut values = Vec::new();
+        for &block_index in block_indices {
+            let data_block = group.data_blocks.get(block_index).ok_or_else(|| {
+                Error::BlockSerializationError("Invalid data block index".to_string())
+            })?;
+            let block_data = Self::read_block_records(group, data_block, reader)?;
+            for record in block_data.chunks_exact(record_size) {
+                values.push(Self::decode_record_value(group, channel, record)?);
+            }
+        }
+        Ok(values)
+    }
+}
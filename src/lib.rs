@@ -18,6 +18,10 @@
 //! - **Indexing** (std only): Generate lightweight JSON indexes
 //! - **Cutting** (std only): Extract time-based segments from recordings
 //! - **Merging** (std only): Combine multiple MDF files
+//! - **Splitting** (std only): Split recordings by time, records or size
+//! - **Transcoding** (std only): Rewrite files sorted, slimmed or compressed
+//! - **Export** (std only): Write channel groups to NDJSON, Parquet, MATLAB and Excel files, and GPS tracks to GPX and KML
+//! - **Reports** (std only): Summaries of groups, channels, value ranges, attachments and events
 //! - **Bus Logging**: ASAM-compliant logging for CAN, Ethernet, LIN, and FlexRay
 //!
 //! ## Feature Flags
@@ -29,6 +33,12 @@
 //! | `can` | Yes | CAN bus support via `embedded-can` crate. |
 //! | `dbc` | Yes | DBC file decoding via `dbc-rs` crate. |
 //! | `compression` | No | DZ block decompression via `miniz_oxide`. |
+//! | `async` | No | [`writer::AsyncMdfWriter`] for tokio `AsyncWrite + AsyncSeek` destinations. |
+//! | `object-store` | No | [`index::ObjectStoreRangeReader`] for indexed reads from S3-compatible storage. |
+//! | `arrow` | No | [`MdfIndex::arrow_schema`] and [`MdfIndex::arrow_reader`] for Apache Arrow schemas and record batches of channel groups. |
+//! | `parquet` | No | `export::parquet` for one Parquet file per channel group. |
+//! | `matlab` | No | `export::matlab` for MATLAB v7.3 MAT-files via the HDF5 library. |
+//! | `xlsx` | No | `export::xlsx` for Excel workbooks with one worksheet per channel group. |
 //!
 //! ## no_std Usage
 //!
@@ -170,8 +180,12 @@
 //! | [`flexray`] | FlexRay bus logging | `alloc` |
 //! | [`parsing`] | File parsing utilities | `std` |
 //! | [`index`] | File indexing | `std` |
-//! | [`cut`] | Time-based segment extraction | `std` |
+//! | [`cut`] | Segment extraction by time, records or condition | `std` |
 //! | [`merge`] | File merging utilities | `std` |
+//! | [`split`] | File splitting by time, records or size | `std` |
+//! | [`transcode`] | Rewriting with a normalized layout | `std` |
+//! | [`export`] | Export to other file formats | `std` |
+//! | [`report`] | Summary reports for data catalogs | `std` |
 //! | [`error`] | Error types and [`Result`] alias | `alloc` |
 //!
 //! ## Error Handling
@@ -223,6 +237,8 @@ mod channel_group;
 #[cfg(feature = "std")]
 pub mod cut;
 #[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
 pub mod index;
 #[cfg(feature = "std")]
 mod mdf;
@@ -230,6 +246,12 @@ mod mdf;
 pub mod merge;
 #[cfg(feature = "std")]
 pub mod parsing;
+#[cfg(feature = "std")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod split;
+#[cfg(feature = "std")]
+pub mod transcode;
 
 // Re-export commonly used types at the crate root
 #[cfg(feature = "alloc")]
@@ -241,17 +263,27 @@ pub use types::DecodedValue;
 #[cfg(feature = "alloc")]
 pub use writer::MdfWriter;
 #[cfg(feature = "alloc")]
-pub use writer::{FlushPolicy, StreamingConfig};
+pub use writer::{BufferLimit, FlushPolicy, StreamingConfig};
 
 #[cfg(feature = "std")]
 pub use channel::{Channel, ChannelValuesIter};
 #[cfg(feature = "std")]
 pub use channel_group::ChannelGroup;
 #[cfg(feature = "std")]
-pub use cut::cut_mdf_by_time;
+pub use cut::{
+    CutOptions, cut_mdf_by_records, cut_mdf_by_time, cut_mdf_by_times, cut_mdf_by_times_to_files,
+    cut_mdf_to_writer, cut_mdf_where, cut_mdf_with_options,
+};
 #[cfg(feature = "std")]
 pub use index::{BufferedRangeReader, ByteRangeReader, FileRangeReader, MdfIndex};
 #[cfg(feature = "std")]
 pub use mdf::MDF;
 #[cfg(feature = "std")]
-pub use merge::merge_files;
+pub use merge::{
+    Deduplication, Interpolation, MergeInput, MergeMode, MergeOptions, NameConflict, TimeAlignment,
+    concatenate, merge_files, merge_files_with_options, merge_resampled, merge_to_writer,
+};
+#[cfg(feature = "std")]
+pub use split::{SplitPolicy, split_mdf};
+#[cfg(feature = "std")]
+pub use transcode::{TranscodeOptions, transcode};
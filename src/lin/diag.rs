@@ -0,0 +1,412 @@
+//! LIN transport layer (ISO 17987-2) reassembly.
+//!
+//! Diagnostic requests and responses, e.g. UDS over LIN, are sent in the
+//! master request frame (ID 0x3C) and the slave response frame (ID 0x3D).
+//! Payloads longer than 6 bytes are split into a first frame and
+//! consecutive frames. [`LinTpReassembler`] turns the raw frames back into
+//! complete payloads; [`RawLinLogger`](super::RawLinLogger) uses it to log
+//! them into a `LIN_Diag` channel group.
+//!
+//! Every diagnostic frame carries 8 bytes: the node address (NAD), the
+//! protocol control information (PCI) and up to 6 data bytes. Master
+//! request frames with a NAD of 0 are go-to-sleep commands and are skipped.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::lin::LinTpReassembler;
+//!
+//! let mut tp = LinTpReassembler::new();
+//! for (timestamp_us, id, data) in frames {
+//!     if let Some(message) = tp.process(id, timestamp_us, &data) {
+//!         println!("NAD 0x{:02X}: {:02X?}", message.nad, message.data);
+//!     }
+//! }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::bus_logging::timestamp_to_seconds;
+
+/// Frame ID of the master request frame.
+pub const MASTER_REQUEST_ID: u8 = 0x3C;
+
+/// Frame ID of the slave response frame.
+pub const SLAVE_RESPONSE_ID: u8 = 0x3D;
+
+/// Default time allowed between two frames of a transfer (N_Cr of
+/// ISO 17987-2).
+pub const DEFAULT_LIN_TP_TIMEOUT_US: u64 = 1_000_000;
+
+/// NAD of the go-to-sleep command.
+const NAD_SLEEP: u8 = 0x00;
+
+/// Protocol control information types (high nibble of the PCI byte).
+const PCI_SINGLE_FRAME: u8 = 0;
+const PCI_FIRST_FRAME: u8 = 1;
+const PCI_CONSECUTIVE_FRAME: u8 = 2;
+
+/// A complete LIN TP payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinTpMessage {
+    /// Timestamp in microseconds of the last frame of the message
+    pub timestamp_us: u64,
+    /// Frame ID: [`MASTER_REQUEST_ID`] or [`SLAVE_RESPONSE_ID`]
+    pub id: u8,
+    /// Node address of the slave
+    pub nad: u8,
+    /// Reassembled payload
+    pub data: Vec<u8>,
+}
+
+impl LinTpMessage {
+    /// Service ID: the first payload byte.
+    pub fn service_id(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Returns `true` if the payload was sent in a slave response frame.
+    pub fn is_response(&self) -> bool {
+        self.id == SLAVE_RESPONSE_ID
+    }
+}
+
+/// A transfer in progress.
+struct Transfer {
+    size: usize,
+    next_sequence: u8,
+    last_timestamp_us: u64,
+    data: Vec<u8>,
+}
+
+/// Reassembles LIN TP transfers from raw LIN frames.
+///
+/// Feed every master request and slave response frame to
+/// [`process()`](Self::process); other frame IDs are ignored. Single
+/// frames are returned right away, segmented transfers once their last
+/// consecutive frame arrives. Each frame ID and NAD carries at most one
+/// transfer at a time. A transfer is dropped when a consecutive frame is
+/// out of sequence or later than the timeout, or when a new first or single
+/// frame interrupts it.
+pub struct LinTpReassembler {
+    /// Transfers keyed by (frame ID, NAD)
+    transfers: BTreeMap<(u8, u8), Transfer>,
+    timeout_us: u64,
+    dropped: usize,
+}
+
+impl Default for LinTpReassembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinTpReassembler {
+    /// Create a reassembler with the default timeout of 1 s.
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_LIN_TP_TIMEOUT_US)
+    }
+
+    /// Create a reassembler that drops transfers whose frames are more than
+    /// `timeout_us` microseconds apart.
+    pub fn with_timeout(timeout_us: u64) -> Self {
+        Self {
+            transfers: BTreeMap::new(),
+            timeout_us,
+            dropped: 0,
+        }
+    }
+
+    /// Number of transfers in progress.
+    pub fn pending_transfers(&self) -> usize {
+        self.transfers.len()
+    }
+
+    /// Number of transfers dropped as incomplete.
+    pub fn dropped_transfers(&self) -> usize {
+        self.dropped
+    }
+
+    /// Process a LIN frame.
+    ///
+    /// # Returns
+    /// The payload this frame completes, if any.
+    pub fn process(&mut self, id: u8, timestamp_us: u64, data: &[u8]) -> Option<LinTpMessage> {
+        if id != MASTER_REQUEST_ID && id != SLAVE_RESPONSE_ID {
+            return None;
+        }
+        let nad = *data.first()?;
+        if id == MASTER_REQUEST_ID && nad == NAD_SLEEP {
+            return None;
+        }
+        let pci = *data.get(1)?;
+        let key = (id, nad);
+        match pci >> 4 {
+            PCI_SINGLE_FRAME => {
+                self.interrupt(key);
+                let len = (pci & 0x0F) as usize;
+                let payload = data.get(2..2 + len)?;
+                Some(LinTpMessage {
+                    timestamp_us,
+                    id,
+                    nad,
+                    data: payload.to_vec(),
+                })
+            }
+            PCI_FIRST_FRAME => {
+                self.interrupt(key);
+                let size = (((pci & 0x0F) as usize) << 8) | *data.get(2)? as usize;
+                let mut transfer = Transfer {
+                    size,
+                    next_sequence: 1,
+                    last_timestamp_us: timestamp_us,
+                    data: Vec::with_capacity(size),
+                };
+                transfer.data.extend_from_slice(&data[3.min(data.len())..]);
+                self.transfers.insert(key, transfer);
+                None
+            }
+            PCI_CONSECUTIVE_FRAME => {
+                let bytes = data.get(2..)?;
+                let transfer = self.transfers.get_mut(&key)?;
+                let in_time =
+                    timestamp_us.saturating_sub(transfer.last_timestamp_us) <= self.timeout_us;
+                if pci & 0x0F != transfer.next_sequence || !in_time {
+                    self.transfers.remove(&key);
+                    self.dropped += 1;
+                    return None;
+                }
+                transfer.data.extend_from_slice(bytes);
+                transfer.next_sequence = (transfer.next_sequence + 1) & 0x0F;
+                transfer.last_timestamp_us = timestamp_us;
+                if transfer.data.len() < transfer.size {
+                    return None;
+                }
+                let mut transfer = self.transfers.remove(&key)?;
+                transfer.data.truncate(transfer.size);
+                Some(LinTpMessage {
+                    timestamp_us,
+                    id,
+                    nad,
+                    data: transfer.data,
+                })
+            }
+            // Reserved PCI types
+            _ => None,
+        }
+    }
+
+    /// Drop the transfer of `key`, if any, because a new one starts.
+    fn interrupt(&mut self, key: (u8, u8)) {
+        if self.transfers.remove(&key).is_some() {
+            self.dropped += 1;
+        }
+    }
+}
+
+/// Buffered payloads of the `{source_name}_LIN_Diag` channel group.
+///
+/// The channel group holds the channels:
+/// - `Timestamp` - Float64 seconds (master)
+/// - `ID` - UInt8 frame ID (0x3C for requests, 0x3D for responses)
+/// - `NAD` - UInt8 node address
+/// - `ServiceID` - UInt8 first payload byte
+/// - `LIN_Diag` - complete payload as variable length ByteArray (VLSD)
+///
+/// It is created by the first flush with buffered payloads.
+pub(super) struct DiagGroup {
+    pub(super) reassembler: LinTpReassembler,
+    messages: Vec<LinTpMessage>,
+    /// Payloads logged, including payloads already flushed
+    pub(super) count: usize,
+    /// Channel group ID
+    channel_group: Option<String>,
+}
+
+impl DiagGroup {
+    pub(super) fn new() -> Self {
+        Self {
+            reassembler: LinTpReassembler::new(),
+            messages: Vec::new(),
+            count: 0,
+            channel_group: None,
+        }
+    }
+
+    /// Feed a frame to the reassembler and buffer the payload it completes.
+    pub(super) fn process(&mut self, id: u8, timestamp_us: u64, data: &[u8]) {
+        match self.reassembler.process(id, timestamp_us, data) {
+            Some(message) if !message.data.is_empty() => {
+                self.messages.push(message);
+                self.count += 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// Number of payloads buffered.
+    pub(super) fn buffered(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Write and clear the buffered payloads, creating the channel group on
+    /// first use.
+    pub(super) fn write<W: crate::writer::MdfWrite>(
+        &mut self,
+        writer: &mut crate::MdfWriter<W>,
+        source_name: &str,
+    ) -> crate::Result<()> {
+        use crate::DecodedValue;
+
+        if self.messages.is_empty() {
+            return Ok(());
+        }
+        let cg = match &self.channel_group {
+            Some(cg) => cg,
+            None => self
+                .channel_group
+                .insert(init_diag_group(writer, source_name)?),
+        };
+
+        writer.start_data_block_for_cg(cg, 0)?;
+        for message in self.messages.drain(..) {
+            let values = [
+                DecodedValue::Float(timestamp_to_seconds(message.timestamp_us)),
+                DecodedValue::UnsignedInteger(message.id as u64),
+                DecodedValue::UnsignedInteger(message.nad as u64),
+                DecodedValue::UnsignedInteger(message.service_id().unwrap_or(0) as u64),
+                DecodedValue::ByteArray(message.data),
+            ];
+            writer.write_record(cg, &values)?;
+        }
+        writer.finish_data_block(cg)
+    }
+}
+
+/// Create the `{source_name}_LIN_Diag` channel group.
+fn init_diag_group<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    source_name: &str,
+) -> crate::Result<String> {
+    use crate::DataType;
+
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, &alloc::format!("{}_LIN_Diag", source_name))?;
+    let source = crate::blocks::SourceBlock::lin_bus();
+    writer.set_channel_group_source(&cg, &source, Some(source_name))?;
+
+    let time_ch = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some(String::from("Timestamp"));
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_ch)?;
+    writer.set_channel_unit(&time_ch, "s")?;
+    let mut prev = time_ch;
+    for name in ["ID", "NAD", "ServiceID"] {
+        prev = writer.add_channel(&cg, Some(&prev), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(String::from(name));
+            ch.bit_count = 8;
+        })?;
+    }
+    writer.add_vlsd_channel(&cg, Some(&prev), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some(String::from("LIN_Diag"));
+    })?;
+    Ok(cg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_frames() {
+        let mut tp = LinTpReassembler::new();
+        // ReadDataByIdentifier 0xF190 to NAD 0x0A
+        let message = tp
+            .process(
+                MASTER_REQUEST_ID,
+                100,
+                &[0x0A, 0x03, 0x22, 0xF1, 0x90, 0xFF, 0xFF, 0xFF],
+            )
+            .unwrap();
+        assert_eq!(message.nad, 0x0A);
+        assert_eq!(message.data, [0x22, 0xF1, 0x90]);
+        assert_eq!(message.service_id(), Some(0x22));
+        assert!(!message.is_response());
+
+        let message = tp
+            .process(
+                SLAVE_RESPONSE_ID,
+                200,
+                &[0x0A, 0x03, 0x7F, 0x22, 0x31, 0xFF, 0xFF, 0xFF],
+            )
+            .unwrap();
+        assert!(message.is_response());
+
+        // Go-to-sleep command and other frame IDs
+        assert!(
+            tp.process(
+                MASTER_REQUEST_ID,
+                300,
+                &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+            )
+            .is_none()
+        );
+        assert!(
+            tp.process(0x20, 400, &[0x0A, 0x03, 0x22, 0xF1, 0x90, 0, 0, 0])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_segmented_transfer() {
+        let mut tp = LinTpReassembler::new();
+        // 14-byte response: FF with 5 bytes, then 2 CFs with 6 bytes each
+        let ff = [0x0A, 0x10, 14, 0x62, 0xF1, 0x90, b'W', b'D'];
+        assert!(tp.process(SLAVE_RESPONSE_ID, 1000, &ff).is_none());
+        let cf1 = [0x0A, 0x21, b'B', b'1', b'2', b'3', b'4', b'5'];
+        assert!(tp.process(SLAVE_RESPONSE_ID, 11_000, &cf1).is_none());
+        assert_eq!(tp.pending_transfers(), 1);
+        let cf2 = [0x0A, 0x22, b'6', b'7', b'8', 0xFF, 0xFF, 0xFF];
+        let message = tp.process(SLAVE_RESPONSE_ID, 21_000, &cf2).unwrap();
+
+        assert_eq!(message.timestamp_us, 21_000);
+        assert_eq!(&message.data[..3], [0x62, 0xF1, 0x90]);
+        assert_eq!(&message.data[3..], b"WDB12345678");
+        assert_eq!(tp.pending_transfers(), 0);
+    }
+
+    #[test]
+    fn test_dropped_transfers() {
+        let mut tp = LinTpReassembler::with_timeout(1000);
+        let ff = [0x0A, 0x10, 20, 1, 2, 3, 4, 5];
+
+        // Out of sequence
+        tp.process(SLAVE_RESPONSE_ID, 0, &ff);
+        assert!(
+            tp.process(SLAVE_RESPONSE_ID, 100, &[0x0A, 0x22, 0, 0, 0, 0, 0, 0])
+                .is_none()
+        );
+        assert_eq!(tp.dropped_transfers(), 1);
+
+        // Timeout
+        tp.process(SLAVE_RESPONSE_ID, 200, &ff);
+        assert!(
+            tp.process(SLAVE_RESPONSE_ID, 5000, &[0x0A, 0x21, 0, 0, 0, 0, 0, 0])
+                .is_none()
+        );
+        assert_eq!(tp.dropped_transfers(), 2);
+
+        // Interrupted by a new first frame; other NADs are independent
+        tp.process(SLAVE_RESPONSE_ID, 6000, &ff);
+        tp.process(SLAVE_RESPONSE_ID, 6100, &[0x0B, 0x10, 20, 1, 2, 3, 4, 5]);
+        tp.process(SLAVE_RESPONSE_ID, 6200, &ff);
+        assert_eq!(tp.dropped_transfers(), 3);
+        assert_eq!(tp.pending_transfers(), 2);
+    }
+}
@@ -5,6 +5,7 @@
 
 use alloc::vec::Vec;
 
+use crate::blocks::SourceBlock;
 use crate::bus_logging::BusFrame;
 
 /// Maximum LIN frame data size (8 bytes).
@@ -304,12 +305,20 @@ impl Default for LinFrame {
 }
 
 impl BusFrame for LinFrame {
+    const CHANNEL_NAME: &'static str = "LIN_Frame";
+    /// ID(1) + Length(1) + Flags(1) + Checksum(1) + Data(8) = 12 bytes
+    const RECORD_SIZE: usize = 12;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
     fn to_mdf_bytes(&self) -> Vec<u8> {
         self.to_bytes()
     }
 
     fn mdf_size(&self) -> usize {
-        12 // Fixed size: ID + Length + Flags + Checksum + Data(8)
+        Self::RECORD_SIZE
     }
 }
 
@@ -329,6 +338,20 @@ pub enum ScheduleEntryType {
     DiagnosticResponse = 4,
 }
 
+impl ScheduleEntryType {
+    /// Create from raw byte value; unknown values map to
+    /// [`ScheduleEntryType::Unconditional`].
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::EventTriggered,
+            2 => Self::Sporadic,
+            3 => Self::DiagnosticRequest,
+            4 => Self::DiagnosticResponse,
+            _ => Self::Unconditional,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,695 @@
+//! LIN Description File (LDF) parsing.
+//!
+//! [`Ldf`] reads the parts of an LDF needed to decode LIN frames: the bus
+//! speed, the nodes, the `Signals` and `Frames` definitions and the signal
+//! encodings from `Signal_encoding_types` and `Signal_representation`.
+//! Other sections, such as schedule tables and node attributes, are
+//! skipped.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::lin::Ldf;
+//!
+//! let ldf = Ldf::parse(&std::fs::read_to_string("body.ldf")?)?;
+//! let frame = ldf.frame(0x10).unwrap();
+//! for signal in &frame.signals {
+//!     let raw = signal.raw_value(&data);
+//! }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Error, Result};
+
+/// A signal of the `Signals` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdfSignal {
+    /// Signal name
+    pub name: String,
+    /// Size in bits (1 to 16 for scalar signals, up to 64 for byte arrays)
+    pub size: u8,
+    /// Initial value; byte array signals use the bytes in little-endian
+    /// order
+    pub init_value: u64,
+    /// Publishing node
+    pub publisher: String,
+    /// Subscribing nodes
+    pub subscribers: Vec<String>,
+}
+
+/// A signal packed in a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdfFrameSignal {
+    /// Signal name
+    pub name: String,
+    /// Bit offset of the least significant bit in the frame
+    pub offset: u8,
+    /// Size in bits
+    pub size: u8,
+}
+
+impl LdfFrameSignal {
+    /// Extract the raw value of the signal from the frame data.
+    ///
+    /// LIN signals are sent least significant bit first.
+    ///
+    /// # Returns
+    /// The raw value, or `None` if the data is too short for the signal
+    pub fn raw_value(&self, data: &[u8]) -> Option<u64> {
+        let start = self.offset as usize;
+        let size = (self.size as usize).min(64);
+        if size == 0 || start + size > data.len() * 8 {
+            return None;
+        }
+        let mut raw = 0u64;
+        for bit in 0..size {
+            let pos = start + bit;
+            if data[pos / 8] & (1 << (pos % 8)) != 0 {
+                raw |= 1 << bit;
+            }
+        }
+        Some(raw)
+    }
+}
+
+/// An unconditional frame of the `Frames` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdfFrame {
+    /// Frame name
+    pub name: String,
+    /// Frame ID (0-63)
+    pub id: u8,
+    /// Publishing node
+    pub publisher: String,
+    /// Data length in bytes
+    pub length: u8,
+    /// Signals of the frame, in the order of the LDF
+    pub signals: Vec<LdfFrameSignal>,
+}
+
+/// A `physical_value` range of a signal encoding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhysicalRange {
+    /// Smallest raw value of the range
+    pub min: u64,
+    /// Largest raw value of the range
+    pub max: u64,
+    /// Scale factor: physical = scale * raw + offset
+    pub scale: f64,
+    /// Offset: physical = scale * raw + offset
+    pub offset: f64,
+    /// Physical unit
+    pub unit: Option<String>,
+}
+
+impl PhysicalRange {
+    /// Returns true if `raw` is within the range.
+    pub fn contains(&self, raw: u64) -> bool {
+        (self.min..=self.max).contains(&raw)
+    }
+}
+
+/// An encoding of the `Signal_encoding_types` section.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LdfEncoding {
+    /// Encoding name
+    pub name: String,
+    /// Physical value ranges
+    pub physical: Vec<PhysicalRange>,
+    /// Logical values with their descriptions
+    pub logical: Vec<(u64, String)>,
+}
+
+impl LdfEncoding {
+    /// The physical value of `raw`, from the first physical range that
+    /// contains it.
+    pub fn physical_value(&self, raw: u64) -> Option<f64> {
+        self.physical
+            .iter()
+            .find(|range| range.contains(raw))
+            .map(|range| range.scale * raw as f64 + range.offset)
+    }
+
+    /// The description of the logical value `raw`.
+    pub fn logical_value(&self, raw: u64) -> Option<&str> {
+        self.logical
+            .iter()
+            .find(|(value, _)| *value == raw)
+            .map(|(_, text)| text.as_str())
+    }
+}
+
+/// A parsed LIN Description File.
+#[derive(Debug, Clone, Default)]
+pub struct Ldf {
+    protocol_version: Option<String>,
+    speed_bps: Option<u32>,
+    master: Option<String>,
+    slaves: Vec<String>,
+    signals: Vec<LdfSignal>,
+    frames: Vec<LdfFrame>,
+    encodings: Vec<LdfEncoding>,
+    /// Signal name and index of its encoding
+    representations: Vec<(String, usize)>,
+}
+
+impl Ldf {
+    /// Parse the text of an LDF file.
+    ///
+    /// # Errors
+    /// Returns an error for unbalanced braces, unterminated strings or
+    /// comments, and malformed signal, frame or encoding definitions.
+    pub fn parse(text: &str) -> Result<Self> {
+        let statements = parse_statements(&mut tokenize(text)?.into_iter().peekable(), false)?;
+        let mut ldf = Self::default();
+        for statement in &statements {
+            match statement.as_slice() {
+                [Node::Word("LIN_protocol_version"), Node::Str(version)] => {
+                    ldf.protocol_version = Some(String::from(*version));
+                }
+                [Node::Word("LIN_speed"), Node::Word(speed), unit @ ..] => {
+                    let scale = match unit {
+                        [Node::Word("bps")] => 1.0,
+                        _ => 1000.0,
+                    };
+                    ldf.speed_bps = speed.parse::<f64>().ok().map(|s| (s * scale) as u32);
+                }
+                [Node::Word("Nodes"), Node::Block(nodes)] => ldf.add_nodes(nodes),
+                [Node::Word("Signals"), Node::Block(signals)] => {
+                    for signal in signals {
+                        ldf.signals.push(parse_signal(signal)?);
+                    }
+                }
+                [Node::Word("Frames"), Node::Block(frames)] => {
+                    for frame in frames {
+                        ldf.frames.push(parse_frame(frame)?);
+                    }
+                }
+                [Node::Word("Signal_encoding_types"), Node::Block(encodings)] => {
+                    for encoding in encodings {
+                        ldf.encodings.push(parse_encoding(encoding)?);
+                    }
+                }
+                [
+                    Node::Word("Signal_representation"),
+                    Node::Block(representations),
+                ] => {
+                    for representation in representations {
+                        ldf.add_representation(representation);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Frames only name their signals; take the sizes from `Signals`
+        for frame in &mut ldf.frames {
+            for frame_signal in &mut frame.signals {
+                let signal = ldf
+                    .signals
+                    .iter()
+                    .find(|signal| signal.name == frame_signal.name)
+                    .ok_or_else(|| {
+                        ldf_error(alloc::format!(
+                            "frame {} uses undefined signal {}",
+                            frame.name,
+                            frame_signal.name
+                        ))
+                    })?;
+                frame_signal.size = signal.size;
+            }
+        }
+        Ok(ldf)
+    }
+
+    /// The `LIN_protocol_version`, e.g. "2.1".
+    pub fn protocol_version(&self) -> Option<&str> {
+        self.protocol_version.as_deref()
+    }
+
+    /// The `LIN_speed` in bits per second.
+    pub fn speed_bps(&self) -> Option<u32> {
+        self.speed_bps
+    }
+
+    /// The master node.
+    pub fn master(&self) -> Option<&str> {
+        self.master.as_deref()
+    }
+
+    /// The slave nodes.
+    pub fn slaves(&self) -> &[String] {
+        &self.slaves
+    }
+
+    /// All signals, in the order of the LDF.
+    pub fn signals(&self) -> &[LdfSignal] {
+        &self.signals
+    }
+
+    /// The signal named `name`.
+    pub fn signal(&self, name: &str) -> Option<&LdfSignal> {
+        self.signals.iter().find(|signal| signal.name == name)
+    }
+
+    /// All unconditional frames, in the order of the LDF.
+    pub fn frames(&self) -> &[LdfFrame] {
+        &self.frames
+    }
+
+    /// The frame with ID `id`.
+    pub fn frame(&self, id: u8) -> Option<&LdfFrame> {
+        self.frames.iter().find(|frame| frame.id == id)
+    }
+
+    /// The frame named `name`.
+    pub fn frame_by_name(&self, name: &str) -> Option<&LdfFrame> {
+        self.frames.iter().find(|frame| frame.name == name)
+    }
+
+    /// The encoding of the signal named `signal`, from
+    /// `Signal_representation`.
+    pub fn encoding(&self, signal: &str) -> Option<&LdfEncoding> {
+        self.representations
+            .iter()
+            .find(|(name, _)| name == signal)
+            .map(|&(_, index)| &self.encodings[index])
+    }
+
+    fn add_nodes(&mut self, nodes: &[Vec<Node<'_>>]) {
+        for node in nodes {
+            match node.as_slice() {
+                [Node::Word("Master"), Node::Word(master), ..] => {
+                    self.master = Some(String::from(*master));
+                }
+                [Node::Word("Slaves"), slaves @ ..] => {
+                    self.slaves = slaves
+                        .iter()
+                        .filter_map(Node::word)
+                        .map(String::from)
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn add_representation(&mut self, representation: &[Node<'_>]) {
+        let [Node::Word(encoding), signals @ ..] = representation else {
+            return;
+        };
+        if let Some(index) = self.encodings.iter().position(|e| e.name == *encoding) {
+            for signal in signals.iter().filter_map(Node::word) {
+                self.representations.push((String::from(signal), index));
+            }
+        }
+    }
+}
+
+fn ldf_error(message: String) -> Error {
+    Error::BlockSerializationError(alloc::format!("LDF: {}", message))
+}
+
+/// `name: size, init_value, publisher, subscriber...;`
+fn parse_signal(statement: &[Node<'_>]) -> Result<LdfSignal> {
+    let invalid = || ldf_error(alloc::format!("invalid signal definition {:?}", statement));
+    let [
+        Node::Word(name),
+        Node::Word(size),
+        init,
+        Node::Word(publisher),
+        subscribers @ ..,
+    ] = statement
+    else {
+        return Err(invalid());
+    };
+    let init_value = match init {
+        Node::Word(value) => parse_int(value).ok_or_else(invalid)?,
+        // Byte array: first byte first
+        Node::Block(bytes) => bytes
+            .iter()
+            .flatten()
+            .filter_map(Node::word)
+            .filter_map(parse_int)
+            .take(8)
+            .enumerate()
+            .fold(0, |value, (i, byte)| value | (byte & 0xFF) << (8 * i)),
+        Node::Str(_) => return Err(invalid()),
+    };
+    Ok(LdfSignal {
+        name: String::from(*name),
+        size: parse_int(size).ok_or_else(invalid)? as u8,
+        init_value,
+        publisher: String::from(*publisher),
+        subscribers: subscribers
+            .iter()
+            .filter_map(Node::word)
+            .map(String::from)
+            .collect(),
+    })
+}
+
+/// `name: id, publisher, length { signal, offset; ... }`
+fn parse_frame(statement: &[Node<'_>]) -> Result<LdfFrame> {
+    let invalid = || ldf_error(alloc::format!("invalid frame definition {:?}", statement));
+    let (header, Some(Node::Block(signals))) = (
+        &statement[..statement.len().saturating_sub(1)],
+        statement.last(),
+    ) else {
+        return Err(invalid());
+    };
+    let (name, id, publisher, length) = match header {
+        [
+            Node::Word(name),
+            Node::Word(id),
+            Node::Word(publisher),
+            Node::Word(length),
+        ] => (name, id, publisher, Some(length)),
+        // LIN 1.3 frames may omit the length
+        [Node::Word(name), Node::Word(id), Node::Word(publisher)] => (name, id, publisher, None),
+        _ => return Err(invalid()),
+    };
+
+    let signals = signals
+        .iter()
+        .map(|signal| match signal.as_slice() {
+            [Node::Word(name), Node::Word(offset)] => Ok(LdfFrameSignal {
+                name: String::from(*name),
+                offset: parse_int(offset).ok_or_else(invalid)? as u8,
+                size: 0,
+            }),
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let length = match length {
+        Some(length) => parse_int(length).ok_or_else(invalid)? as u8,
+        None => 8,
+    };
+    Ok(LdfFrame {
+        name: String::from(*name),
+        id: parse_int(id).ok_or_else(invalid)? as u8 & super::frame::MAX_LIN_ID,
+        publisher: String::from(*publisher),
+        length,
+        signals,
+    })
+}
+
+/// `name { logical_value, value, "text"; physical_value, min, max, scale,
+/// offset, "unit"; ... }`
+fn parse_encoding(statement: &[Node<'_>]) -> Result<LdfEncoding> {
+    let invalid = || ldf_error(alloc::format!("invalid encoding {:?}", statement));
+    let [Node::Word(name), Node::Block(values)] = statement else {
+        return Err(invalid());
+    };
+    let mut encoding = LdfEncoding {
+        name: String::from(*name),
+        ..Default::default()
+    };
+    for value in values {
+        match value.as_slice() {
+            [Node::Word("logical_value"), Node::Word(raw), text @ ..] => {
+                let text = match text {
+                    [Node::Str(text)] => String::from(*text),
+                    _ => String::from(*raw),
+                };
+                let raw = parse_int(raw).ok_or_else(invalid)?;
+                encoding.logical.push((raw, text));
+            }
+            [
+                Node::Word("physical_value"),
+                Node::Word(min),
+                Node::Word(max),
+                Node::Word(scale),
+                Node::Word(offset),
+                unit @ ..,
+            ] => encoding.physical.push(PhysicalRange {
+                min: parse_int(min).ok_or_else(invalid)?,
+                max: parse_int(max).ok_or_else(invalid)?,
+                scale: scale.parse().map_err(|_| invalid())?,
+                offset: offset.parse().map_err(|_| invalid())?,
+                unit: match unit {
+                    [Node::Str(unit)] if !unit.is_empty() => Some(String::from(*unit)),
+                    _ => None,
+                },
+            }),
+            // bcd_value and ascii_value have no conversion
+            _ => {}
+        }
+    }
+    Ok(encoding)
+}
+
+/// Parse a decimal or `0x` hexadecimal integer.
+fn parse_int(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// A lexical token of an LDF file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Word(&'a str),
+    Str(&'a str),
+    Open,
+    Close,
+    /// `,`, which may continue a statement after a block
+    Comma,
+    /// `;`
+    End,
+}
+
+/// A word, string or nested block of a statement. The separators `:`,
+/// `,` and `=` are dropped.
+#[derive(Debug, Clone, PartialEq)]
+enum Node<'a> {
+    Word(&'a str),
+    Str(&'a str),
+    Block(Vec<Vec<Node<'a>>>),
+}
+
+impl<'a> Node<'a> {
+    fn word(&self) -> Option<&'a str> {
+        match *self {
+            Node::Word(word) => Some(word),
+            _ => None,
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        let consumed = match c {
+            '/' if rest.starts_with("//") => rest.find('\n').unwrap_or(rest.len()),
+            '/' if rest.starts_with("/*") => {
+                rest.find("*/")
+                    .ok_or_else(|| ldf_error(String::from("unterminated comment")))?
+                    + 2
+            }
+            '"' => {
+                let end = rest[1..]
+                    .find('"')
+                    .ok_or_else(|| ldf_error(String::from("unterminated string")))?;
+                tokens.push(Token::Str(&rest[1..end + 1]));
+                end + 2
+            }
+            '{' => {
+                tokens.push(Token::Open);
+                1
+            }
+            '}' => {
+                tokens.push(Token::Close);
+                1
+            }
+            ';' => {
+                tokens.push(Token::End);
+                1
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                1
+            }
+            ':' | '=' => 1,
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{};:,=\"".contains(c))
+                    .unwrap_or(rest.len())
+                    .max(c.len_utf8());
+                tokens.push(Token::Word(&rest[..end]));
+                end
+            }
+        };
+        rest = &rest[consumed..];
+    }
+}
+
+/// Group tokens into statements, ended by `;` or by a block that is not
+/// followed by a comma.
+fn parse_statements<'a>(
+    tokens: &mut core::iter::Peekable<alloc::vec::IntoIter<Token<'a>>>,
+    nested: bool,
+) -> Result<Vec<Vec<Node<'a>>>> {
+    let mut statements = Vec::new();
+    let mut statement = Vec::new();
+    loop {
+        match tokens.next() {
+            Some(Token::Word(word)) => statement.push(Node::Word(word)),
+            Some(Token::Str(text)) => statement.push(Node::Str(text)),
+            Some(Token::Open) => {
+                statement.push(Node::Block(parse_statements(tokens, true)?));
+                // `{...}` ends the statement unless a comma follows, as after
+                // the initial value of a byte array signal
+                if tokens.peek() != Some(&Token::Comma) {
+                    statements.push(core::mem::take(&mut statement));
+                }
+            }
+            Some(Token::Comma) => {}
+            Some(Token::End) => {
+                if !statement.is_empty() {
+                    statements.push(core::mem::take(&mut statement));
+                }
+            }
+            Some(Token::Close) if nested => break,
+            Some(Token::Close) => return Err(ldf_error(String::from("unbalanced '}'"))),
+            None if nested => return Err(ldf_error(String::from("missing '}'"))),
+            None => break,
+        }
+    }
+    if !statement.is_empty() {
+        statements.push(statement);
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LDF: &str = r#"
+LIN_description_file;
+LIN_protocol_version = "2.1";
+LIN_language_version = "2.1";
+LIN_speed = 19.2 kbps;
+
+Nodes {
+  Master: BCM, 5 ms, 0.1 ms;
+  Slaves: Mirror, Seat;
+}
+
+/* Signals of the mirror */
+Signals {
+  MirrorPos: 8, 0, BCM, Mirror;
+  MirrorState: 2, 3, Mirror, BCM; // folded by default
+  SerialNo: 24, {0x01, 0x02, 0x03}, Mirror, BCM, Seat;
+}
+
+Frames {
+  MirrorCmd: 0x10, BCM, 2 {
+    MirrorPos, 0;
+    MirrorState, 8;
+  }
+  MirrorInfo: 17, Mirror, 3 {
+    SerialNo, 0;
+  }
+}
+
+Diagnostic_frames {
+  MasterReq: 0x3c { MasterReqB0, 0; }
+  SlaveResp: 0x3d { SlaveRespB0, 0; }
+}
+
+Schedule_tables {
+  Normal {
+    MirrorCmd delay 10 ms;
+  }
+}
+
+Signal_encoding_types {
+  PosEnc {
+    physical_value, 0, 250, 0.4, -50, "%";
+    logical_value, 255, "Invalid";
+  }
+  StateEnc {
+    logical_value, 0, "Folded";
+    logical_value, 1, "Unfolded";
+    logical_value, 2;
+  }
+}
+
+Signal_representation {
+  PosEnc: MirrorPos;
+  StateEnc: MirrorState;
+}
+"#;
+
+    #[test]
+    fn test_parse_ldf() {
+        let ldf = Ldf::parse(LDF).unwrap();
+        assert_eq!(ldf.protocol_version(), Some("2.1"));
+        assert_eq!(ldf.speed_bps(), Some(19_200));
+        assert_eq!(ldf.master(), Some("BCM"));
+        assert_eq!(ldf.slaves(), ["Mirror", "Seat"]);
+
+        assert_eq!(ldf.signals().len(), 3);
+        let serial = ldf.signal("SerialNo").unwrap();
+        assert_eq!(serial.size, 24);
+        assert_eq!(serial.init_value, 0x03_0201);
+        assert_eq!(serial.subscribers, ["BCM", "Seat"]);
+        assert_eq!(ldf.signal("MirrorState").unwrap().init_value, 3);
+
+        let frame = ldf.frame(0x10).unwrap();
+        assert_eq!(frame.name, "MirrorCmd");
+        assert_eq!(frame.publisher, "BCM");
+        assert_eq!(frame.length, 2);
+        assert_eq!(
+            frame.signals[1],
+            LdfFrameSignal {
+                name: String::from("MirrorState"),
+                offset: 8,
+                size: 2,
+            }
+        );
+        assert_eq!(ldf.frame_by_name("MirrorInfo").unwrap().id, 17);
+
+        let pos = ldf.encoding("MirrorPos").unwrap();
+        assert_eq!(pos.physical_value(100), Some(-10.0));
+        assert_eq!(pos.physical_value(255), None);
+        assert_eq!(pos.logical_value(255), Some("Invalid"));
+        assert_eq!(pos.physical[0].unit.as_deref(), Some("%"));
+        let state = ldf.encoding("MirrorState").unwrap();
+        assert_eq!(state.logical_value(1), Some("Unfolded"));
+        assert_eq!(state.logical_value(2), Some("2"));
+        assert!(ldf.encoding("SerialNo").is_none());
+    }
+
+    #[test]
+    fn test_raw_value() {
+        let ldf = Ldf::parse(LDF).unwrap();
+        let frame = ldf.frame(0x10).unwrap();
+        let data = [0xC8, 0b1111_1101];
+        assert_eq!(frame.signals[0].raw_value(&data), Some(200));
+        assert_eq!(frame.signals[1].raw_value(&data), Some(1));
+        assert_eq!(frame.signals[1].raw_value(&data[..1]), None);
+
+        let serial = &ldf.frame(17).unwrap().signals[0];
+        assert_eq!(serial.raw_value(&[0x56, 0x34, 0x12]), Some(0x12_3456));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Ldf::parse("Signals { A: 8, 0, M, S;").is_err());
+        assert!(Ldf::parse("Signals { A: 8, 0, M, S; } }").is_err());
+        assert!(Ldf::parse("LIN_protocol_version = \"2.1;").is_err());
+        assert!(Ldf::parse("Frames { F: 1, M, 1 { Missing, 0; } }").is_err());
+        assert!(Ldf::parse("Signals { A: x, 0, M, S; }").is_err());
+    }
+}
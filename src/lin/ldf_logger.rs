@@ -0,0 +1,401 @@
+//! Decoded LIN logging with signal definitions from an LDF.
+//!
+//! [`LdfLogger`] decodes LIN frames with the frame and signal definitions
+//! of a [`Ldf`] and writes one channel group per LDF frame, named after
+//! the frame:
+//! - `Time_0x{id}`: timestamp in microseconds (master channel)
+//! - one channel per signal with its raw value
+//!
+//! Signal channels get the unit, conversion and limits of their encoding:
+//! a linear conversion for encodings with a `physical_value` range, or a
+//! value-to-text conversion for encodings with only `logical_value`
+//! entries. Encodings with several physical ranges use the first one.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::lin::{Ldf, LdfLogger};
+//!
+//! let ldf = Ldf::parse(&ldf_text)?;
+//! let mut logger = LdfLogger::with_source_name(ldf, "Body_LIN")?;
+//!
+//! logger.log(0x10, timestamp_us, &[0x64, 0x01]);
+//!
+//! let mdf_bytes = logger.finalize()?;
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::frame::{LinFrame, MAX_LIN_ID};
+use super::ldf::{Ldf, LdfFrame};
+use crate::blocks::ConversionBlock;
+use crate::{DataType, DecodedValue};
+
+/// Buffered raw signal values of one LDF frame.
+#[derive(Debug, Default)]
+struct FrameBuffer {
+    /// Timestamps of the buffered frames (microseconds)
+    timestamps: Vec<u64>,
+    /// Raw values per signal (outer vec = signals, inner vec = samples)
+    values: Vec<Vec<u64>>,
+    /// Frames written by earlier flushes
+    flushed_frames: usize,
+    /// Channel group ID
+    channel_group: Option<String>,
+}
+
+/// LIN logger that decodes frames with the signal definitions of an LDF.
+///
+/// Frames not defined in the LDF, frames shorter than their LDF length
+/// and frames with error flags are not logged.
+pub struct LdfLogger<W: crate::writer::MdfWrite> {
+    ldf: Ldf,
+    writer: crate::MdfWriter<W>,
+    /// Source name for metadata
+    source_name: String,
+    /// Buffers keyed by frame ID
+    buffers: BTreeMap<u8, FrameBuffer>,
+    initialized: bool,
+}
+
+impl LdfLogger<crate::writer::VecWriter> {
+    /// Create a new LDF logger with in-memory output.
+    pub fn new(ldf: Ldf) -> crate::Result<Self> {
+        Self::with_source_name(ldf, "LIN")
+    }
+
+    /// Create a new LDF logger with a custom source name.
+    ///
+    /// The source name is used for the source metadata of the channel
+    /// groups. Examples: "LIN", "LIN1", "Body_LIN", etc.
+    pub fn with_source_name(ldf: Ldf, source_name: &str) -> crate::Result<Self> {
+        let writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
+        Ok(Self::with_writer(ldf, writer, source_name))
+    }
+
+    /// Finalize the MDF file and return the bytes.
+    pub fn finalize(mut self) -> crate::Result<Vec<u8>> {
+        self.flush_and_finalize()?;
+        Ok(self.writer.into_inner().into_inner())
+    }
+}
+
+#[cfg(feature = "std")]
+impl LdfLogger<crate::writer::FileWriter> {
+    /// Create a new LDF logger that writes to a file.
+    pub fn new_file(ldf: Ldf, path: &str) -> crate::Result<Self> {
+        Self::new_file_with_source_name(ldf, path, "LIN")
+    }
+
+    /// Create a new LDF logger that writes to a file with custom source name.
+    pub fn new_file_with_source_name(
+        ldf: Ldf,
+        path: &str,
+        source_name: &str,
+    ) -> crate::Result<Self> {
+        let writer = crate::MdfWriter::new(path)?;
+        Ok(Self::with_writer(ldf, writer, source_name))
+    }
+
+    /// Finalize and close the MDF file.
+    pub fn finalize_file(mut self) -> crate::Result<()> {
+        self.flush_and_finalize()
+    }
+}
+
+impl<W: crate::writer::MdfWrite> LdfLogger<W> {
+    fn with_writer(ldf: Ldf, writer: crate::MdfWriter<W>, source_name: &str) -> Self {
+        let buffers = ldf
+            .frames()
+            .iter()
+            .map(|frame| {
+                let buffer = FrameBuffer {
+                    values: frame.signals.iter().map(|_| Vec::new()).collect(),
+                    ..Default::default()
+                };
+                (frame.id, buffer)
+            })
+            .collect();
+        Self {
+            ldf,
+            writer,
+            source_name: String::from(source_name),
+            buffers,
+            initialized: false,
+        }
+    }
+
+    /// Get the LDF used for decoding.
+    pub fn ldf(&self) -> &Ldf {
+        &self.ldf
+    }
+
+    /// Log a LIN frame by ID and data.
+    ///
+    /// # Returns
+    /// `true` if the frame is defined in the LDF and was logged
+    pub fn log(&mut self, id: u8, timestamp_us: u64, data: &[u8]) -> bool {
+        let id = id & MAX_LIN_ID;
+        let (Some(frame), Some(buffer)) = (self.ldf.frame(id), self.buffers.get_mut(&id)) else {
+            return false;
+        };
+        if data.len() < frame.length as usize {
+            return false;
+        }
+        let Some(raw_values) = frame
+            .signals
+            .iter()
+            .map(|signal| signal.raw_value(data))
+            .collect::<Option<Vec<u64>>>()
+        else {
+            return false;
+        };
+        buffer.timestamps.push(timestamp_us);
+        for (values, raw) in buffer.values.iter_mut().zip(raw_values) {
+            values.push(raw);
+        }
+        true
+    }
+
+    /// Log a [`LinFrame`].
+    ///
+    /// Frames with error flags carry no valid response and are not logged.
+    pub fn log_frame(&mut self, timestamp_us: u64, frame: &LinFrame) -> bool {
+        if frame.flags.has_error() {
+            return false;
+        }
+        self.log(frame.id, timestamp_us, frame.data())
+    }
+
+    /// Flush buffered data to the MDF writer.
+    pub fn flush(&mut self) -> crate::Result<()> {
+        if !self.initialized {
+            self.initialize_mdf()?;
+        }
+        for buffer in self.buffers.values_mut() {
+            write_buffer(&mut self.writer, buffer)?;
+        }
+        Ok(())
+    }
+
+    /// Initialize the MDF file with a channel group per LDF frame.
+    fn initialize_mdf(&mut self) -> crate::Result<()> {
+        self.writer.init_mdf_file()?;
+
+        for frame in self.ldf.frames() {
+            let cg = init_frame_group(&mut self.writer, &self.ldf, frame, &self.source_name)?;
+            if let Some(buffer) = self.buffers.get_mut(&frame.id) {
+                buffer.channel_group = Some(cg);
+            }
+        }
+
+        self.initialized = true;
+        Ok(())
+    }
+
+    /// Flush and finalize the MDF file.
+    fn flush_and_finalize(&mut self) -> crate::Result<()> {
+        self.flush()?;
+        self.writer.finalize()
+    }
+
+    /// Get the number of frames logged for a frame ID, including frames
+    /// already flushed.
+    pub fn frame_count(&self, id: u8) -> usize {
+        self.buffers
+            .get(&(id & MAX_LIN_ID))
+            .map_or(0, |buffer| buffer.flushed_frames + buffer.timestamps.len())
+    }
+
+    /// Get the total number of frames logged, including frames already
+    /// flushed.
+    pub fn total_frame_count(&self) -> usize {
+        self.buffers
+            .values()
+            .map(|buffer| buffer.flushed_frames + buffer.timestamps.len())
+            .sum()
+    }
+}
+
+/// Create the channel group of an LDF frame.
+fn init_frame_group<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    ldf: &Ldf,
+    frame: &LdfFrame,
+    source_name: &str,
+) -> crate::Result<String> {
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg, &frame.name)?;
+    let source = crate::blocks::SourceBlock::lin_bus();
+    writer.set_channel_group_source(&cg, &source, Some(source_name))?;
+    writer.set_channel_group_comment(&cg, &alloc::format!("Published by {}", frame.publisher))?;
+
+    let time_ch = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some(alloc::format!("Time_0x{:02X}", frame.id));
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_ch)?;
+    writer.set_channel_unit(&time_ch, "us")?;
+
+    let mut prev_ch = time_ch;
+    for signal in &frame.signals {
+        let bit_count = match signal.size {
+            0..=8 => 8,
+            9..=16 => 16,
+            17..=32 => 32,
+            _ => 64,
+        };
+        let ch = writer.add_channel(&cg, Some(&prev_ch), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some(signal.name.clone());
+            ch.bit_count = bit_count;
+        })?;
+
+        if let Some(encoding) = ldf.encoding(&signal.name) {
+            if let Some(range) = encoding.physical.first() {
+                let conversion = ConversionBlock::linear(range.offset, range.scale);
+                writer.set_channel_conversion(&ch, &conversion)?;
+                let min = range.scale * range.min as f64 + range.offset;
+                let max = range.scale * range.max as f64 + range.offset;
+                writer.set_channel_limits(&ch, min.min(max), min.max(max))?;
+                if let Some(unit) = &range.unit {
+                    writer.set_channel_unit(&ch, unit)?;
+                }
+            } else if !encoding.logical.is_empty() {
+                let mapping: Vec<(i64, &str)> = encoding
+                    .logical
+                    .iter()
+                    .map(|(value, text)| (*value as i64, text.as_str()))
+                    .collect();
+                writer.add_value_to_text_conversion(&mapping, "", Some(&ch))?;
+            }
+        }
+        prev_ch = ch;
+    }
+    Ok(cg)
+}
+
+/// Write and clear the buffered frames of one LDF frame.
+fn write_buffer<W: crate::writer::MdfWrite>(
+    writer: &mut crate::MdfWriter<W>,
+    buffer: &mut FrameBuffer,
+) -> crate::Result<()> {
+    let Some(cg) = &buffer.channel_group else {
+        return Ok(());
+    };
+    if buffer.timestamps.is_empty() {
+        return Ok(());
+    }
+
+    writer.start_data_block_for_cg(cg, 0)?;
+    for (record, &timestamp_us) in buffer.timestamps.iter().enumerate() {
+        let mut values = Vec::with_capacity(1 + buffer.values.len());
+        values.push(DecodedValue::UnsignedInteger(timestamp_us));
+        values.extend(
+            buffer
+                .values
+                .iter()
+                .map(|signal| DecodedValue::UnsignedInteger(signal[record])),
+        );
+        writer.write_record(cg, &values)?;
+    }
+    writer.finish_data_block(cg)?;
+
+    buffer.flushed_frames += buffer.timestamps.len();
+    buffer.timestamps.clear();
+    for values in &mut buffer.values {
+        values.clear();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lin::LinFlags;
+
+    const LDF: &str = r#"
+LIN_description_file;
+LIN_protocol_version = "2.1";
+LIN_speed = 19.2 kbps;
+
+Nodes {
+  Master: BCM, 5 ms, 0.1 ms;
+  Slaves: Mirror;
+}
+
+Signals {
+  MirrorPos: 8, 0, BCM, Mirror;
+  MirrorState: 2, 0, Mirror, BCM;
+}
+
+Frames {
+  MirrorCmd: 0x10, BCM, 2 {
+    MirrorPos, 0;
+    MirrorState, 8;
+  }
+}
+
+Signal_encoding_types {
+  PosEnc {
+    physical_value, 0, 250, 0.4, 0, "%";
+  }
+  StateEnc {
+    logical_value, 0, "Folded";
+    logical_value, 1, "Unfolded";
+  }
+}
+
+Signal_representation {
+  PosEnc: MirrorPos;
+  StateEnc: MirrorState;
+}
+"#;
+
+    #[test]
+    fn test_ldf_logger() {
+        let ldf = Ldf::parse(LDF).unwrap();
+        let mut logger = LdfLogger::with_source_name(ldf, "Body_LIN").unwrap();
+
+        assert!(logger.log(0x10, 1000, &[100, 0x01]));
+        assert!(logger.log(0x10, 2000, &[200, 0x00]));
+        // Unknown ID, short response, error flags
+        assert!(!logger.log(0x11, 3000, &[1, 2]));
+        assert!(!logger.log(0x10, 4000, &[1]));
+        let mut frame = LinFrame::with_enhanced_checksum(0x10, &[50, 0x01]);
+        frame.flags = LinFlags::from_byte(LinFlags::CHECKSUM_ERROR);
+        assert!(!logger.log_frame(5000, &frame));
+        assert_eq!(logger.frame_count(0x10), 2);
+
+        logger.flush().unwrap();
+        assert!(logger.log_frame(6000, &LinFrame::with_enhanced_checksum(0x10, &[250, 0x01])));
+        assert_eq!(logger.total_frame_count(), 3);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("ldf_logger_test.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name().unwrap().as_deref(), Some("MirrorCmd"));
+
+        let channels = groups[0].channels();
+        assert_eq!(channels[1].name().unwrap().as_deref(), Some("MirrorPos"));
+        assert_eq!(channels[1].unit().unwrap().as_deref(), Some("%"));
+        assert_eq!(
+            channels[1].values().unwrap(),
+            [40.0, 80.0, 100.0].map(|v| Some(DecodedValue::Float(v)))
+        );
+        assert_eq!(
+            channels[2].values().unwrap(),
+            ["Unfolded", "Folded", "Unfolded"]
+                .map(|text| Some(DecodedValue::String(String::from(text))))
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+}
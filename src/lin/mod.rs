@@ -10,6 +10,11 @@
 //! - Protected ID calculation with parity bits
 //! - Direction tracking (Tx/Rx)
 //! - Error flag tracking (checksum, sync, framing, no response)
+//! - Dedicated channel groups for checksum, sync and transmission errors
+//!   and for wake-up and sleep events
+//! - Schedule table switches and slot timing of the LIN master
+//! - Diagnostic transport layer reassembly into a `LIN_Diag` channel group
+//! - LDF parsing and decoded signal logging with [`LdfLogger`]
 //!
 //! # LIN Protocol Overview
 //!
@@ -48,8 +53,27 @@
 //! // Finalize
 //! let mdf_bytes = logger.finalize()?;
 //! ```
+//!
+//! # Decoded Logging
+//!
+//! [`LdfLogger`] decodes frames with the signal definitions of an LDF
+//! (LIN Description File) and writes one channel group per frame:
+//!
+//! ```ignore
+//! use mdf4_rs::lin::{Ldf, LdfLogger};
+//!
+//! let ldf = Ldf::parse(&std::fs::read_to_string("body.ldf")?)?;
+//! let mut logger = LdfLogger::with_source_name(ldf, "Body_LIN")?;
+//!
+//! logger.log(0x10, timestamp_us, &[0x64, 0x01]);
+//!
+//! let mdf_bytes = logger.finalize()?;
+//! ```
 
+pub mod diag;
 pub mod frame;
+pub mod ldf;
+mod ldf_logger;
 mod raw_logger;
 
 // Re-export frame types
@@ -57,5 +81,12 @@ pub use frame::{
     ChecksumType, LinFlags, LinFrame, MAX_LIN_DATA_LEN, MAX_LIN_ID, ScheduleEntryType,
 };
 
-// Re-export logger
-pub use raw_logger::RawLinLogger;
+// Re-export diagnostic types
+pub use diag::{LinTpMessage, LinTpReassembler};
+
+// Re-export LDF types
+pub use ldf::{Ldf, LdfEncoding, LdfFrame, LdfFrameSignal, LdfSignal, PhysicalRange};
+
+// Re-export loggers
+pub use ldf_logger::LdfLogger;
+pub use raw_logger::{LinSleepReason, RawLinLogger};
@@ -11,6 +11,11 @@
 //! - Source metadata (LIN bus name)
 //! - Classic and Enhanced checksum support
 //! - Error flag tracking
+//! - Checksum, sync and transmission error channel groups
+//! - Wake-up and sleep event channel groups
+//! - Schedule table switch and slot channel groups
+//! - Reassembled diagnostic (LIN TP) payloads in a `LIN_Diag` channel group
+//! - Streaming to file with bounded memory
 //!
 //! # Example
 //!
@@ -26,21 +31,307 @@
 //! // Or log from components
 //! logger.log(0x20, timestamp_us, &[0x01, 0x02, 0x03, 0x04]);
 //!
+//! // Log bus errors and events
+//! logger.log_checksum_error(0x20, timestamp_us, &[0x01, 0x02], 0x55);
+//! logger.log_no_response(0x21, timestamp_us);
+//! logger.log_wakeup(timestamp_us, false);
+//!
 //! // Get MDF bytes
 //! let mdf_bytes = logger.finalize()?;
 //! ```
+//!
+//! # Bounded Memory
+//!
+//! Frames are buffered until `flush()` or `finalize()`. For long captures,
+//! [`RawLinLogger::set_max_buffered_frames`] writes the buffered frames to
+//! the MDF writer automatically while logging, and
+//! [`RawLinLogger::set_flush_policy`] flushes the written records to disk:
+//!
+//! ```ignore
+//! use mdf4_rs::FlushPolicy;
+//! use mdf4_rs::lin::RawLinLogger;
+//!
+//! let mut logger = RawLinLogger::new_file_with_source_name("body.mf4", "Body_LIN")?;
+//! logger.set_max_buffered_frames(1000);
+//! logger.set_flush_policy(FlushPolicy::EveryNRecords(1000));
+//!
+//! // ... log frames for hours ...
+//!
+//! logger.finalize_file()?;
+//! ```
 
-use alloc::string::String;
 use alloc::vec::Vec;
 
-use super::frame::{LinFlags, LinFrame};
-use crate::bus_logging::{
-    BusLoggerConfig, TimestampedFrame, init_bus_channel_group, write_timestamped_frames,
-};
+use super::diag::{DiagGroup, LinTpReassembler};
+use super::frame::{LinFlags, LinFrame, MAX_LIN_ID, ScheduleEntryType};
+use crate::blocks::SourceBlock;
+use crate::bus_logging::{BusChannel, BusFrame, BusLoggerCore};
+use crate::writer::FlushPolicy;
+
+/// LIN_SyncError size in bytes: Baudrate(4).
+const SYNC_ERROR_SIZE: usize = 4;
+
+/// LIN_TransmissionError size in bytes: ID(1).
+const TRANSMISSION_ERROR_SIZE: usize = 1;
+
+/// LIN_WakeUp size in bytes: Dir(1).
+const WAKEUP_SIZE: usize = 1;
+
+/// LIN_Sleep size in bytes: Reason(1).
+const SLEEP_SIZE: usize = 1;
+
+/// LIN_ScheduleSwitch size in bytes: Table(1).
+const SCHEDULE_SWITCH_SIZE: usize = 1;
+
+/// LIN_ScheduleSlot size in bytes: Table(1) + Slot(1) + EntryType(1) +
+/// ID(1) + Delay(4).
+const SCHEDULE_SLOT_SIZE: usize = 8;
+
+/// Why a LIN cluster went to sleep, stored in the `LIN_Sleep` ByteArray.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LinSleepReason {
+    /// The master sent the go-to-sleep command (diagnostic frame 0x3C with
+    /// the first data byte 0x00)
+    #[default]
+    GoToSleep = 0,
+    /// The bus was inactive for longer than the bus idle timeout
+    BusIdle = 1,
+}
+
+impl LinSleepReason {
+    /// Create from the raw `Reason` value; unknown values map to
+    /// [`LinSleepReason::GoToSleep`].
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::BusIdle,
+            _ => Self::GoToSleep,
+        }
+    }
+}
+
+/// A failed synchronization on the sync byte of a LIN header.
+#[derive(Clone, Copy)]
+struct SyncError {
+    /// Measured baud rate of the sync field, 0 if unknown
+    baudrate: u32,
+}
+
+impl BusFrame for SyncError {
+    const CHANNEL_NAME: &'static str = "LIN_SyncError";
+    const RECORD_SIZE: usize = SYNC_ERROR_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "SyncError"
+    }
+
+    /// Build the LIN_SyncError ByteArray:
+    /// - Bytes 0-3: Baudrate (little-endian)
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        self.baudrate.to_le_bytes().to_vec()
+    }
+
+    fn mdf_size(&self) -> usize {
+        SYNC_ERROR_SIZE
+    }
+}
+
+/// A header without a slave response.
+#[derive(Clone, Copy)]
+struct TransmissionError {
+    /// Frame ID of the unanswered header
+    id: u8,
+}
 
-/// LIN_Frame size in ASAM format.
-/// ID(1) + Length(1) + Flags(1) + Checksum(1) + Data(8) = 12 bytes
-const LIN_FRAME_SIZE: usize = 12;
+impl BusFrame for TransmissionError {
+    const CHANNEL_NAME: &'static str = "LIN_TransmissionError";
+    const RECORD_SIZE: usize = TRANSMISSION_ERROR_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "TransmissionError"
+    }
+
+    /// Build the LIN_TransmissionError ByteArray:
+    /// - Byte 0: Frame ID
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        alloc::vec![self.id]
+    }
+
+    fn mdf_size(&self) -> usize {
+        TRANSMISSION_ERROR_SIZE
+    }
+}
+
+/// A wake-up pulse on the bus.
+#[derive(Clone, Copy)]
+struct WakeUp {
+    /// True if the logging node sent the wake-up pulse
+    tx: bool,
+}
+
+impl BusFrame for WakeUp {
+    const CHANNEL_NAME: &'static str = "LIN_WakeUp";
+    const RECORD_SIZE: usize = WAKEUP_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "WakeUp"
+    }
+
+    /// Build the LIN_WakeUp ByteArray:
+    /// - Byte 0: Dir (0 = Rx, 1 = Tx)
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        alloc::vec![self.tx as u8]
+    }
+
+    fn mdf_size(&self) -> usize {
+        WAKEUP_SIZE
+    }
+}
+
+/// The cluster entering sleep mode.
+#[derive(Clone, Copy)]
+struct Sleep {
+    reason: LinSleepReason,
+}
+
+impl BusFrame for Sleep {
+    const CHANNEL_NAME: &'static str = "LIN_Sleep";
+    const RECORD_SIZE: usize = SLEEP_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "Sleep"
+    }
+
+    /// Build the LIN_Sleep ByteArray:
+    /// - Byte 0: Reason ([`LinSleepReason`])
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        alloc::vec![self.reason as u8]
+    }
+
+    fn mdf_size(&self) -> usize {
+        SLEEP_SIZE
+    }
+}
+
+/// The master switching to another schedule table.
+#[derive(Clone, Copy)]
+struct ScheduleSwitch {
+    /// Index of the new schedule table
+    table: u8,
+}
+
+impl BusFrame for ScheduleSwitch {
+    const CHANNEL_NAME: &'static str = "LIN_ScheduleSwitch";
+    const RECORD_SIZE: usize = SCHEDULE_SWITCH_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "ScheduleSwitch"
+    }
+
+    /// Build the LIN_ScheduleSwitch ByteArray:
+    /// - Byte 0: Table index
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        alloc::vec![self.table]
+    }
+
+    fn mdf_size(&self) -> usize {
+        SCHEDULE_SWITCH_SIZE
+    }
+}
+
+/// The master starting a slot of its schedule table.
+#[derive(Clone, Copy)]
+struct ScheduleSlot {
+    table: u8,
+    slot: u8,
+    entry_type: ScheduleEntryType,
+    id: u8,
+    /// Configured slot delay in microseconds
+    delay_us: u32,
+}
+
+impl BusFrame for ScheduleSlot {
+    const CHANNEL_NAME: &'static str = "LIN_ScheduleSlot";
+    const RECORD_SIZE: usize = SCHEDULE_SLOT_SIZE;
+
+    fn source_block() -> SourceBlock {
+        SourceBlock::lin_bus()
+    }
+
+    fn group_suffix() -> &'static str {
+        "ScheduleSlot"
+    }
+
+    /// Build the LIN_ScheduleSlot ByteArray:
+    /// - Byte 0: Table index
+    /// - Byte 1: Slot index within the table
+    /// - Byte 2: Entry type ([`ScheduleEntryType`])
+    /// - Byte 3: Frame ID
+    /// - Bytes 4-7: Slot delay in microseconds (little-endian)
+    fn to_mdf_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SCHEDULE_SLOT_SIZE);
+        bytes.extend_from_slice(&[self.table, self.slot, self.entry_type as u8, self.id]);
+        bytes.extend_from_slice(&self.delay_us.to_le_bytes());
+        bytes
+    }
+
+    fn mdf_size(&self) -> usize {
+        SCHEDULE_SLOT_SIZE
+    }
+}
+
+/// Running frame counts, kept across flushes.
+#[derive(Debug, Clone, Copy)]
+struct FrameCounts {
+    /// Frames per frame ID
+    per_id: [usize; MAX_LIN_ID as usize + 1],
+    /// Transmitted frames
+    tx: usize,
+    /// Frames with error flags
+    errors: usize,
+}
+
+impl Default for FrameCounts {
+    fn default() -> Self {
+        Self {
+            per_id: [0; MAX_LIN_ID as usize + 1],
+            tx: 0,
+            errors: 0,
+        }
+    }
+}
+
+impl FrameCounts {
+    fn record(&mut self, frame: &LinFrame) {
+        self.per_id[(frame.id & MAX_LIN_ID) as usize] += 1;
+        self.tx += frame.flags.is_tx() as usize;
+        self.errors += frame.flags.has_error() as usize;
+    }
+
+    fn total(&self) -> usize {
+        self.per_id.iter().sum()
+    }
+}
 
 /// Raw LIN frame logger using ASAM MDF4 Bus Logging format.
 ///
@@ -49,9 +340,29 @@ const LIN_FRAME_SIZE: usize = 12;
 ///
 /// ## Channel Group Structure
 ///
-/// All LIN frames are stored in a single channel group:
+/// LIN frames are stored in a single channel group:
 /// - `{source_name}_LIN_Frame` - LIN frames with ID, data, and metadata
 ///
+/// Bus errors and events logged with the dedicated methods get their own
+/// channel groups, created only if they occur:
+/// - `{source_name}_ChecksumError` - frames with a wrong checksum, in the
+///   `LIN_Frame` format (see [`log_checksum_error`](Self::log_checksum_error))
+/// - `{source_name}_SyncError` - failed header synchronization
+///   (see [`log_sync_error`](Self::log_sync_error))
+/// - `{source_name}_TransmissionError` - headers without a response
+///   (see [`log_no_response`](Self::log_no_response))
+/// - `{source_name}_WakeUp` - wake-up pulses (see [`log_wakeup`](Self::log_wakeup))
+/// - `{source_name}_Sleep` - sleep mode entries (see [`log_sleep`](Self::log_sleep))
+/// - `{source_name}_ScheduleSwitch` - schedule table switches
+///   (see [`log_schedule_switch`](Self::log_schedule_switch))
+/// - `{source_name}_ScheduleSlot` - schedule table slots
+///   (see [`log_schedule_slot`](Self::log_schedule_slot))
+///
+/// Master request (0x3C) and slave response (0x3D) frames are also
+/// reassembled into complete diagnostic payloads, e.g. UDS requests and
+/// responses, stored in the `{source_name}_LIN_Diag` channel group with a
+/// variable length `LIN_Diag` ByteArray (see [`diag`](super::diag)).
+///
 /// ## LIN_Frame Format
 ///
 /// Each frame is stored as a 12-byte ByteArray:
@@ -61,14 +372,19 @@ const LIN_FRAME_SIZE: usize = 12;
 /// - Byte 3: Checksum
 /// - Bytes 4-11: Data (8 bytes, zero-padded)
 pub struct RawLinLogger<W: crate::writer::MdfWrite> {
-    writer: crate::MdfWriter<W>,
-    /// Source name for metadata
-    source_name: String,
+    core: BusLoggerCore<W>,
     /// Buffered frames
-    buffer: Vec<TimestampedFrame<LinFrame>>,
-    /// Channel group ID
-    channel_group: Option<String>,
-    initialized: bool,
+    frames: BusChannel<LinFrame>,
+    /// Counts of all frames logged, including frames already flushed
+    counts: FrameCounts,
+    checksum_errors: BusChannel<LinFrame>,
+    sync_errors: BusChannel<SyncError>,
+    transmission_errors: BusChannel<TransmissionError>,
+    wakeups: BusChannel<WakeUp>,
+    sleeps: BusChannel<Sleep>,
+    schedule_switches: BusChannel<ScheduleSwitch>,
+    schedule_slots: BusChannel<ScheduleSlot>,
+    diag: DiagGroup,
 }
 
 impl RawLinLogger<crate::writer::VecWriter> {
@@ -83,13 +399,7 @@ impl RawLinLogger<crate::writer::VecWriter> {
     /// Examples: "LIN", "LIN1", "Body_LIN", etc.
     pub fn with_source_name(source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::from_writer(crate::writer::VecWriter::new());
-        Ok(Self {
-            writer,
-            source_name: String::from(source_name),
-            buffer: Vec::new(),
-            channel_group: None,
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw LIN logger with a custom bus name.
@@ -103,19 +413,15 @@ impl RawLinLogger<crate::writer::VecWriter> {
     pub fn with_capacity(capacity: usize) -> crate::Result<Self> {
         let writer =
             crate::MdfWriter::from_writer(crate::writer::VecWriter::with_capacity(capacity));
-        Ok(Self {
-            writer,
-            source_name: String::from("LIN"),
-            buffer: Vec::with_capacity(capacity / LIN_FRAME_SIZE),
-            channel_group: None,
-            initialized: false,
-        })
+        let mut logger = Self::with_writer(writer, "LIN");
+        logger.frames.reserve(capacity / LinFrame::RECORD_SIZE);
+        Ok(logger)
     }
 
     /// Finalize the MDF file and return the bytes.
     pub fn finalize(mut self) -> crate::Result<Vec<u8>> {
         self.flush_and_finalize()?;
-        Ok(self.writer.into_inner().into_inner())
+        Ok(self.core.into_writer().into_inner().into_inner())
     }
 }
 
@@ -129,13 +435,7 @@ impl RawLinLogger<crate::writer::FileWriter> {
     /// Create a new raw LIN logger that writes to a file with custom source name.
     pub fn new_file_with_source_name(path: &str, source_name: &str) -> crate::Result<Self> {
         let writer = crate::MdfWriter::new(path)?;
-        Ok(Self {
-            writer,
-            source_name: String::from(source_name),
-            buffer: Vec::new(),
-            channel_group: None,
-            initialized: false,
-        })
+        Ok(Self::with_writer(writer, source_name))
     }
 
     /// Create a new raw LIN logger that writes to a file with custom bus name.
@@ -152,11 +452,27 @@ impl RawLinLogger<crate::writer::FileWriter> {
 }
 
 impl<W: crate::writer::MdfWrite> RawLinLogger<W> {
+    fn with_writer(writer: crate::MdfWriter<W>, source_name: &str) -> Self {
+        Self {
+            core: BusLoggerCore::new(writer, source_name),
+            frames: BusChannel::new(),
+            counts: FrameCounts::default(),
+            checksum_errors: BusChannel::with_names("ChecksumError", "LIN_ChecksumError"),
+            sync_errors: BusChannel::new(),
+            transmission_errors: BusChannel::new(),
+            wakeups: BusChannel::new(),
+            sleeps: BusChannel::new(),
+            schedule_switches: BusChannel::new(),
+            schedule_slots: BusChannel::new(),
+            diag: DiagGroup::new(),
+        }
+    }
+
     /// Set the source name for metadata.
     ///
     /// Must be called before logging any frames.
     pub fn set_source_name(&mut self, name: &str) {
-        self.source_name = String::from(name);
+        self.core.set_source_name(name);
     }
 
     /// Set the LIN bus name for source metadata.
@@ -166,6 +482,58 @@ impl<W: crate::writer::MdfWrite> RawLinLogger<W> {
         self.set_source_name(name);
     }
 
+    /// Write the buffered frames to the MDF writer once `frames` frames
+    /// (LIN frames, errors and events) are buffered.
+    ///
+    /// This keeps memory bounded without calling `flush()`. An error of an
+    /// automatic flush is returned by the next call to
+    /// [`flush`](Self::flush).
+    ///
+    /// Default: no limit
+    pub fn set_max_buffered_frames(&mut self, frames: usize) {
+        self.core.set_max_buffered_frames(frames);
+    }
+
+    /// Set the flush policy of the MDF writer.
+    ///
+    /// The policy decides when records written to the MDF writer are flushed
+    /// to disk. Combine it with
+    /// [`set_max_buffered_frames`](Self::set_max_buffered_frames) to stream
+    /// long captures to a file with bounded memory.
+    pub fn set_flush_policy(&mut self, policy: FlushPolicy) {
+        self.core.set_flush_policy(policy);
+    }
+
+    /// Set the reassembler for diagnostic frames, e.g. with a custom
+    /// timeout.
+    ///
+    /// Must be called before logging any frames.
+    pub fn set_diag_reassembler(&mut self, reassembler: LinTpReassembler) {
+        self.diag.reassembler = reassembler;
+    }
+
+    /// Get the number of frames, errors and events buffered and not yet
+    /// written to the MDF writer.
+    pub fn buffered_frame_count(&self) -> usize {
+        self.frames.buffered()
+            + self.checksum_errors.buffered()
+            + self.sync_errors.buffered()
+            + self.transmission_errors.buffered()
+            + self.wakeups.buffered()
+            + self.sleeps.buffered()
+            + self.schedule_switches.buffered()
+            + self.schedule_slots.buffered()
+            + self.diag.buffered()
+    }
+
+    /// Flush if the buffered frames reached the buffer limit.
+    fn maybe_flush(&mut self) {
+        if self.core.is_full(self.buffered_frame_count()) {
+            let result = self.flush_buffers();
+            self.core.record_auto_flush(result);
+        }
+    }
+
     /// Log a raw LIN frame by ID and data.
     ///
     /// Uses enhanced checksum (LIN 2.x) by default.
@@ -208,7 +576,12 @@ impl<W: crate::writer::MdfWrite> RawLinLogger<W> {
     /// * `timestamp_us` - Timestamp in microseconds
     /// * `frame` - The LIN frame to log (consumed)
     pub fn log_frame(&mut self, timestamp_us: u64, frame: LinFrame) -> bool {
-        self.buffer.push(TimestampedFrame::new(timestamp_us, frame));
+        self.counts.record(&frame);
+        if !frame.flags.has_error() {
+            self.diag.process(frame.id, timestamp_us, frame.data());
+        }
+        self.frames.push(timestamp_us, frame);
+        self.maybe_flush();
         true
     }
 
@@ -234,96 +607,261 @@ impl<W: crate::writer::MdfWrite> RawLinLogger<W> {
         self.log_frame(timestamp_us, frame)
     }
 
-    /// Flush buffered data to the MDF writer.
-    pub fn flush(&mut self) -> crate::Result<()> {
-        if !self.initialized {
-            self.initialize_mdf()?;
-        }
+    /// Log a frame received with a wrong checksum.
+    ///
+    /// The frame is stored in the `{source_name}_ChecksumError` channel
+    /// group in the 12-byte `LIN_Frame` format, with the checksum error
+    /// flag set and the received checksum.
+    ///
+    /// # Arguments
+    /// * `id` - Frame ID (0-63)
+    /// * `timestamp_us` - Timestamp in microseconds
+    /// * `data` - Received frame data (up to 8 bytes)
+    /// * `checksum` - Received checksum
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_checksum_error(
+        &mut self,
+        id: u8,
+        timestamp_us: u64,
+        data: &[u8],
+        checksum: u8,
+    ) -> bool {
+        let mut frame = LinFrame::with_enhanced_checksum(id, data);
+        frame.flags = LinFlags::from_byte(frame.flags.to_byte() | LinFlags::CHECKSUM_ERROR);
+        frame.checksum = checksum;
+        self.checksum_errors.push(timestamp_us, frame);
+        self.maybe_flush();
+        true
+    }
 
-        if self.buffer.is_empty() {
-            return Ok(());
-        }
+    /// Log a failed synchronization on the sync byte of a header.
+    ///
+    /// Sync errors are stored in the `{source_name}_SyncError` channel
+    /// group as a 4-byte `LIN_SyncError` ByteArray:
+    /// - Bytes 0-3: Measured baud rate (little-endian, 0 if unknown)
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_sync_error(&mut self, timestamp_us: u64, baudrate: u32) -> bool {
+        self.sync_errors.push(timestamp_us, SyncError { baudrate });
+        self.maybe_flush();
+        true
+    }
+
+    /// Log a header that no slave responded to.
+    ///
+    /// Transmission errors are stored in the `{source_name}_TransmissionError`
+    /// channel group as a 1-byte `LIN_TransmissionError` ByteArray:
+    /// - Byte 0: Frame ID
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_no_response(&mut self, id: u8, timestamp_us: u64) -> bool {
+        let id = id & MAX_LIN_ID;
+        self.transmission_errors
+            .push(timestamp_us, TransmissionError { id });
+        self.maybe_flush();
+        true
+    }
 
-        self.write_frames()?;
-        self.buffer.clear();
+    /// Log a wake-up pulse.
+    ///
+    /// Wake-up events are stored in the `{source_name}_WakeUp` channel
+    /// group as a 1-byte `LIN_WakeUp` ByteArray:
+    /// - Byte 0: Dir (0 = received, 1 = sent by the logging node)
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_wakeup(&mut self, timestamp_us: u64, tx: bool) -> bool {
+        self.wakeups.push(timestamp_us, WakeUp { tx });
+        self.maybe_flush();
+        true
+    }
 
-        Ok(())
+    /// Log the cluster entering sleep mode.
+    ///
+    /// Sleep events are stored in the `{source_name}_Sleep` channel
+    /// group as a 1-byte `LIN_Sleep` ByteArray:
+    /// - Byte 0: Reason ([`LinSleepReason`])
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_sleep(&mut self, timestamp_us: u64, reason: LinSleepReason) -> bool {
+        self.sleeps.push(timestamp_us, Sleep { reason });
+        self.maybe_flush();
+        true
     }
 
-    /// Initialize the MDF file structure.
-    fn initialize_mdf(&mut self) -> crate::Result<()> {
-        self.writer.init_mdf_file()?;
+    /// Log the master switching to another schedule table.
+    ///
+    /// Switches are stored in the `{source_name}_ScheduleSwitch` channel
+    /// group as a 1-byte `LIN_ScheduleSwitch` ByteArray:
+    /// - Byte 0: Index of the new schedule table
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_schedule_switch(&mut self, timestamp_us: u64, table: u8) -> bool {
+        self.schedule_switches
+            .push(timestamp_us, ScheduleSwitch { table });
+        self.maybe_flush();
+        true
+    }
 
-        let config = BusLoggerConfig {
-            source_name: self.source_name.clone(),
-            group_name: alloc::format!("{}_LIN_Frame", self.source_name),
-            data_channel_name: String::from("LIN_Frame"),
-            data_channel_bits: (LIN_FRAME_SIZE * 8) as u32,
-            source_block: crate::blocks::SourceBlock::lin_bus(),
+    /// Log the start of a schedule table slot.
+    ///
+    /// Slots are stored in the `{source_name}_ScheduleSlot` channel group as
+    /// an 8-byte `LIN_ScheduleSlot` ByteArray:
+    /// - Byte 0: Table index
+    /// - Byte 1: Slot index within the table
+    /// - Byte 2: Entry type ([`ScheduleEntryType`])
+    /// - Byte 3: Frame ID
+    /// - Bytes 4-7: Configured slot delay in microseconds (little-endian)
+    ///
+    /// The difference between the timestamps of consecutive slots and the
+    /// configured delay is the scheduling jitter of the master.
+    ///
+    /// # Arguments
+    /// * `timestamp_us` - Start of the slot in microseconds
+    /// * `table` - Index of the running schedule table
+    /// * `slot` - Index of the slot within the table
+    /// * `entry_type` - Kind of schedule entry
+    /// * `id` - Frame ID of the entry (0-63)
+    /// * `delay_us` - Configured slot delay in microseconds
+    ///
+    /// # Returns
+    /// Always returns `true`
+    pub fn log_schedule_slot(
+        &mut self,
+        timestamp_us: u64,
+        table: u8,
+        slot: u8,
+        entry_type: ScheduleEntryType,
+        id: u8,
+        delay_us: u32,
+    ) -> bool {
+        let frame = ScheduleSlot {
+            table,
+            slot,
+            entry_type,
+            id: id & MAX_LIN_ID,
+            delay_us,
         };
+        self.schedule_slots.push(timestamp_us, frame);
+        self.maybe_flush();
+        true
+    }
 
-        let (cg, _data_ch) = init_bus_channel_group(&mut self.writer, &config)?;
-
-        self.channel_group = Some(cg);
-        self.initialized = true;
-        Ok(())
+    /// Flush buffered data to the MDF writer.
+    ///
+    /// If an automatic flush failed since the last call, its error is
+    /// returned instead.
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.core.take_auto_flush_error()?;
+        self.flush_buffers()
     }
 
-    /// Write frames to the MDF file.
-    fn write_frames(&mut self) -> crate::Result<()> {
-        let cg = match &self.channel_group {
-            Some(cg) => cg.clone(),
-            None => return Ok(()),
-        };
+    /// Write and clear all buffers.
+    fn flush_buffers(&mut self) -> crate::Result<()> {
+        self.core.ensure_initialized()?;
 
-        write_timestamped_frames(&mut self.writer, &cg, self.buffer.drain(..))?;
-        Ok(())
+        self.frames.write(&mut self.core)?;
+        self.checksum_errors.write(&mut self.core)?;
+        self.sync_errors.write(&mut self.core)?;
+        self.transmission_errors.write(&mut self.core)?;
+        self.wakeups.write(&mut self.core)?;
+        self.sleeps.write(&mut self.core)?;
+        self.schedule_switches.write(&mut self.core)?;
+        self.schedule_slots.write(&mut self.core)?;
+        let (writer, source_name) = self.core.writer_and_source_name();
+        self.diag.write(writer, source_name)
     }
 
     /// Flush and finalize the MDF file.
     fn flush_and_finalize(&mut self) -> crate::Result<()> {
         self.flush()?;
-        self.writer.finalize()
+        self.core.finalize()
     }
 
-    /// Get the total number of frames logged.
+    /// Get the total number of frames logged, including frames already
+    /// flushed.
     pub fn total_frame_count(&self) -> usize {
-        self.buffer.len()
+        self.counts.total()
     }
 
     /// Get the number of unique frame IDs.
     pub fn unique_id_count(&self) -> usize {
-        let mut ids = alloc::collections::BTreeSet::new();
-        for entry in &self.buffer {
-            ids.insert(entry.frame.id);
-        }
-        ids.len()
+        self.counts
+            .per_id
+            .iter()
+            .filter(|&&count| count > 0)
+            .count()
     }
 
     /// Get the number of frames for a specific ID.
     pub fn frame_count_for_id(&self, id: u8) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| e.frame.id == (id & super::frame::MAX_LIN_ID))
-            .count()
+        self.counts.per_id[(id & MAX_LIN_ID) as usize]
     }
 
     /// Get count of transmitted frames.
     pub fn tx_frame_count(&self) -> usize {
-        self.buffer.iter().filter(|e| e.frame.flags.is_tx()).count()
+        self.counts.tx
     }
 
     /// Get count of received frames.
     pub fn rx_frame_count(&self) -> usize {
-        self.buffer.iter().filter(|e| e.frame.flags.is_rx()).count()
+        self.counts.total() - self.counts.tx
     }
 
     /// Get count of frames with errors.
     pub fn error_frame_count(&self) -> usize {
-        self.buffer
-            .iter()
-            .filter(|e| e.frame.flags.has_error())
-            .count()
+        self.counts.errors
+    }
+
+    /// Get the number of checksum errors logged.
+    pub fn checksum_error_count(&self) -> usize {
+        self.checksum_errors.count()
+    }
+
+    /// Get the number of sync errors logged.
+    pub fn sync_error_count(&self) -> usize {
+        self.sync_errors.count()
+    }
+
+    /// Get the number of headers without response logged.
+    pub fn transmission_error_count(&self) -> usize {
+        self.transmission_errors.count()
+    }
+
+    /// Get the number of wake-up events logged.
+    pub fn wakeup_count(&self) -> usize {
+        self.wakeups.count()
+    }
+
+    /// Get the number of sleep events logged.
+    pub fn sleep_count(&self) -> usize {
+        self.sleeps.count()
+    }
+
+    /// Get the number of schedule table switches logged.
+    pub fn schedule_switch_count(&self) -> usize {
+        self.schedule_switches.count()
+    }
+
+    /// Get the number of schedule table slots logged.
+    pub fn schedule_slot_count(&self) -> usize {
+        self.schedule_slots.count()
+    }
+
+    /// Get the number of reassembled diagnostic payloads logged.
+    pub fn diag_message_count(&self) -> usize {
+        self.diag.count
+    }
+
+    /// The reassembler for diagnostic frames.
+    pub fn diag_reassembler(&self) -> &LinTpReassembler {
+        &self.diag.reassembler
     }
 }
 
@@ -372,7 +910,12 @@ mod tests {
 
         assert_eq!(logger.total_frame_count(), 1);
         // Classic checksum should not set enhanced flag
-        assert!(!logger.buffer[0].frame.flags.uses_enhanced_checksum());
+        assert!(
+            !logger.frames.frames()[0]
+                .frame
+                .flags
+                .uses_enhanced_checksum()
+        );
 
         let mdf_bytes = logger.finalize().unwrap();
         assert!(!mdf_bytes.is_empty());
@@ -401,15 +944,216 @@ mod tests {
         assert!(!mdf_bytes.is_empty());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_and_event_groups() {
+        let mut logger = RawLinLogger::with_source_name("Body_LIN").unwrap();
+        logger.log(0x20, 1000, &[0x01, 0x02]);
+        assert!(logger.log_checksum_error(0x21, 2000, &[0x03, 0x04], 0x55));
+        assert!(logger.log_sync_error(3000, 18_500));
+        assert!(logger.log_no_response(0x22, 4000));
+        assert!(logger.log_sleep(5000, LinSleepReason::BusIdle));
+        assert!(logger.log_wakeup(6000, true));
+
+        assert_eq!(logger.total_frame_count(), 1);
+        assert_eq!(logger.checksum_error_count(), 1);
+        assert_eq!(logger.sync_error_count(), 1);
+        assert_eq!(logger.transmission_error_count(), 1);
+        assert_eq!(logger.sleep_count(), 1);
+        assert_eq!(logger.wakeup_count(), 1);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("test_lin_error_groups.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let data = |name: &str| {
+            let group = groups
+                .iter()
+                .find(|g| g.name().unwrap().as_deref() == Some(name))
+                .unwrap();
+            match group.channels()[1].values().unwrap().as_slice() {
+                [Some(crate::DecodedValue::ByteArray(bytes))] => bytes.clone(),
+                other => panic!("unexpected values {:?}", other),
+            }
+        };
+
+        let checksum_error = data("Body_LIN_ChecksumError");
+        assert_eq!(checksum_error[..4], [0x21, 2, 0x84, 0x55]);
+        assert_eq!(checksum_error[4..6], [0x03, 0x04]);
+        assert_eq!(data("Body_LIN_SyncError"), 18_500u32.to_le_bytes());
+        assert_eq!(data("Body_LIN_TransmissionError"), [0x22]);
+        assert_eq!(data("Body_LIN_WakeUp"), [1]);
+        assert_eq!(data("Body_LIN_Sleep"), [LinSleepReason::BusIdle as u8]);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_schedule_tracking() {
+        let mut logger = RawLinLogger::with_source_name("Body_LIN").unwrap();
+        assert!(logger.log_schedule_switch(0, 1));
+        assert!(logger.log_schedule_slot(0, 1, 0, ScheduleEntryType::Unconditional, 0x20, 10_000));
+        assert!(logger.log_schedule_slot(10_150, 1, 1, ScheduleEntryType::Sporadic, 0x21, 20_000));
+        assert!(logger.log_schedule_switch(30_000, 2));
+
+        assert_eq!(logger.schedule_switch_count(), 2);
+        assert_eq!(logger.schedule_slot_count(), 2);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        let temp_path = std::env::temp_dir().join("test_lin_schedule.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let group = |name: &str| {
+            groups
+                .iter()
+                .find(|g| g.name().unwrap().as_deref() == Some(name))
+                .unwrap()
+        };
+        let switches = group("Body_LIN_ScheduleSwitch").channels();
+        assert_eq!(
+            switches[1].values().unwrap(),
+            [1, 2].map(|table| Some(crate::DecodedValue::ByteArray(alloc::vec![table])))
+        );
+
+        let slots = group("Body_LIN_ScheduleSlot").channels();
+        assert_eq!(
+            slots[0].values().unwrap(),
+            [0.0, 0.01015].map(|t| Some(crate::DecodedValue::Float(t)))
+        );
+        let mut expected = alloc::vec![1, 1, ScheduleEntryType::Sporadic as u8, 0x21];
+        expected.extend_from_slice(&20_000u32.to_le_bytes());
+        assert_eq!(
+            slots[1].values().unwrap()[1],
+            Some(crate::DecodedValue::ByteArray(expected))
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_diag_reassembly() {
+        use crate::DecodedValue::{ByteArray, UnsignedInteger};
+
+        let mut logger = RawLinLogger::with_source_name("Body_LIN").unwrap();
+        // Request as a single frame, response in a first and a consecutive frame
+        logger.log(
+            0x3C,
+            1000,
+            &[0x0A, 0x03, 0x22, 0xF1, 0x90, 0xFF, 0xFF, 0xFF],
+        );
+        logger.log(0x3D, 11_000, &[0x0A, 0x10, 8, 0x62, 0xF1, 0x90, 1, 2]);
+        logger.log(0x20, 15_000, &[0x01]);
+        logger.log(0x3D, 21_000, &[0x0A, 0x21, 3, 4, 5, 0xFF, 0xFF, 0xFF]);
+        // Go-to-sleep command
+        logger.log(
+            0x3C,
+            30_000,
+            &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        );
+
+        assert_eq!(logger.total_frame_count(), 5);
+        assert_eq!(logger.diag_message_count(), 2);
+        assert_eq!(logger.diag_reassembler().pending_transfers(), 0);
+
+        let mdf_bytes = logger.finalize().unwrap();
+        assert!(crate::writer::verify_mdf_bytes(&mdf_bytes).is_ok());
+        let temp_path = std::env::temp_dir().join("test_lin_diag.mf4");
+        std::fs::write(&temp_path, &mdf_bytes).unwrap();
+
+        let mdf = crate::MDF::from_file(temp_path.to_str().unwrap()).unwrap();
+        let groups = mdf.channel_groups();
+        let diag = groups
+            .iter()
+            .find(|g| g.name().unwrap().as_deref() == Some("Body_LIN_LIN_Diag"))
+            .unwrap();
+        let channels = diag.channels();
+        assert_eq!(channels[4].name().unwrap().as_deref(), Some("LIN_Diag"));
+        assert_eq!(
+            channels[1].values().unwrap(),
+            [Some(UnsignedInteger(0x3C)), Some(UnsignedInteger(0x3D))]
+        );
+        assert_eq!(
+            channels[3].values().unwrap(),
+            [Some(UnsignedInteger(0x22)), Some(UnsignedInteger(0x62))]
+        );
+        assert_eq!(
+            channels[4].values().unwrap(),
+            [
+                Some(ByteArray(alloc::vec![0x22, 0xF1, 0x90])),
+                Some(ByteArray(alloc::vec![0x62, 0xF1, 0x90, 1, 2, 3, 4, 5])),
+            ]
+        );
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_streaming_with_buffer_limit() {
+        let temp_path = std::env::temp_dir().join("test_lin_streaming.mf4");
+        let path = temp_path.to_str().unwrap();
+
+        let mut logger = RawLinLogger::new_file_with_source_name(path, "Body_LIN").unwrap();
+        logger.set_max_buffered_frames(10);
+        logger.set_flush_policy(FlushPolicy::EveryNRecords(10));
+
+        for i in 0..25u64 {
+            assert!(logger.log(0x20 + (i % 2) as u8, i * 1000, &[i as u8, 0x02]));
+            assert!(logger.buffered_frame_count() < 10);
+        }
+        // Event groups created after the first automatic flush
+        assert!(logger.log_no_response(0x22, 25_000));
+        assert!(logger.log_wakeup(26_000, false));
+
+        assert_eq!(logger.total_frame_count(), 25);
+        assert_eq!(logger.frame_count_for_id(0x20), 13);
+        assert_eq!(logger.unique_id_count(), 2);
+        assert_eq!(logger.rx_frame_count(), 25);
+        assert_eq!(logger.transmission_error_count(), 1);
+        logger.finalize_file().unwrap();
+
+        let mdf = crate::MDF::from_file(path).unwrap();
+        let groups = mdf.channel_groups();
+        let values = |name: &str| {
+            groups
+                .iter()
+                .find(|g| g.name().unwrap().as_deref() == Some(name))
+                .unwrap()
+                .channels()[1]
+                .values()
+                .unwrap()
+        };
+        let frames = values("Body_LIN_LIN_Frame");
+        assert_eq!(frames.len(), 25);
+        // Last frame: ID 0x20, length 2, enhanced checksum, data 24
+        match &frames[24] {
+            Some(crate::DecodedValue::ByteArray(bytes)) => {
+                assert_eq!(bytes[..3], [0x20, 2, LinFlags::ENHANCED_CHECKSUM]);
+                assert_eq!(bytes[4], 24);
+            }
+            other => panic!("unexpected value {:?}", other),
+        }
+        assert_eq!(values("Body_LIN_TransmissionError").len(), 1);
+        assert_eq!(values("Body_LIN_WakeUp").len(), 1);
+
+        std::fs::remove_file(&temp_path).ok();
+    }
+
     #[test]
     fn test_source_name() {
         let logger = RawLinLogger::with_source_name("Body_LIN").unwrap();
-        assert_eq!(logger.source_name, "Body_LIN");
+        assert_eq!(logger.core.source_name(), "Body_LIN");
     }
 
     #[test]
     fn test_bus_name_alias() {
         let logger = RawLinLogger::with_bus_name("Body_LIN").unwrap();
-        assert_eq!(logger.source_name, "Body_LIN");
+        assert_eq!(logger.core.source_name(), "Body_LIN");
     }
 }
@@ -1,74 +1,847 @@
 use crate::{
-    Result,
-    blocks::{DataType, read_string_block},
-    parsing::{
-        MdfFile,
-        decoder::{DecodedValue, decode_channel_value},
-    },
-    writer::MdfWriter,
+    Error, Result,
+    blocks::{ChannelBlock, ConversionType, DataListBlock, DataType},
+    index::ByteRangeReader,
+    parsing::decoder::{DecodedValue, decode_channel_value},
+    writer::{DuplicateNamePolicy, MdfWrite, MdfWriter},
 };
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
 
+mod source;
+
+pub(crate) use source::SourceFile;
+use source::{BlockRecords, SignalValues, SourceChannelGroup, SourceDataGroup};
+
+/// How the time bases of merged files are aligned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TimeAlignment {
+    /// Master channel values are copied unchanged, so all files start at
+    /// their own t=0.
+    #[default]
+    None,
+    /// Align the files on the absolute start times of their header blocks.
+    ///
+    /// Master values are shifted by the start time of their file relative
+    /// to the earliest one, which becomes the start time of the result.
+    StartTime,
+    /// Shift the master values of each file by an offset in seconds, one per
+    /// input file.
+    Offsets(Vec<f64>),
+}
+
+/// Interpolation of channel values between samples in
+/// [`merge_resampled()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation between the neighboring samples.
+    #[default]
+    Linear,
+    /// The value of the last sample at or before the raster time
+    /// (zero-order hold).
+    Hold,
+}
+
+/// An input file of [`merge_to_writer()`].
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct ChannelMeta {
-    name: Option<String>,
-    data_type: DataType,
-    bit_offset: u8,
-    byte_offset: u32,
-    bit_count: u32,
-    channel_type: u8,
+pub enum MergeInput<'a> {
+    /// Path of a file on disk
+    Path(&'a str),
+    /// Complete contents of a file
+    Bytes(Vec<u8>),
+}
+
+impl MergeInput<'_> {
+    /// Read a complete file from a reader, e.g. a
+    /// [`Cursor`](std::io::Cursor) or a seekable network stream.
+    pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Ok(Self::Bytes(data))
+    }
+
+    /// Read a complete file of `file_size` bytes through a
+    /// [`ByteRangeReader`], e.g. from object storage.
+    pub fn from_range_reader<R: ByteRangeReader<Error = Error>>(
+        reader: &mut R,
+        file_size: u64,
+    ) -> Result<Self> {
+        Ok(Self::Bytes(reader.read_range(0, file_size)?))
+    }
+
+    pub(crate) fn open(self) -> Result<SourceFile> {
+        SourceFile::open(self)
+    }
+}
+
+impl<'a> From<&'a str> for MergeInput<'a> {
+    fn from(path: &'a str) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<Vec<u8>> for MergeInput<'_> {
+    fn from(data: Vec<u8>) -> Self {
+        Self::Bytes(data)
+    }
+}
+
+/// How the channel groups of merged files are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeMode {
+    /// Concatenate the records of groups with the same layout; other groups
+    /// are copied side by side.
+    #[default]
+    Groups,
+    /// Combine the channels of all groups into one channel group.
+    ///
+    /// For files sampled on the same raster, e.g. a GPS file and a CAN file
+    /// of the same logger: all groups must have the same number of records,
+    /// and only the first time channel is kept.
+    Channels,
+}
+
+/// Handling of channels whose name is already used in a shared group (see
+/// [`MergeMode::Channels`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NameConflict {
+    /// Append `_1`, `_2`, ... to the name until it is unique.
+    #[default]
+    Suffix,
+    /// Fail the merge.
+    Error,
+    /// Keep only the first channel with the name.
+    Skip,
+}
+
+/// Removal of signals that occur in more than one merged file, e.g. when
+/// recordings overlap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Deduplication {
+    /// Keep all data.
+    #[default]
+    None,
+    /// Keep a single copy of data that occurs more than once: groups with
+    /// the same layout and the same values (see [`MergeMode::Groups`]), or
+    /// channels with the same name and the same values (see
+    /// [`MergeMode::Channels`]).
+    IdenticalData,
+    /// Skip records whose time stamp is not after that of the last record
+    /// written to the channel group, so overlapping parts of time-sorted
+    /// recordings are written once.
+    Timestamps,
+}
+
+/// Options for [`merge_files_with_options()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeOptions {
+    time_alignment: TimeAlignment,
+    mode: MergeMode,
+    name_conflict: NameConflict,
+    deduplication: Deduplication,
+}
+
+impl MergeOptions {
+    /// Default options: groups are matched by layout and time values are
+    /// copied unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Align the time bases of the merged files.
+    pub fn with_time_alignment(mut self, alignment: TimeAlignment) -> Self {
+        self.set_time_alignment(alignment);
+        self
+    }
+
+    /// Align the time bases of the merged files.
+    pub fn set_time_alignment(&mut self, alignment: TimeAlignment) {
+        self.time_alignment = alignment;
+    }
+
+    /// How the time bases of the merged files are aligned.
+    pub fn time_alignment(&self) -> &TimeAlignment {
+        &self.time_alignment
+    }
+
+    /// Set how channel groups are combined.
+    pub fn with_mode(mut self, mode: MergeMode) -> Self {
+        self.set_mode(mode);
+        self
+    }
+
+    /// Set how channel groups are combined.
+    pub fn set_mode(&mut self, mode: MergeMode) {
+        self.mode = mode;
+    }
+
+    /// How channel groups are combined.
+    pub fn mode(&self) -> MergeMode {
+        self.mode
+    }
+
+    /// Set the handling of duplicate channel names in a shared group.
+    pub fn with_name_conflict(mut self, policy: NameConflict) -> Self {
+        self.set_name_conflict(policy);
+        self
+    }
+
+    /// Set the handling of duplicate channel names in a shared group.
+    pub fn set_name_conflict(&mut self, policy: NameConflict) {
+        self.name_conflict = policy;
+    }
+
+    /// Handling of duplicate channel names in a shared group.
+    pub fn name_conflict(&self) -> NameConflict {
+        self.name_conflict
+    }
+
+    /// Set how signals contained in more than one file are deduplicated.
+    pub fn with_deduplication(mut self, deduplication: Deduplication) -> Self {
+        self.set_deduplication(deduplication);
+        self
+    }
+
+    /// Set how signals contained in more than one file are deduplicated.
+    pub fn set_deduplication(&mut self, deduplication: Deduplication) {
+        self.deduplication = deduplication;
+    }
+
+    /// How signals contained in more than one file are deduplicated.
+    pub fn deduplication(&self) -> Deduplication {
+        self.deduplication
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct GroupMeta {
-    record_id_size: u8,
-    channels: Vec<ChannelMeta>,
+pub(crate) struct ChannelMeta {
+    pub(crate) name: Option<String>,
+    pub(crate) data_type: DataType,
+    pub(crate) bit_offset: u8,
+    pub(crate) byte_offset: u32,
+    pub(crate) bit_count: u32,
+    pub(crate) channel_type: u8,
+    pub(crate) sync_type: u8,
+}
+
+impl ChannelMeta {
+    /// Whether this is a master channel with stored time values.
+    pub(crate) fn is_time_master(&self) -> bool {
+        self.channel_type == 2 && self.sync_type == 1
+    }
+
+    /// Whether this is a non-master channel with numeric values in the
+    /// record.
+    fn is_numeric(&self) -> bool {
+        matches!(self.channel_type, 0 | 4 | 5)
+            && matches!(
+                self.data_type,
+                DataType::UnsignedIntegerLE
+                    | DataType::UnsignedIntegerBE
+                    | DataType::SignedIntegerLE
+                    | DataType::SignedIntegerBE
+                    | DataType::FloatLE
+                    | DataType::FloatBE
+            )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GroupMeta {
+    pub(crate) record_id_size: u8,
+    pub(crate) channels: Vec<ChannelMeta>,
+}
+
+/// A channel group of an input file.
+pub(crate) struct SourceGroup<'a> {
+    pub(crate) file: &'a SourceFile,
+    pub(crate) dg: &'a SourceDataGroup,
+    pub(crate) cg: &'a SourceChannelGroup,
+    pub(crate) meta: GroupMeta,
+    /// Raw value added to the values of time master channels
+    time_offset: f64,
 }
 
-struct MergedGroup {
+/// A channel group of the merged file.
+struct OutputGroup {
     meta: GroupMeta,
-    data: Vec<Vec<DecodedValue>>, // per channel
+    /// Sets of source groups (indices into the source list) whose records
+    /// are combined side by side; the sets are written one after the other
+    runs: Vec<Vec<usize>>,
+    /// Position of the value of each channel: source within the run and
+    /// channel within the source group
+    columns: Vec<(usize, usize)>,
+}
+
+/// Values of the records of a source group, read one record at a time.
+pub(crate) struct SourceReader<'a> {
+    pub(crate) source: &'a SourceGroup<'a>,
+    records: BlockRecords<'a>,
+    /// Signal data of VLSD channels, `None` for channels in the record
+    vlsd: Vec<Option<SignalValues<'a>>>,
+}
+
+impl<'a> SourceReader<'a> {
+    pub(crate) fn new(source: &'a SourceGroup<'a>) -> Result<Self> {
+        let vlsd = source
+            .cg
+            .channels
+            .iter()
+            .zip(&source.cg.signal_data)
+            .map(|(ch, blocks)| {
+                let is_vlsd = ch.channel_type == 1 && ch.data_addr != 0;
+                is_vlsd.then(|| SignalValues::new(source.file, blocks))
+            })
+            .collect();
+        Ok(Self {
+            source,
+            records: BlockRecords::new(source.file, source.dg, source.cg)?,
+            vlsd,
+        })
+    }
+
+    /// Decode the values of the next record into `values`; `false` after
+    /// the last record.
+    pub(crate) fn next_values(&mut self, values: &mut Vec<DecodedValue>) -> Result<bool> {
+        let Some(record) = self.records.next_record()? else {
+            return Ok(false);
+        };
+        let source = self.source;
+        let record_id_size = source.meta.record_id_size as usize;
+        values.clear();
+        let channels = source.cg.channels.iter().zip(&source.meta.channels);
+        for ((ch, meta), vlsd) in channels.zip(&mut self.vlsd) {
+            let bytes = match vlsd {
+                Some(signal) => match signal.next_value()? {
+                    Some(bytes) => bytes,
+                    None => return Ok(false),
+                },
+                None => record,
+            };
+            let mut value =
+                decode_channel_value(bytes, record_id_size, ch).unwrap_or(DecodedValue::Unknown);
+            if source.time_offset != 0.0 && meta.is_time_master() {
+                value = shift_time(value, source.time_offset);
+            }
+            values.push(value);
+        }
+        Ok(true)
+    }
 }
 
-fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>> {
+/// Add the raw `offset` to a master channel value, keeping its type.
+fn shift_time(value: DecodedValue, offset: f64) -> DecodedValue {
+    match value {
+        DecodedValue::Float(t) => DecodedValue::Float(t + offset),
+        // Integer time stamps are rounded; `as` saturates out of range values
+        DecodedValue::UnsignedInteger(t) => {
+            DecodedValue::UnsignedInteger((t as f64 + offset).round() as u64)
+        }
+        DecodedValue::SignedInteger(t) => {
+            DecodedValue::SignedInteger((t as f64 + offset).round() as i64)
+        }
+        other => other,
+    }
+}
+
+/// Seconds per unit of a time value; no unit means seconds.
+fn seconds_per_unit(unit: Option<&str>) -> Option<f64> {
+    match unit.map(str::trim).unwrap_or_default() {
+        "" | "s" => Some(1.0),
+        "ms" => Some(1e-3),
+        "us" | "\u{b5}s" | "\u{3bc}s" => Some(1e-6),
+        "ns" => Some(1e-9),
+        "min" => Some(60.0),
+        "h" => Some(3600.0),
+        _ => None,
+    }
+}
+
+/// Convert a time offset in seconds into the raw domain of the time master
+/// channel `channel`, whose physical values have the unit `unit`.
+///
+/// Only identity and linear conversions can be inverted for an offset.
+fn raw_time_offset(channel: &ChannelBlock, unit: Option<&str>, offset: f64) -> Result<f64> {
+    let unsupported = |what: String| {
+        Error::BlockSerializationError(format!("Cannot shift time master channel {}", what))
+    };
+    let seconds = seconds_per_unit(unit)
+        .ok_or_else(|| unsupported(format!("with unit {:?}", unit.unwrap_or_default())))?;
+    let factor = match &channel.conversion {
+        None => 1.0,
+        Some(cc) => match (cc.conversion_type, cc.values.get(1)) {
+            (ConversionType::Identity, _) | (ConversionType::Linear, None) => 1.0,
+            (ConversionType::Linear, Some(&factor)) if factor != 0.0 && factor.is_finite() => {
+                factor
+            }
+            (conversion_type, _) => {
+                return Err(unsupported(format!(
+                    "with {:?} conversion",
+                    conversion_type
+                )));
+            }
+        },
+    };
+    Ok(offset / seconds / factor)
+}
+
+/// Collect the channel groups of a file, with `time_offset` seconds to add
+/// to the values of time master channels.
+///
+/// # Errors
+/// Returns an error if the offset is not zero and the time master channel
+/// of a group cannot be converted from seconds, see [`raw_time_offset()`].
+pub(crate) fn source_groups(file: &SourceFile, time_offset: f64) -> Result<Vec<SourceGroup<'_>>> {
     let mut groups = Vec::new();
-    let mmap = &file.mmap;
     for dg in &file.data_groups {
-        let record_id_size = dg.block.record_id_size;
         for cg in &dg.channel_groups {
-            let mut metas = Vec::new();
-            for ch in &cg.raw_channels {
-                let name = read_string_block(mmap, ch.block.name_addr)?;
-                metas.push(ChannelMeta {
-                    name,
-                    data_type: ch.block.data_type,
-                    bit_offset: ch.block.bit_offset,
-                    byte_offset: ch.block.byte_offset,
-                    bit_count: ch.block.bit_count,
-                    channel_type: ch.block.channel_type,
-                });
-            }
-            let mut data: Vec<Vec<DecodedValue>> = metas.iter().map(|_| Vec::new()).collect();
-            for (idx, ch) in cg.raw_channels.iter().enumerate() {
-                let iter = ch.records(dg, cg, mmap)?;
-                for rec in iter {
-                    let bytes = rec?;
-                    let val = decode_channel_value(bytes, record_id_size as usize, &ch.block)
-                        .unwrap_or(DecodedValue::Unknown);
-                    data[idx].push(val);
+            let channels: Vec<ChannelMeta> = cg
+                .channels
+                .iter()
+                .zip(&cg.channel_names)
+                .map(|(ch, name)| ChannelMeta {
+                    name: name.clone(),
+                    data_type: ch.data_type,
+                    bit_offset: ch.bit_offset,
+                    byte_offset: ch.byte_offset,
+                    bit_count: ch.bit_count,
+                    channel_type: ch.channel_type,
+                    sync_type: ch.sync_type,
+                })
+                .collect();
+            let time = channels.iter().position(ChannelMeta::is_time_master);
+            let time_offset = match time {
+                Some(time) if time_offset != 0.0 => {
+                    let unit = cg.channel_units[time].as_deref();
+                    raw_time_offset(&cg.channels[time], unit, time_offset)?
                 }
-            }
-            groups.push(MergedGroup {
+                _ => 0.0,
+            };
+            groups.push(SourceGroup {
+                file,
+                dg,
+                cg,
                 meta: GroupMeta {
-                    record_id_size,
-                    channels: metas,
+                    record_id_size: dg.block.record_id_size,
+                    channels,
                 },
-                data,
+                time_offset,
             });
         }
     }
     Ok(groups)
 }
 
+/// A sample of the resampled channels of a source group: time and values.
+type Sample = (f64, Vec<f64>);
+
+/// Numeric channels of a source group, read along a time raster.
+struct ResampleCursor<'a> {
+    reader: SourceReader<'a>,
+    /// Index of the time master channel
+    time: usize,
+    /// Indices of the resampled channels
+    channels: Vec<usize>,
+    raw: Vec<DecodedValue>,
+    /// Last sample at or before the current raster time
+    prev: Option<Sample>,
+    /// First sample after the current raster time
+    next: Option<Sample>,
+}
+
+impl<'a> ResampleCursor<'a> {
+    fn new(source: &'a SourceGroup<'a>, time: usize) -> Result<Self> {
+        let channels = source
+            .meta
+            .channels
+            .iter()
+            .enumerate()
+            .filter(|(_, meta)| meta.is_numeric())
+            .map(|(c, _)| c)
+            .collect();
+        let mut cursor = Self {
+            reader: SourceReader::new(source)?,
+            time,
+            channels,
+            raw: Vec::new(),
+            prev: None,
+            next: None,
+        };
+        cursor.next = cursor.read()?;
+        Ok(cursor)
+    }
+
+    /// Read the next sample with a valid time, with conversions applied.
+    fn read(&mut self) -> Result<Option<Sample>> {
+        let source = self.reader.source;
+        while self.reader.next_values(&mut self.raw)? {
+            let physical = |c: usize| -> Result<Option<f64>> {
+                let value =
+                    source.cg.channels[c].apply_conversion_value(self.raw[c].clone(), &[])?;
+                Ok(value.as_f64())
+            };
+            let Some(time) = physical(self.time)?.filter(|t| !t.is_nan()) else {
+                continue;
+            };
+            let values = self
+                .channels
+                .iter()
+                .map(|&c| Ok(physical(c)?.unwrap_or(f64::NAN)))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(Some((time, values)));
+        }
+        Ok(None)
+    }
+
+    /// Move to the raster time `time`.
+    fn advance(&mut self, time: f64) -> Result<()> {
+        while self.next.as_ref().is_some_and(|(t, _)| *t <= time) {
+            self.prev = self.next.take();
+            self.next = self.read()?;
+        }
+        Ok(())
+    }
+
+    /// Whether `time` lies after the last sample.
+    fn is_done(&self, time: f64) -> bool {
+        self.next.is_none() && self.prev.as_ref().is_none_or(|(t, _)| time > *t)
+    }
+
+    /// Append the values at raster time `time`; outside the recorded range
+    /// the first or last sample is used.
+    fn push_values(&self, time: f64, interpolation: Interpolation, out: &mut Vec<DecodedValue>) {
+        match (&self.prev, &self.next) {
+            (Some((t0, v0)), Some((t1, v1))) if interpolation == Interpolation::Linear => {
+                let ratio = (time - t0) / (t1 - t0);
+                out.extend(
+                    v0.iter()
+                        .zip(v1)
+                        .map(|(a, b)| DecodedValue::Float(a + (b - a) * ratio)),
+                );
+            }
+            (Some((_, values)), _) | (None, Some((_, values))) => {
+                out.extend(values.iter().map(|&v| DecodedValue::Float(v)));
+            }
+            (None, None) => {
+                out.extend(self.channels.iter().map(|_| DecodedValue::Float(f64::NAN)));
+            }
+        }
+    }
+}
+
+/// Merge MDF files onto a common time raster.
+///
+/// All numeric channels of the channel groups with a time master channel
+/// are interpolated onto one raster of `rate` samples per second, from the
+/// earliest to the latest time stamp of all inputs. The result is a single
+/// channel group with a `Time` master channel followed by the resampled
+/// channels as 64-bit floats, ready for analysis. Values are physical, i.e.
+/// with conversions applied; before the first and after the last sample of
+/// a group its first or last values are repeated.
+///
+/// Groups without a time master channel and non-numeric channels are left
+/// out. Time stamps are used as stored, and channel names that occur more
+/// than once get a `_1`, `_2`, ... suffix. Like [`merge_files()`], records
+/// are read one at a time.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::merge::{Interpolation, merge_resampled};
+///
+/// merge_resampled("synced.mf4", &["can.mf4", "gps.mf4"], 100.0, Interpolation::Linear)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `output` - Path for the merged file
+/// * `inputs` - Paths of the input files
+/// * `rate` - Raster rate in samples per second
+/// * `interpolation` - How values between samples are computed
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] otherwise, e.g. if `rate` is
+/// not positive.
+pub fn merge_resampled(
+    output: &str,
+    inputs: &[&str],
+    rate: f64,
+    interpolation: Interpolation,
+) -> Result<()> {
+    if !(rate.is_finite() && rate > 0.0) {
+        return Err(Error::BlockSerializationError(format!(
+            "Invalid resampling rate {}",
+            rate
+        )));
+    }
+    let files = open_inputs(output, inputs)?;
+    let mut sources = Vec::new();
+    for file in &files {
+        sources.extend(source_groups(file, 0.0)?);
+    }
+    let mut cursors = Vec::new();
+    for source in &sources {
+        if let Some(time) = source.meta.channels.iter().position(|c| c.is_time_master()) {
+            cursors.push(ResampleCursor::new(source, time)?);
+        }
+    }
+
+    let mut writer = MdfWriter::new(output)?;
+    writer.init_mdf_file()?;
+    writer.set_duplicate_name_policy(DuplicateNamePolicy::Rename);
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |cn| {
+        cn.data_type = DataType::FloatLE;
+        cn.bit_count = 64;
+        cn.name = Some("Time".to_string());
+    })?;
+    writer.set_time_channel(&time_id)?;
+    let mut last_cn = time_id;
+    for cursor in &cursors {
+        let source = cursor.reader.source;
+        for &c in &cursor.channels {
+            last_cn = writer.add_channel(&cg_id, Some(&last_cn), |cn| {
+                cn.data_type = DataType::FloatLE;
+                cn.bit_count = 64;
+                cn.name = source.meta.channels[c].name.clone();
+            })?;
+        }
+    }
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+
+    let start = cursors
+        .iter()
+        .filter_map(|c| c.next.as_ref().map(|(t, _)| *t))
+        .min_by(f64::total_cmp);
+    if let Some(start) = start {
+        let mut record = Vec::new();
+        for k in 0u64.. {
+            // Computed from the index so that rounding errors do not add up
+            let time = start + k as f64 / rate;
+            for cursor in &mut cursors {
+                cursor.advance(time)?;
+            }
+            if cursors.iter().all(|c| c.is_done(time)) {
+                break;
+            }
+            record.clear();
+            record.push(DecodedValue::Float(time));
+            for cursor in &cursors {
+                cursor.push_values(time, interpolation, &mut record);
+            }
+            writer.write_record(&cg_id, &record)?;
+        }
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()
+}
+
+/// Layout of the records of a data group, which must match between the
+/// files of [`concatenate()`].
+#[derive(Debug, PartialEq)]
+struct DataGroupLayout {
+    record_id_size: u8,
+    /// Record ID, record size, invalidation bytes and channels of each group
+    groups: Vec<(u64, u32, u32, Vec<ChannelMeta>)>,
+}
+
+/// Record layout of the data groups of a file for [`concatenate()`].
+fn concatenation_layout(file: &SourceFile) -> Result<Vec<DataGroupLayout>> {
+    if file.is_unfinalized {
+        return Err(Error::BlockSerializationError(
+            "Cannot concatenate unfinalized files".to_string(),
+        ));
+    }
+    let mut layout = Vec::with_capacity(file.data_groups.len());
+    let mut sources = source_groups(file, 0.0)?.into_iter();
+    for dg in &file.data_groups {
+        let mut groups = Vec::with_capacity(dg.channel_groups.len());
+        for (cg, source) in dg.channel_groups.iter().zip(sources.by_ref()) {
+            // Offsets into signal data would have to be rewritten
+            let vlsd = cg.block.flags & 1 != 0
+                || cg
+                    .channels
+                    .iter()
+                    .any(|ch| ch.channel_type == 1 && ch.data_addr != 0);
+            if vlsd {
+                return Err(Error::BlockSerializationError(
+                    "Cannot concatenate files with VLSD channels; use merge_files()".to_string(),
+                ));
+            }
+            let block = &cg.block;
+            groups.push((
+                block.record_id,
+                block.record_size,
+                block.invalidation_size,
+                source.meta.channels,
+            ));
+        }
+        layout.push(DataGroupLayout {
+            record_id_size: dg.block.record_id_size,
+            groups,
+        });
+    }
+    Ok(layout)
+}
+
+/// Concatenate sequential recordings with the same channel layout.
+///
+/// Meant for the consecutive files a logger writes for one recording:
+/// instead of decoding and re-encoding all records like [`merge_files()`],
+/// the output is a copy of the first file whose data groups link the data
+/// blocks of all files in order, with the cycle counts of the channel
+/// groups adjusted. Data blocks (also compressed ones) are copied
+/// unchanged, which makes this much faster than a general merge.
+///
+/// Master values are copied unchanged, as are the metadata, attachments and
+/// events of the first file. Sample reductions of the first file are
+/// dropped, since they no longer cover the data.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::merge::concatenate;
+///
+/// concatenate("recording.mf4", &["part1.mf4", "part2.mf4", "part3.mf4"])?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `output` - Path for the concatenated file
+/// * `inputs` - Paths of the input files in recording order
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if the files do not have the
+/// same data groups, channel groups and channel layouts, have VLSD
+/// channels or are unfinalized, if `output` is one of the inputs, or if
+/// reading or writing fails.
+pub fn concatenate(output: &str, inputs: &[&str]) -> Result<()> {
+    let files = open_inputs(output, inputs)?;
+    let Some(first) = files.first() else {
+        return Err(Error::BlockSerializationError(
+            "No files to concatenate".to_string(),
+        ));
+    };
+    let layout = concatenation_layout(first)?;
+    for (file, path) in files.iter().zip(inputs).skip(1) {
+        if concatenation_layout(file)? != layout {
+            return Err(Error::BlockSerializationError(format!(
+                "{} does not have the channel layout of {}",
+                path, inputs[0]
+            )));
+        }
+    }
+
+    let mut out = BufWriter::new(File::create(output)?);
+    copy_range(first, 0, first.file_size, &mut out)?;
+    let mut pos = first.file_size;
+    // Links and counts of the first file to overwrite: (address, value)
+    let mut patches = Vec::new();
+
+    let mut dg_addr = first.header.first_dg_addr;
+    for (g, dg) in first.data_groups.iter().enumerate() {
+        let mut block_addrs = Vec::new();
+        let mut block_offsets = Vec::new();
+        let mut data_len = 0;
+        for (f, file) in files.iter().enumerate() {
+            for block in &file.data_groups[g].data_blocks {
+                let size = if block.is_compressed {
+                    // Offsets count the uncompressed data
+                    let length = file.read_range(block.file_offset + 32, 8)?;
+                    u64::from_le_bytes(length.try_into().unwrap())
+                } else {
+                    block.size.saturating_sub(24)
+                };
+                if size == 0 {
+                    continue;
+                }
+                if f == 0 {
+                    block_addrs.push(block.file_offset);
+                } else {
+                    pos = pad_to_alignment(&mut out, pos)?;
+                    block_addrs.push(pos);
+                    copy_range(file, block.file_offset, block.size, &mut out)?;
+                    pos += block.size;
+                }
+                block_offsets.push(data_len);
+                data_len += size;
+            }
+        }
+
+        let data_addr = match block_addrs.len() {
+            0 => 0,
+            1 => block_addrs[0],
+            _ => {
+                pos = pad_to_alignment(&mut out, pos)?;
+                let list = DataListBlock::new_with_offsets(block_addrs, block_offsets);
+                let bytes = list.to_bytes()?;
+                out.write_all(&bytes)?;
+                let list_addr = pos;
+                pos += bytes.len() as u64;
+                list_addr
+            }
+        };
+        patches.push((dg_addr + 40, data_addr));
+
+        let mut cg_addr = dg.block.first_cg_addr;
+        for (c, cg) in dg.channel_groups.iter().enumerate() {
+            let cycles = files
+                .iter()
+                .map(|file| file.data_groups[g].channel_groups[c].block.cycle_count)
+                .sum();
+            patches.push((cg_addr + 56, 0));
+            patches.push((cg_addr + cg.block.data_offset() + 8, cycles));
+            cg_addr = cg.block.next_cg_addr;
+        }
+        dg_addr = dg.block.next_dg_addr;
+    }
+
+    for (addr, value) in patches {
+        out.seek(SeekFrom::Start(addr))?;
+        out.write_all(&value.to_le_bytes())?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Copy `length` bytes at `offset` of `file` to `out`, a few MiB at a time.
+fn copy_range(file: &SourceFile, offset: u64, length: u64, out: &mut impl Write) -> Result<()> {
+    const CHUNK: u64 = 4 << 20;
+    let mut copied = 0;
+    while copied < length {
+        let len = CHUNK.min(length - copied);
+        out.write_all(&file.read_range(offset + copied, len)?)?;
+        copied += len;
+    }
+    Ok(())
+}
+
+/// Open the input files of a merge into `output`.
+///
+/// Inputs are read while the output is written, so `output` must not be
+/// one of them.
+fn open_inputs(output: &str, inputs: &[&str]) -> Result<Vec<SourceFile>> {
+    let output_path = std::fs::canonicalize(output).ok();
+    inputs
+        .iter()
+        .map(|&path| {
+            if output_path.is_some() && std::fs::canonicalize(path).ok() == output_path {
+                return Err(Error::BlockSerializationError(format!(
+                    "Output {} is also an input",
+                    output
+                )));
+            }
+            MergeInput::Path(path).open()
+        })
+        .collect()
+}
+
+/// Write zero bytes up to the next 8-byte boundary after `pos`.
+fn pad_to_alignment(out: &mut impl Write, pos: u64) -> Result<u64> {
+    let padding = (8 - pos % 8) % 8;
+    out.write_all(&[0u8; 8][..padding as usize])?;
+    Ok(pos + padding)
+}
+
 /// Merge two MDF files into a new file.
 ///
 /// All channel groups that share the same layout are concatenated. Groups that
@@ -83,52 +856,342 @@ fn collect_groups(file: &MdfFile) -> Result<Vec<MergedGroup>> {
 /// # Returns
 /// `Ok(())` on success or an [`crate::Error`] otherwise.
 pub fn merge_files(output: &str, first: &str, second: &str) -> Result<()> {
-    let mdf1 = MdfFile::parse_from_file(first)?;
-    let mdf2 = MdfFile::parse_from_file(second)?;
+    merge_files_with_options(output, &[first, second], &MergeOptions::default())
+}
+
+/// Merge any number of MDF files into a new file.
+///
+/// Works like [`merge_files()`] for the `inputs` in the given order. With a
+/// [`TimeAlignment`] other than `None`, the values of master time channels
+/// are shifted so that recordings of different loggers share a common
+/// timebase:
+///
+/// ```no_run
+/// use mdf4_rs::merge::{MergeOptions, TimeAlignment, merge_files_with_options};
+///
+/// let options = MergeOptions::new().with_time_alignment(TimeAlignment::StartTime);
+/// merge_files_with_options("merged.mf4", &["can.mf4", "gps.mf4"], &options)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// Offsets are converted into the raw values of each time master channel
+/// by its unit (`s`, `ms`, `us`, `ns`, `min` or `h`; seconds if it has
+/// none) and its identity or linear conversion, so integer microsecond time
+/// stamps are shifted correctly. Master channels with other units or
+/// conversions fail the merge when they would have to be shifted.
+///
+/// Inputs are read one data block at a time, and records are decoded and
+/// written one at a time, so memory use does not grow with the size of the
+/// inputs. `output` must not be one of the inputs.
+///
+/// With [`MergeMode::Channels`], the channels of all files are combined into
+/// one channel group instead; duplicate names are handled according to the
+/// [`NameConflict`] policy. Signals recorded in more than one file can be
+/// written once with a [`Deduplication`] setting.
+///
+/// # Arguments
+/// * `output` - Path for the merged file
+/// * `inputs` - Paths of the input files
+/// * `options` - Merge options
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] otherwise, e.g. if the number
+/// of offsets differs from the number of inputs, if a time master channel
+/// cannot be shifted, if groups with different record counts would share a
+/// group, or if `output` is one of the inputs.
+pub fn merge_files_with_options(
+    output: &str,
+    inputs: &[&str],
+    options: &MergeOptions,
+) -> Result<()> {
+    let files = open_inputs(output, inputs)?;
+    let mut writer = MdfWriter::new(output)?;
+    merge_parsed(&files, &mut writer, options)
+}
 
-    let mut groups = collect_groups(&mdf1)?;
-    let other_groups = collect_groups(&mdf2)?;
+/// Merge MDF files from any source into any [`MdfWriter`] backend.
+///
+/// Works like [`merge_files_with_options()`], but the inputs may be files
+/// in memory or read through a reader (see [`MergeInput`]), and the result
+/// is written through `writer`, so merges can run on data from network or
+/// object storage end to end. `writer` must be new; the file is initialized
+/// and finalized here.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::MdfWriter;
+/// use mdf4_rs::merge::{MergeInput, MergeOptions, merge_to_writer};
+///
+/// let downloaded: Vec<u8> = std::fs::read("part2.mf4")?;
+/// let inputs = vec![MergeInput::Path("part1.mf4"), MergeInput::Bytes(downloaded)];
+/// let mut writer = MdfWriter::in_memory();
+/// merge_to_writer(inputs, &mut writer, &MergeOptions::new())?;
+/// let bytes = writer.into_inner().into_inner();
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `inputs` - Input files in merge order
+/// * `writer` - Destination of the merged file
+/// * `options` - Merge options
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading, merging or
+/// writing fails.
+pub fn merge_to_writer<'a, W: MdfWrite>(
+    inputs: impl IntoIterator<Item = MergeInput<'a>>,
+    writer: &mut MdfWriter<W>,
+    options: &MergeOptions,
+) -> Result<()> {
+    let files = inputs
+        .into_iter()
+        .map(MergeInput::open)
+        .collect::<Result<Vec<_>>>()?;
+    merge_parsed(&files, writer, options)
+}
 
-    for og in other_groups {
-        if let Some(g1) = groups.iter_mut().find(|g| g.meta == og.meta) {
-            for (vals1, vals2) in g1.data.iter_mut().zip(og.data) {
-                vals1.extend(vals2);
+/// Merge opened files into a new `writer`.
+fn merge_parsed<W: MdfWrite>(
+    files: &[SourceFile],
+    writer: &mut MdfWriter<W>,
+    options: &MergeOptions,
+) -> Result<()> {
+    let earliest_start = files.iter().map(|f| f.header.start_time_ns).min();
+    let offsets: Vec<f64> = match &options.time_alignment {
+        TimeAlignment::None => vec![0.0; files.len()],
+        TimeAlignment::StartTime => {
+            let earliest = earliest_start.unwrap_or(0);
+            files
+                .iter()
+                .map(|f| (f.header.start_time_ns - earliest) as f64 / 1e9)
+                .collect()
+        }
+        TimeAlignment::Offsets(offsets) => {
+            if offsets.len() != files.len() {
+                return Err(Error::BlockSerializationError(format!(
+                    "{} time offsets for {} input files",
+                    offsets.len(),
+                    files.len()
+                )));
             }
-        } else {
-            groups.push(og);
+            offsets.clone()
         }
+    };
+
+    let mut sources = Vec::new();
+    for (file, offset) in files.iter().zip(offsets) {
+        sources.extend(source_groups(file, offset)?);
     }
+    let identical = options.deduplication == Deduplication::IdenticalData;
+    let groups = match options.mode {
+        MergeMode::Groups => concatenate_groups(&sources, identical)?,
+        MergeMode::Channels => vec![shared_group(&sources, options.name_conflict, identical)?],
+    };
 
-    let mut writer = MdfWriter::new(output)?;
     writer.init_mdf_file()?;
+    if options.time_alignment == TimeAlignment::StartTime {
+        writer.set_start_time_ns(earliest_start.unwrap_or(0))?;
+    }
+    if options.mode == MergeMode::Channels {
+        writer.set_duplicate_name_policy(match options.name_conflict {
+            NameConflict::Error => DuplicateNamePolicy::Reject,
+            _ => DuplicateNamePolicy::Rename,
+        });
+    }
 
     for group in groups {
-        let cg_id = writer.add_channel_group(None, |_| {})?;
-        let mut last_cn: Option<String> = None;
-        for ch in &group.meta.channels {
-            let id = writer.add_channel(&cg_id, last_cn.as_deref(), |cn| {
-                cn.channel_type = ch.channel_type;
-                cn.data_type = ch.data_type;
-                cn.bit_offset = ch.bit_offset;
-                cn.byte_offset = ch.byte_offset;
-                cn.bit_count = ch.bit_count;
-                if let Some(n) = &ch.name {
-                    cn.name = Some(n.clone());
-                }
-            })?;
-            last_cn = Some(id);
-        }
+        let cg_id = add_group(writer, &group.meta)?;
         writer.start_data_block_for_cg(&cg_id, group.meta.record_id_size)?;
-        let record_count = group.data.first().map(|v| v.len()).unwrap_or(0);
-        for i in 0..record_count {
-            let mut vals = Vec::new();
-            for ch_data in &group.data {
-                vals.push(ch_data[i].clone());
+
+        // Records are streamed one at a time, so no channel is held in
+        // memory as a whole
+        let mut record = Vec::with_capacity(group.columns.len());
+        let time_column = group.meta.channels.iter().position(|c| c.is_time_master());
+        let mut last_time = None;
+        for run in &group.runs {
+            let mut readers = run
+                .iter()
+                .map(|&s| SourceReader::new(&sources[s]))
+                .collect::<Result<Vec<_>>>()?;
+            let mut values = vec![Vec::new(); readers.len()];
+            loop {
+                let mut ended = 0;
+                for (reader, values) in readers.iter_mut().zip(&mut values) {
+                    if !reader.next_values(values)? {
+                        ended += 1;
+                    }
+                }
+                if ended == readers.len() {
+                    break;
+                }
+                if ended > 0 {
+                    return Err(Error::BlockSerializationError(
+                        "Cannot share a channel group between groups with different record counts"
+                            .to_string(),
+                    ));
+                }
+                record.clear();
+                record.extend(group.columns.iter().map(|&(r, c)| values[r][c].clone()));
+                if options.deduplication == Deduplication::Timestamps {
+                    let time = time_column.and_then(|c| record[c].as_f64());
+                    if time.zip(last_time).is_some_and(|(t, last)| t <= last) {
+                        continue;
+                    }
+                    last_time = time.or(last_time);
+                }
+                writer.write_record(&cg_id, &record)?;
             }
-            writer.write_record(&cg_id, &vals)?;
         }
         writer.finish_data_block(&cg_id)?;
     }
 
     writer.finalize()
 }
+
+/// Add a channel group with the channels of `meta` to `writer`.
+pub(crate) fn add_group<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    meta: &GroupMeta,
+) -> Result<String> {
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let mut last_cn: Option<String> = None;
+    for ch in &meta.channels {
+        let id = writer.add_channel(&cg_id, last_cn.as_deref(), |cn| {
+            cn.channel_type = ch.channel_type;
+            cn.sync_type = ch.sync_type;
+            cn.data_type = ch.data_type;
+            cn.bit_offset = ch.bit_offset;
+            cn.byte_offset = ch.byte_offset;
+            cn.bit_count = ch.bit_count;
+            if let Some(n) = &ch.name {
+                cn.name = Some(n.clone());
+            }
+        })?;
+        last_cn = Some(id);
+    }
+    Ok(cg_id)
+}
+
+/// Whether the channels `a_columns` of the source group `a` have the same
+/// values in all records as the channels `b_columns` of `b`.
+fn same_values(
+    a: &SourceGroup<'_>,
+    a_columns: &[usize],
+    b: &SourceGroup<'_>,
+    b_columns: &[usize],
+) -> Result<bool> {
+    if a.cg.block.cycle_count != b.cg.block.cycle_count {
+        return Ok(false);
+    }
+    let (mut a_reader, mut b_reader) = (SourceReader::new(a)?, SourceReader::new(b)?);
+    let (mut a_values, mut b_values) = (Vec::new(), Vec::new());
+    loop {
+        match (
+            a_reader.next_values(&mut a_values)?,
+            b_reader.next_values(&mut b_values)?,
+        ) {
+            (false, false) => return Ok(true),
+            (true, true) => {
+                let same = a_columns
+                    .iter()
+                    .zip(b_columns)
+                    .all(|(&ca, &cb)| a_values[ca] == b_values[cb]);
+                if !same {
+                    return Ok(false);
+                }
+            }
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// Plan the output groups for [`MergeMode::Groups`]: the records of groups
+/// with the same layout are concatenated. With `dedupe`, groups with the
+/// same values as an earlier one are left out.
+fn concatenate_groups(sources: &[SourceGroup<'_>], dedupe: bool) -> Result<Vec<OutputGroup>> {
+    let mut merged: Vec<OutputGroup> = Vec::new();
+    for (s, source) in sources.iter().enumerate() {
+        if let Some(group) = merged.iter_mut().find(|g| g.meta == source.meta) {
+            if dedupe {
+                let all: Vec<usize> = (0..source.meta.channels.len()).collect();
+                let mut duplicate = false;
+                for run in &group.runs {
+                    if same_values(&sources[run[0]], &all, source, &all)? {
+                        duplicate = true;
+                        break;
+                    }
+                }
+                if duplicate {
+                    continue;
+                }
+            }
+            group.runs.push(vec![s]);
+        } else {
+            merged.push(OutputGroup {
+                meta: source.meta.clone(),
+                runs: vec![vec![s]],
+                columns: (0..source.meta.channels.len()).map(|c| (0, c)).collect(),
+            });
+        }
+    }
+    Ok(merged)
+}
+
+/// Plan the output group for [`MergeMode::Channels`]: the channels of all
+/// groups side by side, with the first time channel first. With `dedupe`,
+/// channels with the same name and values as an earlier one are left out.
+fn shared_group(
+    sources: &[SourceGroup<'_>],
+    name_conflict: NameConflict,
+    dedupe: bool,
+) -> Result<OutputGroup> {
+    let mut channels: Vec<ChannelMeta> = Vec::new();
+    let mut columns: Vec<(usize, usize)> = Vec::new();
+    let mut has_time = false;
+    for (s, source) in sources.iter().enumerate() {
+        for (c, meta) in source.meta.channels.iter().enumerate() {
+            let is_time = meta.is_time_master();
+            if is_time && has_time {
+                continue;
+            }
+            let same_name: Vec<usize> = match &meta.name {
+                Some(name) => (0..channels.len())
+                    .filter(|&i| channels[i].name.as_ref() == Some(name))
+                    .collect(),
+                None => Vec::new(),
+            };
+            if !same_name.is_empty() && name_conflict == NameConflict::Skip {
+                continue;
+            }
+            if dedupe {
+                let mut duplicate = false;
+                for &i in &same_name {
+                    let (os, oc) = columns[i];
+                    if same_values(&sources[os], &[oc], source, &[c])? {
+                        duplicate = true;
+                        break;
+                    }
+                }
+                if duplicate {
+                    continue;
+                }
+            }
+            // Channels are packed into the new records
+            let mut meta = meta.clone();
+            meta.byte_offset = 0;
+            meta.bit_offset = 0;
+            let position = if is_time { 0 } else { channels.len() };
+            has_time |= is_time;
+            channels.insert(position, meta);
+            columns.insert(position, (s, c));
+        }
+    }
+    Ok(OutputGroup {
+        meta: GroupMeta {
+            record_id_size: 0,
+            channels,
+        },
+        runs: vec![(0..sources.len()).collect()],
+        columns,
+    })
+}
@@ -0,0 +1,347 @@
+//! Input files of merges and splits, read through a [`ByteRangeReader`].
+//!
+//! Only the block structure of an input is kept in memory. Records and
+//! signal data are read one data block at a time, so memory use does not
+//! grow with the size of the inputs.
+
+use super::MergeInput;
+use crate::{
+    Error, Result,
+    blocks::{
+        BlockParse, ChannelBlock, ChannelGroupBlock, DataGroupBlock, HeaderBlock,
+        IdentificationBlock,
+    },
+    index::{
+        ByteRangeReader, DataBlockInfo, FileRangeReader, MdfIndex, RecordIdLayout,
+        SignalDataBlockInfo,
+    },
+};
+use core::cell::RefCell;
+
+/// Reader of an input file on disk or in memory.
+enum InputReader {
+    File(FileRangeReader),
+    Bytes(Vec<u8>),
+}
+
+impl ByteRangeReader for InputReader {
+    type Error = Error;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
+        match self {
+            Self::File(reader) => reader.read_range(offset, length),
+            Self::Bytes(data) => {
+                let end = offset.saturating_add(length);
+                let range = (usize::try_from(offset).ok(), usize::try_from(end).ok());
+                match range {
+                    (Some(start), Some(end)) if end <= data.len() => Ok(data[start..end].to_vec()),
+                    _ => Err(Error::TooShortBuffer {
+                        actual: data.len(),
+                        expected: end as usize,
+                        file: file!(),
+                        line: line!(),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+/// An input file: its block structure, with the reader for its data.
+pub(crate) struct SourceFile {
+    reader: RefCell<InputReader>,
+    pub(crate) file_size: u64,
+    pub(crate) header: HeaderBlock,
+    pub(crate) is_unfinalized: bool,
+    pub(crate) data_groups: Vec<SourceDataGroup>,
+}
+
+/// A data group of an input file.
+pub(crate) struct SourceDataGroup {
+    pub(crate) block: DataGroupBlock,
+    /// Data blocks in order; an open data block of an unfinalized file
+    /// extends to the end of the file
+    pub(crate) data_blocks: Vec<DataBlockInfo>,
+    /// Record ID and record length of each channel group
+    layouts: Vec<RecordIdLayout>,
+    pub(crate) channel_groups: Vec<SourceChannelGroup>,
+}
+
+/// A channel group of an input file.
+pub(crate) struct SourceChannelGroup {
+    pub(crate) block: ChannelGroupBlock,
+    pub(crate) name: Option<String>,
+    /// Channel blocks with their conversions resolved
+    pub(crate) channels: Vec<ChannelBlock>,
+    pub(crate) channel_names: Vec<Option<String>>,
+    /// Units of the physical channel values
+    pub(crate) channel_units: Vec<Option<String>>,
+    /// Signal data blocks of VLSD channels, empty for other channels
+    pub(crate) signal_data: Vec<Vec<SignalDataBlockInfo>>,
+}
+
+impl SourceFile {
+    /// Open an input and read its block structure.
+    pub(crate) fn open(input: MergeInput<'_>) -> Result<Self> {
+        let (reader, file_size) = match input {
+            MergeInput::Path(path) => {
+                let file_size = std::fs::metadata(path)?.len();
+                (InputReader::File(FileRangeReader::new(path)?), file_size)
+            }
+            MergeInput::Bytes(data) => {
+                let file_size = data.len() as u64;
+                (InputReader::Bytes(data), file_size)
+            }
+        };
+        Self::from_reader(reader, file_size)
+    }
+
+    fn from_reader(mut reader: InputReader, file_size: u64) -> Result<Self> {
+        let identification = IdentificationBlock::from_bytes(&reader.read_range(0, 64)?)?;
+        let header = HeaderBlock::from_bytes(&reader.read_range(64, 104)?)?;
+        let is_unfinalized = identification.file_id.trim() == "UnFinMF";
+
+        let mut data_groups = Vec::new();
+        let mut dg_addr = header.first_dg_addr;
+        while dg_addr != 0 {
+            let block = DataGroupBlock::from_bytes(&reader.read_range(dg_addr, 64)?)?;
+            let mut data_blocks =
+                MdfIndex::extract_data_blocks_streaming(&mut reader, block.data_block_addr)?;
+            if let [open] = &mut data_blocks[..] {
+                // The open data block of an unfinalized file has no length yet
+                if is_unfinalized && !open.is_compressed && open.size == 24 {
+                    open.size = file_size.saturating_sub(open.file_offset);
+                }
+            }
+
+            let cg_blocks = MdfIndex::read_channel_group_blocks(&mut reader, &block)?;
+            let layouts = cg_blocks
+                .iter()
+                .map(|cg| RecordIdLayout {
+                    record_id: cg.record_id,
+                    // cg_flags bit 0: VLSD channel group
+                    length: (cg.flags & 0x01 == 0).then_some(cg.record_size + cg.invalidation_size),
+                })
+                .collect();
+            let mut channel_groups = Vec::with_capacity(cg_blocks.len());
+            for cg in cg_blocks {
+                channel_groups.push(SourceChannelGroup::read(&mut reader, cg)?);
+            }
+
+            dg_addr = block.next_dg_addr;
+            data_groups.push(SourceDataGroup {
+                block,
+                data_blocks,
+                layouts,
+                channel_groups,
+            });
+        }
+
+        Ok(Self {
+            reader: RefCell::new(reader),
+            file_size,
+            header,
+            is_unfinalized,
+            data_groups,
+        })
+    }
+
+    /// Read `length` bytes at `offset`.
+    pub(crate) fn read_range(&self, offset: u64, length: u64) -> Result<Vec<u8>> {
+        self.reader.borrow_mut().read_range(offset, length)
+    }
+
+    /// Read the record data of a data block, decompressing it if needed.
+    fn read_block_data(&self, block: &DataBlockInfo) -> Result<Vec<u8>> {
+        MdfIndex::read_block_data(block, &mut *self.reader.borrow_mut()).map(|data| data.into_vec())
+    }
+}
+
+impl SourceChannelGroup {
+    fn read(reader: &mut InputReader, block: ChannelGroupBlock) -> Result<Self> {
+        let name = MdfIndex::read_text_block(reader, block.acq_name_addr)?;
+        let mut channels = Vec::new();
+        let mut channel_names = Vec::new();
+        let mut channel_units = Vec::new();
+        let mut signal_data = Vec::new();
+        let mut cn_addr = block.first_ch_addr;
+        while cn_addr != 0 {
+            let mut channel = ChannelBlock::from_bytes(&reader.read_range(cn_addr, 160)?)?;
+            channel.conversion =
+                MdfIndex::read_conversion_block_streaming(reader, channel.conversion_addr)?;
+            channel_names.push(MdfIndex::read_text_block(reader, channel.name_addr)?);
+            // The unit of the conversion applies if the channel has none
+            let conversion_unit = channel.conversion.as_ref().and_then(|cc| cc.unit_addr);
+            let unit_addr = match channel.unit_addr {
+                0 => conversion_unit.unwrap_or(0),
+                addr => addr,
+            };
+            channel_units.push(MdfIndex::read_comment_block(reader, unit_addr)?);
+            signal_data.push(if channel.channel_type == 1 && channel.data_addr != 0 {
+                MdfIndex::index_vlsd_blocks(channel.data_addr, reader)?
+            } else {
+                Vec::new()
+            });
+            cn_addr = channel.next_ch_addr;
+            channels.push(channel);
+        }
+        Ok(Self {
+            block,
+            name,
+            channels,
+            channel_names,
+            channel_units,
+            signal_data,
+        })
+    }
+}
+
+/// Records of one channel group, read one data block at a time.
+///
+/// Records may continue from one block of a list into the next; the bytes
+/// of such a record are joined with the next block.
+pub(crate) struct BlockRecords<'a> {
+    file: &'a SourceFile,
+    data_group: &'a SourceDataGroup,
+    record_id: u64,
+    blocks: core::slice::Iter<'a, DataBlockInfo>,
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a> BlockRecords<'a> {
+    pub(crate) fn new(
+        file: &'a SourceFile,
+        data_group: &'a SourceDataGroup,
+        channel_group: &SourceChannelGroup,
+    ) -> Result<Self> {
+        let id_len = data_group.block.record_id_size as usize;
+        if id_len > 8 {
+            return Err(Error::BlockSerializationError(format!(
+                "Invalid record ID size {}",
+                id_len
+            )));
+        }
+        Ok(Self {
+            file,
+            data_group,
+            record_id: channel_group.block.record_id,
+            blocks: data_group.data_blocks.iter(),
+            data: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// The next record of the channel group, including its record ID;
+    /// `None` after the last one.
+    ///
+    /// # Errors
+    /// Returns an error for unreadable data blocks and unknown record IDs.
+    pub(crate) fn next_record(&mut self) -> Result<Option<&[u8]>> {
+        loop {
+            if let Some((id, len)) = self.record_at(self.pos)? {
+                let start = self.pos;
+                self.pos += len;
+                if id == self.record_id {
+                    return Ok(Some(&self.data[start..start + len]));
+                }
+                continue;
+            }
+
+            let Some(block) = self.blocks.next() else {
+                return Ok(None);
+            };
+            let data = self.file.read_block_data(block)?;
+            if self.pos == self.data.len() {
+                self.data = data;
+            } else {
+                self.data.drain(..self.pos);
+                self.data.extend_from_slice(&data);
+            }
+            self.pos = 0;
+        }
+    }
+
+    /// Record ID and length of the record at `pos`; `None` if it is
+    /// incomplete.
+    fn record_at(&self, pos: usize) -> Result<Option<(u64, usize)>> {
+        let data = &self.data[pos..];
+        let id_len = self.data_group.block.record_id_size as usize;
+        let layout_of = |id: u64| {
+            self.data_group
+                .layouts
+                .iter()
+                .find(|layout| layout.record_id == id)
+                .ok_or_else(|| {
+                    Error::BlockSerializationError(format!(
+                        "Unknown record ID {} in data block",
+                        id
+                    ))
+                })
+        };
+        if id_len == 0 {
+            let len = layout_of(self.record_id)?.length.unwrap_or(0) as usize;
+            return Ok((len > 0 && data.len() >= len).then_some((self.record_id, len)));
+        }
+        if data.len() < id_len {
+            return Ok(None);
+        }
+        let mut id = [0u8; 8];
+        id[..id_len].copy_from_slice(&data[..id_len]);
+        let id = u64::from_le_bytes(id);
+        let len = match layout_of(id)?.length {
+            Some(length) => id_len + length as usize,
+            // VLSD records: ID, 4 byte length and the value bytes
+            None => match data.get(id_len..id_len + 4) {
+                Some(len) => id_len + 4 + u32::from_le_bytes(len.try_into().unwrap()) as usize,
+                None => return Ok(None),
+            },
+        };
+        Ok((data.len() >= len).then_some((id, len)))
+    }
+}
+
+/// Values of a VLSD channel, read one signal data block at a time.
+pub(crate) struct SignalValues<'a> {
+    file: &'a SourceFile,
+    blocks: core::slice::Iter<'a, SignalDataBlockInfo>,
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a> SignalValues<'a> {
+    pub(crate) fn new(file: &'a SourceFile, blocks: &'a [SignalDataBlockInfo]) -> Self {
+        Self {
+            file,
+            blocks: blocks.iter(),
+            data: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// The bytes of the next value; `None` after the last complete one.
+    ///
+    /// Values may continue from one block into the next.
+    pub(crate) fn next_value(&mut self) -> Result<Option<&[u8]>> {
+        loop {
+            if let Some(len) = self.data.get(self.pos..self.pos + 4) {
+                let start = self.pos + 4;
+                let end = start + u32::from_le_bytes(len.try_into().unwrap()) as usize;
+                if end <= self.data.len() {
+                    self.pos = end;
+                    return Ok(Some(&self.data[start..end]));
+                }
+            }
+
+            let Some(block) = self.blocks.next() else {
+                return Ok(None);
+            };
+            let data = self
+                .file
+                .read_range(block.file_offset + 24, block.size.saturating_sub(24))?;
+            self.data.drain(..self.pos);
+            self.data.extend_from_slice(&data);
+            self.pos = 0;
+        }
+    }
+}
@@ -5,6 +5,8 @@ use crate::{
         DataBlock, DataGroupBlock, DataListBlock, HlBlock, u64_to_usize, {BlockHeader, BlockParse},
     },
 };
+use alloc::borrow::Cow;
+use alloc::format;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 
@@ -167,7 +169,44 @@ impl RawDataGroup {
     /// - Without `compression` feature: Returns error on DZ blocks
     /// - With `compression` feature: Decompresses DZ blocks transparently
     pub fn resolved_data_blocks<'a>(&self, mmap: &'a [u8]) -> Result<Vec<ResolvedDataBlock<'a>>> {
-        let mut collected_blocks = Vec::new();
+        self.data_block_addrs(mmap)?
+            .into_iter()
+            .map(|(addr, direct)| self.resolve_data_block(mmap, addr, direct))
+            .collect()
+    }
+
+    /// Iterate over the records of one channel group of this data group.
+    ///
+    /// Unlike [`resolved_data_blocks()`](Self::resolved_data_blocks), data
+    /// blocks are resolved (and decompressed) one at a time, so memory use
+    /// does not grow with the size of the data.
+    pub(crate) fn group_records<'a>(
+        &'a self,
+        channel_group: &'a RawChannelGroup,
+        mmap: &'a [u8],
+    ) -> Result<GroupRecords<'a>> {
+        let id_len = self.block.record_id_size as usize;
+        if id_len > 8 {
+            return Err(Error::BlockSerializationError(format!(
+                "Invalid record ID size {}",
+                id_len
+            )));
+        }
+        Ok(GroupRecords {
+            data_group: self,
+            channel_group,
+            mmap,
+            blocks: self.data_block_addrs(mmap)?.into_iter(),
+            data: Cow::Borrowed(&[]),
+            pos: 0,
+        })
+    }
+
+    /// Addresses of the DT, DV and DZ blocks of this data group in order,
+    /// following `DL` lists and skipping `HL` blocks. The flag is set for a
+    /// block linked directly from the data group.
+    pub(crate) fn data_block_addrs(&self, mmap: &[u8]) -> Result<Vec<(u64, bool)>> {
+        let mut addrs = Vec::new();
 
         let mut current_block_address = self.block.data_block_addr;
         while current_block_address != 0 {
@@ -175,38 +214,10 @@ impl RawDataGroup {
             let block_header = BlockHeader::from_bytes(&mmap[byte_offset..byte_offset + 24])?;
 
             match block_header.id.as_str() {
-                "##DT" | "##DV" => {
-                    let data_block = if self.is_unfinalized && block_header.length == 24 {
-                        DataBlock::from_bytes_unfinalized(&mmap[byte_offset..])?
-                    } else {
-                        DataBlock::from_bytes(&mmap[byte_offset..])?
-                    };
-                    collected_blocks.push(ResolvedDataBlock {
-                        block_id: if block_header.id == "##DT" {
-                            "##DT"
-                        } else {
-                            "##DV"
-                        },
-                        data: DataBlockData::Borrowed(data_block.data),
-                    });
+                "##DT" | "##DV" | "##DZ" => {
+                    addrs.push((current_block_address, true));
                     current_block_address = 0;
                 }
-                #[cfg(feature = "compression")]
-                "##DZ" => {
-                    let dz_block = DzBlock::from_bytes(&mmap[byte_offset..])?;
-                    let decompressed = dz_block.decompress()?;
-                    collected_blocks.push(ResolvedDataBlock {
-                        block_id: "##DT", // DZ decompresses to DT-equivalent data
-                        data: DataBlockData::Owned(decompressed),
-                    });
-                    current_block_address = 0;
-                }
-                #[cfg(not(feature = "compression"))]
-                "##DZ" => {
-                    return Err(Error::BlockSerializationError(
-                        "DZ blocks require the 'compression' feature".to_string(),
-                    ));
-                }
                 "##DL" => {
                     let data_list_block = DataListBlock::from_bytes(&mmap[byte_offset..])?;
 
@@ -214,45 +225,9 @@ impl RawDataGroup {
                         if fragment_address == 0 {
                             continue;
                         }
-                        let (frag_addr, frag_header) =
+                        let (frag_addr, _) =
                             HlBlock::skip_hierarchy_blocks(mmap, fragment_address)?;
-                        let fragment_offset = u64_to_usize(frag_addr, "DL fragment address")?;
-
-                        match frag_header.id.as_str() {
-                            "##DT" | "##DV" => {
-                                let fragment_block =
-                                    DataBlock::from_bytes(&mmap[fragment_offset..])?;
-                                collected_blocks.push(ResolvedDataBlock {
-                                    block_id: if frag_header.id == "##DT" {
-                                        "##DT"
-                                    } else {
-                                        "##DV"
-                                    },
-                                    data: DataBlockData::Borrowed(fragment_block.data),
-                                });
-                            }
-                            #[cfg(feature = "compression")]
-                            "##DZ" => {
-                                let dz_block = DzBlock::from_bytes(&mmap[fragment_offset..])?;
-                                let decompressed = dz_block.decompress()?;
-                                collected_blocks.push(ResolvedDataBlock {
-                                    block_id: "##DT",
-                                    data: DataBlockData::Owned(decompressed),
-                                });
-                            }
-                            #[cfg(not(feature = "compression"))]
-                            "##DZ" => {
-                                return Err(Error::BlockSerializationError(
-                                    "DZ blocks require the 'compression' feature".to_string(),
-                                ));
-                            }
-                            other => {
-                                return Err(Error::BlockIDError {
-                                    actual: other.to_string(),
-                                    expected: "##DT / ##DV / ##DZ".to_string(),
-                                });
-                            }
-                        }
+                        addrs.push((frag_addr, false));
                     }
 
                     current_block_address = data_list_block.next_dl_addr;
@@ -271,7 +246,146 @@ impl RawDataGroup {
             }
         }
 
-        Ok(collected_blocks)
+        Ok(addrs)
+    }
+
+    /// Resolve the DT, DV or DZ block at `addr`, decompressing DZ blocks.
+    ///
+    /// `direct` marks a block linked directly from the data group, which may
+    /// be the open data block of an unfinalized file.
+    fn resolve_data_block<'a>(
+        &self,
+        mmap: &'a [u8],
+        addr: u64,
+        direct: bool,
+    ) -> Result<ResolvedDataBlock<'a>> {
+        let byte_offset = u64_to_usize(addr, "data block address")?;
+        let block_header = BlockHeader::from_bytes(&mmap[byte_offset..byte_offset + 24])?;
+
+        match block_header.id.as_str() {
+            "##DT" | "##DV" => {
+                let data_block = if direct && self.is_unfinalized && block_header.length == 24 {
+                    DataBlock::from_bytes_unfinalized(&mmap[byte_offset..])?
+                } else {
+                    DataBlock::from_bytes(&mmap[byte_offset..])?
+                };
+                Ok(ResolvedDataBlock {
+                    block_id: if block_header.id == "##DT" {
+                        "##DT"
+                    } else {
+                        "##DV"
+                    },
+                    data: DataBlockData::Borrowed(data_block.data),
+                })
+            }
+            #[cfg(feature = "compression")]
+            "##DZ" => {
+                let dz_block = DzBlock::from_bytes(&mmap[byte_offset..])?;
+                let decompressed = dz_block.decompress()?;
+                Ok(ResolvedDataBlock {
+                    block_id: "##DT", // DZ decompresses to DT-equivalent data
+                    data: DataBlockData::Owned(decompressed),
+                })
+            }
+            #[cfg(not(feature = "compression"))]
+            "##DZ" => Err(Error::BlockSerializationError(
+                "DZ blocks require the 'compression' feature".to_string(),
+            )),
+            other => Err(Error::BlockIDError {
+                actual: other.to_string(),
+                expected: "##DT / ##DV / ##DZ".to_string(),
+            }),
+        }
+    }
+}
+
+/// Records of one channel group, read block by block (see
+/// [`RawDataGroup::group_records()`]).
+///
+/// Records may continue from one block of a list into the next; the bytes of
+/// such a record are joined with the next block.
+pub(crate) struct GroupRecords<'a> {
+    data_group: &'a RawDataGroup,
+    channel_group: &'a RawChannelGroup,
+    mmap: &'a [u8],
+    blocks: alloc::vec::IntoIter<(u64, bool)>,
+    data: Cow<'a, [u8]>,
+    pos: usize,
+}
+
+impl GroupRecords<'_> {
+    /// The next record of the channel group, including its record ID;
+    /// `None` after the last one.
+    ///
+    /// # Errors
+    /// Returns an error for unreadable data blocks and unknown record IDs.
+    pub(crate) fn next_record(&mut self) -> Result<Option<&[u8]>> {
+        loop {
+            if let Some((id, len)) = self.record_at(self.pos)? {
+                let start = self.pos;
+                self.pos += len;
+                if id == self.channel_group.block.record_id {
+                    return Ok(Some(&self.data[start..start + len]));
+                }
+                continue;
+            }
+
+            let Some((addr, direct)) = self.blocks.next() else {
+                return Ok(None);
+            };
+            let block = self
+                .data_group
+                .resolve_data_block(self.mmap, addr, direct)?;
+            let rest = &self.data[self.pos..];
+            self.data = match block.data {
+                DataBlockData::Borrowed(data) if rest.is_empty() => Cow::Borrowed(data),
+                #[cfg(feature = "compression")]
+                DataBlockData::Owned(data) if rest.is_empty() => Cow::Owned(data),
+                data => {
+                    let mut joined = rest.to_vec();
+                    joined.extend_from_slice(data.as_slice());
+                    Cow::Owned(joined)
+                }
+            };
+            self.pos = 0;
+        }
+    }
+
+    /// Record ID and length of the record at `pos`; `None` if it is
+    /// incomplete.
+    fn record_at(&self, pos: usize) -> Result<Option<(u64, usize)>> {
+        let data = &self.data[pos..];
+        let id_len = self.data_group.block.record_id_size as usize;
+        if id_len == 0 {
+            let cg = &self.channel_group.block;
+            let len = cg.record_size as usize + cg.invalidation_size as usize;
+            return Ok((len > 0 && data.len() >= len).then_some((cg.record_id, len)));
+        }
+        if data.len() < id_len {
+            return Ok(None);
+        }
+        let mut id = [0u8; 8];
+        id[..id_len].copy_from_slice(&data[..id_len]);
+        let id = u64::from_le_bytes(id);
+        let group = self
+            .data_group
+            .channel_groups
+            .iter()
+            .find(|g| g.block.record_id == id)
+            .ok_or_else(|| {
+                Error::BlockSerializationError(format!("Unknown record ID {} in data block", id))
+            })?;
+        let len = if group.block.flags & 1 != 0 {
+            // VLSD records: ID, 4 byte length and the value bytes
+            if data.len() < id_len + 4 {
+                return Ok(None);
+            }
+            let value_len = u32::from_le_bytes(data[id_len..id_len + 4].try_into().unwrap());
+            id_len + 4 + value_len as usize
+        } else {
+            id_len + group.block.record_size as usize + group.block.invalidation_size as usize
+        };
+        Ok((data.len() >= len).then_some((id, len)))
     }
 }
 
@@ -14,6 +14,10 @@ pub struct SourceInfo {
     pub path: Option<String>,
     /// Any extended comment/XML (si_md_comment)
     pub comment: Option<String>,
+    /// Source type (si_type, see [`crate::blocks::SourceType`])
+    pub source_type: u8,
+    /// Bus type (si_bus_type, see [`crate::blocks::BusType`])
+    pub bus_type: u8,
 }
 
 impl SourceInfo {
@@ -41,6 +45,8 @@ impl SourceInfo {
             name,
             path,
             comment,
+            source_type: sb.source_type,
+            bus_type: sb.bus_type,
         }))
     }
 }
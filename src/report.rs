@@ -0,0 +1,295 @@
+//! Summary reports of MDF files.
+//!
+//! [`summarize()`] collects what a data catalog needs to know about a
+//! recording without keeping its values: channel groups with their sample
+//! counts and time ranges, channels with units and value ranges, and the
+//! attachments and events of the file. The [`Report`] serializes to JSON
+//! for ingestion, to CSV with one row per channel, and prints as text.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mdf4_rs::report::summarize;
+//!
+//! let report = summarize("recording.mf4")?;
+//! println!("{}", report);
+//! std::fs::write("recording.json", report.to_json()?)?;
+//! # Ok::<(), mdf4_rs::Error>(())
+//! ```
+
+use crate::{
+    BufferedRangeReader, ByteRangeReader, DataType, Error, MdfIndex, Result,
+    blocks::{BlockParse, EventSyncType, HeaderBlock},
+    export::gps::format_time,
+    index::{ChannelStats, IndexedAttachment, IndexedEvent},
+};
+use core::fmt;
+use std::io::Write;
+
+/// Summary of a channel.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelSummary {
+    /// Channel name
+    pub name: Option<String>,
+    /// Physical unit
+    pub unit: Option<String>,
+    /// Data type of the raw values
+    pub data_type: DataType,
+    /// Whether this is the master (e.g. time) channel of the group
+    pub master: bool,
+    /// Range and number of the valid physical values; `None` for
+    /// non-numeric channels and channels without valid values
+    pub stats: Option<ChannelStats>,
+}
+
+/// Summary of a channel group.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupSummary {
+    /// Acquisition name of the group
+    pub name: Option<String>,
+    /// Group comment (plain text or XML)
+    pub comment: Option<String>,
+    /// Number of records
+    pub samples: u64,
+    /// Smallest and largest master value, in seconds for time masters;
+    /// `None` for groups without master channel or records
+    pub time_range: Option<(f64, f64)>,
+    /// Channels in group order
+    pub channels: Vec<ChannelSummary>,
+}
+
+/// Summary of an MDF file, created by [`summarize()`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// File size in bytes
+    pub file_size: u64,
+    /// Start time of the recording in nanoseconds since 1970-01-01 (UTC,
+    /// or local time if flagged in the header)
+    pub start_time_ns: u64,
+    /// Channel groups in file order
+    pub groups: Vec<GroupSummary>,
+    /// Attachments of the file
+    pub attachments: Vec<IndexedAttachment>,
+    /// Events of the file
+    pub events: Vec<IndexedEvent>,
+}
+
+impl Report {
+    /// Build the report of an index whose statistics have been computed
+    /// with [`MdfIndex::compute_statistics()`].
+    pub fn from_index(index: &MdfIndex, start_time_ns: u64) -> Self {
+        let groups = index
+            .channel_groups
+            .iter()
+            .map(|group| {
+                let time_range = group
+                    .master()
+                    .and_then(|master| master.stats)
+                    .map(|stats| (stats.min, stats.max))
+                    .or_else(|| group.time_bounds());
+                let channels = group
+                    .channels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, channel)| ChannelSummary {
+                        name: channel.name.clone(),
+                        unit: channel.unit.clone(),
+                        data_type: channel.data_type,
+                        master: group.master_channel == Some(i),
+                        stats: channel.stats,
+                    })
+                    .collect();
+                GroupSummary {
+                    name: group.name.clone(),
+                    comment: group.comment.clone(),
+                    samples: group.record_count,
+                    time_range: time_range.filter(|_| group.record_count > 0),
+                    channels,
+                }
+            })
+            .collect();
+        Self {
+            file_size: index.file_size,
+            start_time_ns,
+            groups,
+            attachments: index.attachments.clone(),
+            events: index.events.clone(),
+        }
+    }
+
+    /// Serialize the report as pretty-printed JSON.
+    ///
+    /// Requires the `serde` and `serde_json` features.
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            Error::BlockSerializationError(format!("JSON serialization failed: {}", e))
+        })
+    }
+
+    /// Write the channels as CSV with a header row and one row per channel:
+    /// `group,channel,unit,data_type,master,samples,valid,min,max,start,end`.
+    ///
+    /// `samples`, `start` and `end` are those of the channel group, and
+    /// `data_type` is spelled as in the JSON report. `valid`, `min` and
+    /// `max` are empty for channels without statistics.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(
+            writer,
+            "group,channel,unit,data_type,master,samples,valid,min,max,start,end"
+        )?;
+        for group in &self.groups {
+            let group_name = csv_field(group.name.as_deref().unwrap_or_default());
+            let (start, end) = group
+                .time_range
+                .map(|(start, end)| (start.to_string(), end.to_string()))
+                .unwrap_or_default();
+            for channel in &group.channels {
+                let (valid, min, max) = channel
+                    .stats
+                    .map(|s| (s.count.to_string(), s.min.to_string(), s.max.to_string()))
+                    .unwrap_or_default();
+                writeln!(
+                    writer,
+                    "{},{},{},{:?},{},{},{},{},{},{},{}",
+                    group_name,
+                    csv_field(channel.name.as_deref().unwrap_or_default()),
+                    csv_field(channel.unit.as_deref().unwrap_or_default()),
+                    channel.data_type,
+                    channel.master,
+                    group.samples,
+                    valid,
+                    min,
+                    max,
+                    start,
+                    end
+                )?;
+            }
+        }
+        writer.flush().map_err(Error::IOError)
+    }
+}
+
+/// Quote a CSV field if it contains separators, quotes or line breaks.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Unit of event synchronization values.
+fn sync_unit(sync_type: EventSyncType) -> &'static str {
+    match sync_type {
+        EventSyncType::Time => "s",
+        EventSyncType::Angle => "rad",
+        EventSyncType::Distance => "m",
+        EventSyncType::Index => "",
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Start {}, {} bytes, {} channel group(s)",
+            format_time(self.start_time_ns),
+            self.file_size,
+            self.groups.len()
+        )?;
+        for (i, group) in self.groups.iter().enumerate() {
+            write!(
+                f,
+                "\nGroup {} {:?}: {} samples",
+                i,
+                group.name.as_deref().unwrap_or_default(),
+                group.samples
+            )?;
+            if let Some((start, end)) = group.time_range {
+                write!(f, ", {} .. {}", start, end)?;
+            }
+            for channel in &group.channels {
+                write!(f, "\n  {}", channel.name.as_deref().unwrap_or("(unnamed)"))?;
+                if let Some(unit) = channel.unit.as_deref().filter(|u| !u.is_empty()) {
+                    write!(f, " [{}]", unit)?;
+                }
+                write!(f, " {}", channel.data_type)?;
+                if channel.master {
+                    write!(f, " master")?;
+                }
+                if let Some(stats) = channel.stats {
+                    write!(
+                        f,
+                        ": {} .. {} ({} valid)",
+                        stats.min, stats.max, stats.count
+                    )?;
+                }
+            }
+        }
+        write!(f, "\n{} attachment(s)", self.attachments.len())?;
+        for attachment in &self.attachments {
+            write!(
+                f,
+                "\n  {:?} {}, {} bytes, {}",
+                attachment.file_name.as_deref().unwrap_or_default(),
+                attachment.mime_type.as_deref().unwrap_or("-"),
+                attachment.original_size,
+                if attachment.embedded {
+                    "embedded"
+                } else {
+                    "external"
+                }
+            )?;
+        }
+        write!(f, "\n{} event(s)", self.events.len())?;
+        for event in &self.events {
+            write!(
+                f,
+                "\n  {} {} {:?} {:?}",
+                event.sync_value,
+                sync_unit(event.sync_type),
+                event.event_type,
+                event.name.as_deref().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Summarize an MDF file read through a byte range reader, e.g. from object
+/// storage.
+///
+/// Reads the structure of the file and all record data once to compute the
+/// value ranges of the numeric channels.
+///
+/// # Arguments
+/// * `reader` - Reader of the file
+/// * `file_size` - Total size of the file in bytes
+pub fn summarize_from_reader<R: ByteRangeReader<Error = Error>>(
+    reader: &mut R,
+    file_size: u64,
+) -> Result<Report> {
+    let mut index = MdfIndex::from_reader(reader, file_size)?;
+    index.compute_statistics(reader)?;
+    let header = HeaderBlock::from_bytes(&reader.read_range(64, 104)?)?;
+    Ok(Report::from_index(&index, header.start_time_ns))
+}
+
+/// Summarize an MDF file.
+///
+/// The report lists every channel group with its number of records and
+/// master value range, every channel with unit, data type and the range
+/// and number of its valid values (numeric channels only), and the
+/// attachments and events of the file.
+///
+/// # Returns
+/// The report, or an [`crate::Error`] if the file cannot be read.
+pub fn summarize(path: &str) -> Result<Report> {
+    let file_size = std::fs::metadata(path).map_err(Error::IOError)?.len();
+    let mut reader = BufferedRangeReader::new(path)?;
+    summarize_from_reader(&mut reader, file_size)
+}
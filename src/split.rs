@@ -0,0 +1,338 @@
+//! Splitting MDF files into a sequence of smaller files.
+//!
+//! This is the inverse of [`crate::merge`]: a long recording is cut into
+//! consecutive parts, e.g. for uploading it in chunks. Every part is a
+//! complete MDF file with all channel groups of the source.
+
+use crate::{
+    Error, Result,
+    merge::{MergeInput, SourceFile, SourceGroup, SourceReader, add_group, source_groups},
+    parsing::decoder::DecodedValue,
+    writer::{MdfWrite, MdfWriter},
+};
+use std::path::Path;
+
+/// Where [`split_mdf()`] starts a new file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitPolicy {
+    /// A new file every `n` seconds of the time master channels, counted
+    /// from the earliest time stamp.
+    EveryNSeconds(f64),
+    /// A new file every `n` records of each channel group.
+    EveryNRecords(u64),
+    /// A new file before the size of a file would exceed the given number
+    /// of bytes.
+    ///
+    /// Sizes are estimated from the record sizes of the source. Signal data
+    /// of VLSD channels is not counted, and the headers of additional data
+    /// blocks (one per 4 MiB of records) may add a few bytes.
+    MaxFileSize(u64),
+}
+
+impl SplitPolicy {
+    /// Check that the policy starts new files at all: a positive, finite
+    /// interval or a non-zero count or size.
+    pub(crate) fn validate(&self) -> Result<()> {
+        let invalid = match *self {
+            SplitPolicy::EveryNSeconds(s) => !(s.is_finite() && s > 0.0),
+            SplitPolicy::EveryNRecords(n) | SplitPolicy::MaxFileSize(n) => n == 0,
+        };
+        if invalid {
+            return Err(Error::BlockSerializationError(format!(
+                "Invalid split policy {:?}",
+                self
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Assignment of records to output files.
+enum Router {
+    Records(u64),
+    Interval {
+        start: f64,
+        step: f64,
+    },
+    /// Start times of the second, third, ... file
+    Boundaries(Vec<f64>),
+}
+
+impl Router {
+    /// Output file of a record, from its index in the group and its time;
+    /// `None` if it depends on the time and there is none.
+    fn file_of(&self, record: u64, time: Option<f64>) -> Option<u64> {
+        match self {
+            Router::Records(n) => Some(record / n),
+            Router::Interval { start, step } => {
+                time.map(|t| ((t - start) / step).floor().max(0.0) as u64)
+            }
+            Router::Boundaries(bounds) => time.map(|t| bounds.partition_point(|b| *b <= t) as u64),
+        }
+    }
+}
+
+/// Physical time of the decoded values of a record; `None` if it is not a
+/// number.
+fn record_time(
+    source: &SourceGroup<'_>,
+    time: usize,
+    values: &[DecodedValue],
+) -> Result<Option<f64>> {
+    let value = source.cg.channels[time].apply_conversion_value(values[time].clone(), &[])?;
+    Ok(value.as_f64().filter(|t| !t.is_nan()))
+}
+
+/// The records of a source group, with the output file of the pending one.
+struct SplitCursor<'a> {
+    reader: SourceReader<'a>,
+    /// Index of the time master channel
+    time: Option<usize>,
+    /// Values of the pending record
+    values: Vec<DecodedValue>,
+    /// Whether `values` holds a record
+    pending: bool,
+    /// Time of the pending record
+    record_time: Option<f64>,
+    /// Index of the pending record in the group
+    record: u64,
+    /// Output file of the pending record
+    file: u64,
+}
+
+impl<'a> SplitCursor<'a> {
+    fn new(source: &'a SourceGroup<'a>) -> Result<Self> {
+        let mut cursor = Self {
+            reader: SourceReader::new(source)?,
+            time: source.meta.channels.iter().position(|c| c.is_time_master()),
+            values: Vec::new(),
+            pending: false,
+            record_time: None,
+            record: 0,
+            file: 0,
+        };
+        cursor.read()?;
+        Ok(cursor)
+    }
+
+    /// Read the next record.
+    fn read(&mut self) -> Result<()> {
+        if self.pending {
+            self.record += 1;
+        }
+        self.pending = self.reader.next_values(&mut self.values)?;
+        self.record_time = match (self.pending, self.time) {
+            (true, Some(time)) => record_time(self.reader.source, time, &self.values)?,
+            _ => None,
+        };
+        Ok(())
+    }
+
+    /// Assign the pending record to a file. Records stay in the file of
+    /// the previous record if they have no valid time or go back in time.
+    fn route(&mut self, router: &Router) {
+        if let Some(file) = router.file_of(self.record, self.record_time) {
+            self.file = self.file.max(file);
+        }
+    }
+
+    /// Output file of the pending record; `None` after the last record.
+    fn pending_file(&self) -> Option<u64> {
+        self.pending.then_some(self.file)
+    }
+}
+
+/// Initialize `writer` with the channel groups of `sources`.
+fn write_layout<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    mdf: &SourceFile,
+    sources: &[SourceGroup<'_>],
+) -> Result<Vec<String>> {
+    writer.init_mdf_file()?;
+    writer.set_start_time_ns(mdf.header.start_time_ns)?;
+    let mut cg_ids = Vec::with_capacity(sources.len());
+    for source in sources {
+        let cg_id = add_group(writer, &source.meta)?;
+        if let Some(name) = &source.cg.name {
+            writer.set_channel_group_name(&cg_id, name)?;
+        }
+        cg_ids.push(cg_id);
+    }
+    Ok(cg_ids)
+}
+
+/// Bytes of a record of a source group in a data block.
+fn record_len(source: &SourceGroup<'_>) -> u64 {
+    let cg = &source.cg.block;
+    source.meta.record_id_size as u64 + cg.record_size as u64 + cg.invalidation_size as u64
+}
+
+/// Start times of the second, third, ... file such that no file exceeds
+/// `max_size` bytes.
+///
+/// Records of all groups are visited in time order; records with the same
+/// time stamp are kept in one file. Records of groups without a time master
+/// channel all go to the first file.
+fn size_boundaries(
+    mdf: &SourceFile,
+    sources: &[SourceGroup<'_>],
+    max_size: u64,
+) -> Result<Vec<f64>> {
+    // Size of a file without records
+    let mut empty = MdfWriter::in_memory();
+    let cg_ids = write_layout(&mut empty, mdf, sources)?;
+    for (cg_id, source) in cg_ids.iter().zip(sources) {
+        empty.start_data_block_for_cg(cg_id, source.meta.record_id_size)?;
+        empty.finish_data_block(cg_id)?;
+    }
+    empty.finalize()?;
+    let overhead = empty.into_inner().into_inner().len() as u64;
+    let budget = max_size.saturating_sub(overhead);
+    if budget == 0 {
+        return Err(Error::BlockSerializationError(format!(
+            "Maximum file size {} is below the {} bytes of the file structure",
+            max_size, overhead
+        )));
+    }
+
+    let mut size = 0;
+    let mut cursors = Vec::new();
+    for source in sources {
+        let mut cursor = SplitCursor::new(source)?;
+        if cursor.time.is_some() {
+            cursors.push(cursor);
+        } else {
+            while cursor.pending {
+                size += record_len(source);
+                cursor.read()?;
+            }
+        }
+    }
+
+    let mut bounds = Vec::new();
+    let mut last_time = None;
+    // Bytes of the records at `last_time`
+    let mut tied = 0;
+    loop {
+        let next = cursors.iter_mut().filter(|c| c.pending).min_by(|a, b| {
+            let time = |c: &SplitCursor<'_>| c.record_time.unwrap_or(f64::NEG_INFINITY);
+            time(a).total_cmp(&time(b))
+        });
+        let Some(cursor) = next else {
+            break;
+        };
+        let len = record_len(cursor.reader.source);
+        let time = cursor.record_time;
+        cursor.read()?;
+        // Records without a valid time stay with the previous record
+        let Some(time) = time.or(last_time) else {
+            size += len;
+            continue;
+        };
+
+        if last_time != Some(time) {
+            last_time = Some(time);
+            tied = 0;
+        }
+        if size + len > budget && tied < size {
+            bounds.push(time);
+            size = tied;
+        }
+        tied += len;
+        size += len;
+    }
+    Ok(bounds)
+}
+
+/// Path of the part `index` (counted from 0) of `input_path`:
+/// `recording.mf4` becomes `recording_001.mf4`, `recording_002.mf4`, ...
+pub(crate) fn part_path(input_path: &str, index: usize) -> String {
+    let path = Path::new(input_path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{:03}.{}", stem, index + 1, ext.to_string_lossy()),
+        None => format!("{}_{:03}", stem, index + 1),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Split an MDF file into a sequence of smaller files.
+///
+/// The parts are written next to the source as `<name>_001.mf4`,
+/// `<name>_002.mf4`, ... Each part has all channel groups of the source,
+/// its start time and the records that `policy` assigns to it; merging
+/// the parts in order with [`crate::merge::merge_files_with_options()`]
+/// gives back the records of the source. Records are streamed, and only
+/// one part is open at a time.
+///
+/// With the time based policies, records of groups without a time master
+/// channel go to the first part. Parts without records are not written,
+/// except for a single part if the source has no records at all.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::split::{SplitPolicy, split_mdf};
+///
+/// let parts = split_mdf("recording.mf4", SplitPolicy::MaxFileSize(64 << 20))?;
+/// println!("{} parts", parts.len());
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `policy` - Where new parts start
+///
+/// # Returns
+/// The paths of the written parts in order, or an [`crate::Error`] if the
+/// policy is invalid or reading or writing fails.
+pub fn split_mdf(input_path: &str, policy: SplitPolicy) -> Result<Vec<String>> {
+    policy.validate()?;
+
+    let mdf = MergeInput::Path(input_path).open()?;
+    let sources = source_groups(&mdf, 0.0)?;
+    let mut cursors = sources
+        .iter()
+        .map(SplitCursor::new)
+        .collect::<Result<Vec<_>>>()?;
+    let router = match policy {
+        SplitPolicy::EveryNRecords(n) => Router::Records(n),
+        SplitPolicy::EveryNSeconds(step) => {
+            let start = cursors
+                .iter()
+                .filter_map(|c| c.record_time)
+                .min_by(f64::total_cmp)
+                .unwrap_or(0.0);
+            Router::Interval { start, step }
+        }
+        SplitPolicy::MaxFileSize(max_size) => {
+            Router::Boundaries(size_boundaries(&mdf, &sources, max_size)?)
+        }
+    };
+    for cursor in &mut cursors {
+        cursor.route(&router);
+    }
+
+    let mut parts = Vec::new();
+    loop {
+        let file = cursors.iter().filter_map(SplitCursor::pending_file).min();
+        let Some(file) = file.or(parts.is_empty().then_some(0)) else {
+            break;
+        };
+        let path = part_path(input_path, parts.len());
+        let mut writer = MdfWriter::new(&path)?;
+        let cg_ids = write_layout(&mut writer, &mdf, &sources)?;
+        for (cursor, cg_id) in cursors.iter_mut().zip(&cg_ids) {
+            let record_id_size = cursor.reader.source.meta.record_id_size;
+            writer.start_data_block_for_cg(cg_id, record_id_size)?;
+            while cursor.pending_file() == Some(file) {
+                writer.write_record(cg_id, &cursor.values)?;
+                cursor.read()?;
+                cursor.route(&router);
+            }
+            writer.finish_data_block(cg_id)?;
+        }
+        writer.finalize()?;
+        parts.push(path);
+    }
+    Ok(parts)
+}
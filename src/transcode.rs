@@ -0,0 +1,707 @@
+//! Rewriting of MDF files with a normalized layout.
+//!
+//! [`transcode()`] reads any file the reader supports, whether sorted or
+//! unsorted, compressed or not, and writes it back as a sorted MDF 4.10
+//! file. It drops the channel groups and channels that are not selected,
+//! and can optionally compress the data. Records are copied byte for byte,
+//! so conversions, units, sources and comments are kept as they are.
+//!
+//! Records are written row by row by default. With
+//! [`TranscodeOptions::with_column_storage()`] each channel is written to a
+//! channel group of its own in the column oriented storage of MDF 4.2.
+
+use crate::{
+    Error, Result,
+    blocks::{
+        BlockHeader, BlockParse, ChannelBlock, ChannelGroupBlock, ConversionBlock, DataListBlock,
+        SourceBlock, read_string_block,
+    },
+    cut::{
+        CutOptions, block_bytes, copy_attachments_and_events, copy_text_block, time_channel_index,
+    },
+    parsing::{MdfFile, RawChannelGroup, RawDataGroup},
+    writer::{MdfWrite, MdfWriter},
+};
+
+/// Maximum size of the data section of a written data block.
+const MAX_BLOCK_DATA: usize = 4 * 1024 * 1024;
+
+/// Maximum nesting of conversions, as a guard against cycles in corrupted
+/// files.
+const MAX_CONVERSION_DEPTH: usize = 16;
+
+/// Options for [`transcode()`].
+///
+/// By default all channel groups and channels are kept and the data is
+/// written uncompressed.
+///
+/// # Example
+/// ```no_run
+/// use mdf4_rs::transcode::{TranscodeOptions, transcode};
+///
+/// let options = TranscodeOptions::new()
+///     .with_groups(&["Powertrain"])
+///     .with_channels(&["EngineSpeed", "Throttle"]);
+/// transcode("input.mf4", "normalized.mf4", &options)?;
+/// # Ok::<(), mdf4_rs::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscodeOptions {
+    /// Channel groups and channels to keep
+    selection: CutOptions,
+    /// Write DZ instead of DT blocks
+    compression: bool,
+    /// Write one channel group per channel in DV and DI blocks
+    column_storage: bool,
+}
+
+impl TranscodeOptions {
+    /// Options keeping all channel groups and channels, uncompressed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only the channels with these names.
+    ///
+    /// The time channel of a group is kept along with any of its channels,
+    /// and groups without kept channels are dropped. Records are packed so
+    /// that dropped channels leave no gaps.
+    pub fn with_channels(mut self, names: &[&str]) -> Self {
+        self.set_channels(names);
+        self
+    }
+
+    /// Keep only the channels with these names (see
+    /// [`with_channels()`](Self::with_channels)).
+    pub fn set_channels(&mut self, names: &[&str]) {
+        self.selection.set_channels(names);
+    }
+
+    /// Keep only the channel groups with these (acquisition) names.
+    pub fn with_groups(mut self, names: &[&str]) -> Self {
+        self.set_groups(names);
+        self
+    }
+
+    /// Keep only the channel groups with these (acquisition) names.
+    pub fn set_groups(&mut self, names: &[&str]) {
+        self.selection.set_groups(names);
+    }
+
+    /// Write the data compressed in DZ blocks (`true`) or in plain DT and
+    /// SD blocks (`false`, the default).
+    ///
+    /// Records are transposed before deflating, which compresses slowly
+    /// changing signals best.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.set_compression(compression);
+        self
+    }
+
+    /// Write the data compressed or not (see
+    /// [`with_compression()`](Self::with_compression)).
+    #[cfg(feature = "compression")]
+    pub fn set_compression(&mut self, compression: bool) {
+        self.compression = compression;
+    }
+
+    /// Whether the data is written compressed.
+    pub fn compression(&self) -> bool {
+        self.compression
+    }
+
+    /// Write the data in the column oriented storage of MDF 4.2 (`true`)
+    /// or in records (`false`, the default).
+    ///
+    /// Each kept channel then gets a data group and channel group of its
+    /// own. The group of the time channel is the master of the other
+    /// groups of its source group, which link to it as remote master. The
+    /// values are written in DV blocks and the invalidation bits in DI
+    /// blocks, one byte per value, and the output is an MDF 4.20 file.
+    pub fn with_column_storage(mut self, column_storage: bool) -> Self {
+        self.set_column_storage(column_storage);
+        self
+    }
+
+    /// Write the data column oriented or not (see
+    /// [`with_column_storage()`](Self::with_column_storage)).
+    pub fn set_column_storage(&mut self, column_storage: bool) {
+        self.column_storage = column_storage;
+    }
+
+    /// Whether the data is written in column oriented storage.
+    pub fn column_storage(&self) -> bool {
+        self.column_storage
+    }
+}
+
+/// Rewrite an MDF file with a normalized layout.
+///
+/// The output has one channel group per data group and no record IDs. The
+/// data of each group is written in blocks of up to 4 MiB, linked by a DL
+/// block if there are several, and VLSD values are collected in one signal
+/// data block per channel. Dropped channels are left out of the records,
+/// invalidation bytes are kept. In column oriented storage the blocks of a
+/// column with invalidation bits are linked by an LD block instead.
+///
+/// Channel names, units, comments, sources and conversions (including the
+/// texts and conversions they reference) are copied, as are the header
+/// comment, all attachments and all events. Channel compositions, sample
+/// reductions and channel groups holding VLSD data of other groups are not
+/// copied.
+///
+/// Reading compressed input requires the `compression` feature.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `output_path` - Destination path for the rewritten file
+/// * `options` - Kept structure and compression
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading or writing fails.
+pub fn transcode(input_path: &str, output_path: &str, options: &TranscodeOptions) -> Result<()> {
+    let mut writer = MdfWriter::new(output_path)?;
+    transcode_to_writer(input_path, &mut writer, options)
+}
+
+/// Rewrite an MDF file into any [`MdfWriter`] backend.
+///
+/// Works like [`transcode()`], but writes through `writer` instead of to a
+/// path. `writer` must be new; the file is initialized and finalized here.
+///
+/// # Arguments
+/// * `input_path` - Path to the source MF4 file
+/// * `writer` - Destination of the rewritten file
+/// * `options` - Kept structure and compression
+///
+/// # Returns
+/// `Ok(())` on success or an [`crate::Error`] if reading or writing fails.
+pub fn transcode_to_writer<W: MdfWrite>(
+    input_path: &str,
+    writer: &mut MdfWriter<W>,
+    options: &TranscodeOptions,
+) -> Result<()> {
+    let mdf = MdfFile::parse_from_file(input_path)?;
+    let header = &mdf.header;
+    writer.init_mdf_file()?;
+    if options.column_storage() {
+        writer.set_format_version(420)?;
+    }
+    writer.set_start_time_ns(header.start_time_ns)?;
+    writer.set_local_time(header.time_flags & 1 != 0)?;
+    if header.time_flags & 2 != 0 {
+        writer.set_time_zone(header.tz_offset_min, header.dst_offset_min)?;
+    }
+    writer.set_time_quality(header.time_quality)?;
+
+    let comment = copy_text_block(writer, &mdf.mmap, header.comment_addr)?;
+    if comment != 0 {
+        let hd_addr = writer
+            .get_block_position("hd_block")
+            .ok_or_else(|| Error::BlockSerializationError("Missing header block".to_string()))?;
+        writer.update_link(hd_addr + 64, comment)?;
+    }
+
+    let mut prev_dg: Option<String> = None;
+    for dg in &mdf.data_groups {
+        for cg in &dg.channel_groups {
+            // VLSD channel groups only hold values of channels of other groups
+            if cg.block.flags & 1 != 0 {
+                continue;
+            }
+            if let Some(dg_id) = transcode_group(&mdf, writer, dg, cg, prev_dg.as_deref(), options)?
+            {
+                prev_dg = Some(dg_id);
+            }
+        }
+    }
+
+    let all_time = [(f64::NEG_INFINITY, f64::INFINITY)];
+    copy_attachments_and_events(&mdf, std::slice::from_mut(writer), &all_time, false)?;
+    writer.finalize()
+}
+
+/// Placement of a kept channel in the source and in the output records.
+struct ChannelLayout {
+    /// Byte offset in the source record (after the record ID)
+    source_offset: usize,
+    /// Byte offset in the output record
+    offset: usize,
+    /// Number of bytes holding the value
+    len: usize,
+}
+
+/// A written channel group and its data, collected into blocks.
+struct OutputGroup {
+    /// Kept channels of the group (indices into the kept channels)
+    channels: Vec<usize>,
+    dg_addr: u64,
+    /// Address of the cycle count of the channel group block
+    cycle_count_addr: u64,
+    /// Bytes of a record in the data blocks
+    record_len: usize,
+    /// Column oriented storage: position of the invalidation bit of the
+    /// channel in the invalidation bytes of the packed records, if any
+    column_invalidation: Option<Option<u32>>,
+    chunk: Vec<u8>,
+    invalidation: Vec<u8>,
+    /// Written data blocks and invalidation blocks: address and length
+    blocks: Vec<(u64, u64)>,
+    invalidation_blocks: Vec<(u64, u64)>,
+}
+
+impl OutputGroup {
+    /// Append the values of the group from a packed record.
+    fn push(&mut self, record: &[u8], layouts: &[ChannelLayout], record_size: usize) {
+        let Some(invalidation) = self.column_invalidation else {
+            self.chunk.extend_from_slice(record);
+            return;
+        };
+        let layout = &layouts[self.channels[0]];
+        self.chunk
+            .extend_from_slice(&record[layout.offset..layout.offset + layout.len]);
+        if let Some(bit) = invalidation {
+            let byte = record[record_size + (bit >> 3) as usize];
+            self.invalidation.push((byte >> (bit & 7)) & 1);
+        }
+    }
+
+    /// Write the pending data as a data block and an invalidation block.
+    fn flush<W: MdfWrite>(
+        &mut self,
+        writer: &mut MdfWriter<W>,
+        options: &TranscodeOptions,
+    ) -> Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+        let block_type = match self.column_invalidation {
+            Some(_) => *b"DV",
+            None => *b"DT",
+        };
+        let block = write_data_block(writer, block_type, &self.chunk, self.record_len, options)?;
+        self.blocks.push(block);
+        self.chunk.clear();
+        if !self.invalidation.is_empty() {
+            let block = write_data_block(writer, *b"DI", &self.invalidation, 0, options)?;
+            self.invalidation_blocks.push(block);
+            self.invalidation.clear();
+        }
+        Ok(())
+    }
+
+    /// Link the written blocks from the data group: directly, through a DL
+    /// block, or through an LD block if there are invalidation blocks.
+    fn finish<W: MdfWrite>(&self, writer: &mut MdfWriter<W>, cycle_count: u64) -> Result<()> {
+        let mut offsets = Vec::with_capacity(self.blocks.len());
+        let mut offset = 0;
+        for &(_, len) in &self.blocks {
+            offsets.push(offset);
+            offset += len;
+        }
+        let addrs = self.blocks.iter().map(|&(addr, _)| addr).collect();
+        let data_addr = if !self.invalidation_blocks.is_empty() {
+            // LD offsets count records
+            let record_len = self.record_len.max(1) as u64;
+            let offsets = offsets.iter().map(|o| o / record_len).collect::<Vec<_>>();
+            let invalidation = self.invalidation_blocks.iter().map(|&(addr, _)| addr);
+            writer.write_block(&list_data_block(addrs, invalidation.collect(), offsets))?
+        } else {
+            match self.blocks.len() {
+                0 => 0,
+                1 => self.blocks[0].0,
+                _ => {
+                    let list = DataListBlock::new_with_offsets(addrs, offsets);
+                    writer.write_block(&list.to_bytes()?)?
+                }
+            }
+        };
+        writer.update_link(self.dg_addr + 40, data_addr)?;
+        writer.update_link(self.cycle_count_addr, cycle_count)
+    }
+}
+
+/// Serialize an LD block listing data blocks with their invalidation
+/// blocks and the index of the first record in each.
+fn list_data_block(data: Vec<u64>, invalidation: Vec<u64>, sample_offsets: Vec<u64>) -> Vec<u8> {
+    // ld_flags bit 31: invalidation data present
+    const INVALIDATION_DATA: u32 = 1 << 31;
+    let count = data.len();
+    let links = 1 + 2 * count;
+    let length = 24 + 8 * links + 8 + 8 * count;
+    let mut bytes = Vec::with_capacity(length);
+    bytes.extend_from_slice(b"##LD");
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&(length as u64).to_le_bytes());
+    bytes.extend_from_slice(&(links as u64).to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    for addr in data.into_iter().chain(invalidation) {
+        bytes.extend_from_slice(&addr.to_le_bytes());
+    }
+    bytes.extend_from_slice(&INVALIDATION_DATA.to_le_bytes());
+    bytes.extend_from_slice(&(count as u32).to_le_bytes());
+    for offset in sample_offsets {
+        bytes.extend_from_slice(&offset.to_le_bytes());
+    }
+    bytes
+}
+
+/// Write a channel group of the source as a data group of its own, or as
+/// one data group per channel in column oriented storage.
+///
+/// Returns the ID of the last written data group, or `None` if the group
+/// is dropped.
+fn transcode_group<W: MdfWrite>(
+    mdf: &MdfFile,
+    writer: &mut MdfWriter<W>,
+    dg: &RawDataGroup,
+    cg: &RawChannelGroup,
+    prev_dg: Option<&str>,
+    options: &TranscodeOptions,
+) -> Result<Option<String>> {
+    let mmap = &mdf.mmap;
+    let mut channel_blocks = Vec::with_capacity(cg.raw_channels.len());
+    for ch in &cg.raw_channels {
+        let mut block = ch.block.clone();
+        block.resolve_name(mmap)?;
+        channel_blocks.push(block);
+    }
+    let group_name = read_string_block(mmap, cg.block.acq_name_addr)?;
+    let time = time_channel_index(cg);
+    let kept = options
+        .selection
+        .kept_channels(group_name.as_deref(), &channel_blocks, time);
+    if kept.is_empty() {
+        return Ok(None);
+    }
+
+    // Dropped channels leave no gaps in the records
+    let pack = kept.len() < channel_blocks.len();
+    let mut layouts = Vec::with_capacity(kept.len());
+    let mut record_size = 0;
+    for &i in &kept {
+        let ch = &channel_blocks[i];
+        let source_offset = ch.byte_offset as usize;
+        let len = (ch.bit_offset as usize + ch.bit_count as usize).div_ceil(8);
+        let offset = if pack { record_size } else { source_offset };
+        record_size = record_size.max(offset + len);
+        layouts.push(ChannelLayout {
+            source_offset,
+            offset,
+            len,
+        });
+    }
+    let source_size = cg.block.record_size as usize;
+    if !pack {
+        record_size = source_size;
+    }
+    let invalidation_size = cg.block.invalidation_size as usize;
+
+    // All kept channels in one group, or one group per channel with the
+    // master channel first
+    let mut order: Vec<usize> = (0..kept.len()).collect();
+    let time = time.and_then(|t| kept.iter().position(|&i| i == t));
+    let groups = if options.column_storage() {
+        if let Some(time) = time {
+            order.remove(time);
+            order.insert(0, time);
+        }
+        order.into_iter().map(|k| vec![k]).collect()
+    } else {
+        vec![order]
+    };
+
+    let mut outputs: Vec<OutputGroup> = Vec::with_capacity(groups.len());
+    let mut cn_addrs = vec![0; kept.len()];
+    let mut prev_dg = prev_dg.map(str::to_string);
+    let mut master_cg = None;
+    for channels in groups {
+        let column_invalidation = options.column_storage().then(|| {
+            let ch = &channel_blocks[kept[channels[0]]];
+            let valid = invalidation_size > 0 && ch.flags & ChannelBlock::FLAG_INVAL_BIT_VALID != 0;
+            valid.then_some(ch.pos_invalidation_bit)
+        });
+        let (data_size, group_invalidation_size) = match column_invalidation {
+            Some(bit) => (layouts[channels[0]].len, usize::from(bit.is_some())),
+            None => (record_size, invalidation_size),
+        };
+
+        let dg_id = writer.add_data_group(prev_dg.as_deref())?;
+        let cg_id = writer.add_channel_group_with_dg(&dg_id, None, |block| {
+            block.flags = cg.block.flags;
+            if master_cg.is_some() {
+                block.flags |= ChannelGroupBlock::FLAG_REMOTE_MASTER;
+                block.master_cg_addr = master_cg;
+            }
+            block.path_separator = cg.block.path_separator;
+            block.record_size = data_size as u32;
+            block.invalidation_size = group_invalidation_size as u32;
+        })?;
+        let position = |id: &str| {
+            writer
+                .get_block_position(id)
+                .ok_or_else(|| Error::BlockSerializationError(format!("Missing block {}", id)))
+        };
+        let dg_addr = position(&dg_id)?;
+        let cg_addr = position(&cg_id)?;
+        // cg_cycle_count follows cg_record_id after the links
+        let data_offset = if master_cg.is_some() { 80 } else { 72 };
+        for (link, addr) in [(40, cg.block.acq_name_addr), (64, cg.block.comment_addr)] {
+            let copy = copy_text_block(writer, mmap, addr)?;
+            writer.update_link(cg_addr + link, copy)?;
+        }
+        let source = copy_source(writer, mmap, cg.block.acq_source_addr)?;
+        writer.update_link(cg_addr + 48, source)?;
+
+        // Channels, each linked from the previous one
+        let mut prev_link = cg_addr + 32;
+        for &k in &channels {
+            let source = &channel_blocks[kept[k]];
+            let (offset, invalidation_bit) = match column_invalidation {
+                Some(_) => (0, 0),
+                None => (layouts[k].offset as u32, source.pos_invalidation_bit),
+            };
+            let addr = copy_channel(writer, mmap, source, offset, invalidation_bit)?;
+            writer.update_link(prev_link, addr)?;
+            prev_link = addr + 24;
+            cn_addrs[k] = addr;
+        }
+        if column_invalidation.is_some() && time == Some(channels[0]) {
+            master_cg = Some(cg_addr);
+        }
+
+        outputs.push(OutputGroup {
+            channels,
+            dg_addr,
+            cycle_count_addr: cg_addr + data_offset + 8,
+            record_len: data_size
+                + group_invalidation_size * usize::from(column_invalidation.is_none()),
+            column_invalidation,
+            chunk: Vec::new(),
+            invalidation: Vec::new(),
+            blocks: Vec::new(),
+            invalidation_blocks: Vec::new(),
+        });
+        prev_dg = Some(dg_id);
+    }
+
+    // Values of VLSD channels are read from their signal data, in the
+    // order of the records
+    let mut vlsd = Vec::new();
+    for (k, &i) in kept.iter().enumerate() {
+        let ch = &cg.raw_channels[i];
+        if ch.block.channel_type == 1 && ch.block.data_addr != 0 {
+            vlsd.push((k, ch.records(dg, cg, mmap)?, Vec::new()));
+        }
+    }
+
+    let id_len = dg.block.record_id_size as usize;
+    let record_len = record_size + invalidation_size;
+    let mut record = vec![0u8; record_len];
+    // Bytes added to the pending blocks of all groups per record
+    let step: usize = outputs
+        .iter()
+        .map(|o| o.record_len + usize::from(matches!(o.column_invalidation, Some(Some(_)))))
+        .sum();
+    let mut pending = 0;
+    let mut cycle_count = 0u64;
+    let mut records = dg.group_records(cg, mmap)?;
+    while let Some(source) = records.next_record()? {
+        let source = source.get(id_len..).unwrap_or_default();
+        if source.len() < source_size + invalidation_size {
+            return Err(Error::TooShortBuffer {
+                actual: source.len(),
+                expected: source_size + invalidation_size,
+                file: file!(),
+                line: line!(),
+            });
+        }
+        if pack {
+            for layout in &layouts {
+                let value = source.get(layout.source_offset..layout.source_offset + layout.len);
+                let value = value.ok_or_else(|| {
+                    Error::BlockSerializationError(
+                        "Channel exceeds the record size of its group".to_string(),
+                    )
+                })?;
+                record[layout.offset..layout.offset + layout.len].copy_from_slice(value);
+            }
+        } else {
+            record[..record_size].copy_from_slice(&source[..record_size]);
+        }
+        record[record_size..]
+            .copy_from_slice(&source[source_size..source_size + invalidation_size]);
+
+        // Offsets of VLSD values into the rewritten signal data
+        for (k, values, data) in &mut vlsd {
+            let value = values.next().ok_or_else(|| {
+                Error::BlockSerializationError("Missing VLSD value of a record".to_string())
+            })??;
+            let layout = &layouts[*k];
+            let offset = (data.len() as u64).to_le_bytes();
+            let len = layout.len.min(8);
+            record[layout.offset..layout.offset + len].copy_from_slice(&offset[..len]);
+            data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+
+        // The blocks of all groups cover the same records
+        if pending > 0 && pending + step > MAX_BLOCK_DATA {
+            for output in &mut outputs {
+                output.flush(writer, options)?;
+            }
+            pending = 0;
+        }
+        for output in &mut outputs {
+            output.push(&record, &layouts, record_size);
+        }
+        pending += step;
+        cycle_count += 1;
+    }
+    for output in &mut outputs {
+        output.flush(writer, options)?;
+        output.finish(writer, cycle_count)?;
+    }
+
+    for (k, _, data) in &vlsd {
+        if data.is_empty() {
+            continue;
+        }
+        let (addr, _) = write_data_block(writer, *b"SD", data, 0, options)?;
+        writer.update_link(cn_addrs[*k] + 64, addr)?;
+    }
+    Ok(prev_dg)
+}
+
+/// Copy a channel block with its texts, source and conversion, placed at
+/// `byte_offset` of the output records, and return its new address.
+fn copy_channel<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    mmap: &[u8],
+    source: &ChannelBlock,
+    byte_offset: u32,
+    pos_invalidation_bit: u32,
+) -> Result<u64> {
+    let block = ChannelBlock {
+        name_addr: copy_text_block(writer, mmap, source.name_addr)?,
+        source_addr: copy_source(writer, mmap, source.source_addr)?,
+        conversion_addr: copy_conversion(writer, mmap, source.conversion_addr, 0)?,
+        unit_addr: copy_text_block(writer, mmap, source.unit_addr)?,
+        comment_addr: copy_text_block(writer, mmap, source.comment_addr)?,
+        channel_type: source.channel_type,
+        sync_type: source.sync_type,
+        data_type: source.data_type,
+        bit_offset: source.bit_offset,
+        byte_offset,
+        bit_count: source.bit_count,
+        flags: source.flags,
+        pos_invalidation_bit,
+        precision: source.precision,
+        min_raw_value: source.min_raw_value,
+        max_raw_value: source.max_raw_value,
+        lower_limit: source.lower_limit,
+        upper_limit: source.upper_limit,
+        lower_ext_limit: source.lower_ext_limit,
+        upper_ext_limit: source.upper_ext_limit,
+        ..ChannelBlock::default()
+    };
+    writer.write_block(&block.to_bytes()?)
+}
+
+/// Write `data` as a DT or SD block, or as a DZ block if compression is
+/// selected, and return its address and uncompressed length.
+///
+/// `record_len` is the transposition width of compressed records; 0 only
+/// deflates.
+fn write_data_block<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    block_type: [u8; 2],
+    data: &[u8],
+    record_len: usize,
+    options: &TranscodeOptions,
+) -> Result<(u64, u64)> {
+    #[cfg(feature = "compression")]
+    if options.compression() {
+        use crate::blocks::{DzBlock, DzCompressionType};
+
+        let bytes = if record_len > 1 && data.len().is_multiple_of(record_len) {
+            DzBlock::compress(
+                block_type,
+                data,
+                DzCompressionType::TranspositionDeflate,
+                record_len as u32,
+            )?
+        } else {
+            DzBlock::compress(block_type, data, DzCompressionType::Deflate, 0)?
+        };
+        let addr = writer.write_block(&bytes)?;
+        return Ok((addr, data.len() as u64));
+    }
+    #[cfg(not(feature = "compression"))]
+    let _ = (record_len, options);
+
+    let header = BlockHeader {
+        id: format!("##{}", String::from_utf8_lossy(&block_type)),
+        reserved: 0,
+        length: 24 + data.len() as u64,
+        link_count: 0,
+    };
+    let mut bytes = header.to_bytes()?;
+    bytes.extend_from_slice(data);
+    let addr = writer.write_block(&bytes)?;
+    Ok((addr, data.len() as u64))
+}
+
+/// Copy the source information block at `addr` with its texts and return
+/// its new address; 0 stays 0.
+fn copy_source<W: MdfWrite>(writer: &mut MdfWriter<W>, mmap: &[u8], addr: u64) -> Result<u64> {
+    if addr == 0 {
+        return Ok(0);
+    }
+    let mut block = SourceBlock::from_bytes(block_bytes(mmap, addr)?)?;
+    block.name_addr = copy_text_block(writer, mmap, block.name_addr)?;
+    block.path_addr = copy_text_block(writer, mmap, block.path_addr)?;
+    block.comment_addr = copy_text_block(writer, mmap, block.comment_addr)?;
+    writer.write_block(&block.to_bytes()?)
+}
+
+/// Copy the conversion block at `addr` with the texts and conversions it
+/// references and return its new address; 0 stays 0.
+fn copy_conversion<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    mmap: &[u8],
+    addr: u64,
+    depth: usize,
+) -> Result<u64> {
+    if addr == 0 {
+        return Ok(0);
+    }
+    if depth > MAX_CONVERSION_DEPTH {
+        return Err(Error::BlockSerializationError(
+            "Conversions are nested too deeply".to_string(),
+        ));
+    }
+    let mut block = ConversionBlock::from_bytes(block_bytes(mmap, addr)?)?;
+    let copy_text = |writer: &mut MdfWriter<W>, addr: Option<u64>| -> Result<Option<u64>> {
+        let copy = copy_text_block(writer, mmap, addr.unwrap_or(0))?;
+        Ok((copy != 0).then_some(copy))
+    };
+    block.name_addr = copy_text(writer, block.name_addr)?;
+    block.unit_addr = copy_text(writer, block.unit_addr)?;
+    block.comment_addr = copy_text(writer, block.comment_addr)?;
+    let inverse = copy_conversion(writer, mmap, block.inverse_addr.unwrap_or(0), depth + 1)?;
+    block.inverse_addr = (inverse != 0).then_some(inverse);
+
+    // References are texts or conversions
+    for reference in &mut block.refs {
+        if *reference == 0 {
+            continue;
+        }
+        *reference = match &block_bytes(mmap, *reference)?[..4] {
+            b"##CC" => copy_conversion(writer, mmap, *reference, depth + 1)?,
+            _ => copy_text_block(writer, mmap, *reference)?,
+        };
+    }
+    writer.write_block(&block.to_bytes()?)
+}
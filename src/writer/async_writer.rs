@@ -0,0 +1,139 @@
+//! Async writer backend for tokio based services (`async` feature).
+//!
+//! [`AsyncMdfWriter`] pairs an [`MdfWriter`] writing into a [`SpoolWriter`]
+//! with an async destination implementing tokio's `AsyncWrite + AsyncSeek`,
+//! such as [`tokio::fs::File`]. All block and record methods of [`MdfWriter`]
+//! are available through `Deref`; they only encode into memory and never
+//! block. The spooled bytes are written to the destination by the async
+//! [`flush()`](AsyncMdfWriter::flush), [`flush_now()`](AsyncMdfWriter::flush_now)
+//! and [`finalize()`](AsyncMdfWriter::finalize) methods.
+//!
+//! Flush policies configured on the inner writer cannot perform async I/O;
+//! check [`pending_bytes()`](AsyncMdfWriter::pending_bytes) and call
+//! `flush().await` to bound memory use.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mdf4_rs::writer::AsyncMdfWriter;
+//! use mdf4_rs::{DataType, DecodedValue};
+//!
+//! # async fn run() -> mdf4_rs::Result<()> {
+//! let mut writer = AsyncMdfWriter::create("telemetry.mf4").await?;
+//! writer.init_mdf_file()?;
+//! let cg = writer.add_channel_group(None, |_| {})?;
+//! writer.add_channel(&cg, None, |ch| {
+//!     ch.data_type = DataType::FloatLE;
+//!     ch.bit_count = 64;
+//! })?;
+//! writer.start_data_block_for_cg(&cg, 0)?;
+//! for i in 0..1000 {
+//!     writer.write_record(&cg, &[DecodedValue::Float(i as f64)])?;
+//!     if writer.pending_bytes() > 64 * 1024 {
+//!         writer.flush().await?;
+//!     }
+//! }
+//! writer.finish_data_block(&cg)?;
+//! writer.finalize().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use core::ops::{Deref, DerefMut};
+use std::io::SeekFrom;
+use std::path::Path;
+
+use tokio::io::{AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+use super::{MdfWriter, SpoolWriter};
+use crate::Result;
+
+/// MDF writer whose file I/O is performed asynchronously.
+///
+/// See the [module documentation](self) for how the sync writer API and the
+/// async flushing methods work together.
+pub struct AsyncMdfWriter<W> {
+    inner: MdfWriter<SpoolWriter>,
+    io: W,
+    /// Current position of `io`
+    io_position: u64,
+}
+
+impl AsyncMdfWriter<tokio::fs::File> {
+    /// Create (or truncate) the file at `path` and write to it asynchronously.
+    pub async fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let file = tokio::fs::File::create(path).await?;
+        Ok(Self::new(file))
+    }
+}
+
+impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncMdfWriter<W> {
+    /// Write to an async destination, which must be positioned at its start.
+    pub fn new(io: W) -> Self {
+        Self {
+            inner: MdfWriter::from_writer(SpoolWriter::new()),
+            io,
+            io_position: 0,
+        }
+    }
+
+    /// Number of encoded bytes not yet written to the destination.
+    pub fn pending_bytes(&self) -> usize {
+        self.inner.writer().pending_bytes()
+    }
+
+    /// Write all spooled data to the destination and flush it.
+    ///
+    /// Like [`MdfWriter::flush()`], this does not update cycle counts or
+    /// create DL blocks; use [`flush_now()`](Self::flush_now) for that.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        self.write_spooled().await
+    }
+
+    /// Make all records written so far readable, then write and flush them
+    /// (see [`MdfWriter::flush_now()`]).
+    pub async fn flush_now(&mut self) -> Result<()> {
+        self.inner.flush_now()?;
+        self.write_spooled().await
+    }
+
+    /// Finalize the file and write all remaining data to the destination.
+    pub async fn finalize(&mut self) -> Result<()> {
+        self.inner.finalize()?;
+        self.write_spooled().await
+    }
+
+    /// Consume the writer and return the destination.
+    ///
+    /// Data not written by one of the async flushing methods is discarded.
+    pub fn into_inner(self) -> W {
+        self.io
+    }
+
+    async fn write_spooled(&mut self) -> Result<()> {
+        for (pos, data) in self.inner.writer_mut().take_ops() {
+            if self.io_position != pos {
+                self.io.seek(SeekFrom::Start(pos)).await?;
+            }
+            self.io.write_all(&data).await?;
+            self.io_position = pos + data.len() as u64;
+        }
+        self.io.flush().await?;
+        Ok(())
+    }
+}
+
+impl<W> Deref for AsyncMdfWriter<W> {
+    type Target = MdfWriter<SpoolWriter>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<W> DerefMut for AsyncMdfWriter<W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,234 @@
+//! Background I/O pipeline for high-rate captures.
+//!
+//! [`BackgroundWriter`] is an [`MdfWrite`] backend that moves file writes off
+//! the caller thread. Records are still encoded by [`MdfWriter`] on the
+//! caller thread, but the encoded bytes are collected into chunks which are
+//! handed to a worker thread through a bounded channel. A slow disk therefore
+//! only stalls the caller once the queue is full, instead of on every buffer
+//! flush. [`MdfWriter`] emits uncompressed DT blocks, so the worker has no
+//! block compression to do; should DZ output be added, it belongs here.
+//!
+//! Link updates (seek + small write) that fall into the chunk currently being
+//! filled are applied in memory; all other writes are queued with their file
+//! position, so the worker applies them in exactly the order they were made.
+//!
+//! I/O errors on the worker thread are reported by the next call that needs
+//! the worker, at the latest by [`flush()`](MdfWrite::flush) (and therefore
+//! [`MdfWriter::finalize()`]).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mdf4_rs::MdfWriter;
+//!
+//! let mut writer = MdfWriter::new_background("capture.mf4")?;
+//! writer.init_mdf_file()?;
+//! // ... add channels and write records as usual ...
+//! writer.finalize()?;
+//! # Ok::<(), mdf4_rs::Error>(())
+//! ```
+
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::JoinHandle;
+use std::vec::Vec;
+
+use super::{MdfWrite, MdfWriter};
+use crate::{Error, Result};
+
+/// Default size of the chunks handed to the worker thread (1 MB).
+const DEFAULT_CHUNK_SIZE: usize = 1_048_576;
+
+/// Default number of chunks that may be queued before the caller blocks.
+const DEFAULT_QUEUE_DEPTH: usize = 8;
+
+enum Command {
+    /// Write `data` at file position `pos`
+    Write { pos: u64, data: Vec<u8> },
+    /// Flush the file and acknowledge on the given channel
+    Flush(SyncSender<()>),
+}
+
+/// [`MdfWrite`] backend that performs file I/O on a worker thread.
+///
+/// Create it through [`MdfWriter::new_background()`] or
+/// [`BackgroundWriter::with_config()`] and [`MdfWriter::from_writer()`].
+/// Dropping the writer waits for all queued writes to complete; call
+/// [`MdfWriter::finalize()`] first to observe any I/O error.
+pub struct BackgroundWriter {
+    sender: Option<SyncSender<Command>>,
+    worker: Option<JoinHandle<io::Result<()>>>,
+    /// Buffers returned by the worker for reuse
+    recycled: Receiver<Vec<u8>>,
+    /// Bytes not yet handed to the worker
+    chunk: Vec<u8>,
+    /// File position of the first byte of `chunk`
+    chunk_start: u64,
+    chunk_size: usize,
+    position: u64,
+}
+
+impl BackgroundWriter {
+    /// Create a background writer with 1 MB chunks and a queue of 8 chunks.
+    pub fn new(path: &str) -> Result<Self> {
+        Self::with_config(path, DEFAULT_CHUNK_SIZE, DEFAULT_QUEUE_DEPTH)
+    }
+
+    /// Create a background writer with a custom chunk size and queue depth.
+    ///
+    /// At most `queue_depth` chunks of roughly `chunk_size` bytes are in
+    /// flight; when the queue is full, writes block until the worker catches
+    /// up. This bounds the memory used for buffering to about
+    /// `chunk_size * (queue_depth + 2)` bytes.
+    pub fn with_config(path: &str, chunk_size: usize, queue_depth: usize) -> Result<Self> {
+        let file = File::create(path)?;
+        let (sender, receiver) = mpsc::sync_channel(queue_depth.max(1));
+        let (recycle_sender, recycled) = mpsc::sync_channel(queue_depth.max(1));
+        let worker = std::thread::Builder::new()
+            .name("mdf4-writer-io".into())
+            .spawn(move || run_worker(file, receiver, recycle_sender))?;
+        let chunk_size = chunk_size.max(1);
+        Ok(Self {
+            sender: Some(sender),
+            worker: Some(worker),
+            recycled,
+            chunk: Vec::with_capacity(chunk_size),
+            chunk_start: 0,
+            chunk_size,
+            position: 0,
+        })
+    }
+
+    /// Queue a command, reporting the worker's error if it has stopped.
+    fn send(&mut self, command: Command) -> Result<()> {
+        let sent = match &self.sender {
+            Some(sender) => sender.send(command).is_ok(),
+            None => false,
+        };
+        if sent {
+            Ok(())
+        } else {
+            Err(self.worker_error())
+        }
+    }
+
+    /// Hand the current chunk to the worker and start a new one at `position`.
+    fn send_chunk(&mut self, position: u64) -> Result<()> {
+        if !self.chunk.is_empty() {
+            let mut next = self
+                .recycled
+                .try_recv()
+                .unwrap_or_else(|_| Vec::with_capacity(self.chunk_size));
+            next.clear();
+            let data = core::mem::replace(&mut self.chunk, next);
+            let pos = self.chunk_start;
+            self.send(Command::Write { pos, data })?;
+        }
+        self.chunk_start = position;
+        Ok(())
+    }
+
+    /// Stop the worker and return the error that made it stop.
+    fn worker_error(&mut self) -> Error {
+        self.sender = None;
+        match self.worker.take().map(JoinHandle::join) {
+            Some(Ok(Err(e))) => Error::IOError(e),
+            Some(Err(_)) => Error::IOError(io::Error::other("background writer thread panicked")),
+            _ => Error::IOError(io::Error::other("background writer thread stopped")),
+        }
+    }
+}
+
+fn run_worker(
+    mut file: File,
+    receiver: Receiver<Command>,
+    recycle: SyncSender<Vec<u8>>,
+) -> io::Result<()> {
+    let mut position = 0u64;
+    for command in receiver {
+        match command {
+            Command::Write { pos, data } => {
+                if pos != position {
+                    file.seek(SeekFrom::Start(pos))?;
+                }
+                file.write_all(&data)?;
+                position = pos + data.len() as u64;
+                // The caller may have enough buffers already
+                let _ = recycle.try_send(data);
+            }
+            Command::Flush(ack) => {
+                file.flush()?;
+                let _ = ack.send(());
+            }
+        }
+    }
+    file.flush()
+}
+
+impl MdfWrite for BackgroundWriter {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        let chunk_end = self.chunk_start + self.chunk.len() as u64;
+        let end = self.position + bytes.len() as u64;
+        if self.position >= self.chunk_start && end <= chunk_end {
+            // Patch of data that has not been handed to the worker yet
+            let offset = (self.position - self.chunk_start) as usize;
+            self.chunk[offset..offset + bytes.len()].copy_from_slice(bytes);
+        } else {
+            if self.position != chunk_end {
+                self.send_chunk(self.position)?;
+            }
+            self.chunk.extend_from_slice(bytes);
+            if self.chunk.len() >= self.chunk_size {
+                self.send_chunk(end)?;
+            }
+        }
+        self.position = end;
+        Ok(())
+    }
+
+    fn seek(&mut self, pos: u64) -> Result<u64> {
+        // Applied lazily by the next write
+        self.position = pos;
+        Ok(pos)
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let chunk_end = self.chunk_start + self.chunk.len() as u64;
+        self.send_chunk(chunk_end)?;
+        let (ack_sender, ack) = mpsc::sync_channel(1);
+        self.send(Command::Flush(ack_sender))?;
+        match ack.recv() {
+            Ok(()) => Ok(()),
+            Err(_) => Err(self.worker_error()),
+        }
+    }
+}
+
+impl Drop for BackgroundWriter {
+    fn drop(&mut self) {
+        let chunk_end = self.chunk_start + self.chunk.len() as u64;
+        let _ = self.send_chunk(chunk_end);
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl MdfWriter<BackgroundWriter> {
+    /// Creates a new MdfWriter whose file I/O runs on a background thread.
+    ///
+    /// Record encoding stays on the caller thread; encoded data is handed to
+    /// an I/O worker through a bounded queue (see [`BackgroundWriter`]).
+    /// Use [`BackgroundWriter::with_config()`] with
+    /// [`from_writer()`](MdfWriter::from_writer) to tune chunk size and queue
+    /// depth.
+    pub fn new_background(path: &str) -> Result<Self> {
+        Ok(Self::from_writer(BackgroundWriter::new(path)?))
+    }
+}
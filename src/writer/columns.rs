@@ -0,0 +1,192 @@
+//! Column-oriented batch writing.
+//!
+//! Data coming from CSV readers, Arrow arrays or numeric libraries is usually
+//! stored channel-major (one slice per signal), while MDF records are
+//! row-major. [`write_columns()`](MdfWriter::write_columns) takes one slice
+//! per channel, identified by channel name, and transposes them into records
+//! internally, without building per-row [`DecodedValue`] slices.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let times = vec![0.0, 0.1, 0.2];
+//! let temps = vec![21.5f32, 21.6, 21.8];
+//! writer.start_data_block_for_cg(&cg, 0)?;
+//! writer.write_columns(&cg, &[("Time", times[..].into()), ("Temp", temps[..].into())])?;
+//! writer.finish_data_block(&cg)?;
+//! ```
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use super::data::ChannelEncoder;
+use super::{MdfWrite, MdfWriter, typed::ChannelValue};
+use crate::{Error, Result, types::DecodedValue};
+
+/// One column of values for [`MdfWriter::write_columns()`].
+///
+/// Created with `From` from slices (or vectors) of the primitive numeric
+/// types or of [`DecodedValue`]. Numeric columns must match the kind of the
+/// target channel (unsigned, signed or floating point); their width is
+/// adapted to the channel's bit count.
+#[derive(Debug, Clone, Copy)]
+pub enum ColumnData<'a> {
+    U8(&'a [u8]),
+    U16(&'a [u16]),
+    U32(&'a [u32]),
+    U64(&'a [u64]),
+    I8(&'a [i8]),
+    I16(&'a [i16]),
+    I32(&'a [i32]),
+    I64(&'a [i64]),
+    F32(&'a [f32]),
+    F64(&'a [f64]),
+    /// Arbitrary values, e.g. byte arrays
+    Values(&'a [DecodedValue]),
+}
+
+macro_rules! impl_column_from {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl<'a> From<&'a [$ty]> for ColumnData<'a> {
+                fn from(values: &'a [$ty]) -> Self {
+                    ColumnData::$variant(values)
+                }
+            }
+
+            impl<'a> From<&'a Vec<$ty>> for ColumnData<'a> {
+                fn from(values: &'a Vec<$ty>) -> Self {
+                    ColumnData::$variant(values)
+                }
+            }
+        )*
+    };
+}
+
+impl_column_from!(
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64,
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64,
+    f32 => F32, f64 => F64,
+    DecodedValue => Values
+);
+
+/// Encode `values[row]` with `enc`.
+#[inline]
+fn encode_at<T: ChannelValue>(values: &[T], row: usize, enc: &ChannelEncoder, buf: &mut [u8]) {
+    enc.encode(buf, &values[row].to_decoded());
+}
+
+impl ColumnData<'_> {
+    /// Number of values in the column.
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::U8(v) => v.len(),
+            ColumnData::U16(v) => v.len(),
+            ColumnData::U32(v) => v.len(),
+            ColumnData::U64(v) => v.len(),
+            ColumnData::I8(v) => v.len(),
+            ColumnData::I16(v) => v.len(),
+            ColumnData::I32(v) => v.len(),
+            ColumnData::I64(v) => v.len(),
+            ColumnData::F32(v) => v.len(),
+            ColumnData::F64(v) => v.len(),
+            ColumnData::Values(v) => v.len(),
+        }
+    }
+
+    /// Returns `true` if the column holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the values of this column can be stored with `enc`.
+    fn fits(&self, enc: &ChannelEncoder) -> bool {
+        match self {
+            ColumnData::U8(_) | ColumnData::U16(_) | ColumnData::U32(_) | ColumnData::U64(_) => {
+                matches!(enc, ChannelEncoder::UInt { .. })
+            }
+            ColumnData::I8(_) | ColumnData::I16(_) | ColumnData::I32(_) | ColumnData::I64(_) => {
+                matches!(enc, ChannelEncoder::Int { .. })
+            }
+            ColumnData::F32(_) | ColumnData::F64(_) => {
+                matches!(enc, ChannelEncoder::F32 { .. } | ChannelEncoder::F64 { .. })
+            }
+            ColumnData::Values(_) => true,
+        }
+    }
+
+    fn encode(&self, row: usize, enc: &ChannelEncoder, buf: &mut [u8]) {
+        match self {
+            ColumnData::U8(v) => encode_at(v, row, enc, buf),
+            ColumnData::U16(v) => encode_at(v, row, enc, buf),
+            ColumnData::U32(v) => encode_at(v, row, enc, buf),
+            ColumnData::U64(v) => encode_at(v, row, enc, buf),
+            ColumnData::I8(v) => encode_at(v, row, enc, buf),
+            ColumnData::I16(v) => encode_at(v, row, enc, buf),
+            ColumnData::I32(v) => encode_at(v, row, enc, buf),
+            ColumnData::I64(v) => encode_at(v, row, enc, buf),
+            ColumnData::F32(v) => encode_at(v, row, enc, buf),
+            ColumnData::F64(v) => encode_at(v, row, enc, buf),
+            ColumnData::Values(v) => enc.encode(buf, &v[row]),
+        }
+    }
+}
+
+impl<W: MdfWrite> MdfWriter<W> {
+    /// Append records given as one column per channel.
+    ///
+    /// Each entry pairs a channel name of `cg_id` with its values; all
+    /// columns must have the same length, which is the number of records
+    /// written. Channels without a column keep their record template value
+    /// (see [`set_record_template()`](Self::set_record_template)).
+    ///
+    /// Records are encoded into the same reusable batch buffer as
+    /// [`write_records()`](Self::write_records). If a flush policy is
+    /// configured, auto-flush is checked after all records are written.
+    pub fn write_columns(&mut self, cg_id: &str, columns: &[(&str, ColumnData<'_>)]) -> Result<()> {
+        let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+            Error::BlockSerializationError("no open DT block for this channel group".into())
+        })?;
+
+        let mut mapped = Vec::with_capacity(columns.len());
+        for (name, column) in columns {
+            let index = dt
+                .channels
+                .iter()
+                .position(|ch| ch.name.as_deref() == Some(*name))
+                .ok_or_else(|| {
+                    Error::BlockSerializationError(format!(
+                        "no channel named '{}' in channel group",
+                        name
+                    ))
+                })?;
+            if mapped.iter().any(|&(i, _)| i == index) {
+                return Err(Error::BlockSerializationError(format!(
+                    "duplicate column for channel '{}'",
+                    name
+                )));
+            }
+            if !column.fits(&dt.encoders[index]) {
+                return Err(Error::BlockSerializationError(format!(
+                    "column type does not match data type of channel '{}'",
+                    name
+                )));
+            }
+            mapped.push((index, *column));
+        }
+
+        let rows = mapped.first().map_or(0, |(_, c)| c.len());
+        if mapped.iter().any(|(_, c)| c.len() != rows) {
+            return Err(Error::BlockSerializationError(
+                "columns have different lengths".into(),
+            ));
+        }
+
+        self.write_batch(cg_id, 0..rows, |dt, row| {
+            for (index, column) in &mapped {
+                column.encode(row, &dt.encoders[*index], &mut dt.record_buf);
+            }
+            Ok(())
+        })
+    }
+}
@@ -9,11 +9,12 @@
 //! - Data list (DL) block creation for large datasets
 
 use alloc::format;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
-use super::{MdfWrite, MdfWriter, OpenDataBlock};
+use super::reduction::SampleReducer;
+use super::{FinishedData, MdfWrite, MdfWriter, OpenDataBlock};
 use crate::{
     Error, Result,
     blocks::{
@@ -23,16 +24,34 @@ use crate::{
 };
 
 pub(super) enum ChannelEncoder {
-    UInt { offset: usize, bytes: usize },
-    Int { offset: usize, bytes: usize },
-    F32 { offset: usize },
-    F64 { offset: usize },
-    Bytes { offset: usize, bytes: usize },
+    UInt {
+        offset: usize,
+        bytes: usize,
+    },
+    Int {
+        offset: usize,
+        bytes: usize,
+    },
+    F32 {
+        offset: usize,
+    },
+    F64 {
+        offset: usize,
+    },
+    Bytes {
+        offset: usize,
+        bytes: usize,
+    },
+    /// VLSD channel: the record holds the offset of the value in the signal
+    /// data, which is stored by [`OpenDataBlock::encode_record()`]
+    Vlsd {
+        offset: usize,
+    },
     Skip,
 }
 
 impl ChannelEncoder {
-    fn encode(&self, buf: &mut [u8], value: &DecodedValue) {
+    pub(super) fn encode(&self, buf: &mut [u8], value: &DecodedValue) {
         match (self, value) {
             (ChannelEncoder::UInt { offset, bytes }, DecodedValue::UnsignedInteger(v)) => {
                 let b = v.to_le_bytes();
@@ -59,6 +78,58 @@ impl ChannelEncoder {
         }
     }
 
+    /// Read back the raw numeric value this encoder stored in `buf`.
+    pub(super) fn raw_value(&self, buf: &[u8]) -> Option<f64> {
+        match self {
+            ChannelEncoder::UInt { offset, bytes } => {
+                let mut b = [0u8; 8];
+                b[..*bytes].copy_from_slice(&buf[*offset..*offset + *bytes]);
+                Some(u64::from_le_bytes(b) as f64)
+            }
+            ChannelEncoder::Int { offset, bytes } => {
+                let mut b = [0u8; 8];
+                b[..*bytes].copy_from_slice(&buf[*offset..*offset + *bytes]);
+                let shift = 64 - 8 * *bytes as u32;
+                Some(((i64::from_le_bytes(b) << shift) >> shift) as f64)
+            }
+            ChannelEncoder::F32 { offset } => {
+                let mut b = [0u8; 4];
+                b.copy_from_slice(&buf[*offset..*offset + 4]);
+                Some(f32::from_le_bytes(b) as f64)
+            }
+            ChannelEncoder::F64 { offset } => {
+                let mut b = [0u8; 8];
+                b.copy_from_slice(&buf[*offset..*offset + 8]);
+                Some(f64::from_le_bytes(b))
+            }
+            ChannelEncoder::Bytes { .. } | ChannelEncoder::Vlsd { .. } | ChannelEncoder::Skip => {
+                None
+            }
+        }
+    }
+
+    /// Store a raw numeric value, converting it to the channel's data type.
+    pub(super) fn encode_f64(&self, buf: &mut [u8], value: f64) {
+        match self {
+            // Round half away from zero; `as` saturates out of range values
+            ChannelEncoder::UInt { .. } => {
+                self.encode(buf, &DecodedValue::UnsignedInteger((value + 0.5) as u64))
+            }
+            ChannelEncoder::Int { .. } => {
+                let rounded = if value < 0.0 {
+                    value - 0.5
+                } else {
+                    value + 0.5
+                };
+                self.encode(buf, &DecodedValue::SignedInteger(rounded as i64))
+            }
+            ChannelEncoder::F32 { .. } | ChannelEncoder::F64 { .. } => {
+                self.encode(buf, &DecodedValue::Float(value))
+            }
+            ChannelEncoder::Bytes { .. } | ChannelEncoder::Vlsd { .. } | ChannelEncoder::Skip => {}
+        }
+    }
+
     fn encode_u64(&self, buf: &mut [u8], value: u64) {
         if let ChannelEncoder::UInt { offset, bytes } = self {
             let b = value.to_le_bytes();
@@ -69,14 +140,125 @@ impl ChannelEncoder {
 
 const MAX_DT_BLOCK_SIZE: usize = 4 * 1024 * 1024;
 
-fn encode_values(encoders: &[ChannelEncoder], buf: &mut [u8], values: &[DecodedValue]) {
+fn check_unsigned(encoders: &[ChannelEncoder]) -> Result<()> {
+    if encoders
+        .iter()
+        .all(|e| matches!(e, ChannelEncoder::UInt { .. }))
+    {
+        Ok(())
+    } else {
+        Err(Error::BlockSerializationError(
+            "channel types not unsigned".into(),
+        ))
+    }
+}
+
+pub(super) fn encode_values(encoders: &[ChannelEncoder], buf: &mut [u8], values: &[DecodedValue]) {
     for (enc, val) in encoders.iter().zip(values.iter()) {
         enc.encode(buf, val);
     }
 }
 
+impl OpenDataBlock {
+    /// Check the number of values of a record: either one value per channel,
+    /// or one value per channel without a default value.
+    pub(super) fn check_record_len(&self, len: usize) -> Result<()> {
+        if len == self.encoders.len() {
+            return Ok(());
+        }
+        let defaults = self.defaults.iter().filter(|d| d.is_some()).count();
+        if defaults > 0 && len + defaults == self.encoders.len() {
+            Ok(())
+        } else {
+            Err(Error::BlockSerializationError(
+                "value count mismatch".into(),
+            ))
+        }
+    }
+
+    /// Encode `values` into `record_buf`. The length must have been checked
+    /// with [`check_record_len()`](Self::check_record_len).
+    pub(super) fn encode_record(&mut self, values: &[DecodedValue]) {
+        if values.len() == self.encoders.len() {
+            encode_values(&self.encoders, &mut self.record_buf, values);
+        } else {
+            let encoders = self.encoders.iter().zip(&self.defaults);
+            let free = encoders.filter(|(_, default)| default.is_none());
+            for ((enc, _), value) in free.zip(values) {
+                enc.encode(&mut self.record_buf, value);
+            }
+        }
+        for (index, data) in &mut self.signal_data {
+            let value = if values.len() == self.encoders.len() {
+                values.get(*index)
+            } else {
+                let free = self.defaults[..*index].iter().filter(|d| d.is_none());
+                match &self.defaults[*index] {
+                    Some(default) => Some(default),
+                    None => values.get(free.count()),
+                }
+            };
+            let bytes: &[u8] = match value {
+                Some(DecodedValue::String(text)) => text.as_bytes(),
+                Some(
+                    DecodedValue::ByteArray(bytes)
+                    | DecodedValue::MimeSample(bytes)
+                    | DecodedValue::MimeStream(bytes),
+                ) => bytes,
+                _ => &[],
+            };
+            if let ChannelEncoder::Vlsd { offset } = self.encoders[*index] {
+                let position = data.len() as u64;
+                self.record_buf[offset..offset + 8].copy_from_slice(&position.to_le_bytes());
+            }
+            data.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            data.extend_from_slice(bytes);
+        }
+    }
+
+    /// Like [`encode_record()`](Self::encode_record) for unsigned integer values.
+    pub(super) fn encode_record_u64(&mut self, values: &[u64]) {
+        if values.len() == self.encoders.len() {
+            for (enc, &v) in self.encoders.iter().zip(values.iter()) {
+                enc.encode_u64(&mut self.record_buf, v);
+            }
+            return;
+        }
+        let encoders = self.encoders.iter().zip(&self.defaults);
+        let free = encoders.filter(|(_, default)| default.is_none());
+        for ((enc, _), &v) in free.zip(values) {
+            enc.encode_u64(&mut self.record_buf, v);
+        }
+    }
+
+    /// Feed the record currently encoded in `record_buf` to the optional
+    /// value range tracking and sample reduction.
+    pub(super) fn track_record(&mut self) {
+        if let Some(ranges) = self.value_ranges.as_mut() {
+            for (enc, range) in self.encoders.iter().zip(ranges.iter_mut()) {
+                if let Some(v) = enc.raw_value(&self.record_buf) {
+                    if v.is_nan() {
+                        continue;
+                    }
+                    *range = match *range {
+                        Some((min, max)) => Some((min.min(v), max.max(v))),
+                        None => Some((v, v)),
+                    };
+                }
+            }
+        }
+        for reducer in &mut self.reducers {
+            reducer.push(&self.encoders, &self.record_buf, self.record_id_len);
+        }
+    }
+}
+
 impl<W: MdfWrite> MdfWriter<W> {
     /// Start writing a DTBLOCK for the given data group.
+    ///
+    /// If the channel group already has finished data blocks, the new one
+    /// continues them: [`finish_data_block()`](Self::finish_data_block)
+    /// links all DT blocks of the group through a DL block.
     pub fn start_data_block(
         &mut self,
         dg_id: &str,
@@ -109,15 +291,45 @@ impl<W: MdfWrite> MdfWriter<W> {
         self.dt_counter += 1;
         let dt_pos = self.write_block_with_id(&header_bytes, &dt_id)?;
 
-        let dg_data_link_offset = 40;
-        self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        // Keep the data link to earlier data blocks until this one is finished
+        let previous = self.finished_data.remove(cg_id);
+        if previous.is_none() {
+            let dg_data_link_offset = 40;
+            self.update_block_link(dg_id, dg_data_link_offset, &dt_id)?;
+        }
+        let previous = previous.unwrap_or(FinishedData {
+            dt_ids: Vec::new(),
+            dt_positions: Vec::new(),
+            dt_sizes: Vec::new(),
+            record_count: 0,
+            reducers: Vec::new(),
+            encoders: Vec::new(),
+            record_id_len: 0,
+        });
+        // Reduction intervals continue across the data blocks of a group
+        let reducers = if previous.reducers.is_empty() {
+            self.sample_reduction_factors
+                .iter()
+                .map(|&f| SampleReducer::new(f, channels.len(), record_size))
+                .collect()
+        } else {
+            previous.reducers
+        };
         self.update_block_u8(dg_id, 56, record_id_len)?;
         self.update_block_u32(cg_id, 96, record_bytes as u32)?;
 
         let mut encoders = Vec::new();
-        for ch in channels {
+        let mut signal_data = Vec::new();
+        for (index, ch) in channels.iter().enumerate() {
             let offset = record_id_len as usize + ch.byte_offset as usize;
             let bytes = ch.bit_count.div_ceil(8) as usize;
+            // VLSD channel added by add_vlsd_channel(): continue the signal
+            // data of earlier data blocks
+            if let Some(data) = self.signal_data.remove(&(cg_id.to_string(), index)) {
+                signal_data.push((index, data));
+                encoders.push(ChannelEncoder::Vlsd { offset });
+                continue;
+            }
             let enc = match ch.data_type {
                 DataType::UnsignedIntegerLE => ChannelEncoder::UInt { offset, bytes },
                 DataType::SignedIntegerLE => ChannelEncoder::Int { offset, bytes },
@@ -136,6 +348,17 @@ impl<W: MdfWrite> MdfWriter<W> {
             encoders.push(enc);
         }
 
+        let mut record_template = vec![0u8; record_size];
+        let mut defaults = vec![None; channels.len()];
+        if let Some(values) = self.channel_defaults.get(cg_id) {
+            for (&idx, value) in values {
+                if let Some(enc) = encoders.get(idx) {
+                    enc.encode(&mut record_template, value);
+                    defaults[idx] = Some(value.clone());
+                }
+            }
+        }
+
         self.open_dts.insert(
             cg_id.to_string(),
             OpenDataBlock {
@@ -144,14 +367,20 @@ impl<W: MdfWrite> MdfWriter<W> {
                 start_pos: dt_pos,
                 record_size,
                 record_count: 0,
-                total_record_count: 0,
+                total_record_count: previous.record_count,
                 channels: channels.to_vec(),
-                dt_ids: vec![dt_id],
-                dt_positions: vec![dt_pos],
-                dt_sizes: Vec::new(),
+                dt_ids: previous.dt_ids.into_iter().chain([dt_id]).collect(),
+                dt_positions: previous.dt_positions.into_iter().chain([dt_pos]).collect(),
+                dt_sizes: previous.dt_sizes,
                 record_buf: vec![0u8; record_size],
-                record_template: vec![0u8; record_size],
+                record_template,
+                defaults,
+                value_ranges: self.track_value_ranges.then(|| vec![None; channels.len()]),
+                reducers,
+                record_id_len: record_id_len as usize,
+                unflushed_bytes: 0,
                 encoders,
+                signal_data,
             },
         );
         Ok(())
@@ -175,6 +404,9 @@ impl<W: MdfWrite> MdfWriter<W> {
     /// Precomputes constant values for a channel group. The provided slice must
     /// have the same length as the channel list and will be encoded into the
     /// internal record template used for each record.
+    ///
+    /// Channels with a default value (see
+    /// [`set_channel_default()`](Self::set_channel_default)) keep their default.
     pub fn set_record_template(&mut self, cg_id: &str, values: &[DecodedValue]) -> Result<()> {
         let dt = self.open_dts.get_mut(cg_id).ok_or_else(|| {
             Error::BlockSerializationError("no open DT block for this channel group".into())
@@ -186,67 +418,223 @@ impl<W: MdfWrite> MdfWriter<W> {
         }
         dt.record_template.fill(0);
         encode_values(&dt.encoders, &mut dt.record_template, values);
+        for (enc, default) in dt.encoders.iter().zip(&dt.defaults) {
+            if let Some(value) = default {
+                enc.encode(&mut dt.record_template, value);
+            }
+        }
         Ok(())
     }
 
-    /// Append one record to the currently open DTBLOCK for the given channel group.
+    /// Set a constant value for a single channel.
     ///
-    /// If a flush policy is configured, this method will automatically flush
-    /// to disk when the policy threshold is reached.
-    pub fn write_record(&mut self, cg_id: &str, values: &[DecodedValue]) -> Result<()> {
-        let potential_new_block = {
+    /// The value is stored in every record of the channel group, and the
+    /// channel may be left out of the values passed to
+    /// [`write_record()`](Self::write_record), [`write_records()`](Self::write_records)
+    /// and their `u64` variants: those accept either one value per channel,
+    /// or one value per channel without a default (in channel order).
+    /// Typed and columnar writes use the default for channels they do not set.
+    ///
+    /// Can be called before or while the channel group's data block is open;
+    /// the value applies to records written afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// writer.set_channel_default(&config_word, DecodedValue::UnsignedInteger(0x2A))?;
+    /// writer.start_data_block_for_cg(&cg, 0)?;
+    /// // Values for time and speed only
+    /// writer.write_record(&cg, &[DecodedValue::Float(0.0), DecodedValue::Float(12.5)])?;
+    /// ```
+    pub fn set_channel_default(&mut self, cn_id: &str, value: DecodedValue) -> Result<()> {
+        let (cg, idx) = self
+            .channel_map
+            .get(cn_id)
+            .cloned()
+            .ok_or_else(|| Error::BlockLinkError(format!("Channel '{}' not found", cn_id)))?;
+        if let Some(dt) = self.open_dts.get_mut(&cg) {
+            dt.encoders[idx].encode(&mut dt.record_template, &value);
+            dt.defaults[idx] = Some(value.clone());
+        }
+        self.channel_defaults
+            .entry(cg)
+            .or_default()
+            .insert(idx, value);
+        Ok(())
+    }
+
+    /// Make sure the next record of `cg_id` can be appended to its current
+    /// DT block.
+    ///
+    /// A new DT block is started if appending another record would exceed the
+    /// maximum DT block size, or if other blocks (for example records of
+    /// another channel group) were written after the current DT block, so its
+    /// data can no longer grow in place. An empty DT block is replaced by the
+    /// new one instead of being kept in the block chain.
+    pub(super) fn roll_dt_if_needed(&mut self, cg_id: &str) -> Result<()> {
+        let (full, at_end, empty) = {
             let dt = self.open_dts.get(cg_id).ok_or_else(|| {
                 Error::BlockSerializationError("no open DT block for this channel group".into())
             })?;
-            if values.len() != dt.channels.len() {
-                return Err(Error::BlockSerializationError(
-                    "value count mismatch".into(),
-                ));
-            }
-            24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+            let end = dt.start_pos + 24 + (dt.record_size as u64) * dt.record_count;
+            (
+                24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE,
+                end == self.offset,
+                dt.record_count == 0,
+            )
         };
+        if at_end && !full {
+            Ok(())
+        } else if empty && !full {
+            self.relocate_empty_dt(cg_id)
+        } else {
+            self.open_next_dt(cg_id)
+        }
+    }
 
-        if potential_new_block {
-            let (start_pos, record_count, record_size) = {
-                let dt = self.open_dts.get(cg_id).unwrap();
-                (dt.start_pos, dt.record_count, dt.record_size)
-            };
-            let size = 24 + record_size * record_count as usize;
-            self.update_link(start_pos + 8, size as u64)?;
-            {
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.total_record_count += record_count;
-                dt.dt_sizes.push(size as u64);
+    /// Replace the current, still empty DT block of `cg_id` by a new one at
+    /// the end of the file. The old block stays unreferenced.
+    fn relocate_empty_dt(&mut self, cg_id: &str) -> Result<()> {
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.dt_ids.pop();
+        dt.dt_positions.pop();
+        let first = dt.dt_ids.is_empty();
+        let dg_id = dt.dg_id.clone();
+        self.append_dt(cg_id)?;
+        if first {
+            let dt_id = self.open_dts.get(cg_id).unwrap().dt_id.clone();
+            let dg_data_link_offset = 40;
+            self.update_block_link(&dg_id, dg_data_link_offset, &dt_id)?;
+        }
+        Ok(())
+    }
+
+    /// Close the current DT block of `cg_id` and append a new, empty one.
+    fn open_next_dt(&mut self, cg_id: &str) -> Result<()> {
+        self.close_dt(cg_id)?;
+        self.append_dt(cg_id)
+    }
+
+    /// Patch the size of the current DT block of `cg_id` and account for its
+    /// records. A new DT block must be appended before writing more records.
+    fn close_dt(&mut self, cg_id: &str) -> Result<()> {
+        let (start_pos, record_count, record_size) = {
+            let dt = self.open_dts.get(cg_id).unwrap();
+            (dt.start_pos, dt.record_count, dt.record_size)
+        };
+        let size = 24 + record_size * record_count as usize;
+        self.update_link(start_pos + 8, size as u64)?;
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.total_record_count += record_count;
+        dt.record_count = 0;
+        dt.dt_sizes.push(size as u64);
+        Ok(())
+    }
+
+    /// Write a new, empty DT block and make it the current one of `cg_id`.
+    fn append_dt(&mut self, cg_id: &str) -> Result<()> {
+        let header = BlockHeader {
+            id: "##DT".to_string(),
+            reserved: 0,
+            length: 24,
+            link_count: 0,
+        };
+        let header_bytes = header.to_bytes()?;
+        let new_dt_id = format!("dt_{}", self.dt_counter);
+        self.dt_counter += 1;
+        let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
+
+        let dt = self.open_dts.get_mut(cg_id).unwrap();
+        dt.dt_id = new_dt_id.clone();
+        dt.start_pos = new_dt_pos;
+        dt.dt_ids.push(new_dt_id);
+        dt.dt_positions.push(new_dt_pos);
+        Ok(())
+    }
+
+    /// Write a DL block referencing the DT blocks at `positions` with the
+    /// given block sizes and link it from the data group `dg_id`.
+    fn write_data_list(&mut self, dg_id: &str, positions: &[u64], sizes: &[u64]) -> Result<()> {
+        let dl_count = self
+            .block_positions
+            .keys()
+            .filter(|k| k.starts_with("dl_"))
+            .count();
+        let dl_id = format!("dl_{}", dl_count);
+        let common_len = sizes[0];
+        let dl_block = if sizes[..sizes.len() - 1].iter().all(|&s| s == common_len) {
+            DataListBlock::new_equal_length(positions.to_vec(), common_len)
+        } else {
+            let mut offsets = Vec::with_capacity(sizes.len());
+            let mut offset = 0u64;
+            for size in sizes {
+                offsets.push(offset);
+                offset += size - 24;
             }
-            let header = BlockHeader {
-                id: "##DT".to_string(),
-                reserved: 0,
-                length: 24,
-                link_count: 0,
-            };
-            let header_bytes = header.to_bytes()?;
-            let new_dt_id = format!("dt_{}", self.dt_counter);
-            self.dt_counter += 1;
-            let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
+            DataListBlock::new_with_offsets(positions.to_vec(), offsets)
+        };
+        let dl_bytes = dl_block.to_bytes()?;
+        let _pos = self.write_block_with_id(&dl_bytes, &dl_id)?;
+        let dg_data_link_offset = 40;
+        self.update_block_link(dg_id, dg_data_link_offset, &dl_id)
+    }
 
-            let dt = self.open_dts.get_mut(cg_id).unwrap();
-            dt.dt_id = new_dt_id.clone();
-            dt.start_pos = new_dt_pos;
-            dt.record_count = 0;
-            dt.dt_ids.push(new_dt_id);
-            dt.dt_positions.push(new_dt_pos);
+    /// Make everything written so far for `cg_id` reachable by readers
+    /// without closing the data block.
+    ///
+    /// Patches the size of the open DT block and the cycle count of the
+    /// channel group. When the data spans several DT blocks, the open DT
+    /// block is closed, an interim DL block is written and a new DT block is
+    /// started after it.
+    pub(super) fn checkpoint_data_block(&mut self, cg_id: &str) -> Result<()> {
+        let (start_pos, size, total, multiple) = {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+                Error::BlockSerializationError("no open DT block for this channel group".into())
+            })?;
+            (
+                dt.start_pos,
+                24 + dt.record_size as u64 * dt.record_count,
+                dt.total_record_count + dt.record_count,
+                dt.dt_ids.len() > 1,
+            )
+        };
+        self.update_link(start_pos + 8, size)?;
+        self.update_block_u64(cg_id, 80, total)?;
+
+        if multiple {
+            // The DL block must not be written into the open DT block, so
+            // close it and continue in a new DT block after the DL block
+            self.close_dt(cg_id)?;
+            let dt = self.open_dts.get(cg_id).unwrap();
+            let dg_id = dt.dg_id.clone();
+            let positions = dt.dt_positions.clone();
+            let sizes = dt.dt_sizes.clone();
+            self.write_data_list(&dg_id, &positions, &sizes)?;
+            self.append_dt(cg_id)?;
+        }
+        Ok(())
+    }
+
+    /// Append one record to the currently open DTBLOCK for the given channel group.
+    ///
+    /// If a flush policy is configured, this method will automatically flush
+    /// to disk when the policy threshold is reached.
+    pub fn write_record(&mut self, cg_id: &str, values: &[DecodedValue]) -> Result<()> {
+        {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+                Error::BlockSerializationError("no open DT block for this channel group".into())
+            })?;
+            dt.check_record_len(values.len())?;
         }
+        self.reserve_record(cg_id)?;
+        self.roll_dt_if_needed(cg_id)?;
 
-        // Validate and encode in scoped mutable borrow
+        // Encode in scoped mutable borrow
         let record_bytes = {
             let dt = self.open_dts.get_mut(cg_id).unwrap();
-            if values.len() != dt.channels.len() {
-                return Err(Error::BlockSerializationError(
-                    "value count mismatch".into(),
-                ));
-            }
             dt.record_buf.copy_from_slice(&dt.record_template);
-            encode_values(&dt.encoders, &mut dt.record_buf, values);
+            dt.encode_record(values);
+            dt.track_record();
             dt.record_count += 1;
             dt.record_buf.len() as u64
         }; // dt dropped here
@@ -257,7 +645,7 @@ impl<W: MdfWrite> MdfWriter<W> {
         self.offset += record_bytes;
 
         // Track write for streaming and check auto-flush
-        self.record_write(1, record_bytes);
+        self.record_write(cg_id, 1, record_bytes);
         self.maybe_auto_flush()?;
 
         Ok(())
@@ -268,44 +656,40 @@ impl<W: MdfWrite> MdfWriter<W> {
     /// If a flush policy is configured, this method will automatically flush
     /// to disk when the policy threshold is reached.
     pub fn write_record_u64(&mut self, cg_id: &str, values: &[u64]) -> Result<()> {
-        let dt = self.open_dts.get_mut(cg_id).ok_or_else(|| {
-            Error::BlockSerializationError("no open DT block for this channel group".into())
-        })?;
-        if values.len() != dt.encoders.len() {
-            return Err(Error::BlockSerializationError(
-                "value count mismatch".into(),
-            ));
-        }
-        if !dt
-            .encoders
-            .iter()
-            .all(|e| matches!(e, ChannelEncoder::UInt { .. }))
         {
-            return Err(Error::BlockSerializationError(
-                "channel types not unsigned".into(),
-            ));
-        }
-        dt.record_buf.copy_from_slice(&dt.record_template);
-        for (enc, &v) in dt.encoders.iter().zip(values.iter()) {
-            enc.encode_u64(&mut dt.record_buf, v);
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+                Error::BlockSerializationError("no open DT block for this channel group".into())
+            })?;
+            dt.check_record_len(values.len())?;
+            check_unsigned(&dt.encoders)?;
         }
-        let buf = dt.record_buf.clone();
-        let record_bytes = buf.len() as u64;
-        self.writer.write_all(&buf)?;
-        let dt = self.open_dts.get_mut(cg_id).unwrap();
-        dt.record_count += 1;
+        self.reserve_record(cg_id)?;
+        self.roll_dt_if_needed(cg_id)?;
+
+        let record_bytes = {
+            let dt = self.open_dts.get_mut(cg_id).unwrap();
+            dt.record_buf.copy_from_slice(&dt.record_template);
+            dt.encode_record_u64(values);
+            dt.track_record();
+            dt.record_count += 1;
+            dt.record_buf.len() as u64
+        };
+
+        let buf = &self.open_dts.get(cg_id).unwrap().record_buf;
+        self.writer.write_all(buf)?;
         self.offset += record_bytes;
 
         // Track write for streaming and check auto-flush
-        self.record_write(1, record_bytes);
+        self.record_write(cg_id, 1, record_bytes);
         self.maybe_auto_flush()?;
 
         Ok(())
     }
 
     /// Append multiple records sequentially for the specified channel group.
-    /// The provided iterator yields record value slices. All encoded bytes are
-    /// buffered and written in a single call to reduce I/O overhead.
+    /// The provided iterator yields record value slices. Encoded records are
+    /// collected in a reusable batch buffer and written with as few I/O calls
+    /// as possible.
     ///
     /// If a flush policy is configured, this method will check for auto-flush
     /// after all records are written.
@@ -313,91 +697,11 @@ impl<W: MdfWrite> MdfWriter<W> {
     where
         I: IntoIterator<Item = &'a [DecodedValue]>,
     {
-        let record_size = {
-            self.open_dts
-                .get(cg_id)
-                .ok_or_else(|| {
-                    Error::BlockSerializationError("no open DT block for this channel group".into())
-                })?
-                .record_size
-        };
-        let max_records = (MAX_DT_BLOCK_SIZE - 24) / record_size;
-        let mut buffer = Vec::with_capacity(record_size * max_records);
-        let mut records_written = 0u64;
-        let mut bytes_written = 0u64;
-
-        for record in records {
-            let potential_new_block = {
-                let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                    Error::BlockSerializationError("no open DT block for this channel group".into())
-                })?;
-                if record.len() != dt.channels.len() {
-                    return Err(Error::BlockSerializationError(
-                        "value count mismatch".into(),
-                    ));
-                }
-                24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
-            };
-
-            if potential_new_block {
-                let buf_len = buffer.len() as u64;
-                self.writer.write_all(&buffer)?;
-                self.offset += buf_len;
-                bytes_written += buf_len;
-                buffer.clear();
-
-                let (start_pos, record_count, record_size) = {
-                    let dt = self.open_dts.get(cg_id).unwrap();
-                    (dt.start_pos, dt.record_count, dt.record_size)
-                };
-                let size = 24 + record_size * record_count as usize;
-                self.update_link(start_pos + 8, size as u64)?;
-                {
-                    let dt = self.open_dts.get_mut(cg_id).unwrap();
-                    dt.total_record_count += record_count;
-                    dt.dt_sizes.push(size as u64);
-                }
-                let header = BlockHeader {
-                    id: "##DT".to_string(),
-                    reserved: 0,
-                    length: 24,
-                    link_count: 0,
-                };
-                let header_bytes = header.to_bytes()?;
-                let new_dt_id = format!("dt_{}", self.dt_counter);
-                self.dt_counter += 1;
-                let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
-
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.dt_id = new_dt_id.clone();
-                dt.start_pos = new_dt_pos;
-                dt.record_count = 0;
-                dt.dt_ids.push(new_dt_id);
-                dt.dt_positions.push(new_dt_pos);
-            }
-
-            let dt = self.open_dts.get_mut(cg_id).unwrap();
-            dt.record_buf.copy_from_slice(&dt.record_template);
-            encode_values(&dt.encoders, &mut dt.record_buf, record);
-            buffer.extend_from_slice(&dt.record_buf);
-            dt.record_count += 1;
-            records_written += 1;
-        }
-
-        if !buffer.is_empty() {
-            let buf_len = buffer.len() as u64;
-            self.writer.write_all(&buffer)?;
-            self.offset += buf_len;
-            bytes_written += buf_len;
-        }
-
-        // Track writes for streaming and check auto-flush
-        if records_written > 0 {
-            self.record_write(records_written, bytes_written);
-            self.maybe_auto_flush()?;
-        }
-
-        Ok(())
+        self.write_batch(cg_id, records, |dt, record| {
+            dt.check_record_len(record.len())?;
+            dt.encode_record(record);
+            Ok(())
+        })
     }
 
     /// Batch write for uniform unsigned integer channel groups.
@@ -408,102 +712,104 @@ impl<W: MdfWrite> MdfWriter<W> {
     where
         I: IntoIterator<Item = &'a [u64]>,
     {
-        let record_size = {
-            self.open_dts
-                .get(cg_id)
-                .ok_or_else(|| {
-                    Error::BlockSerializationError("no open DT block for this channel group".into())
-                })?
-                .record_size
-        };
-        let max_records = (MAX_DT_BLOCK_SIZE - 24) / record_size;
-        let mut buffer = Vec::with_capacity(record_size * max_records);
+        {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+                Error::BlockSerializationError("no open DT block for this channel group".into())
+            })?;
+            check_unsigned(&dt.encoders)?;
+        }
+        self.write_batch(cg_id, records, |dt, rec| {
+            dt.check_record_len(rec.len())?;
+            dt.encode_record_u64(rec);
+            Ok(())
+        })
+    }
+
+    /// Shared implementation of the batch writers.
+    ///
+    /// `encode` fills `record_buf` (already initialised from the record
+    /// template) for one record. Records encoded before an error are still
+    /// written, so the DT block stays consistent with its record count.
+    pub(super) fn write_batch<T, I, F>(
+        &mut self,
+        cg_id: &str,
+        records: I,
+        mut encode: F,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&mut OpenDataBlock, T) -> Result<()>,
+    {
+        if !self.open_dts.contains_key(cg_id) {
+            return Err(Error::BlockSerializationError(
+                "no open DT block for this channel group".into(),
+            ));
+        }
+        let mut buffer = core::mem::take(&mut self.batch_buf);
         let mut records_written = 0u64;
         let mut bytes_written = 0u64;
+        let mut result = Ok(());
 
-        for rec in records {
-            let potential_new_block = {
-                let dt = self.open_dts.get(cg_id).ok_or_else(|| {
-                    Error::BlockSerializationError("no open DT block for this channel group".into())
-                })?;
-                if rec.len() != dt.encoders.len() {
-                    return Err(Error::BlockSerializationError(
-                        "value count mismatch".into(),
-                    ));
-                }
-                if !dt
-                    .encoders
-                    .iter()
-                    .all(|e| matches!(e, ChannelEncoder::UInt { .. }))
-                {
-                    return Err(Error::BlockSerializationError(
-                        "channel types not unsigned".into(),
-                    ));
-                }
-                24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE
+        self.roll_dt_if_needed(cg_id)?;
+        for record in records {
+            let (full, record_size) = {
+                let dt = self.open_dts.get(cg_id).unwrap();
+                (
+                    24 + dt.record_size * (dt.record_count as usize + 1) > MAX_DT_BLOCK_SIZE,
+                    dt.record_size as u64,
+                )
             };
-
-            if potential_new_block {
-                let buf_len = buffer.len() as u64;
-                self.writer.write_all(&buffer)?;
-                self.offset += buf_len;
-                bytes_written += buf_len;
-                buffer.clear();
-
-                let (start_pos, record_count, record_size) = {
-                    let dt = self.open_dts.get(cg_id).unwrap();
-                    (dt.start_pos, dt.record_count, dt.record_size)
-                };
-                let size = 24 + record_size * record_count as usize;
-                self.update_link(start_pos + 8, size as u64)?;
-                {
-                    let dt = self.open_dts.get_mut(cg_id).unwrap();
-                    dt.total_record_count += record_count;
-                    dt.dt_sizes.push(size as u64);
+            if full {
+                // The buffered records belong to the current DT block
+                bytes_written += self.write_batch_buffer(&mut buffer)?;
+                self.roll_dt_if_needed(cg_id)?;
+            }
+            if self.exceeds_buffer_limit(buffer.len() as u64 + record_size) {
+                // Account for the records encoded so far before applying the limit
+                bytes_written += self.write_batch_buffer(&mut buffer)?;
+                self.record_write(cg_id, records_written, bytes_written);
+                records_written = 0;
+                bytes_written = 0;
+                if let Err(e) = self.apply_buffer_limit(record_size) {
+                    result = Err(e);
+                    break;
                 }
-                let header = BlockHeader {
-                    id: "##DT".to_string(),
-                    reserved: 0,
-                    length: 24,
-                    link_count: 0,
-                };
-                let header_bytes = header.to_bytes()?;
-                let new_dt_id = format!("dt_{}", self.dt_counter);
-                self.dt_counter += 1;
-                let new_dt_pos = self.write_block_with_id(&header_bytes, &new_dt_id)?;
-
-                let dt = self.open_dts.get_mut(cg_id).unwrap();
-                dt.dt_id = new_dt_id.clone();
-                dt.start_pos = new_dt_pos;
-                dt.record_count = 0;
-                dt.dt_ids.push(new_dt_id);
-                dt.dt_positions.push(new_dt_pos);
             }
 
             let dt = self.open_dts.get_mut(cg_id).unwrap();
             dt.record_buf.copy_from_slice(&dt.record_template);
-            for (enc, &v) in dt.encoders.iter().zip(rec.iter()) {
-                enc.encode_u64(&mut dt.record_buf, v);
+            if let Err(e) = encode(dt, record) {
+                result = Err(e);
+                break;
             }
+            dt.track_record();
             buffer.extend_from_slice(&dt.record_buf);
             dt.record_count += 1;
             records_written += 1;
         }
 
-        if !buffer.is_empty() {
-            let buf_len = buffer.len() as u64;
-            self.writer.write_all(&buffer)?;
-            self.offset += buf_len;
-            bytes_written += buf_len;
-        }
+        bytes_written += self.write_batch_buffer(&mut buffer)?;
+        self.batch_buf = buffer;
 
         // Track writes for streaming and check auto-flush
         if records_written > 0 {
-            self.record_write(records_written, bytes_written);
+            self.record_write(cg_id, records_written, bytes_written);
             self.maybe_auto_flush()?;
         }
 
-        Ok(())
+        result
+    }
+
+    /// Write and clear the batch buffer, returning the number of bytes written.
+    fn write_batch_buffer(&mut self, buffer: &mut Vec<u8>) -> Result<u64> {
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+        let len = buffer.len() as u64;
+        self.writer.write_all(buffer)?;
+        self.offset += len;
+        buffer.clear();
+        Ok(len)
     }
 
     /// Finalize the currently open DTBLOCK for a given channel group and patch its size field.
@@ -518,18 +824,66 @@ impl<W: MdfWrite> MdfWriter<W> {
         self.update_block_u64(cg_id, 80, dt.total_record_count)?;
 
         if dt.dt_ids.len() > 1 {
-            let dl_count = self
-                .block_positions
-                .keys()
-                .filter(|k| k.starts_with("dl_"))
-                .count();
-            let dl_id = format!("dl_{}", dl_count);
-            let common_len = *dt.dt_sizes.first().unwrap_or(&size);
-            let dl_block = DataListBlock::new_equal_length(dt.dt_positions.clone(), common_len);
-            let dl_bytes = dl_block.to_bytes()?;
-            let _pos = self.write_block_with_id(&dl_bytes, &dl_id)?;
-            let dg_data_link_offset = 40;
-            self.update_block_link(&dt.dg_id, dg_data_link_offset, &dl_id)?;
+            self.write_data_list(&dt.dg_id, &dt.dt_positions, &dt.dt_sizes)?;
+        }
+        for (index, data) in core::mem::take(&mut dt.signal_data) {
+            self.signal_data.insert((cg_id.to_string(), index), data);
+        }
+
+        if let Some(ranges) = dt.value_ranges.take() {
+            self.write_value_ranges(cg_id, &ranges)?;
+        }
+        self.finished_data.insert(
+            cg_id.to_string(),
+            FinishedData {
+                dt_ids: dt.dt_ids,
+                dt_positions: dt.dt_positions,
+                dt_sizes: dt.dt_sizes,
+                record_count: dt.total_record_count,
+                reducers: dt.reducers,
+                encoders: dt.encoders,
+                record_id_len: dt.record_id_len,
+            },
+        );
+        Ok(())
+    }
+
+    /// Patch `min_raw_value`/`max_raw_value` and the value range valid flag of
+    /// every channel in `cg_id` that received at least one numeric value.
+    fn write_value_ranges(&mut self, cg_id: &str, ranges: &[Option<(f64, f64)>]) -> Result<()> {
+        const FLAGS_OFFSET: u64 = 100;
+        const MIN_RAW_OFFSET: u64 = 112;
+        const MAX_RAW_OFFSET: u64 = 120;
+
+        let cn_ids: Vec<(String, usize)> = self
+            .channel_map
+            .iter()
+            .filter(|(_, (cg, _))| cg == cg_id)
+            .map(|(cn, (_, idx))| (cn.clone(), *idx))
+            .collect();
+        for (cn_id, idx) in cn_ids {
+            let Some(Some((min, max))) = ranges.get(idx).copied() else {
+                continue;
+            };
+            let (min, max, flags) =
+                match self.cg_channels.get_mut(cg_id).and_then(|c| c.get_mut(idx)) {
+                    Some(ch) => {
+                        // Widen a range recorded by an earlier data block of this group
+                        let (min, max) = if ch.flags & ChannelBlock::FLAG_VALUE_RANGE_VALID != 0 {
+                            (min.min(ch.min_raw_value), max.max(ch.max_raw_value))
+                        } else {
+                            (min, max)
+                        };
+                        ch.min_raw_value = min;
+                        ch.max_raw_value = max;
+                        ch.flags |= ChannelBlock::FLAG_VALUE_RANGE_VALID;
+                        (min, max, ch.flags)
+                    }
+                    None => continue,
+                };
+            self.update_block_u64(&cn_id, MIN_RAW_OFFSET, min.to_bits())?;
+            self.update_block_u64(&cn_id, MAX_RAW_OFFSET, max.to_bits())?;
+            self.update_block_u32(&cn_id, FLAGS_OFFSET, flags)?;
         }
         Ok(())
     }
@@ -18,11 +18,61 @@ use super::{MdfWrite, MdfWriter};
 use crate::{
     Result,
     blocks::{
-        BlockHeader, ChannelBlock, ChannelGroupBlock, DataGroupBlock, HeaderBlock,
-        IdentificationBlock, SourceBlock, TextBlock, {ConversionBlock, ConversionType},
+        AttachmentBlock, BlockHeader, ChannelBlock, ChannelGroupBlock, CommonProperties,
+        DataGroupBlock, HeaderBlock, IdentificationBlock, MetadataBlock, SourceBlock, TextBlock,
+        xml_escape, {ConversionBlock, ConversionType},
     },
 };
 
+/// Comment, display name and properties stored for a channel or channel
+/// group until its comment block is written.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ChannelDescription {
+    comment: Option<String>,
+    display_name: Option<String>,
+    properties: Option<CommonProperties>,
+}
+
+impl ChannelDescription {
+    /// Serializes the description as a TX block, or as an MD block with a
+    /// `root` XML element when a display name or properties are present.
+    fn to_block_bytes(&self, root: &str) -> Result<Vec<u8>> {
+        let comment = self.comment.as_deref().unwrap_or("");
+        if self.display_name.is_none() && self.properties.is_none() {
+            return TextBlock::new(comment).to_bytes();
+        }
+        let mut xml = format!("<{}><TX>{}</TX>", root, xml_escape(comment));
+        if let Some(display) = &self.display_name {
+            xml.push_str(&format!(
+                "<names><display>{}</display></names>",
+                xml_escape(display)
+            ));
+        }
+        if let Some(properties) = &self.properties {
+            xml.push_str(&properties.to_xml());
+        }
+        xml.push_str(&format!("</{}>", root));
+        MetadataBlock::new(&xml).to_bytes()
+    }
+}
+
+/// How [`add_channel()`](MdfWriter::add_channel) handles a channel name that
+/// is already used by another channel of the same channel group.
+///
+/// Readers that look up channels by name (e.g. `read_channel_values_by_name`)
+/// can only return one of several equally named channels, so files with
+/// duplicate names are easy to misread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicateNamePolicy {
+    /// Accept duplicate names. This is the default behavior.
+    #[default]
+    Allow,
+    /// Fail with [`Error::BlockSerializationError`](crate::Error::BlockSerializationError) without adding the channel.
+    Reject,
+    /// Append `_1`, `_2`, ... to the name until it is unique within the group.
+    Rename,
+}
+
 impl<W: MdfWrite> MdfWriter<W> {
     /// Initializes a new MDF 4.1 file with identification and header blocks.
     pub fn init_mdf_file(&mut self) -> Result<(u64, u64)> {
@@ -30,12 +80,145 @@ impl<W: MdfWrite> MdfWriter<W> {
         let id_bytes = id_block.to_bytes()?;
         let id_pos = self.write_block_with_id(&id_bytes, "id_block")?;
 
-        let hd_block = HeaderBlock::default();
-        let hd_bytes = hd_block.to_bytes()?;
+        let hd_bytes = self.header.to_bytes()?;
         let hd_pos = self.write_block_with_id(&hd_bytes, "hd_block")?;
         Ok((id_pos, hd_pos))
     }
 
+    /// Sets the absolute start time of the recording.
+    ///
+    /// The value is stored in the header block as nanoseconds since
+    /// Jan 1, 1970. It can be set before or after [`init_mdf_file()`](Self::init_mdf_file);
+    /// an already written header block is patched in place.
+    pub fn set_start_time_ns(&mut self, start_time_ns: u64) -> Result<()> {
+        self.header.start_time_ns = start_time_ns;
+        self.patch_header_time_section()
+    }
+
+    /// Sets the start time of the recording to the current system time (UTC).
+    #[cfg(feature = "std")]
+    pub fn set_start_time_now(&mut self) -> Result<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        self.set_start_time_ns(now)
+    }
+
+    /// Sets the timezone and daylight saving time offsets in minutes.
+    ///
+    /// This also marks the offsets as valid in the header time flags.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // Central European Summer Time: UTC+1 plus 1 hour DST
+    /// writer.set_time_zone(60, 60)?;
+    /// ```
+    pub fn set_time_zone(&mut self, tz_offset_min: i16, dst_offset_min: i16) -> Result<()> {
+        self.header.tz_offset_min = tz_offset_min;
+        self.header.dst_offset_min = dst_offset_min;
+        self.header.time_flags |= HeaderBlock::TIME_FLAG_OFFSETS_VALID;
+        self.patch_header_time_section()
+    }
+
+    /// Sets whether the start time is local time (`true`) or UTC (`false`).
+    pub fn set_local_time(&mut self, local: bool) -> Result<()> {
+        if local {
+            self.header.time_flags |= HeaderBlock::TIME_FLAG_LOCAL_TIME;
+        } else {
+            self.header.time_flags &= !HeaderBlock::TIME_FLAG_LOCAL_TIME;
+        }
+        self.patch_header_time_section()
+    }
+
+    /// Sets the time quality class of the start time.
+    ///
+    /// See [`HeaderBlock::TIME_QUALITY_LOCAL_PC`] and related constants.
+    pub fn set_time_quality(&mut self, time_quality: u8) -> Result<()> {
+        self.header.time_quality = time_quality;
+        self.patch_header_time_section()
+    }
+
+    /// Sets the header flags (e.g. [`HeaderBlock::FLAG_START_ANGLE_VALID`]).
+    pub fn set_header_flags(&mut self, flags: u8) -> Result<()> {
+        self.header.flags = flags;
+        self.patch_header_time_section()
+    }
+
+    /// Sets the format version of an already written identification block,
+    /// e.g. 420 for files using MDF 4.2 column oriented storage.
+    pub fn set_format_version(&mut self, version_number: u16) -> Result<()> {
+        let version = format!("{}.{:02}    ", version_number / 100, version_number % 100);
+        self.update_block_bytes("id_block", 8, &version.as_bytes()[..8])?;
+        self.update_block_bytes("id_block", 28, &version_number.to_le_bytes())
+    }
+
+    /// Rewrites the time section (offset 72..88) of an already written header block.
+    fn patch_header_time_section(&mut self) -> Result<()> {
+        if self.get_block_position("hd_block").is_none() {
+            return Ok(());
+        }
+        let mut section = [0u8; 16];
+        section[0..8].copy_from_slice(&self.header.start_time_ns.to_le_bytes());
+        section[8..10].copy_from_slice(&self.header.tz_offset_min.to_le_bytes());
+        section[10..12].copy_from_slice(&self.header.dst_offset_min.to_le_bytes());
+        section[12] = self.header.time_flags;
+        section[13] = self.header.time_quality;
+        section[14] = self.header.flags;
+        const TIME_SECTION_OFFSET: u64 = 72;
+        self.update_block_bytes("hd_block", TIME_SECTION_OFFSET, &section)
+    }
+
+    /// Embeds a file in the MDF file as an attachment (AT block).
+    ///
+    /// The data is stored uncompressed together with its MD5 checksum and
+    /// appended to the attachment list of the header block.
+    ///
+    /// # Arguments
+    /// * `file_name` - Name of the attached file, e.g. "vehicle.dbc"
+    /// * `mime_type` - MIME type such as "application/x-dbc"; empty for none
+    /// * `data` - File contents
+    ///
+    /// # Returns
+    /// The ID of the attachment block.
+    pub fn add_attachment(
+        &mut self,
+        file_name: &str,
+        mime_type: &str,
+        data: &[u8],
+    ) -> Result<String> {
+        let at_count = self
+            .block_positions
+            .keys()
+            .filter(|k| k.starts_with("at_"))
+            .count();
+        let at_id = format!("at_{}", at_count);
+
+        let mut at_block = AttachmentBlock::embedded(data).with_md5();
+        let filename_bytes = TextBlock::new(file_name).to_bytes()?;
+        at_block.filename_addr =
+            self.write_text_block_with_id(&filename_bytes, &format!("tx_filename_{}", at_id))?;
+        if !mime_type.is_empty() {
+            let mime_bytes = TextBlock::new(mime_type).to_bytes()?;
+            at_block.mimetype_addr =
+                self.write_text_block_with_id(&mime_bytes, &format!("tx_mimetype_{}", at_id))?;
+        }
+        self.write_block_with_id(&at_block.to_bytes()?, &at_id)?;
+
+        if at_count > 0 {
+            let prev_next_link_offset = 24;
+            self.update_block_link(
+                &format!("at_{}", at_count - 1),
+                prev_next_link_offset,
+                &at_id,
+            )?;
+        } else {
+            let hd_at_link_offset = 48;
+            self.update_block_link("hd_block", hd_at_link_offset, &at_id)?;
+        }
+        Ok(at_id)
+    }
+
     /// Adds a data group block to the file and links it from the header block.
     pub fn add_data_group(&mut self, prev_dg_id: Option<&str>) -> Result<String> {
         let dg_count = self
@@ -127,13 +310,13 @@ impl<W: MdfWrite> MdfWriter<W> {
             let tx_id = format!("tx_{}_{}", cc_id, idx);
             let tx_block = TextBlock::new(txt);
             let tx_bytes = tx_block.to_bytes()?;
-            let pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
+            let pos = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
             refs.push(pos);
         }
         let tx_default_id = format!("tx_{}_default", cc_id);
         let tx_default = TextBlock::new(default_text);
         let tx_bytes = tx_default.to_bytes()?;
-        let default_pos = self.write_block_with_id(&tx_bytes, &tx_default_id)?;
+        let default_pos = self.write_text_block_with_id(&tx_bytes, &tx_default_id)?;
         refs.push(default_pos);
 
         let vals: Vec<f64> = mapping.iter().map(|(v, _)| *v as f64).collect();
@@ -195,6 +378,12 @@ impl<W: MdfWrite> MdfWriter<W> {
         if ch.bit_count == 0 {
             ch.bit_count = ch.data_type.default_bits();
         }
+        if ch.precision != 0 {
+            ch.flags |= ChannelBlock::FLAG_PRECISION_VALID;
+        }
+        if let Some(name) = ch.name.take() {
+            ch.name = Some(self.unique_channel_name(cg_id, name)?);
+        }
         if let Some(off) = self.cg_offsets.get_mut(cg_id) {
             if ch.byte_offset == 0 {
                 ch.byte_offset = *off as u32;
@@ -209,7 +398,7 @@ impl<W: MdfWrite> MdfWriter<W> {
             let tx_id = format!("tx_name_{}", cn_id);
             let tx_block = TextBlock::new(channel_name);
             let tx_bytes = tx_block.to_bytes()?;
-            let tx_pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
+            let tx_pos = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
             let name_link_offset = 40;
             self.update_link(cn_pos + name_link_offset, tx_pos)?;
         }
@@ -230,6 +419,150 @@ impl<W: MdfWrite> MdfWriter<W> {
         Ok(cn_id)
     }
 
+    /// Adds a VLSD (variable length signal data) channel, e.g. for payloads
+    /// or texts of varying length.
+    ///
+    /// The records hold the 64-bit offset of each value in the signal data.
+    /// The byte array or string values given to
+    /// [`write_record()`](Self::write_record) or
+    /// [`write_records()`](Self::write_records) are kept in memory and
+    /// written as an SD block by [`finalize()`](Self::finalize).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let payload = writer.add_vlsd_channel(&cg, Some(&time_ch), |ch| {
+    ///     ch.data_type = DataType::ByteArray;
+    ///     ch.name = Some("Payload".into());
+    /// })?;
+    /// ```
+    pub fn add_vlsd_channel<F>(
+        &mut self,
+        cg_id: &str,
+        prev_cn_id: Option<&str>,
+        configure: F,
+    ) -> Result<String>
+    where
+        F: FnOnce(&mut ChannelBlock),
+    {
+        let cn_id = self.add_channel(cg_id, prev_cn_id, |ch| {
+            configure(ch);
+            ch.channel_type = 1;
+            ch.bit_count = 64;
+        })?;
+        if let Some(key) = self.channel_map.get(&cn_id).cloned() {
+            self.signal_data.insert(key, Vec::new());
+        }
+        Ok(cn_id)
+    }
+
+    /// Adds a member channel to the composition of a structure channel.
+    ///
+    /// Members describe fields inside the record bytes of their parent, e.g.
+    /// the ID and DLC of an ASAM `CAN_DataFrame` ByteArray, so viewers can
+    /// display them as separate signals. They do not take part in
+    /// [`write_record()`](Self::write_record): the parent's value holds their
+    /// data.
+    ///
+    /// The `byte_offset` set by `configure` is relative to the start of the
+    /// parent channel and is converted to a record offset here. The first
+    /// member (`prev_cn_id` of `None`) is linked from the parent, later ones
+    /// from their predecessor.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let frame = writer.add_channel(&cg, Some(&time_ch), |ch| {
+    ///     ch.data_type = DataType::ByteArray;
+    ///     ch.name = Some("CAN_DataFrame".into());
+    ///     ch.bit_count = 13 * 8;
+    /// })?;
+    /// let id = writer.add_component_channel(&frame, None, |ch| {
+    ///     ch.data_type = DataType::UnsignedIntegerLE;
+    ///     ch.name = Some("CAN_DataFrame.ID".into());
+    ///     ch.bit_count = 29;
+    /// })?;
+    /// ```
+    pub fn add_component_channel<F>(
+        &mut self,
+        parent_cn_id: &str,
+        prev_cn_id: Option<&str>,
+        configure: F,
+    ) -> Result<String>
+    where
+        F: FnOnce(&mut ChannelBlock),
+    {
+        let parent_offset = self
+            .channel_map
+            .get(parent_cn_id)
+            .and_then(|(cg, idx)| self.cg_channels.get(cg)?.get(*idx))
+            .map(|parent| parent.byte_offset)
+            .ok_or_else(|| {
+                crate::Error::BlockLinkError(format!("Channel '{}' not found", parent_cn_id))
+            })?;
+
+        let cn_count = self
+            .block_positions
+            .keys()
+            .filter(|k| k.starts_with("cn_"))
+            .count();
+        let cn_id = format!("cn_{}", cn_count);
+
+        let mut ch = ChannelBlock::default();
+        configure(&mut ch);
+        if ch.bit_count == 0 {
+            ch.bit_count = ch.data_type.default_bits();
+        }
+        if ch.precision != 0 {
+            ch.flags |= ChannelBlock::FLAG_PRECISION_VALID;
+        }
+        ch.byte_offset += parent_offset;
+
+        let cn_bytes = ch.to_bytes()?;
+        let cn_pos = self.write_block_with_id(&cn_bytes, &cn_id)?;
+        if let Some(channel_name) = &ch.name {
+            let tx_id = format!("tx_name_{}", cn_id);
+            let tx_block = TextBlock::new(channel_name);
+            let tx_bytes = tx_block.to_bytes()?;
+            let tx_pos = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
+            let name_link_offset = 40;
+            self.update_link(cn_pos + name_link_offset, tx_pos)?;
+        }
+
+        if let Some(prev_cn) = prev_cn_id {
+            let prev_cn_next_link_offset = 24;
+            self.update_block_link(prev_cn, prev_cn_next_link_offset, &cn_id)?;
+        } else {
+            let parent_component_link_offset = 32;
+            self.update_block_link(parent_cn_id, parent_component_link_offset, &cn_id)?;
+        }
+        Ok(cn_id)
+    }
+
+    /// Apply the duplicate name policy to the name of a new channel of `cg_id`.
+    fn unique_channel_name(&self, cg_id: &str, name: String) -> Result<String> {
+        let Some(channels) = self.cg_channels.get(cg_id) else {
+            return Ok(name);
+        };
+        let taken = |candidate: &str| {
+            channels
+                .iter()
+                .any(|ch| ch.name.as_deref() == Some(candidate))
+        };
+        if !taken(&name) {
+            return Ok(name);
+        }
+        match self.duplicate_names {
+            DuplicateNamePolicy::Allow => Ok(name),
+            DuplicateNamePolicy::Reject => Err(crate::Error::BlockSerializationError(format!(
+                "duplicate channel name '{}' in channel group",
+                name
+            ))),
+            DuplicateNamePolicy::Rename => Ok((1..)
+                .map(|i| format!("{}_{}", name, i))
+                .find(|candidate| !taken(candidate))
+                .unwrap()),
+        }
+    }
+
     /// Mark an existing channel as the time (master) channel.
     pub fn set_time_channel(&mut self, cn_id: &str) -> Result<()> {
         const CHANNEL_TYPE_OFFSET: u64 = 88;
@@ -278,7 +611,7 @@ impl<W: MdfWrite> MdfWriter<W> {
         let tx_id = format!("tx_unit_{}", cn_id);
         let tx_block = TextBlock::new(unit);
         let tx_bytes = tx_block.to_bytes()?;
-        let tx_pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
+        let tx_pos = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
 
         // unit_addr is at offset 72 in ChannelBlock (after header + 6 links)
         const UNIT_ADDR_OFFSET: u64 = 72;
@@ -290,7 +623,9 @@ impl<W: MdfWrite> MdfWriter<W> {
     /// Sets the comment/description for an existing channel.
     ///
     /// This creates a text block containing the comment and links it
-    /// to the channel's comment_addr field.
+    /// to the channel's comment_addr field. If a display name has been set
+    /// with [`set_channel_display_name()`](Self::set_channel_display_name),
+    /// both are written together as an XML metadata block.
     ///
     /// # Arguments
     /// * `cn_id` - The channel ID returned from `add_channel()`
@@ -299,15 +634,114 @@ impl<W: MdfWrite> MdfWriter<W> {
         if comment.is_empty() {
             return Ok(());
         }
+        self.channel_descriptions
+            .entry(cn_id.to_string())
+            .or_default()
+            .comment = Some(comment.to_string());
+        self.write_channel_description(cn_id)
+    }
+
+    /// Sets the display name for an existing channel.
+    ///
+    /// The display name is an alternative, human friendly name shown by
+    /// measurement viewers instead of the (often technical) channel name.
+    /// It is stored in the `<names><display>` element of the channel's
+    /// `CNcomment` XML metadata block, together with any comment set via
+    /// [`set_channel_comment()`](Self::set_channel_comment).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let ch = writer.add_channel(&cg, None, |ch| {
+    ///     ch.name = Some("EngSpd_RPM_01".into());
+    /// })?;
+    /// writer.set_channel_display_name(&ch, "Engine Speed")?;
+    /// ```
+    pub fn set_channel_display_name(&mut self, cn_id: &str, display_name: &str) -> Result<()> {
+        if display_name.is_empty() {
+            return Ok(());
+        }
+        self.channel_descriptions
+            .entry(cn_id.to_string())
+            .or_default()
+            .display_name = Some(display_name.to_string());
+        self.write_channel_description(cn_id)
+    }
+
+    /// Sets the common properties of an existing channel.
+    ///
+    /// The properties are stored in the `<common_properties>` element of the
+    /// channel's `CNcomment` XML metadata block, together with any comment
+    /// and display name. Setting properties again replaces them.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use mdf4_rs::blocks::CommonProperties;
+    ///
+    /// let props = CommonProperties::new().with_value("Source", "Bench 3");
+    /// writer.set_channel_properties(&ch, &props)?;
+    /// ```
+    pub fn set_channel_properties(
+        &mut self,
+        cn_id: &str,
+        properties: &CommonProperties,
+    ) -> Result<()> {
+        if properties.is_empty() {
+            return Ok(());
+        }
+        self.channel_descriptions
+            .entry(cn_id.to_string())
+            .or_default()
+            .properties = Some(properties.clone());
+        self.write_channel_description(cn_id)
+    }
+
+    /// Sets the number of decimal places used to display the channel's values.
+    ///
+    /// Writes `precision` and sets the precision valid flag. A precision set
+    /// through the `add_channel()` configuration closure is honored the same
+    /// way when it is non-zero; use this method to explicitly request zero
+    /// decimal places. `0xFF` means infinite precision.
+    pub fn set_channel_precision(&mut self, cn_id: &str, precision: u8) -> Result<()> {
+        const FLAGS_OFFSET: u64 = 100;
+        const PRECISION_OFFSET: u64 = 108;
+
+        let (cg, idx) = self.channel_map.get(cn_id).cloned().ok_or_else(|| {
+            crate::Error::BlockLinkError(format!("Channel '{}' not found", cn_id))
+        })?;
+        let flags = {
+            let ch = self
+                .cg_channels
+                .get_mut(&cg)
+                .and_then(|chs| chs.get_mut(idx))
+                .ok_or_else(|| {
+                    crate::Error::BlockLinkError(format!("Channel '{}' not found", cn_id))
+                })?;
+            ch.precision = precision;
+            ch.flags |= ChannelBlock::FLAG_PRECISION_VALID;
+            ch.flags
+        };
+        self.update_block_u8(cn_id, PRECISION_OFFSET, precision)?;
+        self.update_block_u32(cn_id, FLAGS_OFFSET, flags)
+    }
 
+    /// Writes the comment block for a channel from its stored description.
+    ///
+    /// A plain comment is stored as a TX block; as soon as a display name or
+    /// properties are present an MD block with `CNcomment` XML is written
+    /// instead.
+    fn write_channel_description(&mut self, cn_id: &str) -> Result<()> {
         let cn_pos = self.get_block_position(cn_id).ok_or_else(|| {
             crate::Error::BlockLinkError(format!("Channel '{}' not found", cn_id))
         })?;
+        let desc = self
+            .channel_descriptions
+            .get(cn_id)
+            .cloned()
+            .unwrap_or_default();
 
         let tx_id = format!("tx_comment_{}", cn_id);
-        let tx_block = TextBlock::new(comment);
-        let tx_bytes = tx_block.to_bytes()?;
-        let tx_pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
+        let block_bytes = desc.to_block_bytes("CNcomment")?;
+        let tx_pos = self.write_text_block_with_id(&block_bytes, &tx_id)?;
 
         // comment_addr is at offset 80 in ChannelBlock
         const COMMENT_ADDR_OFFSET: u64 = 80;
@@ -455,7 +889,7 @@ impl<W: MdfWrite> MdfWriter<W> {
         let tx_id = format!("tx_cgname_{}", cg_id);
         let tx_block = TextBlock::new(name);
         let tx_bytes = tx_block.to_bytes()?;
-        let tx_pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
+        let tx_pos = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
 
         // acq_name_addr is at offset 40 in ChannelGroupBlock (after header + 2 links)
         const ACQ_NAME_ADDR_OFFSET: u64 = 40;
@@ -467,7 +901,9 @@ impl<W: MdfWrite> MdfWriter<W> {
     /// Sets the comment for an existing channel group.
     ///
     /// This creates a text block containing the comment and links it
-    /// to the channel group's comment_addr field.
+    /// to the channel group's comment_addr field. If properties have been set
+    /// with [`set_channel_group_properties()`](Self::set_channel_group_properties),
+    /// both are written together as an XML metadata block.
     ///
     /// # Arguments
     /// * `cg_id` - The channel group ID returned from `add_channel_group()`
@@ -477,14 +913,49 @@ impl<W: MdfWrite> MdfWriter<W> {
             return Ok(());
         }
 
+        self.channel_group_descriptions
+            .entry(cg_id.to_string())
+            .or_default()
+            .comment = Some(comment.to_string());
+        self.write_channel_group_description(cg_id)
+    }
+
+    /// Sets the common properties of an existing channel group.
+    ///
+    /// The properties are stored in the `<common_properties>` element of the
+    /// group's `CGcomment` XML metadata block, together with any comment set
+    /// via [`set_channel_group_comment()`](Self::set_channel_group_comment).
+    /// Setting properties again replaces them.
+    pub fn set_channel_group_properties(
+        &mut self,
+        cg_id: &str,
+        properties: &CommonProperties,
+    ) -> Result<()> {
+        if properties.is_empty() {
+            return Ok(());
+        }
+        self.channel_group_descriptions
+            .entry(cg_id.to_string())
+            .or_default()
+            .properties = Some(properties.clone());
+        self.write_channel_group_description(cg_id)
+    }
+
+    /// Writes the comment block for a channel group from its stored
+    /// description, as TX block or as MD block with `CGcomment` XML.
+    fn write_channel_group_description(&mut self, cg_id: &str) -> Result<()> {
         let cg_pos = self.get_block_position(cg_id).ok_or_else(|| {
             crate::Error::BlockLinkError(format!("Channel group '{}' not found", cg_id))
         })?;
+        let desc = self
+            .channel_group_descriptions
+            .get(cg_id)
+            .cloned()
+            .unwrap_or_default();
 
         let tx_id = format!("tx_cgcomment_{}", cg_id);
-        let tx_block = TextBlock::new(comment);
-        let tx_bytes = tx_block.to_bytes()?;
-        let tx_pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
+        let tx_bytes = desc.to_block_bytes("CGcomment")?;
+        let tx_pos = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
 
         // comment_addr is at offset 64 in ChannelGroupBlock
         const COMMENT_ADDR_OFFSET: u64 = 64;
@@ -516,11 +987,85 @@ impl<W: MdfWrite> MdfWriter<W> {
         cg_id: &str,
         source: &SourceBlock,
         source_name: Option<&str>,
+    ) -> Result<()> {
+        self.set_channel_group_source_with_path(cg_id, source, source_name, None)
+    }
+
+    /// Sets the acquisition source for an existing channel group, with a
+    /// tool-specific path.
+    ///
+    /// Like [`set_channel_group_source()`](Self::set_channel_group_source),
+    /// with an optional source path, e.g. the switch port an Ethernet
+    /// capture was taken from ("Switch1/Port3").
+    pub fn set_channel_group_source_with_path(
+        &mut self,
+        cg_id: &str,
+        source: &SourceBlock,
+        source_name: Option<&str>,
+        source_path: Option<&str>,
     ) -> Result<()> {
         let cg_pos = self.get_block_position(cg_id).ok_or_else(|| {
             crate::Error::BlockLinkError(format!("Channel group '{}' not found", cg_id))
         })?;
 
+        let si_pos = self.write_source_block(source, source_name, source_path)?;
+
+        // acq_source_addr is at offset 48 in ChannelGroupBlock
+        const ACQ_SOURCE_ADDR_OFFSET: u64 = 48;
+        self.update_link(cg_pos + ACQ_SOURCE_ADDR_OFFSET, si_pos)?;
+
+        Ok(())
+    }
+
+    /// Sets the source for an individual channel.
+    ///
+    /// This writes a source block with the given name and path and links it
+    /// to the channel's source_addr field. Use this when channels of one group
+    /// originate from different ECUs or interfaces.
+    ///
+    /// # Arguments
+    /// * `cn_id` - The channel ID returned from `add_channel()`
+    /// * `source` - The source block to attach
+    /// * `source_name` - Optional name for the source (e.g., ECU name)
+    /// * `source_path` - Optional tool-specific path (e.g., "CAN1/ECM")
+    ///
+    /// # Example
+    /// ```ignore
+    /// use mdf4_rs::blocks::SourceBlock;
+    ///
+    /// let ch = writer.add_channel(&cg, None, |ch| {
+    ///     ch.name = Some("LinTemp".into());
+    /// })?;
+    /// writer.set_channel_source(&ch, &SourceBlock::lin_ecu(), Some("Seat"), Some("LIN1"))?;
+    /// ```
+    pub fn set_channel_source(
+        &mut self,
+        cn_id: &str,
+        source: &SourceBlock,
+        source_name: Option<&str>,
+        source_path: Option<&str>,
+    ) -> Result<()> {
+        let cn_pos = self.get_block_position(cn_id).ok_or_else(|| {
+            crate::Error::BlockLinkError(format!("Channel '{}' not found", cn_id))
+        })?;
+
+        let si_pos = self.write_source_block(source, source_name, source_path)?;
+
+        // source_addr is at offset 48 in ChannelBlock
+        const SOURCE_ADDR_OFFSET: u64 = 48;
+        self.update_link(cn_pos + SOURCE_ADDR_OFFSET, si_pos)?;
+
+        Ok(())
+    }
+
+    /// Writes a source block together with its optional name and path text
+    /// blocks and returns the position of the source block.
+    fn write_source_block(
+        &mut self,
+        source: &SourceBlock,
+        source_name: Option<&str>,
+        source_path: Option<&str>,
+    ) -> Result<u64> {
         let si_count = self
             .block_positions
             .keys()
@@ -528,34 +1073,22 @@ impl<W: MdfWrite> MdfWriter<W> {
             .count();
         let si_id = format!("si_{}", si_count);
 
-        // Clone source and optionally set name
+        // Clone source and optionally set name and path
         let mut source = source.clone();
 
-        // Write source name text block if provided
-        if let Some(name) = source_name {
-            if !name.is_empty() {
-                let tx_id = format!("tx_siname_{}", si_id);
-                let tx_block = TextBlock::new(name);
-                let tx_bytes = tx_block.to_bytes()?;
-                let tx_pos = self.write_block_with_id(&tx_bytes, &tx_id)?;
-                source.name_addr = tx_pos;
-            }
+        if let Some(name) = source_name.filter(|n| !n.is_empty()) {
+            let tx_id = format!("tx_siname_{}", si_id);
+            let tx_bytes = TextBlock::new(name).to_bytes()?;
+            source.name_addr = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
         }
-
-        let si_bytes = source.to_bytes()?;
-        let si_pos = self.write_block_with_id(&si_bytes, &si_id)?;
-
-        // Update name link in the source block if we wrote one
-        if source.name_addr != 0 {
-            // name_addr is at offset 24 in SourceBlock
-            self.update_link(si_pos + 24, source.name_addr)?;
+        if let Some(path) = source_path.filter(|p| !p.is_empty()) {
+            let tx_id = format!("tx_sipath_{}", si_id);
+            let tx_bytes = TextBlock::new(path).to_bytes()?;
+            source.path_addr = self.write_text_block_with_id(&tx_bytes, &tx_id)?;
         }
 
-        // acq_source_addr is at offset 48 in ChannelGroupBlock
-        const ACQ_SOURCE_ADDR_OFFSET: u64 = 48;
-        self.update_link(cg_pos + ACQ_SOURCE_ADDR_OFFSET, si_pos)?;
-
-        Ok(())
+        let si_bytes = source.to_bytes()?;
+        self.write_block_with_id(&si_bytes, &si_id)
     }
 
     /// Convenience method to set channel group source with just a name.
@@ -1,10 +1,11 @@
 // Low level file and block handling utilities for MdfWriter
 use alloc::format;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use alloc::vec;
+use alloc::vec::Vec;
 
-use super::{MdfWrite, MdfWriter};
-use crate::{Error, Result};
+use super::{BufferLimit, MdfWrite, MdfWriter};
+use crate::{Error, Result, blocks::BlockHeader};
 
 #[cfg(feature = "std")]
 use super::FileWriter;
@@ -62,6 +63,28 @@ impl<W: MdfWrite> MdfWriter<W> {
         Ok(block_start)
     }
 
+    /// Writes a TX or MD block and tracks its position with the given ID.
+    ///
+    /// With text deduplication enabled (the default), a block whose bytes are
+    /// identical to a previously written text block is not written again;
+    /// `block_id` is registered at the position of the existing block instead.
+    pub(super) fn write_text_block_with_id(
+        &mut self,
+        block_bytes: &[u8],
+        block_id: &str,
+    ) -> Result<u64> {
+        let cached = self.text_blocks.as_ref().and_then(|c| c.get(block_bytes));
+        if let Some(&pos) = cached {
+            self.block_positions.insert(block_id.to_string(), pos);
+            return Ok(pos);
+        }
+        let pos = self.write_block_with_id(block_bytes, block_id)?;
+        if let Some(cache) = &mut self.text_blocks {
+            cache.insert(block_bytes.to_vec(), pos);
+        }
+        Ok(pos)
+    }
+
     /// Retrieves the file position of a previously written block.
     pub fn get_block_position(&self, block_id: &str) -> Option<u64> {
         self.block_positions.get(block_id).copied()
@@ -117,6 +140,22 @@ impl<W: MdfWrite> MdfWriter<W> {
         Ok(())
     }
 
+    pub(super) fn update_block_bytes(
+        &mut self,
+        block_id: &str,
+        field_offset: u64,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let block_pos = self
+            .get_block_position(block_id)
+            .ok_or_else(|| Error::BlockLinkError(format!("Block '{}' not found", block_id)))?;
+        let current_pos = self.offset;
+        self.writer.seek(block_pos + field_offset)?;
+        self.writer.write_all(bytes)?;
+        self.writer.seek(current_pos)?;
+        Ok(())
+    }
+
     pub(super) fn update_block_u32(
         &mut self,
         block_id: &str,
@@ -170,13 +209,89 @@ impl<W: MdfWrite> MdfWriter<W> {
     /// - Writing can continue normally
     ///
     /// Note: This does NOT create DL blocks or update final record counts.
-    /// Those are handled during [`finish_data_block()`](Self::finish_data_block) and finalization.
+    /// Those are handled during [`finish_data_block()`](Self::finish_data_block) and finalization;
+    /// use [`flush_now()`](Self::flush_now) to update them as well.
     pub fn flush(&mut self) -> Result<()> {
         self.writer.flush()?;
         self.flush_state.on_flush();
+        for dt in self.open_dts.values_mut() {
+            dt.unflushed_bytes = 0;
+        }
         Ok(())
     }
 
+    /// Record bytes written since the last flush, across all channel groups.
+    ///
+    /// This is the data held in the writer backend's buffers (or, for
+    /// in-memory backends, not yet handed off) that a flush would persist.
+    pub fn unflushed_bytes(&self) -> u64 {
+        self.flush_state.bytes_since_flush
+    }
+
+    /// Record bytes written to the open data block of `cg_id` since the last
+    /// flush, or `None` if the channel group has no open data block.
+    pub fn unflushed_bytes_for(&self, cg_id: &str) -> Option<u64> {
+        self.open_dts.get(cg_id).map(|dt| dt.unflushed_bytes)
+    }
+
+    /// Whether writing `incoming` more record bytes would exceed the buffer limit.
+    pub(super) fn exceeds_buffer_limit(&self, incoming: u64) -> bool {
+        match self.streaming_config.buffer_limit {
+            BufferLimit::Unlimited => false,
+            BufferLimit::FlushAt(limit) | BufferLimit::ErrorAt(limit) => {
+                self.flush_state.bytes_since_flush + incoming > limit
+            }
+        }
+    }
+
+    /// Apply the buffer limit before `incoming` more record bytes are written:
+    /// flush, or fail with [`Error::BufferLimitExceeded`].
+    pub(super) fn apply_buffer_limit(&mut self, incoming: u64) -> Result<()> {
+        if !self.exceeds_buffer_limit(incoming) {
+            return Ok(());
+        }
+        let buffered = self.flush_state.bytes_since_flush;
+        match self.streaming_config.buffer_limit {
+            // A record larger than the limit on its own is still written
+            BufferLimit::FlushAt(_) if buffered == 0 => Ok(()),
+            BufferLimit::FlushAt(_) => self.flush(),
+            BufferLimit::ErrorAt(limit) => Err(Error::BufferLimitExceeded {
+                buffered,
+                incoming,
+                limit,
+            }),
+            BufferLimit::Unlimited => Ok(()),
+        }
+    }
+
+    /// Apply the buffer limit before one more record of `cg_id` is written.
+    pub(super) fn reserve_record(&mut self, cg_id: &str) -> Result<()> {
+        let record_size = self
+            .open_dts
+            .get(cg_id)
+            .map_or(0, |dt| dt.record_size as u64);
+        self.apply_buffer_limit(record_size)
+    }
+
+    /// Make all records written so far readable and flush them to disk.
+    ///
+    /// In addition to [`flush()`](Self::flush), this patches the size of every
+    /// open DT block and the cycle count of every channel group with an open
+    /// data block, and links channel groups whose data already spans several
+    /// DT blocks through an interim DL block. If the logger stops after this
+    /// call (crash, power loss), the file reads back with every record written
+    /// before it. Writing continues normally afterwards.
+    ///
+    /// Combine with `FlushPolicy::EveryDuration` or call it periodically to
+    /// bound data loss to a fixed time window.
+    pub fn flush_now(&mut self) -> Result<()> {
+        let cg_ids: Vec<String> = self.open_dts.keys().cloned().collect();
+        for cg_id in &cg_ids {
+            self.checkpoint_data_block(cg_id)?;
+        }
+        self.flush()
+    }
+
     /// Check if auto-flush should be triggered and perform it if needed.
     ///
     /// This is called internally after each write_record when a flush policy is set.
@@ -189,14 +304,53 @@ impl<W: MdfWrite> MdfWriter<W> {
         }
     }
 
-    /// Record that data was written for streaming tracking.
-    pub(super) fn record_write(&mut self, records: u64, bytes: u64) {
+    /// Record that data was written to `cg_id` for streaming tracking.
+    pub(super) fn record_write(&mut self, cg_id: &str, records: u64, bytes: u64) {
         self.flush_state.record_write(records, bytes);
+        if let Some(dt) = self.open_dts.get_mut(cg_id) {
+            dt.unflushed_bytes += bytes;
+        }
     }
 
     /// Finalizes the file (flushes all data to disk).
+    ///
+    /// The signal data of VLSD channels is written as one SD block per
+    /// channel here, as are the sample reductions of the channel groups, so
+    /// data blocks must have been finished before.
     pub fn finalize(&mut self) -> Result<()> {
+        self.write_signal_data()?;
+        self.write_all_sample_reductions()?;
         self.writer.flush()?;
         Ok(())
     }
+
+    /// Write the buffered signal data of VLSD channels as SD blocks and
+    /// link them from their channels.
+    fn write_signal_data(&mut self) -> Result<()> {
+        const DATA_ADDR_OFFSET: u64 = 64;
+        for ((cg_id, index), data) in core::mem::take(&mut self.signal_data) {
+            let cn_id = self
+                .channel_map
+                .iter()
+                .find(|(_, (cg, idx))| *cg == cg_id && *idx == index)
+                .map(|(cn, _)| cn.clone());
+            let (Some(cn_id), false) = (cn_id, data.is_empty()) else {
+                continue;
+            };
+            let header = BlockHeader {
+                id: "##SD".to_string(),
+                reserved: 0,
+                length: 24 + data.len() as u64,
+                link_count: 0,
+            };
+            let mut block = header.to_bytes()?;
+            block.extend_from_slice(&data);
+            let sd_pos = self.write_block(&block)?;
+            let cn_pos = self
+                .get_block_position(&cn_id)
+                .ok_or_else(|| Error::BlockLinkError(format!("Channel '{}' not found", cn_id)))?;
+            self.update_link(cn_pos + DATA_ADDR_OFFSET, sd_pos)?;
+        }
+        Ok(())
+    }
 }
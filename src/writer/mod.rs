@@ -29,6 +29,26 @@
 //! 7. Finish the data block with [`finish_data_block()`](MdfWriter::finish_data_block)
 //! 8. Finalize the file with [`finalize()`](MdfWriter::finalize)
 //!
+//! Data blocks of several channel groups may be open at the same time and
+//! written in any order. Each channel group keeps its own chain of DT blocks:
+//! when another block was written after a group's current DT block, the next
+//! record of that group starts a new DT block, and the blocks are linked
+//! through a DL block when the data block is finished. Since every switch
+//! between groups starts a new block, write records in batches (for example
+//! with [`write_records()`](MdfWriter::write_records)) when interleaving.
+//!
+//! For compile-time checked records, create channels with
+//! [`add_typed_channel()`](MdfWriter::add_typed_channel) and write them with
+//! [`write_record_typed()`](MdfWriter::write_record_typed) (see [`ChannelHandle`]).
+//!
+//! Column-oriented data (one slice per channel) can be written directly with
+//! [`write_columns()`](MdfWriter::write_columns).
+//!
+//! To let the writer fill in the master time channel, create it with
+//! [`add_time_channel()`](MdfWriter::add_time_channel) and write records with
+//! [`write_record_at()`](MdfWriter::write_record_at) or, for fixed rate data,
+//! [`write_sample()`](MdfWriter::write_sample).
+//!
 //! # Example (std feature)
 //!
 #![cfg_attr(feature = "std", doc = "```no_run")]
@@ -134,21 +154,46 @@ use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::blocks::ChannelBlock;
+use crate::blocks::{ChannelBlock, HeaderBlock};
+use crate::types::DecodedValue;
 
+#[cfg(feature = "async")]
+mod async_writer;
+#[cfg(feature = "std")]
+mod background;
+mod columns;
 mod data;
 mod init;
 mod io;
+mod reduction;
+mod spool;
 mod streaming;
+mod time;
 mod traits;
+mod typed;
+mod verify;
 
+pub use columns::ColumnData;
 use data::ChannelEncoder;
+use init::ChannelDescription;
+pub use init::DuplicateNamePolicy;
+use reduction::SampleReducer;
+pub use spool::SpoolWriter;
 use streaming::FlushState;
-pub use streaming::{FlushPolicy, StreamingConfig};
+pub use streaming::{BufferLimit, FlushPolicy, StreamingConfig};
+use time::MasterClock;
 pub use traits::{MdfWrite, VecWriter};
+pub use typed::{ChannelHandle, ChannelValue, TypedRecord};
+pub use verify::{VerificationIssue, VerificationReport, verify_mdf_bytes};
 
+#[cfg(feature = "async")]
+pub use async_writer::AsyncMdfWriter;
+#[cfg(feature = "std")]
+pub use background::BackgroundWriter;
 #[cfg(feature = "std")]
 pub use traits::FileWriter;
+#[cfg(feature = "std")]
+pub use verify::verify_mdf_file;
 
 /// Helper structure tracking an open data block during writing.
 struct OpenDataBlock {
@@ -167,8 +212,33 @@ struct OpenDataBlock {
     record_buf: Vec<u8>,
     /// Template filled with constant values used to initialise each record
     record_template: Vec<u8>,
+    /// Per-channel default values, which may be omitted from written records
+    defaults: Vec<Option<DecodedValue>>,
     /// Precomputed per-channel encoders
     encoders: Vec<ChannelEncoder>,
+    /// Per-channel (min, max) raw values, present when range tracking is enabled
+    value_ranges: Option<Vec<Option<(f64, f64)>>>,
+    /// One reducer per configured sample reduction factor
+    reducers: Vec<SampleReducer>,
+    /// Length of the record ID prefix of each record
+    record_id_len: usize,
+    /// Record bytes written to this data block since the last flush
+    unflushed_bytes: u64,
+    /// Signal data of the VLSD channels by channel index
+    signal_data: Vec<(usize, Vec<u8>)>,
+}
+
+/// DT blocks of the finished data blocks of a channel group, continued by
+/// its next data block.
+struct FinishedData {
+    dt_ids: Vec<String>,
+    dt_positions: Vec<u64>,
+    dt_sizes: Vec<u64>,
+    record_count: u64,
+    /// Reducers continued by the next data block, written by `finalize()`
+    reducers: Vec<SampleReducer>,
+    encoders: Vec<ChannelEncoder>,
+    record_id_len: usize,
 }
 
 /// Writer for creating MDF4 files.
@@ -207,6 +277,32 @@ pub struct MdfWriter<W: MdfWrite = VecWriter> {
     cg_offsets: BTreeMap<String, usize>,
     cg_channels: BTreeMap<String, Vec<ChannelBlock>>,
     channel_map: BTreeMap<String, (String, usize)>,
+    /// Comment and display name per channel ID
+    channel_descriptions: BTreeMap<String, ChannelDescription>,
+    /// Comment and properties per channel group ID
+    channel_group_descriptions: BTreeMap<String, ChannelDescription>,
+    /// Default values per channel group ID and channel index
+    channel_defaults: BTreeMap<String, BTreeMap<usize, DecodedValue>>,
+    /// Reduction factors for SR blocks of channel groups started afterwards
+    sample_reduction_factors: Vec<u64>,
+    /// Generated master time channels per channel group ID
+    master_clocks: BTreeMap<String, MasterClock>,
+    /// Reusable buffer for encoded records of the batch writers
+    batch_buf: Vec<u8>,
+    /// Positions of written TX/MD blocks keyed by their bytes; `None` disables
+    /// text block deduplication
+    text_blocks: Option<BTreeMap<Vec<u8>, u64>>,
+    /// Whether min/max raw values are tracked for newly started data blocks
+    track_value_ranges: bool,
+    /// Handling of duplicate channel names within a channel group
+    duplicate_names: DuplicateNamePolicy,
+    /// Header block contents; the time section is patched in place when changed
+    header: HeaderBlock,
+    /// Signal data of channels added by `add_vlsd_channel()` by channel
+    /// group ID and channel index, written as SD blocks by `finalize()`
+    signal_data: BTreeMap<(String, usize), Vec<u8>>,
+    /// Finished data blocks per channel group ID
+    finished_data: BTreeMap<String, FinishedData>,
     /// Streaming configuration for auto-flush behavior
     streaming_config: StreamingConfig,
     /// Tracks flush state for streaming writes
@@ -229,6 +325,18 @@ impl<W: MdfWrite> MdfWriter<W> {
             cg_offsets: BTreeMap::new(),
             cg_channels: BTreeMap::new(),
             channel_map: BTreeMap::new(),
+            channel_descriptions: BTreeMap::new(),
+            channel_group_descriptions: BTreeMap::new(),
+            channel_defaults: BTreeMap::new(),
+            sample_reduction_factors: Vec::new(),
+            master_clocks: BTreeMap::new(),
+            batch_buf: Vec::new(),
+            text_blocks: Some(BTreeMap::new()),
+            track_value_ranges: false,
+            duplicate_names: DuplicateNamePolicy::default(),
+            header: HeaderBlock::default(),
+            signal_data: BTreeMap::new(),
+            finished_data: BTreeMap::new(),
             streaming_config: StreamingConfig::default(),
             flush_state: FlushState::default(),
         }
@@ -268,6 +376,104 @@ impl<W: MdfWrite> MdfWriter<W> {
         &self.streaming_config.policy
     }
 
+    /// Cap the record bytes buffered between flushes.
+    ///
+    /// Before a record is written that would raise
+    /// [`unflushed_bytes()`](Self::unflushed_bytes) above the limit, the
+    /// writer either flushes ([`BufferLimit::FlushAt`]) or rejects the record
+    /// with [`Error::BufferLimitExceeded`](crate::Error::BufferLimitExceeded)
+    /// ([`BufferLimit::ErrorAt`]), so the caller can apply backpressure.
+    /// Batch writes stop at the first rejected record; the records before it
+    /// are written.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Never hold more than 64 KB of unflushed record data
+    /// let mut writer = MdfWriter::new("output.mf4")?
+    ///     .with_buffer_limit(BufferLimit::FlushAt(64 * 1024));
+    /// ```
+    pub fn with_buffer_limit(mut self, limit: BufferLimit) -> Self {
+        self.streaming_config.buffer_limit = limit;
+        self
+    }
+
+    /// Set the buffer limit after construction.
+    pub fn set_buffer_limit(&mut self, limit: BufferLimit) {
+        self.streaming_config.buffer_limit = limit;
+    }
+
+    /// Enable or disable text block deduplication.
+    ///
+    /// Enabled by default: identical names, units and comments (e.g. `"°C"`
+    /// or `"rpm"` on hundreds of DBC signals) are written once and every
+    /// channel links to the same TX/MD block. Disabling it writes a separate
+    /// block for every string and drops the cache.
+    pub fn with_text_deduplication(mut self, enabled: bool) -> Self {
+        self.set_text_deduplication(enabled);
+        self
+    }
+
+    /// Enable or disable text block deduplication after construction.
+    ///
+    /// Only blocks written after enabling are considered for reuse.
+    pub fn set_text_deduplication(&mut self, enabled: bool) {
+        match (enabled, self.text_blocks.is_some()) {
+            (true, false) => self.text_blocks = Some(BTreeMap::new()),
+            (false, true) => self.text_blocks = None,
+            _ => {}
+        }
+    }
+
+    /// Enable or disable automatic min/max raw value tracking.
+    ///
+    /// When enabled, the writer records the smallest and largest raw value of
+    /// every numeric channel while records are written. When the data block is
+    /// finished, the values are stored in the channel's `min_raw_value` and
+    /// `max_raw_value` fields and the value range valid flag is set, so viewers
+    /// can autoscale without scanning the data.
+    ///
+    /// The setting applies to data blocks started after this call.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut writer = MdfWriter::new("output.mf4")?.with_value_range_tracking(true);
+    /// ```
+    pub fn with_value_range_tracking(mut self, enabled: bool) -> Self {
+        self.track_value_ranges = enabled;
+        self
+    }
+
+    /// Enable or disable automatic min/max raw value tracking after construction.
+    pub fn set_value_range_tracking(&mut self, enabled: bool) {
+        self.track_value_ranges = enabled;
+    }
+
+    /// Set how duplicate channel names within a channel group are handled.
+    ///
+    /// By default duplicates are accepted. With
+    /// [`DuplicateNamePolicy::Reject`], [`add_channel()`](Self::add_channel)
+    /// fails for a name already used in the group; with
+    /// [`DuplicateNamePolicy::Rename`] the new channel gets a numeric suffix.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut writer = MdfWriter::new("output.mf4")?
+    ///     .with_duplicate_name_policy(DuplicateNamePolicy::Rename);
+    /// // A second "Speed" channel in the same group is named "Speed_1"
+    /// ```
+    pub fn with_duplicate_name_policy(mut self, policy: DuplicateNamePolicy) -> Self {
+        self.duplicate_names = policy;
+        self
+    }
+
+    /// Set the duplicate channel name policy after construction.
+    pub fn set_duplicate_name_policy(&mut self, policy: DuplicateNamePolicy) {
+        self.duplicate_names = policy;
+    }
+
     /// Get streaming statistics.
     ///
     /// Returns (total_records, total_bytes, flush_count).
@@ -0,0 +1,211 @@
+//! Sample reduction (SR block) generation.
+//!
+//! When sample reduction is enabled with
+//! [`with_sample_reduction()`](MdfWriter::with_sample_reduction), every
+//! record written to a channel group is folded into one reducer per
+//! configured reduction factor. A reducer collects the mean, minimum and
+//! maximum raw value of each numeric channel over `factor` consecutive
+//! records. The reducers of a channel group continue through all of its
+//! data blocks. When the file is finalized, the reduction records are
+//! written to RD blocks and described by a chain of SR blocks linked from
+//! the channel group, so viewers can draw an overview of long recordings
+//! without decoding every sample.
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::data::ChannelEncoder;
+use super::{MdfWrite, MdfWriter};
+use crate::{
+    Result,
+    blocks::{BlockHeader, SampleReductionBlock},
+};
+
+/// Accumulates reduction records for one reduction factor.
+pub(super) struct SampleReducer {
+    /// Number of records folded into one reduction record
+    factor: u64,
+    /// Records in the current interval
+    count: u64,
+    sum: Vec<f64>,
+    min: Vec<f64>,
+    max: Vec<f64>,
+    /// First record of the current interval; provides the bytes of
+    /// non-numeric channels
+    first: Vec<u8>,
+    /// Scratch buffer for encoding one part of a reduction record
+    scratch: Vec<u8>,
+    /// Encoded reduction records
+    data: Vec<u8>,
+    /// Number of finished reduction records
+    cycles: u64,
+}
+
+impl SampleReducer {
+    pub(super) fn new(factor: u64, channel_count: usize, record_size: usize) -> Self {
+        Self {
+            factor,
+            count: 0,
+            sum: vec![0.0; channel_count],
+            min: vec![0.0; channel_count],
+            max: vec![0.0; channel_count],
+            first: vec![0u8; record_size],
+            scratch: vec![0u8; record_size],
+            data: Vec::new(),
+            cycles: 0,
+        }
+    }
+
+    /// Fold one encoded record into the current interval.
+    pub(super) fn push(
+        &mut self,
+        encoders: &[ChannelEncoder],
+        record: &[u8],
+        record_id_len: usize,
+    ) {
+        if self.count == 0 {
+            self.first.copy_from_slice(record);
+        }
+        for (i, enc) in encoders.iter().enumerate() {
+            let Some(v) = enc.raw_value(record) else {
+                continue;
+            };
+            if self.count == 0 {
+                self.sum[i] = v;
+                self.min[i] = v;
+                self.max[i] = v;
+            } else {
+                self.sum[i] += v;
+                self.min[i] = self.min[i].min(v);
+                self.max[i] = self.max[i].max(v);
+            }
+        }
+        self.count += 1;
+        if self.count == self.factor {
+            self.close_interval(encoders, record_id_len);
+        }
+    }
+
+    /// Append the reduction record of the current interval, if any.
+    fn close_interval(&mut self, encoders: &[ChannelEncoder], record_id_len: usize) {
+        if self.count == 0 {
+            return;
+        }
+        let n = self.count as f64;
+        for part in 0..3 {
+            self.scratch.copy_from_slice(&self.first);
+            for (i, enc) in encoders.iter().enumerate() {
+                let v = match part {
+                    0 => self.sum[i] / n,
+                    1 => self.min[i],
+                    _ => self.max[i],
+                };
+                enc.encode_f64(&mut self.scratch, v);
+            }
+            // Reduction records never carry the record ID
+            self.data.extend_from_slice(&self.scratch[record_id_len..]);
+        }
+        self.cycles += 1;
+        self.count = 0;
+    }
+}
+
+impl<W: MdfWrite> MdfWriter<W> {
+    /// Enable SR block generation with the given reduction factors.
+    ///
+    /// For each factor `n`, one reduction record holding the mean, minimum and
+    /// maximum of every numeric channel is computed per `n` records, over all
+    /// data blocks of a channel group. The reductions are written as RD/SR
+    /// blocks by [`finalize()`](Self::finalize). An empty slice disables
+    /// sample reduction.
+    ///
+    /// The setting applies to channel groups whose first data block is
+    /// started after this call.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // One overview point per 100 and per 10000 records
+    /// let mut writer = MdfWriter::new("output.mf4")?.with_sample_reduction(&[100, 10_000]);
+    /// ```
+    pub fn with_sample_reduction(mut self, factors: &[u64]) -> Self {
+        self.set_sample_reduction(factors);
+        self
+    }
+
+    /// Set the SR block reduction factors after construction.
+    ///
+    /// Factors below 2 are ignored, as they would not reduce anything.
+    pub fn set_sample_reduction(&mut self, factors: &[u64]) {
+        let mut factors: Vec<u64> = factors.iter().copied().filter(|&f| f > 1).collect();
+        factors.sort_unstable();
+        factors.dedup();
+        self.sample_reduction_factors = factors;
+    }
+
+    /// Write the sample reductions of all channel groups with finished
+    /// data blocks.
+    pub(super) fn write_all_sample_reductions(&mut self) -> Result<()> {
+        let mut pending = Vec::new();
+        for (cg_id, finished) in &mut self.finished_data {
+            if !finished.reducers.is_empty() {
+                let reducers = core::mem::take(&mut finished.reducers);
+                let encoders = core::mem::take(&mut finished.encoders);
+                pending.push((cg_id.clone(), reducers, encoders, finished.record_id_len));
+            }
+        }
+        for (cg_id, reducers, encoders, record_id_len) in pending {
+            self.write_sample_reductions(&cg_id, reducers, &encoders, record_id_len)?;
+        }
+        Ok(())
+    }
+
+    /// Write the RD and SR blocks of `reducers` and link them to `cg_id`.
+    fn write_sample_reductions(
+        &mut self,
+        cg_id: &str,
+        reducers: Vec<SampleReducer>,
+        encoders: &[ChannelEncoder],
+        record_id_len: usize,
+    ) -> Result<()> {
+        const CG_SR_LINK_OFFSET: u64 = 56;
+        const SR_NEXT_LINK_OFFSET: u64 = 24;
+
+        let mut prev_sr: Option<u64> = None;
+        for mut reducer in reducers {
+            reducer.close_interval(encoders, record_id_len);
+            if reducer.cycles == 0 {
+                continue;
+            }
+
+            let header = BlockHeader {
+                id: "##RD".to_string(),
+                reserved: 0,
+                length: 24 + reducer.data.len() as u64,
+                link_count: 0,
+            };
+            let mut rd_bytes = header.to_bytes()?;
+            rd_bytes.extend_from_slice(&reducer.data);
+            let rd_id = format!("rd_{}_{}", cg_id, reducer.factor);
+            let rd_pos = self.write_block_with_id(&rd_bytes, &rd_id)?;
+
+            let sr_block = SampleReductionBlock::new(
+                rd_pos,
+                reducer.cycles,
+                reducer.factor as f64,
+                SampleReductionBlock::SYNC_TYPE_INDEX,
+            );
+            let sr_id = format!("sr_{}_{}", cg_id, reducer.factor);
+            let sr_pos = self.write_block_with_id(&sr_block.to_bytes()?, &sr_id)?;
+
+            match prev_sr {
+                Some(prev) => self.update_link(prev + SR_NEXT_LINK_OFFSET, sr_pos)?,
+                None => self.update_block_link(cg_id, CG_SR_LINK_OFFSET, &sr_id)?,
+            }
+            prev_sr = Some(sr_pos);
+        }
+        Ok(())
+    }
+}
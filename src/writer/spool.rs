@@ -0,0 +1,97 @@
+//! In-memory spooling of write operations.
+//!
+//! [`SpoolWriter`] is an [`MdfWrite`] backend that does not perform any I/O.
+//! It records the writes made by [`MdfWriter`](super::MdfWriter), including
+//! the seek + write pairs used to patch links and sizes, as a list of
+//! positioned byte runs. The caller later applies them, in order, to the real
+//! destination with [`take_ops()`](SpoolWriter::take_ops). This decouples
+//! record encoding from I/O that cannot happen inside [`MdfWrite`], such as
+//! async file handles or DMA-driven storage.
+//!
+//! Consecutive writes are merged into one run, and patches of data that is
+//! still spooled are applied in place, so the number of operations stays
+//! small.
+
+use alloc::vec::Vec;
+
+use super::MdfWrite;
+use crate::Result;
+
+/// [`MdfWrite`] backend collecting positioned writes in memory.
+#[derive(Debug, Default)]
+pub struct SpoolWriter {
+    /// Pending writes as (file position, bytes), in the order they were made
+    ops: Vec<(u64, Vec<u8>)>,
+    position: u64,
+    pending: usize,
+}
+
+impl SpoolWriter {
+    /// Create an empty spool positioned at the start of the file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of spooled bytes not yet taken with [`take_ops()`](Self::take_ops).
+    pub fn pending_bytes(&self) -> usize {
+        self.pending
+    }
+
+    /// Take the spooled writes as `(position, bytes)` pairs.
+    ///
+    /// Applying them in the returned order (seek to `position`, write
+    /// `bytes`) reproduces the file written so far.
+    pub fn take_ops(&mut self) -> Vec<(u64, Vec<u8>)> {
+        self.pending = 0;
+        core::mem::take(&mut self.ops)
+    }
+}
+
+impl MdfWrite for SpoolWriter {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        let start = self.position;
+        let end = start + bytes.len() as u64;
+        self.position = end;
+
+        // Appending to the latest run is the common case for records
+        match self.ops.last_mut() {
+            Some((pos, data)) if *pos + data.len() as u64 == start => {
+                data.extend_from_slice(bytes);
+                self.pending += bytes.len();
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        // Patch of data that is still spooled, unless a later run overlaps it
+        for (pos, data) in self.ops.iter_mut().rev() {
+            let data_end = *pos + data.len() as u64;
+            if start >= *pos && end <= data_end {
+                let offset = (start - *pos) as usize;
+                data[offset..offset + bytes.len()].copy_from_slice(bytes);
+                return Ok(());
+            }
+            if start < data_end && end > *pos {
+                break;
+            }
+        }
+
+        self.ops.push((start, bytes.to_vec()));
+        self.pending += bytes.len();
+        Ok(())
+    }
+
+    fn seek(&mut self, pos: u64) -> Result<u64> {
+        self.position = pos;
+        Ok(pos)
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        // Spooled data is written by whoever takes the operations
+        Ok(())
+    }
+}
@@ -23,6 +23,11 @@
 //!     writer.write_record(&cg_id, &values)?;
 //! }
 //! ```
+//!
+//! Loggers that must bound data loss to a time window can use
+//! `FlushPolicy::EveryDuration` (`std` feature), and call
+//! [`flush_now()`](crate::MdfWriter::flush_now) to make everything written
+//! so far readable, including cycle counts, without finishing data blocks.
 
 /// Policy for automatic flushing of MDF4 data during streaming writes.
 ///
@@ -69,6 +74,21 @@ pub enum FlushPolicy {
     /// FlushPolicy::EveryNBytes(1024 * 1024)
     /// ```
     EveryNBytes(u64),
+
+    /// Flush when the given wall clock time has passed since the last flush.
+    ///
+    /// This bounds the amount of data lost on a crash or power cut to a
+    /// fixed time window, independent of the data rate. The check happens
+    /// on each write, so a flush is only triggered once a record arrives
+    /// after the interval has elapsed.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // Flush at least every 5 seconds while records are being written
+    /// FlushPolicy::EveryDuration(Duration::from_secs(5))
+    /// ```
+    #[cfg(feature = "std")]
+    EveryDuration(core::time::Duration),
 }
 
 impl FlushPolicy {
@@ -78,11 +98,32 @@ impl FlushPolicy {
     }
 }
 
+/// Upper bound for record data buffered between flushes.
+///
+/// Used by embedded loggers to guarantee bounded memory use: the limit is
+/// checked before each record is written, against the record bytes written
+/// since the last flush.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BufferLimit {
+    /// No limit. This is the default behavior.
+    #[default]
+    Unlimited,
+
+    /// Flush before the buffered record data would exceed N bytes.
+    FlushAt(u64),
+
+    /// Reject records that would make the buffered record data exceed N
+    /// bytes with `Error::BufferLimitExceeded`, until the caller flushes.
+    ErrorAt(u64),
+}
+
 /// Configuration for streaming MDF4 writes.
 #[derive(Debug, Clone, Default)]
 pub struct StreamingConfig {
     /// The flush policy to use.
     pub policy: FlushPolicy,
+    /// Limit for record data buffered between flushes.
+    pub buffer_limit: BufferLimit,
 }
 
 impl StreamingConfig {
@@ -95,6 +136,7 @@ impl StreamingConfig {
     pub fn every_n_records(n: u64) -> Self {
         Self {
             policy: FlushPolicy::EveryNRecords(n),
+            ..Self::default()
         }
     }
 
@@ -102,6 +144,16 @@ impl StreamingConfig {
     pub fn every_n_bytes(n: u64) -> Self {
         Self {
             policy: FlushPolicy::EveryNBytes(n),
+            ..Self::default()
+        }
+    }
+
+    /// Create a streaming configuration that flushes after a wall clock interval.
+    #[cfg(feature = "std")]
+    pub fn every_duration(interval: core::time::Duration) -> Self {
+        Self {
+            policy: FlushPolicy::EveryDuration(interval),
+            ..Self::default()
         }
     }
 }
@@ -119,6 +171,9 @@ pub(super) struct FlushState {
     pub total_bytes: u64,
     /// Number of flushes performed.
     pub flush_count: u64,
+    /// Time of the last flush, or of the first write if none happened yet.
+    #[cfg(feature = "std")]
+    pub last_flush: Option<std::time::Instant>,
 }
 
 impl FlushState {
@@ -128,6 +183,10 @@ impl FlushState {
         self.bytes_since_flush += bytes;
         self.total_records += records;
         self.total_bytes += bytes;
+        #[cfg(feature = "std")]
+        if self.last_flush.is_none() {
+            self.last_flush = Some(std::time::Instant::now());
+        }
     }
 
     /// Check if a flush should be triggered based on the policy.
@@ -136,14 +195,32 @@ impl FlushState {
             FlushPolicy::Manual => false,
             FlushPolicy::EveryNRecords(n) => self.records_since_flush >= *n,
             FlushPolicy::EveryNBytes(n) => self.bytes_since_flush >= *n,
+            #[cfg(feature = "std")]
+            FlushPolicy::EveryDuration(interval) => {
+                self.flush_due(*interval, std::time::Instant::now())
+            }
         }
     }
 
+    /// Check if records are pending and `interval` has passed at `now` since
+    /// the last flush.
+    #[cfg(feature = "std")]
+    fn flush_due(&self, interval: core::time::Duration, now: std::time::Instant) -> bool {
+        self.records_since_flush > 0
+            && self
+                .last_flush
+                .is_some_and(|last| now.saturating_duration_since(last) >= interval)
+    }
+
     /// Reset counters after a flush.
     pub fn on_flush(&mut self) {
         self.records_since_flush = 0;
         self.bytes_since_flush = 0;
         self.flush_count += 1;
+        #[cfg(feature = "std")]
+        {
+            self.last_flush = Some(std::time::Instant::now());
+        }
     }
 }
 
@@ -205,4 +282,37 @@ mod tests {
         let config = StreamingConfig::every_n_bytes(1024);
         assert_eq!(config.policy, FlushPolicy::EveryNBytes(1024));
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_flush_state_every_duration() {
+        use core::time::Duration;
+
+        let mut state = FlushState::default();
+        let policy = FlushPolicy::EveryDuration(Duration::from_millis(20));
+        assert!(policy.is_auto());
+
+        // Nothing written yet
+        assert!(!state.should_flush(&policy));
+
+        let interval = Duration::from_millis(20);
+        state.record_write(1, 8);
+        let first_write = state.last_flush.unwrap();
+        assert!(!state.flush_due(interval, first_write + Duration::from_millis(10)));
+        assert!(state.flush_due(interval, first_write + Duration::from_millis(20)));
+
+        // Interval restarts at the flush, and there is nothing new to flush
+        state.on_flush();
+        let flushed = state.last_flush.unwrap();
+        assert!(!state.flush_due(interval, flushed + Duration::from_millis(25)));
+        state.record_write(1, 8);
+        assert!(!state.flush_due(interval, flushed + Duration::from_millis(10)));
+        assert!(state.flush_due(interval, flushed + Duration::from_millis(25)));
+
+        let config = StreamingConfig::every_duration(Duration::from_secs(5));
+        assert_eq!(
+            config.policy,
+            FlushPolicy::EveryDuration(Duration::from_secs(5))
+        );
+    }
 }
@@ -0,0 +1,208 @@
+//! Writer-generated master time channels.
+//!
+//! Almost every channel group starts with a time channel, and without help
+//! every caller has to compute the timestamp and put it at the right position
+//! of each record. [`add_time_channel()`](MdfWriter::add_time_channel) creates
+//! a 64-bit floating point master channel (unit `s`) whose values are filled
+//! in by the writer:
+//!
+//! - [`write_record_at()`](MdfWriter::write_record_at) takes the timestamp in
+//!   seconds next to the remaining channel values.
+//! - [`write_sample()`](MdfWriter::write_sample) derives the timestamp from
+//!   the sample rate configured with
+//!   [`set_sample_rate()`](MdfWriter::set_sample_rate).
+//! - `write_record_at_instant()` (`std` feature) takes a
+//!   [`std::time::Instant`] and stores the time elapsed since the first
+//!   record of the group.
+//!
+//! In all three cases `values` holds one entry per channel **except** the
+//! time channel.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let cg = writer.add_channel_group(None, |_| {})?;
+//! let time = writer.add_time_channel(&cg, None, |_| {})?;
+//! writer.add_channel(&cg, Some(&time), |ch| {
+//!     ch.name = Some("Speed".into());
+//!     ch.data_type = DataType::FloatLE;
+//!     ch.bit_count = 64;
+//! })?;
+//! writer.set_sample_rate(&cg, 100.0)?;
+//!
+//! writer.start_data_block_for_cg(&cg, 0)?;
+//! writer.write_sample(&cg, &[DecodedValue::Float(12.5)])?; // t = 0.00 s
+//! writer.write_sample(&cg, &[DecodedValue::Float(12.7)])?; // t = 0.01 s
+//! writer.finish_data_block(&cg)?;
+//! ```
+
+use alloc::string::{String, ToString};
+
+use super::data::encode_values;
+use super::{MdfWrite, MdfWriter};
+use crate::{
+    Error, Result,
+    blocks::{ChannelBlock, DataType},
+    types::DecodedValue,
+};
+
+/// State of a master time channel generated by the writer.
+pub(super) struct MasterClock {
+    /// Index of the time channel within its channel group
+    index: usize,
+    /// Samples per second used by `write_sample`
+    sample_rate: Option<f64>,
+    /// Number of records written with `write_sample`
+    samples: u64,
+    /// Timestamp of the first record written with `write_record_at_instant`
+    #[cfg(feature = "std")]
+    origin: Option<std::time::Instant>,
+}
+
+impl<W: MdfWrite> MdfWriter<W> {
+    /// Adds a master time channel whose values are generated by the writer.
+    ///
+    /// The channel is a 64-bit little endian float named `Time` with unit
+    /// `s`; the `configure` closure may rename it or set other fields, but
+    /// must keep the floating point data type. Records for the group are then
+    /// written with [`write_record_at()`](Self::write_record_at) or
+    /// [`write_sample()`](Self::write_sample) without a time value.
+    pub fn add_time_channel<F>(
+        &mut self,
+        cg_id: &str,
+        prev_cn_id: Option<&str>,
+        configure: F,
+    ) -> Result<String>
+    where
+        F: FnOnce(&mut ChannelBlock),
+    {
+        let mut is_float = true;
+        let cn_id = self.add_channel(cg_id, prev_cn_id, |ch| {
+            ch.name = Some("Time".to_string());
+            ch.data_type = DataType::FloatLE;
+            ch.bit_count = 64;
+            configure(ch);
+            is_float = matches!(ch.data_type, DataType::FloatLE) && matches!(ch.bit_count, 32 | 64);
+        })?;
+        if !is_float {
+            return Err(Error::BlockSerializationError(
+                "generated time channel must be a little endian float".into(),
+            ));
+        }
+        self.set_time_channel(&cn_id)?;
+        self.set_channel_unit(&cn_id, "s")?;
+
+        let index = self
+            .channel_map
+            .get(&cn_id)
+            .map(|(_, idx)| *idx)
+            .ok_or_else(|| Error::BlockLinkError("time channel was not registered".into()))?;
+        self.master_clocks.insert(
+            cg_id.to_string(),
+            MasterClock {
+                index,
+                sample_rate: None,
+                samples: 0,
+                #[cfg(feature = "std")]
+                origin: None,
+            },
+        );
+        Ok(cn_id)
+    }
+
+    /// Set the sample rate in Hz used by [`write_sample()`](Self::write_sample).
+    ///
+    /// The n-th sample written afterwards gets the timestamp `n / rate`
+    /// seconds, counted from the first sample of the group.
+    pub fn set_sample_rate(&mut self, cg_id: &str, rate_hz: f64) -> Result<()> {
+        if !(rate_hz.is_finite() && rate_hz > 0.0) {
+            return Err(Error::BlockSerializationError(
+                "sample rate must be a positive number".into(),
+            ));
+        }
+        self.master_clock_mut(cg_id)?.sample_rate = Some(rate_hz);
+        Ok(())
+    }
+
+    /// Append one record with the given timestamp in seconds.
+    ///
+    /// `values` contains one value per channel of the group except the
+    /// generated time channel, in channel order.
+    pub fn write_record_at(&mut self, cg_id: &str, t: f64, values: &[DecodedValue]) -> Result<()> {
+        let index = self.master_clock_mut(cg_id)?.index;
+        {
+            let dt = self.open_dts.get(cg_id).ok_or_else(|| {
+                Error::BlockSerializationError("no open DT block for this channel group".into())
+            })?;
+            if values.len() + 1 != dt.encoders.len() {
+                return Err(Error::BlockSerializationError(
+                    "value count mismatch".into(),
+                ));
+            }
+        }
+        self.reserve_record(cg_id)?;
+        self.roll_dt_if_needed(cg_id)?;
+
+        let record_bytes = {
+            let dt = self.open_dts.get_mut(cg_id).unwrap();
+            dt.record_buf.copy_from_slice(&dt.record_template);
+            let (before, after) = dt.encoders.split_at(index);
+            encode_values(before, &mut dt.record_buf, &values[..index]);
+            after[0].encode(&mut dt.record_buf, &DecodedValue::Float(t));
+            encode_values(&after[1..], &mut dt.record_buf, &values[index..]);
+            dt.track_record();
+            dt.record_count += 1;
+            dt.record_buf.len() as u64
+        };
+
+        let buf = &self.open_dts.get(cg_id).unwrap().record_buf;
+        self.writer.write_all(buf)?;
+        self.offset += record_bytes;
+
+        self.record_write(cg_id, 1, record_bytes);
+        self.maybe_auto_flush()?;
+
+        Ok(())
+    }
+
+    /// Append one record timestamped from the configured sample rate.
+    ///
+    /// Requires [`set_sample_rate()`](Self::set_sample_rate). `values` has the
+    /// same layout as for [`write_record_at()`](Self::write_record_at).
+    pub fn write_sample(&mut self, cg_id: &str, values: &[DecodedValue]) -> Result<()> {
+        let clock = self.master_clock_mut(cg_id)?;
+        let rate = clock.sample_rate.ok_or_else(|| {
+            Error::BlockSerializationError("no sample rate set for this channel group".into())
+        })?;
+        let t = clock.samples as f64 / rate;
+        self.write_record_at(cg_id, t, values)?;
+        self.master_clock_mut(cg_id)?.samples += 1;
+        Ok(())
+    }
+
+    /// Append one record timestamped with a monotonic [`Instant`](std::time::Instant).
+    ///
+    /// The first instant written for a group becomes its time origin, so the
+    /// time channel starts at zero. To relate it to wall clock time, set the
+    /// file start time with [`set_start_time_now()`](Self::set_start_time_now).
+    #[cfg(feature = "std")]
+    pub fn write_record_at_instant(
+        &mut self,
+        cg_id: &str,
+        at: std::time::Instant,
+        values: &[DecodedValue],
+    ) -> Result<()> {
+        let clock = self.master_clock_mut(cg_id)?;
+        let origin = *clock.origin.get_or_insert(at);
+        let t = at.saturating_duration_since(origin).as_secs_f64();
+        self.write_record_at(cg_id, t, values)
+    }
+
+    fn master_clock_mut(&mut self, cg_id: &str) -> Result<&mut MasterClock> {
+        self.master_clocks.get_mut(cg_id).ok_or_else(|| {
+            Error::BlockSerializationError(
+                "channel group has no generated time channel (see add_time_channel)".into(),
+            )
+        })
+    }
+}
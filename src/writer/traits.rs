@@ -115,11 +115,13 @@ mod std_impl {
     use crate::Result;
     use std::fs::File;
     use std::io::{BufWriter, Seek, SeekFrom, Write};
+    use std::path::{Path, PathBuf};
 
     /// A wrapper that implements MdfWrite for standard file I/O.
     pub struct FileWriter {
         inner: BufWriter<File>,
         position: u64,
+        path: PathBuf,
     }
 
     impl FileWriter {
@@ -132,7 +134,16 @@ mod std_impl {
         pub fn with_capacity(path: &str, capacity: usize) -> Result<Self> {
             let file = File::create(path)?;
             let inner = BufWriter::with_capacity(capacity, file);
-            Ok(Self { inner, position: 0 })
+            Ok(Self {
+                inner,
+                position: 0,
+                path: PathBuf::from(path),
+            })
+        }
+
+        /// Path of the file being written.
+        pub fn path(&self) -> &Path {
+            &self.path
         }
     }
 
@@ -0,0 +1,283 @@
+//! Typed channel handles for compile-time checked record writing.
+//!
+//! [`MdfWriter::add_channel`] returns an untyped `String` identifier and
+//! [`MdfWriter::write_record`] takes a positional slice of [`DecodedValue`]s,
+//! so swapping two values of different types silently corrupts the record.
+//!
+//! A [`ChannelHandle<T>`] remembers both the channel group and the Rust type
+//! of its samples. Records written with
+//! [`write_record_typed()`](MdfWriter::write_record_typed) pair every value
+//! with its handle, so the compiler rejects mismatched types and the field
+//! order no longer matters.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mdf4_rs::MdfWriter;
+//! use mdf4_rs::writer::ChannelHandle;
+//!
+//! let mut writer = MdfWriter::new("typed.mf4")?;
+//! writer.init_mdf_file()?;
+//! let cg = writer.add_channel_group(None, |_| {})?;
+//! let time: ChannelHandle<f64> = writer.add_typed_channel(&cg, None, |ch| {
+//!     ch.name = Some("Time".into());
+//! })?;
+//! let rpm: ChannelHandle<u16> = writer.add_typed_channel(&cg, Some(&time), |ch| {
+//!     ch.name = Some("RPM".into());
+//! })?;
+//! writer.set_time_channel(&time)?;
+//!
+//! writer.start_data_block_for_cg(&cg, 0)?;
+//! writer.write_record_typed(&cg, ((&time, 0.0), (&rpm, 850)))?;
+//! // writer.write_record_typed(&cg, ((&time, 850), (&rpm, 0.0)))?; // does not compile
+//! writer.finish_data_block(&cg)?;
+//! writer.finalize()?;
+//! ```
+
+use alloc::string::String;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use super::{MdfWrite, MdfWriter};
+use crate::{Error, Result, blocks::ChannelBlock, blocks::DataType, types::DecodedValue};
+
+/// A Rust type that can be stored in a fixed-size MDF channel.
+///
+/// Implemented for the primitive integer and floating point types. Each
+/// implementation fixes the MDF [`DataType`] and bit width used for the
+/// channel when it is created with
+/// [`add_typed_channel()`](MdfWriter::add_typed_channel).
+pub trait ChannelValue: Copy {
+    /// MDF data type of the channel.
+    const DATA_TYPE: DataType;
+    /// Number of bits occupied by one sample.
+    const BIT_COUNT: u32;
+
+    /// Converts the sample into the value representation used by the encoder.
+    fn to_decoded(self) -> DecodedValue;
+}
+
+macro_rules! impl_channel_value {
+    ($($ty:ty => $data_type:ident, $variant:ident as $target:ty;)*) => {
+        $(
+            impl ChannelValue for $ty {
+                const DATA_TYPE: DataType = DataType::$data_type;
+                const BIT_COUNT: u32 = <$ty>::BITS;
+
+                #[inline]
+                fn to_decoded(self) -> DecodedValue {
+                    DecodedValue::$variant(self as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_channel_value! {
+    u8 => UnsignedIntegerLE, UnsignedInteger as u64;
+    u16 => UnsignedIntegerLE, UnsignedInteger as u64;
+    u32 => UnsignedIntegerLE, UnsignedInteger as u64;
+    u64 => UnsignedIntegerLE, UnsignedInteger as u64;
+    i8 => SignedIntegerLE, SignedInteger as i64;
+    i16 => SignedIntegerLE, SignedInteger as i64;
+    i32 => SignedIntegerLE, SignedInteger as i64;
+    i64 => SignedIntegerLE, SignedInteger as i64;
+}
+
+impl ChannelValue for f32 {
+    const DATA_TYPE: DataType = DataType::FloatLE;
+    const BIT_COUNT: u32 = 32;
+
+    #[inline]
+    fn to_decoded(self) -> DecodedValue {
+        DecodedValue::Float(self as f64)
+    }
+}
+
+impl ChannelValue for f64 {
+    const DATA_TYPE: DataType = DataType::FloatLE;
+    const BIT_COUNT: u32 = 64;
+
+    #[inline]
+    fn to_decoded(self) -> DecodedValue {
+        DecodedValue::Float(self)
+    }
+}
+
+/// Handle to a channel whose samples have the Rust type `T`.
+///
+/// Returned by [`MdfWriter::add_typed_channel()`]. The handle dereferences to
+/// the channel ID string, so it can be passed to every API that accepts a
+/// `&str` channel ID (`set_time_channel`, `set_channel_unit`, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelHandle<T> {
+    id: String,
+    cg_id: String,
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ChannelHandle<T> {
+    /// The channel ID as returned by [`MdfWriter::add_channel()`].
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The ID of the channel group this channel belongs to.
+    pub fn channel_group(&self) -> &str {
+        &self.cg_id
+    }
+
+    /// Position of the channel within its channel group.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Deref for ChannelHandle<T> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl<T> AsRef<str> for ChannelHandle<T> {
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+/// A set of `(handle, value)` pairs forming one record.
+///
+/// Implemented for a single `(&ChannelHandle<T>, T)` pair and for tuples of
+/// up to twelve such pairs. Channels of the group that are not part of the
+/// record keep the value from the record template (zero by default).
+pub trait TypedRecord {
+    /// Calls `f` with the channel group ID, channel index and value of each field.
+    fn for_each_field(&self, f: &mut dyn FnMut(&str, usize, DecodedValue));
+}
+
+impl<T: ChannelValue> TypedRecord for (&ChannelHandle<T>, T) {
+    fn for_each_field(&self, f: &mut dyn FnMut(&str, usize, DecodedValue)) {
+        f(&self.0.cg_id, self.0.index, self.1.to_decoded());
+    }
+}
+
+macro_rules! impl_typed_record_tuple {
+    ($($ty:ident . $idx:tt),+) => {
+        impl<$($ty: ChannelValue),+> TypedRecord for ($((&ChannelHandle<$ty>, $ty),)+) {
+            fn for_each_field(&self, f: &mut dyn FnMut(&str, usize, DecodedValue)) {
+                $(
+                    f(&(self.$idx).0.cg_id, (self.$idx).0.index, (self.$idx).1.to_decoded());
+                )+
+            }
+        }
+    };
+}
+
+impl_typed_record_tuple!(A.0);
+impl_typed_record_tuple!(A.0, B.1);
+impl_typed_record_tuple!(A.0, B.1, C.2);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+impl_typed_record_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
+
+impl<W: MdfWrite> MdfWriter<W> {
+    /// Adds a channel whose data type and bit count are derived from `T`.
+    ///
+    /// The `configure` closure can set any other channel field (name, flags,
+    /// limits, ...). Changing `data_type` or `bit_count` to something that does
+    /// not match `T` is rejected with [`Error::BlockSerializationError`], and
+    /// the channel group is left unchanged.
+    pub fn add_typed_channel<T, F>(
+        &mut self,
+        cg_id: &str,
+        prev_cn_id: Option<&str>,
+        configure: F,
+    ) -> Result<ChannelHandle<T>>
+    where
+        T: ChannelValue,
+        F: FnOnce(&mut ChannelBlock),
+    {
+        // Check the configured channel before anything is written
+        let mut channel = ChannelBlock {
+            data_type: T::DATA_TYPE,
+            bit_count: T::BIT_COUNT,
+            ..ChannelBlock::default()
+        };
+        configure(&mut channel);
+        if channel.data_type != T::DATA_TYPE || channel.bit_count != T::BIT_COUNT {
+            return Err(Error::BlockSerializationError(
+                "typed channel data type does not match its Rust type".into(),
+            ));
+        }
+        let id = self.add_channel(cg_id, prev_cn_id, |ch| *ch = channel)?;
+        let (cg_id, index) = self
+            .channel_map
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Error::BlockLinkError("typed channel was not registered".into()))?;
+        Ok(ChannelHandle {
+            id,
+            cg_id,
+            index,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Append one record built from typed `(handle, value)` pairs.
+    ///
+    /// Values are placed by handle rather than by position, and the compiler
+    /// checks that each value matches the type of its handle. All handles must
+    /// belong to `cg_id`. Channels that are not listed keep their record
+    /// template value (see [`set_record_template()`](Self::set_record_template)).
+    ///
+    /// If a flush policy is configured, this method will automatically flush
+    /// to disk when the policy threshold is reached.
+    pub fn write_record_typed<R: TypedRecord>(&mut self, cg_id: &str, record: R) -> Result<()> {
+        if !self.open_dts.contains_key(cg_id) {
+            return Err(Error::BlockSerializationError(
+                "no open DT block for this channel group".into(),
+            ));
+        }
+        // Validate the handles before anything is reserved or written
+        let mut foreign = false;
+        record.for_each_field(&mut |handle_cg, _, _| foreign |= handle_cg != cg_id);
+        if foreign {
+            return Err(Error::BlockSerializationError(
+                "channel handle belongs to a different channel group".into(),
+            ));
+        }
+        self.reserve_record(cg_id)?;
+        self.roll_dt_if_needed(cg_id)?;
+
+        let record_bytes = {
+            let dt = self.open_dts.get_mut(cg_id).unwrap();
+            dt.record_buf.copy_from_slice(&dt.record_template);
+            record.for_each_field(&mut |_, index, value| {
+                if let Some(enc) = dt.encoders.get(index) {
+                    enc.encode(&mut dt.record_buf, &value);
+                }
+            });
+            dt.track_record();
+            dt.record_count += 1;
+            dt.record_buf.len() as u64
+        };
+
+        let buf = &self.open_dts.get(cg_id).unwrap().record_buf;
+        self.writer.write_all(buf)?;
+        self.offset += record_bytes;
+
+        self.record_write(cg_id, 1, record_bytes);
+        self.maybe_auto_flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! Structural self-check of written MDF files.
+//!
+//! [`verify_mdf_bytes()`] walks every link reachable from the header block
+//! and checks that it points to a well-formed block, then compares the
+//! cycle counts of all channel groups with the amount of record data of
+//! their data group. [`MdfWriter::finalize_and_verify()`] runs these checks
+//! on the file just written, so writer bugs surface before files are handed
+//! to other tools.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mdf4_rs::MdfWriter;
+//!
+//! let mut writer = MdfWriter::new("output.mf4")?;
+//! writer.init_mdf_file()?;
+//! // ... add channels and write records ...
+//! let report = writer.finalize_and_verify()?;
+//! assert!(report.is_ok(), "{}", report);
+//! # Ok::<(), mdf4_rs::Error>(())
+//! ```
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::blocks::{BlockHeader, BlockParse, ChannelGroupBlock, DataGroupBlock};
+
+/// Block types that may be linked from an MDF 4 file.
+const KNOWN_BLOCK_IDS: &[&str] = &[
+    "##HD", "##FH", "##CH", "##AT", "##EV", "##DG", "##CG", "##SI", "##CN", "##CC", "##CA", "##DT",
+    "##SR", "##RD", "##SD", "##DL", "##DZ", "##HL", "##LD", "##DV", "##DI", "##RV", "##RI", "##TX",
+    "##MD",
+];
+
+/// Address of the header block, right after the identification block.
+const HD_ADDRESS: u64 = 64;
+
+/// Channel group flag marking a VLSD channel group.
+const CG_FLAG_VLSD: u16 = 1;
+
+/// A single problem found while verifying a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationIssue {
+    /// File offset of the block the problem was found in (or at)
+    pub address: u64,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for VerificationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:08X}: {}", self.address, self.message)
+    }
+}
+
+/// Result of verifying an MDF file.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationReport {
+    /// Number of distinct blocks reached from the header block
+    pub blocks_checked: usize,
+    /// Number of data groups
+    pub data_groups: usize,
+    /// Number of channel groups
+    pub channel_groups: usize,
+    /// Problems found; empty for a consistent file
+    pub issues: Vec<VerificationIssue>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn issue(&mut self, address: u64, message: String) {
+        self.issues.push(VerificationIssue { address, message });
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} blocks, {} data groups, {} channel groups: ",
+            self.blocks_checked, self.data_groups, self.channel_groups
+        )?;
+        if self.issues.is_empty() {
+            return write!(f, "no issues");
+        }
+        write!(f, "{} issue(s)", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Verify the structure of an MDF 4 file held in memory.
+///
+/// Checks that:
+/// - the file starts with an `MDF` identification block and a header block
+/// - every link reachable from the header block points to an 8-byte aligned
+///   block of a known type that lies completely within the file and whose
+///   length covers its links
+/// - the record data of each data group matches the cycle counts and record
+///   sizes of its channel groups (data groups with VLSD channel groups are
+///   skipped)
+///
+/// Problems are collected in the returned report instead of aborting at the
+/// first one.
+pub fn verify_mdf_bytes(bytes: &[u8]) -> VerificationReport {
+    let mut report = VerificationReport::default();
+    if bytes.len() < HD_ADDRESS as usize || !bytes.starts_with(b"MDF") {
+        report.issue(0, "missing MDF identification block".into());
+        return report;
+    }
+
+    walk_links(bytes, &mut report);
+    if report.is_ok() {
+        // Only interpret block contents once all links are known to be sound
+        check_cycle_counts(bytes, &mut report);
+    }
+    report
+}
+
+/// Read a link or other `u64` field; `offset` must be in bounds.
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_le_bytes(buf)
+}
+
+/// Parse and validate the header of the block at `address`.
+fn block_header(bytes: &[u8], address: u64) -> core::result::Result<BlockHeader, String> {
+    if !address.is_multiple_of(8) {
+        return Err("block is not 8-byte aligned".into());
+    }
+    let start = address as usize;
+    if address.saturating_add(24) > bytes.len() as u64 {
+        return Err("block header lies beyond the end of the file".into());
+    }
+    let header = BlockHeader::from_bytes(&bytes[start..start + 24]).map_err(|e| format!("{e}"))?;
+    if !KNOWN_BLOCK_IDS.contains(&header.id.as_str()) {
+        return Err(format!("unknown block id {:?}", header.id));
+    }
+    let links_end = header.link_count.saturating_mul(8).saturating_add(24);
+    if header.length < links_end {
+        return Err(format!(
+            "{} block length {} is too small for {} links",
+            header.id, header.length, header.link_count
+        ));
+    }
+    if address.saturating_add(header.length) > bytes.len() as u64 {
+        return Err(format!(
+            "{} block of length {} extends beyond the end of the file",
+            header.id, header.length
+        ));
+    }
+    Ok(header)
+}
+
+/// Visit every block reachable from the header block.
+fn walk_links(bytes: &[u8], report: &mut VerificationReport) {
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![(HD_ADDRESS, 0u64)];
+    while let Some((address, source)) = pending.pop() {
+        if !visited.insert(address) {
+            continue;
+        }
+        let header = match block_header(bytes, address) {
+            Ok(header) => header,
+            Err(message) => {
+                let message = format!("invalid link from 0x{:08X}: {}", source, message);
+                report.issue(address, message);
+                continue;
+            }
+        };
+        if address == HD_ADDRESS && header.id != "##HD" {
+            report.issue(address, format!("expected ##HD block, found {}", header.id));
+            continue;
+        }
+        report.blocks_checked += 1;
+        for i in 0..header.link_count as usize {
+            let link = read_u64(bytes, address as usize + 24 + i * 8);
+            if link != 0 {
+                pending.push((link, address));
+            }
+        }
+    }
+}
+
+/// Compare record data sizes with channel group cycle counts.
+fn check_cycle_counts(bytes: &[u8], report: &mut VerificationReport) {
+    let mut dg_addr = read_u64(bytes, HD_ADDRESS as usize + 24);
+    let mut seen = BTreeSet::new();
+    while dg_addr != 0 && seen.insert(dg_addr) {
+        report.data_groups += 1;
+        let dg = match DataGroupBlock::from_bytes(&bytes[dg_addr as usize..]) {
+            Ok(dg) => dg,
+            Err(e) => {
+                report.issue(dg_addr, format!("malformed data group: {e}"));
+                return;
+            }
+        };
+
+        let mut expected = 0u64;
+        let mut has_vlsd = false;
+        let mut cg_addr = dg.first_cg_addr;
+        while cg_addr != 0 && seen.insert(cg_addr) {
+            report.channel_groups += 1;
+            let cg = match ChannelGroupBlock::from_bytes(&bytes[cg_addr as usize..]) {
+                Ok(cg) => cg,
+                Err(e) => {
+                    report.issue(cg_addr, format!("malformed channel group: {e}"));
+                    return;
+                }
+            };
+            has_vlsd |= cg.flags & CG_FLAG_VLSD != 0;
+            let record_len =
+                dg.record_id_size as u64 + cg.record_size as u64 + cg.invalidation_size as u64;
+            expected = expected.saturating_add(record_len.saturating_mul(cg.cycle_count));
+            cg_addr = cg.next_cg_addr;
+        }
+
+        if !has_vlsd {
+            match data_length(bytes, dg.data_block_addr) {
+                Ok(actual) if actual != expected => report.issue(
+                    dg_addr,
+                    format!(
+                        "cycle counts require {} bytes of record data, data blocks hold {}",
+                        expected, actual
+                    ),
+                ),
+                Ok(_) => {}
+                Err((address, message)) => report.issue(address, message),
+            }
+        }
+        dg_addr = dg.next_dg_addr;
+    }
+}
+
+/// Total number of (uncompressed) data bytes referenced by a data link.
+fn data_length(bytes: &[u8], address: u64) -> core::result::Result<u64, (u64, String)> {
+    if address == 0 {
+        return Ok(0);
+    }
+    // Headers were validated while walking the links
+    let header = block_header(bytes, address).map_err(|m| (address, m))?;
+    let start = address as usize;
+    match header.id.as_str() {
+        "##DT" | "##DV" => Ok(header.length - 24),
+        // Original data length of the compressed block
+        "##DZ" => Ok(read_u64(bytes, start + 32)),
+        "##HL" => data_length(bytes, read_u64(bytes, start + 24)),
+        "##DL" => {
+            let mut total = 0u64;
+            let mut dl_addr = address;
+            let mut seen = BTreeSet::new();
+            while dl_addr != 0 && seen.insert(dl_addr) {
+                let dl = block_header(bytes, dl_addr).map_err(|m| (dl_addr, m))?;
+                let dl_start = dl_addr as usize;
+                for i in 1..dl.link_count as usize {
+                    let link = read_u64(bytes, dl_start + 24 + i * 8);
+                    total += data_length(bytes, link)?;
+                }
+                dl_addr = read_u64(bytes, dl_start + 24);
+            }
+            Ok(total)
+        }
+        other => Err((
+            address,
+            format!("unexpected {} block as record data", other),
+        )),
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impl {
+    use super::{VerificationReport, verify_mdf_bytes};
+    use crate::Result;
+    use crate::writer::{FileWriter, MdfWriter};
+    use alloc::format;
+
+    /// Verify the structure of an MDF 4 file on disk (see [`verify_mdf_bytes()`]).
+    pub fn verify_mdf_file(path: impl AsRef<std::path::Path>) -> Result<VerificationReport> {
+        let bytes = std::fs::read(path)?;
+        Ok(verify_mdf_bytes(&bytes))
+    }
+
+    impl MdfWriter<FileWriter> {
+        /// Finalize the file, then re-open it and verify its structure.
+        ///
+        /// Channel groups whose data block was never finished are reported as
+        /// issues, since their records are not accounted for. An `Err` is only
+        /// returned for I/O errors; structural problems are listed in the
+        /// returned [`VerificationReport`].
+        pub fn finalize_and_verify(&mut self) -> Result<VerificationReport> {
+            self.finalize()?;
+            let mut report = verify_mdf_file(self.writer.path())?;
+            for (cg_id, dt) in &self.open_dts {
+                report.issue(
+                    dt.start_pos,
+                    format!("data block of channel group '{}' was not finished", cg_id),
+                );
+            }
+            Ok(report)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use std_impl::verify_mdf_file;
@@ -1,6 +1,7 @@
 use mdf4_rs::{
-    DataType, DecodedValue, MDF, MdfWriter, Result, blocks::ChannelBlock, cut_mdf_by_time,
-    parsing::decoder::decode_channel_value,
+    CutOptions, DataType, DecodedValue, MDF, MdfWriter, Result, blocks::ChannelBlock,
+    cut_mdf_by_records, cut_mdf_by_time, cut_mdf_by_times, cut_mdf_by_times_to_files,
+    cut_mdf_to_writer, cut_mdf_where, cut_mdf_with_options, parsing::decoder::decode_channel_value,
 };
 
 #[test]
@@ -222,6 +223,386 @@ fn cut_mdf_file_by_time() -> Result<()> {
     std::fs::remove_file(output)?;
     Ok(())
 }
+
+/// Write a file with a `Time` master channel (0.0, 0.1, ... 0.9 s) and a
+/// `Val` channel holding the record index.
+fn write_cut_input(path: &std::path::Path) -> Result<()> {
+    cut_input_writer(path)?.finalize()
+}
+
+/// Writer of the file of [`write_cut_input()`], before finalizing it.
+fn cut_input_writer(path: &std::path::Path) -> Result<MdfWriter<mdf4_rs::writer::FileWriter>> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+        ch.name = Some("Val".into());
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..10u64 {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64 * 0.1),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    Ok(writer)
+}
+
+/// Values of the `Val` channel of the first channel group.
+fn cut_values(path: &std::path::Path) -> Result<Vec<u64>> {
+    cut_group_values(path, 0)
+}
+
+/// Values of the second channel of a channel group.
+fn cut_group_values(path: &std::path::Path, group: usize) -> Result<Vec<u64>> {
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    let values = groups[group].channels()[1].values()?;
+    Ok(values
+        .into_iter()
+        .map(|v| match v {
+            Some(DecodedValue::UnsignedInteger(u)) => u,
+            other => panic!("unexpected value {:?}", other),
+        })
+        .collect())
+}
+
+#[test]
+fn cut_mdf_file_by_multiple_windows() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("cut_windows_input.mf4");
+    let output = dir.join("cut_windows_output.mf4");
+    let parts = [
+        dir.join("cut_windows_part0.mf4"),
+        dir.join("cut_windows_part1.mf4"),
+    ];
+    write_cut_input(&input)?;
+
+    // Concatenated, with overlapping windows written once
+    let windows = [(0.1, 0.2), (0.6, 0.7), (0.65, 0.8)];
+    cut_mdf_by_times(input.to_str().unwrap(), output.to_str().unwrap(), &windows)?;
+    assert_eq!(cut_values(&output)?, vec![1, 2, 6, 7, 8]);
+
+    // One file per window, keeping the original timestamps
+    let paths: Vec<&str> = parts.iter().map(|p| p.to_str().unwrap()).collect();
+    cut_mdf_by_times_to_files(input.to_str().unwrap(), &paths, &[(0.6, 0.7), (0.0, 0.2)])?;
+    assert_eq!(cut_values(&parts[0])?, vec![6, 7]);
+    assert_eq!(cut_values(&parts[1])?, vec![0, 1, 2]);
+    let mdf = MDF::from_file(paths[0])?;
+    let times = mdf.channel_groups()[0].channels()[0].values()?;
+    match times[0] {
+        Some(DecodedValue::Float(t)) => assert!((t - 0.6).abs() < 1e-6),
+        ref other => panic!("unexpected time {:?}", other),
+    }
+
+    // Each window needs an output path
+    assert!(cut_mdf_by_times_to_files(input.to_str().unwrap(), &paths[..1], &windows).is_err());
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    for part in parts {
+        std::fs::remove_file(part)?;
+    }
+    Ok(())
+}
+#[test]
+fn cut_mdf_file_with_fragmented_data() -> Result<()> {
+    use mdf4_rs::blocks::DataListBlock;
+
+    let dir = std::env::temp_dir();
+    let input = dir.join("cut_fragmented_input.mf4");
+    let output = dir.join("cut_fragmented_output.mf4");
+
+    let mut writer = MdfWriter::new(input.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.byte_offset = 8;
+        ch.bit_count = 32;
+        ch.name = Some("Val".into());
+    })?;
+
+    // Written to set up the record layout; replaced below
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    writer.finish_data_block(&cg_id)?;
+
+    // Records split over two DT blocks, one of them across the boundary
+    let mut records = Vec::new();
+    for i in 0..10u32 {
+        records.extend_from_slice(&(i as f64 * 0.1).to_le_bytes());
+        records.extend_from_slice(&i.to_le_bytes());
+    }
+    let split = 12 * 4 + 5;
+    let mut dt_addrs = Vec::new();
+    for part in [&records[..split], &records[split..]] {
+        let mut block = b"##DT".to_vec();
+        block.extend_from_slice(&[0; 4]);
+        block.extend_from_slice(&(24 + part.len() as u64).to_le_bytes());
+        block.extend_from_slice(&0u64.to_le_bytes());
+        block.extend_from_slice(part);
+        dt_addrs.push(writer.write_block(&block)?);
+    }
+    let dl = DataListBlock::new_with_offsets(dt_addrs, vec![0, split as u64]);
+    let dl_addr = writer.write_block(&dl.to_bytes()?)?;
+    let dg_addr = writer.get_block_position("dg_0").unwrap();
+    writer.update_link(dg_addr + 40, dl_addr)?;
+    writer.finalize()?;
+
+    cut_mdf_by_time(input.to_str().unwrap(), output.to_str().unwrap(), 0.3, 0.6)?;
+    assert_eq!(cut_values(&output)?, vec![3, 4, 5, 6]);
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+#[test]
+fn cut_mdf_file_keeps_events_and_attachments() -> Result<()> {
+    use mdf4_rs::MdfIndex;
+    use mdf4_rs::blocks::{AttachmentBlock, EventBlock, EventRangeType, TextBlock};
+
+    let dir = std::env::temp_dir();
+    let input = dir.join("cut_events_input.mf4");
+    let output = dir.join("cut_events_output.mf4");
+
+    let mut writer = cut_input_writer(&input)?;
+    let dbc = b"BO_ 100 Engine: 8 ECU".to_vec();
+    let mut attachment = AttachmentBlock::embedded(&dbc);
+    attachment.filename_addr = writer.write_block(&TextBlock::new("engine.dbc").to_bytes()?)?;
+    let at_addr = writer.write_block(&attachment.to_bytes()?)?;
+
+    // A range from 0.3 to 0.5 s, and a marker after the cut window
+    let mut begin = EventBlock::trigger(0.0);
+    begin.range_type = EventRangeType::RangeBegin;
+    (begin.sync_base_value, begin.sync_factor) = (3, 0.1);
+    begin.name_addr = writer.write_block(&TextBlock::new("Brake").to_bytes()?)?;
+    begin.attachment_addrs = vec![at_addr];
+    begin.attachment_count = 1;
+    let begin_addr = writer.write_block(&begin.to_bytes()?)?;
+    let mut end = EventBlock::marker(0.0);
+    end.range_type = EventRangeType::RangeEnd;
+    (end.sync_base_value, end.sync_factor) = (5, 0.1);
+    end.range_ev_addr = begin_addr;
+    let end_addr = writer.write_block(&end.to_bytes()?)?;
+    let late_addr = writer.write_block(&EventBlock::marker(9.0).to_bytes()?)?;
+    writer.update_link(begin_addr + 24, end_addr)?;
+    writer.update_link(end_addr + 24, late_addr)?;
+    let hd_addr = writer.get_block_position("hd_block").unwrap();
+    writer.update_link(hd_addr + 48, at_addr)?;
+    writer.update_link(hd_addr + 56, begin_addr)?;
+    writer.finalize()?;
+
+    cut_mdf_by_time(input.to_str().unwrap(), output.to_str().unwrap(), 0.2, 0.6)?;
+    assert_eq!(cut_values(&output)?, vec![2, 3, 4, 5, 6]);
+
+    let index = MdfIndex::from_file(output.to_str().unwrap())?;
+    assert_eq!(index.attachments.len(), 1);
+    assert_eq!(
+        index.attachments[0].file_name.as_deref(),
+        Some("engine.dbc")
+    );
+    let mut reader = mdf4_rs::FileRangeReader::new(output.to_str().unwrap())?;
+    assert_eq!(index.read_attachment(0, &mut reader)?, dbc);
+
+    assert_eq!(index.events.len(), 2);
+    let (begin, end) = (&index.events[0], &index.events[1]);
+    assert_eq!(begin.name.as_deref(), Some("Brake"));
+    assert!((begin.sync_value - 0.3).abs() < 1e-9);
+    assert_eq!(begin.attachments, vec![0]);
+    assert_eq!(end.range_begin, Some(0));
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
+/// Add a channel group named `SlowGroup` with a `SlowTime` master channel
+/// (0.0, 0.2, ... 0.8 s) and a `Slow` channel holding the record index.
+fn add_slow_group(writer: &mut MdfWriter<mdf4_rs::writer::FileWriter>) -> Result<()> {
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_name(&cg_id, "SlowGroup")?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("SlowTime".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+        ch.name = Some("Slow".into());
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..5u64 {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64 * 0.2),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg_id)
+}
+
+#[test]
+fn cut_mdf_file_by_records_and_condition() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("cut_records_input.mf4");
+    let output = dir.join("cut_records_output.mf4");
+
+    let mut writer = cut_input_writer(&input)?;
+    add_slow_group(&mut writer)?;
+    writer.finalize()?;
+    let (input_path, output_path) = (input.to_str().unwrap(), output.to_str().unwrap());
+
+    // Records 3..6 span 0.3 to 0.5 s, which holds one record of the other group
+    cut_mdf_by_records(input_path, output_path, 0, 3, 3)?;
+    assert_eq!(cut_values(&output)?, vec![3, 4, 5]);
+    assert_eq!(cut_group_values(&output, 1)?, vec![2]);
+
+    // The runs 1..3 and 7..9 span 0.1 to 0.2 s and 0.7 to 0.8 s
+    cut_mdf_where(input_path, output_path, "Val", |value| {
+        matches!(value, DecodedValue::UnsignedInteger(1 | 2 | 7 | 8))
+    })?;
+    assert_eq!(cut_values(&output)?, vec![1, 2, 7, 8]);
+    assert_eq!(cut_group_values(&output, 1)?, vec![1, 4]);
+
+    assert!(cut_mdf_by_records(input_path, output_path, 2, 0, 1).is_err());
+    assert!(cut_mdf_where(input_path, output_path, "Missing", |_| true).is_err());
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
+#[test]
+fn cut_mdf_file_with_channel_subset() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("cut_subset_input.mf4");
+    let output = dir.join("cut_subset_output.mf4");
+
+    // Like the usual input, with a `Flag` channel between `Time` and `Val`
+    let mut writer = MdfWriter::new(input.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    let flag_id = writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 8;
+        ch.name = Some("Flag".into());
+    })?;
+    writer.add_channel(&cg_id, Some(&flag_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+        ch.name = Some("Val".into());
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..10u64 {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64 * 0.1),
+                DecodedValue::UnsignedInteger(1),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    add_slow_group(&mut writer)?;
+    writer.finalize()?;
+    let (input_path, output_path) = (input.to_str().unwrap(), output.to_str().unwrap());
+    let window = [(0.2, 0.6)];
+
+    // Only the named group is kept
+    let options = CutOptions::new().with_groups(&["SlowGroup"]);
+    cut_mdf_with_options(input_path, output_path, &window, &options)?;
+    let mdf = MDF::from_file(output_path)?;
+    assert_eq!(mdf.channel_groups().len(), 1);
+    assert_eq!(
+        mdf.channel_groups()[0].name()?.as_deref(),
+        Some("SlowGroup")
+    );
+    assert_eq!(cut_values(&output)?, vec![1, 2, 3]);
+
+    // Selected channels keep their group's time channel and are packed
+    let options = CutOptions::new().with_channels(&["Val"]);
+    cut_mdf_with_options(input_path, output_path, &window, &options)?;
+    let mdf = MDF::from_file(output_path)?;
+    let groups = mdf.channel_groups();
+    assert_eq!(groups.len(), 1);
+    let names: Vec<_> = groups[0]
+        .channels()
+        .iter()
+        .map(|ch| ch.name())
+        .collect::<Result<_>>()?;
+    assert_eq!(
+        names,
+        vec![Some("Time".to_string()), Some("Val".to_string())]
+    );
+    assert_eq!(cut_values(&output)?, vec![2, 3, 4, 5, 6]);
+
+    // Nothing matches
+    let options = CutOptions::new()
+        .with_channels(&["Val"])
+        .with_groups(&["SlowGroup"]);
+    cut_mdf_with_options(input_path, output_path, &window, &options)?;
+    assert!(MDF::from_file(output_path)?.channel_groups().is_empty());
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
+#[test]
+fn cut_mdf_file_to_memory() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("cut_memory_input.mf4");
+    let output = dir.join("cut_memory_output.mf4");
+    write_cut_input(&input)?;
+
+    let mut writer = MdfWriter::in_memory();
+    cut_mdf_to_writer(
+        input.to_str().unwrap(),
+        &mut writer,
+        &[(0.2, 0.5)],
+        &CutOptions::new(),
+    )?;
+    let bytes = writer.into_inner().into_inner();
+
+    // Same file as when writing to a path
+    cut_mdf_by_time(input.to_str().unwrap(), output.to_str().unwrap(), 0.2, 0.5)?;
+    assert_eq!(bytes, std::fs::read(&output)?);
+    assert_eq!(cut_values(&output)?, vec![2, 3, 4, 5]);
+
+    std::fs::remove_file(input)?;
+    std::fs::remove_file(output)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use mdf4_rs::MDF;
@@ -216,3 +216,56 @@ fn asam_j1939_extended_ids() -> Result<()> {
     std::fs::remove_file(&temp_path)?;
     Ok(())
 }
+
+#[test]
+fn asam_batch_ingestion() -> Result<()> {
+    use embedded_can::{ExtendedId, StandardId};
+    use mdf4_rs::can::{SimpleFdFrame, TimestampedFrame};
+
+    let standard = StandardId::new(0x100).unwrap();
+    let extended = ExtendedId::new(0x18FEF100).unwrap();
+    let frame =
+        |id: embedded_can::Id, byte: u8| SimpleFdFrame::new_classic(id, &[byte; 8]).unwrap();
+
+    // A chunk of a ring buffer, not in time order
+    let batch = [
+        TimestampedFrame::new(300_000, frame(standard.into(), 3)),
+        TimestampedFrame::new(100_000, frame(standard.into(), 1)),
+        TimestampedFrame::new(200_000, frame(extended.into(), 2)),
+        TimestampedFrame::new(100_000, frame(standard.into(), 4)),
+    ];
+    let fd_batch = [TimestampedFrame::new(
+        250_000,
+        SimpleFdFrame::new_fd_frame(standard, &[0xAA; 16], FdFlags::new(true, false)).unwrap(),
+    )];
+
+    let mut logger = RawCanLogger::new()?;
+    assert_eq!(logger.log_batch(&batch), 4);
+    assert_eq!(logger.log_fd_batch(&fd_batch), 1);
+    assert_eq!(logger.total_frame_count(), 5);
+
+    let mdf_bytes = logger.finalize()?;
+    let temp_path = std::env::temp_dir().join("asam_batch_test.mf4");
+    std::fs::write(&temp_path, &mdf_bytes)?;
+
+    let mdf = MDF::from_file(temp_path.to_str().unwrap())?;
+    let group = mdf
+        .channel_groups()
+        .into_iter()
+        .find(|g| g.name().ok().flatten().as_deref() == Some("CAN_DataFrame"))
+        .unwrap();
+    let channels = group.channels();
+    let mut frames = Vec::new();
+    let timestamps = channels[0].values()?;
+    let dataframes = channels[1].values()?;
+    for (ts, df) in timestamps.iter().zip(&dataframes) {
+        if let (Some(DecodedValue::Float(ts)), Some(DecodedValue::ByteArray(bytes))) = (ts, df) {
+            frames.push(((ts * 1e6).round() as u64, bytes[5]));
+        }
+    }
+    // Sorted by timestamp, equal timestamps in batch order
+    assert_eq!(frames, [(100_000, 1), (100_000, 4), (300_000, 3)]);
+
+    std::fs::remove_file(&temp_path)?;
+    Ok(())
+}
@@ -172,3 +172,53 @@ fn dbc_encode_decode_accuracy() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn dbc_logger_batch_ingestion() -> Result<()> {
+    use embedded_can::StandardId;
+    use mdf4_rs::can::{SimpleFdFrame, TimestampedFrame};
+
+    let dbc = Dbc::parse(COMPLETE_DBC).expect("Failed to parse DBC");
+    let mut logger = CanDbcLogger::new(dbc.clone())?;
+
+    let frame = |id: u16, rpm: f64| {
+        let payload = dbc
+            .encode(id as u32, &[("RPM", rpm)], false)
+            .unwrap_or_else(|_| vec![0; 8]);
+        SimpleFdFrame::new_classic(StandardId::new(id).unwrap(), &payload[..8]).unwrap()
+    };
+    let batch = [
+        TimestampedFrame::new(200_000, frame(256, 2000.0)),
+        TimestampedFrame::new(100_000, frame(256, 1000.0)),
+        // Not in the DBC
+        TimestampedFrame::new(150_000, frame(0x7FF, 0.0)),
+    ];
+    assert_eq!(logger.log_batch(&batch), 2);
+    assert_eq!(logger.frame_count(256), 2);
+
+    let mdf_bytes = logger.finalize()?;
+    let temp_path = std::env::temp_dir().join("dbc_batch_test.mf4");
+    std::fs::write(&temp_path, &mdf_bytes)?;
+
+    let mdf = MDF::from_file(temp_path.to_str().unwrap())?;
+    let group = mdf
+        .channel_groups()
+        .into_iter()
+        .find(|g| g.name().ok().flatten().as_deref() == Some("EngineData"))
+        .unwrap();
+    let channels = group.channels();
+    let rpm = channels
+        .iter()
+        .find(|ch| ch.name().ok().flatten().as_deref() == Some("RPM"))
+        .unwrap();
+    assert_eq!(
+        rpm.values()?,
+        vec![
+            Some(DecodedValue::Float(1000.0)),
+            Some(DecodedValue::Float(2000.0))
+        ]
+    );
+
+    std::fs::remove_file(&temp_path)?;
+    Ok(())
+}
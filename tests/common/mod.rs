@@ -0,0 +1,104 @@
+//! Recording fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use mdf4_rs::blocks::ConversionBlock;
+use mdf4_rs::writer::MdfWrite;
+use mdf4_rs::{DataType, DecodedValue, MdfWriter, Result};
+use std::path::Path;
+
+/// A channel of a test group and its value in record `i`.
+pub struct TestChannel {
+    name: &'static str,
+    data_type: DataType,
+    bit_count: u32,
+    value: fn(u64) -> DecodedValue,
+    unit: Option<&'static str>,
+    conversion: Option<ConversionBlock>,
+}
+
+impl TestChannel {
+    pub fn new(
+        name: &'static str,
+        data_type: DataType,
+        bit_count: u32,
+        value: fn(u64) -> DecodedValue,
+    ) -> Self {
+        Self {
+            name,
+            data_type,
+            bit_count,
+            value,
+            unit: None,
+            conversion: None,
+        }
+    }
+
+    /// A 64-bit float time channel.
+    pub fn time(value: fn(u64) -> DecodedValue) -> Self {
+        Self::new("Time", DataType::FloatLE, 64, value)
+    }
+
+    pub fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    pub fn with_conversion(mut self, conversion: ConversionBlock) -> Self {
+        self.conversion = Some(conversion);
+        self
+    }
+}
+
+/// Add a channel group with `records` records to an initialized writer.
+///
+/// The first channel is the time channel of the group.
+pub fn write_group<W: MdfWrite>(
+    writer: &mut MdfWriter<W>,
+    name: Option<&str>,
+    channels: &[TestChannel],
+    records: u64,
+) -> Result<()> {
+    let cg = writer.add_channel_group(None, |_| {})?;
+    if let Some(name) = name {
+        writer.set_channel_group_name(&cg, name)?;
+    }
+    let mut prev: Option<String> = None;
+    for (i, channel) in channels.iter().enumerate() {
+        let cn = writer.add_channel(&cg, prev.as_deref(), |ch| {
+            ch.data_type = channel.data_type;
+            ch.name = Some(channel.name.into());
+            ch.bit_count = channel.bit_count;
+        })?;
+        if i == 0 {
+            writer.set_time_channel(&cn)?;
+        }
+        if let Some(unit) = channel.unit {
+            writer.set_channel_unit(&cn, unit)?;
+        }
+        if let Some(conversion) = &channel.conversion {
+            writer.set_channel_conversion(&cn, conversion)?;
+        }
+        prev = Some(cn);
+    }
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..records {
+        let values: Vec<DecodedValue> = channels.iter().map(|ch| (ch.value)(i)).collect();
+        writer.write_record(&cg, &values)?;
+    }
+    writer.finish_data_block(&cg)
+}
+
+/// Write a recording with one channel group.
+pub fn write_recording(
+    path: &Path,
+    name: Option<&str>,
+    channels: &[TestChannel],
+    records: u64,
+) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    write_group(&mut writer, name, channels, records)?;
+    writer.finalize()
+}
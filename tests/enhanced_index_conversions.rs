@@ -266,6 +266,9 @@ fn test_index_serialization_with_resolved_data() -> Result<()> {
         pos_invalidation_bit: 0,
         conversion: Some(conversion),
         vlsd_data_address: None,
+        vlsd_blocks: Vec::new(),
+        stats: None,
+        block_stats: Vec::new(),
     };
 
     let indexed_group = IndexedChannelGroup {
@@ -277,11 +280,17 @@ fn test_index_serialization_with_resolved_data() -> Result<()> {
         record_count: 1,
         channels: vec![indexed_channel],
         data_blocks: vec![],
+        record_id: 0,
+        unsorted_records: vec![],
+        master_channel: None,
     };
 
     let index = MdfIndex {
         file_size: 1024,
+        fingerprint: None,
         channel_groups: vec![indexed_group],
+        attachments: vec![],
+        events: vec![],
     };
 
     index.save_to_file(temp_index_path.to_str().unwrap())?;
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{TestChannel, write_group};
+use mdf4_rs::export::gps::{GpsFormat, GpsOptions, export_gps, gps_tracks};
+use mdf4_rs::export::ndjson::{NdjsonOptions, export_ndjson};
+use mdf4_rs::{DataType, DecodedValue, MDF, MdfWriter, Result};
+use std::path::Path;
+
+/// Write a recording with a time channel (0.0, 0.5, ...), a speed and a
+/// gear channel.
+fn write_recording(path: &Path) -> Result<()> {
+    let channels = [
+        TestChannel::time(|i| DecodedValue::Float(i as f64 * 0.5)),
+        TestChannel::new("Speed", DataType::UnsignedIntegerLE, 16, |i| {
+            DecodedValue::UnsignedInteger(1000 + i * 100)
+        }),
+        TestChannel::new("Gear", DataType::SignedIntegerLE, 8, |i| {
+            DecodedValue::SignedInteger(i as i64 - 1)
+        }),
+    ];
+    common::write_recording(path, None, &channels, 4)
+}
+
+#[test]
+fn export_ndjson_with_selection_and_window() -> Result<()> {
+    let dir = std::env::temp_dir().join("mf4_export_ndjson");
+    std::fs::create_dir_all(&dir)?;
+    let input = dir.join("drive.mf4");
+    write_recording(&input)?;
+    let input = input.to_str().unwrap();
+    let output_dir = dir.to_str().unwrap();
+
+    let files = export_ndjson(input, output_dir, &NdjsonOptions::new())?;
+    assert_eq!(files.len(), 1);
+    assert!(files[0].ends_with("drive_cg0.ndjson"));
+    let text = std::fs::read_to_string(&files[0])?;
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], r#"{"t":0.0,"Speed":1000,"Gear":-1}"#);
+    let record: serde_json::Value = serde_json::from_str(lines[3]).unwrap();
+    assert_eq!(record["t"], 1.5);
+    assert_eq!(record["Speed"], 1300);
+
+    let options = NdjsonOptions::new()
+        .with_channels(&["Gear"])
+        .with_time_window(0.5, 1.0);
+    let files = export_ndjson(input, output_dir, &options)?;
+    let text = std::fs::read_to_string(&files[0])?;
+    assert_eq!(text, "{\"t\":0.5,\"Gear\":0}\n{\"t\":1.0,\"Gear\":1}\n");
+
+    let options = NdjsonOptions::new().with_channels(&["Missing"]);
+    assert!(export_ndjson(input, output_dir, &options)?.is_empty());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// Write a GNSS group (time, latitude, longitude, height) started at
+/// 2024-05-17 09:30:00 UTC, with an invalid position in the third record.
+fn write_gnss(path: &Path) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_start_time_ns(1_715_938_200_000_000_000)?;
+    let channels = [
+        TestChannel::time(|i| DecodedValue::Float(i as f64 * 0.25)),
+        TestChannel::new("GPS_Lat", DataType::FloatLE, 64, |i| {
+            DecodedValue::Float([48.5, 48.25, 120.0, 48.75][i as usize])
+        })
+        .with_unit("deg"),
+        TestChannel::new("GPS_Lon", DataType::FloatLE, 64, |_| {
+            DecodedValue::Float(9.5)
+        })
+        .with_unit("deg"),
+        TestChannel::new("Height", DataType::FloatLE, 64, |i| {
+            DecodedValue::Float(300.0 + i as f64)
+        })
+        .with_unit("m"),
+    ];
+    write_group(&mut writer, Some("GNSS"), &channels, 4)?;
+    writer.finalize()
+}
+
+#[test]
+fn export_gps_tracks() -> Result<()> {
+    let dir = std::env::temp_dir().join("mf4_export_gps");
+    std::fs::create_dir_all(&dir)?;
+    let input = dir.join("gnss.mf4");
+    write_gnss(&input)?;
+    let gpx = dir.join("gnss.gpx");
+    let kml = dir.join("gnss.kml");
+    let input = input.to_str().unwrap();
+
+    // Latitude and longitude detected by name; no altitude channel matches
+    let mdf = MDF::from_file(input)?;
+    let tracks = gps_tracks(&mdf, &GpsOptions::new())?;
+    assert_eq!(tracks.len(), 1);
+    assert_eq!(tracks[0].name.as_deref(), Some("GNSS"));
+    assert_eq!(tracks[0].points.len(), 3);
+    assert_eq!(tracks[0].points[2].latitude, 48.75);
+    assert_eq!(tracks[0].points[2].altitude, None);
+
+    let options = GpsOptions::new()
+        .with_channels("GPS_Lat", "GPS_Lon")
+        .with_altitude("Height");
+    let points = export_gps(input, gpx.to_str().unwrap(), GpsFormat::Gpx, &options)?;
+    assert_eq!(points, 3);
+    let text = std::fs::read_to_string(&gpx)?;
+    assert!(text.contains("<name>GNSS</name>"));
+    assert!(text.contains(
+        "<trkpt lat=\"48.25\" lon=\"9.5\"><ele>301</ele><time>2024-05-17T09:30:00.250Z</time></trkpt>"
+    ));
+    assert_eq!(text.matches("<trkpt").count(), 3);
+
+    export_gps(input, kml.to_str().unwrap(), GpsFormat::Kml, &options)?;
+    let text = std::fs::read_to_string(&kml)?;
+    assert!(text.contains("<when>2024-05-17T09:30:00.750Z</when>"));
+    assert!(text.contains("<gx:coord>9.5 48.75 303</gx:coord>"));
+
+    let options = GpsOptions::new().with_channels("Lat", "Lon");
+    assert!(export_gps(input, gpx.to_str().unwrap(), GpsFormat::Gpx, &options).is_err());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -1,8 +1,24 @@
+use mdf4_rs::index::ByteRangeReader;
 use mdf4_rs::{
     BufferedRangeReader, DataType, DecodedValue, FileRangeReader, MDF, MdfIndex, MdfWriter, Result,
 };
 use std::fs;
 
+/// Counts the bytes requested from the wrapped reader
+struct CountingReader {
+    inner: FileRangeReader,
+    bytes_read: u64,
+}
+
+impl ByteRangeReader for CountingReader {
+    type Error = mdf4_rs::Error;
+
+    fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
+        self.bytes_read += length;
+        self.inner.read_range(offset, length)
+    }
+}
+
 #[test]
 fn test_index_roundtrip() -> Result<()> {
     let mdf_path = std::env::temp_dir().join("index_test.mf4");
@@ -637,3 +653,1571 @@ fn test_buffered_reader() -> Result<()> {
     let _ = fs::remove_file(mdf_path);
     Ok(())
 }
+
+#[test]
+fn test_binary_index_roundtrip() -> Result<()> {
+    use mdf4_rs::blocks::{BlockHeader, ConversionBlock, ConversionType};
+    use std::collections::BTreeMap;
+
+    let mdf_path = std::env::temp_dir().join("binary_index_test.mf4");
+    let json_path = std::env::temp_dir().join("binary_index_test.json");
+    let index_path = std::env::temp_dir().join("binary_index_test.idx");
+
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Gear".to_string());
+        ch.bit_count = 8;
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..500u64 {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64 * 0.01),
+                DecodedValue::UnsignedInteger(i % 6),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let mut index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+
+    // Attach a value-to-text conversion with nested and default conversions
+    let linear = ConversionBlock {
+        header: BlockHeader {
+            id: "##CC".to_string(),
+            reserved: 0,
+            length: 96,
+            link_count: 4,
+        },
+        name_addr: None,
+        unit_addr: Some(0x1234),
+        comment_addr: None,
+        inverse_addr: None,
+        refs: vec![],
+        conversion_type: ConversionType::Linear,
+        precision: 3,
+        flags: 2,
+        ref_count: 0,
+        value_count: 2,
+        phys_range_min: Some(-1.5),
+        phys_range_max: Some(f64::MAX),
+        values: vec![0.5, 2.0],
+        formula: None,
+        resolved_texts: None,
+        resolved_conversions: None,
+        default_conversion: None,
+    };
+    let mut texts = BTreeMap::new();
+    texts.insert(0, "Neutral".to_string());
+    texts.insert(1, "Fünfter".to_string());
+    let mut nested = BTreeMap::new();
+    nested.insert(2, Box::new(linear.clone()));
+    let value_to_text = ConversionBlock {
+        conversion_type: ConversionType::ValueToText,
+        refs: vec![100, 200, 300],
+        values: vec![0.0, 5.0],
+        formula: Some("X".to_string()),
+        resolved_texts: Some(texts),
+        resolved_conversions: Some(nested),
+        default_conversion: Some(Box::new(linear.clone())),
+        ..linear
+    };
+    index.channel_groups[0].channels[1].conversion = Some(value_to_text);
+    index.channel_groups[0].channels[1].data_type = DataType::Unknown(());
+
+    index.save_to_file(json_path.to_str().unwrap())?;
+    index.save_to_file_binary(index_path.to_str().unwrap())?;
+    let json_size = fs::metadata(&json_path)?.len();
+    let binary_size = fs::metadata(&index_path)?.len();
+    assert!(
+        binary_size * 4 < json_size,
+        "binary index ({} bytes) should be much smaller than JSON ({} bytes)",
+        binary_size,
+        json_size
+    );
+
+    let loaded = MdfIndex::load_from_file_binary(index_path.to_str().unwrap())?;
+    assert_eq!(format!("{:?}", loaded), format!("{:?}", index));
+
+    // Truncated data, a foreign file and a newer version are rejected
+    let bytes = index.to_binary_bytes();
+    assert!(MdfIndex::from_binary_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(MdfIndex::from_binary_bytes(b"{\"file_size\": 0}").is_err());
+    let mut newer = bytes.clone();
+    newer[8..10].copy_from_slice(&(mdf4_rs::index::BINARY_INDEX_VERSION + 1).to_le_bytes());
+    assert!(MdfIndex::from_binary_bytes(&newer).is_err());
+
+    // The loaded index reads the same data
+    let mut reader = FileRangeReader::new(mdf_path.to_str().unwrap())?;
+    let times = loaded.read_channel_values(0, 0, &mut reader)?;
+    assert_eq!(times.len(), 500);
+    assert_eq!(times[100], Some(DecodedValue::Float(1.0)));
+
+    let _ = fs::remove_file(mdf_path);
+    let _ = fs::remove_file(json_path);
+    let _ = fs::remove_file(index_path);
+    Ok(())
+}
+
+#[test]
+fn test_time_range_read() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("time_range_test.mf4");
+
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 32;
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..100_000u64 {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64 * 0.5),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let mut reader = CountingReader {
+        inner: FileRangeReader::new(mdf_path.to_str().unwrap())?,
+        bytes_read: 0,
+    };
+
+    // Inclusive bounds between samples and on samples
+    let values = index.read_channel_values_in_time_range(0, 1, 100.25, 110.0, &mut reader)?;
+    let expected: Vec<_> = (201..=220)
+        .map(|i| Some(DecodedValue::UnsignedInteger(i)))
+        .collect();
+    assert_eq!(values, expected);
+    assert!(
+        reader.bytes_read < 2_000,
+        "read {} bytes for a 20 record window",
+        reader.bytes_read
+    );
+
+    assert_eq!(
+        index.find_record_range_for_time(0, -10.0, 1.0, &mut reader)?,
+        0..3
+    );
+    assert_eq!(
+        index.find_record_range_for_time(0, 49_999.0, 1e9, &mut reader)?,
+        99_998..100_000
+    );
+    assert_eq!(
+        index.find_record_range_for_time(0, 1e9, 2e9, &mut reader)?,
+        100_000..100_000
+    );
+    assert!(
+        index
+            .read_channel_values_in_time_range(0, 1, 20.0, 10.0, &mut reader)?
+            .is_empty()
+    );
+
+    // The master channel itself
+    let times = index.read_channel_values_in_time_range(0, 0, 0.0, 1.0, &mut reader)?;
+    assert_eq!(
+        times,
+        vec![
+            Some(DecodedValue::Float(0.0)),
+            Some(DecodedValue::Float(0.5)),
+            Some(DecodedValue::Float(1.0)),
+        ]
+    );
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_read_multiple_channels() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("multi_channel_read_test.mf4");
+
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    let speed_id = writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Speed".to_string());
+        ch.bit_count = 16;
+    })?;
+    writer.add_channel(&cg_id, Some(&speed_id), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Offset".to_string());
+        ch.bit_count = 32;
+    })?;
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for i in 0..1000u64 {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64 * 0.1),
+                DecodedValue::UnsignedInteger(i * 2),
+                DecodedValue::SignedInteger(-(i as i64)),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg_id)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let mut reader = CountingReader {
+        inner: FileRangeReader::new(mdf_path.to_str().unwrap())?,
+        bytes_read: 0,
+    };
+
+    let columns = index.read_channels(0, &[2, 0, 1], &mut reader)?;
+    let batch_bytes = reader.bytes_read;
+    assert_eq!(columns.len(), 3);
+    assert_eq!(columns[0], index.read_channel_values(0, 2, &mut reader)?);
+    assert_eq!(columns[1], index.read_channel_values(0, 0, &mut reader)?);
+    assert_eq!(columns[2], index.read_channel_values(0, 1, &mut reader)?);
+    assert_eq!(columns[2][10], Some(DecodedValue::UnsignedInteger(20)));
+
+    // The records were fetched once instead of once per channel
+    assert_eq!(reader.bytes_read, batch_bytes * 4);
+
+    assert!(index.read_channels(0, &[0, 3], &mut reader).is_err());
+    assert!(index.read_channels(0, &[], &mut reader)?.is_empty());
+
+    // Time windows select the same records for every channel
+    let window = index.read_channels_in_time_range(0, &[1, 2], 10.0, 20.0, &mut reader)?;
+    assert_eq!(
+        window[0],
+        index.read_channel_values_in_time_range(0, 1, 10.0, 20.0, &mut reader)?
+    );
+    assert_eq!(
+        window[1],
+        index.read_channel_values_in_time_range(0, 2, 10.0, 20.0, &mut reader)?
+    );
+    assert_eq!(
+        window[0].first(),
+        Some(&Some(DecodedValue::UnsignedInteger(200)))
+    );
+    let empty = index.read_channels_in_time_range(0, &[0, 1], 200.0, 300.0, &mut reader)?;
+    assert_eq!(empty, vec![Vec::new(), Vec::new()]);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_read_block_channels() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("block_channel_read_test.mf4");
+
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 32;
+    })?;
+    // Three data blocks of 10, 20 and 30 records
+    let mut counter = 0u64;
+    for block in 1..=3u64 {
+        writer.start_data_block_for_cg(&cg_id, 0)?;
+        for _ in 0..block * 10 {
+            writer.write_record(
+                &cg_id,
+                &[
+                    DecodedValue::Float(counter as f64 * 0.01),
+                    DecodedValue::UnsignedInteger(counter),
+                ],
+            )?;
+            counter += 1;
+        }
+        writer.finish_data_block(&cg_id)?;
+    }
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(mdf_path.to_str().unwrap())?;
+    let mut reader = FileRangeReader::new(mdf_path.to_str().unwrap())?;
+    let group = &index.channel_groups[0];
+    assert_eq!(group.data_blocks.len(), 3);
+
+    let mut counters = Vec::new();
+    for block_index in 0..group.data_blocks.len() {
+        let columns = index.read_block_channels(0, block_index, &[1, 0], &mut reader)?;
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].len(), (block_index + 1) * 10);
+        assert_eq!(columns[0].len(), columns[1].len());
+        counters.extend(columns[0].iter().cloned());
+    }
+    assert_eq!(counters, index.read_channel_values(0, 1, &mut reader)?);
+
+    assert!(index.read_block_channels(0, 3, &[0], &mut reader).is_err());
+    assert!(index.read_block_channels(0, 0, &[2], &mut reader).is_err());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_vlsd_channel_index() -> Result<()> {
+    use mdf4_rs::blocks::DataListBlock;
+
+    let mdf_path = std::env::temp_dir().join("vlsd_index_test.mf4");
+    let messages = ["engine start", "idle", "P0301 misfire cylinder 1", "ok"];
+
+    let mut writer = MdfWriter::new(mdf_path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg_id = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg_id, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    let text_id = writer.add_channel(&cg_id, Some(&time_id), |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.channel_type = 1;
+        ch.name = Some("Message".to_string());
+        ch.bit_count = 64;
+    })?;
+
+    // Records hold the offset of their value in the signal data stream
+    let mut stream = Vec::new();
+    writer.start_data_block_for_cg(&cg_id, 0)?;
+    for (i, message) in messages.iter().enumerate() {
+        writer.write_record(
+            &cg_id,
+            &[
+                DecodedValue::Float(i as f64),
+                DecodedValue::ByteArray((stream.len() as u64).to_le_bytes().to_vec()),
+            ],
+        )?;
+        stream.extend_from_slice(&(message.len() as u32).to_le_bytes());
+        stream.extend_from_slice(message.as_bytes());
+    }
+    writer.finish_data_block(&cg_id)?;
+
+    // Split the stream over two SD blocks referenced by a DL block
+    let split = 4 + messages[0].len() + 4;
+    let mut sd_addrs = Vec::new();
+    for part in [&stream[..split], &stream[split..]] {
+        let mut block = b"##SD".to_vec();
+        block.extend_from_slice(&[0; 4]);
+        block.extend_from_slice(&(24 + part.len() as u64).to_le_bytes());
+        block.extend_from_slice(&0u64.to_le_bytes());
+        block.extend_from_slice(part);
+        block.resize(block.len().next_multiple_of(8), 0);
+        sd_addrs.push(writer.write_block(&block)?);
+    }
+    let dl = DataListBlock::new_with_offsets(sd_addrs.clone(), vec![0, split as u64]);
+    let dl_addr = writer.write_block(&dl.to_bytes()?)?;
+    let cn_addr = writer.get_block_position(&text_id).unwrap();
+    writer.update_link(cn_addr + 64, dl_addr)?;
+    writer.finalize()?;
+
+    let expected: Vec<_> = messages
+        .iter()
+        .map(|m| Some(DecodedValue::String(m.to_string())))
+        .collect();
+
+    for index in [
+        MdfIndex::from_file(mdf_path.to_str().unwrap())?,
+        MdfIndex::from_file_streaming(mdf_path.to_str().unwrap())?,
+    ] {
+        let channel = &index.channel_groups[0].channels[1];
+        assert_eq!(channel.vlsd_blocks.len(), 2);
+        assert_eq!(channel.vlsd_blocks[0].file_offset, sd_addrs[0]);
+        assert_eq!(channel.vlsd_blocks[1].file_offset, sd_addrs[1]);
+        assert_eq!(channel.vlsd_blocks[1].data_offset, split as u64);
+
+        let ranges = index.get_channel_byte_ranges(0, 1)?;
+        assert_eq!(ranges[0], (sd_addrs[0] + 24, split as u64));
+
+        // Blocks survive both cache formats
+        let loaded = MdfIndex::from_binary_bytes(&index.to_binary_bytes())?;
+        assert_eq!(loaded.channel_groups[0].channels[1].vlsd_blocks.len(), 2);
+
+        let mut reader = CountingReader {
+            inner: FileRangeReader::new(mdf_path.to_str().unwrap())?,
+            bytes_read: 0,
+        };
+        let values = loaded.read_channel_values_by_name("Message", &mut reader)?;
+        assert_eq!(values, expected);
+        // Only the signal data is read, no block headers
+        assert_eq!(reader.bytes_read, stream.len() as u64);
+    }
+
+    // Indexes without recorded blocks still locate them when reading
+    let mut index = MdfIndex::from_file_streaming(mdf_path.to_str().unwrap())?;
+    index.channel_groups[0].channels[1].vlsd_blocks.clear();
+    let mut reader = FileRangeReader::new(mdf_path.to_str().unwrap())?;
+    assert_eq!(index.read_channel_values(0, 1, &mut reader)?, expected);
+
+    // Streaming joins values split over block boundaries
+    let index = MdfIndex::from_file_streaming(mdf_path.to_str().unwrap())?;
+    let streamed = index
+        .iter_channel_values(0, 1, &mut reader)?
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(streamed, expected);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_index_refresh_growing_file() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_refresh_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+
+    let mut next = 0u64;
+    let mut write = |writer: &mut MdfWriter<_>, n: u64| -> Result<()> {
+        for _ in 0..n {
+            writer.write_record_u64(&cg, &[next])?;
+            next += 1;
+        }
+        Ok(())
+    };
+
+    write(&mut writer, 1000)?;
+    writer.flush_now()?;
+    let file_len = || fs::metadata(&mdf_path).map(|m| m.len());
+    let mut index = MdfIndex::from_reader(&mut FileRangeReader::new(path)?, file_len()?)?;
+    assert_eq!(index.channel_groups[0].record_count, 1000);
+
+    // Nothing changed
+    assert_eq!(index.refresh(&mut FileRangeReader::new(path)?)?, 0);
+
+    // Grow past several DT blocks and add a channel group
+    write(&mut writer, 600_000)?;
+    let cg2 = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg2, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Status".to_string());
+        ch.bit_count = 8;
+    })?;
+    writer.start_data_block_for_cg(&cg2, 0)?;
+    for i in 0..10 {
+        writer.write_record_u64(&cg2, &[i])?;
+    }
+    writer.flush_now()?;
+
+    let mut reader = CountingReader {
+        inner: FileRangeReader::new(path)?,
+        bytes_read: 0,
+    };
+    assert_eq!(index.refresh(&mut reader)?, 600_010);
+    assert!(
+        reader.bytes_read < 4096,
+        "refresh read {} bytes",
+        reader.bytes_read
+    );
+
+    let rebuilt = MdfIndex::from_reader(&mut FileRangeReader::new(path)?, file_len()?)?;
+    assert_eq!(index.channel_groups.len(), 2);
+    for (refreshed, expected) in index.channel_groups.iter().zip(&rebuilt.channel_groups) {
+        assert_eq!(refreshed.record_count, expected.record_count);
+        assert_eq!(
+            format!("{:?}", refreshed.data_blocks),
+            format!("{:?}", expected.data_blocks)
+        );
+    }
+    assert!(index.file_size > 600_000 * 8);
+
+    let mut reader = FileRangeReader::new(path)?;
+    let values = index.read_channel_values(0, 0, &mut reader)?;
+    assert_eq!(values.len(), 601_000);
+    assert_eq!(
+        values[600_999],
+        Some(DecodedValue::UnsignedInteger(600_999))
+    );
+    let status = index.read_channel_values_by_name("Status", &mut reader)?;
+    assert_eq!(status.len(), 10);
+
+    writer.finish_data_block(&cg)?;
+    writer.finish_data_block(&cg2)?;
+    writer.finalize()?;
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_index_refresh_updates_statistics() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_refresh_stats_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Value".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10u64 {
+        writer.write_record_u64(&cg, &[i])?;
+    }
+    writer.flush_now()?;
+
+    let file_len = fs::metadata(&mdf_path)?.len();
+    let mut index = MdfIndex::from_reader(&mut FileRangeReader::new(path)?, file_len)?;
+    index.compute_statistics(&mut FileRangeReader::new(path)?)?;
+    assert!(index.blocks_in_value_range(0, 0, 500.0, 2000.0)?.is_empty());
+
+    // The last data block grows in place
+    for _ in 0..5 {
+        writer.write_record_u64(&cg, &[1000])?;
+    }
+    writer.flush_now()?;
+    assert_eq!(index.refresh(&mut FileRangeReader::new(path)?)?, 5);
+
+    let group = &index.channel_groups[0];
+    let stats = group.channels[0].stats.expect("value statistics");
+    assert_eq!((stats.min, stats.max, stats.count), (0.0, 1000.0, 15));
+    assert_eq!(group.channels[0].block_stats.len(), group.data_blocks.len());
+    let blocks = index.blocks_in_value_range(0, 0, 500.0, 2000.0)?;
+    assert_eq!(blocks, vec![group.data_blocks.len() - 1]);
+
+    let mut reader = FileRangeReader::new(path)?;
+    let values = index.read_channel_values_in_blocks(0, 0, &blocks, &mut reader)?;
+    assert_eq!(
+        values.last(),
+        Some(&Some(DecodedValue::UnsignedInteger(1000)))
+    );
+
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_iter_channel_values() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_iter_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    // More than one 4 MB data block
+    for i in 0..300_000u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.001),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    assert!(index.channel_groups[0].data_blocks.len() > 1);
+
+    let mut reader = CountingReader {
+        inner: FileRangeReader::new(path)?,
+        bytes_read: 0,
+    };
+    let mut count = 0u64;
+    for value in index.iter_channel_values(0, 1, &mut reader)? {
+        assert_eq!(value?, Some(DecodedValue::UnsignedInteger(count)));
+        count += 1;
+    }
+    assert_eq!(count, 300_000);
+    let streamed_bytes = reader.bytes_read;
+
+    // Stops early without reading the remaining blocks
+    reader.bytes_read = 0;
+    let first: Vec<_> = index
+        .iter_channel_values(0, 0, &mut reader)?
+        .take(3)
+        .collect::<Result<_>>()?;
+    assert_eq!(first[2], Some(DecodedValue::Float(0.002)));
+    assert!(reader.bytes_read < streamed_bytes);
+
+    assert!(index.iter_channel_values(0, 2, &mut reader).is_err());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_channel_statistics() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_stats_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    // More than one 4 MB data block
+    for i in 0..300_000u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.001),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    // Without statistics no block can be skipped
+    let plain = MdfIndex::from_file_streaming(path)?;
+    assert!(plain.channel_groups[0].channels[1].stats.is_none());
+    let block_count = plain.channel_groups[0].data_blocks.len();
+    assert!(block_count > 1);
+    assert_eq!(
+        plain
+            .blocks_in_value_range(0, 1, 299_990.0, 300_000.0)?
+            .len(),
+        block_count
+    );
+
+    let index = MdfIndex::from_file_streaming_with_stats(path)?;
+    let counter = &index.channel_groups[0].channels[1];
+    let stats = counter.stats.expect("counter statistics");
+    assert_eq!(
+        (stats.min, stats.max, stats.count),
+        (0.0, 299_999.0, 300_000)
+    );
+    assert_eq!(counter.block_stats.len(), block_count);
+    let time_stats = index.channel_groups[0].channels[0].stats.unwrap();
+    assert!((time_stats.max - 299.999).abs() < 1e-9);
+
+    // Only the last block can hold the largest counter values
+    let blocks = index.blocks_in_value_range(0, 1, 299_990.0, 300_000.0)?;
+    assert_eq!(blocks, vec![block_count - 1]);
+    assert!(index.blocks_in_value_range(0, 1, -10.0, -1.0)?.is_empty());
+
+    let mut reader = FileRangeReader::new(path)?;
+    let values = index.read_channel_values_in_blocks(0, 1, &blocks, &mut reader)?;
+    assert_eq!(
+        values.last(),
+        Some(&Some(DecodedValue::UnsignedInteger(299_999)))
+    );
+    assert!(values.len() < 300_000);
+
+    // Statistics are persisted
+    let restored = MdfIndex::from_binary_bytes(&index.to_binary_bytes())?;
+    assert_eq!(restored.channel_groups[0].channels[1].stats, Some(stats));
+    assert_eq!(
+        restored.channel_groups[0].channels[1].block_stats,
+        counter.block_stats
+    );
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_typed_column_reads() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_typed_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    let temp_id = writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Temperature".to_string());
+        ch.bit_count = 16;
+    })?;
+    writer.add_linear_conversion(&temp_id, -40.0, 0.5)?;
+    writer.add_channel(&cg, Some(&temp_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Status".to_string());
+        ch.bit_count = 8;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..1000i64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.01),
+                DecodedValue::SignedInteger(i - 500),
+                DecodedValue::UnsignedInteger((i % 200) as u64),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    let mut reader = FileRangeReader::new(path)?;
+
+    // Same physical values as the DecodedValue based read
+    let (temperature, valid) = index.read_channel_f64(0, 1, &mut reader)?;
+    let decoded = index.read_channel_values(0, 1, &mut reader)?;
+    assert_eq!(temperature.len(), 1000);
+    assert_eq!(valid.len(), 1000);
+    assert!(valid.all());
+    for (value, expected) in temperature.iter().zip(&decoded) {
+        assert_eq!(Some(*value), expected.as_ref().and_then(|v| v.as_f64()));
+    }
+    assert_eq!(temperature[0], -40.0 + 0.5 * -500.0);
+
+    let (raw, valid) = index.read_channel_i64(0, 2, &mut reader)?;
+    assert_eq!(raw[199], 199);
+    assert_eq!(valid.get(999), Some(true));
+    assert_eq!(valid.get(1000), None);
+    let (status, _) = index.read_channel_u64(0, 2, &mut reader)?;
+    assert_eq!(status[250], 50);
+
+    let (time, _) = index.read_channel_f64(0, 0, &mut reader)?;
+    assert_eq!(time[100], 1.0);
+
+    // Integer reads need integer values without a conversion
+    assert!(index.read_channel_i64(0, 0, &mut reader).is_err());
+    assert!(index.read_channel_i64(0, 1, &mut reader).is_err());
+    assert!(index.read_channel_u64(0, 1, &mut reader).is_err());
+    assert!(index.read_channel_f64(0, 3, &mut reader).is_err());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_channel_search() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_search_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let mut groups = Vec::new();
+    for names in [
+        &[
+            ("Time", "s"),
+            ("Wheel1_Speed", "km/h"),
+            ("Wheel2_Speed", "km/h"),
+        ][..],
+        &[("Time", "s"), ("Engine_Temp", "°C"), ("Oil_Temp", "°C")][..],
+    ] {
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let mut prev: Option<String> = None;
+        for (name, unit) in names {
+            let id = writer.add_channel(&cg, prev.as_deref(), |ch| {
+                ch.data_type = DataType::FloatLE;
+                ch.name = Some(name.to_string());
+                ch.bit_count = 64;
+            })?;
+            writer.set_channel_unit(&id, unit)?;
+            if prev.is_none() {
+                writer.set_time_channel(&id)?;
+            }
+            prev = Some(id);
+        }
+        groups.push(cg);
+    }
+    for cg in &groups {
+        writer.start_data_block_for_cg(cg, 0)?;
+        writer.write_record(cg, &vec![DecodedValue::Float(0.0); 3])?;
+        writer.finish_data_block(cg)?;
+    }
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(path)?;
+
+    assert_eq!(
+        index.find_channels_matching("Wheel?_Speed"),
+        vec![(0, 1), (0, 2)]
+    );
+    assert_eq!(index.find_channels_matching("*_Temp"), vec![(1, 1), (1, 2)]);
+    assert_eq!(index.find_channels_matching("Wheel[!1]*"), vec![(0, 2)]);
+    assert_eq!(index.find_channels_matching("[A-Z]*e*_T*"), vec![(1, 1)]);
+    assert_eq!(index.find_channels_matching("Time").len(), 2);
+    assert_eq!(index.find_channels_matching("*").len(), 6);
+    assert!(index.find_channels_matching("time").is_empty());
+    assert!(index.find_channels_matching("Wheel?").is_empty());
+
+    assert_eq!(index.find_channels_by_unit("°C"), vec![(1, 1), (1, 2)]);
+    assert_eq!(index.find_channels_by_unit("km/h").len(), 2);
+    assert!(index.find_channels_by_unit("rpm").is_empty());
+
+    let speeds_in_group_0 = index.find_channels_where(|ch| {
+        ch.unit.as_deref() == Some("km/h") && ch.name.as_deref() != Some("Wheel1_Speed")
+    });
+    assert_eq!(speeds_in_group_0, vec![(0, 2)]);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_index_fingerprint_validation() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_fingerprint_test.mf4");
+    let other_path = std::env::temp_dir().join("index_fingerprint_other.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    for (file, count) in [(&mdf_path, 10_000u64), (&other_path, 10_001)] {
+        let mut writer = MdfWriter::new(file.to_str().unwrap())?;
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Counter".to_string());
+            ch.bit_count = 64;
+        })?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..count {
+            writer.write_record_u64(&cg, &[i])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()?;
+    }
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    assert!(index.fingerprint.is_some());
+    assert_eq!(MdfIndex::from_file(path)?.fingerprint, index.fingerprint);
+    index.validate_against(&mut FileRangeReader::new(path)?)?;
+
+    let restored = MdfIndex::from_binary_bytes(&index.to_binary_bytes())?;
+    assert_eq!(restored.fingerprint, index.fingerprint);
+    restored.validate_against(&mut FileRangeReader::new(path)?)?;
+
+    let is_mismatch = |result: Result<()>| matches!(result, Err(mdf4_rs::Error::IndexMismatch(_)));
+
+    // A different file
+    let other = other_path.to_str().unwrap();
+    assert!(is_mismatch(
+        index.validate_against(&mut FileRangeReader::new(other)?)
+    ));
+
+    // Modified content, same size
+    let original = fs::read(&mdf_path)?;
+    let mut modified = original.clone();
+    let last = modified.len() - 1;
+    modified[last] ^= 0xFF;
+    fs::write(&mdf_path, &modified)?;
+    assert!(is_mismatch(
+        index.validate_against(&mut FileRangeReader::new(path)?)
+    ));
+
+    // Grown and truncated files
+    let mut grown = original.clone();
+    grown.extend_from_slice(&[0; 16]);
+    fs::write(&mdf_path, &grown)?;
+    assert!(is_mismatch(
+        index.validate_against(&mut FileRangeReader::new(path)?)
+    ));
+    fs::write(&mdf_path, &original[..original.len() - 16])?;
+    assert!(is_mismatch(
+        index.validate_against(&mut FileRangeReader::new(path)?)
+    ));
+
+    // Indexes without a fingerprint are only checked for their size
+    fs::write(&mdf_path, &modified)?;
+    let mut legacy = index.clone();
+    legacy.fingerprint = None;
+    legacy.validate_against(&mut FileRangeReader::new(path)?)?;
+
+    // Refreshing clears the fingerprint until it is updated
+    fs::write(&mdf_path, &original)?;
+    let mut refreshed = index.clone();
+    refreshed.refresh(&mut FileRangeReader::new(path)?)?;
+    assert!(refreshed.fingerprint.is_none());
+    refreshed.update_fingerprint(&mut FileRangeReader::new(path)?, original.len() as u64)?;
+    assert_eq!(refreshed.fingerprint, index.fingerprint);
+    refreshed.validate_against(&mut FileRangeReader::new(path)?)?;
+
+    let _ = fs::remove_file(mdf_path);
+    let _ = fs::remove_file(other_path);
+    Ok(())
+}
+
+#[test]
+fn test_unsorted_group_index() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_unsorted_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    // Two channel groups sharing one data group, told apart by a 1-byte record ID
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let dg = writer.add_data_group(None)?;
+    let fast = writer.add_channel_group_with_dg(&dg, None, |cg| {
+        cg.record_id = 1;
+        cg.record_size = 8;
+        cg.cycle_count = 30;
+    })?;
+    writer.add_channel(&fast, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Fast".to_string());
+        ch.bit_count = 64;
+    })?;
+    let slow = writer.add_channel_group_with_dg(&dg, Some(&fast), |cg| {
+        cg.record_id = 2;
+        cg.record_size = 2;
+        cg.cycle_count = 10;
+    })?;
+    writer.add_channel(&slow, None, |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.name = Some("Slow".to_string());
+        ch.bit_count = 16;
+    })?;
+
+    let mut data = Vec::new();
+    for i in 0..30u64 {
+        data.push(1);
+        data.extend_from_slice(&i.to_le_bytes());
+        if i % 3 == 2 {
+            data.push(2);
+            data.extend_from_slice(&(-(i as i16)).to_le_bytes());
+        }
+    }
+    let mut block = b"##DT".to_vec();
+    block.extend_from_slice(&[0; 4]);
+    block.extend_from_slice(&(24 + data.len() as u64).to_le_bytes());
+    block.extend_from_slice(&0u64.to_le_bytes());
+    block.extend_from_slice(&data);
+    block.resize(block.len().next_multiple_of(8), 0);
+    let dt_addr = writer.write_block(&block)?;
+    let dg_addr = writer.get_block_position(&dg).unwrap();
+    writer.update_link(dg_addr + 40, dt_addr)?;
+    // dg_rec_id_size is followed by reserved bytes, so a link write sets it
+    writer.update_link(dg_addr + 56, 1)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path)?;
+    let direct_fast = mdf.channel_groups()[0].channels()[0].values()?;
+    let direct_slow = mdf.channel_groups()[1].channels()[0].values()?;
+    assert_eq!(direct_fast.len(), 30);
+    assert_eq!(direct_slow.len(), 10);
+
+    for index in [
+        MdfIndex::from_file(path)?,
+        MdfIndex::from_file_streaming(path)?,
+    ] {
+        let group = &index.channel_groups[1];
+        assert!(group.is_unsorted());
+        assert_eq!(group.record_id, 2);
+        assert_eq!(group.unsorted_records.len(), 2);
+
+        let index = MdfIndex::from_binary_bytes(&index.to_binary_bytes())?;
+        let mut reader = FileRangeReader::new(path)?;
+        assert_eq!(index.read_channel_values(0, 0, &mut reader)?, direct_fast);
+        assert_eq!(index.read_channel_values(1, 0, &mut reader)?, direct_slow);
+        assert_eq!(
+            index.read_channel_values(1, 0, &mut reader)?[3],
+            Some(DecodedValue::SignedInteger(-11))
+        );
+
+        let (slow, valid) = index.read_channel_i64(1, 0, &mut reader)?;
+        assert_eq!((slow.len(), valid.len()), (10, 10));
+        let streamed = index
+            .iter_channel_values(0, 0, &mut reader)?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(streamed, direct_fast);
+
+        // Record positions depend on the other group's records
+        assert!(index.get_channel_byte_ranges(1, 0).is_err());
+    }
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_decimated_read() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_decimated_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    // More than one 4 MB data block
+    for i in 0..300_000u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.001),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    assert!(index.channel_groups[0].data_blocks.len() > 1);
+    let mut reader = CountingReader {
+        inner: FileRangeReader::new(path)?,
+        bytes_read: 0,
+    };
+
+    let all = index.read_channel_values(0, 1, &mut reader)?;
+    let full_bytes = reader.bytes_read;
+
+    for every_nth in [1, 7, 1000, 299_999, 1_000_000] {
+        reader.bytes_read = 0;
+        let decimated = index.read_channel_values_decimated(0, 1, every_nth, &mut reader)?;
+        let expected: Vec<_> = all.iter().step_by(every_nth as usize).cloned().collect();
+        assert_eq!(decimated, expected, "every {every_nth}th value");
+        if every_nth >= 1000 {
+            assert!(reader.bytes_read * 100 < full_bytes);
+        }
+    }
+    assert_eq!(
+        index.read_channel_values_decimated(0, 1, 299_999, &mut reader)?,
+        vec![
+            Some(DecodedValue::UnsignedInteger(0)),
+            Some(DecodedValue::UnsignedInteger(299_999))
+        ]
+    );
+    assert!(
+        index
+            .read_channel_values_decimated(0, 1, 0, &mut reader)
+            .is_err()
+    );
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_downsampled_read() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_downsample_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Signal".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    // A slow sine with a single spike at record 4321
+    for i in 0..10_000u64 {
+        let value = if i == 4321 {
+            100.0
+        } else {
+            (i as f64 / 1000.0).sin()
+        };
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.01),
+                DecodedValue::Float(value),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    let mut reader = FileRangeReader::new(path)?;
+    let (t0, t1) = (f64::NEG_INFINITY, f64::INFINITY);
+
+    let lttb = index.read_channel_lttb(0, 1, t0, t1, 100, &mut reader)?;
+    assert_eq!(lttb.len(), 100);
+    assert_eq!(lttb[0], (0.0, 0.0));
+    assert_eq!(lttb[99].0, 9999.0 * 0.01);
+    assert!(lttb.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(lttb.contains(&(4321.0 * 0.01, 100.0)));
+
+    let min_max = index.read_channel_min_max(0, 1, t0, t1, 50, &mut reader)?;
+    assert!(min_max.len() <= 100);
+    assert!(min_max.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(min_max.contains(&(4321.0 * 0.01, 100.0)));
+    let min = min_max.iter().map(|s| s.1).fold(f64::INFINITY, f64::min);
+    assert!(min < -0.99);
+
+    // Windows with few records are returned completely
+    let window = index.read_channel_lttb(0, 1, 10.0, 10.495, 100, &mut reader)?;
+    assert_eq!(window.len(), 50);
+    assert_eq!(window[0].0, 10.0);
+    let window = index.read_channel_min_max(0, 1, 20.0, 29.995, 100, &mut reader)?;
+    assert_eq!(window.len(), 200);
+    assert!(window.iter().all(|s| s.0 >= 20.0 && s.0 <= 29.995));
+
+    assert!(
+        index
+            .read_channel_lttb(0, 1, t0, t1, 2, &mut reader)
+            .is_err()
+    );
+    assert!(
+        index
+            .read_channel_min_max(0, 1, t0, t1, 0, &mut reader)
+            .is_err()
+    );
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_prefetching_reader() -> Result<()> {
+    use mdf4_rs::index::PrefetchingRangeReader;
+
+    let mdf_path = std::env::temp_dir().join("index_prefetch_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 32;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    // Several 4 MB data blocks
+    for i in 0..800_000u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.001),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    let blocks = index.channel_groups[0].data_blocks.len();
+    assert!(blocks > 1);
+    let mut plain = FileRangeReader::new(path)?;
+    let expected = index.read_channel_values(0, 1, &mut plain)?;
+
+    let plan = index.get_channel_read_plan(0, 1)?;
+    assert_eq!(plan.len(), blocks);
+    let mut reader = PrefetchingRangeReader::from_file(path, 4)?;
+    reader.prefetch(&plan);
+    assert_eq!(index.read_channel_values(0, 1, &mut reader)?, expected);
+    assert_eq!(reader.hits(), blocks as u64);
+    assert_eq!(reader.misses(), 0);
+    assert_eq!(reader.prefetched_ranges(), 0);
+
+    // Without a plan, reads are fetched on demand
+    assert_eq!(index.read_channel_f64(0, 0, &mut reader)?.0.len(), 800_000);
+    assert_eq!(reader.misses(), blocks as u64);
+    assert!(index.get_channel_read_plan(0, 5).is_err());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_merged_index() -> Result<()> {
+    use mdf4_rs::index::MultiFileRangeReader;
+
+    let write_part = |name: &str, first: u64, count: u64, extra_channel: bool| -> Result<String> {
+        let path = std::env::temp_dir().join(name);
+        let path = path.to_str().unwrap().to_string();
+        let mut writer = MdfWriter::new(&path)?;
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let time_id = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some("Time".to_string());
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&time_id)?;
+        let counter_id = writer.add_channel(&cg, Some(&time_id), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Counter".to_string());
+            ch.bit_count = 32;
+        })?;
+        if extra_channel {
+            writer.add_channel(&cg, Some(&counter_id), |ch| {
+                ch.data_type = DataType::UnsignedIntegerLE;
+                ch.name = Some("Extra".to_string());
+                ch.bit_count = 8;
+            })?;
+        }
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in first..first + count {
+            let mut values = vec![
+                DecodedValue::Float(i as f64 * 0.01),
+                DecodedValue::UnsignedInteger(i),
+            ];
+            if extra_channel {
+                values.push(DecodedValue::UnsignedInteger(0));
+            }
+            writer.write_record(&cg, &values)?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()?;
+        Ok(path)
+    };
+
+    let paths = [
+        write_part("index_merge_part1.mf4", 0, 100, false)?,
+        write_part("index_merge_part2.mf4", 100, 50, false)?,
+        write_part("index_merge_part3.mf4", 150, 25, false)?,
+    ];
+    let files: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let mut indices = files
+        .iter()
+        .map(|f| MdfIndex::from_file_streaming_with_stats(f))
+        .collect::<Result<Vec<_>>>()?;
+
+    let merged = MdfIndex::merge(&indices, &[0, 1, 2])?;
+    let group = &merged.channel_groups[0];
+    assert_eq!(group.record_count, 175);
+    assert_eq!(group.data_blocks.len(), 3);
+    let ids: Vec<u16> = group.data_blocks.iter().map(|b| b.file_id()).collect();
+    assert_eq!(ids, vec![0, 1, 2]);
+    let stats = group.channels[1].stats.unwrap();
+    assert_eq!((stats.min, stats.max, stats.count), (0.0, 174.0, 175));
+
+    let mut reader = MultiFileRangeReader::from_files(&files)?;
+    let counter = merged.read_channel_values_by_name("Counter", &mut reader)?;
+    let expected: Vec<_> = (0..175)
+        .map(|i| Some(DecodedValue::UnsignedInteger(i)))
+        .collect();
+    assert_eq!(counter, expected);
+    let (time, _) = merged.read_channel_f64(0, 0, &mut reader)?;
+    assert_eq!(time.len(), 175);
+    assert!((time[120] - 1.2).abs() < 1e-9);
+
+    // Blocks are only read through the reader of their file
+    let mut partial = MultiFileRangeReader::new().with_file(0, FileRangeReader::new(files[0])?);
+    assert!(merged.read_channel_values(0, 1, &mut partial).is_err());
+
+    // Statistics are dropped if one of the files has none
+    indices[1] = MdfIndex::from_file_streaming(files[1])?;
+    let merged = MdfIndex::merge(&indices, &[7, 8, 9])?;
+    assert!(merged.channel_groups[0].channels[1].stats.is_none());
+    assert_eq!(merged.channel_groups[0].data_blocks[2].file_id(), 9);
+
+    assert!(MdfIndex::merge(&indices, &[0, 1]).is_err());
+    assert!(MdfIndex::merge(&indices, &[0, 1, 1]).is_err());
+    let other = write_part("index_merge_other.mf4", 0, 10, true)?;
+    indices.push(MdfIndex::from_file_streaming(&other)?);
+    assert!(MdfIndex::merge(&indices, &[0, 1, 2, 3]).is_err());
+
+    for path in paths.iter().chain([&other]) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_time_bounds() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_time_bounds_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let value_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 64;
+    })?;
+    let time_id = writer.add_channel(&cg, Some(&value_id), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    // Several 4 MB data blocks
+    for i in 0..600_000u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::UnsignedInteger(i),
+                DecodedValue::Float(10.0 + i as f64 * 0.01),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    let group = &index.channel_groups[0];
+    assert_eq!(group.master_channel, Some(1));
+    assert_eq!(group.master().unwrap().name.as_deref(), Some("Time"));
+    assert!(group.data_blocks.len() > 1);
+
+    // Consecutive blocks hold consecutive time spans
+    let bounds: Vec<(f64, f64)> = group
+        .data_blocks
+        .iter()
+        .map(|b| b.time_bounds.unwrap())
+        .collect();
+    for pair in bounds.windows(2) {
+        assert!(pair[0].0 <= pair[0].1);
+        assert!((pair[1].0 - pair[0].1 - 0.01).abs() < 1e-6);
+    }
+    let (first, last) = group.time_bounds().unwrap();
+    assert_eq!(first, 10.0);
+    assert!((last - 6009.99).abs() < 1e-6);
+    assert_eq!(index.time_bounds(), Some((first, last)));
+
+    let from_file = MdfIndex::from_file(path)?;
+    assert_eq!(from_file.time_bounds(), index.time_bounds());
+    let loaded = MdfIndex::from_binary_bytes(&index.to_binary_bytes())?;
+    assert_eq!(loaded.channel_groups[0].master_channel, Some(1));
+    assert_eq!(loaded.time_bounds(), index.time_bounds());
+
+    let mut recomputed = index.clone();
+    recomputed.compute_time_bounds(&mut FileRangeReader::new(path)?)?;
+    assert_eq!(
+        format!("{:?}", recomputed.channel_groups[0].data_blocks),
+        format!("{:?}", group.data_blocks)
+    );
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_attachment_and_event_index() -> Result<()> {
+    use mdf4_rs::blocks::{
+        AttachmentBlock, EventBlock, EventRangeType, EventSyncType, EventType, MetadataBlock,
+        TextBlock,
+    };
+
+    let mdf_path = std::env::temp_dir().join("index_attachments_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 32;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10 {
+        writer.write_record_u64(&cg, &[i])?;
+    }
+    writer.finish_data_block(&cg)?;
+
+    // Two attachments: an embedded DBC file and an external video
+    let dbc = b"VERSION \"1.0\"\n\nBO_ 100 Engine: 8 ECU\n".to_vec();
+    let mut embedded = AttachmentBlock::embedded(&dbc);
+    embedded.filename_addr = writer.write_block(&TextBlock::new("engine.dbc").to_bytes()?)?;
+    embedded.mimetype_addr =
+        writer.write_block(&TextBlock::new("application/x-dbc").to_bytes()?)?;
+    let mut external = AttachmentBlock::external(1_000_000);
+    external.filename_addr = writer.write_block(&TextBlock::new("video.mp4").to_bytes()?)?;
+    external.comment_addr = writer.write_block(&MetadataBlock::new("<ATcomment/>").to_bytes()?)?;
+    let external_addr = writer.write_block(&external.to_bytes()?)?;
+    embedded.next_at_addr = external_addr;
+    let embedded_addr = writer.write_block(&embedded.to_bytes()?)?;
+
+    // A range of two events; the end event refers to the begin event
+    let mut begin = EventBlock::new(EventType::Trigger, EventSyncType::Time, 2.0);
+    begin.range_type = EventRangeType::RangeBegin;
+    begin.name_addr = writer.write_block(&TextBlock::new("Brake").to_bytes()?)?;
+    begin.attachment_addrs = vec![external_addr];
+    begin.attachment_count = 1;
+    let begin_addr = writer.write_block(&begin.to_bytes()?)?;
+    let mut end = EventBlock::marker(5.0);
+    end.range_type = EventRangeType::RangeEnd;
+    end.range_ev_addr = begin_addr;
+    end.parent_ev_addr = begin_addr;
+    let end_addr = writer.write_block(&end.to_bytes()?)?;
+    writer.update_link(begin_addr + 24, end_addr)?;
+
+    let hd_addr = writer.get_block_position("hd_block").unwrap();
+    writer.update_link(hd_addr + 48, embedded_addr)?;
+    writer.update_link(hd_addr + 56, begin_addr)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    assert_eq!(index.attachments.len(), 2);
+    let at = &index.attachments[0];
+    assert_eq!(at.file_name.as_deref(), Some("engine.dbc"));
+    assert_eq!(at.mime_type.as_deref(), Some("application/x-dbc"));
+    assert!(at.embedded && !at.compressed);
+    assert_eq!(at.embedded_size, dbc.len() as u64);
+    let external = &index.attachments[1];
+    assert!(!external.embedded);
+    assert_eq!(external.original_size, 1_000_000);
+    assert_eq!(external.comment.as_deref(), Some("<ATcomment/>"));
+    assert_eq!(external.data_range(), None);
+
+    assert_eq!(index.events.len(), 2);
+    let (begin, end) = (&index.events[0], &index.events[1]);
+    assert_eq!(begin.name.as_deref(), Some("Brake"));
+    assert_eq!(begin.event_type, EventType::Trigger);
+    assert_eq!(begin.attachments, vec![1]);
+    assert_eq!(end.sync_value, 5.0);
+    assert_eq!((end.parent, end.range_begin), (Some(0), Some(0)));
+    assert_eq!(index.events_in_range(0.0, 3.0).len(), 1);
+
+    // Only the embedded data is read
+    let mut reader = CountingReader {
+        inner: FileRangeReader::new(path)?,
+        bytes_read: 0,
+    };
+    assert_eq!(index.read_attachment(0, &mut reader)?, dbc);
+    assert_eq!(reader.bytes_read, dbc.len() as u64);
+    assert!(index.read_attachment(1, &mut reader).is_err());
+    assert!(index.read_attachment(2, &mut reader).is_err());
+
+    let from_file = MdfIndex::from_file(path)?;
+    assert_eq!(from_file.attachments, index.attachments);
+    assert_eq!(from_file.events, index.events);
+    let loaded = MdfIndex::from_binary_bytes(&index.to_binary_bytes())?;
+    assert_eq!(loaded.attachments, index.attachments);
+    assert_eq!(loaded.events, index.events);
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
+
+#[test]
+fn test_buffered_reader_plan() -> Result<()> {
+    let mdf_path = std::env::temp_dir().join("index_buffered_plan_test.mf4");
+    let path = mdf_path.to_str().unwrap();
+
+    let mut writer = MdfWriter::new(path)?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time_id = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time_id)?;
+    writer.add_channel(&cg, Some(&time_id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Counter".to_string());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..300_000u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.001),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file_streaming(path)?;
+    let mut plain = FileRangeReader::new(path)?;
+    let expected = index.read_channel_values(0, 1, &mut plain)?;
+
+    // Strided channel ranges are merged into one planned range per block
+    let mut reader = BufferedRangeReader::new(path)?;
+    let ranges = index.get_channel_byte_ranges(0, 1)?;
+    reader.plan(&ranges);
+    assert!(reader.planned_ranges().len() <= index.channel_groups[0].data_blocks.len());
+    assert_eq!(index.read_channel_values(0, 1, &mut reader)?, expected);
+    assert!(reader.planned_ranges().is_empty());
+
+    // Nearby ranges are read together, in any order
+    let mut reader = BufferedRangeReader::new(path)?.with_max_gap(16);
+    reader.plan(&[(100, 8), (120, 8), (1000, 8)]);
+    assert_eq!(reader.planned_ranges(), &[(100, 28), (1000, 8)]);
+    let ranges = [(1000, 8), (100, 8), (104, 20), (4_000_000, 100)];
+    let merged = reader.read_ranges(&ranges)?;
+    for (&(offset, length), data) in ranges.iter().zip(&merged) {
+        assert_eq!(data, &plain.read_range(offset, length)?);
+    }
+    reader.clear_plan();
+    assert!(reader.planned_ranges().is_empty());
+
+    let _ = fs::remove_file(mdf_path);
+    Ok(())
+}
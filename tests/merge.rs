@@ -1,4 +1,9 @@
-use mdf4_rs::{DataType, DecodedValue, MDF, MdfWriter, Result, merge_files};
+use mdf4_rs::index::FileRangeReader;
+use mdf4_rs::{
+    DataType, DecodedValue, Deduplication, Interpolation, MDF, MdfWriter, MergeInput, MergeMode,
+    MergeOptions, NameConflict, Result, TimeAlignment, concatenate, merge_files,
+    merge_files_with_options, merge_resampled, merge_to_writer,
+};
 
 #[test]
 fn merge_simple_files() -> Result<()> {
@@ -104,3 +109,447 @@ fn merge_different_files() -> Result<()> {
     }
     Ok(())
 }
+
+/// Write a file starting at `start_time_ns` with a `Time` master channel
+/// (0.0, 0.5 s) and a `Value` channel.
+fn write_timed_file(path: &std::path::Path, start_time_ns: u64) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_start_time_ns(start_time_ns)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Value".into());
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..2u64 {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64 * 0.5),
+                DecodedValue::UnsignedInteger(i),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()
+}
+
+fn merged_times(path: &std::path::Path) -> Result<Vec<f64>> {
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let values = mdf.channel_groups()[0].channels()[0].values()?;
+    Ok(values
+        .into_iter()
+        .map(|v| match v {
+            Some(DecodedValue::Float(t)) => t,
+            other => panic!("unexpected time {:?}", other),
+        })
+        .collect())
+}
+
+#[test]
+fn merge_with_time_alignment() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_align_1.mf4");
+    let f2 = dir.join("mf4_merge_align_2.mf4");
+    let out = dir.join("mf4_merge_align_out.mf4");
+    let start = 1_700_000_000_000_000_000;
+    // The second logger started 2 s after the first
+    write_timed_file(&f2, start + 2_000_000_000)?;
+    write_timed_file(&f1, start)?;
+    let inputs = [f2.to_str().unwrap(), f1.to_str().unwrap()];
+    let output = out.to_str().unwrap();
+
+    merge_files_with_options(output, &inputs, &MergeOptions::new())?;
+    assert_eq!(merged_times(&out)?, vec![0.0, 0.5, 0.0, 0.5]);
+
+    let options = MergeOptions::new().with_time_alignment(TimeAlignment::StartTime);
+    merge_files_with_options(output, &inputs, &options)?;
+    assert_eq!(merged_times(&out)?, vec![2.0, 2.5, 0.0, 0.5]);
+    let mdf = MDF::from_file(output)?;
+    assert_eq!(mdf.raw().header.start_time_ns, start);
+
+    let options = MergeOptions::new().with_time_alignment(TimeAlignment::Offsets(vec![10.0, 0.0]));
+    merge_files_with_options(output, &inputs, &options)?;
+    assert_eq!(merged_times(&out)?, vec![10.0, 10.5, 0.0, 0.5]);
+
+    let options = MergeOptions::new().with_time_alignment(TimeAlignment::Offsets(vec![1.0]));
+    assert!(merge_files_with_options(output, &inputs, &options).is_err());
+
+    for p in [&f1, &f2, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+/// A file with microsecond time stamps, like `CanDbcLogger` writes them.
+fn write_microsecond_file(path: &std::path::Path, start_time_ns: u64) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_start_time_ns(start_time_ns)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    writer.set_channel_unit(&time, "us")?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for t in [0u64, 500_000] {
+        writer.write_record(&cg, &[DecodedValue::UnsignedInteger(t)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()
+}
+
+#[test]
+fn merge_aligns_microsecond_time_stamps() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_align_us_1.mf4");
+    let f2 = dir.join("mf4_merge_align_us_2.mf4");
+    let out = dir.join("mf4_merge_align_us_out.mf4");
+    let start = 1_700_000_000_000_000_000;
+    write_microsecond_file(&f1, start)?;
+    write_microsecond_file(&f2, start + 2_000_000_000)?;
+    let inputs = [f1.to_str().unwrap(), f2.to_str().unwrap()];
+    let output = out.to_str().unwrap();
+
+    let options = MergeOptions::new().with_time_alignment(TimeAlignment::StartTime);
+    merge_files_with_options(output, &inputs, &options)?;
+    let mdf = MDF::from_file(output)?;
+    let times = mdf.channel_groups()[0].channels()[0].values()?;
+    let expected = [0u64, 500_000, 2_000_000, 2_500_000];
+    assert_eq!(
+        times,
+        expected
+            .iter()
+            .map(|&t| Some(DecodedValue::UnsignedInteger(t)))
+            .collect::<Vec<_>>()
+    );
+
+    for p in [&f1, &f2, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+fn merged_channels(path: &str) -> Result<Vec<(String, Vec<u64>)>> {
+    let mdf = MDF::from_file(path)?;
+    assert_eq!(mdf.channel_groups().len(), 1);
+    let mut channels = Vec::new();
+    for ch in mdf.channel_groups()[0].channels() {
+        let values = ch
+            .values()?
+            .into_iter()
+            .map(|v| match v {
+                Some(DecodedValue::UnsignedInteger(n)) => n,
+                Some(DecodedValue::Float(t)) => (t * 10.0) as u64,
+                other => panic!("unexpected value {:?}", other),
+            })
+            .collect();
+        channels.push((ch.name()?.unwrap_or_default(), values));
+    }
+    Ok(channels)
+}
+
+#[test]
+fn merge_channels_into_shared_group() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_shared_1.mf4");
+    let f2 = dir.join("mf4_merge_shared_2.mf4");
+    let out = dir.join("mf4_merge_shared_out.mf4");
+    write_timed_file(&f1, 0)?;
+    write_timed_file(&f2, 0)?;
+    let inputs = [f1.to_str().unwrap(), f2.to_str().unwrap()];
+    let output = out.to_str().unwrap();
+
+    let options = MergeOptions::new().with_mode(MergeMode::Channels);
+    merge_files_with_options(output, &inputs, &options)?;
+    assert_eq!(
+        merged_channels(output)?,
+        vec![
+            ("Time".to_string(), vec![0, 5]),
+            ("Value".to_string(), vec![0, 1]),
+            ("Value_1".to_string(), vec![0, 1]),
+        ]
+    );
+
+    let options = options.with_name_conflict(NameConflict::Skip);
+    merge_files_with_options(output, &inputs, &options)?;
+    let names: Vec<_> = merged_channels(output)?.into_iter().map(|c| c.0).collect();
+    assert_eq!(names, vec!["Time", "Value"]);
+
+    let options = options.with_name_conflict(NameConflict::Error);
+    assert!(merge_files_with_options(output, &inputs, &options).is_err());
+
+    for p in [&f1, &f2, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_many_records() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let files = [
+        dir.join("mf4_merge_many_1.mf4"),
+        dir.join("mf4_merge_many_2.mf4"),
+    ];
+    let out = dir.join("mf4_merge_many_out.mf4");
+    // More than one 4 MiB data block per file
+    let count = 600_000u64;
+    for (n, path) in files.iter().enumerate() {
+        let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.name = Some("Counter".into());
+            ch.bit_count = 64;
+        })?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..count {
+            writer.write_record(&cg, &[DecodedValue::UnsignedInteger(n as u64 * count + i)])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()?;
+    }
+
+    merge_files(
+        out.to_str().unwrap(),
+        files[0].to_str().unwrap(),
+        files[1].to_str().unwrap(),
+    )?;
+    let mdf = MDF::from_file(out.to_str().unwrap())?;
+    let values = mdf.channel_groups()[0].channels()[0].values()?;
+    assert_eq!(values.len(), 2 * count as usize);
+    assert!(
+        values
+            .iter()
+            .enumerate()
+            .all(|(i, v)| *v == Some(DecodedValue::UnsignedInteger(i as u64)))
+    );
+
+    for p in files.iter().chain([&out]) {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_rejects_output_among_inputs() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_inplace_1.mf4");
+    let f2 = dir.join("mf4_merge_inplace_2.mf4");
+    write_timed_file(&f1, 0)?;
+    write_timed_file(&f2, 0)?;
+    let before = std::fs::read(&f1)?;
+
+    let inputs = [f1.to_str().unwrap(), f2.to_str().unwrap()];
+    assert!(merge_files_with_options(inputs[0], &inputs, &MergeOptions::new()).is_err());
+    assert!(concatenate(inputs[0], &inputs).is_err());
+    assert_eq!(std::fs::read(&f1)?, before);
+
+    for p in [&f1, &f2] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_readers_to_memory() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_readers_1.mf4");
+    let f2 = dir.join("mf4_merge_readers_2.mf4");
+    let out = dir.join("mf4_merge_readers_out.mf4");
+    write_timed_file(&f1, 0)?;
+    write_timed_file(&f2, 0)?;
+    merge_files(
+        out.to_str().unwrap(),
+        f1.to_str().unwrap(),
+        f2.to_str().unwrap(),
+    )?;
+
+    let size = std::fs::metadata(&f1)?.len();
+    let mut ranges = FileRangeReader::new(f1.to_str().unwrap())?;
+    let mut cursor = std::io::Cursor::new(std::fs::read(&f2)?);
+    let inputs = vec![
+        MergeInput::from_range_reader(&mut ranges, size)?,
+        MergeInput::from_reader(&mut cursor)?,
+    ];
+    let mut writer = MdfWriter::in_memory();
+    merge_to_writer(inputs, &mut writer, &MergeOptions::new())?;
+    assert_eq!(writer.into_inner().into_inner(), std::fs::read(&out)?);
+
+    for p in [&f1, &f2, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+fn write_signal_file(path: &std::path::Path, name: &str, samples: &[(f64, f64)]) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some(name.into());
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for &(t, v) in samples {
+        writer.write_record(&cg, &[DecodedValue::Float(t), DecodedValue::Float(v)])?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()
+}
+
+fn float_channels(path: &str) -> Result<Vec<(String, Vec<f64>)>> {
+    let mdf = MDF::from_file(path)?;
+    let mut channels = Vec::new();
+    for ch in mdf.channel_groups()[0].channels() {
+        let values = ch
+            .values()?
+            .into_iter()
+            .map(|v| match v {
+                Some(DecodedValue::Float(f)) => f,
+                other => panic!("unexpected value {:?}", other),
+            })
+            .collect();
+        channels.push((ch.name()?.unwrap_or_default(), values));
+    }
+    Ok(channels)
+}
+
+#[test]
+fn merge_resampled_onto_raster() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_resample_1.mf4");
+    let f2 = dir.join("mf4_merge_resample_2.mf4");
+    let out = dir.join("mf4_merge_resample_out.mf4");
+    write_signal_file(&f1, "A", &[(0.0, 0.0), (1.0, 10.0), (2.0, 20.0)])?;
+    write_signal_file(&f2, "B", &[(0.5, 5.0), (1.5, 15.0)])?;
+    let inputs = [f1.to_str().unwrap(), f2.to_str().unwrap()];
+    let output = out.to_str().unwrap();
+
+    merge_resampled(output, &inputs, 2.0, Interpolation::Linear)?;
+    assert_eq!(
+        float_channels(output)?,
+        vec![
+            ("Time".to_string(), vec![0.0, 0.5, 1.0, 1.5, 2.0]),
+            ("A".to_string(), vec![0.0, 5.0, 10.0, 15.0, 20.0]),
+            ("B".to_string(), vec![5.0, 5.0, 10.0, 15.0, 15.0]),
+        ]
+    );
+
+    merge_resampled(output, &inputs, 2.0, Interpolation::Hold)?;
+    let channels = float_channels(output)?;
+    assert_eq!(channels[1].1, vec![0.0, 0.0, 10.0, 10.0, 20.0]);
+    assert_eq!(channels[2].1, vec![5.0, 5.0, 5.0, 15.0, 15.0]);
+
+    assert!(merge_resampled(output, &inputs, 0.0, Interpolation::Hold).is_err());
+
+    for p in [&f1, &f2, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn concatenate_logger_parts() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_concat_1.mf4");
+    let f2 = dir.join("mf4_concat_2.mf4");
+    let f3 = dir.join("mf4_concat_3.mf4");
+    let other = dir.join("mf4_concat_other.mf4");
+    let out = dir.join("mf4_concat_out.mf4");
+    write_signal_file(&f1, "A", &[(0.0, 0.0), (1.0, 10.0)])?;
+    write_signal_file(&f2, "A", &[(2.0, 20.0)])?;
+    write_signal_file(&f3, "A", &[(3.0, 30.0), (4.0, 40.0)])?;
+    write_signal_file(&other, "B", &[(5.0, 50.0)])?;
+    let output = out.to_str().unwrap();
+
+    let parts = [
+        f1.to_str().unwrap(),
+        f2.to_str().unwrap(),
+        f3.to_str().unwrap(),
+    ];
+    concatenate(output, &parts)?;
+    assert_eq!(
+        float_channels(output)?,
+        vec![
+            ("Time".to_string(), vec![0.0, 1.0, 2.0, 3.0, 4.0]),
+            ("A".to_string(), vec![0.0, 10.0, 20.0, 30.0, 40.0]),
+        ]
+    );
+    let mdf = MDF::from_file(output)?;
+    assert_eq!(
+        mdf.raw().data_groups[0].channel_groups[0].block.cycle_count,
+        5
+    );
+
+    assert!(concatenate(output, &[parts[0], other.to_str().unwrap()]).is_err());
+
+    for p in [&f1, &f2, &f3, &other, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_with_deduplication() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let f1 = dir.join("mf4_merge_dedupe_1.mf4");
+    let f2 = dir.join("mf4_merge_dedupe_2.mf4");
+    let f3 = dir.join("mf4_merge_dedupe_3.mf4");
+    let out = dir.join("mf4_merge_dedupe_out.mf4");
+    write_signal_file(&f1, "A", &[(0.0, 0.0), (1.0, 10.0), (2.0, 20.0)])?;
+    write_signal_file(&f2, "A", &[(0.0, 0.0), (1.0, 10.0), (2.0, 20.0)])?;
+    // Overlaps the end of the first file
+    write_signal_file(&f3, "A", &[(1.0, 10.0), (2.0, 20.0), (3.0, 30.0)])?;
+    let (p1, p2, p3) = (
+        f1.to_str().unwrap(),
+        f2.to_str().unwrap(),
+        f3.to_str().unwrap(),
+    );
+    let output = out.to_str().unwrap();
+
+    let options = MergeOptions::new().with_deduplication(Deduplication::IdenticalData);
+    merge_files_with_options(output, &[p1, p2, p3], &options)?;
+    assert_eq!(
+        float_channels(output)?[0].1,
+        vec![0.0, 1.0, 2.0, 1.0, 2.0, 3.0]
+    );
+
+    let options = MergeOptions::new().with_deduplication(Deduplication::Timestamps);
+    merge_files_with_options(output, &[p1, p2, p3], &options)?;
+    assert_eq!(float_channels(output)?[1].1, vec![0.0, 10.0, 20.0, 30.0]);
+
+    let options = MergeOptions::new()
+        .with_mode(MergeMode::Channels)
+        .with_deduplication(Deduplication::IdenticalData);
+    merge_files_with_options(output, &[p1, p2], &options)?;
+    let names: Vec<_> = float_channels(output)?.into_iter().map(|c| c.0).collect();
+    assert_eq!(names, vec!["Time", "A"]);
+    merge_files_with_options(output, &[p1, p3], &options)?;
+    let names: Vec<_> = float_channels(output)?.into_iter().map(|c| c.0).collect();
+    assert_eq!(names, vec!["Time", "A", "A_1"]);
+
+    for p in [&f1, &f2, &f3, &out] {
+        std::fs::remove_file(p)?;
+    }
+    Ok(())
+}
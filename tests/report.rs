@@ -0,0 +1,81 @@
+mod common;
+
+use common::{TestChannel, write_group};
+use mdf4_rs::blocks::{AttachmentBlock, EventBlock, TextBlock};
+use mdf4_rs::report::summarize;
+use mdf4_rs::{DataType, DecodedValue, MdfWriter, Result};
+use std::path::Path;
+
+/// Write a recording with an `Engine` group (time, speed in rpm), an
+/// embedded attachment and a marker event.
+fn write_recording(path: &Path) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.set_start_time_ns(1_715_938_200_000_000_000)?;
+    let channels = [
+        TestChannel::time(|i| DecodedValue::Float(1.0 + i as f64 * 0.5)),
+        TestChannel::new("Speed", DataType::UnsignedIntegerLE, 16, |i| {
+            DecodedValue::UnsignedInteger(800 + (i * 7) % 10 * 100)
+        })
+        .with_unit("rpm"),
+    ];
+    write_group(&mut writer, Some("Engine"), &channels, 10)?;
+
+    let dbc = b"VERSION \"\"\n".to_vec();
+    let mut attachment = AttachmentBlock::embedded(&dbc);
+    attachment.filename_addr = writer.write_block(&TextBlock::new("engine.dbc").to_bytes()?)?;
+    let attachment_addr = writer.write_block(&attachment.to_bytes()?)?;
+    let mut event = EventBlock::marker(3.0);
+    event.name_addr = writer.write_block(&TextBlock::new("Lap, 1").to_bytes()?)?;
+    let event_addr = writer.write_block(&event.to_bytes()?)?;
+    let hd_addr = writer.get_block_position("hd_block").unwrap();
+    writer.update_link(hd_addr + 48, attachment_addr)?;
+    writer.update_link(hd_addr + 56, event_addr)?;
+    writer.finalize()
+}
+
+#[test]
+fn summarize_recording() -> Result<()> {
+    let path = std::env::temp_dir().join("mf4_report.mf4");
+    write_recording(&path)?;
+    let report = summarize(path.to_str().unwrap())?;
+
+    assert_eq!(report.start_time_ns, 1_715_938_200_000_000_000);
+    assert_eq!(report.groups.len(), 1);
+    let group = &report.groups[0];
+    assert_eq!(group.name.as_deref(), Some("Engine"));
+    assert_eq!(group.samples, 10);
+    assert_eq!(group.time_range, Some((1.0, 5.5)));
+    assert!(group.channels[0].master);
+    let speed = &group.channels[1];
+    assert_eq!(speed.unit.as_deref(), Some("rpm"));
+    let stats = speed.stats.unwrap();
+    assert_eq!((stats.min, stats.max, stats.count), (800.0, 1700.0, 10));
+    assert_eq!(
+        report.attachments[0].file_name.as_deref(),
+        Some("engine.dbc")
+    );
+    assert_eq!(report.events[0].name.as_deref(), Some("Lap, 1"));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()?).unwrap();
+    assert_eq!(json["groups"][0]["channels"][1]["stats"]["max"], 1700.0);
+    assert_eq!(json["events"][0]["sync_value"], 3.0);
+
+    let mut csv = Vec::new();
+    report.write_csv(&mut csv)?;
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[2],
+        "Engine,Speed,rpm,UnsignedIntegerLE,false,10,10,800,1700,1,5.5"
+    );
+
+    let text = report.to_string();
+    assert!(text.starts_with("Start 2024-05-17T09:30:00.000Z"));
+    assert!(text.contains("Speed [rpm]"));
+    assert!(text.contains("3 s Marker \"Lap, 1\""));
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::TestChannel;
+use mdf4_rs::{DataType, DecodedValue, MDF, Result, SplitPolicy, split_mdf};
+use std::path::{Path, PathBuf};
+
+/// Write a recording with a time channel (0.0, 0.1, ...) and a counter.
+fn write_recording(path: &Path, records: u64) -> Result<()> {
+    let channels = [
+        TestChannel::time(|i| DecodedValue::Float(i as f64 / 10.0)),
+        TestChannel::new(
+            "Counter",
+            DataType::UnsignedIntegerLE,
+            64,
+            DecodedValue::UnsignedInteger,
+        ),
+    ];
+    common::write_recording(path, None, &channels, records)
+}
+
+fn counters(path: &str) -> Result<Vec<u64>> {
+    let mdf = MDF::from_file(path)?;
+    let values = mdf.channel_groups()[0].channels()[1].values()?;
+    Ok(values
+        .into_iter()
+        .map(|v| match v {
+            Some(DecodedValue::UnsignedInteger(n)) => n,
+            other => panic!("unexpected value {:?}", other),
+        })
+        .collect())
+}
+
+fn split_counters(parts: &[String]) -> Result<Vec<Vec<u64>>> {
+    parts.iter().map(|p| counters(p)).collect()
+}
+
+fn remove_parts(parts: &[String]) -> Result<()> {
+    for part in parts {
+        std::fs::remove_file(part)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn split_by_records_and_time() -> Result<()> {
+    let input: PathBuf = std::env::temp_dir().join("mf4_split_input.mf4");
+    write_recording(&input, 10)?;
+    let input = input.to_str().unwrap();
+
+    let parts = split_mdf(input, SplitPolicy::EveryNRecords(4))?;
+    assert_eq!(parts.len(), 3);
+    assert!(parts[0].ends_with("mf4_split_input_001.mf4"));
+    assert_eq!(
+        split_counters(&parts)?,
+        vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]
+    );
+    remove_parts(&parts)?;
+
+    let parts = split_mdf(input, SplitPolicy::EveryNSeconds(0.5))?;
+    assert_eq!(
+        split_counters(&parts)?,
+        vec![vec![0, 1, 2, 3, 4], vec![5, 6, 7, 8, 9]]
+    );
+    remove_parts(&parts)?;
+
+    assert!(split_mdf(input, SplitPolicy::EveryNRecords(0)).is_err());
+    assert!(split_mdf(input, SplitPolicy::EveryNSeconds(-1.0)).is_err());
+    std::fs::remove_file(input)?;
+    Ok(())
+}
+
+#[test]
+fn split_by_file_size() -> Result<()> {
+    let input: PathBuf = std::env::temp_dir().join("mf4_split_size.mf4");
+    write_recording(&input, 100)?;
+    let input = input.to_str().unwrap();
+    // Room for the file structure and about half of the 16 byte records
+    let max_size = std::fs::metadata(input)?.len() - 50 * 16;
+
+    let parts = split_mdf(input, SplitPolicy::MaxFileSize(max_size))?;
+    assert!(parts.len() > 1);
+    for part in &parts {
+        assert!(std::fs::metadata(part)?.len() <= max_size);
+    }
+    let all: Vec<u64> = split_counters(&parts)?.concat();
+    assert_eq!(all, (0..100).collect::<Vec<_>>());
+    remove_parts(&parts)?;
+
+    assert!(split_mdf(input, SplitPolicy::MaxFileSize(100)).is_err());
+    std::fs::remove_file(input)?;
+    Ok(())
+}
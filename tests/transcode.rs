@@ -0,0 +1,164 @@
+mod common;
+
+use common::{TestChannel, write_group};
+use mdf4_rs::blocks::{ChannelGroupBlock, ConversionBlock};
+use mdf4_rs::{DataType, DecodedValue, MDF, MdfWriter, Result, TranscodeOptions, transcode};
+use std::path::Path;
+
+/// Write a recording with an `Engine` group (time, speed with unit and
+/// conversion, throttle) and a `Body` group (time, door state).
+fn write_recording(path: &Path, records: u64) -> Result<()> {
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let engine = [
+        TestChannel::time(|i| DecodedValue::Float(i as f64 / 10.0)),
+        TestChannel::new("Speed", DataType::UnsignedIntegerLE, 16, |i| {
+            DecodedValue::UnsignedInteger(i * 4)
+        })
+        .with_unit("rpm")
+        .with_conversion(ConversionBlock::linear(0.0, 0.5)),
+        TestChannel::new("Throttle", DataType::UnsignedIntegerLE, 8, |i| {
+            DecodedValue::UnsignedInteger(i % 100)
+        }),
+    ];
+    write_group(&mut writer, Some("Engine"), &engine, records)?;
+    let body = [
+        TestChannel::time(|i| DecodedValue::Float(i as f64 / 10.0)),
+        TestChannel::new("Door", DataType::UnsignedIntegerLE, 8, |i| {
+            DecodedValue::UnsignedInteger(i % 2)
+        }),
+    ];
+    write_group(&mut writer, Some("Body"), &body, records)?;
+    writer.finalize()
+}
+
+fn channel_names(mdf: &MDF) -> Result<Vec<Vec<String>>> {
+    mdf.channel_groups()
+        .iter()
+        .map(|group| {
+            group
+                .channels()
+                .iter()
+                .map(|ch| Ok(ch.name()?.unwrap_or_default()))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn transcode_keeps_all_values() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("mf4_transcode_all_in.mf4");
+    let output = dir.join("mf4_transcode_all_out.mf4");
+    write_recording(&input, 50)?;
+    transcode(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        &TranscodeOptions::new(),
+    )?;
+
+    let source = MDF::from_file(input.to_str().unwrap())?;
+    let result = MDF::from_file(output.to_str().unwrap())?;
+    assert_eq!(channel_names(&result)?, channel_names(&source)?);
+    for (a, b) in source.channel_groups().iter().zip(result.channel_groups()) {
+        assert_eq!(a.name()?, b.name()?);
+        for (x, y) in a.channels().iter().zip(b.channels()) {
+            assert_eq!(x.values()?, y.values()?);
+            assert_eq!(x.unit()?, y.unit()?);
+        }
+    }
+    let door = &result.channel_groups()[1].channels()[1];
+    assert_eq!(door.values()?[3], Some(DecodedValue::UnsignedInteger(1)));
+    let speed = &result.channel_groups()[0].channels()[1];
+    assert_eq!(speed.unit()?.as_deref(), Some("rpm"));
+    assert_eq!(speed.values()?[3], Some(DecodedValue::Float(6.0)));
+
+    std::fs::remove_file(&input)?;
+    std::fs::remove_file(&output)?;
+    Ok(())
+}
+
+#[test]
+fn transcode_writes_column_storage() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("mf4_transcode_column_in.mf4");
+    let output = dir.join("mf4_transcode_column_out.mf4");
+    write_recording(&input, 30)?;
+    let options = TranscodeOptions::new().with_column_storage(true);
+    transcode(input.to_str().unwrap(), output.to_str().unwrap(), &options)?;
+
+    let source = MDF::from_file(input.to_str().unwrap())?;
+    let result = MDF::from_file(output.to_str().unwrap())?;
+    assert_eq!(result.raw().identification.version_number, 420);
+    assert_eq!(result.raw().identification.format_version.trim(), "4.20");
+    assert_eq!(
+        channel_names(&result)?,
+        vec![
+            vec!["Time"],
+            vec!["Speed"],
+            vec!["Throttle"],
+            vec!["Time"],
+            vec!["Door"]
+        ]
+    );
+    let source_groups = source.channel_groups();
+    let groups = result.channel_groups();
+    let columns = source_groups.iter().flat_map(|group| group.channels());
+    for (x, group) in columns.zip(&groups) {
+        let y = &group.channels()[0];
+        assert_eq!(x.values()?, y.values()?);
+        assert_eq!(x.unit()?, y.unit()?);
+    }
+
+    // The time column of each source group is the master of its other columns
+    let master_addr = |i: usize| result.raw().data_groups[i].block.first_cg_addr;
+    for (i, master) in [
+        (0, None),
+        (1, Some(0)),
+        (2, Some(0)),
+        (3, None),
+        (4, Some(3)),
+    ] {
+        let block = &groups[i].raw_channel_group().block;
+        assert_eq!(block.master_cg_addr, master.map(master_addr));
+        assert_eq!(
+            block.flags & ChannelGroupBlock::FLAG_REMOTE_MASTER != 0,
+            master.is_some()
+        );
+    }
+
+    std::fs::remove_file(&input)?;
+    std::fs::remove_file(&output)?;
+    Ok(())
+}
+
+#[test]
+fn transcode_drops_channels_and_groups() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("mf4_transcode_drop_in.mf4");
+    let output = dir.join("mf4_transcode_drop_out.mf4");
+    write_recording(&input, 20)?;
+    let options = TranscodeOptions::new()
+        .with_groups(&["Engine"])
+        .with_channels(&["Throttle"]);
+    transcode(input.to_str().unwrap(), output.to_str().unwrap(), &options)?;
+
+    let result = MDF::from_file(output.to_str().unwrap())?;
+    assert_eq!(channel_names(&result)?, vec![vec!["Time", "Throttle"]]);
+    let group = &result.channel_groups()[0];
+    // Time and throttle packed into 9 byte records
+    assert_eq!(group.raw_channel_group().block.record_size, 9);
+    let throttle: Vec<_> = group.channels()[1].values()?;
+    let expected: Vec<_> = (0..20)
+        .map(|i| Some(DecodedValue::UnsignedInteger(i)))
+        .collect();
+    assert_eq!(throttle, expected);
+    assert_eq!(
+        group.channels()[0].values()?[19],
+        Some(DecodedValue::Float(1.9))
+    );
+
+    std::fs::remove_file(&input)?;
+    std::fs::remove_file(&output)?;
+    Ok(())
+}
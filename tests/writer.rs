@@ -0,0 +1,1393 @@
+use mdf4_rs::writer::ChannelHandle;
+use mdf4_rs::{DataType, DecodedValue, Error, MDF, MdfWriter, Result};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(name);
+    if path.exists() {
+        std::fs::remove_file(&path).unwrap();
+    }
+    path
+}
+
+#[test]
+fn typed_handles_roundtrip() -> Result<()> {
+    let path = temp_path("typed_handles.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time: ChannelHandle<f64> = writer.add_typed_channel(&cg, None, |ch| {
+        ch.name = Some("Time".into());
+    })?;
+    writer.set_time_channel(&time)?;
+    let rpm: ChannelHandle<u16> = writer.add_typed_channel(&cg, Some(&time), |ch| {
+        ch.name = Some("RPM".into());
+    })?;
+    let temp: ChannelHandle<i8> = writer.add_typed_channel(&cg, Some(&rpm), |ch| {
+        ch.name = Some("Temp".into());
+    })?;
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..5u16 {
+        // Field order does not matter for typed records
+        writer.write_record_typed(
+            &cg,
+            (
+                (&rpm, 800 + i),
+                (&temp, -(i as i8)),
+                (&time, i as f64 * 0.5),
+            ),
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    assert_eq!(channels[1].block().data_type, DataType::UnsignedIntegerLE);
+    assert_eq!(channels[1].block().bit_count, 16);
+    assert_eq!(channels[2].block().data_type, DataType::SignedIntegerLE);
+    assert_eq!(channels[2].block().bit_count, 8);
+
+    let times = channels[0].values()?;
+    let rpms = channels[1].values()?;
+    let temps = channels[2].values()?;
+    assert_eq!(times[4], Some(DecodedValue::Float(2.0)));
+    assert_eq!(rpms[3], Some(DecodedValue::UnsignedInteger(803)));
+    assert_eq!(temps[2], Some(DecodedValue::SignedInteger(-2)));
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn typed_handles_reject_foreign_group() -> Result<()> {
+    let mut writer = MdfWriter::in_memory();
+    writer.init_mdf_file()?;
+    let cg1 = writer.add_channel_group(None, |_| {})?;
+    let a: ChannelHandle<u32> = writer.add_typed_channel(&cg1, None, |_| {})?;
+    let cg2 = writer.add_channel_group(None, |_| {})?;
+    let _b: ChannelHandle<u32> = writer.add_typed_channel(&cg2, None, |_| {})?;
+
+    writer.start_data_block_for_cg(&cg2, 0)?;
+    writer.start_data_block_for_cg(&cg1, 0)?;
+    writer.write_record_typed(&cg1, (&a, 1u32))?;
+
+    // The empty DT block of cg2 is no longer at the end of the file; the
+    // rejected record must not relocate it or reserve space
+    let offset = writer.offset();
+    let result = writer.write_record_typed(&cg2, (&a, 1u32));
+    assert!(matches!(result, Err(Error::BlockSerializationError(_))));
+    assert_eq!(writer.offset(), offset);
+    Ok(())
+}
+
+#[test]
+fn typed_channel_rejects_changed_data_type() -> Result<()> {
+    let path = temp_path("typed_reject.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let result = writer.add_typed_channel::<f64, _>(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+    });
+    assert!(result.is_err());
+
+    // The rejected channel was not added to the group
+    let speed: ChannelHandle<u16> = writer.add_typed_channel(&cg, None, |ch| {
+        ch.name = Some("Speed".into());
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_record_typed(&cg, (&speed, 7u16))?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    let channels = groups[0].channels();
+    assert_eq!(channels.len(), 1);
+    assert_eq!(channels[0].name()?.as_deref(), Some("Speed"));
+    assert_eq!(groups[0].raw_channel_group().block.record_size, 2);
+    assert_eq!(
+        channels[0].values()?,
+        vec![Some(DecodedValue::UnsignedInteger(7))]
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn header_start_time_and_timezone() -> Result<()> {
+    use mdf4_rs::blocks::HeaderBlock;
+
+    let path = temp_path("header_time.mf4");
+    let start_ns = 1_704_067_200_000_000_000; // 2024-01-01 00:00:00 UTC
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    // Set before the header block is written...
+    writer.set_start_time_ns(start_ns)?;
+    writer.init_mdf_file()?;
+    // ...and patch after it has been written
+    writer.set_time_zone(60, 60)?;
+    writer.set_time_quality(HeaderBlock::TIME_QUALITY_EXTERNAL_ABSOLUTE)?;
+    writer.set_header_flags(HeaderBlock::FLAG_START_DISTANCE_VALID)?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+    })?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let header = &mdf.raw().header;
+    assert_eq!(header.start_time_ns, start_ns);
+    assert_eq!(header.tz_offset_min, 60);
+    assert_eq!(header.dst_offset_min, 60);
+    assert_eq!(header.time_flags, HeaderBlock::TIME_FLAG_OFFSETS_VALID);
+    assert_eq!(
+        header.time_quality,
+        HeaderBlock::TIME_QUALITY_EXTERNAL_ABSOLUTE
+    );
+    assert_eq!(header.flags, HeaderBlock::FLAG_START_DISTANCE_VALID);
+    assert_ne!(header.first_dg_addr, 0);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn channel_comment_display_name_and_precision() -> Result<()> {
+    use mdf4_rs::blocks::ChannelBlock;
+
+    let path = temp_path("channel_description.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let speed = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 64;
+        ch.name = Some("VehSpd_01".into());
+        ch.precision = 2;
+    })?;
+    writer.set_channel_comment(&speed, "Vehicle speed <filtered> & scaled")?;
+    writer.set_channel_display_name(&speed, "Vehicle Speed")?;
+    let gear = writer.add_channel(&cg, Some(&speed), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 8;
+        ch.name = Some("Gear".into());
+    })?;
+    writer.set_channel_comment(&gear, "Selected gear")?;
+    writer.set_channel_precision(&gear, 0)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+
+    assert_eq!(
+        channels[0].display_name()?.as_deref(),
+        Some("Vehicle Speed")
+    );
+    let xml = channels[0].comment()?.unwrap();
+    assert!(xml.contains("<TX>Vehicle speed &lt;filtered&gt; &amp; scaled</TX>"));
+    assert_eq!(channels[0].block().precision, 2);
+    assert_ne!(
+        channels[0].block().flags & ChannelBlock::FLAG_PRECISION_VALID,
+        0
+    );
+
+    assert_eq!(channels[1].comment()?.as_deref(), Some("Selected gear"));
+    assert_eq!(channels[1].display_name()?, None);
+    assert_eq!(channels[1].block().precision, 0);
+    assert_ne!(
+        channels[1].block().flags & ChannelBlock::FLAG_PRECISION_VALID,
+        0
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn channel_and_group_properties() -> Result<()> {
+    use mdf4_rs::blocks::CommonProperties;
+
+    let path = temp_path("channel_properties.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_comment(&cg, "Engine status")?;
+    writer.set_channel_group_properties(
+        &cg,
+        &CommonProperties::new().with_value("GenMsgCycleTime", "100"),
+    )?;
+    let gear = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 8;
+        ch.name = Some("Gear".into());
+    })?;
+    writer.set_channel_properties(
+        &gear,
+        &CommonProperties::new().with_tree(
+            "ValueTable",
+            CommonProperties::new()
+                .with_value("0", "Neutral")
+                .with_value("1", "First & only"),
+        ),
+    )?;
+    writer.set_channel_comment(&gear, "Selected gear")?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let group = &mdf.channel_groups()[0];
+    assert_eq!(
+        group.comment()?.as_deref(),
+        Some(
+            "<CGcomment><TX>Engine status</TX><common_properties>\
+             <e name=\"GenMsgCycleTime\">100</e></common_properties></CGcomment>"
+        )
+    );
+    assert_eq!(
+        group.channels()[0].comment()?.as_deref(),
+        Some(
+            "<CNcomment><TX>Selected gear</TX><common_properties>\
+             <tree name=\"ValueTable\"><e name=\"0\">Neutral</e>\
+             <e name=\"1\">First &amp; only</e></tree></common_properties></CNcomment>"
+        )
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn embedded_attachments_with_md5() -> Result<()> {
+    use mdf4_rs::MdfIndex;
+
+    let path = temp_path("attachments.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    writer.add_attachment("engine.dbc", "application/x-dbc", b"abc")?;
+    writer.add_attachment("notes.txt", "", b"")?;
+    writer.finalize()?;
+
+    let index = MdfIndex::from_file(path.to_str().unwrap())?;
+    assert_eq!(index.attachments.len(), 2);
+    let dbc = &index.attachments[0];
+    assert_eq!(dbc.file_name.as_deref(), Some("engine.dbc"));
+    assert_eq!(dbc.mime_type.as_deref(), Some("application/x-dbc"));
+    assert!(dbc.embedded && !dbc.compressed);
+    assert_eq!(
+        dbc.md5,
+        Some([
+            0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1,
+            0x7f, 0x72
+        ])
+    );
+    assert_eq!(index.attachments[1].mime_type, None);
+    assert_eq!(index.attachments[1].original_size, 0);
+
+    let mut reader = mdf4_rs::FileRangeReader::new(path.to_str().unwrap())?;
+    assert_eq!(index.read_attachment(0, &mut reader)?, b"abc");
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn channel_level_sources() -> Result<()> {
+    use mdf4_rs::blocks::{BusType, SourceBlock, SourceType};
+
+    let path = temp_path("channel_sources.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.set_channel_group_source(&cg, &SourceBlock::usb(), Some("Logger"))?;
+    let seat = writer.add_channel(&cg, None, |ch| {
+        ch.name = Some("SeatTemp".into());
+    })?;
+    writer.set_channel_source(&seat, &SourceBlock::lin_ecu(), Some("Seat"), Some("LIN1"))?;
+    writer.add_channel(&cg, Some(&seat), |ch| {
+        ch.name = Some("Plain".into());
+    })?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let group = &mdf.channel_groups()[0];
+    let group_source = group.source()?.unwrap();
+    assert_eq!(group_source.name.as_deref(), Some("Logger"));
+    assert_eq!(group_source.bus_type, BusType::USB as u8);
+
+    let channels = group.channels();
+    let seat_source = channels[0].source()?.unwrap();
+    assert_eq!(seat_source.name.as_deref(), Some("Seat"));
+    assert_eq!(seat_source.path.as_deref(), Some("LIN1"));
+    assert_eq!(seat_source.source_type, SourceType::ECU as u8);
+    assert_eq!(seat_source.bus_type, BusType::LIN as u8);
+    assert!(channels[1].source()?.is_none());
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn value_range_tracking() -> Result<()> {
+    use mdf4_rs::blocks::ChannelBlock;
+
+    let path = temp_path("value_ranges.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?.with_value_range_tracking(true);
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let temp = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+    let speed = writer.add_channel(&cg, Some(&temp), |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 32;
+    })?;
+    writer.add_channel(&cg, Some(&speed), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.bit_count = 16;
+    })?;
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for (t, s) in [(-40i64, 12.5f64), (85, 0.0), (20, 250.25)] {
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::SignedInteger(t),
+                DecodedValue::Float(s),
+                DecodedValue::ByteArray(vec![1, 2]),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    let temp = channels[0].block();
+    assert_eq!((temp.min_raw_value, temp.max_raw_value), (-40.0, 85.0));
+    assert_ne!(temp.flags & ChannelBlock::FLAG_VALUE_RANGE_VALID, 0);
+    let speed = channels[1].block();
+    assert_eq!((speed.min_raw_value, speed.max_raw_value), (0.0, 250.25));
+    let bytes = channels[2].block();
+    assert_eq!(bytes.flags & ChannelBlock::FLAG_VALUE_RANGE_VALID, 0);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn generated_time_channel() -> Result<()> {
+    let path = temp_path("generated_time.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let fixed = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_time_channel(&fixed, None, |_| {})?;
+    writer.add_channel(&fixed, Some(&time), |ch| {
+        ch.name = Some("Speed".into());
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+    writer.set_sample_rate(&fixed, 4.0)?;
+
+    let events = writer.add_channel_group(None, |_| {})?;
+    let value = writer.add_channel(&events, None, |ch| {
+        ch.name = Some("Value".into());
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 64;
+    })?;
+    // Time channel in the middle of the record
+    let time = writer.add_time_channel(&events, Some(&value), |ch| {
+        ch.name = Some("EventTime".into());
+    })?;
+    writer.add_channel(&events, Some(&time), |ch| {
+        ch.name = Some("Flag".into());
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 8;
+    })?;
+
+    writer.start_data_block_for_cg(&fixed, 0)?;
+    for i in 0..5 {
+        writer.write_sample(&fixed, &[DecodedValue::UnsignedInteger(i)])?;
+    }
+    writer.finish_data_block(&fixed)?;
+
+    writer.start_data_block_for_cg(&events, 0)?;
+    writer.write_record_at(
+        &events,
+        0.25,
+        &[DecodedValue::Float(1.5), DecodedValue::UnsignedInteger(1)],
+    )?;
+    writer.write_record_at(
+        &events,
+        3.0,
+        &[DecodedValue::Float(-2.0), DecodedValue::UnsignedInteger(0)],
+    )?;
+    let result = writer.write_record_at(&events, 4.0, &[DecodedValue::Float(0.0)]);
+    assert!(matches!(result, Err(Error::BlockSerializationError(_))));
+    writer.finish_data_block(&events)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+
+    let channels = groups[0].channels();
+    assert_eq!(channels[0].name()?.as_deref(), Some("Time"));
+    assert_eq!(channels[0].unit()?.as_deref(), Some("s"));
+    assert_eq!(channels[0].block().channel_type, 2);
+    let times = channels[0].values()?;
+    assert_eq!(times[4], Some(DecodedValue::Float(1.0)));
+    assert_eq!(
+        channels[1].values()?[3],
+        Some(DecodedValue::UnsignedInteger(3))
+    );
+
+    let channels = groups[1].channels();
+    assert_eq!(channels[1].name()?.as_deref(), Some("EventTime"));
+    assert_eq!(
+        channels[1].values()?,
+        vec![
+            Some(DecodedValue::Float(0.25)),
+            Some(DecodedValue::Float(3.0))
+        ]
+    );
+    assert_eq!(channels[0].values()?[1], Some(DecodedValue::Float(-2.0)));
+    assert_eq!(
+        channels[2].values()?[0],
+        Some(DecodedValue::UnsignedInteger(1))
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn write_sample_requires_sample_rate() -> Result<()> {
+    let mut writer = MdfWriter::in_memory();
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_time_channel(&cg, None, |_| {})?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    assert!(writer.write_sample(&cg, &[]).is_err());
+    assert!(writer.set_sample_rate(&cg, 0.0).is_err());
+    writer.set_sample_rate(&cg, 10.0)?;
+    writer.write_sample(&cg, &[])?;
+    Ok(())
+}
+
+#[test]
+fn sample_reduction_blocks() -> Result<()> {
+    use mdf4_rs::blocks::{BlockParse, SampleReductionBlock};
+
+    let path = temp_path("sample_reduction.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?.with_sample_reduction(&[4, 1, 10]);
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..10i64 {
+        let v = if i % 2 == 0 { i } else { -i };
+        writer.write_record(
+            &cg,
+            &[
+                DecodedValue::Float(i as f64),
+                DecodedValue::SignedInteger(v),
+            ],
+        )?;
+    }
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let bytes = std::fs::read(&path)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    let cg_block = &groups[0].raw_channel_group().block;
+    assert_ne!(cg_block.first_sample_reduction_addr, 0);
+
+    // Factor 4: 10 records -> 3 reduction records (4 + 4 + 2)
+    let sr =
+        SampleReductionBlock::from_bytes(&bytes[cg_block.first_sample_reduction_addr as usize..])?;
+    assert_eq!(sr.cycle_count, 3);
+    assert_eq!(sr.interval, 4.0);
+    assert_eq!(sr.sync_type, SampleReductionBlock::SYNC_TYPE_INDEX);
+
+    // RD record = mean, min, max copies of the 10 byte record
+    let rd = sr.data_addr as usize;
+    assert_eq!(&bytes[rd..rd + 4], b"##RD");
+    let rd_len = u64::from_le_bytes(bytes[rd + 8..rd + 16].try_into().unwrap());
+    assert_eq!(rd_len, 24 + 3 * 3 * 10);
+    let record = |n: usize, part: usize| &bytes[rd + 24 + n * 30 + part * 10..][..10];
+    let time = |r: &[u8]| f64::from_le_bytes(r[..8].try_into().unwrap());
+    let value = |r: &[u8]| i16::from_le_bytes(r[8..10].try_into().unwrap());
+    // Second interval: records 4..8, values 4, -5, 6, -7
+    assert_eq!(time(record(1, 0)), 5.5);
+    assert_eq!(value(record(1, 0)), -1);
+    assert_eq!((time(record(1, 1)), value(record(1, 1))), (4.0, -7));
+    assert_eq!((time(record(1, 2)), value(record(1, 2))), (7.0, 6));
+    // Partial last interval: records 8, 9
+    assert_eq!((value(record(2, 1)), value(record(2, 2))), (-9, 8));
+
+    // Factor 10 is chained after factor 4, factor 1 is ignored
+    let next = SampleReductionBlock::from_bytes(&bytes[sr.next_sr_addr as usize..])?;
+    assert_eq!((next.cycle_count, next.interval), (1, 10.0));
+    assert_eq!(next.next_sr_addr, 0);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn sample_reduction_spans_data_blocks() -> Result<()> {
+    use mdf4_rs::blocks::{BlockParse, SampleReductionBlock};
+
+    let path = temp_path("sample_reduction_blocks.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?.with_sample_reduction(&[4, 10]);
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+
+    // Two data blocks of 6 records each
+    for block in 0..2u64 {
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..6 {
+            writer.write_record(&cg, &[DecodedValue::UnsignedInteger(block * 6 + i)])?;
+        }
+        writer.finish_data_block(&cg)?;
+    }
+    writer.finalize()?;
+
+    let bytes = std::fs::read(&path)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let groups = mdf.channel_groups();
+    let cg_block = &groups[0].raw_channel_group().block;
+
+    // One chain of one SR block per factor over all 12 records
+    let sr =
+        SampleReductionBlock::from_bytes(&bytes[cg_block.first_sample_reduction_addr as usize..])?;
+    assert_eq!((sr.cycle_count, sr.interval), (3, 4.0));
+    let rd = sr.data_addr as usize;
+    let value = |n: usize, part: usize| {
+        u16::from_le_bytes(bytes[rd + 24 + n * 6 + part * 2..][..2].try_into().unwrap())
+    };
+    // Second interval: records 4..8 across both data blocks
+    assert_eq!((value(1, 1), value(1, 2)), (4, 7));
+
+    let next = SampleReductionBlock::from_bytes(&bytes[sr.next_sr_addr as usize..])?;
+    assert_eq!((next.cycle_count, next.interval), (2, 10.0));
+    assert_eq!(next.next_sr_addr, 0);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn text_blocks_are_deduplicated() -> Result<()> {
+    fn write_file(dedup: bool) -> Result<(Vec<u8>, usize)> {
+        let mut writer = MdfWriter::in_memory().with_text_deduplication(dedup);
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let mut prev: Option<String> = None;
+        for i in 0..20 {
+            let cn = writer.add_channel(&cg, prev.as_deref(), |ch| {
+                ch.name = Some(format!("Temp_{}", i % 10));
+            })?;
+            writer.set_channel_unit(&cn, "°C")?;
+            writer.set_channel_comment(&cn, "Temperature sensor")?;
+            prev = Some(cn);
+        }
+        writer.finalize()?;
+        let bytes = writer.into_inner().into_inner();
+        let tx_count = bytes.windows(4).filter(|w| w == b"##TX").count();
+        Ok((bytes, tx_count))
+    }
+
+    let (plain, plain_tx) = write_file(false)?;
+    let (dedup, dedup_tx) = write_file(true)?;
+    assert_eq!(plain_tx, 60);
+    // 10 distinct names + one unit + one comment
+    assert_eq!(dedup_tx, 12);
+    assert!(dedup.len() < plain.len());
+
+    let path = temp_path("text_dedup.mf4");
+    std::fs::write(&path, &dedup)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    assert_eq!(channels.len(), 20);
+    assert_eq!(channels[13].name()?.as_deref(), Some("Temp_3"));
+    assert_eq!(channels[13].unit()?.as_deref(), Some("°C"));
+    assert_eq!(
+        channels[19].comment()?.as_deref(),
+        Some("Temperature sensor")
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn background_writer_matches_file_writer() -> Result<()> {
+    use mdf4_rs::writer::{BackgroundWriter, MdfWrite};
+
+    fn write_file<W: MdfWrite>(mut writer: MdfWriter<W>) -> Result<()> {
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let time = writer.add_time_channel(&cg, None, |_| {})?;
+        writer.set_channel_comment(&time, "Sample time")?;
+        writer.add_channel(&cg, Some(&time), |ch| {
+            ch.name = Some("Counter".into());
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.bit_count = 32;
+        })?;
+        writer.set_sample_rate(&cg, 1000.0)?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        // More than one 4 MB DT block, so rollover patches are exercised
+        for i in 0..400_000u64 {
+            writer.write_sample(&cg, &[DecodedValue::UnsignedInteger(i)])?;
+        }
+        writer.finish_data_block(&cg)?;
+        writer.finalize()
+    }
+
+    let plain = temp_path("background_plain.mf4");
+    let background = temp_path("background_threaded.mf4");
+    write_file(MdfWriter::new(plain.to_str().unwrap())?)?;
+    // Small chunks and a short queue to exercise chunk boundaries
+    write_file(MdfWriter::from_writer(BackgroundWriter::with_config(
+        background.to_str().unwrap(),
+        4096,
+        2,
+    )?))?;
+
+    assert_eq!(std::fs::read(&plain)?, std::fs::read(&background)?);
+
+    let mdf = MDF::from_file(background.to_str().unwrap())?;
+    let values = mdf.channel_groups()[0].channels()[1].values()?;
+    assert_eq!(values.len(), 400_000);
+    assert_eq!(
+        values[399_999],
+        Some(DecodedValue::UnsignedInteger(399_999))
+    );
+
+    std::fs::remove_file(plain)?;
+    std::fs::remove_file(background)?;
+    Ok(())
+}
+
+#[test]
+fn columnar_writes() -> Result<()> {
+    use mdf4_rs::writer::ColumnData;
+
+    let path = temp_path("columns.mf4");
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.name = Some("Time".into());
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let temp = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.name = Some("Temp".into());
+        ch.data_type = DataType::FloatLE;
+        ch.bit_count = 32;
+    })?;
+    let gear = writer.add_channel(&cg, Some(&temp), |ch| {
+        ch.name = Some("Gear".into());
+        ch.data_type = DataType::SignedIntegerLE;
+        ch.bit_count = 8;
+    })?;
+    writer.add_channel(&cg, Some(&gear), |ch| {
+        ch.name = Some("Unused".into());
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+
+    let times: Vec<f64> = (0..1000).map(|i| i as f64 * 0.01).collect();
+    let temps: Vec<f32> = (0..1000).map(|i| 20.0 + i as f32 * 0.5).collect();
+    let gears: Vec<i64> = (0..1000).map(|i| (i % 7) - 1).collect();
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_columns(
+        &cg,
+        &[
+            ("Gear", gears[..].into()),
+            ("Time", times[..].into()),
+            ("Temp", (&temps).into()),
+        ],
+    )?;
+
+    // Mismatched lengths, unknown names and wrong kinds are rejected
+    assert!(
+        writer
+            .write_columns(
+                &cg,
+                &[("Time", times[..2].into()), ("Temp", temps[..3].into())]
+            )
+            .is_err()
+    );
+    assert!(
+        writer
+            .write_columns(&cg, &[("Nope", times[..].into())])
+            .is_err()
+    );
+    assert!(
+        writer
+            .write_columns(&cg, &[("Gear", times[..].into())])
+            .is_err()
+    );
+    writer.write_columns(&cg, &[("Gear", ColumnData::I8(&[5]))])?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    let read_times = channels[0].values()?;
+    let read_temps = channels[1].values()?;
+    let read_gears = channels[2].values()?;
+    assert_eq!(read_times.len(), 1001);
+    assert_eq!(read_times[500], Some(DecodedValue::Float(5.0)));
+    assert_eq!(read_temps[10], Some(DecodedValue::Float(25.0)));
+    assert_eq!(read_gears[0], Some(DecodedValue::SignedInteger(-1)));
+    assert_eq!(read_gears[1000], Some(DecodedValue::SignedInteger(5)));
+    assert_eq!(
+        channels[3].values()?[999],
+        Some(DecodedValue::UnsignedInteger(0))
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn flush_now_makes_records_readable() -> Result<()> {
+    let path = temp_path("flush_now.mf4");
+
+    let read_back = |expected: u64| -> Result<()> {
+        let mdf = MDF::from_file(path.to_str().unwrap())?;
+        let group = &mdf.channel_groups()[0];
+        assert_eq!(group.raw_channel_group().block.cycle_count, expected);
+        let values = group.channels()[0].values()?;
+        assert_eq!(values.len() as u64, expected);
+        assert_eq!(
+            values.last().cloned().flatten(),
+            Some(DecodedValue::UnsignedInteger(expected - 1))
+        );
+        Ok(())
+    };
+
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 64;
+    })?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+
+    let mut next = 0u64;
+    let mut write = |writer: &mut MdfWriter<_>, n: u64| -> Result<()> {
+        for _ in 0..n {
+            writer.write_record_u64(&cg, &[next])?;
+            next += 1;
+        }
+        Ok(())
+    };
+
+    // Single DT block
+    write(&mut writer, 1000)?;
+    writer.flush_now()?;
+    read_back(1000)?;
+
+    // Spanning several DT blocks (4 MB each), linked through an interim DL block
+    write(&mut writer, 600_000)?;
+    writer.flush_now()?;
+    read_back(601_000)?;
+
+    write(&mut writer, 500)?;
+    writer.flush_now()?;
+    read_back(601_500)?;
+
+    write(&mut writer, 500)?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    read_back(602_000)?;
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn finalize_and_verify_reports_issues() -> Result<()> {
+    use mdf4_rs::writer::verify_mdf_bytes;
+
+    let path = temp_path("verify.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?.with_sample_reduction(&[10]);
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_time_channel(&cg, None, |_| {})?;
+    let speed = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.name = Some("Speed".into());
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+    writer.set_channel_unit(&speed, "km/h")?;
+    writer.set_sample_rate(&cg, 10.0)?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    for i in 0..100 {
+        writer.write_sample(&cg, &[DecodedValue::UnsignedInteger(i)])?;
+    }
+    writer.finish_data_block(&cg)?;
+
+    let open = writer.add_channel_group(None, |_| {})?;
+    writer.add_channel(&open, None, |_| {})?;
+    writer.start_data_block_for_cg(&open, 0)?;
+
+    let report = writer.finalize_and_verify()?;
+    assert_eq!(report.data_groups, 2);
+    assert_eq!(report.channel_groups, 2);
+    assert!(report.blocks_checked > 10);
+    assert_eq!(report.issues.len(), 1, "{}", report);
+    assert!(report.issues[0].message.contains("not finished"));
+
+    // The file itself is consistent
+    let mut bytes = std::fs::read(&path)?;
+    assert!(verify_mdf_bytes(&bytes).is_ok());
+
+    // A wrong cycle count is detected
+    let cg_pos = bytes.windows(4).position(|w| w == b"##CG").unwrap();
+    bytes[cg_pos + 80..cg_pos + 88].copy_from_slice(&99u64.to_le_bytes());
+    let report = verify_mdf_bytes(&bytes);
+    assert_eq!(report.issues.len(), 1, "{}", report);
+    assert!(report.issues[0].message.contains("cycle counts"));
+
+    // A dangling link is detected
+    bytes[cg_pos + 80..cg_pos + 88].copy_from_slice(&100u64.to_le_bytes());
+    let len = bytes.len() as u64;
+    bytes[cg_pos + 32..cg_pos + 40].copy_from_slice(&(len + 8).to_le_bytes());
+    let report = verify_mdf_bytes(&bytes);
+    assert!(!report.is_ok());
+    assert!(report.issues[0].message.contains("invalid link"));
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn interleaved_channel_groups() -> Result<()> {
+    use mdf4_rs::writer::verify_mdf_bytes;
+
+    let path = temp_path("interleaved.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+
+    let mut groups = Vec::new();
+    for name in ["Fast", "Slow", "Burst"] {
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.set_channel_group_name(&cg, name)?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.bit_count = 32;
+            ch.name = Some(format!("{}_value", name));
+        })?;
+        groups.push(cg);
+    }
+    let (fast, slow, burst) = (&groups[0], &groups[1], &groups[2]);
+
+    writer.start_data_block_for_cg(fast, 0)?;
+    writer.start_data_block_for_cg(slow, 0)?;
+    writer.start_data_block_for_cg(burst, 0)?;
+    for i in 0..50u64 {
+        writer.write_record_u64(fast, &[i])?;
+        writer.write_record_u64(fast, &[i + 1000])?;
+        if i % 5 == 0 {
+            writer.write_record_u64(slow, &[i])?;
+        }
+        if i % 10 == 0 {
+            let batch: Vec<[u64; 1]> = (0..3).map(|j| [i * 10 + j]).collect();
+            writer.write_records_u64(burst, batch.iter().map(|r| &r[..]))?;
+        }
+    }
+    writer.finish_data_block(slow)?;
+    // Blocks written by finishing one group do not disturb the others
+    writer.write_record_u64(fast, &[9999])?;
+    writer.finish_data_block(fast)?;
+    writer.finish_data_block(burst)?;
+    writer.finalize()?;
+
+    let bytes = std::fs::read(&path)?;
+    let report = verify_mdf_bytes(&bytes);
+    assert!(report.is_ok(), "{}", report);
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let values = |index: usize| -> Result<Vec<u64>> {
+        let values = mdf.channel_groups()[index].channels()[0].values()?;
+        Ok(values
+            .into_iter()
+            .map(|v| match v {
+                Some(DecodedValue::UnsignedInteger(v)) => v,
+                other => panic!("unexpected value {:?}", other),
+            })
+            .collect())
+    };
+
+    let mut expected_fast: Vec<u64> = (0..50).flat_map(|i| [i, i + 1000]).collect();
+    expected_fast.push(9999);
+    assert_eq!(values(0)?, expected_fast);
+    assert_eq!(values(1)?, (0..50).step_by(5).collect::<Vec<_>>());
+    let expected_burst: Vec<u64> = (0..50)
+        .step_by(10)
+        .flat_map(|i| (0..3).map(move |j| i * 10 + j))
+        .collect();
+    assert_eq!(values(2)?, expected_burst);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn channel_defaults() -> Result<()> {
+    let path = temp_path("channel_defaults.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let counter = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 16;
+    })?;
+    let config = writer.add_channel(&cg, Some(&counter), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 32;
+    })?;
+    let level = writer.add_channel(&cg, Some(&config), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.bit_count = 8;
+    })?;
+
+    // Set before the data block is started
+    writer.set_channel_default(&config, DecodedValue::UnsignedInteger(0xC0FFEE))?;
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_record(
+        &cg,
+        &[
+            DecodedValue::UnsignedInteger(1),
+            DecodedValue::UnsignedInteger(10),
+        ],
+    )?;
+    // A full record still overrides the default
+    writer.write_record_u64(&cg, &[2, 7, 20])?;
+    writer.write_records_u64(&cg, [&[3u64, 30][..], &[4, 40][..]])?;
+
+    // Set while the data block is open
+    writer.set_channel_default(&level, DecodedValue::UnsignedInteger(99))?;
+    writer.write_record(&cg, &[DecodedValue::UnsignedInteger(5)])?;
+    writer.write_records(&cg, [&[DecodedValue::UnsignedInteger(6)][..]])?;
+    assert!(matches!(
+        writer.write_record_u64(&cg, &[7, 70]),
+        Err(Error::BlockSerializationError(_))
+    ));
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    let column = |i: usize| -> Result<Vec<u64>> {
+        Ok(channels[i]
+            .values()?
+            .into_iter()
+            .map(|v| match v {
+                Some(DecodedValue::UnsignedInteger(v)) => v,
+                other => panic!("unexpected value {:?}", other),
+            })
+            .collect())
+    };
+    assert_eq!(column(0)?, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(
+        column(1)?,
+        vec![0xC0FFEE, 7, 0xC0FFEE, 0xC0FFEE, 0xC0FFEE, 0xC0FFEE]
+    );
+    assert_eq!(column(2)?, vec![10, 20, 30, 40, 99, 99]);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn duplicate_channel_names() -> Result<()> {
+    use mdf4_rs::writer::{DuplicateNamePolicy, MdfWrite};
+
+    fn add_named<W: MdfWrite>(
+        writer: &mut MdfWriter<W>,
+        cg: &str,
+        prev: Option<&str>,
+        name: &str,
+    ) -> Result<String> {
+        writer.add_channel(cg, prev, |ch| ch.name = Some(name.into()))
+    }
+
+    // Allowed by default
+    let mut writer = MdfWriter::in_memory();
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let a = add_named(&mut writer, &cg, None, "Speed")?;
+    add_named(&mut writer, &cg, Some(&a), "Speed")?;
+
+    let mut writer = MdfWriter::in_memory().with_duplicate_name_policy(DuplicateNamePolicy::Reject);
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let a = add_named(&mut writer, &cg, None, "Speed")?;
+    assert!(matches!(
+        add_named(&mut writer, &cg, Some(&a), "Speed"),
+        Err(Error::BlockSerializationError(_))
+    ));
+    // Names only need to be unique within their group
+    let other = writer.add_channel_group(None, |_| {})?;
+    add_named(&mut writer, &other, None, "Speed")?;
+
+    let path = temp_path("duplicate_names.mf4");
+    let mut writer = MdfWriter::new(path.to_str().unwrap())?;
+    writer.set_duplicate_name_policy(DuplicateNamePolicy::Rename);
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let mut prev = None;
+    for name in ["Speed", "Speed", "Speed_1", "Speed"] {
+        prev = Some(add_named(&mut writer, &cg, prev.as_deref(), name)?);
+    }
+    writer.finalize()?;
+
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let names: Vec<Option<String>> = mdf.channel_groups()[0]
+        .channels()
+        .iter()
+        .map(|ch| ch.name())
+        .collect::<Result<_>>()?;
+    assert_eq!(
+        names,
+        ["Speed", "Speed_1", "Speed_1_1", "Speed_2"].map(|n| Some(n.to_string()))
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn spool_writer_reproduces_file() -> Result<()> {
+    use mdf4_rs::writer::{MdfWrite, SpoolWriter};
+
+    fn write_file<W: MdfWrite>(writer: &mut MdfWriter<W>) -> Result<()> {
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let time = writer.add_time_channel(&cg, None, |_| {})?;
+        writer.add_channel(&cg, Some(&time), |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.bit_count = 32;
+        })?;
+        writer.set_sample_rate(&cg, 100.0)?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for i in 0..200 {
+            writer.write_sample(&cg, &[DecodedValue::UnsignedInteger(i)])?;
+        }
+        writer.flush_now()?;
+        writer.finish_data_block(&cg)?;
+        writer.finalize()
+    }
+
+    let mut expected = MdfWriter::in_memory();
+    write_file(&mut expected)?;
+    let expected = expected.into_inner().into_inner();
+
+    let mut writer = MdfWriter::from_writer(SpoolWriter::new());
+    write_file(&mut writer)?;
+    let spool = writer.writer_mut();
+    assert!(spool.pending_bytes() >= expected.len());
+    let mut bytes = Vec::new();
+    for (pos, data) in spool.take_ops() {
+        let end = pos as usize + data.len();
+        if bytes.len() < end {
+            bytes.resize(end, 0);
+        }
+        bytes[pos as usize..end].copy_from_slice(&data);
+    }
+    assert_eq!(spool.pending_bytes(), 0);
+    assert_eq!(bytes, expected);
+    Ok(())
+}
+
+#[test]
+fn buffer_limit_backpressure() -> Result<()> {
+    use mdf4_rs::BufferLimit;
+    use mdf4_rs::writer::verify_mdf_bytes;
+
+    fn setup(limit: BufferLimit) -> Result<(MdfWriter, String)> {
+        let mut writer = MdfWriter::in_memory().with_buffer_limit(limit);
+        writer.init_mdf_file()?;
+        let cg = writer.add_channel_group(None, |_| {})?;
+        writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::UnsignedIntegerLE;
+            ch.bit_count = 64;
+        })?;
+        writer.start_data_block_for_cg(&cg, 0)?;
+        Ok((writer, cg))
+    }
+
+    // Rejecting writes
+    let (mut writer, cg) = setup(BufferLimit::ErrorAt(32))?;
+    for i in 0..4 {
+        writer.write_record_u64(&cg, &[i])?;
+    }
+    assert_eq!(writer.unflushed_bytes(), 32);
+    assert_eq!(writer.unflushed_bytes_for(&cg), Some(32));
+    assert!(matches!(
+        writer.write_record_u64(&cg, &[4]),
+        Err(Error::BufferLimitExceeded {
+            buffered: 32,
+            incoming: 8,
+            limit: 32
+        })
+    ));
+    writer.flush()?;
+    assert_eq!(writer.unflushed_bytes_for(&cg), Some(0));
+
+    // A batch stops at the limit, keeping the records before it
+    let batch: Vec<[u64; 1]> = (4..10).map(|i| [i]).collect();
+    let result = writer.write_records_u64(&cg, batch.iter().map(|r| &r[..]));
+    assert!(matches!(result, Err(Error::BufferLimitExceeded { .. })));
+    assert_eq!(writer.unflushed_bytes(), 32);
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    let bytes = writer.into_inner().into_inner();
+    assert!(verify_mdf_bytes(&bytes).is_ok());
+    let path = temp_path("buffer_limit.mf4");
+    std::fs::write(&path, &bytes)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    assert_eq!(mdf.channel_groups()[0].channels()[0].values()?.len(), 8);
+    std::fs::remove_file(path)?;
+
+    // Forced flushes
+    let (mut writer, cg) = setup(BufferLimit::FlushAt(32))?;
+    for i in 0..10 {
+        writer.write_record_u64(&cg, &[i])?;
+        assert!(writer.unflushed_bytes() <= 32);
+    }
+    let batch: Vec<[u64; 1]> = (10..20).map(|i| [i]).collect();
+    writer.write_records_u64(&cg, batch.iter().map(|r| &r[..]))?;
+    assert!(writer.unflushed_bytes() <= 32);
+    let (records, _, flushes) = writer.streaming_stats();
+    assert_eq!(records, 20);
+    assert_eq!(flushes, 4);
+    Ok(())
+}
+
+#[test]
+fn composition_members() -> Result<()> {
+    use mdf4_rs::blocks::{BlockParse, ChannelBlock, TextBlock};
+    use mdf4_rs::writer::verify_mdf_bytes;
+
+    let mut writer = MdfWriter::in_memory();
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let frame = writer.add_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Frame".into());
+        ch.bit_count = 24;
+    })?;
+    let id = writer.add_component_channel(&frame, None, |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Frame.ID".into());
+        ch.bit_count = 12;
+    })?;
+    writer.add_component_channel(&frame, Some(&id), |ch| {
+        ch.data_type = DataType::UnsignedIntegerLE;
+        ch.name = Some("Frame.Flag".into());
+        ch.byte_offset = 2;
+        ch.bit_offset = 3;
+        ch.bit_count = 1;
+    })?;
+    assert!(writer.add_component_channel("cn_99", None, |_| {}).is_err());
+
+    writer.start_data_block_for_cg(&cg, 0)?;
+    writer.write_record(
+        &cg,
+        &[
+            DecodedValue::Float(0.5),
+            DecodedValue::ByteArray(vec![0x23, 0x01, 0x08]),
+        ],
+    )?;
+    writer.finish_data_block(&cg)?;
+    writer.finalize()?;
+    let bytes = writer.into_inner().into_inner();
+    assert!(verify_mdf_bytes(&bytes).is_ok());
+
+    let path = temp_path("composition_members.mf4");
+    std::fs::write(&path, &bytes)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    assert_eq!(channels.len(), 2);
+    assert_eq!(
+        channels[1].values()?,
+        vec![Some(DecodedValue::ByteArray(vec![0x23, 0x01, 0x08]))]
+    );
+
+    // Members follow the composition link of the parent, at record offsets
+    let mut members = Vec::new();
+    let mut address = channels[1].block().component_addr;
+    while address != 0 {
+        let member = ChannelBlock::from_bytes(&bytes[address as usize..])?;
+        let name = TextBlock::from_bytes(&bytes[member.name_addr as usize..])?.text;
+        members.push((
+            name,
+            member.byte_offset,
+            member.bit_offset,
+            member.bit_count,
+        ));
+        address = member.next_ch_addr;
+    }
+    assert_eq!(
+        members,
+        vec![
+            ("Frame.ID".to_string(), 8, 0, 12),
+            ("Frame.Flag".to_string(), 10, 3, 1)
+        ]
+    );
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn vlsd_channels() -> Result<()> {
+    use mdf4_rs::writer::verify_mdf_bytes;
+
+    let mut writer = MdfWriter::in_memory();
+    writer.init_mdf_file()?;
+    let cg = writer.add_channel_group(None, |_| {})?;
+    let time = writer.add_channel(&cg, None, |ch| {
+        ch.data_type = DataType::FloatLE;
+        ch.name = Some("Time".into());
+        ch.bit_count = 64;
+    })?;
+    writer.set_time_channel(&time)?;
+    let payload = writer.add_vlsd_channel(&cg, Some(&time), |ch| {
+        ch.data_type = DataType::ByteArray;
+        ch.name = Some("Payload".into());
+    })?;
+    writer.add_vlsd_channel(&cg, Some(&payload), |ch| {
+        ch.data_type = DataType::StringUtf8;
+        ch.name = Some("Text".into());
+    })?;
+
+    // Signal data continues across data blocks of the group
+    let records = [
+        (0.0, vec![1u8], "a"),
+        (1.0, vec![2, 3, 4], ""),
+        (2.0, vec![], "hello"),
+    ];
+    for chunk in records.chunks(2) {
+        writer.start_data_block_for_cg(&cg, 0)?;
+        for (t, bytes, text) in chunk {
+            writer.write_record(
+                &cg,
+                &[
+                    DecodedValue::Float(*t),
+                    DecodedValue::ByteArray(bytes.clone()),
+                    DecodedValue::String(text.to_string()),
+                ],
+            )?;
+        }
+        writer.finish_data_block(&cg)?;
+    }
+    writer.finalize()?;
+    let bytes = writer.into_inner().into_inner();
+    assert!(verify_mdf_bytes(&bytes).is_ok());
+
+    let path = temp_path("vlsd_channels.mf4");
+    std::fs::write(&path, &bytes)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    let channels = mdf.channel_groups()[0].channels();
+    let payloads: Vec<_> = records
+        .iter()
+        .map(|(_, bytes, _)| Some(DecodedValue::ByteArray(bytes.clone())))
+        .collect();
+    assert_eq!(channels[1].values()?, payloads);
+    let texts: Vec<_> = records
+        .iter()
+        .map(|(_, _, text)| Some(DecodedValue::String(text.to_string())))
+        .collect();
+    assert_eq!(channels[2].values()?, texts);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn data_blocks_continue_group() -> Result<()> {
+    use mdf4_rs::writer::verify_mdf_bytes;
+
+    let mut writer = MdfWriter::in_memory();
+    writer.init_mdf_file()?;
+    let mut groups = Vec::new();
+    for name in ["A", "B"] {
+        let cg = writer.add_channel_group(None, |_| {})?;
+        let time = writer.add_channel(&cg, None, |ch| {
+            ch.data_type = DataType::FloatLE;
+            ch.name = Some(name.into());
+            ch.bit_count = 64;
+        })?;
+        writer.set_time_channel(&time)?;
+        groups.push(cg);
+    }
+
+    // Data blocks of both groups interleaved, as written by repeated flushes
+    for block in 0..3 {
+        for (g, cg) in groups.iter().enumerate() {
+            writer.start_data_block_for_cg(cg, 0)?;
+            for i in 0..=block {
+                let t = (g * 100 + block * 10 + i) as f64;
+                writer.write_record(cg, &[DecodedValue::Float(t)])?;
+            }
+            writer.finish_data_block(cg)?;
+        }
+    }
+    writer.finalize()?;
+    let bytes = writer.into_inner().into_inner();
+    assert!(verify_mdf_bytes(&bytes).is_ok());
+
+    let path = temp_path("data_blocks_continue_group.mf4");
+    std::fs::write(&path, &bytes)?;
+    let mdf = MDF::from_file(path.to_str().unwrap())?;
+    for (g, group) in mdf.channel_groups().iter().enumerate() {
+        let expected: Vec<_> = (0..3)
+            .flat_map(|block| (0..=block).map(move |i| (g * 100 + block * 10 + i) as f64))
+            .map(|t| Some(DecodedValue::Float(t)))
+            .collect();
+        assert_eq!(group.channels()[0].values()?, expected);
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}